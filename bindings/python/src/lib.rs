@@ -0,0 +1,85 @@
+//! Python bindings for forge-lib, built with maturin as `forge_lib_py`.
+//!
+//! Results are the library's serde output converted to Python dicts and
+//! lists, the same shape as the CLIs' `--json` output, so pipelines that
+//! scraped the binaries' text can read fields directly.
+
+use forge_lib::deploy::{self, provider::Provider, DeployOptions};
+use forge_lib::frontmatter::{AgentFrontmatter, SkillFrontmatter};
+use forge_lib::progress::NoProgress;
+use forge_lib::sidecar::SidecarConfig;
+use forge_lib::{parse, validate};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+
+fn value_error(e: &impl ToString) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// `value` as Python objects, through JSON.
+fn to_python<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| value_error(&e))?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// Plan deploying the agents in `src_dir` to `dst_dir` without writing
+/// anything. The sidecar config is loaded from the parent of `src_dir`, as
+/// `install-agents` does. Returns the plan as a dict.
+#[pyfunction]
+#[pyo3(signature = (src_dir, dst_dir, provider = "claude", source_prefix = ""))]
+fn plan_agents<'py>(
+    py: Python<'py>,
+    src_dir: &str,
+    dst_dir: &str,
+    provider: &str,
+    source_prefix: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let provider = Provider::from_str(provider)
+        .ok_or_else(|| value_error(&format!("unknown provider: {provider}")))?;
+    let src_dir = Path::new(src_dir);
+    let config = SidecarConfig::load(src_dir.parent().unwrap_or(Path::new(".")));
+    let options = DeployOptions::new(provider).with_source_prefix(source_prefix);
+    let plan = deploy::plan_agents_from_dir(src_dir, Path::new(dst_dir), &config, &options)
+        .map_err(|e| value_error(&e))?;
+    to_python(py, &plan)
+}
+
+/// Run every validation suite on the module at `root`. Returns a list of
+/// `{"name", "checks": [{"desc", "passed", ...}]}` dicts.
+#[pyfunction]
+fn validate_module<'py>(py: Python<'py>, root: &str) -> PyResult<Bound<'py, PyAny>> {
+    let suites = validate::validate_all(Path::new(root), &mut NoProgress);
+    to_python(py, &suites)
+}
+
+/// The typed frontmatter of an agent file as a dict.
+#[pyfunction]
+fn agent_frontmatter<'py>(py: Python<'py>, content: &str) -> PyResult<Bound<'py, PyAny>> {
+    let fm = AgentFrontmatter::from_markdown(content).map_err(|e| value_error(&e))?;
+    to_python(py, &fm)
+}
+
+/// The typed frontmatter of a `SKILL.md` as a dict.
+#[pyfunction]
+fn skill_frontmatter<'py>(py: Python<'py>, content: &str) -> PyResult<Bound<'py, PyAny>> {
+    let fm = SkillFrontmatter::from_markdown(content).map_err(|e| value_error(&e))?;
+    to_python(py, &fm)
+}
+
+/// One frontmatter value as a string, or `None`.
+#[pyfunction]
+fn fm_value(content: &str, key: &str) -> Option<String> {
+    parse::fm_value(content, key)
+}
+
+#[pymodule]
+fn forge_lib_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(plan_agents, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_module, m)?)?;
+    m.add_function(wrap_pyfunction!(agent_frontmatter, m)?)?;
+    m.add_function(wrap_pyfunction!(skill_frontmatter, m)?)?;
+    m.add_function(wrap_pyfunction!(fm_value, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+"""Smoke tests for the bindings: `maturin develop && pytest bindings/python`."""
+
+import forge_lib_py
+
+
+def test_agent_frontmatter_reads_legacy_fields():
+    fm = forge_lib_py.agent_frontmatter("---\nclaude.name: Dev\nclaude.tools: Read, Grep\n---\n")
+    assert fm["claude.name"] == "Dev"
+    assert fm["claude.tools"] == ["Read", "Grep"]
+
+
+def test_plan_agents_writes_nothing(tmp_path):
+    agents = tmp_path / "agents"
+    agents.mkdir()
+    (agents / "Dev.md").write_text("---\nname: Dev\n---\nBody.\n")
+
+    plan = forge_lib_py.plan_agents(str(agents), str(tmp_path / "out"))
+
+    assert plan["actions"][0]["result"] == "deployed"
+    assert not (tmp_path / "out").exists()
+
+
+def test_validate_module_reports_suites(tmp_path):
+    suites = forge_lib_py.validate_module(str(tmp_path))
+    assert all({"name", "checks"} <= suite.keys() for suite in suites)
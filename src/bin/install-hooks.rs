@@ -0,0 +1,7 @@
+use forge_lib::cli;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    cli::init_logging();
+    cli::install_hooks::run(&cli::program_args())
+}
@@ -0,0 +1,135 @@
+//! Incremental deploy cache: a `.forge-cache` file in a destination
+//! directory mapping each source (an agent file, a skill) to a digest of
+//! everything its output depends on, and the digests of the files deployed
+//! from it.
+//!
+//! A source whose digest is unchanged and whose deployed files still hash
+//! as recorded is fresh: deploying it again would write the same bytes, so
+//! it is skipped without rendering. Any edit to the deployed files, the
+//! source, or the configuration makes it stale again. Modules sharing a
+//! destination share its cache, each under its own keys.
+
+use crate::error::ForgeError;
+use crate::pack::sha256_hex;
+use crate::vfs::Vfs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const CACHE_FILE: &str = ".forge-cache";
+
+/// What one source deployed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// [`digest`] of the source and everything its render depends on.
+    pub source: String,
+    /// Deployed files, relative to the destination, and their digests.
+    pub outputs: BTreeMap<String, String>,
+}
+
+/// The `.forge-cache` of one destination directory, keyed by source.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DeployCache {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+impl DeployCache {
+    /// The cache in `dst_dir`; empty when missing or unreadable.
+    pub fn load(vfs: &dyn Vfs, dst_dir: &Path) -> Self {
+        vfs.read_to_string(&dst_dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|c| serde_yaml::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the cache to `dst_dir`, removing the file when empty.
+    pub fn save(&self, vfs: &dyn Vfs, dst_dir: &Path) -> Result<(), ForgeError> {
+        let path = dst_dir.join(CACHE_FILE);
+        if self.entries.is_empty() {
+            let _ = vfs.remove_file(&path);
+            return Ok(());
+        }
+        let yaml = serde_yaml::to_string(self)
+            .map_err(ForgeError::yaml("failed to serialize deploy cache"))?;
+        vfs.write(&path, yaml.as_bytes())
+            .map_err(ForgeError::io("write", &path))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&CacheEntry> {
+        self.entries.get(key)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, entry: CacheEntry) {
+        self.entries.insert(key.into(), entry);
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<CacheEntry> {
+        self.entries.remove(key)
+    }
+
+    /// Record `entry` for `key`, or forget `key` when its output should not
+    /// be trusted next time.
+    pub fn update(&mut self, key: impl Into<String>, entry: Option<CacheEntry>) {
+        let key = key.into();
+        match entry {
+            Some(entry) => self.insert(key, entry),
+            None => {
+                self.remove(&key);
+            }
+        }
+    }
+
+    /// Whether `key` was deployed from a source with digest `source` and
+    /// every file it deployed to `dst_dir` is still as written.
+    pub fn is_fresh(&self, vfs: &dyn Vfs, dst_dir: &Path, key: &str, source: &str) -> bool {
+        let Some(entry) = self.entries.get(key) else {
+            return false;
+        };
+        entry.source == source
+            && !entry.outputs.is_empty()
+            && entry.outputs.iter().all(|(path, hash)| {
+                vfs.read(&dst_dir.join(path))
+                    .is_ok_and(|data| sha256_hex(&data) == *hash)
+            })
+    }
+}
+
+/// SHA-256 over `parts`, each length-prefixed so `["ab", "c"]` and
+/// `["a", "bc"]` differ.
+pub fn digest(parts: &[&[u8]]) -> String {
+    let mut data = Vec::new();
+    for part in parts {
+        data.extend_from_slice(&(part.len() as u64).to_be_bytes());
+        data.extend_from_slice(part);
+    }
+    sha256_hex(&data)
+}
+
+/// Digests of every file under `dir` (through `vfs`), keyed by path
+/// relative to `base` with `/` separators.
+pub fn digest_tree(
+    vfs: &dyn Vfs,
+    dir: &Path,
+    base: &Path,
+) -> Result<BTreeMap<String, String>, ForgeError> {
+    let mut digests = BTreeMap::new();
+    for path in vfs.read_dir(dir).map_err(ForgeError::io("read", dir))? {
+        if vfs.is_dir(&path) {
+            digests.extend(digest_tree(vfs, &path, base)?);
+        } else {
+            let data = vfs.read(&path).map_err(ForgeError::io("read", &path))?;
+            let relative = path.strip_prefix(base).unwrap_or(&path);
+            let key = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            digests.insert(key, sha256_hex(&data));
+        }
+    }
+    Ok(digests)
+}
+
+#[cfg(test)]
+mod tests;
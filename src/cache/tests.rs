@@ -0,0 +1,77 @@
+use super::*;
+use crate::vfs::MemoryFs;
+
+fn entry(source: &str, outputs: &[(&str, &str)]) -> CacheEntry {
+    CacheEntry {
+        source: source.to_string(),
+        outputs: outputs
+            .iter()
+            .map(|(path, data)| (path.to_string(), sha256_hex(data.as_bytes())))
+            .collect(),
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let vfs = MemoryFs::new();
+    vfs.create_dir_all(Path::new("/dst")).unwrap();
+    let mut cache = DeployCache::default();
+    cache.insert("Dev.md", entry("abc", &[("Dev.md", "body")]));
+    cache.save(&vfs, Path::new("/dst")).unwrap();
+    assert_eq!(DeployCache::load(&vfs, Path::new("/dst")), cache);
+}
+
+#[test]
+fn empty_cache_removes_file() {
+    let vfs = MemoryFs::new().with_file("/dst/.forge-cache", "Dev.md: {}\n");
+    DeployCache::default()
+        .save(&vfs, Path::new("/dst"))
+        .unwrap();
+    assert!(!vfs.exists(Path::new("/dst/.forge-cache")));
+}
+
+#[test]
+fn invalid_cache_loads_empty() {
+    let vfs = MemoryFs::new().with_file("/dst/.forge-cache", "- not a map\n");
+    assert_eq!(
+        DeployCache::load(&vfs, Path::new("/dst")),
+        DeployCache::default()
+    );
+}
+
+#[test]
+fn fresh_until_source_or_output_changes() {
+    let vfs = MemoryFs::new().with_file("/dst/Dev.md", "body");
+    let dst = Path::new("/dst");
+    let mut cache = DeployCache::default();
+    cache.insert("Dev.md", entry("abc", &[("Dev.md", "body")]));
+
+    assert!(cache.is_fresh(&vfs, dst, "Dev.md", "abc"));
+    assert!(!cache.is_fresh(&vfs, dst, "Dev.md", "abd"));
+    assert!(!cache.is_fresh(&vfs, dst, "Other.md", "abc"));
+
+    vfs.write(Path::new("/dst/Dev.md"), b"edited").unwrap();
+    assert!(!cache.is_fresh(&vfs, dst, "Dev.md", "abc"));
+
+    vfs.remove_file(Path::new("/dst/Dev.md")).unwrap();
+    assert!(!cache.is_fresh(&vfs, dst, "Dev.md", "abc"));
+}
+
+#[test]
+fn digest_separates_parts() {
+    assert_ne!(digest(&[b"ab", b"c"]), digest(&[b"a", b"bc"]));
+    assert_eq!(digest(&[b"a", b"b"]), digest(&[b"a", b"b"]));
+}
+
+#[test]
+fn digest_tree_keys_relative_paths() {
+    let vfs = MemoryFs::new()
+        .with_file("/dst/Review/SKILL.md", "skill")
+        .with_file("/dst/Review/scripts/run.sh", "echo");
+    let digests = digest_tree(&vfs, Path::new("/dst/Review"), Path::new("/dst")).unwrap();
+    assert_eq!(
+        digests.keys().collect::<Vec<_>>(),
+        ["Review/SKILL.md", "Review/scripts/run.sh"]
+    );
+    assert_eq!(digests["Review/SKILL.md"], sha256_hex(b"skill"));
+}
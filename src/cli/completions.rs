@@ -0,0 +1,794 @@
+//! `forge completions <bash|zsh|fish>` — completion scripts for `forge` and
+//! the standalone binaries, generated from one static description of every
+//! command's flags.
+
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+const SCOPES: &[&str] = &["user", "workspace", "project", "all"];
+const PROVIDERS: &[&str] = &[
+    "claude", "gemini", "codex", "opencode", "cursor", "windsurf", "copilot", "zed",
+];
+const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+enum Values {
+    None,
+    /// Free-form value: nothing to complete, but the flag consumes it.
+    Text,
+    Words(&'static [&'static str]),
+    Dir,
+    File,
+}
+
+struct Flag {
+    long: &'static str,
+    values: Values,
+}
+
+const fn flag(long: &'static str) -> Flag {
+    Flag {
+        long,
+        values: Values::None,
+    }
+}
+
+const fn with(long: &'static str, values: Values) -> Flag {
+    Flag { long, values }
+}
+
+struct Tool {
+    /// Subcommand path under `forge`, e.g. `agents install`.
+    command: &'static str,
+    /// Standalone alias binary, if any.
+    binary: Option<&'static str>,
+    flags: &'static [Flag],
+    /// Completions for positional arguments.
+    positional: Values,
+}
+
+const TOOLS: &[Tool] = &[
+    Tool {
+        command: "agents install",
+        binary: Some("install-agents"),
+        flags: &[
+            with("--scope", Values::Words(SCOPES)),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--dst", Values::Dir),
+            with("--config", Values::File),
+            with("--locale", Values::Text),
+            with("--force-agent", Values::Text),
+            flag("--dry-run"),
+            flag("--diff"),
+            flag("--force"),
+            flag("--interactive"),
+            flag("--clean"),
+            flag("--backup"),
+            flag("--restore"),
+            flag("--link"),
+            flag("--no-snapshot"),
+            flag("--no-cache"),
+            flag("--continue-on-error"),
+            flag("--yes"),
+            with("--report", Values::File),
+            flag("--detailed-exit-codes"),
+            flag("--json"),
+            flag("--progress"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "skills install",
+        binary: Some("install-skills"),
+        flags: &[
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--scope", Values::Words(SCOPES)),
+            with("--dst", Values::Dir),
+            with("--config", Values::File),
+            with("--agents-dir", Values::Dir),
+            flag("--include-agent-wrappers"),
+            flag("--dry-run"),
+            flag("--clean"),
+            flag("--link"),
+            flag("--no-snapshot"),
+            flag("--no-cache"),
+            flag("--continue-on-error"),
+            flag("--yes"),
+            with("--report", Values::File),
+            flag("--detailed-exit-codes"),
+            flag("--json"),
+            flag("--progress"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "commands install",
+        binary: Some("install-commands"),
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace"])),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--dst", Values::Dir),
+            with("--config", Values::File),
+            flag("--dry-run"),
+            flag("--clean"),
+            flag("--no-snapshot"),
+            flag("--yes"),
+            with("--report", Values::File),
+            flag("--detailed-exit-codes"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "hooks install",
+        binary: Some("install-hooks"),
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace", "project"])),
+            with("--dst", Values::File),
+            flag("--dry-run"),
+            flag("--clean"),
+            flag("--no-snapshot"),
+            flag("--yes"),
+            with("--report", Values::File),
+            flag("--detailed-exit-codes"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "mcp install",
+        binary: Some("install-mcp"),
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace"])),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--dst", Values::File),
+            with("--config", Values::File),
+            flag("--dry-run"),
+            flag("--clean"),
+            flag("--no-snapshot"),
+            flag("--yes"),
+            with("--report", Values::File),
+            flag("--detailed-exit-codes"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "memory install",
+        binary: Some("install-memory"),
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace"])),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--dst", Values::File),
+            with("--config", Values::File),
+            flag("--dry-run"),
+            flag("--clean"),
+            flag("--no-snapshot"),
+            flag("--yes"),
+            with("--report", Values::File),
+            flag("--detailed-exit-codes"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "styles install",
+        binary: Some("install-styles"),
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace"])),
+            with("--dst", Values::Dir),
+            with("--settings", Values::File),
+            flag("--dry-run"),
+            flag("--clean"),
+            flag("--no-snapshot"),
+            flag("--yes"),
+            with("--report", Values::File),
+            flag("--detailed-exit-codes"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "validate",
+        binary: Some("validate-module"),
+        flags: &[flag("--json"), flag("--no-color")],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "council generate",
+        binary: None,
+        flags: &[
+            flag("--check"),
+            flag("--dry-run"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "plugin generate",
+        binary: None,
+        flags: &[
+            flag("--check"),
+            flag("--dry-run"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "graph",
+        binary: None,
+        flags: &[
+            with("--format", Values::Words(&["dot", "mermaid"])),
+            with("--out", Values::File),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "export",
+        binary: None,
+        flags: &[
+            with("--format", Values::Words(&["openai", "plain"])),
+            with("--out", Values::File),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "new",
+        binary: None,
+        flags: &[
+            with("--dir", Values::Dir),
+            with("--root", Values::Dir),
+            flag("--dry-run"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Words(&["module", "agent", "skill"]),
+    },
+    Tool {
+        command: "pack",
+        binary: None,
+        flags: &[
+            with("--out", Values::Dir),
+            flag("--dry-run"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "search",
+        binary: None,
+        flags: &[
+            with("--registry", Values::Text),
+            flag("--insecure"),
+            flag("--json"),
+        ],
+        positional: Values::None,
+    },
+    Tool {
+        command: "install",
+        binary: None,
+        flags: &[
+            with("--registry", Values::Text),
+            flag("--insecure"),
+            with("--scope", Values::Words(SCOPES)),
+            with("--provider", Values::Words(PROVIDERS)),
+            flag("--dry-run"),
+            flag("--yes"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::None,
+    },
+    Tool {
+        command: "update",
+        binary: None,
+        flags: &[
+            with("--registry", Values::Text),
+            flag("--insecure"),
+            with("--scope", Values::Words(SCOPES)),
+            with("--provider", Values::Words(PROVIDERS)),
+            flag("--dry-run"),
+            flag("--yes"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::None,
+    },
+    Tool {
+        command: "outdated",
+        binary: None,
+        flags: &[
+            with("--registry", Values::Text),
+            flag("--insecure"),
+            with("--scope", Values::Words(&["user", "workspace", "all"])),
+            with("--provider", Values::Words(PROVIDERS)),
+            flag("--redeploy"),
+            flag("--dry-run"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "status",
+        binary: None,
+        flags: &[
+            with("--scope", Values::Words(SCOPES)),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--dst", Values::Dir),
+            with("--config", Values::File),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "verify",
+        binary: None,
+        flags: &[
+            with("--scope", Values::Words(SCOPES)),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--dst", Values::Dir),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::None,
+    },
+    Tool {
+        command: "snapshot",
+        binary: None,
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace", "all"])),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--label", Values::Text),
+            flag("--dry-run"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Words(&["create", "restore", "list"]),
+    },
+    Tool {
+        command: "history",
+        binary: None,
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace", "all"])),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--module", Values::Text),
+            with("--limit", Values::Text),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "migrate",
+        binary: None,
+        flags: &[
+            with("--scope", Values::Words(&["user", "workspace", "all"])),
+            with("--provider", Values::Words(PROVIDERS)),
+            flag("--dry-run"),
+            flag("--json"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "watch",
+        binary: None,
+        flags: &[
+            with("--interval", Values::Text),
+            with("--scope", Values::Words(SCOPES)),
+            with("--provider", Values::Words(PROVIDERS)),
+            with("--config", Values::File),
+            flag("--dry-run"),
+            flag("--no-color"),
+        ],
+        positional: Values::Dir,
+    },
+    Tool {
+        command: "yaml",
+        binary: Some("yaml"),
+        flags: &[
+            with("--doc", Values::Text),
+            flag("--all-docs"),
+            flag("--shell-quote"),
+            flag("--raw"),
+            flag("--escape-newlines"),
+            flag("--resolve-aliases"),
+            flag("--keep-aliases"),
+            flag("--strict"),
+            flag("--tab"),
+            flag("--in-place"),
+            with("--prefix", Values::Text),
+            with("--format", Values::Text),
+            with("--sep", Values::Text),
+            with("--default", Values::Text),
+            with("--where", Values::Text),
+            with("--to", Values::Words(&["json", "toml", "yaml"])),
+        ],
+        positional: Values::Words(&[
+            "get",
+            "value",
+            "list",
+            "map",
+            "keys",
+            "count",
+            "nested",
+            "exists",
+            "paths",
+            "set",
+            "delete",
+            "sort-keys",
+            "merge",
+            "env",
+            "envsubst",
+            "diff",
+            "validate",
+            "convert",
+        ]),
+    },
+    Tool {
+        command: "strip",
+        binary: Some("strip-front"),
+        flags: &[with("--keep", Values::Text)],
+        positional: Values::File,
+    },
+    Tool {
+        command: "completions",
+        binary: None,
+        flags: &[],
+        positional: Values::Words(SHELLS),
+    },
+];
+
+const GLOBAL_FLAGS: &[Flag] = &[
+    with("--scope", Values::Words(SCOPES)),
+    with("--provider", Values::Words(PROVIDERS)),
+    flag("--json"),
+    flag("--dry-run"),
+    flag("--no-color"),
+    flag("--help"),
+    flag("--version"),
+];
+
+fn top_level_commands() -> Vec<&'static str> {
+    let mut cmds: Vec<&str> = Vec::new();
+    for tool in TOOLS {
+        let first = tool.command.split(' ').next().unwrap_or(tool.command);
+        if !cmds.contains(&first) {
+            cmds.push(first);
+        }
+    }
+    cmds
+}
+
+/// Commands that take an action word (`agents install`), by group name.
+fn groups() -> Vec<&'static str> {
+    TOOLS
+        .iter()
+        .filter_map(|t| t.command.split_once(' ').map(|(group, _)| group))
+        .collect()
+}
+
+fn flag_names(flags: &[Flag]) -> String {
+    flags.iter().map(|f| f.long).collect::<Vec<_>>().join(" ")
+}
+
+fn fn_name(tool: &Tool) -> String {
+    format!("_forge_{}", tool.command.replace([' ', '-'], "_"))
+}
+
+// ─── bash ───
+
+fn bash_value_cases(out: &mut String) {
+    let mut seen: Vec<&str> = Vec::new();
+    for f in TOOLS
+        .iter()
+        .flat_map(|t| t.flags.iter())
+        .chain(GLOBAL_FLAGS)
+    {
+        if seen.contains(&f.long) {
+            continue;
+        }
+        let reply = match f.values {
+            Values::Text => "COMPREPLY=()".to_string(),
+            Values::None => continue,
+            Values::Words(words) => {
+                format!(
+                    "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                    words.join(" ")
+                )
+            }
+            Values::Dir => "COMPREPLY=($(compgen -d -- \"$cur\"))".to_string(),
+            Values::File => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+        };
+        seen.push(f.long);
+        let _ = writeln!(out, "        {}) {reply}; return 0 ;;", f.long);
+    }
+}
+
+fn bash_positional(values: &Values) -> String {
+    match values {
+        Values::Words(words) => format!("compgen -W \"{}\" -- \"$cur\"", words.join(" ")),
+        Values::Dir => "compgen -d -- \"$cur\"".to_string(),
+        Values::File | Values::None | Values::Text => "compgen -f -- \"$cur\"".to_string(),
+    }
+}
+
+pub fn bash() -> String {
+    let mut out = String::new();
+    out.push_str("# bash completion for forge and forge-lib binaries\n");
+    out.push_str("# Install: forge completions bash > /etc/bash_completion.d/forge\n\n");
+
+    out.push_str("_forge_values() {\n");
+    out.push_str("    case \"$prev\" in\n");
+    bash_value_cases(&mut out);
+    out.push_str("    esac\n");
+    out.push_str("    return 1\n");
+    out.push_str("}\n\n");
+
+    for tool in TOOLS {
+        let _ = writeln!(out, "{}() {{", fn_name(tool));
+        out.push_str("    if [[ \"$cur\" == -* ]]; then\n");
+        let _ = writeln!(
+            out,
+            "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+            flag_names(tool.flags)
+        );
+        out.push_str("    else\n");
+        let _ = writeln!(
+            out,
+            "        COMPREPLY=($({}))",
+            bash_positional(&tool.positional)
+        );
+        out.push_str("    fi\n");
+        out.push_str("}\n\n");
+    }
+
+    out.push_str("_forge() {\n");
+    out.push_str(
+        "    local cur=\"${COMP_WORDS[COMP_CWORD]}\" prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n",
+    );
+    out.push_str("    _forge_values && return 0\n");
+    out.push_str("    local i word cmd=\"\"\n");
+    out.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+    out.push_str("        word=\"${COMP_WORDS[i]}\"\n");
+    out.push_str("        case \"$word\" in\n");
+    out.push_str("            --scope|--provider) ((i++)) ;;\n");
+    out.push_str("            -*) ;;\n");
+    out.push_str("            *) cmd=\"${cmd:+$cmd }$word\" ;;\n");
+    out.push_str("        esac\n");
+    out.push_str("        case \"$cmd\" in\n");
+    for tool in TOOLS {
+        let _ = writeln!(
+            out,
+            "            \"{}\") {}; return 0 ;;",
+            tool.command,
+            fn_name(tool)
+        );
+    }
+    out.push_str("        esac\n");
+    out.push_str("    done\n");
+    out.push_str("    case \"$cmd\" in\n");
+    let _ = writeln!(
+        out,
+        "        {}) COMPREPLY=($(compgen -W \"install\" -- \"$cur\")) ;;",
+        groups().join("|")
+    );
+    let _ = writeln!(
+        out,
+        "        *) COMPREPLY=($(compgen -W \"{} {}\" -- \"$cur\")) ;;",
+        top_level_commands().join(" "),
+        flag_names(GLOBAL_FLAGS)
+    );
+    out.push_str("    esac\n");
+    out.push_str("}\n\n");
+
+    for tool in TOOLS {
+        if let Some(binary) = tool.binary {
+            let wrapper = format!("_{}", binary.replace('-', "_"));
+            let _ = writeln!(out, "{wrapper}() {{");
+            out.push_str("    local cur=\"${COMP_WORDS[COMP_CWORD]}\" prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+            let _ = writeln!(out, "    _forge_values || {}", fn_name(tool));
+            out.push_str("}\n");
+            let _ = writeln!(out, "complete -o default -F {wrapper} {binary}\n");
+        }
+    }
+    out.push_str("complete -o default -F _forge forge\n");
+    out
+}
+
+// ─── zsh ───
+
+fn zsh_action(values: &Values) -> String {
+    match values {
+        Values::None => String::new(),
+        Values::Text => ":value:".to_string(),
+        Values::Words(words) => format!(":value:({})", words.join(" ")),
+        Values::Dir => ":directory:_files -/".to_string(),
+        Values::File => ":file:_files".to_string(),
+    }
+}
+
+fn zsh_arguments(tool: &Tool, indent: &str) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "{indent}_arguments");
+    for f in tool.flags {
+        let value = zsh_action(&f.values);
+        let _ = write!(out, " \\\n{indent}    '{}[]{value}'", f.long);
+    }
+    let positional = match tool.positional {
+        Values::None | Values::Text => String::new(),
+        ref v => zsh_action(v),
+    };
+    if !positional.is_empty() {
+        let _ = write!(out, " \\\n{indent}    '*{positional}'");
+    }
+    out.push('\n');
+    out
+}
+
+pub fn zsh() -> String {
+    let mut out = String::new();
+    let binaries: Vec<&str> = TOOLS.iter().filter_map(|t| t.binary).collect();
+    let _ = writeln!(out, "#compdef forge {}", binaries.join(" "));
+    out.push_str("# zsh completion for forge and forge-lib binaries\n");
+    out.push_str("# Install: forge completions zsh > \"${fpath[1]}/_forge\"\n\n");
+
+    for tool in TOOLS {
+        let _ = writeln!(out, "{}() {{", fn_name(tool));
+        out.push_str(&zsh_arguments(tool, "    "));
+        out.push_str("}\n\n");
+    }
+
+    out.push_str("_forge_main() {\n");
+    out.push_str("    local -a globals\n");
+    out.push_str("    globals=(\n");
+    for f in GLOBAL_FLAGS {
+        let _ = writeln!(out, "        '{}[]{}'", f.long, zsh_action(&f.values));
+    }
+    out.push_str("    )\n");
+    let _ = writeln!(
+        out,
+        "    _arguments -C \"${{globals[@]}}\" '1:command:({})' '*::arg:->args'",
+        top_level_commands().join(" ")
+    );
+    out.push_str("    case \"$words[1]\" in\n");
+    let _ = writeln!(out, "        {})", groups().join("|"));
+    out.push_str("            if (( CURRENT == 2 )); then\n");
+    out.push_str("                _values action install\n");
+    out.push_str("            else\n");
+    out.push_str("                local group=$words[1]\n");
+    out.push_str("                shift words; (( CURRENT-- ))\n");
+    out.push_str("                _forge_${group}_install\n");
+    out.push_str("            fi ;;\n");
+    for tool in TOOLS.iter().filter(|t| !t.command.contains(' ')) {
+        let _ = writeln!(out, "        {}) {} ;;", tool.command, fn_name(tool));
+    }
+    out.push_str("    esac\n");
+    out.push_str("}\n\n");
+
+    out.push_str("case \"$service\" in\n");
+    for tool in TOOLS {
+        if let Some(binary) = tool.binary {
+            let _ = writeln!(out, "    {binary}) {} ;;", fn_name(tool));
+        }
+    }
+    out.push_str("    *) _forge_main ;;\n");
+    out.push_str("esac\n");
+    out
+}
+
+// ─── fish ───
+
+fn fish_flag(out: &mut String, command: &str, condition: Option<&str>, f: &Flag) {
+    let long = f.long.trim_start_matches('-');
+    let _ = write!(out, "complete -c {command}");
+    if let Some(cond) = condition {
+        let _ = write!(out, " -n '{cond}'");
+    }
+    let _ = write!(out, " -l {long}");
+    match f.values {
+        Values::Text => out.push_str(" -x"),
+        Values::None => {}
+        Values::Words(words) => {
+            let _ = write!(out, " -xa '{}'", words.join(" "));
+        }
+        Values::Dir => out.push_str(" -xa '(__fish_complete_directories)'"),
+        Values::File => out.push_str(" -rF"),
+    }
+    out.push('\n');
+}
+
+fn fish_positional(out: &mut String, command: &str, condition: Option<&str>, values: &Values) {
+    let Values::Words(words) = values else {
+        return;
+    };
+    let _ = write!(out, "complete -c {command} -f");
+    if let Some(cond) = condition {
+        let _ = write!(out, " -n '{cond}'");
+    }
+    let _ = writeln!(out, " -a '{}'", words.join(" "));
+}
+
+pub fn fish() -> String {
+    let mut out = String::new();
+    out.push_str("# fish completion for forge and forge-lib binaries\n");
+    out.push_str("# Install: forge completions fish > ~/.config/fish/completions/forge.fish\n\n");
+
+    let _ = writeln!(
+        out,
+        "complete -c forge -f -n '__fish_use_subcommand' -a '{}'",
+        top_level_commands().join(" ")
+    );
+    for f in GLOBAL_FLAGS {
+        fish_flag(&mut out, "forge", Some("__fish_use_subcommand"), f);
+    }
+    let _ = writeln!(
+        out,
+        "complete -c forge -f -n '__fish_seen_subcommand_from {}; \
+         and not __fish_seen_subcommand_from install' -a install",
+        groups().join(" ")
+    );
+
+    for tool in TOOLS {
+        let mut parts = tool.command.split(' ');
+        let first = parts.next().unwrap_or(tool.command);
+        let condition = match parts.next() {
+            Some(action) => format!(
+                "__fish_seen_subcommand_from {first}; and __fish_seen_subcommand_from {action}"
+            ),
+            None => format!("__fish_seen_subcommand_from {first}"),
+        };
+        out.push('\n');
+        for f in tool.flags {
+            fish_flag(&mut out, "forge", Some(&condition), f);
+        }
+        fish_positional(&mut out, "forge", Some(&condition), &tool.positional);
+
+        if let Some(binary) = tool.binary {
+            for f in tool.flags {
+                fish_flag(&mut out, binary, None, f);
+            }
+            fish_positional(
+                &mut out,
+                binary,
+                Some("__fish_is_first_arg"),
+                &tool.positional,
+            );
+        }
+    }
+    out
+}
+
+/// Run `forge completions`.
+pub fn run(args: &[String]) -> ExitCode {
+    let script = match args.first().map(String::as_str) {
+        Some("bash") => bash(),
+        Some("zsh") => zsh(),
+        Some("fish") => fish(),
+        Some("-h" | "--help") => {
+            println!("Usage: forge completions <bash|zsh|fish>");
+            return ExitCode::SUCCESS;
+        }
+        Some(other) => {
+            eprintln!("Error: unsupported shell {other:?}: use bash, zsh, or fish");
+            return ExitCode::from(1);
+        }
+        None => {
+            eprintln!("Usage: forge completions <bash|zsh|fish>");
+            return ExitCode::from(1);
+        }
+    };
+    print!("{script}");
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,121 @@
+//! `forge council generate`: write each council's SKILL.md and SKILL.yaml
+//! from the `councils:` roster in defaults.yaml.
+
+use super::Reporter;
+use crate::council::{self, CouncilResult};
+use crate::sidecar::SidecarConfig;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge council generate [module-root] [--check] [--dry-run] [--json] [--no-color]
+       --check exits 1 when a generated council skill is out of date";
+
+struct Args {
+    module_root: PathBuf,
+    check: bool,
+    dry_run: bool,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        module_root: PathBuf::from("."),
+        check: false,
+        dry_run: false,
+        json: false,
+    };
+    for arg in args {
+        match arg.as_str() {
+            "--check" => parsed.check = true,
+            "--dry-run" => parsed.dry_run = true,
+            "--json" => parsed.json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.module_root = PathBuf::from(arg),
+        }
+    }
+    Ok(parsed)
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<ExitCode, String> {
+    if !args.module_root.is_dir() {
+        return Err(format!("not a directory: {}", args.module_root.display()));
+    }
+    let config = SidecarConfig::load(&args.module_root);
+    let skills = council::generate_council_skills(&args.module_root, &config)?;
+    if skills.is_empty() {
+        out.note("No councils in defaults.yaml");
+        return Ok(ExitCode::from(super::EXIT_NOTHING));
+    }
+
+    let skills_dir = args.module_root.join("skills");
+    let mut stale = false;
+    for skill in &skills {
+        let dir = skills_dir.join(&skill.name);
+        match council::write_council_skill(&skills_dir, skill, args.dry_run || args.check)? {
+            CouncilResult::Written if args.check => {
+                stale = true;
+                out.warn(
+                    "stale",
+                    &skill.name,
+                    Some(&dir),
+                    format!(
+                        "{} is out of date with councils.{} (run forge council generate)",
+                        dir.display(),
+                        skill.name
+                    ),
+                );
+            }
+            CouncilResult::Written => {
+                let message = if args.dry_run {
+                    format!("[dry-run] Would generate: {}", dir.display())
+                } else {
+                    format!("Generated: {}", dir.display())
+                };
+                out.info("create", &skill.name, Some(&dir), message);
+            }
+            CouncilResult::Unchanged => out.note(&format!("Up to date: {}", dir.display())),
+            CouncilResult::SkippedUserOwned => out.warn(
+                "skip-user-owned",
+                &skill.name,
+                Some(&dir),
+                format!(
+                    "Skipping {} — user-owned skill (no generation: method: {})",
+                    dir.display(),
+                    council::GENERATION_METHOD
+                ),
+            ),
+        }
+    }
+    if stale {
+        return Ok(ExitCode::from(super::EXIT_ERROR));
+    }
+    if args.check {
+        return Ok(ExitCode::from(super::EXIT_OK));
+    }
+    Ok(out.exit_code())
+}
+
+/// Run `forge council generate`.
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            let mut out = Reporter::new(args.json);
+            let code = execute(args, &mut out).unwrap_or_else(|e| {
+                eprintln!("Error: {e}");
+                ExitCode::from(1)
+            });
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
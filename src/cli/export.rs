@@ -0,0 +1,103 @@
+//! `forge export`: write a module's agents in formats for tools forge does
+//! not deploy to.
+
+use super::flag_value;
+use crate::export;
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge export [module-root] --format openai|plain [--out <path>]
+       openai: JSON bundle of OpenAI assistant definitions (--out <file>)
+       plain:  system prompts with model/tool comments; one concatenated
+               document on stdout, or <name>.txt per agent with --out <dir>
+       Without --out, the export is printed to stdout.";
+
+struct Args {
+    module_root: PathBuf,
+    format: Option<String>,
+    out: Option<PathBuf>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        module_root: PathBuf::from("."),
+        format: None,
+        out: None,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => parsed.format = Some(flag_value(args, &mut i)?.to_string()),
+            "--out" => parsed.out = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.module_root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+fn execute(args: &Args) -> Result<(), String> {
+    if !args.module_root.is_dir() {
+        return Err(format!("not a directory: {}", args.module_root.display()));
+    }
+    let config = SidecarConfig::load(&args.module_root);
+    let agents = export::load_agents(&args.module_root.join("agents"), &config)?;
+    let module = std::fs::read_to_string(args.module_root.join("module.yaml"))
+        .ok()
+        .and_then(|c| parse::module_name(&c))
+        .unwrap_or_default();
+    let text = match args.format.as_deref() {
+        Some("openai") => {
+            let bundle = export::openai_bundle(&module, &agents);
+            let mut text = serde_json::to_string_pretty(&bundle).unwrap_or_default();
+            text.push('\n');
+            text
+        }
+        Some("plain") => {
+            if let Some(dir) = &args.out {
+                for filename in export::write_plain_dir(dir, &agents)? {
+                    println!("Exported: {}", dir.join(filename).display());
+                }
+                return Ok(());
+            }
+            export::plain_bundle(&agents)
+        }
+        Some(other) => {
+            return Err(format!(
+                "unknown format {other:?} (expected openai or plain)"
+            ))
+        }
+        None => return Err("--format is required (openai or plain)".to_string()),
+    };
+    if let Some(path) = &args.out {
+        return std::fs::write(path, text)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()));
+    }
+    print!("{text}");
+    Ok(())
+}
+
+/// Run `forge export`.
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => match execute(args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                ExitCode::from(1)
+            }
+        },
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,80 @@
+//! `forge graph`: print a module's councils, role agents, and skills as a
+//! DOT or Mermaid diagram.
+
+use super::flag_value;
+use crate::graph;
+use crate::sidecar::SidecarConfig;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge graph [module-root] [--format dot|mermaid] [--out <file>]";
+
+struct Args {
+    module_root: PathBuf,
+    format: String,
+    out: Option<PathBuf>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        module_root: PathBuf::from("."),
+        format: "dot".to_string(),
+        out: None,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => flag_value(args, &mut i)?.clone_into(&mut parsed.format),
+            "--out" => parsed.out = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.module_root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+fn execute(args: &Args) -> Result<(), String> {
+    if !args.module_root.is_dir() {
+        return Err(format!("not a directory: {}", args.module_root.display()));
+    }
+    let config = SidecarConfig::load(&args.module_root);
+    let graph = graph::build(&args.module_root, &config);
+    let text = match args.format.as_str() {
+        "dot" => graph::to_dot(&graph),
+        "mermaid" => graph::to_mermaid(&graph),
+        other => {
+            return Err(format!(
+                "unknown format {other:?} (expected dot or mermaid)"
+            ))
+        }
+    };
+    if let Some(path) = &args.out {
+        return std::fs::write(path, text)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()));
+    }
+    print!("{text}");
+    Ok(())
+}
+
+/// Run `forge graph`.
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => match execute(args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                ExitCode::from(1)
+            }
+        },
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,219 @@
+//! The install-side half of the audit log (appending one entry per
+//! destination root and provider when a run finishes) and `forge history`,
+//! which prints it.
+
+use super::{exit_status, flag_value, report, Record, EXIT_OK, EXIT_PARTIAL, EXIT_SKIPPED};
+use crate::history::{self, Change, Entry};
+use crate::outdated;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USAGE: &str = "\
+Usage: forge history [dest-root...] [--scope <user|workspace|all>] [--provider <name>]
+                     [--module <name>] [--limit <n>] [--json] [--no-color]
+       Destination roots are provider directories such as ~/.claude.";
+
+const PROVIDERS: &[&str] = &[
+    "claude", "gemini", "codex", "opencode", "cursor", "windsurf", "copilot", "zed",
+];
+
+/// What a run was, for its history entries.
+pub(super) struct Context {
+    pub(super) tool: String,
+    pub(super) operation: &'static str,
+    pub(super) scope: Option<String>,
+    pub(super) module: Option<String>,
+}
+
+fn result_word(records: &[&Record]) -> &'static str {
+    match exit_status(records) {
+        EXIT_PARTIAL => "partial",
+        EXIT_SKIPPED => "skipped",
+        EXIT_OK => "ok",
+        _ => "unchanged",
+    }
+}
+
+/// Append one entry per destination root and provider that `records`
+/// touched. Records without a target (e.g. `gemini skills install`) have
+/// no root to log under.
+pub(super) fn append(context: &Context, records: &[Record]) -> Result<(), String> {
+    let mut groups: Vec<(PathBuf, Option<&str>, Vec<&Record>)> = Vec::new();
+    for record in records {
+        // Agents the deploy cache skipped were not written.
+        let Some(target) = record
+            .target
+            .as_ref()
+            .filter(|_| record.action != "unchanged")
+        else {
+            continue;
+        };
+        let root = history::destination_root(Path::new(target));
+        let provider = record.provider.as_deref();
+        match groups
+            .iter_mut()
+            .find(|(r, p, _)| *r == root && *p == provider)
+        {
+            Some((_, _, group)) => group.push(record),
+            None => groups.push((root, provider, vec![record])),
+        }
+    }
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let timestamp = report::utc_timestamp(secs);
+    for (root, provider, group) in groups {
+        let entry = Entry {
+            timestamp: timestamp.clone(),
+            tool: context.tool.clone(),
+            operation: context.operation.to_string(),
+            module: context.module.clone(),
+            provider: provider.map(String::from),
+            scope: context.scope.clone(),
+            result: result_word(&group).to_string(),
+            files: group
+                .iter()
+                .map(|r| Change {
+                    action: r.action.clone(),
+                    name: r.name.clone(),
+                })
+                .collect(),
+        };
+        history::append(&root, &entry)?;
+    }
+    Ok(())
+}
+
+// ─── forge history ───
+
+struct Args {
+    roots: Vec<PathBuf>,
+    scope: String,
+    provider: Option<String>,
+    module: Option<String>,
+    limit: Option<usize>,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        roots: Vec::new(),
+        scope: "all".to_string(),
+        provider: None,
+        module: None,
+        limit: None,
+        json: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--scope" => flag_value(args, &mut i)?.clone_into(&mut parsed.scope),
+            "--provider" => parsed.provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--module" => parsed.module = Some(flag_value(args, &mut i)?.to_string()),
+            "--limit" => {
+                let value = flag_value(args, &mut i)?;
+                let Ok(n) = value.parse() else {
+                    eprintln!("Error: invalid --limit {value:?}: expected a number");
+                    return Err(ExitCode::from(1));
+                };
+                parsed.limit = Some(n);
+            }
+            "--json" => parsed.json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.roots.push(PathBuf::from(arg)),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+fn roots(args: &Args) -> Result<Vec<PathBuf>, String> {
+    if !args.roots.is_empty() {
+        return Ok(args.roots.clone());
+    }
+    let home = PathBuf::from(env::var("HOME").unwrap_or_default());
+    let providers: Vec<String> = match &args.provider {
+        Some(p) => vec![p.clone()],
+        None => PROVIDERS.iter().map(|p| (*p).to_string()).collect(),
+    };
+    outdated::provider_roots(&home, &providers, &args.scope)
+}
+
+fn execute(args: &Args) -> Result<ExitCode, String> {
+    let mut entries: Vec<(PathBuf, Entry)> = Vec::new();
+    for root in roots(args)? {
+        for entry in history::read(&root)? {
+            entries.push((root.clone(), entry));
+        }
+    }
+    entries.retain(|(_, e)| {
+        args.module
+            .as_ref()
+            .is_none_or(|m| e.module.as_ref() == Some(m))
+            && args
+                .provider
+                .as_ref()
+                .is_none_or(|p| e.provider.as_ref() == Some(p))
+    });
+    entries.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp));
+    if let Some(limit) = args.limit {
+        entries.drain(..entries.len().saturating_sub(limit));
+    }
+
+    if args.json {
+        let doc: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|(root, entry)| {
+                let mut value = serde_json::to_value(entry).unwrap_or_default();
+                value["root"] = root.display().to_string().into();
+                value
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&doc).unwrap_or_default());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if entries.is_empty() {
+        eprintln!("No deploy history found");
+        return Ok(ExitCode::from(4));
+    }
+    for (root, entry) in &entries {
+        let module = entry.module.as_deref().unwrap_or("-");
+        let provider = entry.provider.as_deref().unwrap_or("-");
+        let scope = entry.scope.as_deref().unwrap_or("-");
+        println!(
+            "{} {} {} {module} ({provider}, {scope}) in {}: {}",
+            entry.timestamp,
+            entry.tool,
+            entry.operation,
+            root.display(),
+            entry.result
+        );
+        for change in &entry.files {
+            println!("  {} {}", change.action, change.name);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Run `forge history`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    execute(&args).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })
+}
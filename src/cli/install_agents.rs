@@ -0,0 +1,855 @@
+use super::{flag_value, Reporter};
+use crate::deploy::provider::{CustomProvider, Provider};
+use crate::deploy::{self, CodexConfigEntry, DeployOptions, DeployResult};
+use crate::manifest;
+use crate::merge;
+use crate::parse;
+use crate::policy::Policy;
+use crate::sidecar::SidecarConfig;
+use crate::vfs::RealFs;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+const USAGE: &str = "Usage: install-agents <agents-dir> [--scope user|workspace|project|all] \
+                     [--provider claude|gemini|codex|opencode|cursor|windsurf|copilot|zed|<custom>] [--dry-run] [--diff] [--force] [--force-agent <name>]... [--interactive] \
+                     [--clean] [--backup] [--restore] [--link] [--no-snapshot] [--no-cache] [--dst <path>] [--config <file>]... [--locale <code>] \
+                     [--continue-on-error] [--yes] \
+                     [--report <file>] [--detailed-exit-codes] [--json] [--progress] [--no-color]";
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Args {
+    src_dir: String,
+    scope: String,
+    /// Resolved against the module's config, which may declare custom
+    /// providers.
+    provider: Option<String>,
+    dry_run: bool,
+    /// Print a unified diff of every file a deploy would change; implies
+    /// `dry_run`.
+    diff: bool,
+    force: bool,
+    /// Agents to overwrite as with `--force`, leaving the others alone.
+    force_agents: Vec<String>,
+    /// Ask what to do with each user-created agent instead of skipping it.
+    interactive: bool,
+    clean: bool,
+    /// Save the files a deploy replaces under `.forge-state/<dir>/backup/`.
+    backup: bool,
+    /// Bring back what the last `--backup` deploy replaced instead of
+    /// deploying.
+    restore: bool,
+    /// Symlink agents to their sources instead of rendering them.
+    link: bool,
+    no_snapshot: bool,
+    no_cache: bool,
+    continue_on_error: bool,
+    yes: bool,
+    json: bool,
+    report: Option<PathBuf>,
+    /// Exit 3 when items were skipped and 4 when there was nothing to do.
+    detailed_exit_codes: bool,
+    progress: bool,
+    config: Vec<PathBuf>,
+    locale: Option<String>,
+    dst_override: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut src_dir: Option<String> = None;
+    let mut scope = "all".to_string();
+    let mut provider: Option<String> = None;
+    let mut dry_run = false;
+    let mut diff = false;
+    let mut force = false;
+    let mut force_agents: Vec<String> = Vec::new();
+    let mut interactive = false;
+    let mut clean = false;
+    let mut backup = false;
+    let mut restore = false;
+    let mut link = false;
+    let mut no_snapshot = false;
+    let mut no_cache = false;
+    let mut continue_on_error = false;
+    let mut yes = false;
+    let mut json = false;
+    let mut report: Option<PathBuf> = None;
+    let mut detailed_exit_codes = false;
+    let mut progress = false;
+    let mut config: Vec<PathBuf> = Vec::new();
+    let mut locale: Option<String> = None;
+    let mut dst_override: Option<String> = None;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                println!("install-agents {}", env!("CARGO_PKG_VERSION"));
+                return Err(ExitCode::SUCCESS);
+            }
+            "--dry-run" => dry_run = true,
+            "--diff" => diff = true,
+            "--force" => force = true,
+            "--force-agent" => force_agents.push(flag_value(args, &mut i)?.to_string()),
+            "--interactive" => interactive = true,
+            "--clean" => clean = true,
+            "--backup" => backup = true,
+            "--restore" => restore = true,
+            "--link" => link = true,
+            "--no-snapshot" => no_snapshot = true,
+            "--no-cache" => no_cache = true,
+            "--continue-on-error" => continue_on_error = true,
+            "-y" | "--yes" => yes = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--detailed-exit-codes" => detailed_exit_codes = true,
+            "--progress" => progress = true,
+            "--no-color" => super::style::disable(),
+            "--scope" => scope = flag_value(args, &mut i)?.to_string(),
+            "--provider" => provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--config" => config.push(PathBuf::from(flag_value(args, &mut i)?)),
+            "--locale" => locale = Some(flag_value(args, &mut i)?.to_string()),
+            "--dst" => dst_override = Some(flag_value(args, &mut i)?.to_string()),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            _ => {
+                src_dir = Some(args[i].clone());
+            }
+        }
+        i += 1;
+    }
+
+    let Some(src_dir) = src_dir else {
+        eprintln!("Error: source directory required.");
+        eprintln!("{USAGE}");
+        return Err(ExitCode::from(1));
+    };
+
+    Ok(Args {
+        src_dir,
+        scope,
+        provider,
+        dry_run: dry_run || diff,
+        diff,
+        force,
+        force_agents,
+        interactive,
+        clean,
+        backup,
+        restore,
+        link,
+        no_snapshot,
+        no_cache,
+        continue_on_error,
+        yes,
+        json,
+        report,
+        detailed_exit_codes,
+        progress,
+        config,
+        locale,
+        dst_override,
+    })
+}
+
+pub(super) fn read_module_field(
+    input_dir: &Path,
+    field: fn(&str) -> Option<String>,
+) -> Option<String> {
+    let module_root = input_dir.parent()?;
+    let content = std::fs::read_to_string(module_root.join("module.yaml")).ok()?;
+    field(&content)
+}
+
+fn sync_manifest(
+    out: &mut Reporter,
+    dst_dir: &Path,
+    module: (&str, Option<&str>),
+    installed: &[String],
+    provider: &Provider,
+    dry_run: bool,
+) {
+    let (module_name, version) = module;
+    match deploy::clean_orphaned_agents(&RealFs, dst_dir, module_name, installed, provider, dry_run)
+    {
+        Ok(orphans) => {
+            for name in &orphans {
+                let file = provider.agent_filename(name);
+                let message = if dry_run {
+                    format!("[dry-run] Would remove orphan: {file}")
+                } else {
+                    format!("Removed orphan: {file}")
+                };
+                out.info("remove-orphan", name, Some(dst_dir), message);
+            }
+        }
+        Err(e) => out.warn(
+            "error",
+            "",
+            Some(dst_dir),
+            format!("orphan scan failed: {e}"),
+        ),
+    }
+
+    if !dry_run {
+        let mut files = Vec::new();
+        for name in installed {
+            files.push(provider.agent_filename(name));
+            if *provider == Provider::Codex {
+                files.push(format!("{name}.prompt.md"));
+            }
+        }
+        let recorded = manifest::record(&RealFs, dst_dir, module_name, version, installed)
+            .and_then(|()| manifest::record_checksums(&RealFs, dst_dir, module_name, &files));
+        if let Err(e) = recorded {
+            out.warn(
+                "error",
+                "",
+                Some(dst_dir),
+                format!("manifest update failed: {e}"),
+            );
+        }
+    }
+}
+
+fn sync_codex_config(
+    out: &mut Reporter,
+    dst_dir: &Path,
+    entries: &[CodexConfigEntry],
+    config: &SidecarConfig,
+    source_prefix: &str,
+    dry_run: bool,
+) -> Result<(), ExitCode> {
+    let codex_root = dst_dir.parent().unwrap_or(dst_dir);
+    let config_path = codex_root.join("config.toml");
+    if let Err(e) = deploy::write_codex_config_block(
+        &RealFs,
+        &config_path,
+        entries,
+        source_prefix,
+        config.templates(),
+        dry_run,
+    ) {
+        eprintln!("Error writing config.toml: {e}");
+        return Err(ExitCode::from(1));
+    }
+    let message = if dry_run {
+        format!(
+            "[dry-run] Would write config.toml with {} agent entries",
+            entries.len()
+        )
+    } else {
+        format!(
+            "Updated {} with {} agent entries",
+            config_path.display(),
+            entries.len()
+        )
+    };
+    out.info("update-config", "config.toml", Some(&config_path), message);
+    Ok(())
+}
+
+fn resolve_dirs(args: &Args, config: &SidecarConfig) -> Result<Vec<(PathBuf, Provider)>, String> {
+    target_dirs(
+        &args.scope,
+        args.provider.as_deref(),
+        args.dst_override.as_deref(),
+        config,
+    )
+}
+
+/// The agent directories to deploy to, each with its provider: `dst` for
+/// `provider` (or the provider its path names), or else every configured
+/// provider's directory for `scope`. Providers the config declares resolve
+/// by name and directory alongside the built-in ones.
+pub(super) fn target_dirs(
+    scope: &str,
+    provider: Option<&str>,
+    dst: Option<&str>,
+    config: &SidecarConfig,
+) -> Result<Vec<(PathBuf, Provider)>, String> {
+    let custom: Vec<Arc<CustomProvider>> = config
+        .custom_providers()?
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+    let provider = match provider {
+        Some(name) => Some(Provider::named(name, &custom).ok_or_else(|| {
+            format!(
+                "invalid provider {name:?}: use claude, gemini, codex, opencode, cursor, \
+                 windsurf, copilot, zed, or a provider declared in defaults.yaml"
+            )
+        })?),
+        None => None,
+    };
+
+    if let Some(dst) = dst {
+        let dst = PathBuf::from(dst);
+        let provider = provider.unwrap_or_else(|| Provider::for_path(&dst, &custom));
+        return Ok(vec![(dst, provider)]);
+    }
+
+    let home = env::var("HOME").unwrap_or_default();
+    let providers = match provider {
+        Some(p) => vec![p.as_str().to_string()],
+        None => config.providers(),
+    };
+    let dirs = deploy::scope_dirs(scope, Path::new(&home), &providers, &custom)?;
+    Ok(dirs
+        .into_iter()
+        .map(|d| {
+            let provider = Provider::for_path(&d, &custom);
+            (d, provider)
+        })
+        .collect())
+}
+
+/// The module's sidecar config with `--config` overrides, `--locale`, and
+/// the organization policy applied.
+fn load_config(args: &Args, module_root: &Path) -> Result<SidecarConfig, String> {
+    let policy = Policy::locate(&super::registry::forge_home())?;
+    Ok(SidecarConfig::load(module_root)
+        .with_overrides(&args.config)?
+        .with_locale(args.locale.as_deref())
+        .with_policy(policy))
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> ExitCode {
+    let src_path = Path::new(&args.src_dir);
+    if !src_path.is_dir() {
+        eprintln!("Error: not a directory: {}", args.src_dir);
+        return ExitCode::from(1);
+    }
+
+    let module_name = read_module_field(src_path, parse::module_name).unwrap_or_default();
+
+    out.set_module(&module_name);
+    let module_version = read_module_field(src_path, parse::module_version);
+    let source_prefix = if module_name.is_empty() {
+        String::new()
+    } else {
+        format!("{module_name}/{}", args.src_dir)
+    };
+
+    let module_root = src_path.parent().unwrap_or(Path::new("."));
+    let config = match load_config(args, module_root) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    let dirs = match resolve_dirs(args, &config) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    if args.clean {
+        if let Err(code) = snapshot_before_clean(out, args, &dirs) {
+            return code;
+        }
+    }
+
+    for (i, (dst_dir, provider)) in dirs.iter().enumerate() {
+        let (dst_dir, provider) = (dst_dir.as_path(), provider);
+        out.begin_group(format!(
+            "[{}/{}] {} {}",
+            i + 1,
+            dirs.len(),
+            provider.as_str(),
+            dst_dir.display()
+        ));
+        out.set_provider(provider.as_str());
+        out.note(&format!(
+            "Targeting provider directory: {}",
+            dst_dir.display()
+        ));
+
+        if args.restore {
+            if let Err(code) = restore_dir(out, args, src_path, dst_dir, provider) {
+                return code;
+            }
+            out.end_group();
+            continue;
+        }
+
+        if args.clean {
+            if let Err(code) = clean_dir(out, args, src_path, dst_dir, provider) {
+                return code;
+            }
+        }
+
+        let deployed = match deploy_to_dir(
+            out,
+            args,
+            src_path,
+            dst_dir,
+            provider,
+            &config,
+            &source_prefix,
+        ) {
+            Ok(deployed) => deployed,
+            Err(code) => return code,
+        };
+
+        if !module_name.is_empty() {
+            sync_manifest(
+                out,
+                dst_dir,
+                (&module_name, module_version.as_deref()),
+                &deployed.installed,
+                provider,
+                args.dry_run,
+            );
+        }
+
+        if *provider == Provider::Codex {
+            if let Err(code) = sync_codex_config(
+                out,
+                dst_dir,
+                &deployed.codex_entries,
+                &config,
+                &source_prefix,
+                args.dry_run,
+            ) {
+                return code;
+            }
+        }
+        out.end_group();
+    }
+
+    out.exit_code()
+}
+
+fn clean_dir(
+    out: &mut Reporter,
+    args: &Args,
+    src_path: &Path,
+    dst_dir: &Path,
+    provider: &Provider,
+) -> Result<(), ExitCode> {
+    match deploy::clean_agents(&RealFs, src_path, dst_dir, provider, args.dry_run) {
+        Ok(removed) => {
+            for name in &removed {
+                let file = provider.agent_filename(name);
+                let message = if args.dry_run {
+                    format!("[dry-run] Would remove: {file}")
+                } else {
+                    format!("Removed: {file}")
+                };
+                out.info("remove", name, Some(dst_dir), message);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Err(ExitCode::from(1));
+        }
+    }
+
+    if *provider == Provider::Codex {
+        let codex_root = dst_dir.parent().unwrap_or(dst_dir);
+        let config_path = codex_root.join("config.toml");
+        if let Err(e) = deploy::clean_codex_config_block(&RealFs, &config_path, args.dry_run) {
+            eprintln!("Error cleaning config.toml: {e}");
+            return Err(ExitCode::from(1));
+        }
+        let message = if args.dry_run {
+            "[dry-run] Would clean config.toml managed block".to_string()
+        } else {
+            "Cleaned config.toml managed block".to_string()
+        };
+        out.info("clean-config", "config.toml", Some(&config_path), message);
+    }
+    Ok(())
+}
+
+/// Snapshot every directory (and Codex `config.toml`) `--clean` will touch.
+fn snapshot_before_clean(
+    out: &mut Reporter,
+    args: &Args,
+    dirs: &[(PathBuf, Provider)],
+) -> Result<(), ExitCode> {
+    let mut paths = Vec::new();
+    for (dst_dir, provider) in dirs {
+        paths.push(dst_dir.clone());
+        if *provider == Provider::Codex {
+            paths.push(dst_dir.parent().unwrap_or(dst_dir).join("config.toml"));
+        }
+    }
+    super::snapshot::before_clean(out, "agents", &paths, args.dry_run, args.no_snapshot).map_err(
+        |e| {
+            eprintln!("Error: {e}");
+            ExitCode::from(1)
+        },
+    )
+}
+
+fn restore_dir(
+    out: &mut Reporter,
+    args: &Args,
+    src_path: &Path,
+    dst_dir: &Path,
+    provider: &Provider,
+) -> Result<(), ExitCode> {
+    let restored = deploy::restore_agents(&RealFs, src_path, dst_dir, provider, args.dry_run)
+        .map_err(|e| {
+            eprintln!("Error: {e}");
+            ExitCode::from(1)
+        })?;
+    for name in &restored {
+        let file = provider.agent_filename(name);
+        let message = if args.dry_run {
+            format!("[dry-run] Would restore: {file}")
+        } else {
+            format!("Restored: {file}")
+        };
+        out.info("restore", name, Some(dst_dir), message);
+    }
+    Ok(())
+}
+
+/// What deploying to one directory produced for the steps after it: the
+/// agents to record in the manifest and the Codex `config.toml` entries.
+struct Deployed {
+    installed: Vec<String>,
+    codex_entries: Vec<CodexConfigEntry>,
+}
+
+fn deploy_options(
+    args: &Args,
+    provider: &Provider,
+    src_path: &Path,
+    source_prefix: &str,
+) -> Result<DeployOptions, ExitCode> {
+    let options = DeployOptions::new(provider.clone())
+        .with_dry_run(args.dry_run)
+        .with_force(args.force)
+        .with_force_agents(args.force_agents.iter().cloned())
+        .with_source_prefix(source_prefix)
+        .with_cache(!args.no_cache)
+        .with_backup(args.backup);
+    if !args.link {
+        return Ok(options);
+    }
+    // Links must point at the source wherever the destination is.
+    let src_dir = std::fs::canonicalize(src_path).map_err(|e| {
+        eprintln!("Error: failed to resolve {}: {e}", src_path.display());
+        ExitCode::from(1)
+    })?;
+    Ok(options.with_link(src_dir))
+}
+
+fn deploy_to_dir(
+    out: &mut Reporter,
+    args: &Args,
+    src_path: &Path,
+    dst_dir: &Path,
+    provider: &Provider,
+    config: &SidecarConfig,
+    source_prefix: &str,
+) -> Result<Deployed, ExitCode> {
+    let dry_run = args.dry_run;
+    let options = deploy_options(args, provider, src_path, source_prefix)?;
+    let results = if args.continue_on_error {
+        deploy::deploy_agents_from_dir_continue(src_path, dst_dir, config, &options, out)
+    } else {
+        deploy::deploy_plan_from_dir(src_path, dst_dir, config, &options, out).map(|plan| {
+            plan.actions
+                .into_iter()
+                .map(|a| (a.filename.clone(), Ok(a)))
+                .collect()
+        })
+    }
+    .map_err(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })?;
+
+    let mut deployed = Deployed {
+        installed: Vec::new(),
+        codex_entries: Vec::new(),
+    };
+    for (filename, result) in &results {
+        let action = match result {
+            Ok(action) => action,
+            Err(e) => {
+                let name = filename.trim_end_matches(".md");
+                out.warn("error", name, Some(dst_dir), format!("{filename}: {e}"));
+                continue;
+            }
+        };
+        let Some(meta) = &action.meta else {
+            continue;
+        };
+        let name = meta.name.as_str();
+        let file = provider.agent_filename(name);
+        if action.result == DeployResult::SkippedProviderExcluded {
+            out.note(&format!(
+                "Skipping {file} — agents.{name}.providers excludes {}",
+                provider.as_str()
+            ));
+            continue;
+        }
+        deployed.codex_entries.push(CodexConfigEntry {
+            name: meta.name.clone(),
+            description: meta.description.clone(),
+        });
+        match action.result {
+            DeployResult::Deployed => {
+                let forced = options.forces(name);
+                report_deployed(out, args, action, forced, dst_dir, name, &file);
+            }
+            DeployResult::Unchanged => out.info(
+                "unchanged",
+                name,
+                Some(dst_dir),
+                format!("Unchanged: {file}"),
+            ),
+            DeployResult::Merged => report_merge(out, false, dry_run, dst_dir, name, &file),
+            DeployResult::Conflicted => report_merge(out, true, dry_run, dst_dir, name, &file),
+            DeployResult::SkippedUserOwned => {
+                // The confirmation replay is a dry run, so it never prompts.
+                let resolved = args.interactive
+                    && !dry_run
+                    && resolve_user_owned(out, args, action, src_path, dst_dir, config, &options);
+                if !resolved {
+                    out.warn(
+                        "skip-user-owned",
+                        name,
+                        Some(dst_dir),
+                        format!("Skipping {file} — user-created agent (no source field)"),
+                    );
+                    continue;
+                }
+            }
+            DeployResult::SkippedTemplate
+            | DeployResult::SkippedNoName
+            | DeployResult::SkippedProviderExcluded => continue,
+        }
+        if args.diff {
+            print_diffs(out, action);
+        }
+        deployed.installed.push(name.to_string());
+        // Record the model the agent resolved to, for `--report`.
+        let pinned = config.is_model_whitelisted(provider.as_str(), &meta.model);
+        out.model(&meta.display_name, &meta.model, pinned);
+    }
+    Ok(deployed)
+}
+
+/// Ask what to do with the user-created file `action` skipped, for
+/// `--interactive`: overwrite it (saving a backup), keep it, show how the
+/// deploy would change it, or move it aside and deploy. Returns whether the
+/// agent was deployed; a failure is reported and leaves it skipped.
+fn resolve_user_owned(
+    out: &mut Reporter,
+    args: &Args,
+    action: &deploy::AgentAction,
+    src_path: &Path,
+    dst_dir: &Path,
+    config: &SidecarConfig,
+    options: &DeployOptions,
+) -> bool {
+    let Some(name) = action.name.as_deref() else {
+        return false;
+    };
+    let file = options.provider.agent_filename(name);
+    let plan = |options: &DeployOptions| {
+        let path = src_path.join(&action.filename);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        deploy::plan_agent(&content, &action.filename, dst_dir, config, options)
+            .map_err(|e| e.to_string())
+    };
+    let apply = |planned: deploy::AgentAction| {
+        deploy::apply_agent(&*options.vfs, dst_dir, &planned)
+            .map(|()| planned)
+            .map_err(|e| e.to_string())
+    };
+    let forced = options.clone().with_force_agents([name]);
+    let question = format!(
+        "{file} in {} is user-created (no source field).",
+        dst_dir.display()
+    );
+    let result = loop {
+        match super::choose(&question, &["overwrite", "skip", "diff", "rename"]) {
+            None | Some("skip") => return false,
+            Some("diff") => match plan(&forced) {
+                Ok(planned) => print_diffs(out, &planned),
+                Err(e) => break Err(e),
+            },
+            Some("overwrite") => {
+                break plan(&forced).and_then(apply).map(|planned| {
+                    report_deployed(out, args, &planned, true, dst_dir, name, &file);
+                });
+            }
+            Some(_) => {
+                let Some(renamed) =
+                    super::ask(&format!("Rename your {file} to"), &local_name(&file))
+                else {
+                    return false;
+                };
+                break rename_aside(dst_dir, &file, &renamed)
+                    .and_then(|()| plan(options))
+                    .and_then(apply)
+                    .map(|_| {
+                        let message = format!(
+                            "Installed: {file} to {}; yours is now {renamed}",
+                            dst_dir.display()
+                        );
+                        out.info("install", name, Some(dst_dir), message);
+                    });
+            }
+        }
+    };
+    result
+        .map_err(|e| out.warn("error", name, Some(dst_dir), format!("{file}: {e}")))
+        .is_ok()
+}
+
+/// Default name a user-created `file` is moved aside to, e.g.
+/// `Reviewer-local.md`.
+fn local_name(file: &str) -> String {
+    match file.split_once('.') {
+        Some((stem, ext)) => format!("{stem}-local.{ext}"),
+        None => format!("{file}-local"),
+    }
+}
+
+/// Rename `file` in `dst_dir` to `renamed`, which must be a new file name
+/// in the same directory.
+fn rename_aside(dst_dir: &Path, file: &str, renamed: &str) -> Result<(), String> {
+    let to = dst_dir.join(renamed);
+    if renamed.contains(['/', '\\']) || renamed == file {
+        return Err(format!("{renamed:?} is not a new file name"));
+    }
+    if to.exists() {
+        return Err(format!("{} already exists", to.display()));
+    }
+    std::fs::rename(dst_dir.join(file), &to)
+        .map_err(|e| format!("failed to rename to {}: {e}", to.display()))
+}
+
+/// Report an agent `action` deploys, calling out a `forced` one that
+/// overwrites a user-created or edited file, which `--restore` can undo.
+fn report_deployed(
+    out: &mut Reporter,
+    args: &Args,
+    action: &deploy::AgentAction,
+    forced: bool,
+    dst_dir: &Path,
+    name: &str,
+    file: &str,
+) {
+    let dry_run = args.dry_run;
+    if forced && action.files.iter().any(|f| f.backup.is_some()) {
+        let backups = deploy::state_dir(dst_dir).join(deploy::BACKUP_DIR);
+        let message = if dry_run {
+            format!(
+                "[dry-run] Would replace: {file}, saving it to {}",
+                backups.display()
+            )
+        } else {
+            format!(
+                "Replaced: {file}; the previous version is in {} (undo with --restore)",
+                backups.display()
+            )
+        };
+        out.info("replace", name, Some(dst_dir), message);
+        return;
+    }
+    let verb = if args.link { "link" } else { "install" };
+    let message = if dry_run {
+        format!("[dry-run] Would {verb}: {file} to {}", dst_dir.display())
+    } else if args.link {
+        format!("Linked: {file} to {}", dst_dir.display())
+    } else {
+        format!("Installed: {file} to {}", dst_dir.display())
+    };
+    out.info("install", name, Some(dst_dir), message);
+}
+
+/// Report a deploy that merged the new render into user edits, leaving
+/// conflict markers where they overlap.
+fn report_merge(
+    out: &mut Reporter,
+    conflicted: bool,
+    dry_run: bool,
+    dst_dir: &Path,
+    name: &str,
+    file: &str,
+) {
+    if !conflicted {
+        let message = if dry_run {
+            format!("[dry-run] Would merge: {file} keeps your edits")
+        } else {
+            format!("Merged: {file} keeps your edits")
+        };
+        out.info("merge", name, Some(dst_dir), message);
+        return;
+    }
+    let message = if dry_run {
+        format!("[dry-run] Would leave conflict markers in {file}")
+    } else {
+        format!(
+            "Conflict: your edits of {file} overlap the new source; \
+             resolve the markers in {}",
+            dst_dir.join(file).display()
+        )
+    };
+    out.warn("conflict", name, Some(dst_dir), message);
+}
+
+/// Print how each file `action` plans to write differs from what is
+/// deployed now.
+fn print_diffs(out: &Reporter, action: &deploy::AgentAction) {
+    for file in &action.files {
+        let Some(content) = &file.content else {
+            continue;
+        };
+        let path = file.path.display().to_string();
+        let diff = match std::fs::read_to_string(&file.path) {
+            Ok(existing) => merge::unified_diff(&existing, content, &path, &path),
+            Err(_) => merge::unified_diff("", content, "/dev/null", &path),
+        };
+        out.detail(&diff);
+    }
+}
+
+/// Run `install-agents` (also `forge agents install`).
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            if !args.dry_run && !args.yes && super::interactive() {
+                let dry = Args {
+                    dry_run: true,
+                    ..args.clone()
+                };
+                if let Err(code) = super::confirm_plan(|plan| execute(&dry, plan)) {
+                    return code;
+                }
+            }
+            let mut out = Reporter::new(args.json)
+                .with_report("install-agents", args.report.clone())
+                .with_detailed_exit_codes(args.detailed_exit_codes)
+                .with_history("install-agents", &args.scope, args.clean, args.dry_run)
+                .with_progress(args.progress);
+            let code = execute(args, &mut out);
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,352 @@
+use super::{flag_value, Reporter};
+use crate::commands;
+use crate::deploy::provider::Provider;
+use crate::deploy::DeployResult;
+use crate::manifest;
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use crate::vfs::RealFs;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: install-commands <commands-dir> [--scope user|workspace] \
+                     [--provider claude|gemini|codex|opencode|cursor|windsurf|copilot|zed] [--dry-run] [--clean] \
+                     [--no-snapshot] [--yes] [--dst <path>] [--config <file>]... [--report <file>] [--detailed-exit-codes] \
+                     [--json] [--no-color]";
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Args {
+    src_dir: String,
+    scope: String,
+    provider: Option<Provider>,
+    dry_run: bool,
+    clean: bool,
+    no_snapshot: bool,
+    yes: bool,
+    json: bool,
+    report: Option<PathBuf>,
+    /// Exit 3 when items were skipped and 4 when there was nothing to do.
+    detailed_exit_codes: bool,
+    config: Vec<PathBuf>,
+    dst_override: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut src_dir: Option<String> = None;
+    let mut scope = "workspace".to_string();
+    let mut provider: Option<Provider> = None;
+    let mut dry_run = false;
+    let mut clean = false;
+    let mut no_snapshot = false;
+    let mut yes = false;
+    let mut json = false;
+    let mut report: Option<PathBuf> = None;
+    let mut detailed_exit_codes = false;
+    let mut config: Vec<PathBuf> = Vec::new();
+    let mut dst_override: Option<String> = None;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                println!("install-commands {}", env!("CARGO_PKG_VERSION"));
+                return Err(ExitCode::SUCCESS);
+            }
+            "--dry-run" => dry_run = true,
+            "--clean" => clean = true,
+            "--no-snapshot" => no_snapshot = true,
+            "-y" | "--yes" => yes = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--detailed-exit-codes" => detailed_exit_codes = true,
+            "--no-color" => super::style::disable(),
+            "--scope" => scope = flag_value(args, &mut i)?.to_string(),
+            "--provider" => {
+                let value = flag_value(args, &mut i)?;
+                let Some(p) = Provider::from_str(value) else {
+                    eprintln!(
+                        "Error: invalid provider {value:?}: use claude, gemini, codex, opencode, cursor, windsurf, copilot, or zed"
+                    );
+                    return Err(ExitCode::from(1));
+                };
+                provider = Some(p);
+            }
+            "--config" => config.push(PathBuf::from(flag_value(args, &mut i)?)),
+            "--dst" => dst_override = Some(flag_value(args, &mut i)?.to_string()),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            _ => src_dir = Some(args[i].clone()),
+        }
+        i += 1;
+    }
+
+    let Some(src_dir) = src_dir else {
+        eprintln!("Error: source directory required.");
+        eprintln!("{USAGE}");
+        return Err(ExitCode::from(1));
+    };
+
+    Ok(Args {
+        src_dir,
+        scope,
+        provider,
+        dry_run,
+        clean,
+        no_snapshot,
+        yes,
+        json,
+        report,
+        detailed_exit_codes,
+        config,
+        dst_override,
+    })
+}
+
+fn resolve_dirs(args: &Args, config: &SidecarConfig) -> Result<Vec<(PathBuf, Provider)>, String> {
+    if let Some(ref dst) = args.dst_override {
+        let dst = PathBuf::from(dst);
+        let provider = args
+            .provider
+            .clone()
+            .unwrap_or_else(|| Provider::from_path(&dst));
+        return Ok(vec![(dst, provider)]);
+    }
+
+    let home = env::var("HOME").unwrap_or_default();
+    let providers = match &args.provider {
+        Some(p) => vec![p.clone()],
+        None => config
+            .providers()
+            .iter()
+            .filter_map(|p| Provider::from_str(p))
+            .collect(),
+    };
+    providers
+        .into_iter()
+        .map(|p| commands::command_dir(&p, &args.scope, Path::new(&home)).map(|d| (d, p)))
+        .collect()
+}
+
+fn remove_stale(
+    out: &mut Reporter,
+    args: &Args,
+    dst_dir: &Path,
+    module_name: &str,
+    keep: &[String],
+    provider: &Provider,
+) -> Result<(), ExitCode> {
+    let removed =
+        commands::clean_orphaned_commands(dst_dir, module_name, keep, provider, args.dry_run)
+            .map_err(|e| {
+                eprintln!("Error: {e}");
+                ExitCode::from(1)
+            })?;
+    let (action, label) = if args.clean {
+        ("remove", "")
+    } else {
+        ("remove-orphan", " orphan")
+    };
+    let ext = commands::command_extension(provider);
+    for name in &removed {
+        let message = if args.dry_run {
+            format!("[dry-run] Would remove{label}: {name}.{ext}")
+        } else {
+            format!("Removed{label}: {name}.{ext}")
+        };
+        out.info(action, name, Some(dst_dir), message);
+    }
+    Ok(())
+}
+
+fn deploy_to_dir(
+    out: &mut Reporter,
+    args: &Args,
+    src_path: &Path,
+    dst_dir: &Path,
+    provider: &Provider,
+    config: &SidecarConfig,
+    source_prefix: &str,
+) -> Result<Vec<String>, ExitCode> {
+    let results = commands::deploy_commands_from_dir(
+        src_path,
+        dst_dir,
+        provider,
+        config,
+        args.dry_run,
+        source_prefix,
+    )
+    .map_err(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })?;
+
+    let ext = commands::command_extension(provider);
+    let mut installed = Vec::new();
+    for (name, result) in results {
+        match result {
+            DeployResult::Deployed => {
+                let message = if args.dry_run {
+                    format!(
+                        "[dry-run] Would install: {name}.{ext} to {}",
+                        dst_dir.display()
+                    )
+                } else {
+                    format!("Installed: {name}.{ext} to {}", dst_dir.display())
+                };
+                out.info("install", &name, Some(dst_dir), message);
+                installed.push(name);
+            }
+            DeployResult::SkippedUserOwned => out.warn(
+                "skip-user-owned",
+                &name,
+                Some(dst_dir),
+                format!("Skipping {name}.{ext} — user-created command (no source field)"),
+            ),
+            // Only agent deploys merge user edits.
+            DeployResult::Merged
+            | DeployResult::Conflicted
+            | DeployResult::Unchanged
+            | DeployResult::SkippedTemplate
+            | DeployResult::SkippedNoName
+            | DeployResult::SkippedProviderExcluded => {}
+        }
+    }
+    Ok(installed)
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> ExitCode {
+    let src_path = Path::new(&args.src_dir);
+    if !src_path.is_dir() {
+        eprintln!("Error: not a directory: {}", args.src_dir);
+        return ExitCode::from(1);
+    }
+
+    let module_root = src_path.parent().unwrap_or(Path::new("."));
+    let module_yaml = std::fs::read_to_string(module_root.join("module.yaml")).unwrap_or_default();
+    let module_name = parse::module_name(&module_yaml).unwrap_or_default();
+    out.set_module(&module_name);
+    let module_version = parse::module_version(&module_yaml);
+    let source_prefix = if module_name.is_empty() {
+        String::new()
+    } else {
+        format!("{module_name}/{}", args.src_dir)
+    };
+
+    let config = match SidecarConfig::load(module_root).with_overrides(&args.config) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+    let dirs = match resolve_dirs(args, &config) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    if args.clean {
+        let paths: Vec<PathBuf> = dirs.iter().map(|(d, _)| d.clone()).collect();
+        if let Err(e) =
+            super::snapshot::before_clean(out, "commands", &paths, args.dry_run, args.no_snapshot)
+        {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    }
+
+    for (dst_dir, provider) in &dirs {
+        out.set_provider(provider.as_str());
+        let (dst_dir, provider) = (dst_dir.as_path(), provider);
+        out.note(&format!(
+            "Targeting provider directory: {}",
+            dst_dir.display()
+        ));
+
+        if args.clean {
+            if let Err(code) = remove_stale(out, args, dst_dir, &module_name, &[], provider) {
+                return code;
+            }
+        }
+
+        let installed = match deploy_to_dir(
+            out,
+            args,
+            src_path,
+            dst_dir,
+            provider,
+            &config,
+            &source_prefix,
+        ) {
+            Ok(names) => names,
+            Err(code) => return code,
+        };
+
+        if module_name.is_empty() {
+            continue;
+        }
+        if !args.clean {
+            if let Err(code) = remove_stale(out, args, dst_dir, &module_name, &installed, provider)
+            {
+                return code;
+            }
+        }
+        if !args.dry_run {
+            let ext = commands::command_extension(provider);
+            let files: Vec<String> = installed.iter().map(|n| format!("{n}.{ext}")).collect();
+            let recorded = manifest::record(
+                &RealFs,
+                dst_dir,
+                &module_name,
+                module_version.as_deref(),
+                &installed,
+            )
+            .and_then(|()| manifest::record_checksums(&RealFs, dst_dir, &module_name, &files));
+            if let Err(e) = recorded {
+                out.warn(
+                    "error",
+                    "",
+                    Some(dst_dir),
+                    format!("manifest update failed: {e}"),
+                );
+            }
+        }
+    }
+
+    out.exit_code()
+}
+
+/// Run `install-commands` (also `forge commands install`).
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            if !args.dry_run && !args.yes && super::interactive() {
+                let dry = Args {
+                    dry_run: true,
+                    ..args.clone()
+                };
+                if let Err(code) = super::confirm_plan(|plan| execute(&dry, plan)) {
+                    return code;
+                }
+            }
+            let mut out = Reporter::new(args.json)
+                .with_report("install-commands", args.report.clone())
+                .with_detailed_exit_codes(args.detailed_exit_codes)
+                .with_history("install-commands", &args.scope, args.clean, args.dry_run);
+            let code = execute(args, &mut out);
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
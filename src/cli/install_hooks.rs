@@ -0,0 +1,199 @@
+use super::{flag_value, Reporter};
+use crate::hooks::{self, HookEntry, HooksReport};
+use crate::parse;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: install-hooks [module-root] [--scope user|workspace|project] \
+                     [--dst <settings.json>] [--dry-run] [--clean] [--no-snapshot] [--yes] \
+                     [--report <file>] [--detailed-exit-codes] [--json] [--no-color]";
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Args {
+    module_root: PathBuf,
+    scope: String,
+    dst_override: Option<PathBuf>,
+    dry_run: bool,
+    clean: bool,
+    no_snapshot: bool,
+    yes: bool,
+    json: bool,
+    report: Option<PathBuf>,
+    /// Exit 3 when items were skipped and 4 when there was nothing to do.
+    detailed_exit_codes: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut module_root = PathBuf::from(".");
+    let mut scope = "user".to_string();
+    let mut dst_override: Option<PathBuf> = None;
+    let mut dry_run = false;
+    let mut clean = false;
+    let mut no_snapshot = false;
+    let mut yes = false;
+    let mut json = false;
+    let mut report: Option<PathBuf> = None;
+    let mut detailed_exit_codes = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                println!("install-hooks {}", env!("CARGO_PKG_VERSION"));
+                return Err(ExitCode::SUCCESS);
+            }
+            "--scope" => scope = flag_value(args, &mut i)?.to_string(),
+            "--dst" => dst_override = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--dry-run" => dry_run = true,
+            "--clean" => clean = true,
+            "--no-snapshot" => no_snapshot = true,
+            "-y" | "--yes" => yes = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--detailed-exit-codes" => detailed_exit_codes = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => module_root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+
+    Ok(Args {
+        module_root,
+        scope,
+        dst_override,
+        dry_run,
+        clean,
+        no_snapshot,
+        yes,
+        json,
+        report,
+        detailed_exit_codes,
+    })
+}
+
+fn describe(entry: &HookEntry) -> String {
+    match &entry.matcher {
+        Some(matcher) => format!("{} ({matcher})", entry.event),
+        None => entry.event.clone(),
+    }
+}
+
+fn report(out: &mut Reporter, result: &HooksReport, settings: &Path, dry_run: bool) {
+    for entry in &result.removed {
+        let what = describe(entry);
+        let message = if dry_run {
+            format!("[dry-run] Would remove hook: {what}")
+        } else {
+            format!("Removed hook: {what}")
+        };
+        out.info("remove", &entry.event, Some(settings), message);
+    }
+    for entry in &result.added {
+        let what = describe(entry);
+        let message = if dry_run {
+            format!(
+                "[dry-run] Would install hook: {what} to {}",
+                settings.display()
+            )
+        } else {
+            format!("Installed hook: {what} to {}", settings.display())
+        };
+        out.info("install", &entry.event, Some(settings), message);
+    }
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> ExitCode {
+    out.set_provider("claude");
+    if !args.module_root.is_dir() {
+        eprintln!("Error: not a directory: {}", args.module_root.display());
+        return ExitCode::from(1);
+    }
+    let module_name = std::fs::read_to_string(args.module_root.join("module.yaml"))
+        .ok()
+        .and_then(|c| parse::module_name(&c));
+    let Some(module_name) = module_name else {
+        eprintln!("Error: module.yaml with a name is required to track deployed hooks");
+        return ExitCode::from(1);
+    };
+    out.set_module(&module_name);
+
+    let home = env::var("HOME").unwrap_or_default();
+    let settings = match &args.dst_override {
+        Some(dst) => Ok(dst.clone()),
+        None => hooks::scope_settings_path(&args.scope, Path::new(&home)),
+    };
+    let settings = match settings {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    if args.clean {
+        let paths = [settings.clone(), hooks::manifest_path(&settings)];
+        if let Err(e) =
+            super::snapshot::before_clean(out, "hooks", &paths, args.dry_run, args.no_snapshot)
+        {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    }
+
+    let result = if args.clean {
+        hooks::clean_hooks(&settings, &module_name, args.dry_run)
+    } else {
+        match hooks::load_module_hooks(&args.module_root) {
+            Ok(Some(groups)) => hooks::deploy_hooks(&settings, &module_name, &groups, args.dry_run),
+            // No hooks file: still retract anything deployed earlier.
+            Ok(None) => hooks::clean_hooks(&settings, &module_name, args.dry_run),
+            Err(e) => Err(e),
+        }
+    };
+
+    match result {
+        Ok(result) => {
+            report(out, &result, &settings, args.dry_run);
+            out.exit_code()
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+/// Run `install-hooks` (also `forge hooks install`).
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            if !args.dry_run && !args.yes && super::interactive() {
+                let dry = Args {
+                    dry_run: true,
+                    ..args.clone()
+                };
+                if let Err(code) = super::confirm_plan(|plan| execute(&dry, plan)) {
+                    return code;
+                }
+            }
+            let mut out = Reporter::new(args.json)
+                .with_report("install-hooks", args.report.clone())
+                .with_detailed_exit_codes(args.detailed_exit_codes)
+                .with_history("install-hooks", &args.scope, args.clean, args.dry_run);
+            let code = execute(args, &mut out);
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
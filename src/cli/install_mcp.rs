@@ -0,0 +1,254 @@
+use super::{flag_value, Reporter};
+use crate::deploy::provider::Provider;
+use crate::mcp::{self, McpReport};
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: install-mcp [module-root] [--scope user|workspace] \
+                     [--provider claude|gemini|codex] [--dst <config-file>] [--config <file>]... \
+                     [--dry-run] [--clean] [--no-snapshot] [--yes] [--report <file>] [--detailed-exit-codes] [--json] [--no-color]";
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Args {
+    module_root: PathBuf,
+    scope: String,
+    provider: Option<Provider>,
+    dst_override: Option<PathBuf>,
+    config: Vec<PathBuf>,
+    dry_run: bool,
+    clean: bool,
+    no_snapshot: bool,
+    yes: bool,
+    json: bool,
+    report: Option<PathBuf>,
+    /// Exit 3 when items were skipped and 4 when there was nothing to do.
+    detailed_exit_codes: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut module_root = PathBuf::from(".");
+    let mut scope = "workspace".to_string();
+    let mut provider: Option<Provider> = None;
+    let mut dst_override: Option<PathBuf> = None;
+    let mut config: Vec<PathBuf> = Vec::new();
+    let mut dry_run = false;
+    let mut clean = false;
+    let mut no_snapshot = false;
+    let mut yes = false;
+    let mut json = false;
+    let mut report: Option<PathBuf> = None;
+    let mut detailed_exit_codes = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                println!("install-mcp {}", env!("CARGO_PKG_VERSION"));
+                return Err(ExitCode::SUCCESS);
+            }
+            "--scope" => scope = flag_value(args, &mut i)?.to_string(),
+            "--provider" => {
+                let value = flag_value(args, &mut i)?;
+                let Some(p) = Provider::from_str(value) else {
+                    eprintln!(
+                        "Error: invalid provider {value:?}: use claude, gemini, codex, opencode, cursor, windsurf, copilot, or zed"
+                    );
+                    return Err(ExitCode::from(1));
+                };
+                provider = Some(p);
+            }
+            "--dst" => dst_override = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--config" => config.push(PathBuf::from(flag_value(args, &mut i)?)),
+            "--dry-run" => dry_run = true,
+            "--clean" => clean = true,
+            "--no-snapshot" => no_snapshot = true,
+            "-y" | "--yes" => yes = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--detailed-exit-codes" => detailed_exit_codes = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => module_root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+
+    Ok(Args {
+        module_root,
+        scope,
+        provider,
+        dst_override,
+        config,
+        dry_run,
+        clean,
+        no_snapshot,
+        yes,
+        json,
+        report,
+        detailed_exit_codes,
+    })
+}
+
+/// Config files to update, one per provider. Providers without MCP support
+/// are skipped with a note.
+fn resolve_targets(
+    out: &mut Reporter,
+    args: &Args,
+    config: &SidecarConfig,
+) -> Result<Vec<(PathBuf, Provider)>, String> {
+    if let Some(ref dst) = args.dst_override {
+        return Ok(vec![(
+            dst.clone(),
+            args.provider.clone().unwrap_or(Provider::Claude),
+        )]);
+    }
+
+    let home = env::var("HOME").unwrap_or_default();
+    let providers: Vec<Provider> = match &args.provider {
+        Some(p) => vec![p.clone()],
+        None => config
+            .providers()
+            .iter()
+            .filter_map(|p| Provider::from_str(p))
+            .collect(),
+    };
+    let mut targets = Vec::new();
+    for provider in providers {
+        match mcp::mcp_config_path(&provider, &args.scope, Path::new(&home))? {
+            Some(path) => targets.push((path, provider)),
+            None => out.note(&format!(
+                "Skipping {}: MCP servers are not supported",
+                provider.as_str()
+            )),
+        }
+    }
+    Ok(targets)
+}
+
+fn report(out: &mut Reporter, result: &McpReport, path: &Path, dry_run: bool) {
+    for name in &result.removed {
+        let message = if dry_run {
+            format!("[dry-run] Would remove server: {name}")
+        } else {
+            format!("Removed server: {name}")
+        };
+        out.info("remove", name, Some(path), message);
+    }
+    for name in &result.added {
+        let message = if dry_run {
+            format!(
+                "[dry-run] Would install server: {name} to {}",
+                path.display()
+            )
+        } else {
+            format!("Installed server: {name} to {}", path.display())
+        };
+        out.info("install", name, Some(path), message);
+    }
+    for name in &result.skipped {
+        out.warn(
+            "skip-user-owned",
+            name,
+            Some(path),
+            format!("Skipping server {name} — already defined outside this module"),
+        );
+    }
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> ExitCode {
+    if !args.module_root.is_dir() {
+        eprintln!("Error: not a directory: {}", args.module_root.display());
+        return ExitCode::from(1);
+    }
+    let module_name = std::fs::read_to_string(args.module_root.join("module.yaml"))
+        .ok()
+        .and_then(|c| parse::module_name(&c));
+    let Some(module_name) = module_name else {
+        eprintln!("Error: module.yaml with a name is required to track deployed servers");
+        return ExitCode::from(1);
+    };
+    out.set_module(&module_name);
+
+    let config = match SidecarConfig::load(&args.module_root).with_overrides(&args.config) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+    let servers = if args.clean {
+        Ok(mcp::Servers::new())
+    } else {
+        mcp::load_module_servers(&args.module_root)
+    };
+    let targets = servers
+        .and_then(|servers| resolve_targets(out, args, &config).map(|targets| (servers, targets)));
+    let (servers, targets) = match targets {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    if args.clean {
+        let paths: Vec<PathBuf> = targets
+            .iter()
+            .flat_map(|(path, _)| [path.clone(), mcp::manifest_path(path)])
+            .collect();
+        if let Err(e) =
+            super::snapshot::before_clean(out, "mcp", &paths, args.dry_run, args.no_snapshot)
+        {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    }
+
+    for (path, provider) in &targets {
+        out.set_provider(provider.as_str());
+        match mcp::deploy_servers(path, provider, &module_name, &servers, args.dry_run) {
+            Ok(result) => report(out, &result, path, args.dry_run),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return ExitCode::from(1);
+            }
+        }
+    }
+    out.exit_code()
+}
+
+/// Run `install-mcp` (also `forge mcp install`).
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            if !args.dry_run && !args.yes && super::interactive() {
+                let dry = Args {
+                    dry_run: true,
+                    ..args.clone()
+                };
+                if let Err(code) = super::confirm_plan(|plan| execute(&dry, plan)) {
+                    return code;
+                }
+            }
+            let mut out = Reporter::new(args.json)
+                .with_report("install-mcp", args.report.clone())
+                .with_detailed_exit_codes(args.detailed_exit_codes)
+                .with_history("install-mcp", &args.scope, args.clean, args.dry_run);
+            let code = execute(args, &mut out);
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,242 @@
+use super::{flag_value, Reporter};
+use crate::deploy::provider::Provider;
+use crate::memory::{self, MemoryResult};
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: install-memory [module-root] [--scope user|workspace] \
+                     [--provider claude|gemini|codex|opencode|cursor|windsurf|copilot|zed] [--dst <file>] \
+                     [--config <file>]... [--dry-run] [--clean] [--no-snapshot] [--yes] \
+                     [--report <file>] [--detailed-exit-codes] [--json] [--no-color]";
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Args {
+    module_root: PathBuf,
+    scope: String,
+    provider: Option<Provider>,
+    dst_override: Option<PathBuf>,
+    config: Vec<PathBuf>,
+    dry_run: bool,
+    clean: bool,
+    no_snapshot: bool,
+    yes: bool,
+    json: bool,
+    report: Option<PathBuf>,
+    /// Exit 3 when items were skipped and 4 when there was nothing to do.
+    detailed_exit_codes: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut module_root = PathBuf::from(".");
+    let mut scope = "workspace".to_string();
+    let mut provider: Option<Provider> = None;
+    let mut dst_override: Option<PathBuf> = None;
+    let mut config: Vec<PathBuf> = Vec::new();
+    let mut dry_run = false;
+    let mut clean = false;
+    let mut no_snapshot = false;
+    let mut yes = false;
+    let mut json = false;
+    let mut report: Option<PathBuf> = None;
+    let mut detailed_exit_codes = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                println!("install-memory {}", env!("CARGO_PKG_VERSION"));
+                return Err(ExitCode::SUCCESS);
+            }
+            "--scope" => scope = flag_value(args, &mut i)?.to_string(),
+            "--provider" => {
+                let value = flag_value(args, &mut i)?;
+                let Some(p) = Provider::from_str(value) else {
+                    eprintln!(
+                        "Error: invalid provider {value:?}: use claude, gemini, codex, opencode, cursor, windsurf, copilot, or zed"
+                    );
+                    return Err(ExitCode::from(1));
+                };
+                provider = Some(p);
+            }
+            "--dst" => dst_override = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--config" => config.push(PathBuf::from(flag_value(args, &mut i)?)),
+            "--dry-run" => dry_run = true,
+            "--clean" => clean = true,
+            "--no-snapshot" => no_snapshot = true,
+            "-y" | "--yes" => yes = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--detailed-exit-codes" => detailed_exit_codes = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => module_root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+
+    Ok(Args {
+        module_root,
+        scope,
+        provider,
+        dst_override,
+        config,
+        dry_run,
+        clean,
+        no_snapshot,
+        yes,
+        json,
+        report,
+        detailed_exit_codes,
+    })
+}
+
+/// Memory files to update. Codex and opencode share the workspace
+/// `AGENTS.md`, which is only written once (with the first provider's
+/// agent names).
+fn resolve_targets(
+    args: &Args,
+    config: &SidecarConfig,
+) -> Result<Vec<(PathBuf, Provider)>, String> {
+    if let Some(ref dst) = args.dst_override {
+        return Ok(vec![(
+            dst.clone(),
+            args.provider.clone().unwrap_or(Provider::Claude),
+        )]);
+    }
+
+    let home = env::var("HOME").unwrap_or_default();
+    let providers: Vec<Provider> = match &args.provider {
+        Some(p) => vec![p.clone()],
+        None => config
+            .providers()
+            .iter()
+            .filter_map(|p| Provider::from_str(p))
+            .collect(),
+    };
+    let mut targets: Vec<(PathBuf, Provider)> = Vec::new();
+    for provider in providers {
+        let path = memory::memory_file(&provider, &args.scope, Path::new(&home))?;
+        if !targets.iter().any(|(p, _)| *p == path) {
+            targets.push((path, provider));
+        }
+    }
+    Ok(targets)
+}
+
+fn report(
+    out: &mut Reporter,
+    result: &MemoryResult,
+    path: &Path,
+    module_name: &str,
+    dry_run: bool,
+) {
+    let file = path.display();
+    let (action, message) = match result {
+        MemoryResult::Installed => ("install", format!("Added {module_name} block to {file}")),
+        MemoryResult::Updated => ("update", format!("Updated {module_name} block in {file}")),
+        MemoryResult::Removed => ("remove", format!("Removed {module_name} block from {file}")),
+        MemoryResult::Unchanged => return,
+    };
+    let message = if dry_run {
+        format!("[dry-run] Would {action}: {module_name} block in {file}")
+    } else {
+        message
+    };
+    out.info(action, module_name, Some(path), message);
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> ExitCode {
+    if !args.module_root.is_dir() {
+        eprintln!("Error: not a directory: {}", args.module_root.display());
+        return ExitCode::from(1);
+    }
+    let module_name = std::fs::read_to_string(args.module_root.join("module.yaml"))
+        .ok()
+        .and_then(|c| parse::module_name(&c));
+    let Some(module_name) = module_name else {
+        eprintln!("Error: module.yaml with a name is required to mark the managed block");
+        return ExitCode::from(1);
+    };
+    out.set_module(&module_name);
+
+    let config = match SidecarConfig::load(&args.module_root).with_overrides(&args.config) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+    let targets = match resolve_targets(args, &config) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    if args.clean {
+        let paths: Vec<PathBuf> = targets.iter().map(|(path, _)| path.clone()).collect();
+        if let Err(e) =
+            super::snapshot::before_clean(out, "memory", &paths, args.dry_run, args.no_snapshot)
+        {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    }
+
+    for (path, provider) in &targets {
+        out.set_provider(provider.as_str());
+        let block = if args.clean {
+            Ok(None)
+        } else {
+            memory::build_block(&args.module_root, &module_name, provider, &config)
+        };
+        let result = block.and_then(|block| {
+            memory::write_block(path, &module_name, block.as_deref(), args.dry_run)
+        });
+        match result {
+            Ok(result) => report(out, &result, path, &module_name, args.dry_run),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return ExitCode::from(1);
+            }
+        }
+    }
+    out.exit_code()
+}
+
+/// Run `install-memory` (also `forge memory install`).
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            if !args.dry_run && !args.yes && super::interactive() {
+                let dry = Args {
+                    dry_run: true,
+                    ..args.clone()
+                };
+                if let Err(code) = super::confirm_plan(|plan| execute(&dry, plan)) {
+                    return code;
+                }
+            }
+            let mut out = Reporter::new(args.json)
+                .with_report("install-memory", args.report.clone())
+                .with_detailed_exit_codes(args.detailed_exit_codes)
+                .with_history("install-memory", &args.scope, args.clean, args.dry_run);
+            let code = execute(args, &mut out);
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
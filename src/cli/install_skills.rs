@@ -0,0 +1,651 @@
+use super::{flag_value, Reporter};
+use crate::cache::{self, CacheEntry, DeployCache};
+use crate::credentials;
+use crate::deploy::provider::Provider;
+use crate::manifest;
+use crate::parse;
+use crate::progress::NoProgress;
+use crate::sidecar::SidecarConfig;
+use crate::skill::{self, SkillInstallAction};
+use crate::vfs::RealFs;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Args {
+    skills_dir: String,
+    provider: Provider,
+    scope: String,
+    dry_run: bool,
+    clean: bool,
+    /// Symlink skills to their sources instead of copying them.
+    link: bool,
+    no_snapshot: bool,
+    no_cache: bool,
+    dst_override: Option<String>,
+    agents_dir: String,
+    include_agent_wrappers: bool,
+    continue_on_error: bool,
+    yes: bool,
+    json: bool,
+    report: Option<PathBuf>,
+    /// Exit 3 when items were skipped and 4 when there was nothing to do.
+    detailed_exit_codes: bool,
+    progress: bool,
+    config: Vec<PathBuf>,
+}
+
+const USAGE: &str = "Usage: install-skills <skills-dir> --provider claude|gemini|codex|opencode|cursor|windsurf|copilot|zed \
+                     [--scope user|workspace] [--dry-run] [--clean] [--link] [--no-snapshot] [--no-cache] \
+                     [--dst <path>] [--agents-dir <path>] [--config <file>]... [--include-agent-wrappers] \
+                     [--continue-on-error] [--yes] [--report <file>] [--detailed-exit-codes] [--json] [--progress] \
+                     [--no-color]";
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut skills_dir: Option<String> = None;
+    let mut provider_str: Option<String> = None;
+    let mut scope = "workspace".to_string();
+    let mut dry_run = false;
+    let mut clean = false;
+    let mut link = false;
+    let mut no_snapshot = false;
+    let mut no_cache = false;
+    let mut dst_override: Option<String> = None;
+    let mut agents_dir = "agents".to_string();
+    let mut include_agent_wrappers = false;
+    let mut continue_on_error = false;
+    let mut yes = false;
+    let mut json = false;
+    let mut report: Option<PathBuf> = None;
+    let mut detailed_exit_codes = false;
+    let mut progress = false;
+    let mut config: Vec<PathBuf> = Vec::new();
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                println!("install-skills {}", env!("CARGO_PKG_VERSION"));
+                return Err(ExitCode::SUCCESS);
+            }
+            "--provider" => provider_str = Some(flag_value(args, &mut i)?.to_string()),
+            "--scope" => scope = flag_value(args, &mut i)?.to_string(),
+            "--config" => config.push(PathBuf::from(flag_value(args, &mut i)?)),
+            "--dst" => dst_override = Some(flag_value(args, &mut i)?.to_string()),
+            "--agents-dir" => agents_dir = flag_value(args, &mut i)?.to_string(),
+            "--dry-run" => dry_run = true,
+            "--clean" => clean = true,
+            "--link" => link = true,
+            "--no-snapshot" => no_snapshot = true,
+            "--no-cache" => no_cache = true,
+            "--include-agent-wrappers" => include_agent_wrappers = true,
+            "--continue-on-error" => continue_on_error = true,
+            "-y" | "--yes" => yes = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--detailed-exit-codes" => detailed_exit_codes = true,
+            "--progress" => progress = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            _ => {
+                skills_dir = Some(args[i].clone());
+            }
+        }
+        i += 1;
+    }
+
+    let Some(skills_dir) = skills_dir else {
+        eprintln!("Error: skills directory required.");
+        eprintln!("{USAGE}");
+        return Err(ExitCode::from(1));
+    };
+
+    let Some(ref prov) = provider_str else {
+        eprintln!("Error: --provider is required.");
+        return Err(ExitCode::from(1));
+    };
+
+    let Some(provider) = Provider::from_str(prov) else {
+        eprintln!(
+            "Error: invalid provider {prov:?}: use claude, gemini, codex, opencode, cursor, windsurf, copilot, or zed"
+        );
+        return Err(ExitCode::from(1));
+    };
+
+    if link {
+        check_link(&provider, include_agent_wrappers)?;
+    }
+
+    Ok(Args {
+        skills_dir,
+        provider,
+        scope,
+        dry_run,
+        clean,
+        link,
+        no_snapshot,
+        no_cache,
+        dst_override,
+        agents_dir,
+        include_agent_wrappers,
+        continue_on_error,
+        yes,
+        json,
+        report,
+        detailed_exit_codes,
+        progress,
+        config,
+    })
+}
+
+/// Reject `--link` where there is no source directory to link to.
+fn check_link(provider: &Provider, include_agent_wrappers: bool) -> Result<(), ExitCode> {
+    let conflict = if *provider == Provider::Gemini {
+        "gemini installs skills through its CLI"
+    } else if include_agent_wrappers {
+        "agent wrappers are generated, with no source to link to"
+    } else {
+        return Ok(());
+    };
+    eprintln!("Error: --link is not supported here: {conflict}");
+    Err(ExitCode::from(1))
+}
+
+fn read_module_field(input_dir: &Path, field: fn(&str) -> Option<String>) -> Option<String> {
+    let module_root = input_dir.parent()?;
+    let content = std::fs::read_to_string(module_root.join("module.yaml")).ok()?;
+    field(&content)
+}
+
+fn project_key() -> Result<String, String> {
+    let cwd = env::current_dir().map_err(|e| format!("failed to get cwd: {e}"))?;
+    Ok(cwd.to_string_lossy().replace('/', "-"))
+}
+
+fn resolve_dst(provider: &Provider, scope: &str) -> Result<PathBuf, String> {
+    let home = env::var("HOME").unwrap_or_default();
+    let provider_dir = format!(".{}", provider.as_str());
+
+    match scope {
+        "user" => Ok(PathBuf::from(format!("{home}/{provider_dir}/skills"))),
+
+        "project" => {
+            let key = project_key()?;
+            Ok(PathBuf::from(format!(
+                "{home}/{provider_dir}/projects/{key}/skills"
+            )))
+        }
+
+        "workspace" => Ok(PathBuf::from(format!(
+            "{}/skills",
+            provider.workspace_root()
+        ))),
+
+        other => Err(format!(
+            "invalid scope: {other} (use user, project, or workspace)"
+        )),
+    }
+}
+
+fn clean_module_skills(out: &mut Reporter, dst_dir: &Path, module_name: &str, dry_run: bool) {
+    if !dst_dir.is_dir() || module_name.is_empty() {
+        return;
+    }
+    let previous = manifest::read(&RealFs, dst_dir, module_name);
+    for name in &previous {
+        let path = dst_dir.join(name);
+        if path.is_dir() {
+            if dry_run {
+                out.info(
+                    "clean",
+                    name,
+                    Some(dst_dir),
+                    format!("[dry-run] Would clean: {name}"),
+                );
+            } else {
+                let _ = std::fs::remove_dir_all(&path);
+            }
+        }
+    }
+}
+
+fn action_skill_name(action: &SkillInstallAction) -> &str {
+    match action {
+        SkillInstallAction::Copy { skill_name, .. }
+        | SkillInstallAction::GeminiCli { skill_name, .. }
+        | SkillInstallAction::Skipped { skill_name, .. } => skill_name,
+    }
+}
+
+fn execute_action(
+    out: &mut Reporter,
+    action: &SkillInstallAction,
+    dry_run: bool,
+    link: bool,
+) -> Result<(), String> {
+    match action {
+        SkillInstallAction::Copy {
+            skill_name,
+            src_dir,
+            dst_dir,
+            claude_fields,
+        } => {
+            let kind = if dst_dir.join(skill_name).exists() {
+                "replace"
+            } else {
+                "install"
+            };
+            if dry_run {
+                out.info(
+                    kind,
+                    skill_name,
+                    Some(dst_dir),
+                    format!(
+                        "[dry-run] Would install skill: {skill_name} -> {}",
+                        dst_dir.display()
+                    ),
+                );
+            } else if link {
+                let src_dir = std::fs::canonicalize(src_dir)
+                    .map_err(|e| format!("failed to resolve {}: {e}", src_dir.display()))?;
+                skill::execute_skill_link(&RealFs, &src_dir, skill_name, dst_dir)?;
+                out.info(
+                    kind,
+                    skill_name,
+                    Some(dst_dir),
+                    format!("Linked skill: {skill_name} -> {}", dst_dir.display()),
+                );
+            } else {
+                skill::execute_skill_copy(&RealFs, src_dir, skill_name, dst_dir, out)?;
+                if !claude_fields.is_empty() {
+                    merge_skill_fields(&dst_dir.join(skill_name), claude_fields)?;
+                }
+                out.info(
+                    kind,
+                    skill_name,
+                    Some(dst_dir),
+                    format!("Installed skill: {skill_name} -> {}", dst_dir.display()),
+                );
+            }
+        }
+        SkillInstallAction::GeminiCli {
+            skill_name,
+            skill_dir,
+            scope,
+            fields,
+        } => {
+            if dry_run {
+                out.info(
+                    "install",
+                    skill_name,
+                    None,
+                    format!("[dry-run] Would install Gemini skill: {skill_name} (scope: {scope})"),
+                );
+            } else {
+                out.info(
+                    "install",
+                    skill_name,
+                    None,
+                    format!("Installing Gemini skill: {skill_name} (scope: {scope})..."),
+                );
+                // The CLI copies the directory as-is, so provenance goes
+                // into a staged copy.
+                let staged = stage_with_fields(skill_dir, skill_name, fields)?;
+                let skill_dir = staged
+                    .as_ref()
+                    .map_or(skill_dir.clone(), |tmp| tmp.path().join(skill_name));
+                let home = env::var("HOME").unwrap_or_default();
+                let credential = credentials::resolve_system(&Provider::Gemini, Path::new(&home));
+                let mut cmd = Command::new("gemini");
+                cmd.args([
+                    "skills",
+                    "install",
+                    &skill_dir.to_string_lossy(),
+                    "--scope",
+                    scope,
+                ]);
+                credentials::apply(&mut cmd, credential.as_ref());
+                let status = cmd
+                    .status()
+                    .map_err(|e| format!("failed to run gemini CLI: {e}"))?;
+                if !status.success() {
+                    let hint = match (&credential, credentials::auth_hint(&Provider::Gemini)) {
+                        (None, Some(hint)) => format!("; if the CLI is not authenticated, {hint}"),
+                        _ => String::new(),
+                    };
+                    return Err(format!(
+                        "gemini skills install failed for {skill_name} (exit {}){hint}",
+                        status.code().unwrap_or(-1)
+                    ));
+                }
+            }
+        }
+        SkillInstallAction::Skipped { .. } => {}
+    }
+    Ok(())
+}
+
+/// [`execute_action`], skipping a copy the deploy cache shows is already
+/// deployed as it would be, and recording each copy made.
+fn execute_cached(
+    out: &mut Reporter,
+    action: &SkillInstallAction,
+    dry_run: bool,
+    cache: Option<&mut DeployCache>,
+) -> Result<(), String> {
+    let (
+        Some(cache),
+        SkillInstallAction::Copy {
+            skill_name,
+            src_dir,
+            dst_dir,
+            claude_fields,
+        },
+    ) = (cache, action)
+    else {
+        return execute_action(out, action, dry_run, false);
+    };
+    let source = skill::skill_digest(&RealFs, src_dir, skill_name, claude_fields)?;
+    if cache.is_fresh(&RealFs, dst_dir, skill_name, &source) {
+        out.info(
+            "unchanged",
+            skill_name,
+            Some(dst_dir),
+            format!("Unchanged skill: {skill_name}"),
+        );
+        return Ok(());
+    }
+    cache.remove(skill_name);
+    execute_action(out, action, dry_run, false)?;
+    if !dry_run {
+        let outputs = cache::digest_tree(&RealFs, &dst_dir.join(skill_name), dst_dir)?;
+        cache.insert(skill_name.clone(), CacheEntry { source, outputs });
+    }
+    Ok(())
+}
+
+fn save_cache(out: &mut Reporter, dst_dir: &Path, cache: &DeployCache) {
+    if let Err(e) = cache.save(&RealFs, dst_dir) {
+        out.warn(
+            "error",
+            "",
+            Some(dst_dir),
+            format!("deploy cache update failed: {e}"),
+        );
+    }
+}
+
+fn merge_skill_fields(skill_dir: &Path, fields: &BTreeMap<String, String>) -> Result<(), String> {
+    let md_path = skill_dir.join("SKILL.md");
+    if let Ok(content) = std::fs::read_to_string(&md_path) {
+        let merged = skill::merge_claude_fields(&content, fields);
+        crate::vfs::write_atomic(&md_path, merged.as_bytes())
+            .map_err(|e| format!("failed to write {}: {e}", md_path.display()))?;
+    }
+    Ok(())
+}
+
+fn stage_with_fields(
+    skill_dir: &Path,
+    skill_name: &str,
+    fields: &BTreeMap<String, String>,
+) -> Result<Option<tempfile::TempDir>, String> {
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    let tmp = tempfile::tempdir().map_err(|e| format!("failed to create temp dir: {e}"))?;
+    skill::execute_skill_copy(&RealFs, skill_dir, skill_name, tmp.path(), &mut NoProgress)?;
+    merge_skill_fields(&tmp.path().join(skill_name), fields)?;
+    Ok(Some(tmp))
+}
+
+fn generate_and_plan_wrappers(
+    agents_dir: &Path,
+    _provider: &Provider,
+    dst_dir: &Path,
+    _scope: &str,
+    config: &SidecarConfig,
+) -> Result<(Vec<SkillInstallAction>, Option<tempfile::TempDir>), String> {
+    let generated = skill::generate_skills_from_agents_dir(agents_dir, config.templates())?;
+    if generated.is_empty() {
+        return Ok((Vec::new(), None));
+    }
+
+    let tmp_dir = tempfile::tempdir().map_err(|e| format!("failed to create temp dir: {e}"))?;
+
+    let mut actions = Vec::new();
+    for gen in &generated {
+        let skill_dir = tmp_dir.path().join(&gen.agent_name);
+        std::fs::create_dir_all(&skill_dir)
+            .map_err(|e| format!("failed to create {}: {e}", skill_dir.display()))?;
+        std::fs::write(skill_dir.join("SKILL.md"), &gen.skill_md)
+            .map_err(|e| format!("failed to write SKILL.md: {e}"))?;
+        std::fs::write(skill_dir.join("SKILL.yaml"), &gen.skill_yaml)
+            .map_err(|e| format!("failed to write SKILL.yaml: {e}"))?;
+
+        actions.push(SkillInstallAction::Copy {
+            skill_name: gen.agent_name.clone(),
+            src_dir: skill_dir,
+            dst_dir: dst_dir.to_path_buf(),
+            claude_fields: config.provenance().fields(),
+        });
+    }
+
+    Ok((actions, Some(tmp_dir)))
+}
+
+fn sync_manifest(
+    out: &mut Reporter,
+    dst_dir: &Path,
+    module: (&str, Option<&str>),
+    installed: &[String],
+    dry_run: bool,
+) {
+    let (module_name, version) = module;
+    match skill::clean_orphaned_skills(&RealFs, dst_dir, module_name, installed, dry_run) {
+        Ok(orphans) => {
+            for name in &orphans {
+                let message = if dry_run {
+                    format!("[dry-run] Would remove orphaned skill: {name}")
+                } else {
+                    format!("Removed orphaned skill: {name}")
+                };
+                out.info("remove-orphan", name, Some(dst_dir), message);
+            }
+        }
+        Err(e) => out.warn(
+            "error",
+            "",
+            Some(dst_dir),
+            format!("skill orphan scan failed: {e}"),
+        ),
+    }
+
+    if !dry_run {
+        if let Err(e) = manifest::record(&RealFs, dst_dir, module_name, version, installed) {
+            out.warn(
+                "error",
+                "",
+                Some(dst_dir),
+                format!("manifest update failed: {e}"),
+            );
+        }
+    }
+}
+
+/// Carry out `actions` in `dst_dir`, through the deploy cache unless
+/// `--no-cache`.
+fn execute_actions(
+    out: &mut Reporter,
+    args: &Args,
+    actions: &[SkillInstallAction],
+    dst_dir: &Path,
+) -> Result<(), ExitCode> {
+    let mut cache = (!args.no_cache).then(|| DeployCache::load(&RealFs, dst_dir));
+    for action in actions {
+        let result = if args.link {
+            // A link is not cached, so a later copy replaces it.
+            if let Some(cache) = cache.as_mut() {
+                cache.remove(action_skill_name(action));
+            }
+            execute_action(out, action, args.dry_run, true)
+        } else {
+            execute_cached(out, action, args.dry_run, cache.as_mut())
+        };
+        if let Err(e) = result {
+            if !args.continue_on_error {
+                eprintln!("Error: {e}");
+                return Err(ExitCode::from(1));
+            }
+            out.warn("error", action_skill_name(action), Some(dst_dir), e);
+        }
+    }
+
+    if let Some(cache) = cache.filter(|_| !args.dry_run) {
+        save_cache(out, dst_dir, &cache);
+    }
+    Ok(())
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> ExitCode {
+    let skills_path = Path::new(&args.skills_dir);
+    if !skills_path.is_dir() {
+        eprintln!("Error: not a directory: {}", args.skills_dir);
+        return ExitCode::from(1);
+    }
+
+    let dst_dir = match &args.dst_override {
+        Some(dst) => PathBuf::from(dst),
+        None => match resolve_dst(&args.provider, &args.scope) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return ExitCode::from(1);
+            }
+        },
+    };
+
+    out.begin_group(format!("{} {}", args.provider.as_str(), dst_dir.display()));
+    out.set_provider(args.provider.as_str());
+
+    let module_root = skills_path.parent().unwrap_or(Path::new("."));
+    let config = match SidecarConfig::load(module_root).with_overrides(&args.config) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    let module_name = read_module_field(skills_path, parse::module_name).unwrap_or_default();
+
+    out.set_module(&module_name);
+    let module_version = read_module_field(skills_path, parse::module_version);
+
+    if args.clean {
+        let paths = [dst_dir.clone()];
+        if let Err(e) =
+            super::snapshot::before_clean(out, "skills", &paths, args.dry_run, args.no_snapshot)
+        {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+        clean_module_skills(out, &dst_dir, &module_name, args.dry_run);
+    }
+
+    let mut actions = match skill::plan_skills_from_dir(
+        skills_path,
+        &args.provider,
+        &dst_dir,
+        &args.scope,
+        &config,
+    ) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    let mut _wrapper_tmpdir = None;
+    if args.include_agent_wrappers && args.provider != Provider::Gemini {
+        let agents_path = Path::new(&args.agents_dir);
+        match generate_and_plan_wrappers(
+            agents_path,
+            &args.provider,
+            &dst_dir,
+            &args.scope,
+            &config,
+        ) {
+            Ok((extra, tmpdir)) => {
+                actions.extend(extra);
+                _wrapper_tmpdir = tmpdir;
+            }
+            Err(e) => {
+                eprintln!("Error generating agent wrappers: {e}");
+                return ExitCode::from(1);
+            }
+        }
+    }
+
+    if let Err(code) = execute_actions(out, args, &actions, &dst_dir) {
+        return code;
+    }
+
+    if !module_name.is_empty() && args.provider != Provider::Gemini {
+        // Failed skills stay in the manifest so the orphan scan keeps them.
+        let installed: Vec<String> = actions
+            .iter()
+            .filter_map(|a| match a {
+                SkillInstallAction::Copy { skill_name, .. } => Some(skill_name.clone()),
+                _ => None,
+            })
+            .collect();
+        sync_manifest(
+            out,
+            &dst_dir,
+            (&module_name, module_version.as_deref()),
+            &installed,
+            args.dry_run,
+        );
+    }
+
+    out.end_group();
+    out.exit_code()
+}
+
+/// Run `install-skills` (also `forge skills install`).
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            if !args.dry_run && !args.yes && super::interactive() {
+                let dry = Args {
+                    dry_run: true,
+                    ..args.clone()
+                };
+                if let Err(code) = super::confirm_plan(|plan| execute(&dry, plan)) {
+                    return code;
+                }
+            }
+            let mut out = Reporter::new(args.json)
+                .with_report("install-skills", args.report.clone())
+                .with_detailed_exit_codes(args.detailed_exit_codes)
+                .with_history("install-skills", &args.scope, args.clean, args.dry_run)
+                .with_progress(args.progress);
+            let code = execute(args, &mut out);
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,276 @@
+use super::{flag_value, Reporter};
+use crate::deploy::DeployResult;
+use crate::hooks;
+use crate::manifest;
+use crate::parse;
+use crate::styles::{self, StatuslineResult};
+use crate::vfs::RealFs;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: install-styles [module-root] [--scope user|workspace] \
+                     [--dst <output-styles-dir>] [--settings <settings.json>] [--dry-run] \
+                     [--clean] [--no-snapshot] [--yes] [--report <file>] [--detailed-exit-codes] [--json] [--no-color]";
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Args {
+    module_root: PathBuf,
+    scope: String,
+    dst_override: Option<PathBuf>,
+    settings_override: Option<PathBuf>,
+    dry_run: bool,
+    clean: bool,
+    no_snapshot: bool,
+    yes: bool,
+    json: bool,
+    report: Option<PathBuf>,
+    /// Exit 3 when items were skipped and 4 when there was nothing to do.
+    detailed_exit_codes: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut module_root = PathBuf::from(".");
+    let mut scope = "user".to_string();
+    let mut dst_override: Option<PathBuf> = None;
+    let mut settings_override: Option<PathBuf> = None;
+    let mut dry_run = false;
+    let mut clean = false;
+    let mut no_snapshot = false;
+    let mut yes = false;
+    let mut json = false;
+    let mut report: Option<PathBuf> = None;
+    let mut detailed_exit_codes = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                println!("install-styles {}", env!("CARGO_PKG_VERSION"));
+                return Err(ExitCode::SUCCESS);
+            }
+            "--scope" => scope = flag_value(args, &mut i)?.to_string(),
+            "--dst" => dst_override = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--settings" => settings_override = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--dry-run" => dry_run = true,
+            "--clean" => clean = true,
+            "--no-snapshot" => no_snapshot = true,
+            "-y" | "--yes" => yes = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--detailed-exit-codes" => detailed_exit_codes = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => module_root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+
+    Ok(Args {
+        module_root,
+        scope,
+        dst_override,
+        settings_override,
+        dry_run,
+        clean,
+        no_snapshot,
+        yes,
+        json,
+        report,
+        detailed_exit_codes,
+    })
+}
+
+fn sync_styles(
+    out: &mut Reporter,
+    args: &Args,
+    dst_dir: &Path,
+    module_name: &str,
+) -> Result<(), String> {
+    let src_dir = args.module_root.join(styles::STYLES_DIR);
+    let results = if args.clean {
+        Vec::new()
+    } else {
+        styles::deploy_styles_from_dir(
+            &src_dir,
+            dst_dir,
+            &format!("{module_name}/{}", styles::STYLES_DIR),
+            args.dry_run,
+        )?
+    };
+
+    let mut installed = Vec::new();
+    for (name, result) in results {
+        match result {
+            DeployResult::Deployed => {
+                let message = if args.dry_run {
+                    format!(
+                        "[dry-run] Would install: {name}.md to {}",
+                        dst_dir.display()
+                    )
+                } else {
+                    format!("Installed: {name}.md to {}", dst_dir.display())
+                };
+                out.info("install", &name, Some(dst_dir), message);
+                installed.push(name);
+            }
+            DeployResult::SkippedUserOwned => out.warn(
+                "skip-user-owned",
+                &name,
+                Some(dst_dir),
+                format!("Skipping {name}.md — user-created output style (no source field)"),
+            ),
+            // Only agent deploys merge user edits.
+            DeployResult::Merged
+            | DeployResult::Conflicted
+            | DeployResult::Unchanged
+            | DeployResult::SkippedTemplate
+            | DeployResult::SkippedNoName
+            | DeployResult::SkippedProviderExcluded => {}
+        }
+    }
+
+    for name in manifest::remove_orphans(
+        &RealFs,
+        dst_dir,
+        module_name,
+        &installed,
+        "md",
+        args.dry_run,
+    )? {
+        let message = if args.dry_run {
+            format!("[dry-run] Would remove: {name}.md")
+        } else {
+            format!("Removed: {name}.md")
+        };
+        out.info("remove", &name, Some(dst_dir), message);
+    }
+    if !args.dry_run && dst_dir.is_dir() {
+        manifest::update(&RealFs, dst_dir, module_name, &installed)?;
+    }
+    Ok(())
+}
+
+fn sync_statusline(
+    out: &mut Reporter,
+    args: &Args,
+    settings: &Path,
+    module_name: &str,
+) -> Result<(), String> {
+    let statusline = if args.clean {
+        None
+    } else {
+        styles::load_module_statusline(&args.module_root)?
+    };
+    let result =
+        styles::deploy_statusline(settings, module_name, statusline.as_ref(), args.dry_run)?;
+    let settings_name = settings.display();
+    let (action, message) = match result {
+        StatuslineResult::Installed if args.dry_run => (
+            "install",
+            format!("[dry-run] Would install statusline in {settings_name}"),
+        ),
+        StatuslineResult::Installed => (
+            "install",
+            format!("Installed statusline in {settings_name}"),
+        ),
+        StatuslineResult::Removed if args.dry_run => (
+            "remove",
+            format!("[dry-run] Would remove statusline from {settings_name}"),
+        ),
+        StatuslineResult::Removed => ("remove", format!("Removed statusline from {settings_name}")),
+        StatuslineResult::SkippedUserOwned => {
+            out.warn(
+                "skip-user-owned",
+                "statusLine",
+                Some(settings),
+                format!("Skipping statusline — {settings_name} already sets one"),
+            );
+            return Ok(());
+        }
+        StatuslineResult::Unchanged => return Ok(()),
+    };
+    out.info(action, "statusLine", Some(settings), message);
+    Ok(())
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> ExitCode {
+    out.set_provider("claude");
+    if !args.module_root.is_dir() {
+        eprintln!("Error: not a directory: {}", args.module_root.display());
+        return ExitCode::from(1);
+    }
+    let module_name = std::fs::read_to_string(args.module_root.join("module.yaml"))
+        .ok()
+        .and_then(|c| parse::module_name(&c));
+    let Some(module_name) = module_name else {
+        eprintln!("Error: module.yaml with a name is required to track deployed styles");
+        return ExitCode::from(1);
+    };
+    out.set_module(&module_name);
+
+    let home = env::var("HOME").unwrap_or_default();
+    let home = Path::new(&home);
+    let dst_dir = match &args.dst_override {
+        Some(dst) => Ok(dst.clone()),
+        None => styles::styles_dir(&args.scope, home),
+    };
+    let settings = match &args.settings_override {
+        Some(path) => Ok(path.clone()),
+        None => hooks::scope_settings_path(&args.scope, home),
+    };
+
+    let result = dst_dir.and_then(|dst_dir| {
+        let settings = settings?;
+        if args.clean {
+            let paths = [
+                dst_dir.clone(),
+                settings.clone(),
+                styles::manifest_path(&settings),
+            ];
+            super::snapshot::before_clean(out, "styles", &paths, args.dry_run, args.no_snapshot)?;
+        }
+        sync_styles(out, args, &dst_dir, &module_name)?;
+        sync_statusline(out, args, &settings, &module_name)
+    });
+    match result {
+        Ok(()) => out.exit_code(),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+/// Run `install-styles` (also `forge styles install`).
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            if !args.dry_run && !args.yes && super::interactive() {
+                let dry = Args {
+                    dry_run: true,
+                    ..args.clone()
+                };
+                if let Err(code) = super::confirm_plan(|plan| execute(&dry, plan)) {
+                    return code;
+                }
+            }
+            let mut out = Reporter::new(args.json)
+                .with_report("install-styles", args.report.clone())
+                .with_detailed_exit_codes(args.detailed_exit_codes)
+                .with_history("install-styles", &args.scope, args.clean, args.dry_run);
+            let code = execute(args, &mut out);
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,128 @@
+//! `forge migrate`: rewrite deployments left by the old shell installers
+//! (`# synced-from:` files, flat manifests) into `source:` frontmatter and
+//! per-module manifests.
+
+use super::{flag_value, Reporter};
+use crate::migrate::{self, Finding, SourceIndex};
+use crate::outdated;
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge migrate [module-root...] [--scope <user|workspace|all>] [--provider <name>]
+                     [--dry-run] [--json] [--no-color]
+       Module checkouts attribute legacy files to the module that ships them.";
+
+const PROVIDERS: &[&str] = &[
+    "claude", "gemini", "codex", "opencode", "cursor", "windsurf", "copilot", "zed",
+];
+
+struct Args {
+    checkouts: Vec<PathBuf>,
+    scope: String,
+    provider: Option<String>,
+    dry_run: bool,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        checkouts: Vec::new(),
+        scope: "all".to_string(),
+        provider: None,
+        dry_run: false,
+        json: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--scope" => flag_value(args, &mut i)?.clone_into(&mut parsed.scope),
+            "--provider" => parsed.provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--dry-run" => parsed.dry_run = true,
+            "--json" => parsed.json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.checkouts.push(PathBuf::from(arg)),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<ExitCode, String> {
+    let index = SourceIndex::from_checkouts(&args.checkouts)?;
+    let home = PathBuf::from(env::var("HOME").unwrap_or_default());
+    let providers: Vec<String> = match &args.provider {
+        Some(p) => vec![p.clone()],
+        None => PROVIDERS.iter().map(|p| (*p).to_string()).collect(),
+    };
+    let roots = outdated::provider_roots(&home, &providers, &args.scope)?;
+    let prefix = if args.dry_run {
+        "[dry-run] Would rewrite"
+    } else {
+        "Rewrote"
+    };
+
+    let mut found = false;
+    for dir in outdated::artifact_dirs(&roots) {
+        for finding in migrate::migrate_dir(&dir, &index, args.dry_run)? {
+            found = true;
+            match finding {
+                Finding::Rewritten { path, source } => {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    out.info(
+                        "migrate",
+                        &name,
+                        Some(&dir),
+                        format!("{prefix}: {} (source: {source})", path.display()),
+                    );
+                }
+                Finding::Manifest { path, modules } => {
+                    let names: Vec<&str> = modules.keys().map(String::as_str).collect();
+                    out.info(
+                        "migrate-manifest",
+                        ".manifest",
+                        Some(&dir),
+                        format!("{prefix}: {} ({})", path.display(), names.join(", ")),
+                    );
+                }
+                Finding::Unattributed { path, reason } => {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    out.warn(
+                        "skip-unattributed",
+                        &name,
+                        Some(&dir),
+                        format!("Could not attribute {}: {reason}", path.display()),
+                    );
+                }
+            }
+        }
+    }
+    if !found {
+        out.note("Nothing to migrate");
+    }
+    Ok(out.exit_code())
+}
+
+/// Run `forge migrate`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let mut out = Reporter::new(args.json);
+    let result = execute(&args, &mut out);
+    out.finish();
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })
+}
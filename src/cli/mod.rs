@@ -0,0 +1,774 @@
+//! Command-line front-ends shared by the unified `forge` binary and the
+//! standalone binaries (`install-agents`, `install-skills`, ...), which are
+//! thin aliases for the matching `forge` subcommand.
+//!
+//! Each submodule exposes `run(args)` taking the arguments after the program
+//! (or subcommand) name. This is the only part of the library that prints.
+
+pub mod completions;
+mod council;
+mod export;
+mod graph;
+mod history;
+pub mod install_agents;
+pub mod install_commands;
+pub mod install_hooks;
+pub mod install_mcp;
+pub mod install_memory;
+pub mod install_skills;
+pub mod install_styles;
+mod migrate;
+pub mod new;
+mod outdated;
+pub mod pack;
+mod plugin;
+mod registry;
+mod report;
+mod snapshot;
+mod status;
+pub mod strip_front;
+mod style;
+pub mod validate_module;
+mod verify;
+mod watch;
+pub mod yaml;
+
+use crate::progress::ProgressSink;
+use serde::Serialize;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+use style::Color;
+
+// ─── Output ───
+
+#[derive(Serialize)]
+struct Record {
+    action: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    message: String,
+}
+
+/// The model an agent was deployed with, for `--report`.
+#[derive(Serialize)]
+struct ModelAssignment {
+    provider: String,
+    agent: String,
+    model: String,
+    /// Whether the model is written to the agent file; otherwise the
+    /// provider's whitelist dropped it and the agent inherits the default.
+    pinned: bool,
+}
+
+/// Collects per-item CLI output. In human mode each line is printed as it
+/// happens; with `--json` nothing is printed until `finish`, which emits one
+/// JSON document with every recorded item. In progress mode per-item lines
+/// are replaced by one summary line per group (e.g. per provider directory).
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct Reporter {
+    json: bool,
+    quiet: bool,
+    progress: bool,
+    group: Option<Group>,
+    records: Vec<Record>,
+    /// Provider that following records belong to.
+    provider: Option<String>,
+    models: Vec<ModelAssignment>,
+    /// `--report` destination and the tool name to head it with.
+    report: Option<(PathBuf, String)>,
+    /// Audit-log context; `None` for dry runs, which change nothing.
+    history: Option<history::Context>,
+    /// Report skips and no-op runs in the exit status
+    /// (`--detailed-exit-codes`) instead of exiting 0.
+    detailed_exit: bool,
+}
+
+/// A unit of work summarised as one line in progress mode.
+struct Group {
+    label: String,
+    started: Instant,
+    first_record: usize,
+    /// Whether an unfinished `label ...` line is on the terminal.
+    pending: bool,
+    /// Library progress within the group (see [`ProgressSink`]).
+    done: usize,
+    total: usize,
+}
+
+fn action_color(action: &str) -> Option<Color> {
+    match action {
+        "install" | "create" => Some(Color::Green),
+        "replace" | "merge" | "restore" | "update-config" | "register" => Some(Color::Cyan),
+        "remove" | "remove-orphan" | "clean" | "clean-config" => Some(Color::Red),
+        "outdated" | "modified" | "missing" | "orphaned" | "user-owned" => Some(Color::Yellow),
+        _ => None,
+    }
+}
+
+/// Count records by outcome, e.g. `40 installed, 2 removed, 1 skipped`.
+fn summarize<'a>(records: impl IntoIterator<Item = &'a Record>) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for record in records {
+        let word = match record.action.as_str() {
+            "install" | "create" => "installed",
+            "replace" => "replaced",
+            "merge" => "merged",
+            "restore" => "restored",
+            "conflict" => "conflicted",
+            "unchanged" => "unchanged",
+            "remove" | "remove-orphan" => "removed",
+            "clean" | "clean-config" => "cleaned",
+            "update-config" | "register" => "updated",
+            "error" => "failed",
+            a if a.starts_with("skip") => "skipped",
+            _ => continue,
+        };
+        match counts.iter_mut().find(|(w, _)| *w == word) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((word, 1)),
+        }
+    }
+    if counts.is_empty() {
+        return "nothing to do".to_string();
+    }
+    counts
+        .iter()
+        .map(|(word, n)| format!("{n} {word}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// [`Reporter::exit_code`] for `records`.
+fn exit_status(records: &[&Record]) -> u8 {
+    let any = |pred: fn(&str) -> bool| records.iter().any(|r| pred(&r.action));
+    if any(|a| a == "error" || a == "conflict") {
+        EXIT_PARTIAL
+    } else if any(|a| a.starts_with("skip")) {
+        EXIT_SKIPPED
+    } else if any(|a| !NO_OP_ACTIONS.contains(&a)) {
+        EXIT_OK
+    } else {
+        EXIT_NOTHING
+    }
+}
+
+/// Actions that deploy nothing new: a run with only these had nothing to
+/// do. The Codex `config.toml` block is rewritten on every run.
+const NO_OP_ACTIONS: &[&str] = &["unchanged", "update-config"];
+
+/// Actions that delete or overwrite something at the destination.
+const DESTRUCTIVE_ACTIONS: &[&str] = &[
+    "remove",
+    "remove-orphan",
+    "clean",
+    "clean-config",
+    "replace",
+    "merge",
+    "restore",
+    "conflict",
+];
+
+impl Reporter {
+    pub(crate) fn new(json: bool) -> Self {
+        Self {
+            json,
+            quiet: false,
+            progress: false,
+            group: None,
+            records: Vec::new(),
+            provider: None,
+            models: Vec::new(),
+            report: None,
+            history: None,
+            detailed_exit: false,
+        }
+    }
+
+    /// A reporter that records without printing anything, used for the
+    /// dry-run pass that builds a confirmation list.
+    pub(crate) fn silent() -> Self {
+        Self {
+            json: false,
+            quiet: true,
+            progress: false,
+            group: None,
+            records: Vec::new(),
+            provider: None,
+            models: Vec::new(),
+            report: None,
+            history: None,
+            detailed_exit: false,
+        }
+    }
+
+    /// Summarise each group in one line instead of printing every item
+    /// (`--progress`). Has no effect in `--json` mode.
+    pub(crate) fn with_progress(mut self, progress: bool) -> Self {
+        self.progress = progress && !self.json;
+        self
+    }
+
+    /// Write a deployment report to `path` when the run finishes
+    /// (`--report`).
+    pub(crate) fn with_report(mut self, tool: &str, path: Option<PathBuf>) -> Self {
+        self.report = path.map(|p| (p, tool.to_string()));
+        self
+    }
+
+    /// Exit with [`EXIT_SKIPPED`] or [`EXIT_NOTHING`] instead of
+    /// [`EXIT_OK`] when items were skipped or nothing was found
+    /// (`--detailed-exit-codes`), for scripts that need to tell them apart.
+    pub(crate) fn with_detailed_exit_codes(mut self, detailed: bool) -> Self {
+        self.detailed_exit = detailed;
+        self
+    }
+
+    /// Append what this run changed to `.forge/history.jsonl` in each
+    /// destination root when it finishes. Dry runs are not logged.
+    pub(crate) fn with_history(
+        mut self,
+        tool: &str,
+        scope: &str,
+        clean: bool,
+        dry_run: bool,
+    ) -> Self {
+        self.history = (!dry_run).then(|| history::Context {
+            tool: tool.to_string(),
+            operation: if clean { "clean" } else { "install" },
+            scope: (!scope.is_empty()).then(|| scope.to_string()),
+            module: None,
+        });
+        self
+    }
+
+    /// Name the module being deployed in history entries.
+    pub(crate) fn set_module(&mut self, module: &str) {
+        if let Some(history) = self.history.as_mut() {
+            history.module = (!module.is_empty()).then(|| module.to_string());
+        }
+    }
+
+    /// Attribute the following records to `provider`.
+    pub(crate) fn set_provider(&mut self, provider: &str) {
+        self.provider = Some(provider.to_string());
+    }
+
+    /// Record the model `agent` was deployed with for the current provider.
+    pub(crate) fn model(&mut self, agent: &str, model: &str, pinned: bool) {
+        self.models.push(ModelAssignment {
+            provider: self.provider.clone().unwrap_or_default(),
+            agent: agent.to_string(),
+            model: model.to_string(),
+            pinned,
+        });
+    }
+
+    /// Start a group of work, e.g. `[2/4] codex .codex/agents`.
+    pub(crate) fn begin_group(&mut self, label: String) {
+        let pending = self.progress && !self.quiet && io::stderr().is_terminal();
+        if pending {
+            eprint!("{label} ...");
+            let _ = io::stderr().flush();
+        }
+        self.group = Some(Group {
+            label,
+            started: Instant::now(),
+            first_record: self.records.len(),
+            pending,
+            done: 0,
+            total: 0,
+        });
+    }
+
+    /// Finish the current group, printing its summary line in progress mode.
+    pub(crate) fn end_group(&mut self) {
+        let Some(group) = self.group.take() else {
+            return;
+        };
+        if !self.progress || self.quiet {
+            return;
+        }
+        let summary = summarize(&self.records[group.first_record..]);
+        let millis = group.started.elapsed().as_millis();
+        if group.pending {
+            eprint!("\r\x1b[2K");
+        }
+        eprintln!("{}: {summary} ({millis} ms)", group.label);
+    }
+
+    fn clear_pending(&mut self) {
+        if let Some(group) = self.group.as_mut() {
+            if group.pending {
+                eprintln!();
+                group.pending = false;
+            }
+        }
+    }
+
+    /// Print a status line to stderr (human mode only; never recorded).
+    pub(crate) fn note(&self, message: &str) {
+        if !self.quiet && !self.progress {
+            eprintln!("{message}");
+        }
+    }
+
+    /// Print `text` to stdout as it is (human mode only; never recorded),
+    /// e.g. a diff under the result line it belongs to.
+    pub(crate) fn detail(&self, text: &str) {
+        if !self.json && !self.quiet && !self.progress {
+            print!("{text}");
+        }
+    }
+
+    /// Record a result line (stdout in human mode).
+    pub(crate) fn info(
+        &mut self,
+        action: &str,
+        name: &str,
+        target: Option<&Path>,
+        message: String,
+    ) {
+        if !self.json && !self.quiet && !self.progress {
+            match action_color(action) {
+                Some(color) => println!("{}", style::paint_label(&message, color, style::stdout())),
+                None => println!("{message}"),
+            }
+        }
+        self.push(action, name, target, message);
+    }
+
+    /// Record a warning (stderr in human mode, prefixed with `Warning: `).
+    pub(crate) fn warn(
+        &mut self,
+        action: &str,
+        name: &str,
+        target: Option<&Path>,
+        message: String,
+    ) {
+        if !self.json && !self.quiet {
+            self.clear_pending();
+            let label = style::paint("Warning", Color::Yellow, style::stderr());
+            eprintln!("{label}: {message}");
+        }
+        self.push(action, name, target, message);
+    }
+
+    fn push(&mut self, action: &str, name: &str, target: Option<&Path>, message: String) {
+        self.records.push(Record {
+            action: action.to_string(),
+            name: name.to_string(),
+            provider: self.provider.clone(),
+            target: target.map(|p| p.display().to_string()),
+            message,
+        });
+    }
+
+    /// One line per recorded destructive action, e.g.
+    /// `remove Reviewer in .claude/agents`.
+    pub(crate) fn destructive(&self) -> Vec<String> {
+        self.records
+            .iter()
+            .filter(|r| DESTRUCTIVE_ACTIONS.contains(&r.action.as_str()))
+            .map(|r| match &r.target {
+                Some(target) => format!("{} {} in {target}", r.action, r.name),
+                None => format!("{} {}", r.action, r.name),
+            })
+            .collect()
+    }
+
+    pub(crate) fn is_json(&self) -> bool {
+        self.json
+    }
+
+    /// Exit status summarising a run that reached the end without a hard
+    /// error: partial failure, then skips, then nothing to do, then success.
+    /// Skips and no-op runs exit 0 unless `--detailed-exit-codes` is given.
+    pub(crate) fn exit_code(&self) -> ExitCode {
+        let records: Vec<&Record> = self.records.iter().collect();
+        let status = exit_status(&records);
+        if self.detailed_exit || status == EXIT_PARTIAL {
+            ExitCode::from(status)
+        } else {
+            ExitCode::from(EXIT_OK)
+        }
+    }
+
+    /// Emit the JSON document in `--json` mode; in human mode, repeat every
+    /// recorded error as a summary on stderr so it is not lost in the output.
+    pub(crate) fn finish(&self) {
+        if let Some((path, tool)) = &self.report {
+            if let Err(e) = report::write(path, tool, &self.records, &self.models) {
+                eprintln!("Error: {e}");
+            }
+        }
+        if let Some(context) = &self.history {
+            if let Err(e) = history::append(context, &self.records) {
+                eprintln!("Error: {e}");
+            }
+        }
+        if self.json {
+            let doc = serde_json::json!({ "results": self.records });
+            println!("{}", serde_json::to_string_pretty(&doc).unwrap_or_default());
+            return;
+        }
+        let errors: Vec<&Record> = self
+            .records
+            .iter()
+            .filter(|r| r.action == "error")
+            .collect();
+        if !errors.is_empty() && !self.quiet {
+            let heading = format!("{} error(s)", errors.len());
+            eprintln!("\n{}:", style::paint(&heading, Color::Red, style::stderr()));
+            for record in errors {
+                eprintln!("  - {}", record.message);
+            }
+        }
+    }
+}
+
+/// Counts library work on the pending progress line: `label ... 3/12`.
+impl ProgressSink for Reporter {
+    fn on_start(&mut self, _operation: &str, total: usize) {
+        if let Some(group) = self.group.as_mut() {
+            group.total += total;
+        }
+    }
+
+    fn on_item(&mut self, _operation: &str, _item: &str, _outcome: &str) {
+        if let Some(group) = self.group.as_mut() {
+            group.done += 1;
+            if group.pending {
+                eprint!(
+                    "\r\x1b[2K{} ... {}/{}",
+                    group.label, group.done, group.total
+                );
+                let _ = io::stderr().flush();
+            }
+        }
+    }
+}
+
+// ─── Exit codes ───
+
+// Exit statuses of `agents install` and `skills install`. Anything that
+// aborts the run (bad arguments, unreadable source) is `EXIT_ERROR`.
+/// Everything found was deployed.
+pub const EXIT_OK: u8 = 0;
+/// Hard error: nothing after the failure was attempted.
+pub const EXIT_ERROR: u8 = 1;
+/// The run finished, but some items failed (e.g. manifest or orphan scan).
+pub const EXIT_PARTIAL: u8 = 2;
+/// Everything deployable was deployed, but some items were skipped
+/// (e.g. user-owned agents); only with `--detailed-exit-codes`.
+pub const EXIT_SKIPPED: u8 = 3;
+/// The source held nothing to deploy; only with `--detailed-exit-codes`.
+pub const EXIT_NOTHING: u8 = 4;
+
+// ─── Confirmation ───
+
+/// Whether a confirmation prompt can be answered (stdin is a terminal).
+/// Non-interactive runs never prompt, so existing scripts keep working.
+pub(crate) fn interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// List `items` on stderr and ask whether to proceed. Returns `true`
+/// without asking when there is nothing to confirm.
+fn confirm(items: &[String]) -> bool {
+    if items.is_empty() {
+        return true;
+    }
+    eprintln!("The following will be deleted or replaced:");
+    for item in items {
+        eprintln!("  {item}");
+    }
+    eprint!("Proceed? [y/N] ");
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).is_ok() && is_yes(&answer)
+}
+
+/// Read one answer from stdin; `None` once it is closed.
+fn read_answer() -> Option<String> {
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(answer),
+    }
+}
+
+/// The choice `answer` names, in full or by its first letter.
+fn parse_choice<'a>(answer: &str, choices: &[&'a str]) -> Option<&'a str> {
+    let answer = answer.trim().to_ascii_lowercase();
+    choices
+        .iter()
+        .copied()
+        .find(|c| *c == answer || (answer.len() == 1 && c.starts_with(&answer)))
+}
+
+/// Ask `question` on stderr until the answer is one of `choices`, shown as
+/// `[o]verwrite/[s]kip`. `None` when stdin closes first.
+pub(crate) fn choose<'a>(question: &str, choices: &[&'a str]) -> Option<&'a str> {
+    let options = choices
+        .iter()
+        .map(|c| format!("[{}]{}", &c[..1], &c[1..]))
+        .collect::<Vec<_>>()
+        .join("/");
+    loop {
+        eprint!("{question} {options} ");
+        let _ = io::stderr().flush();
+        if let Some(choice) = parse_choice(&read_answer()?, choices) {
+            return Some(choice);
+        }
+    }
+}
+
+/// Ask for a line of text on stderr, taking `default` for an empty answer.
+/// `None` when stdin is closed.
+pub(crate) fn ask(question: &str, default: &str) -> Option<String> {
+    eprint!("{question} [{default}] ");
+    let _ = io::stderr().flush();
+    let answer = read_answer()?;
+    let answer = answer.trim();
+    Some(if answer.is_empty() { default } else { answer }.to_string())
+}
+
+/// Replay a run as a silent dry run and ask the user to confirm everything
+/// it would delete or replace. `Err` carries the exit code to return: the
+/// dry run's hard error, or 1 when the user declines.
+pub(crate) fn confirm_plan(
+    dry_run: impl FnOnce(&mut Reporter) -> ExitCode,
+) -> Result<(), ExitCode> {
+    let mut plan = Reporter::silent();
+    let code = dry_run(&mut plan);
+    if code == ExitCode::from(EXIT_ERROR) {
+        return Err(code);
+    }
+    if confirm(&plan.destructive()) {
+        Ok(())
+    } else {
+        eprintln!("Aborted.");
+        Err(ExitCode::from(1))
+    }
+}
+
+// ─── forge multiplexer ───
+
+const FORGE_USAGE: &str = "\
+Usage: forge [--scope <scope>] [--provider <provider>] [--json] [--dry-run] [--no-color]
+             <command> [args...]
+
+Commands:
+  agents install <agents-dir> [...]    Deploy agents (alias: install-agents)
+  skills install <skills-dir> [...]    Install skills (alias: install-skills)
+  commands install <commands-dir> [...] Install slash commands (alias: install-commands)
+  hooks install [module-root] [...]    Merge hooks into Claude settings (alias: install-hooks)
+  mcp install [module-root] [...]      Add MCP servers to provider configs (alias: install-mcp)
+  memory install [module-root] [...]   Add a managed block to CLAUDE.md/GEMINI.md/AGENTS.md (alias: install-memory)
+  styles install [module-root] [...]   Install output styles and statusline (alias: install-styles)
+  validate [module-root]               Run convention suites (alias: validate-module)
+  council generate [module-root] [...] Generate council skills from the councils: roster
+  graph [module-root] [--format <fmt>] Diagram councils, agents, and skills (dot|mermaid)
+  plugin generate [module-root] [...]  Refresh .claude-plugin metadata from module content
+  export [module-root] --format <fmt>  Export agents for other tools (openai|plain)
+  new module|agent|skill <name>        Scaffold a module, agent, or skill
+  pack [module-root] [--out <dir>]     Validate and bundle a module into a versioned archive
+  search [query]                       Search the module registry
+  install <module>[@<version>] [...]   Fetch, verify, and deploy a module from the registry
+  update [module...] [...]             Redeploy registry modules with newer releases
+  outdated [module-root...] [...]      List deployed modules behind their source (--redeploy)
+  status <agents-dir> [...]            Report deployed agents that drifted from their source
+  verify [...]                         Check deployed files against their manifest checksums
+  snapshot create|restore|list [...]   Back up and restore provider directories
+  migrate [module-root...] [...]       Convert legacy shell-installer deployments
+  history [dest-root...] [...]         Show the deploy audit log
+  watch [module-root] [...]            Redeploy a module's changed sources as they are edited
+  completions <bash|zsh|fish>          Print a shell completion script
+  yaml <command> <file> <path> [...]   Query YAML files (alias: yaml)
+  strip [--keep k1,k2] <file>          Strip frontmatter and H1 (alias: strip-front)
+
+Global flags are forwarded to the subcommand; run `forge <command> --help` for details.";
+
+/// Global flags accepted before the subcommand, as `(flag, takes_value)`.
+const GLOBAL_FLAGS: &[(&str, bool)] = &[
+    ("--scope", true),
+    ("--provider", true),
+    ("--json", false),
+    ("--dry-run", false),
+    ("--no-color", false),
+];
+
+/// Split leading global flags off `args`, returning them (flag and value
+/// pairs, in order) and the remaining arguments starting at the subcommand.
+fn split_global_flags(args: &[String]) -> Result<(Vec<String>, &[String]), String> {
+    let mut globals = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let Some(&(flag, takes_value)) = GLOBAL_FLAGS.iter().find(|(f, _)| *f == args[i]) else {
+            break;
+        };
+        globals.push(flag.to_string());
+        if takes_value {
+            i += 1;
+            let Some(value) = args.get(i) else {
+                return Err(format!("{flag} requires a value"));
+            };
+            globals.push(value.clone());
+        }
+        i += 1;
+    }
+    Ok((globals, &args[i..]))
+}
+
+/// Require `<action>` after a command group such as `agents` or `skills`.
+fn expect_action<'a>(
+    group: &str,
+    action: &str,
+    rest: &'a [String],
+) -> Result<&'a [String], String> {
+    match rest.first().map(String::as_str) {
+        Some(a) if a == action => Ok(&rest[1..]),
+        Some(other) => Err(format!(
+            "unknown action `{group} {other}` (expected `{group} {action}`)"
+        )),
+        None => Err(format!("missing action (expected `{group} {action}`)")),
+    }
+}
+
+/// Entry point of the `forge` binary.
+pub fn forge(args: &[String]) -> ExitCode {
+    let (globals, rest) = match split_global_flags(args) {
+        Ok(split) => split,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    // Color is process-wide, so `--no-color` is applied here rather than
+    // forwarded; that also keeps it valid before `yaml` and `strip`.
+    if globals.iter().any(|flag| flag == "--no-color") {
+        style::disable();
+    }
+    let globals: Vec<String> = globals.into_iter().filter(|f| f != "--no-color").collect();
+
+    let Some(command) = rest.first() else {
+        eprintln!("{FORGE_USAGE}");
+        return ExitCode::from(1);
+    };
+    let rest = &rest[1..];
+
+    let forward = |sub: &[String]| -> Vec<String> {
+        let mut v = sub.to_vec();
+        v.extend(globals.iter().cloned());
+        v
+    };
+    let reject_globals = |name: &str| -> Result<(), String> {
+        match globals.first() {
+            Some(flag) => Err(format!("{flag} is not supported by `forge {name}`")),
+            None => Ok(()),
+        }
+    };
+
+    let result = match command.as_str() {
+        "--version" => {
+            println!("forge {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        "-h" | "--help" | "help" => {
+            println!("{FORGE_USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        "agents" => {
+            expect_action("agents", "install", rest).map(|sub| install_agents::run(&forward(sub)))
+        }
+        "skills" => {
+            expect_action("skills", "install", rest).map(|sub| install_skills::run(&forward(sub)))
+        }
+        "commands" => expect_action("commands", "install", rest)
+            .map(|sub| install_commands::run(&forward(sub))),
+        "hooks" => {
+            expect_action("hooks", "install", rest).map(|sub| install_hooks::run(&forward(sub)))
+        }
+        "mcp" => expect_action("mcp", "install", rest).map(|sub| install_mcp::run(&forward(sub))),
+        "memory" => {
+            expect_action("memory", "install", rest).map(|sub| install_memory::run(&forward(sub)))
+        }
+        "styles" => {
+            expect_action("styles", "install", rest).map(|sub| install_styles::run(&forward(sub)))
+        }
+        "council" => {
+            expect_action("council", "generate", rest).map(|sub| council::run(&forward(sub)))
+        }
+        "plugin" => expect_action("plugin", "generate", rest).map(|sub| plugin::run(&forward(sub))),
+        "graph" => reject_globals("graph").map(|()| graph::run(rest)),
+        "export" => reject_globals("export").map(|()| export::run(rest)),
+        "validate" => Ok(validate_module::run(&forward(rest))),
+        "new" => Ok(new::run(&forward(rest))),
+        "pack" => Ok(pack::run(&forward(rest))),
+        "search" => Ok(registry::search(&forward(rest))),
+        "install" => Ok(registry::install(&forward(rest))),
+        "update" => Ok(registry::update(&forward(rest))),
+        "outdated" => Ok(outdated::run(&forward(rest))),
+        "status" => Ok(status::run(&forward(rest))),
+        "verify" => Ok(verify::run(&forward(rest))),
+        "snapshot" => Ok(snapshot::run(&forward(rest))),
+        "history" => Ok(history::run(&forward(rest))),
+        "migrate" => Ok(migrate::run(&forward(rest))),
+        "watch" => Ok(watch::run(&forward(rest))),
+        "yaml" => reject_globals("yaml").map(|()| yaml::run(rest)),
+        "strip" => reject_globals("strip").map(|()| strip_front::run(rest)),
+        "completions" => reject_globals("completions").map(|()| completions::run(rest)),
+        other => Err(format!("unknown command {other:?}")),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        eprintln!("{FORGE_USAGE}");
+        ExitCode::from(1)
+    })
+}
+
+/// Advance `i` to the value of the flag at `args[*i]`, printing the usual
+/// error when it is missing.
+pub(crate) fn flag_value<'a>(args: &'a [String], i: &mut usize) -> Result<&'a str, ExitCode> {
+    let flag = &args[*i];
+    *i += 1;
+    args.get(*i).map(String::as_str).ok_or_else(|| {
+        eprintln!("Error: {flag} requires a value");
+        ExitCode::from(1)
+    })
+}
+
+/// Environment variable holding a `tracing` filter (`debug`,
+/// `forge_lib::deploy=trace`, ...) for library logs on stderr.
+pub const LOG_ENV: &str = "FORGE_LOG";
+
+/// Print the library's `tracing` spans and events to stderr when
+/// [`LOG_ENV`] is set. Embedders install their own subscriber instead.
+pub fn init_logging() {
+    let Ok(filter) = tracing_subscriber::EnvFilter::try_from_env(LOG_ENV) else {
+        return;
+    };
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// Arguments after the program name, as passed to every `run`.
+pub fn program_args() -> Vec<String> {
+    std::env::args().skip(1).collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,208 @@
+use super::Reporter;
+use crate::parse;
+use crate::scaffold::{self, ScaffoldFile};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: forge new module <name> [--dir <parent>] [--dry-run] [--json] [--no-color]\n       \
+                     forge new agent <Name> [--root <module-root>] [--dry-run] [--json] [--no-color]\n       \
+                     forge new skill <Name> [--root <module-root>] [--dry-run] [--json] [--no-color]";
+
+struct Args {
+    kind: String,
+    name: String,
+    dir: PathBuf,
+    dry_run: bool,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut positional = Vec::new();
+    let mut dir = PathBuf::from(".");
+    let mut dry_run = false;
+    let mut json = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            "--no-color" => super::style::disable(),
+            flag @ ("--dir" | "--root") => {
+                i += 1;
+                if i >= args.len() {
+                    eprintln!("Error: {flag} requires a value");
+                    return Err(ExitCode::from(1));
+                }
+                dir = PathBuf::from(&args[i]);
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => positional.push(arg.to_string()),
+        }
+        i += 1;
+    }
+
+    let [kind, name] = <[String; 2]>::try_from(positional).map_err(|_| {
+        eprintln!("{USAGE}");
+        ExitCode::from(1)
+    })?;
+
+    Ok(Args {
+        kind,
+        name,
+        dir,
+        dry_run,
+        json,
+    })
+}
+
+fn write_files(
+    out: &mut Reporter,
+    root: &Path,
+    files: &[ScaffoldFile],
+    dry_run: bool,
+) -> Result<(), String> {
+    for file in files {
+        let path = root.join(&file.path);
+        if path.exists() {
+            return Err(format!("refusing to overwrite {}", path.display()));
+        }
+    }
+    for file in files {
+        let path = root.join(&file.path);
+        if !dry_run {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+            }
+            std::fs::write(&path, &file.content)
+                .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+        }
+        let label = file.path.display().to_string();
+        let message = if dry_run {
+            format!("[dry-run] Would create: {}", path.display())
+        } else {
+            format!("Created: {}", path.display())
+        };
+        out.info("create", &label, Some(&path), message);
+    }
+    Ok(())
+}
+
+/// Register `entry` under `parents` in the module's defaults.yaml, unless
+/// a key named `name` is already present there.
+fn register_in_defaults(
+    out: &mut Reporter,
+    root: &Path,
+    parents: &[&str],
+    name: &str,
+    entry: &str,
+    dry_run: bool,
+) -> Result<(), String> {
+    let path = root.join("defaults.yaml");
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+
+    let existing: serde_yaml::Value = serde_yaml::from_str(&content).unwrap_or_default();
+    let mut node = &existing;
+    for key in parents {
+        node = node.get(key).unwrap_or(&serde_yaml::Value::Null);
+    }
+    if node.get(name).is_some() {
+        return Ok(());
+    }
+
+    let updated = scaffold::insert_entry(&content, parents, entry)
+        .map_err(|e| format!("cannot update {}: {e}", path.display()))?;
+    if !dry_run {
+        std::fs::write(&path, updated)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    }
+    let section = parents.join(".");
+    let message = if dry_run {
+        format!("[dry-run] Would register {name} in defaults.yaml ({section})")
+    } else {
+        format!("Registered {name} in defaults.yaml ({section})")
+    };
+    out.info("register", name, Some(&path), message);
+    Ok(())
+}
+
+fn module_name(root: &Path) -> String {
+    std::fs::read_to_string(root.join("module.yaml"))
+        .ok()
+        .and_then(|c| parse::module_name(&c))
+        .unwrap_or_else(|| "this module".to_string())
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<(), String> {
+    match args.kind.as_str() {
+        "module" => {
+            let files = scaffold::plan_module(&args.name)?;
+            let root = args.dir.join(&args.name);
+            write_files(out, &root, &files, args.dry_run)?;
+            if !out.is_json() {
+                eprintln!(
+                    "Next: cd {} && git init && git submodule add https://github.com/N4M3Z/forge-lib.git lib",
+                    root.display()
+                );
+            }
+        }
+        "agent" => {
+            let files = scaffold::plan_agent(&args.name, &module_name(&args.dir))?;
+            write_files(out, &args.dir, &files, args.dry_run)?;
+            let entry = scaffold::agent_roster_entry(&args.name);
+            register_in_defaults(
+                out,
+                &args.dir,
+                &["agents"],
+                &args.name,
+                &entry,
+                args.dry_run,
+            )?;
+        }
+        "skill" => {
+            let files = scaffold::plan_skill(&args.name)?;
+            write_files(out, &args.dir, &files, args.dry_run)?;
+            let entry = scaffold::skill_allowlist_entry(&args.name);
+            register_in_defaults(
+                out,
+                &args.dir,
+                &["skills", "claude"],
+                &args.name,
+                &entry,
+                args.dry_run,
+            )?;
+        }
+        other => {
+            return Err(format!(
+                "unknown kind {other:?}: use module, agent, or skill"
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Run `forge new`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let mut out = Reporter::new(args.json);
+    let result = execute(&args, &mut out);
+    out.finish();
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
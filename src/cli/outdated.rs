@@ -0,0 +1,207 @@
+//! `forge outdated`: list deployed modules whose recorded version is behind
+//! a source checkout or the registry, and optionally redeploy them.
+
+use super::{flag_value, registry, Reporter};
+use crate::outdated::{self, Outdated, Source};
+use crate::pack;
+use crate::registry as index;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge outdated [module-root...] [--registry <url>] [--insecure]
+                      [--scope <user|workspace|all>] [--provider <name>]
+                      [--redeploy] [--dry-run] [--json] [--no-color]";
+
+const PROVIDERS: &[&str] = &[
+    "claude", "gemini", "codex", "opencode", "cursor", "windsurf", "copilot", "zed",
+];
+
+#[allow(clippy::struct_excessive_bools)]
+struct Args {
+    checkouts: Vec<PathBuf>,
+    registry: Option<String>,
+    scope: String,
+    provider: Option<String>,
+    redeploy: bool,
+    dry_run: bool,
+    json: bool,
+    insecure: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        checkouts: Vec::new(),
+        registry: None,
+        scope: "all".to_string(),
+        provider: None,
+        redeploy: false,
+        dry_run: false,
+        json: false,
+        insecure: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--registry" => parsed.registry = Some(flag_value(args, &mut i)?.to_string()),
+            "--scope" => flag_value(args, &mut i)?.clone_into(&mut parsed.scope),
+            "--provider" => parsed.provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--redeploy" => parsed.redeploy = true,
+            "--dry-run" => parsed.dry_run = true,
+            "--json" => parsed.json = true,
+            "--insecure" => parsed.insecure = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.checkouts.push(PathBuf::from(arg)),
+        }
+        i += 1;
+    }
+    if parsed.registry.is_none() {
+        parsed.registry = env::var(index::REGISTRY_ENV).ok().filter(|v| !v.is_empty());
+    }
+    Ok(parsed)
+}
+
+fn available(args: &Args) -> Result<BTreeMap<String, (String, Source)>, String> {
+    let mut available = BTreeMap::new();
+    for root in &args.checkouts {
+        let (name, version) = pack::module_identity(root)?;
+        outdated::offer(
+            &mut available,
+            &name,
+            &version,
+            Source::Checkout(root.clone()),
+        );
+    }
+    if let Some(location) = &args.registry {
+        let index = index::load_index(location, args.insecure)?;
+        for (name, module) in &index.modules {
+            if let Some(latest) = module.latest() {
+                outdated::offer(&mut available, name, latest, Source::Registry);
+            }
+        }
+    }
+    Ok(available)
+}
+
+/// Scope to redeploy into: relative directories are workspace deploys.
+fn redeploy_scope(module: &Outdated) -> &'static str {
+    let user = module.dirs.iter().any(|d| d.is_absolute());
+    let workspace = module.dirs.iter().any(|d| d.is_relative());
+    match (user, workspace) {
+        (true, true) => "all",
+        (false, _) => "workspace",
+        (true, false) => "user",
+    }
+}
+
+fn redeploy(args: &Args, module: &Outdated) -> ExitCode {
+    let mut forward = vec!["--scope".to_string(), redeploy_scope(module).to_string()];
+    if let Some(provider) = &args.provider {
+        forward.extend(["--provider".to_string(), provider.clone()]);
+    }
+    if args.dry_run {
+        forward.push("--dry-run".to_string());
+    }
+    if args.json {
+        forward.push("--json".to_string());
+    }
+    match &module.source {
+        Source::Checkout(root) => registry::deploy(root, &forward),
+        Source::Registry => {
+            let mut install = vec![
+                format!("{}@{}", module.module, module.available),
+                "--registry".to_string(),
+                args.registry.clone().unwrap_or_default(),
+            ];
+            if args.insecure {
+                install.push("--insecure".to_string());
+            }
+            install.extend(forward);
+            registry::install(&install)
+        }
+    }
+}
+
+fn source_label(source: &Source) -> String {
+    match source {
+        Source::Checkout(root) => root.display().to_string(),
+        Source::Registry => "registry".to_string(),
+    }
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<ExitCode, String> {
+    if args.checkouts.is_empty() && args.registry.is_none() {
+        return Err(format!(
+            "nothing to compare against: pass module checkouts, --registry <url>, or set {}",
+            index::REGISTRY_ENV
+        ));
+    }
+    let home = PathBuf::from(env::var("HOME").unwrap_or_default());
+    let providers: Vec<String> = match &args.provider {
+        Some(p) => vec![p.clone()],
+        None => PROVIDERS.iter().map(|p| (*p).to_string()).collect(),
+    };
+    let roots = outdated::provider_roots(&home, &providers, &args.scope)?;
+    let deployed = outdated::scan_deployed(&outdated::artifact_dirs(&roots));
+    let behind = outdated::find_outdated(&deployed, &available(args)?);
+
+    if behind.is_empty() {
+        out.note("All deployed modules are up to date");
+        return Ok(ExitCode::from(4));
+    }
+    let mut code = ExitCode::SUCCESS;
+    for module in &behind {
+        let dirs: Vec<String> = module
+            .dirs
+            .iter()
+            .map(|d| d.display().to_string())
+            .collect();
+        out.info(
+            "outdated",
+            &module.module,
+            Some(Path::new(&source_label(&module.source))),
+            format!(
+                "{} {} -> {} ({})",
+                module.module,
+                module.deployed,
+                module.available,
+                dirs.join(", ")
+            ),
+        );
+        if args.redeploy {
+            let result = redeploy(args, module);
+            if result == ExitCode::from(1) {
+                return Ok(result);
+            }
+            if code == ExitCode::SUCCESS {
+                code = result;
+            }
+        }
+    }
+    Ok(code)
+}
+
+/// Run `forge outdated`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let mut out = Reporter::new(args.json);
+    let result = execute(&args, &mut out);
+    out.finish();
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })
+}
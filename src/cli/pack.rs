@@ -0,0 +1,118 @@
+use super::{flag_value, Reporter};
+use crate::pack;
+use crate::validate;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "Usage: forge pack [module-root] [--out <dir>] [--dry-run] [--json] [--no-color]";
+
+struct Args {
+    root: PathBuf,
+    out_dir: Option<PathBuf>,
+    dry_run: bool,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut root = PathBuf::from(".");
+    let mut out_dir: Option<PathBuf> = None;
+    let mut dry_run = false;
+    let mut json = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--out" => out_dir = Some(PathBuf::from(flag_value(args, &mut i)?)),
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+
+    Ok(Args {
+        root,
+        out_dir,
+        dry_run,
+        json,
+    })
+}
+
+/// Report failed convention checks; true when the module is packable.
+fn check_conventions(out: &mut Reporter, args: &Args) -> bool {
+    let mut ok = true;
+    for suite in validate::validate_all(&args.root, out) {
+        for check in suite.checks.iter().filter(|c| !c.passed) {
+            ok = false;
+            out.warn(
+                "error",
+                &suite.name,
+                None,
+                format!("{}: {}", suite.name, check.desc),
+            );
+        }
+    }
+    ok
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<(), String> {
+    if !args.root.is_dir() {
+        return Err(format!("not a directory: {}", args.root.display()));
+    }
+    if !check_conventions(out, args) {
+        return Err("module failed validation; run `forge validate` for details".to_string());
+    }
+
+    let package = pack::build_package(&args.root)?;
+    let out_dir = args
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| args.root.join("dist"));
+    let archive = pack::write_package(&package, &out_dir, args.dry_run)?;
+
+    let count = package.manifest.files.len();
+    let message = if args.dry_run {
+        format!(
+            "[dry-run] Would pack {count} files into {}",
+            archive.display()
+        )
+    } else {
+        format!("Packed {count} files into {}", archive.display())
+    };
+    out.info("pack", &package.manifest.name, Some(&archive), message);
+    out.info(
+        "checksum",
+        &package.file_name(),
+        Some(&archive),
+        format!("sha256: {}", package.sha256),
+    );
+    Ok(())
+}
+
+/// Run `forge pack`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let mut out = Reporter::new(args.json);
+    let result = execute(&args, &mut out);
+    out.finish();
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
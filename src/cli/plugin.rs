@@ -0,0 +1,102 @@
+//! `forge plugin generate`: refresh `.claude-plugin/plugin.json` and
+//! `marketplace.json` from the module's agents, commands, and skills.
+
+use super::Reporter;
+use crate::plugin;
+use crate::sidecar::SidecarConfig;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge plugin generate [module-root] [--check] [--dry-run] [--json] [--no-color]
+       --check exits 1 when plugin metadata is out of date";
+
+struct Args {
+    module_root: PathBuf,
+    check: bool,
+    dry_run: bool,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        module_root: PathBuf::from("."),
+        check: false,
+        dry_run: false,
+        json: false,
+    };
+    for arg in args {
+        match arg.as_str() {
+            "--check" => parsed.check = true,
+            "--dry-run" => parsed.dry_run = true,
+            "--json" => parsed.json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.module_root = PathBuf::from(arg),
+        }
+    }
+    Ok(parsed)
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<ExitCode, String> {
+    if !args.module_root.is_dir() {
+        return Err(format!("not a directory: {}", args.module_root.display()));
+    }
+    let config = SidecarConfig::load(&args.module_root);
+    let mut stale = false;
+    for (path, content) in plugin::generate(&args.module_root, &config)? {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let dir = path.parent();
+        if !plugin::write_if_changed(&path, &content, args.dry_run || args.check)? {
+            out.note(&format!("Up to date: {}", path.display()));
+        } else if args.check {
+            stale = true;
+            out.warn(
+                "stale",
+                &name,
+                dir,
+                format!(
+                    "{} is out of date with the module (run forge plugin generate)",
+                    path.display()
+                ),
+            );
+        } else {
+            let message = if args.dry_run {
+                format!("[dry-run] Would generate: {}", path.display())
+            } else {
+                format!("Generated: {}", path.display())
+            };
+            out.info("create", &name, dir, message);
+        }
+    }
+    if stale {
+        return Ok(ExitCode::from(super::EXIT_ERROR));
+    }
+    if args.check {
+        return Ok(ExitCode::from(super::EXIT_OK));
+    }
+    Ok(out.exit_code())
+}
+
+/// Run `forge plugin generate`.
+pub fn run(args: &[String]) -> ExitCode {
+    match parse_args(args) {
+        Ok(ref args) => {
+            let mut out = Reporter::new(args.json);
+            let code = execute(args, &mut out).unwrap_or_else(|e| {
+                eprintln!("Error: {e}");
+                ExitCode::from(1)
+            });
+            out.finish();
+            code
+        }
+        Err(code) => code,
+    }
+}
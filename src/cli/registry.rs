@@ -0,0 +1,231 @@
+//! `forge search`, `forge install`, and `forge update`: registry-backed
+//! module installation. Installed archives go through the normal
+//! `install-agents` / `install-skills` pipeline, with every flag not used
+//! here forwarded to it.
+
+use super::{flag_value, install_agents, install_skills};
+use crate::registry::{self, Index, Installed};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const SEARCH_USAGE: &str = "Usage: forge search [query] [--registry <url>] [--insecure] [--json]";
+const INSTALL_USAGE: &str =
+    "Usage: forge install <module>[@<version>] [--registry <url>] [--insecure] [deploy flags...]";
+const UPDATE_USAGE: &str =
+    "Usage: forge update [module...] [--registry <url>] [--insecure] [deploy flags...]";
+
+struct Args {
+    registry: Option<String>,
+    positional: Vec<String>,
+    /// Flags for the deploy pipeline.
+    forward: Vec<String>,
+    json: bool,
+    dry_run: bool,
+    /// Allow plain http registries and archives.
+    insecure: bool,
+}
+
+fn parse_args(args: &[String], usage: &str) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        registry: None,
+        positional: Vec::new(),
+        forward: Vec::new(),
+        json: false,
+        dry_run: false,
+        insecure: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--registry" => parsed.registry = Some(flag_value(args, &mut i)?.to_string()),
+            "--insecure" => parsed.insecure = true,
+            "-h" | "--help" => {
+                println!("{usage}");
+                return Err(ExitCode::SUCCESS);
+            }
+            flag @ ("--scope" | "--provider" | "--config" | "--dst") => {
+                let value = flag_value(args, &mut i)?;
+                parsed.forward.extend([flag.to_string(), value.to_string()]);
+            }
+            "--no-color" => {
+                super::style::disable();
+                parsed.forward.push(args[i].clone());
+            }
+            flag if flag.starts_with('-') => {
+                parsed.json |= flag == "--json";
+                parsed.dry_run |= flag == "--dry-run";
+                parsed.forward.push(flag.to_string());
+            }
+            arg => parsed.positional.push(arg.to_string()),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+fn registry_location(args: &Args) -> Result<String, String> {
+    args.registry
+        .clone()
+        .or_else(|| {
+            env::var(registry::REGISTRY_ENV)
+                .ok()
+                .filter(|v| !v.is_empty())
+        })
+        .ok_or_else(|| {
+            format!(
+                "no registry configured: pass --registry <url> or set {}",
+                registry::REGISTRY_ENV
+            )
+        })
+}
+
+pub(super) fn forge_home() -> PathBuf {
+    match env::var(registry::HOME_ENV) {
+        Ok(home) if !home.is_empty() => PathBuf::from(home),
+        _ => PathBuf::from(env::var("HOME").unwrap_or_default()).join(".forge"),
+    }
+}
+
+/// Run the agents and skills installers over an unpacked module.
+pub(super) fn deploy(root: &Path, forward: &[String]) -> ExitCode {
+    let mut code = ExitCode::SUCCESS;
+    for (dir, run) in [
+        ("agents", install_agents::run as fn(&[String]) -> ExitCode),
+        ("skills", install_skills::run),
+    ] {
+        let src = root.join(dir);
+        if !src.is_dir() {
+            continue;
+        }
+        let mut args = vec![src.display().to_string()];
+        args.extend(forward.iter().cloned());
+        let result = run(&args);
+        if result == ExitCode::from(1) {
+            return result;
+        }
+        if code == ExitCode::SUCCESS {
+            code = result;
+        }
+    }
+    code
+}
+
+fn install_one(
+    args: &Args,
+    location: &str,
+    index: &Index,
+    name: &str,
+    version: Option<&str>,
+) -> Result<ExitCode, String> {
+    let (version, release) = index.resolve(name, version)?;
+    let home = forge_home();
+    let root = registry::install_release(location, name, &version, release, &home, args.insecure)?;
+    if !args.json {
+        eprintln!("Fetched {name}@{version} into {}", root.display());
+    }
+    let code = deploy(&root, &args.forward);
+    if !args.dry_run && code != ExitCode::from(1) {
+        registry::record_installed(&home, name, Installed { version, root })?;
+    }
+    Ok(code)
+}
+
+fn report_error(result: Result<ExitCode, String>) -> ExitCode {
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })
+}
+
+/// Run `forge search`.
+pub fn search(args: &[String]) -> ExitCode {
+    let args = match parse_args(args, SEARCH_USAGE) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let query = args.positional.join(" ");
+    report_error((|| {
+        let index = registry::load_index(&registry_location(&args)?, args.insecure)?;
+        let hits = index.search(&query);
+        if args.json {
+            let results: Vec<_> = hits
+                .iter()
+                .map(|(name, description, latest)| {
+                    serde_json::json!({"name": name, "description": description, "latest": latest})
+                })
+                .collect();
+            let doc = serde_json::json!({ "results": results });
+            println!("{}", serde_json::to_string_pretty(&doc).unwrap_or_default());
+        } else if hits.is_empty() {
+            println!("No modules match {query:?}");
+        } else {
+            for (name, description, latest) in &hits {
+                println!("{name} {}  {description}", latest.unwrap_or("-"));
+            }
+        }
+        Ok(ExitCode::SUCCESS)
+    })())
+}
+
+/// Run `forge install`.
+pub fn install(args: &[String]) -> ExitCode {
+    let args = match parse_args(args, INSTALL_USAGE) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let [spec] = args.positional.as_slice() else {
+        eprintln!("{INSTALL_USAGE}");
+        return ExitCode::from(1);
+    };
+    report_error((|| {
+        let location = registry_location(&args)?;
+        let index = registry::load_index(&location, args.insecure)?;
+        let (name, version) = registry::parse_spec(spec);
+        install_one(&args, &location, &index, name, version)
+    })())
+}
+
+/// Run `forge update`: reinstall installed modules that have a newer
+/// release in the registry.
+pub fn update(args: &[String]) -> ExitCode {
+    let args = match parse_args(args, UPDATE_USAGE) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    report_error((|| {
+        let location = registry_location(&args)?;
+        let index = registry::load_index(&location, args.insecure)?;
+        let installed = registry::read_installed(&forge_home())?;
+        for name in &args.positional {
+            if !installed.contains_key(name) {
+                return Err(format!("{name} is not installed from a registry"));
+            }
+        }
+
+        let mut code = ExitCode::SUCCESS;
+        for (name, current) in &installed {
+            if !args.positional.is_empty() && !args.positional.contains(name) {
+                continue;
+            }
+            let Some(latest) = index.modules.get(name).and_then(|m| m.latest()) else {
+                eprintln!("Warning: {name} is no longer in the registry");
+                continue;
+            };
+            if registry::compare_versions(latest, &current.version).is_le() {
+                if !args.json {
+                    eprintln!("{name} {} is up to date", current.version);
+                }
+                continue;
+            }
+            if !args.json {
+                eprintln!("Updating {name} {} -> {latest}", current.version);
+            }
+            let result = install_one(&args, &location, &index, name, Some(latest))?;
+            if code == ExitCode::SUCCESS {
+                code = result;
+            }
+        }
+        Ok(code)
+    })())
+}
@@ -0,0 +1,171 @@
+//! `--report <path>`: a Markdown (or, for `*.json` paths, JSON) summary of
+//! an install run — what changed per provider, skipped items with their
+//! reasons, and the model each agent was deployed with — for attaching to
+//! change reviews.
+
+use super::{summarize, ModelAssignment, Record};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Section for records not tied to one provider.
+const ALL_PROVIDERS: &str = "all providers";
+
+struct Section<'a> {
+    provider: &'a str,
+    changes: Vec<&'a Record>,
+    skipped: Vec<&'a Record>,
+    all: Vec<&'a Record>,
+}
+
+fn sections(records: &[Record]) -> Vec<Section<'_>> {
+    let mut sections: Vec<Section> = Vec::new();
+    for record in records {
+        let provider = record.provider.as_deref().unwrap_or(ALL_PROVIDERS);
+        let index = sections
+            .iter()
+            .position(|s| s.provider == provider)
+            .unwrap_or_else(|| {
+                sections.push(Section {
+                    provider,
+                    changes: Vec::new(),
+                    skipped: Vec::new(),
+                    all: Vec::new(),
+                });
+                sections.len() - 1
+            });
+        let section = &mut sections[index];
+        section.all.push(record);
+        if record.action.starts_with("skip") {
+            section.skipped.push(record);
+        } else {
+            section.changes.push(record);
+        }
+    }
+    sections
+}
+
+fn summary(records: &[&Record]) -> String {
+    summarize(records.iter().copied())
+}
+
+/// `YYYY-MM-DD HH:MM:SS UTC` for seconds since the Unix epoch.
+pub(super) fn utc_timestamp(secs: u64) -> String {
+    let days = i64::try_from(secs / 86_400).unwrap_or_default();
+    let rem = secs % 86_400;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn markdown(tool: &str, generated: &str, records: &[Record], models: &[ModelAssignment]) -> String {
+    let mut out = format!("# Deployment report: {tool}\n\n");
+    let _ = writeln!(out, "Generated {generated}.\n");
+    let all: Vec<&Record> = records.iter().collect();
+    let _ = writeln!(out, "**Summary:** {}", summary(&all));
+
+    for section in sections(records) {
+        let _ = writeln!(out, "\n## {}\n", section.provider);
+        let _ = writeln!(out, "{}", summary(&section.all));
+        if !section.changes.is_empty() {
+            out.push_str("\n| Action | Item | Target |\n|--------|------|--------|\n");
+            for record in &section.changes {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    record.action,
+                    cell(&record.name),
+                    cell(record.target.as_deref().unwrap_or("")),
+                );
+            }
+        }
+        if !section.skipped.is_empty() {
+            out.push_str("\nSkipped:\n\n");
+            for record in &section.skipped {
+                let _ = writeln!(out, "- `{}`: {}", record.name, record.message);
+            }
+        }
+    }
+
+    if !models.is_empty() {
+        out.push_str("\n## Model assignments\n\n");
+        out.push_str("| Provider | Agent | Model |\n|----------|-------|-------|\n");
+        for m in models {
+            let model = if m.pinned {
+                cell(&m.model)
+            } else {
+                format!("provider default ({} not in whitelist)", cell(&m.model))
+            };
+            let _ = writeln!(out, "| {} | {} | {model} |", m.provider, cell(&m.agent));
+        }
+    }
+    out
+}
+
+fn json(tool: &str, generated: &str, records: &[Record], models: &[ModelAssignment]) -> String {
+    let all: Vec<&Record> = records.iter().collect();
+    let providers: Vec<serde_json::Value> = sections(records)
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "provider": s.provider,
+                "summary": summary(&s.all),
+                "changes": s.changes,
+                "skipped": s.skipped.iter().map(|r| serde_json::json!({
+                    "name": r.name,
+                    "action": r.action,
+                    "reason": r.message,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let doc = serde_json::json!({
+        "tool": tool,
+        "generated": generated,
+        "summary": summary(&all),
+        "providers": providers,
+        "models": models,
+    });
+    serde_json::to_string_pretty(&doc).unwrap_or_default() + "\n"
+}
+
+/// Write the report for a finished run to `path`.
+pub(super) fn write(
+    path: &Path,
+    tool: &str,
+    records: &[Record],
+    models: &[ModelAssignment],
+) -> Result<(), String> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let generated = utc_timestamp(secs);
+    let content = if path.extension().is_some_and(|e| e == "json") {
+        json(tool, &generated, records, models)
+    } else {
+        markdown(tool, &generated, records, models)
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+    }
+    std::fs::write(path, content)
+        .map_err(|e| format!("failed to write report {}: {e}", path.display()))
+}
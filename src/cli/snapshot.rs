@@ -0,0 +1,198 @@
+//! `forge snapshot create|restore|list`, and the automatic snapshot taken
+//! before `--clean` runs.
+
+use super::{flag_value, registry, Reporter};
+use crate::outdated;
+use crate::snapshot;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge snapshot create [--scope user|workspace|all] [--provider <name>] [--label <text>]
+       forge snapshot restore <id> [--dry-run]
+       forge snapshot list
+       Common flags: [--json] [--no-color]";
+
+/// Set to skip the snapshot `--clean` takes, like `--no-snapshot`.
+pub(crate) const NO_SNAPSHOT_ENV: &str = "FORGE_NO_SNAPSHOT";
+
+const PROVIDERS: &[&str] = &[
+    "claude", "gemini", "codex", "opencode", "cursor", "windsurf", "copilot", "zed",
+];
+
+fn snapshots_dir() -> PathBuf {
+    registry::forge_home().join("snapshots")
+}
+
+/// Snapshot `paths` before a `--clean` run removes anything from them.
+/// Skipped for dry runs, with `--no-snapshot`, or when `FORGE_NO_SNAPSHOT`
+/// is set.
+pub(super) fn before_clean(
+    out: &Reporter,
+    tool: &str,
+    paths: &[PathBuf],
+    dry_run: bool,
+    disabled: bool,
+) -> Result<(), String> {
+    if dry_run || disabled || env::var_os(NO_SNAPSHOT_ENV).is_some_and(|v| !v.is_empty()) {
+        return Ok(());
+    }
+    let snap = snapshot::create(&snapshots_dir(), &format!("{tool} clean"), paths, false)
+        .map_err(|e| format!("snapshot before clean failed: {e} (pass --no-snapshot to skip)"))?;
+    out.note(&format!(
+        "Snapshot {} saved; undo with `forge snapshot restore {}`",
+        snap.id, snap.id
+    ));
+    Ok(())
+}
+
+struct Args {
+    action: String,
+    id: Option<String>,
+    scope: String,
+    provider: Option<String>,
+    label: String,
+    dry_run: bool,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut positional = Vec::new();
+    let mut parsed = Args {
+        action: String::new(),
+        id: None,
+        scope: "all".to_string(),
+        provider: None,
+        label: "manual".to_string(),
+        dry_run: false,
+        json: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--scope" => flag_value(args, &mut i)?.clone_into(&mut parsed.scope),
+            "--provider" => parsed.provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--label" => flag_value(args, &mut i)?.clone_into(&mut parsed.label),
+            "--dry-run" => parsed.dry_run = true,
+            "--json" => parsed.json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => positional.push(arg.to_string()),
+        }
+        i += 1;
+    }
+
+    let mut positional = positional.into_iter();
+    match positional.next() {
+        Some(action) if ["create", "restore", "list"].contains(&action.as_str()) => {
+            parsed.action = action;
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            return Err(ExitCode::from(1));
+        }
+    }
+    parsed.id = positional.next();
+    if parsed.action == "restore" && parsed.id.is_none() {
+        eprintln!("Error: snapshot id required.");
+        eprintln!("{USAGE}");
+        return Err(ExitCode::from(1));
+    }
+    Ok(parsed)
+}
+
+fn create(args: &Args, out: &mut Reporter) -> Result<(), String> {
+    let home = PathBuf::from(env::var("HOME").unwrap_or_default());
+    let providers: Vec<String> = match &args.provider {
+        Some(p) => vec![p.clone()],
+        None => PROVIDERS.iter().map(|p| (*p).to_string()).collect(),
+    };
+    let paths: Vec<PathBuf> = outdated::provider_roots(&home, &providers, &args.scope)?
+        .iter()
+        .flat_map(|root| snapshot::PROVIDER_ENTRIES.iter().map(|e| root.join(e)))
+        .filter(|p| p.exists())
+        .collect();
+    if paths.is_empty() {
+        out.note("Nothing to snapshot");
+        return Ok(());
+    }
+
+    let dir = snapshots_dir();
+    let snap = snapshot::create(&dir, &args.label, &paths, args.dry_run)?;
+    let message = if args.dry_run {
+        format!(
+            "[dry-run] Would snapshot {} files from {} paths",
+            snap.files,
+            paths.len()
+        )
+    } else {
+        format!(
+            "Snapshot {}: {} files from {} paths",
+            snap.id,
+            snap.files,
+            paths.len()
+        )
+    };
+    out.info("snapshot", &snap.id, Some(&dir), message);
+    Ok(())
+}
+
+fn restore(args: &Args, out: &mut Reporter) -> Result<(), String> {
+    let id = args.id.as_deref().unwrap_or_default();
+    for path in snapshot::restore(&snapshots_dir(), id, args.dry_run)? {
+        let message = if args.dry_run {
+            format!("[dry-run] Would restore: {}", path.display())
+        } else {
+            format!("Restored: {}", path.display())
+        };
+        out.info("restore", id, Some(Path::new(&path)), message);
+    }
+    Ok(())
+}
+
+fn list(out: &mut Reporter) -> Result<(), String> {
+    let dir = snapshots_dir();
+    let snapshots = snapshot::list(&dir)?;
+    if snapshots.is_empty() {
+        out.note("No snapshots");
+    }
+    for snap in snapshots {
+        out.info(
+            "snapshot",
+            &snap.id,
+            Some(&dir),
+            format!("{}  {} files  {}", snap.id, snap.files, snap.label),
+        );
+    }
+    Ok(())
+}
+
+/// Run `forge snapshot`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let mut out = Reporter::new(args.json);
+    let result = match args.action.as_str() {
+        "create" => create(&args, &mut out),
+        "restore" => restore(&args, &mut out),
+        _ => list(&mut out),
+    };
+    out.finish();
+    match result {
+        Ok(()) => out.exit_code(),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
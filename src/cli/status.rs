@@ -0,0 +1,132 @@
+//! `forge status`: report how the agents deployed from a module's
+//! `agents/` directory have drifted, per provider directory.
+
+use super::install_agents::{read_module_field, target_dirs};
+use super::{flag_value, Reporter};
+use crate::deploy::DeployOptions;
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use crate::status::{self, Drift};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge status <agents-dir> [--scope user|workspace|project|all] [--provider <name>]
+                    [--dst <path>] [--config <file>]... [--json] [--no-color]
+
+Reports each agent as current, outdated (the source changed), modified (edited
+after deploy), missing, user-owned, or orphaned (no longer in the source).
+Exits 0 when every agent is current and 2 when anything has drifted.";
+
+struct Args {
+    src_dir: PathBuf,
+    scope: String,
+    provider: Option<String>,
+    dst: Option<String>,
+    config: Vec<PathBuf>,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut src_dir = None;
+    let mut parsed = Args {
+        src_dir: PathBuf::new(),
+        scope: "all".to_string(),
+        provider: None,
+        dst: None,
+        config: Vec::new(),
+        json: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--scope" => flag_value(args, &mut i)?.clone_into(&mut parsed.scope),
+            "--provider" => parsed.provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--dst" => parsed.dst = Some(flag_value(args, &mut i)?.to_string()),
+            "--config" => parsed.config.push(PathBuf::from(flag_value(args, &mut i)?)),
+            "--json" => parsed.json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => src_dir = Some(PathBuf::from(arg)),
+        }
+        i += 1;
+    }
+    let Some(src_dir) = src_dir else {
+        eprintln!("Error: agents directory required.");
+        eprintln!("{USAGE}");
+        return Err(ExitCode::from(1));
+    };
+    parsed.src_dir = src_dir;
+    Ok(parsed)
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<ExitCode, String> {
+    let src_dir = args.src_dir.as_path();
+    if !src_dir.is_dir() {
+        return Err(format!("not a directory: {}", src_dir.display()));
+    }
+    let module_name = read_module_field(src_dir, parse::module_name).unwrap_or_default();
+    let source_prefix = if module_name.is_empty() {
+        String::new()
+    } else {
+        format!("{module_name}/{}", src_dir.display())
+    };
+    let module_root = src_dir.parent().unwrap_or(Path::new("."));
+    let config = SidecarConfig::load(module_root).with_overrides(&args.config)?;
+    let dirs = target_dirs(
+        &args.scope,
+        args.provider.as_deref(),
+        args.dst.as_deref(),
+        &config,
+    )?;
+
+    let mut drifted = false;
+    for (i, (dst_dir, provider)) in dirs.iter().enumerate() {
+        out.begin_group(format!(
+            "[{}/{}] {} {}",
+            i + 1,
+            dirs.len(),
+            provider.as_str(),
+            dst_dir.display()
+        ));
+        out.set_provider(provider.as_str());
+        out.note(&format!("{}: {}", provider.as_str(), dst_dir.display()));
+        let options = DeployOptions::new(provider.clone()).with_source_prefix(&source_prefix);
+        let statuses = status::scan(src_dir, dst_dir, &module_name, &config, &options)
+            .map_err(|e| e.to_string())?;
+        for agent in statuses {
+            drifted |= agent.drift != Drift::Current;
+            let drift = agent.drift.as_str();
+            out.info(
+                drift,
+                &agent.name,
+                Some(&agent.path),
+                format!("  {drift:<10} {}", agent.name),
+            );
+        }
+        out.end_group();
+    }
+    Ok(ExitCode::from(if drifted { 2 } else { 0 }))
+}
+
+/// Run `forge status`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let mut out = Reporter::new(args.json);
+    let result = execute(&args, &mut out);
+    out.finish();
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })
+}
@@ -0,0 +1,73 @@
+//! ANSI colors for human-mode output. Color is used only when the stream is
+//! a terminal, `NO_COLOR` is unset or empty, and `--no-color` was not given.
+
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy)]
+pub(crate) enum Color {
+    Green,
+    Red,
+    Yellow,
+    Cyan,
+    Dim,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Green => "32",
+            Self::Red => "31",
+            Self::Yellow => "33",
+            Self::Cyan => "36",
+            Self::Dim => "2",
+        }
+    }
+}
+
+/// Turn color off for the rest of the process (`--no-color`).
+pub(crate) fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+fn enabled(is_terminal: bool) -> bool {
+    is_terminal
+        && !DISABLED.load(Ordering::Relaxed)
+        && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+pub(crate) fn paint(text: &str, color: Color, on: bool) -> String {
+    if on {
+        format!("\x1b[{}m{text}\x1b[0m", color.code())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Color the label of a `Label: rest` line, or the whole line if it has none.
+/// A leading `[dry-run] ` marker is dimmed separately.
+pub(crate) fn paint_label(line: &str, color: Color, on: bool) -> String {
+    if let Some(rest) = line.strip_prefix("[dry-run] ") {
+        return format!(
+            "{} {}",
+            paint("[dry-run]", Color::Dim, on),
+            paint_label(rest, color, on)
+        );
+    }
+    match line.split_once(": ") {
+        Some((label, rest)) => format!("{}: {rest}", paint(label, color, on)),
+        None => paint(line, color, on),
+    }
+}
+
+/// Whether stdout output should be colored.
+pub(crate) fn stdout() -> bool {
+    enabled(io::stdout().is_terminal())
+}
+
+/// Whether stderr output should be colored.
+pub(crate) fn stderr() -> bool {
+    enabled(io::stderr().is_terminal())
+}
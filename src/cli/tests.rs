@@ -0,0 +1,234 @@
+use super::*;
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(ToString::to_string).collect()
+}
+
+// ─── split_global_flags ───
+
+#[test]
+fn globals_none() {
+    let args = strings(&["agents", "install", "agents"]);
+    let (globals, rest) = split_global_flags(&args).unwrap();
+    assert!(globals.is_empty());
+    assert_eq!(rest, &args[..]);
+}
+
+#[test]
+fn globals_with_values_and_switches() {
+    let args = strings(&[
+        "--scope",
+        "user",
+        "--dry-run",
+        "--json",
+        "agents",
+        "install",
+    ]);
+    let (globals, rest) = split_global_flags(&args).unwrap();
+    assert_eq!(
+        globals,
+        strings(&["--scope", "user", "--dry-run", "--json"])
+    );
+    assert_eq!(rest, &strings(&["agents", "install"])[..]);
+}
+
+#[test]
+fn globals_stop_at_subcommand() {
+    let args = strings(&["validate", "--json"]);
+    let (globals, rest) = split_global_flags(&args).unwrap();
+    assert!(globals.is_empty());
+    assert_eq!(rest.len(), 2);
+}
+
+#[test]
+fn globals_missing_value() {
+    let args = strings(&["--provider"]);
+    assert!(split_global_flags(&args).is_err());
+}
+
+// ─── expect_action ───
+
+#[test]
+fn action_matches() {
+    let rest = strings(&["install", "agents"]);
+    assert_eq!(
+        expect_action("agents", "install", &rest).unwrap(),
+        &rest[1..]
+    );
+}
+
+#[test]
+fn action_unknown() {
+    let rest = strings(&["remove"]);
+    let err = expect_action("agents", "install", &rest).unwrap_err();
+    assert!(err.contains("agents remove"));
+}
+
+#[test]
+fn action_missing() {
+    assert!(expect_action("skills", "install", &[]).is_err());
+}
+
+// ─── confirmation ───
+
+#[test]
+fn yes_answers() {
+    assert!(is_yes("y\n"));
+    assert!(is_yes(" YES \n"));
+    assert!(!is_yes("\n"));
+    assert!(!is_yes("no\n"));
+    assert!(!is_yes("yep\n"));
+}
+
+#[test]
+fn choices_by_name_or_first_letter() {
+    let choices = ["overwrite", "skip", "diff", "rename"];
+    assert_eq!(parse_choice("o\n", &choices), Some("overwrite"));
+    assert_eq!(parse_choice(" Rename \n", &choices), Some("rename"));
+    assert_eq!(parse_choice("ov\n", &choices), None);
+    assert_eq!(parse_choice("\n", &choices), None);
+    assert_eq!(parse_choice("x\n", &choices), None);
+}
+
+#[test]
+fn destructive_lists_only_deleting_actions() {
+    let mut out = Reporter::silent();
+    out.info("install", "Keep", Some(Path::new("dst")), String::new());
+    out.info("remove", "Gone", Some(Path::new("dst")), String::new());
+    out.info("replace", "Skill", None, String::new());
+    assert_eq!(
+        out.destructive(),
+        vec!["remove Gone in dst", "replace Skill"]
+    );
+}
+
+// ─── exit codes ───
+
+#[test]
+fn exit_code_precedence() {
+    let mut out = Reporter::silent().with_detailed_exit_codes(true);
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_NOTHING));
+    out.info("update-config", "config.toml", None, String::new());
+    out.info("unchanged", "Cached", None, String::new());
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_NOTHING));
+    out.info("install", "A", None, String::new());
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_OK));
+    out.warn("skip-user-owned", "B", None, String::new());
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_SKIPPED));
+    out.warn("error", "", None, String::new());
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_PARTIAL));
+}
+
+#[test]
+fn skips_and_no_ops_exit_0_by_default() {
+    let mut out = Reporter::silent();
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_OK));
+    out.warn("skip-user-owned", "B", None, String::new());
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_OK));
+    out.warn("error", "", None, String::new());
+    assert_eq!(out.exit_code(), ExitCode::from(EXIT_PARTIAL));
+}
+
+// ─── style ───
+
+#[test]
+fn paint_off_is_plain() {
+    assert_eq!(style::paint("PASS", Color::Green, false), "PASS");
+    assert_eq!(
+        style::paint_label("[dry-run] Would install: X", Color::Green, false),
+        "[dry-run] Would install: X"
+    );
+}
+
+#[test]
+fn paint_label_colors_only_the_label() {
+    assert_eq!(
+        style::paint_label("Installed: X.md to dst", Color::Green, true),
+        "\x1b[32mInstalled\x1b[0m: X.md to dst"
+    );
+    assert_eq!(
+        style::paint_label("[dry-run] Would remove: X.md", Color::Red, true),
+        "\x1b[2m[dry-run]\x1b[0m \x1b[31mWould remove\x1b[0m: X.md"
+    );
+}
+
+// ─── progress ───
+
+#[test]
+fn summarize_counts_by_outcome() {
+    let mut out = Reporter::silent();
+    assert_eq!(summarize(&out.records), "nothing to do");
+    out.info("install", "A", None, String::new());
+    out.info("install", "B", None, String::new());
+    out.info("remove-orphan", "C", None, String::new());
+    out.warn("skip-user-owned", "D", None, String::new());
+    assert_eq!(summarize(&out.records), "2 installed, 1 removed, 1 skipped");
+}
+
+// ─── report ───
+
+fn reported() -> Reporter {
+    let mut out = Reporter::silent();
+    out.set_provider("claude");
+    out.info(
+        "install",
+        "Critic",
+        Some(Path::new(".claude/agents")),
+        String::new(),
+    );
+    out.model("Critic", "opus", true);
+    out.set_provider("gemini");
+    out.warn(
+        "skip-user-owned",
+        "tester",
+        None,
+        "user-created agent (no source field)".into(),
+    );
+    out.model("critic", "gemini-2.5-pro", false);
+    out
+}
+
+#[test]
+fn report_markdown_groups_by_provider() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("reports/deploy.md");
+    let out = reported();
+    report::write(&path, "install-agents", &out.records, &out.models).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("# Deployment report: install-agents\n"));
+    assert!(text.contains("**Summary:** 1 installed, 1 skipped\n"));
+    assert!(text.contains("## claude\n\n1 installed\n\n| Action | Item | Target |"));
+    assert!(text.contains("| install | Critic | .claude/agents |\n"));
+    assert!(text.contains("## gemini\n\n1 skipped\n\nSkipped:\n\n- `tester`: user-created agent"));
+    assert!(text.contains("| claude | Critic | opus |\n"));
+    assert!(
+        text.contains("| gemini | critic | provider default (gemini-2.5-pro not in whitelist) |\n")
+    );
+}
+
+#[test]
+fn report_json_for_json_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("deploy.json");
+    let out = reported();
+    report::write(&path, "install-agents", &out.records, &out.models).unwrap();
+    let doc: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(doc["tool"], "install-agents");
+    assert_eq!(doc["providers"][0]["provider"], "claude");
+    assert_eq!(doc["providers"][0]["changes"][0]["name"], "Critic");
+    assert_eq!(
+        doc["providers"][1]["skipped"][0]["reason"],
+        "user-created agent (no source field)"
+    );
+    assert_eq!(doc["models"][1]["pinned"], false);
+}
+
+#[test]
+fn report_timestamps_are_utc_dates() {
+    assert_eq!(report::utc_timestamp(0), "1970-01-01 00:00:00 UTC");
+    assert_eq!(
+        report::utc_timestamp(1_709_210_096),
+        "2024-02-29 12:34:56 UTC"
+    );
+}
@@ -0,0 +1,150 @@
+use super::style::{self, Color};
+use crate::progress::NoProgress;
+use crate::validate;
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn print_suite(suite: &validate::Suite) {
+    let on = style::stdout();
+    println!("\n=== {} ===", suite.name);
+    for check in &suite.checks {
+        if check.passed {
+            println!(
+                "  {}: {}",
+                style::paint("PASS", Color::Green, on),
+                check.desc
+            );
+        } else {
+            println!("  {}: {}", style::paint("FAIL", Color::Red, on), check.desc);
+        }
+    }
+    println!();
+    println!("--- {} ---", suite.name);
+    println!("  Passed: {}", suite.passed());
+    let failed = suite.failed().to_string();
+    let failed_color = if suite.failed() > 0 {
+        Color::Red
+    } else {
+        Color::Green
+    };
+    println!("  Failed: {}", style::paint(&failed, failed_color, on));
+    let failures: Vec<_> = suite
+        .checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| &c.desc)
+        .collect();
+    if !failures.is_empty() {
+        println!("  Failures:");
+        for f in &failures {
+            println!("    - {f}");
+        }
+    }
+    println!();
+}
+
+fn print_warnings(warnings: &validate::Suite) {
+    if warnings.checks.is_empty() {
+        return;
+    }
+    let on = style::stdout();
+    println!("\n=== {} ===", warnings.name);
+    for check in &warnings.checks {
+        if check.passed {
+            println!(
+                "  {}:   {}",
+                style::paint("OK", Color::Green, on),
+                check.desc
+            );
+        } else {
+            println!(
+                "  {}: {}",
+                style::paint("WARN", Color::Yellow, on),
+                check.desc
+            );
+        }
+    }
+    if warnings.failed() > 0 {
+        println!(
+            "\n  ({} warnings — not counted as failures)",
+            warnings.failed()
+        );
+    }
+    println!();
+}
+
+fn suite_json(suite: &validate::Suite) -> serde_json::Value {
+    serde_json::json!({
+        "name": suite.name,
+        "passed": suite.passed(),
+        "failed": suite.failed(),
+        "checks": suite.checks,
+    })
+}
+
+fn print_help() {
+    eprintln!("Usage: validate-module [module-root] [--json] [--no-color]");
+    eprintln!();
+    eprintln!(
+        "Validates forge module structure, agents, defaults, skills, deploy parity, and DCI."
+    );
+    eprintln!("Defaults to current directory if no module-root is specified.");
+}
+
+/// Run `validate-module` (also `forge validate`).
+pub fn run(args: &[String]) -> ExitCode {
+    let mut root: Option<PathBuf> = None;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--version" => {
+                println!("validate-module {}", env!("CARGO_PKG_VERSION"));
+                return ExitCode::SUCCESS;
+            }
+            "-h" | "--help" => {
+                print_help();
+                return ExitCode::SUCCESS;
+            }
+            "--json" => json = true,
+            "--no-color" => super::style::disable(),
+            flag if flag.starts_with('-') => {
+                eprintln!("Error: unknown flag {flag}");
+                return ExitCode::from(1);
+            }
+            path => root = Some(PathBuf::from(path)),
+        }
+    }
+
+    let root = root.unwrap_or_else(|| env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+
+    if !root.is_dir() {
+        eprintln!("Error: not a directory: {}", root.display());
+        return ExitCode::from(1);
+    }
+
+    let suites = validate::validate_all(&root, &mut NoProgress);
+    let warnings = validate::warn_skill_content(&root);
+    let total_fail: usize = suites.iter().map(validate::Suite::failed).sum();
+
+    if json {
+        let doc = serde_json::json!({
+            "suites": suites.iter().map(suite_json).collect::<Vec<_>>(),
+            "warnings": suite_json(&warnings),
+            "failed": total_fail,
+        });
+        println!("{}", serde_json::to_string_pretty(&doc).unwrap_or_default());
+    } else {
+        for suite in &suites {
+            print_suite(suite);
+        }
+        print_warnings(&warnings);
+    }
+
+    if total_fail > 0 {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,101 @@
+//! `forge verify`: check deployed files against the checksums recorded in
+//! each destination's manifest, without needing the module sources.
+
+use super::install_agents::target_dirs;
+use super::{flag_value, Reporter};
+use crate::manifest::{self, Integrity};
+use crate::sidecar::SidecarConfig;
+use crate::vfs::RealFs;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: forge verify [--scope user|workspace|project|all] [--provider <name>] [--dst <path>]
+                    [--json] [--no-color]
+
+Reports each file recorded in the manifest as unchanged, modified (edited after
+deploy), or missing. Exits 0 when every file is unchanged and 2 otherwise.";
+
+struct Args {
+    scope: String,
+    provider: Option<String>,
+    dst: Option<String>,
+    json: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        scope: "all".to_string(),
+        provider: None,
+        dst: None,
+        json: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--scope" => flag_value(args, &mut i)?.clone_into(&mut parsed.scope),
+            "--provider" => parsed.provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--dst" => parsed.dst = Some(flag_value(args, &mut i)?.to_string()),
+            "--json" => parsed.json = true,
+            "--no-color" => super::style::disable(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg => {
+                eprintln!("Error: unexpected argument {arg}");
+                eprintln!("{USAGE}");
+                return Err(ExitCode::from(1));
+            }
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+fn execute(args: &Args, out: &mut Reporter) -> Result<ExitCode, String> {
+    let dirs = target_dirs(
+        &args.scope,
+        args.provider.as_deref(),
+        args.dst.as_deref(),
+        &SidecarConfig::default(),
+    )?;
+
+    let mut changed = false;
+    for (i, (dst_dir, provider)) in dirs.iter().enumerate() {
+        out.begin_group(format!(
+            "[{}/{}] {} {}",
+            i + 1,
+            dirs.len(),
+            provider.as_str(),
+            dst_dir.display()
+        ));
+        out.set_provider(provider.as_str());
+        for verified in manifest::verify(&RealFs, dst_dir) {
+            changed |= verified.integrity != Integrity::Unchanged;
+            let integrity = verified.integrity.as_str();
+            out.info(
+                integrity,
+                &verified.file,
+                Some(&dst_dir.join(&verified.file)),
+                format!("  {integrity:<10} {} ({})", verified.file, verified.module),
+            );
+        }
+        out.end_group();
+    }
+    Ok(ExitCode::from(if changed { 2 } else { 0 }))
+}
+
+/// Run `forge verify`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    let mut out = Reporter::new(args.json);
+    let result = execute(&args, &mut out);
+    out.finish();
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        ExitCode::from(1)
+    })
+}
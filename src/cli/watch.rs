@@ -0,0 +1,184 @@
+//! `forge watch`: poll a module's sources and rerun the installers whose
+//! inputs changed, so edits land in the provider directories without
+//! manual reruns.
+
+use super::{
+    flag_value, install_agents, install_commands, install_hooks, install_mcp, install_memory,
+    install_skills, install_styles,
+};
+use crate::sidecar::SidecarConfig;
+use crate::watch::{self, Change, ChangeKind, Target};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+const USAGE: &str = "Usage: forge watch [module-root] [--interval <ms>] [--scope <scope>] \
+                     [--provider <name>] [--config <file>]... [--dry-run] [--no-color]";
+
+const DEFAULT_INTERVAL_MS: u64 = 500;
+
+struct Args {
+    root: PathBuf,
+    interval: Duration,
+    provider: Option<String>,
+    config: Vec<PathBuf>,
+    /// Flags passed through to every installer.
+    forward: Vec<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, ExitCode> {
+    let mut parsed = Args {
+        root: PathBuf::from("."),
+        interval: Duration::from_millis(DEFAULT_INTERVAL_MS),
+        provider: None,
+        config: Vec::new(),
+        forward: Vec::new(),
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--interval" => {
+                let value = flag_value(args, &mut i)?;
+                let Ok(ms) = value.parse::<u64>() else {
+                    eprintln!("Error: --interval takes milliseconds, got {value:?}");
+                    return Err(ExitCode::from(1));
+                };
+                parsed.interval = Duration::from_millis(ms.max(50));
+            }
+            "--scope" => {
+                let value = flag_value(args, &mut i)?;
+                parsed
+                    .forward
+                    .extend(["--scope".to_string(), value.to_string()]);
+            }
+            "--provider" => parsed.provider = Some(flag_value(args, &mut i)?.to_string()),
+            "--config" => {
+                let value = flag_value(args, &mut i)?;
+                parsed.config.push(PathBuf::from(value));
+                parsed
+                    .forward
+                    .extend(["--config".to_string(), value.to_string()]);
+            }
+            "--dry-run" => parsed.forward.push("--dry-run".to_string()),
+            "--no-color" => {
+                super::style::disable();
+                parsed.forward.push("--no-color".to_string());
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Error: unknown flag {arg}");
+                return Err(ExitCode::from(1));
+            }
+            arg => parsed.root = PathBuf::from(arg),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+fn describe(change: &Change) -> String {
+    let kind = match change.kind {
+        ChangeKind::Added => "added",
+        ChangeKind::Modified => "modified",
+        ChangeKind::Removed => "removed",
+    };
+    format!("{} ({kind})", change.path)
+}
+
+/// Rerun one installer over the module. Skills install per provider, since
+/// `install-skills` routes for a single provider at a time.
+fn redeploy(args: &Args, target: Target) -> Result<(), String> {
+    let source = watch::source_arg(&args.root, target).display().to_string();
+    let mut base = vec![source];
+    base.extend(args.forward.iter().cloned());
+    // Removals are expected while authoring; never stop to prompt.
+    base.push("--yes".to_string());
+
+    let providers: Vec<Option<String>> = if target == Target::Skills {
+        match &args.provider {
+            Some(p) => vec![Some(p.clone())],
+            None => SidecarConfig::load(&args.root)
+                .with_overrides(&args.config)?
+                .providers()
+                .into_iter()
+                .map(Some)
+                .collect(),
+        }
+    } else {
+        vec![args.provider.clone()]
+    };
+
+    let run = match target {
+        Target::Agents => install_agents::run as fn(&[String]) -> ExitCode,
+        Target::Skills => install_skills::run,
+        Target::Commands => install_commands::run,
+        Target::Hooks => install_hooks::run,
+        Target::Mcp => install_mcp::run,
+        Target::Memory => install_memory::run,
+        Target::Styles => install_styles::run,
+    };
+    for provider in providers {
+        let mut call = base.clone();
+        if let Some(provider) = provider {
+            call.extend(["--provider".to_string(), provider]);
+        }
+        if run(&call) == ExitCode::from(1) {
+            return Err(format!("{} install failed", target.as_str()));
+        }
+    }
+    Ok(())
+}
+
+fn watch_loop(args: &Args) -> ! {
+    let mut last = watch::scan(&args.root);
+    eprintln!(
+        "Watching {} every {} ms (Ctrl-C to stop)",
+        args.root.display(),
+        args.interval.as_millis()
+    );
+    loop {
+        thread::sleep(args.interval);
+        if watch::diff(&last, &watch::scan(&args.root)).is_empty() {
+            continue;
+        }
+        // Let an editor finish writing (save-as-rename, formatters) before
+        // deploying, then diff against the settled tree.
+        thread::sleep(args.interval);
+        let current = watch::scan(&args.root);
+        let changes = watch::diff(&last, &current);
+        last = current;
+
+        for change in &changes {
+            println!("Changed: {}", describe(change));
+        }
+        for target in watch::affected_targets(&changes) {
+            if !watch::has_sources(&args.root, target, &changes) {
+                continue;
+            }
+            println!("Redeploying {}", target.as_str());
+            if let Err(e) = redeploy(args, target) {
+                eprintln!("Error: {e}");
+            }
+        }
+    }
+}
+
+/// Run `forge watch`.
+pub fn run(args: &[String]) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+    if !Path::new(&args.root).join("module.yaml").is_file() {
+        eprintln!(
+            "Error: {} is not a module root (no module.yaml)",
+            args.root.display()
+        );
+        return ExitCode::from(1);
+    }
+    watch_loop(&args)
+}
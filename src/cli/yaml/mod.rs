@@ -0,0 +1,1676 @@
+//! YAML CLI — query arbitrary YAML files from shell scripts.
+//!
+//! Dot-path notation for nested access:
+//!   yaml value  <file> <path> [default]    # scalar extraction
+//!   yaml get    <file> <path>... [default] # several paths: one line each
+//!   yaml list   <file> <path>              # array → one item per line; slices too
+//!   yaml map    <file> <path>              # mapping → key\tvalue per line
+//!   yaml keys   <file> <path>              # mapping → keys only
+//!   yaml count  <file> <path> [--where k=v] # number of items or entries
+//!   yaml nested <file> <parent> <child> [default]  # legacy (use value with dot-path)
+//!   yaml exists <file> <path>              # exit 0 if the path resolves, else 1
+//!   yaml paths  <file>                     # every leaf as path\tvalue
+//!   yaml set    <file> <path> <value>      # write a string back (--yaml: a typed value)
+//!   yaml delete <file> <path>              # remove a key or array item
+//!   yaml sort-keys <file> [path]           # sort mapping keys, recursively
+//!   yaml merge  <base> <overlay>...        # deep-merge, print the result
+//!   yaml env    <file> <path> [--prefix P] # mapping → `export KEY='value'` lines
+//!   yaml envsubst <file> [path]            # expand ${VAR} in string values
+//!   yaml diff   <old> <new>                # changed paths; exit 1 if any
+//!   yaml validate <file> <schema.json>     # JSON Schema violations; exit 1 if any
+//!   yaml convert <file> --to json|toml|yaml # print the document in another format (reads .toml too)
+//!
+//! A <file> of `-` reads stdin (`cat config.yaml | yaml get - .agents`);
+//! `set`, `delete`, and `sort-keys` then print the edited document instead
+//! of writing.
+//!
+//! `set`, `delete`, and `sort-keys` write the file in place; `-i` /
+//! `--in-place` says so explicitly, and with a suffix (`-i.bak`,
+//! `--in-place=.bak`) the original is first kept as `<file>.bak`.
+//!
+//! Files with several `---` separated documents use the first; `--doc N`
+//! (zero-based) picks another and `--all-docs` runs the command on each in
+//! turn. `set`, `delete`, and `sort-keys` write every document back,
+//! editing the text in place so comments, blank lines, and quoting are
+//! kept; when an edit cannot be made that way (anchors, tags, block
+//! scalars, wildcard paths) they fail and leave the file alone, unless
+//! `--reformat` allows re-serializing the whole file. `--shell-quote`
+//! prints values (and keys) single-quoted, so `eval "x=$(yaml value ...)"`
+//! survives spaces, quotes, and newlines. Values are otherwise trimmed;
+//! `--raw` prints multi-line strings exactly as stored, and
+//! `--escape-newlines` prints each on one line with `\n` escapes for
+//! line-oriented consumers.
+//!
+//! `get` and `value` take several paths (`yaml get f .name .version`) and
+//! then print one line per path in order — lists of scalars space-joined,
+//! a missing path as the default or an empty line — or, with `--tab`, one
+//! tab-separated line, ready for `read`. A <file> holding `*` or `?`
+//! (`yaml value 'Modules/*/module.yaml' .name`) queries every matching file
+//! and prints a `file<TAB>value` row for each.
+//!
+//! `map` separates keys from values with a tab, or with `--sep SEP`
+//! (`--sep =` for `key=value` lines). `list` and `map` take
+//! `--format TEMPLATE` to shape each row: `{value}` and `{index}` for list
+//! items, `{key}` and `{value}` for mapping entries, with `\t` and `\n`
+//! read as tab and newline (`yaml map f .env --format '{key}: {value}'`).
+//!
+//! `list`, `map`, and `keys` take `--default LINE`, printed when the path
+//! is missing (an empty collection still prints nothing), so scripts can
+//! tell the two apart.
+//!
+//! Queries expand aliases (`*base`) and apply `<<: *base` merge keys
+//! (`--resolve-aliases`, the default); `--keep-aliases` prints each alias
+//! as the string `*base` instead. `set`, `delete`, and `merge` always work
+//! on the document as written.
+//!
+//! Queries print nothing for a missing or invalid file or a path that
+//! matches nothing. With `--strict` they report it on stderr instead: a file
+//! that cannot be read or parsed exits 2, a path that matches nothing (and
+//! has no default) or holds the wrong kind of node exits 1.
+//!
+//! Path examples:
+//!   .agents                    → top-level key
+//!   .skills.claude             → nested key
+//!   .skills.claude.DebateCouncil.scope → deep nesting
+//!   .modules[0]                → array index
+//!   .modules[0].name           → array index + nested key
+//!   .agents.*.model            → wildcard: one match per agent
+//!   .modules[*].name           → wildcard over an array
+//!   ..scope                    → every `scope` key at any depth
+//!   .roster[-1]                → last array item
+//!   .roster[1:3], .roster[:2]  → array slices (end excluded)
+//!   .modules[?enabled=true].name → filter items by a key (also `!=`)
+//!   `.["claude.name"]`         → quoted key, dots and brackets literal
+//!   agents                     → leading dot is optional
+
+use crate::query::{
+    delete, delete_in_source, diff, leaves, parse_path, quote_aliases, set, set_in_source,
+    sort_keys, sort_keys_in_source, split_documents, walk_all, Difference, PathSegment,
+};
+use crate::sidecar::merge_values;
+use crate::toml_fmt;
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitCode};
+
+#[cfg(test)]
+mod tests;
+
+// --- Helpers ---
+
+/// The contents of `path`, or of stdin when `path` is `-`.
+fn read_source(path: &str) -> std::io::Result<String> {
+    if path == "-" {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Which documents of a multi-document (`---` separated) file to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Docs {
+    /// One document by zero-based index (`--doc N`; the first by default).
+    Index(usize),
+    /// Every document in turn (`--all-docs`).
+    All,
+}
+
+impl Default for Docs {
+    fn default() -> Self {
+        Self::Index(0)
+    }
+}
+
+impl Docs {
+    fn select(self, mut all: Vec<Value>) -> Vec<Value> {
+        match self {
+            Self::All => all,
+            Self::Index(i) if i < all.len() => vec![all.swap_remove(i)],
+            Self::Index(_) => Vec::new(),
+        }
+    }
+}
+
+/// How queries read aliases (`*name`) to anchored nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Aliases {
+    /// Expand each alias to the node it names and apply `<<: *base` merge
+    /// keys (`--resolve-aliases`, the default).
+    #[default]
+    Resolve,
+    /// Read each alias as the string `*name` and leave `<<` keys as they
+    /// are (`--keep-aliases`).
+    Keep,
+}
+
+/// How values (and keys) are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Style {
+    /// Trimmed, with surrounding quotes dropped.
+    #[default]
+    Plain,
+    /// Single-quoted for `eval`, however the value is spelled
+    /// (`--shell-quote`).
+    ShellQuote,
+    /// Exactly as stored, so block scalars keep every line and the trailing
+    /// newline (`--raw`).
+    Raw,
+    /// Trimmed, with newlines, tabs, and backslashes escaped as `\n`, `\t`,
+    /// and `\\`, so every value fits on one line (`--escape-newlines`).
+    EscapeNewlines,
+}
+
+/// Options accepted by every command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Options {
+    docs: Docs,
+    style: Style,
+    aliases: Aliases,
+    /// `--tab`: print a batch query's results on one tab-separated line.
+    tab: bool,
+    /// `--strict`: a missing or invalid file exits 2 and a path that
+    /// matches nothing exits 1, instead of printing nothing.
+    strict: bool,
+}
+
+/// Split `--doc N`, `--all-docs`, `--shell-quote`, `--raw`,
+/// `--escape-newlines`, `--resolve-aliases`, `--keep-aliases`, `--strict`,
+/// and `--tab` out of `args`, wherever they appear.
+fn split_flags(args: &[String]) -> Result<(Options, Vec<String>), String> {
+    let mut opts = Options::default();
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--all-docs" => opts.docs = Docs::All,
+            "--shell-quote" => opts.style = Style::ShellQuote,
+            "--raw" => opts.style = Style::Raw,
+            "--escape-newlines" => opts.style = Style::EscapeNewlines,
+            "--resolve-aliases" => opts.aliases = Aliases::Resolve,
+            "--keep-aliases" => opts.aliases = Aliases::Keep,
+            "--strict" => opts.strict = true,
+            "--tab" => opts.tab = true,
+            "--doc" => {
+                let n = iter
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--doc needs a document number (0 is the first)")?;
+                opts.docs = Docs::Index(n);
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((opts, rest))
+}
+
+/// Every document in `content`; none for an empty file.
+fn parse_documents(content: &str) -> Result<Vec<Value>, serde_yaml::Error> {
+    serde_yaml::Deserializer::from_str(content)
+        .map(Value::deserialize)
+        .collect()
+}
+
+/// Every document in `content` as queries see it, with aliases read as
+/// `aliases` says. Edits and merges use [`parse_documents`], which neither
+/// applies merge keys nor hides aliases.
+fn parse_query(content: &str, aliases: Aliases) -> Result<Vec<Value>, serde_yaml::Error> {
+    match aliases {
+        Aliases::Resolve => parse_documents(content)?
+            .into_iter()
+            .map(|mut doc| doc.apply_merge().map(|()| doc))
+            .collect(),
+        Aliases::Keep => parse_documents(&quote_aliases(content)),
+    }
+}
+
+/// The selected documents of `path`; an error when the file is missing or
+/// invalid, or has documents but not the one asked for.
+fn try_load(path: &str, opts: Options) -> Result<Vec<Value>, String> {
+    let content = read_source(path).map_err(|e| format!("{path}: {e}"))?;
+    let all = parse_query(&content, opts.aliases).map_err(|e| format!("{path}: {e}"))?;
+    let count = all.len();
+    match opts.docs.select(all) {
+        selected if selected.is_empty() && count > 0 => {
+            Err(format!("{path}: no such document ({count} in file)"))
+        }
+        selected => Ok(selected),
+    }
+}
+
+/// The documents `try_load` returned, or none after an error, which
+/// `--strict` reports before exiting 2.
+fn loaded(result: Result<Vec<Value>, String>, opts: Options) -> Vec<Value> {
+    match result {
+        Ok(all) => all,
+        Err(e) if opts.strict => {
+            eprintln!("yaml: {e}");
+            process::exit(2);
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+/// The selected documents of `path`, or one empty mapping when the file is
+/// missing, invalid, or has no such document.
+fn load(path: &str, opts: Options) -> Vec<Value> {
+    let selected = loaded(try_load(path, opts), opts);
+    if selected.is_empty() {
+        vec![Value::Mapping(Mapping::default())]
+    } else {
+        selected
+    }
+}
+
+/// With `--strict`, exit 1 when `path` matched nothing in a document.
+fn require_match(cmd: &str, path: &str, found: &[&Value], opts: Options) {
+    if opts.strict && found.is_empty() {
+        eprintln!("yaml {cmd}: {path}: no such path");
+        process::exit(1);
+    }
+}
+
+/// With `--strict`, exit 1 because `path` matched something other than a
+/// `kind` (list or mapping).
+fn require_kind(cmd: &str, path: &str, kind: &str, opts: Options) {
+    if opts.strict {
+        eprintln!("yaml {cmd}: {path}: not a {kind}");
+        process::exit(1);
+    }
+}
+
+fn as_str(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+        _ => format!("{v:?}"),
+    }
+}
+
+fn strip_quotes(s: &str) -> &str {
+    let s = s.trim();
+    if (s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')) {
+        &s[1..s.len() - 1]
+    } else {
+        s
+    }
+}
+
+/// `s` as printed in `style`.
+fn shown(s: &str, style: Style) -> String {
+    match style {
+        Style::Plain => strip_quotes(s).to_string(),
+        Style::ShellQuote => shell_quote(s),
+        Style::Raw => s.to_string(),
+        Style::EscapeNewlines => strip_quotes(s)
+            .replace('\\', "\\\\")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+            .replace('\t', "\\t"),
+    }
+}
+
+fn print_value(v: &Value, style: Style) {
+    match v {
+        Value::String(_) | Value::Number(_) | Value::Bool(_) => {
+            println!("{}", shown(&as_str(v), style));
+        }
+        Value::Null | Value::Tagged(_) => {}
+        Value::Sequence(items) => {
+            for item in items {
+                let s = as_str(item);
+                if !strip_quotes(&s).is_empty() {
+                    println!("{}", shown(&s, style));
+                }
+            }
+        }
+        Value::Mapping(map) => {
+            for (k, v) in map {
+                let key = as_str(k);
+                if !key.is_empty() {
+                    println!("{}\t{}", shown(&key, style), shown(&as_str(v), style));
+                }
+            }
+        }
+    }
+}
+
+/// The value `yaml set` writes: `raw` as a plain string, so `USE WHEN: x`
+/// and `1.10` are stored as written, or with `--yaml` read as YAML (so
+/// `42`, `true` and `[a, b]` keep their type).
+fn parse_value(raw: &str, yaml: bool) -> Result<Value, String> {
+    if !yaml {
+        return Ok(Value::String(raw.to_string()));
+    }
+    serde_yaml::from_str(raw).map_err(|e| format!("--yaml value {raw:?}: {e}"))
+}
+
+/// The text of `file` for editing or merging: empty when missing, an error
+/// when it cannot be read.
+fn read_strict(file: &str) -> Result<String, String> {
+    match read_source(file) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("{file}: {e}")),
+    }
+}
+
+/// Every document of `content`: a single null document when empty, an
+/// error when it does not parse (so it is never overwritten or skipped).
+fn parse_strict(file: &str, content: &str) -> Result<Vec<Value>, String> {
+    let all = parse_documents(content).map_err(|e| format!("{file}: {e}"))?;
+    Ok(if all.is_empty() {
+        vec![Value::Null]
+    } else {
+        all
+    })
+}
+
+/// Every document of `file`, as [`parse_strict`] reads it.
+fn load_strict(file: &str) -> Result<Vec<Value>, String> {
+    parse_strict(file, &read_strict(file)?)
+}
+
+/// `all` as YAML text, documents separated by `---`.
+fn render(file: &str, all: &[Value]) -> Result<String, String> {
+    Ok(all
+        .iter()
+        .map(serde_yaml::to_string)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{file}: {e}"))?
+        .join("---\n"))
+}
+
+/// Write `yaml` to `file`, or to stdout when `file` is `-`.
+fn save(file: &str, yaml: &str) -> Result<(), String> {
+    if file == "-" {
+        print!("{yaml}");
+        return Ok(());
+    }
+    fs::write(file, yaml).map_err(|e| format!("{file}: {e}"))
+}
+
+/// Take `-i`/`--in-place` out of `args`, returning its backup suffix.
+/// `set`, `delete`, and `sort-keys` always write the file back, so the
+/// flag only matters with a suffix (`-i.bak`, `--in-place=.bak`), which
+/// first keeps a copy of the file as `<file><suffix>`, as `sed -i` does.
+/// `-i` takes only a suffix starting with `.`, so a value such as `-inf`
+/// is not read as one.
+fn take_in_place(args: &[String]) -> (Option<&str>, Vec<&str>) {
+    let mut suffix = None;
+    let mut rest = Vec::new();
+    for arg in args {
+        if arg == "-i" || arg == "--in-place" {
+            continue;
+        }
+        match arg
+            .strip_prefix("--in-place=")
+            .or_else(|| arg.strip_prefix("-i").filter(|s| s.starts_with('.')))
+        {
+            Some(s) => suffix = Some(s).filter(|s| !s.is_empty()),
+            None => rest.push(arg.as_str()),
+        }
+    }
+    (suffix, rest)
+}
+
+/// Take `-i`/`--in-place` and `--reformat` out of `args`.
+fn take_edit(args: &[String], docs: Docs) -> (Edit<'_>, Vec<&str>) {
+    let (backup, mut rest) = take_in_place(args);
+    let reformat = rest.contains(&"--reformat");
+    rest.retain(|a| *a != "--reformat");
+    let opts = Edit {
+        docs,
+        backup,
+        reformat,
+    };
+    (opts, rest)
+}
+
+/// Copy `file` to `<file><suffix>` before it is edited. Stdin and files
+/// that do not exist yet have nothing to keep.
+fn backup(file: &str, suffix: Option<&str>) -> Result<(), String> {
+    match suffix {
+        Some(suffix) if file != "-" && Path::new(file).exists() => {
+            let copy = format!("{file}{suffix}");
+            fs::copy(file, &copy)
+                .map(drop)
+                .map_err(|e| format!("{copy}: {e}"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `content` with `edit_source` applied to the documents at `indices`, if
+/// that reads back as `all`, so comments and formatting survive the edit.
+fn edit_in_place(
+    content: &str,
+    indices: &[usize],
+    all: &[Value],
+    edit_source: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let mut sources = split_documents(content);
+    if sources.len() != all.len() {
+        return None;
+    }
+    for &i in indices {
+        sources[i] = edit_source(&sources[i])?;
+    }
+    let text = sources.concat();
+    (parse_documents(&text).ok()? == all).then_some(text)
+}
+
+/// How `set`, `delete`, and `sort-keys` write a file back.
+#[derive(Debug, Clone, Copy, Default)]
+struct Edit<'a> {
+    docs: Docs,
+    /// `-i` suffix: keep the original as `<file><suffix>` before writing.
+    backup: Option<&'a str>,
+    /// `--reformat`: re-serialize the file when the edit cannot be made to
+    /// its text, losing comments and formatting.
+    reformat: bool,
+}
+
+impl From<Docs> for Edit<'_> {
+    fn from(docs: Docs) -> Self {
+        Self {
+            docs,
+            ..Self::default()
+        }
+    }
+}
+
+/// Apply `edit` to the selected documents of `file`, writing every document
+/// back when any of them changed. `edit_source` makes the same edit to one
+/// document's text; when it cannot, the file is left alone with an error,
+/// or re-serialized with `--reformat`. Returns whether anything changed.
+fn edit_file(
+    file: &str,
+    opts: Edit,
+    mut edit: impl FnMut(&mut Value) -> Result<bool, String>,
+    edit_source: impl Fn(&str) -> Option<String>,
+) -> Result<bool, String> {
+    let content = read_strict(file)?;
+    let mut all = parse_strict(file, &content)?;
+    let count = all.len();
+    let indices: Vec<usize> = match opts.docs {
+        Docs::All => (0..count).collect(),
+        Docs::Index(i) if i < count => vec![i],
+        Docs::Index(i) => return Err(format!("{file}: no document {i} ({count} in file)")),
+    };
+    let mut changed = false;
+    for &i in &indices {
+        changed |= edit(&mut all[i])?;
+    }
+    if changed || file == "-" {
+        let yaml = match edit_in_place(&content, &indices, &all, edit_source) {
+            Some(yaml) => yaml,
+            None if opts.reformat => render(file, &all)?,
+            None => {
+                return Err(format!(
+                    "{file}: cannot make this edit without losing comments and formatting; \
+                     pass --reformat to rewrite the whole file"
+                ))
+            }
+        };
+        backup(file, opts.backup)?;
+        save(file, &yaml)?;
+    }
+    Ok(changed)
+}
+
+/// Set `path` in the YAML file at `file` to `value`, creating the file
+/// when missing.
+fn set_in_file(file: &str, path: &str, value: &Value, opts: Edit) -> Result<(), String> {
+    let segments = parse_path(path);
+    edit_file(
+        file,
+        opts,
+        |doc| {
+            set(doc, &segments, value.clone()).map_err(|e| format!("{path}: {e}"))?;
+            Ok(true)
+        },
+        |source| set_in_source(source, &segments, value),
+    )
+    .map(drop)
+}
+
+/// Remove `path` from the YAML file at `file`. Returns whether anything was
+/// removed; a missing path or file leaves the file as it is.
+fn delete_in_file(file: &str, path: &str, opts: Edit) -> Result<bool, String> {
+    let segments = parse_path(path);
+    if segments.is_empty() {
+        return Err("cannot delete the whole document".to_string());
+    }
+    edit_file(
+        file,
+        opts,
+        |doc| Ok(!delete(doc, &segments).is_empty()),
+        |source| delete_in_source(source, &segments),
+    )
+}
+
+/// Sort mapping keys at `path` (the whole document by default) and below
+/// in the YAML file at `file`. Returns whether any key moved.
+fn sort_keys_in_file(file: &str, path: &str, opts: Edit) -> Result<bool, String> {
+    let segments = parse_path(path);
+    edit_file(
+        file,
+        opts,
+        |doc| Ok(sort_keys(doc, &segments)),
+        |source| sort_keys_in_source(source, &segments),
+    )
+}
+
+/// The selected documents of `files` deep-merged in order, each overriding
+/// the last, the way `config.yaml` overrides `defaults.yaml`. Missing files
+/// and documents count as empty.
+fn merge_files(files: &[String], docs: Docs) -> Result<Value, String> {
+    let mut merged = Value::Null;
+    for file in files {
+        for doc in docs.select(load_strict(file)?) {
+            merged = merge_values(merged, doc);
+        }
+    }
+    Ok(merged)
+}
+
+/// `'value'` for a POSIX shell: single-quoted, embedded quotes as `'\''`.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// `key` as a shell variable name: upper case, anything but letters, digits
+/// and `_` turned into `_`, and never starting with a digit.
+fn env_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// `export NAME='value'` lines for `node`: nested mappings flatten into
+/// `PARENT_CHILD` names, lists of scalars join with spaces, and nulls and
+/// other lists are skipped.
+fn env_lines(node: &Value, prefix: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let Value::Mapping(map) = node else {
+        return lines;
+    };
+    for (k, v) in map {
+        let name = format!("{prefix}{}", env_name(&as_str(k)));
+        match v {
+            Value::Mapping(_) => lines.extend(env_lines(v, &format!("{name}_"))),
+            Value::Sequence(items) => {
+                if items
+                    .iter()
+                    .all(|item| !item.is_mapping() && !item.is_sequence())
+                {
+                    let joined: Vec<String> = items.iter().map(as_str).collect();
+                    lines.push(format!("export {name}={}", shell_quote(&joined.join(" "))));
+                }
+            }
+            Value::String(_) | Value::Number(_) | Value::Bool(_) => {
+                lines.push(format!("export {name}={}", shell_quote(&as_str(v))));
+            }
+            Value::Null | Value::Tagged(_) => {}
+        }
+    }
+    lines
+}
+
+/// `s` with each `${VAR}` replaced by `lookup(VAR)`, or by `fallback` for
+/// `${VAR:-fallback}` when that is unset or empty. Unset variables without
+/// a fallback expand to nothing and are added to `missing`; a `${` without
+/// its `}` is kept as is.
+fn expand_vars(
+    s: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let inner = &rest[start + 2..start + 2 + len];
+        let (name, fallback) = match inner.split_once(":-") {
+            Some((name, fallback)) => (name, Some(fallback)),
+            None => (inner, None),
+        };
+        match (lookup(name), fallback) {
+            (Some(value), Some(fallback)) if value.is_empty() => out.push_str(fallback),
+            (Some(value), _) => out.push_str(&value),
+            (None, Some(fallback)) => out.push_str(fallback),
+            (None, None) => missing.push(name.to_string()),
+        }
+        rest = &rest[start + 3 + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Expand `${VAR}` placeholders in every string value under `node` (keys
+/// are left alone).
+fn substitute(
+    node: &mut Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) {
+    match node {
+        Value::String(s) => *s = expand_vars(s, lookup, missing),
+        Value::Sequence(items) => {
+            for item in items {
+                substitute(item, lookup, missing);
+            }
+        }
+        Value::Mapping(map) => {
+            for value in map.values_mut() {
+                substitute(value, lookup, missing);
+            }
+        }
+        Value::Tagged(tagged) => substitute(&mut tagged.value, lookup, missing),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+/// A value on one line for `yaml diff`: scalars as is, anything else as
+/// JSON.
+fn inline(v: &Value) -> String {
+    match v {
+        Value::Mapping(_) | Value::Sequence(_) | Value::Tagged(_) => {
+            serde_json::to_string(v).unwrap_or_else(|_| format!("{v:?}"))
+        }
+        Value::String(s) => s.clone(),
+        _ => as_str(v),
+    }
+}
+
+/// `yaml paths` lines: `path<TAB>value` for every leaf, with empty
+/// containers as `{}`/`[]` and strings holding tabs or newlines JSON-quoted
+/// so each leaf stays on one line.
+fn path_lines(doc: &Value) -> Vec<String> {
+    leaves(doc)
+        .into_iter()
+        .map(|(path, v)| {
+            let value = match v {
+                Value::String(s) if s.contains(['\n', '\t', '\r']) => {
+                    serde_json::to_string(s).unwrap_or_default()
+                }
+                Value::Mapping(_) => "{}".to_string(),
+                Value::Sequence(_) => "[]".to_string(),
+                _ => inline(v),
+            };
+            format!("{path}\t{value}")
+        })
+        .collect()
+}
+
+/// `yaml diff` lines: `+ path: value`, `- path: value`, `~ path: old -> new`.
+fn diff_lines(old: &Value, new: &Value) -> Vec<String> {
+    diff(old, new)
+        .into_iter()
+        .map(|(path, d)| match d {
+            Difference::Added(v) => format!("+ {path}: {}", inline(&v)),
+            Difference::Removed(v) => format!("- {path}: {}", inline(&v)),
+            Difference::Changed(a, b) => format!("~ {path}: {} -> {}", inline(&a), inline(&b)),
+        })
+        .collect()
+}
+
+/// The selected documents of `file` as one value: the document itself, or a
+/// list of every document with `--all-docs`.
+fn load_compared(file: &str, opts: Options) -> Result<Value, String> {
+    let content = read_strict(file)?;
+    let mut all = parse_query(&content, opts.aliases).map_err(|e| format!("{file}: {e}"))?;
+    if all.is_empty() {
+        all.push(Value::Null);
+    }
+    let mut selected = opts.docs.select(all);
+    Ok(match opts.docs {
+        Docs::All => Value::Sequence(selected),
+        Docs::Index(_) => selected.pop().unwrap_or(Value::Null),
+    })
+}
+
+/// Violations of the JSON Schema in `schema_file` by the selected
+/// documents of `file`, as `path: message` lines (prefixed with the
+/// document number when checking several).
+fn validate_file(file: &str, schema_file: &str, docs: Docs) -> Result<Vec<String>, String> {
+    let schema: serde_json::Value = read_source(schema_file)
+        .map_err(|e| format!("{schema_file}: {e}"))
+        .and_then(|c| serde_yaml::from_str(&c).map_err(|e| format!("{schema_file}: {e}")))?;
+    let all = read_source(file)
+        .map_err(|e| format!("{file}: {e}"))
+        .and_then(|c| parse_documents(&c).map_err(|e| format!("{file}: {e}")))?;
+    let selected = docs.select(all);
+    let several = selected.len() > 1;
+    let mut lines = Vec::new();
+    for (i, doc) in selected.iter().enumerate() {
+        let doc = serde_json::to_value(doc).map_err(|e| format!("{file}: {e}"))?;
+        let violations =
+            crate::schema::validate(&doc, &schema).map_err(|e| format!("{schema_file}: {e}"))?;
+        for violation in violations {
+            lines.push(if several {
+                format!("document {i}: {violation}")
+            } else {
+                violation.to_string()
+            });
+        }
+    }
+    Ok(lines)
+}
+
+/// The selected documents of `file` printed as `format` (`yaml`, `json`,
+/// or `toml`). Input is YAML (which includes JSON), or TOML when `from` is
+/// `toml` or, without `from`, the file ends in `.toml`. Several documents
+/// become a `---` stream or a JSON array; TOML holds one.
+fn convert_file(
+    file: &str,
+    from: Option<&str>,
+    format: &str,
+    opts: Options,
+) -> Result<String, String> {
+    let toml_input = match from {
+        Some("toml") => true,
+        Some("yaml" | "yml" | "json") => false,
+        Some(other) => {
+            return Err(format!(
+                "unknown input format {other:?} (expected json, toml, or yaml)"
+            ))
+        }
+        None => Path::new(file).extension().is_some_and(|e| e == "toml"),
+    };
+    let content = read_source(file).map_err(|e| format!("{file}: {e}"))?;
+    let all = if toml_input {
+        let doc: toml::Table = toml::from_str(&content).map_err(|e| format!("{file}: {e}"))?;
+        vec![from_toml(toml::Value::Table(doc))]
+    } else {
+        parse_query(&content, opts.aliases).map_err(|e| format!("{file}: {e}"))?
+    };
+    let selected = opts.docs.select(all);
+    match format {
+        "yaml" | "yml" => render(file, &selected),
+        "json" => {
+            let json = if let [doc] = &selected[..] {
+                serde_json::to_value(doc)
+            } else {
+                serde_json::to_value(&selected)
+            }
+            .map_err(|e| format!("{file}: {e}"))?;
+            let text = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+            Ok(format!("{text}\n"))
+        }
+        "toml" => match &selected[..] {
+            [doc] => {
+                let json = serde_json::to_value(doc).map_err(|e| format!("{file}: {e}"))?;
+                to_toml(&json)
+            }
+            _ => Err("TOML holds one document; pick it with --doc N".to_string()),
+        },
+        other => Err(format!(
+            "unknown format {other:?} (expected json, toml, or yaml)"
+        )),
+    }
+}
+
+/// A parsed TOML value as YAML; dates and times become their TOML text.
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(n) => Value::from(n),
+        toml::Value::Float(n) => Value::from(n),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Sequence(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => Value::Mapping(
+            table
+                .into_iter()
+                .map(|(k, v)| (Value::String(k), from_toml(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// `doc` as TOML: plain keys first, then a `[table]` per nested mapping and
+/// a `[[table]]` per mapping in a list of mappings. Nulls are dropped from
+/// tables; TOML has no way to write them.
+fn to_toml(doc: &serde_json::Value) -> Result<String, String> {
+    let serde_json::Value::Object(map) = doc else {
+        return Err("TOML needs a mapping at the top level".to_string());
+    };
+    let mut out = String::new();
+    write_toml_table(&mut out, "", map)?;
+    Ok(out)
+}
+
+fn is_table_array(value: &serde_json::Value) -> bool {
+    matches!(value, serde_json::Value::Array(items)
+        if !items.is_empty() && items.iter().all(serde_json::Value::is_object))
+}
+
+fn write_toml_table(
+    out: &mut String,
+    prefix: &str,
+    map: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    for (key, value) in map {
+        if !value.is_null() && !value.is_object() && !is_table_array(value) {
+            let _ = writeln!(out, "{} = {}", toml_fmt::key(key), toml_fmt::inline(value)?);
+        }
+    }
+    for (key, value) in map {
+        let path = format!("{prefix}{}", toml_fmt::key(key));
+        match value {
+            serde_json::Value::Object(table) => {
+                // A table holding only sub-tables needs no header of its own.
+                if table.is_empty() || table.values().any(|v| !v.is_object() && !is_table_array(v))
+                {
+                    let _ = write!(out, "\n[{path}]\n");
+                }
+                write_toml_table(out, &format!("{path}."), table)?;
+            }
+            serde_json::Value::Array(items) if is_table_array(value) => {
+                for item in items.iter().filter_map(serde_json::Value::as_object) {
+                    let _ = write!(out, "\n[[{path}]]\n");
+                    write_toml_table(out, &format!("{path}."), item)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// --- Commands ---
+
+/// The paths and default among the `get`/`value` arguments after the file:
+/// every argument is a path, except that a last one not starting with `.`
+/// after at least one path is the default.
+fn paths_and_default(args: &[String]) -> (Vec<&str>, Option<&str>) {
+    let mut paths: Vec<&str> = args.iter().map(String::as_str).collect();
+    let default = match paths[..] {
+        [_, .., last] if !last.starts_with('.') => paths.pop(),
+        _ => None,
+    };
+    (paths, default)
+}
+
+/// One path's result in a batch: its scalars, and the scalar items of the
+/// lists it matches, space-separated; `default` when there are none.
+fn batch_field(found: &[&Value], default: &str, style: Style) -> String {
+    let words: Vec<String> = found
+        .iter()
+        .flat_map(|v| match v {
+            Value::Sequence(items) => items.iter().collect(),
+            other => vec![*other],
+        })
+        .filter(|v| matches!(v, Value::String(_) | Value::Number(_) | Value::Bool(_)))
+        .map(|v| shown(&as_str(v), style))
+        .collect();
+    if words.is_empty() {
+        shown(default, style)
+    } else {
+        words.join(" ")
+    }
+}
+
+/// The batch field of each of `paths` in `doc`.
+fn batch_fields(
+    cmd: &str,
+    doc: &Value,
+    paths: &[&str],
+    default: Option<&str>,
+    opts: Options,
+) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| {
+            let found = walk_all(doc, &parse_path(path));
+            if default.is_none() {
+                require_match(cmd, path, &found, opts);
+            }
+            batch_field(&found, default.unwrap_or(""), opts.style)
+        })
+        .collect()
+}
+
+/// `yaml get`/`value` with several paths: one line per path (one
+/// tab-separated line with `--tab`) for each document, in path order, so
+/// a script can read them all from one run.
+fn print_batch(cmd: &str, file: &str, paths: &[&str], default: Option<&str>, opts: Options) {
+    let sep = if opts.tab { "\t" } else { "\n" };
+    for doc in load(file, opts) {
+        println!(
+            "{}",
+            batch_fields(cmd, &doc, paths, default, opts).join(sep)
+        );
+    }
+}
+
+/// Whether `file` is a glob pattern to expand rather than a file name: it
+/// holds a `*` or `?` and no file of that literal name exists.
+fn is_glob(file: &str) -> bool {
+    file.contains(['*', '?']) && !Path::new(file).exists()
+}
+
+/// Whether `name` matches the glob `pattern`, where `*` matches any run of
+/// characters and `?` any one.
+fn glob_matches(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (Some(('*', rest)), _) => {
+            glob_matches(rest, name) || (!name.is_empty() && glob_matches(pattern, &name[1..]))
+        }
+        (Some((p, rest)), Some((n, name_rest))) if *p == '?' || p == n => {
+            glob_matches(rest, name_rest)
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// The files matching glob `pattern`, sorted. Wildcards match within one
+/// path component, and only a component pattern starting with `.` matches
+/// hidden entries.
+fn expand_glob(pattern: &str) -> Vec<String> {
+    let mut found = vec![if pattern.starts_with('/') {
+        PathBuf::from("/")
+    } else {
+        PathBuf::new()
+    }];
+    for part in pattern.split('/').filter(|part| !part.is_empty()) {
+        if !part.contains(['*', '?']) {
+            found = found.into_iter().map(|dir| dir.join(part)).collect();
+            continue;
+        }
+        let part: Vec<char> = part.chars().collect();
+        found = found
+            .into_iter()
+            .flat_map(|dir| {
+                let listed = if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    &dir
+                };
+                let mut names: Vec<String> = fs::read_dir(listed)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                    .filter(|name| !name.starts_with('.') || part[0] == '.')
+                    .filter(|name| glob_matches(&part, &name.chars().collect::<Vec<_>>()))
+                    .collect();
+                names.sort();
+                names.into_iter().map(move |name| dir.join(name))
+            })
+            .collect();
+    }
+    found
+        .into_iter()
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// `yaml get`/`value` over every file matching a glob: one
+/// `file<TAB>field...` row per file (and document), the fields as in a
+/// batch query.
+fn print_glob(cmd: &str, pattern: &str, paths: &[&str], default: Option<&str>, opts: Options) {
+    let files = expand_glob(pattern);
+    if files.is_empty() && opts.strict {
+        eprintln!("yaml: {pattern}: no matching files");
+        process::exit(2);
+    }
+    for file in files {
+        let name = if opts.style == Style::ShellQuote {
+            shell_quote(&file)
+        } else {
+            file.clone()
+        };
+        for doc in load(&file, opts) {
+            let fields = batch_fields(cmd, &doc, paths, default, opts);
+            println!("{name}\t{}", fields.join("\t"));
+        }
+    }
+}
+
+fn cmd_value(args: &[String], opts: Options) {
+    if args.len() < 2 {
+        eprintln!("Usage: yaml value <file> <path>... [default]");
+        process::exit(1);
+    }
+    let (paths, default) = paths_and_default(&args[1..]);
+    if is_glob(&args[0]) {
+        print_glob("value", &args[0], &paths, default, opts);
+        return;
+    }
+    let [path] = paths[..] else {
+        print_batch("value", &args[0], &paths, default, opts);
+        return;
+    };
+    let segments = parse_path(path);
+    for doc in load(&args[0], opts) {
+        let found = walk_all(&doc, &segments);
+        if default.is_none() {
+            require_match("value", path, &found, opts);
+        }
+        let scalars: Vec<_> = found
+            .into_iter()
+            .filter(|v| matches!(v, Value::String(_) | Value::Number(_) | Value::Bool(_)))
+            .collect();
+        if scalars.is_empty() {
+            println!("{}", shown(default.unwrap_or(""), opts.style));
+        }
+        for v in scalars {
+            print_value(v, opts.style);
+        }
+    }
+}
+
+/// Take a command's own `flag VALUE` out of `args`, returning the value
+/// (the last one given) and the remaining arguments.
+fn take_flag<'a>(cmd: &str, flag: &str, args: Vec<&'a str>) -> (Option<&'a str>, Vec<&'a str>) {
+    let mut value = None;
+    let mut rest = Vec::new();
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            let Some(v) = iter.next() else {
+                eprintln!("yaml {cmd}: {flag} needs a value");
+                process::exit(1);
+            };
+            value = Some(v);
+        } else {
+            rest.push(arg);
+        }
+    }
+    (value, rest)
+}
+
+/// A `--format` row: `template` with each `{name}` in `fields` replaced by
+/// its value and `\t` and `\n` read as tab and newline. Other braces are
+/// kept as written.
+fn format_row(template: &str, fields: &[(&str, &str)]) -> String {
+    let template = template.replace("\\t", "\t").replace("\\n", "\n");
+    let mut out = String::new();
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let field = rest.find('}').and_then(|end| {
+            let (_, value) = fields.iter().find(|(name, _)| *name == &rest[1..end])?;
+            Some((value, end))
+        });
+        if let Some((value, end)) = field {
+            out.push_str(value);
+            rest = &rest[end + 1..];
+        } else {
+            out.push('{');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// For `list`, `map`, and `keys`: when `path` matched nothing, print the
+/// `--default` line if one was given, and otherwise check it as `--strict`
+/// asks. An empty collection that does exist prints nothing either way.
+fn or_default(cmd: &str, path: &str, found: &[&Value], default: Option<&str>, opts: Options) {
+    match default {
+        Some(line) if found.is_empty() => println!("{}", shown(line, opts.style)),
+        Some(_) => {}
+        None => require_match(cmd, path, found, opts),
+    }
+}
+
+fn cmd_list(args: &[String], opts: Options) {
+    let (format, args) = take_flag(
+        "list",
+        "--format",
+        args.iter().map(String::as_str).collect(),
+    );
+    let (default, args) = take_flag("list", "--default", args);
+    let [file, path] = args[..] else {
+        eprintln!("Usage: yaml list <file> <path> [--format TEMPLATE] [--default LINE]");
+        process::exit(1);
+    };
+    let segments = parse_path(path);
+    for doc in load(file, opts) {
+        let found = walk_all(&doc, &segments);
+        or_default("list", path, &found, default, opts);
+        for (index, item) in list_items(&found, &segments) {
+            let Some(item) = item else {
+                require_kind("list", path, "list", opts);
+                continue;
+            };
+            let s = as_str(item);
+            if strip_quotes(&s).is_empty() {
+                continue;
+            }
+            let value = shown(&s, opts.style);
+            match format {
+                Some(template) => {
+                    let index = index.to_string();
+                    let fields = [("value", value.as_str()), ("index", &index)];
+                    println!("{}", format_row(template, &fields));
+                }
+                None => println!("{value}"),
+            }
+        }
+    }
+}
+
+/// The `(index, item)` rows `yaml list` prints for the nodes a path found:
+/// the items of each list, and a scalar match itself when the path picks
+/// out list items or several nodes (`.list[1:3]`, `.list[-1]`, `..name`),
+/// numbered in match order. `None` marks a match that is not a list.
+fn list_items<'a>(
+    found: &[&'a Value],
+    segments: &[PathSegment],
+) -> Vec<(usize, Option<&'a Value>)> {
+    let selects_items =
+        found.len() > 1 || !matches!(segments.last(), None | Some(PathSegment::Key(_)));
+    let mut rows = Vec::new();
+    for (position, node) in found.iter().enumerate() {
+        match node {
+            Value::Sequence(items) => rows.extend(items.iter().map(Some).enumerate()),
+            Value::Mapping(_) | Value::Tagged(_) => rows.push((position, None)),
+            scalar if selects_items => rows.push((position, Some(*scalar))),
+            _ => rows.push((position, None)),
+        }
+    }
+    rows
+}
+
+fn cmd_map(args: &[String], opts: Options) {
+    let (format, args) = take_flag("map", "--format", args.iter().map(String::as_str).collect());
+    let (sep, args) = take_flag("map", "--sep", args);
+    let (default, args) = take_flag("map", "--default", args);
+    let [file, path] = args[..] else {
+        eprintln!("Usage: yaml map <file> <path> [--sep SEP | --format TEMPLATE] [--default LINE]");
+        process::exit(1);
+    };
+    let sep = sep.unwrap_or("\t");
+    let segments = parse_path(path);
+    for doc in load(file, opts) {
+        let found = walk_all(&doc, &segments);
+        or_default("map", path, &found, default, opts);
+        for node in found {
+            let Value::Mapping(map) = node else {
+                require_kind("map", path, "mapping", opts);
+                continue;
+            };
+            for (k, v) in map {
+                let key = as_str(k);
+                let shown_key = shown(&key, opts.style);
+                let values: Vec<String> = match v {
+                    Value::Sequence(items) => items.iter().map(as_str).collect(),
+                    _ if key.is_empty() => Vec::new(),
+                    _ => vec![as_str(v)],
+                };
+                for val in values.iter().filter(|val| !strip_quotes(val).is_empty()) {
+                    let value = shown(val, opts.style);
+                    match format {
+                        Some(template) => {
+                            let fields = [("key", shown_key.as_str()), ("value", &value)];
+                            println!("{}", format_row(template, &fields));
+                        }
+                        None => println!("{shown_key}{sep}{value}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn cmd_keys(args: &[String], opts: Options) {
+    let (default, args) = take_flag(
+        "keys",
+        "--default",
+        args.iter().map(String::as_str).collect(),
+    );
+    let [file, path] = args[..] else {
+        eprintln!("Usage: yaml keys <file> <path> [--default LINE]");
+        process::exit(1);
+    };
+    let segments = parse_path(path);
+    for doc in load(file, opts) {
+        let found = walk_all(&doc, &segments);
+        or_default("keys", path, &found, default, opts);
+        for node in found {
+            let Value::Mapping(map) = node else {
+                require_kind("keys", path, "mapping", opts);
+                continue;
+            };
+            for k in map.keys() {
+                let key = as_str(k);
+                if !key.is_empty() {
+                    println!("{}", shown(&key, opts.style));
+                }
+            }
+        }
+    }
+}
+
+/// The number of items of the list, or entries of the mapping, at each
+/// `found` node that `filter` keeps (a wildcard keeps them all).
+fn count_children(found: &[&Value], filter: &PathSegment) -> usize {
+    found
+        .iter()
+        .map(|node| walk_all(node, std::slice::from_ref(filter)).len())
+        .sum()
+}
+
+fn cmd_count(args: &[String], opts: Options) {
+    let (condition, args) = take_flag(
+        "count",
+        "--where",
+        args.iter().map(String::as_str).collect(),
+    );
+    let [file, path] = args[..] else {
+        eprintln!("Usage: yaml count <file> <path> [--where key=value]");
+        process::exit(1);
+    };
+    let filter = match condition {
+        None => PathSegment::Wildcard,
+        Some(condition) => {
+            let Some(filter @ PathSegment::Filter { .. }) =
+                parse_path(&format!("[?{condition}]")).pop()
+            else {
+                eprintln!("yaml count: --where needs key=value or key!=value");
+                process::exit(1);
+            };
+            filter
+        }
+    };
+    let segments = parse_path(path);
+    for doc in load(file, opts) {
+        let found = walk_all(&doc, &segments);
+        require_match("count", path, &found, opts);
+        if found
+            .iter()
+            .any(|node| !node.is_sequence() && !node.is_mapping())
+        {
+            require_kind("count", path, "list or mapping", opts);
+        }
+        println!("{}", count_children(&found, &filter));
+    }
+}
+
+fn cmd_get(args: &[String], opts: Options) {
+    if args.len() < 2 {
+        eprintln!("Usage: yaml get <file> <path>... [default]");
+        process::exit(1);
+    }
+    let (paths, default) = paths_and_default(&args[1..]);
+    if is_glob(&args[0]) {
+        print_glob("get", &args[0], &paths, default, opts);
+        return;
+    }
+    let [path] = paths[..] else {
+        print_batch("get", &args[0], &paths, default, opts);
+        return;
+    };
+    let segments = parse_path(path);
+    for doc in load(&args[0], opts) {
+        let found = walk_all(&doc, &segments);
+        match default {
+            None => require_match("get", path, &found, opts),
+            Some(default) if found.is_empty() && !default.is_empty() => {
+                println!("{}", shown(default, opts.style));
+            }
+            Some(_) => {}
+        }
+        for v in found {
+            print_value(v, opts.style);
+        }
+    }
+}
+
+/// Whether `path` resolves in any selected document of `file`, even to an
+/// empty or null value.
+fn path_exists(file: &str, path: &str, opts: Options) -> bool {
+    let segments = parse_path(path);
+    loaded(try_load(file, opts), opts)
+        .iter()
+        .any(|doc| !walk_all(doc, &segments).is_empty())
+}
+
+fn cmd_exists(args: &[String], opts: Options) {
+    if args.len() < 2 {
+        eprintln!("Usage: yaml exists <file> <path>");
+        process::exit(2);
+    }
+    if !path_exists(&args[0], &args[1], opts) {
+        process::exit(1);
+    }
+}
+
+fn cmd_paths(args: &[String], opts: Options) {
+    let Some(file) = args.first() else {
+        eprintln!("Usage: yaml paths <file>");
+        process::exit(1);
+    };
+    for doc in load(file, opts) {
+        for line in path_lines(&doc) {
+            println!("{line}");
+        }
+    }
+}
+
+// Legacy: `yaml nested <file> <parent> <child> [default]`
+fn cmd_nested(args: &[String], opts: Options) {
+    if args.len() < 3 {
+        eprintln!("Usage: yaml nested <file> <parent> <child> [default]");
+        process::exit(1);
+    }
+    let path = format!("{}.{}", args[1], args[2]);
+    let mut new_args = vec![args[0].clone(), path];
+    if let Some(d) = args.get(3) {
+        new_args.push(d.clone());
+    }
+    cmd_value(&new_args, opts);
+}
+
+fn cmd_set(args: &[String], docs: Docs) {
+    let (opts, mut args) = take_edit(args, docs);
+    let yaml = args.contains(&"--yaml");
+    args.retain(|a| *a != "--yaml");
+    let [file, path, raw] = args[..] else {
+        eprintln!("Usage: yaml set [-i[SUFFIX]] [--reformat] [--yaml] <file> <path> <value>");
+        process::exit(1);
+    };
+    let result = parse_value(raw, yaml).and_then(|value| set_in_file(file, path, &value, opts));
+    if let Err(e) = result {
+        eprintln!("yaml set: {e}");
+        process::exit(1);
+    }
+}
+
+fn cmd_delete(args: &[String], docs: Docs) {
+    let (opts, args) = take_edit(args, docs);
+    let [file, path] = args[..] else {
+        eprintln!("Usage: yaml delete [-i[SUFFIX]] [--reformat] <file> <path>");
+        process::exit(1);
+    };
+    if let Err(e) = delete_in_file(file, path, opts) {
+        eprintln!("yaml delete: {e}");
+        process::exit(1);
+    }
+}
+
+fn cmd_sort_keys(args: &[String], docs: Docs) {
+    let (opts, args) = take_edit(args, docs);
+    let Some(&file) = args.first() else {
+        eprintln!("Usage: yaml sort-keys [-i[SUFFIX]] [--reformat] <file> [path]");
+        process::exit(1);
+    };
+    let path = args.get(1).copied().unwrap_or(".");
+    if let Err(e) = sort_keys_in_file(file, path, opts) {
+        eprintln!("yaml sort-keys: {e}");
+        process::exit(1);
+    }
+}
+
+fn cmd_merge(args: &[String], docs: Docs) {
+    if args.len() < 2 {
+        eprintln!("Usage: yaml merge <base> <overlay>...");
+        process::exit(1);
+    }
+    match merge_files(args, docs)
+        .and_then(|doc| serde_yaml::to_string(&doc).map_err(|e| e.to_string()))
+    {
+        Ok(yaml) => print!("{yaml}"),
+        Err(e) => {
+            eprintln!("yaml merge: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn cmd_env(args: &[String], opts: Options) {
+    let (prefix, positional) =
+        take_flag("env", "--prefix", args.iter().map(String::as_str).collect());
+    let prefix = prefix.unwrap_or("");
+    let [file, path] = positional[..] else {
+        eprintln!("Usage: yaml env <file> <path> [--prefix PREFIX]");
+        process::exit(1);
+    };
+    let segments = parse_path(path);
+    for doc in load(file, opts) {
+        let found = walk_all(&doc, &segments);
+        require_match("env", path, &found, opts);
+        for node in found {
+            if !node.is_mapping() {
+                require_kind("env", path, "mapping", opts);
+            }
+            for line in env_lines(node, prefix) {
+                println!("{line}");
+            }
+        }
+    }
+}
+
+fn cmd_envsubst(args: &[String], opts: Options) {
+    let Some(file) = args.first() else {
+        eprintln!("Usage: yaml envsubst <file> [path]");
+        process::exit(1);
+    };
+    let path = args.get(1).map_or(".", String::as_str);
+    let segments = parse_path(path);
+    let lookup = |name: &str| std::env::var(name).ok();
+    let mut missing = Vec::new();
+    let mut out = Vec::new();
+    for doc in load(file, opts) {
+        let found = walk_all(&doc, &segments);
+        require_match("envsubst", path, &found, opts);
+        for node in found {
+            let mut node = node.clone();
+            substitute(&mut node, &lookup, &mut missing);
+            match serde_yaml::to_string(&node) {
+                Ok(yaml) => out.push(yaml),
+                Err(e) => {
+                    eprintln!("yaml envsubst: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+    }
+    if opts.strict && !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        eprintln!("yaml envsubst: not set: {}", missing.join(", "));
+        process::exit(1);
+    }
+    print!("{}", out.join("---\n"));
+}
+
+fn cmd_diff(args: &[String], opts: Options) {
+    if args.len() < 2 {
+        eprintln!("Usage: yaml diff <old> <new>");
+        process::exit(2);
+    }
+    let (old, new) = match (load_compared(&args[0], opts), load_compared(&args[1], opts)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("yaml diff: {e}");
+            process::exit(2);
+        }
+    };
+    let lines = diff_lines(&old, &new);
+    for line in &lines {
+        println!("{line}");
+    }
+    if !lines.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn cmd_validate(args: &[String], docs: Docs) {
+    if args.len() < 2 {
+        eprintln!("Usage: yaml validate <file> <schema.json>");
+        process::exit(2);
+    }
+    match validate_file(&args[0], &args[1], docs) {
+        Ok(violations) if violations.is_empty() => {}
+        Ok(violations) => {
+            for line in &violations {
+                println!("{line}");
+            }
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("yaml validate: {e}");
+            process::exit(2);
+        }
+    }
+}
+
+fn cmd_convert(args: &[String], opts: Options) {
+    let (mut file, mut from, mut format) = (None, None, None);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--to" => format = iter.next(),
+            "--from" => from = iter.next(),
+            _ => file = Some(arg),
+        }
+    }
+    let (Some(file), Some(format)) = (file, format) else {
+        eprintln!("Usage: yaml convert <file> [--from json|toml|yaml] --to json|toml|yaml");
+        process::exit(1);
+    };
+    match convert_file(file, from.map(String::as_str), format, opts) {
+        Ok(text) => print!("{text}"),
+        Err(e) => {
+            eprintln!("yaml convert: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn print_usage() {
+    eprintln!("Usage: yaml <command> <file> <path> [...]");
+    eprintln!();
+    eprintln!("Commands:");
+    eprintln!("  get    <file> <path>... [default] Auto-detect type and print");
+    eprintln!("  value  <file> <path>... [default] Extract scalar (default if missing)");
+    eprintln!("  list   <file> <path>             Print array (or selected) items, one per line");
+    eprintln!("  map    <file> <path>             Print mapping as key\\tvalue lines (--sep SEP for key<SEP>value)");
+    eprintln!("         [--format TEMPLATE]       Print list/map rows as TEMPLATE, e.g. '{{key}}: {{value}}'");
+    eprintln!("  keys   <file> <path>             Print mapping keys, one per line");
+    eprintln!(
+        "         [--default LINE]          list/map/keys: print LINE when the path is missing"
+    );
+    eprintln!("  count  <file> <path> [--where k=v] Count items or entries (those where k is v)");
+    eprintln!("  nested <file> <p> <c> [default]  Legacy: same as value with <p>.<c>");
+    eprintln!(
+        "  exists <file> <path>             Exit 0 if the path resolves, 1 if not (prints nothing)"
+    );
+    eprintln!("  paths  <file>                    Print every leaf as path\tvalue");
+    eprintln!(
+        "  set    <file> <path> <value>     Write value as a string at path (--yaml: parse it)"
+    );
+    eprintln!("  delete <file> <path>             Remove a key or array item (if present)");
+    eprintln!("  sort-keys <file> [path]          Sort mapping keys recursively (under path)");
+    eprintln!("  merge  <base> <overlay>...       Deep-merge files and print the result");
+    eprintln!("  env    <file> <path> [--prefix P] Print mapping as export KEY='value' lines");
+    eprintln!(
+        "  envsubst <file> [path]           Expand ${{VAR}} and ${{VAR:-default}} in string values"
+    );
+    eprintln!(
+        "  diff   <old> <new>               Print +/-/~ changed paths (exit 1 if any, 2 on error)"
+    );
+    eprintln!("  validate <file> <schema>         Check against a JSON Schema (exit 1 if invalid, 2 on error)");
+    eprintln!(
+        "  convert <file> --to <format>     Print as json, toml, or yaml (input: YAML, JSON, or TOML; --from toml for stdin)"
+    );
+    eprintln!();
+    eprintln!("Paths: .field.subfield, .array[0], .deep.path[1].key, .map.*.key, ..key, .array[-1], .array[1:3]");
+    eprintln!("       .list[?key=value].field (filter; also !=), .[\"dotted.key\"] (literal key)");
+    eprintln!("A <file> of - reads stdin; set, delete, and sort-keys then print the result.");
+    eprintln!("set, delete, and sort-keys edit in place; -i.bak (--in-place=.bak) keeps a backup.");
+    eprintln!("They keep comments and formatting or fail; --reformat rewrites the file instead.");
+    eprintln!(
+        "get and value take a quoted glob (Modules/*/module.yaml): one file\\tvalue row each."
+    );
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --doc N     Use document N (from 0) of a multi-document file");
+    eprintln!("  --all-docs  Use every document in turn (merge folds them in order)");
+    eprintln!("  --shell-quote  Print values single-quoted, safe for eval");
+    eprintln!("  --raw       Print multi-line values exactly as stored");
+    eprintln!("  --escape-newlines  Print each value on one line, escaping \\n, \\t, and \\\\");
+    eprintln!("  --resolve-aliases  Expand *aliases and apply <<: merge keys (default)");
+    eprintln!("  --keep-aliases     Print each *alias as written instead of expanding it");
+    eprintln!("  --tab       Print several get/value paths on one tab-separated line");
+    eprintln!("  --strict    Exit 2 on a missing or invalid file, 1 when the path matches nothing");
+}
+
+/// Run `yaml` (also `forge yaml`).
+pub fn run(args: &[String]) -> ExitCode {
+    let (opts, args) = match split_flags(args) {
+        Ok(split) => split,
+        Err(e) => {
+            eprintln!("yaml: {e}");
+            return ExitCode::from(1);
+        }
+    };
+    let Some(cmd) = args.first() else {
+        print_usage();
+        return ExitCode::from(1);
+    };
+    let rest = &args[1..];
+
+    match cmd.as_str() {
+        "--version" => println!("yaml {}", env!("CARGO_PKG_VERSION")),
+        "-h" | "--help" => print_usage(),
+        "get" => cmd_get(rest, opts),
+        "value" => cmd_value(rest, opts),
+        "list" => cmd_list(rest, opts),
+        "map" => cmd_map(rest, opts),
+        "keys" => cmd_keys(rest, opts),
+        "count" => cmd_count(rest, opts),
+        "nested" => cmd_nested(rest, opts),
+        "exists" => cmd_exists(rest, opts),
+        "paths" => cmd_paths(rest, opts),
+        "set" => cmd_set(rest, opts.docs),
+        "delete" => cmd_delete(rest, opts.docs),
+        "sort-keys" => cmd_sort_keys(rest, opts.docs),
+        "merge" => cmd_merge(rest, opts.docs),
+        "env" => cmd_env(rest, opts),
+        "envsubst" => cmd_envsubst(rest, opts),
+        "diff" => cmd_diff(rest, opts),
+        "validate" => cmd_validate(rest, opts.docs),
+        "convert" => cmd_convert(rest, opts),
+        _ => {
+            eprintln!("Unknown command: {cmd}");
+            eprintln!(
+                "Commands: get, value, list, map, keys, count, nested, exists, paths, set, delete, sort-keys, merge, env, envsubst, diff, validate, convert"
+            );
+            return ExitCode::from(1);
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,1016 @@
+use super::*;
+use crate::query::{walk, PathSegment};
+use std::io::Write as IoWrite;
+
+fn opts(docs: Docs) -> Options {
+    Options {
+        docs,
+        ..Options::default()
+    }
+}
+
+fn first_doc(path: &str) -> Value {
+    load(path, opts(Docs::default())).remove(0)
+}
+
+fn temp_yaml(content: &str) -> tempfile::NamedTempFile {
+    let mut f = tempfile::NamedTempFile::new().unwrap();
+    f.write_all(content.as_bytes()).unwrap();
+    f.flush().unwrap();
+    f
+}
+
+// --- parse_path ---
+
+#[test]
+fn parse_simple_key() {
+    let segs = parse_path("agents");
+    assert_eq!(segs.len(), 1);
+    assert!(matches!(&segs[0], PathSegment::Key(k) if k == "agents"));
+}
+
+#[test]
+fn parse_dotted_path() {
+    let segs = parse_path(".skills.claude");
+    assert_eq!(segs.len(), 2);
+    assert!(matches!(&segs[0], PathSegment::Key(k) if k == "skills"));
+    assert!(matches!(&segs[1], PathSegment::Key(k) if k == "claude"));
+}
+
+#[test]
+fn parse_leading_dot_optional() {
+    let a = parse_path(".agents");
+    let b = parse_path("agents");
+    assert_eq!(a.len(), b.len());
+}
+
+#[test]
+fn parse_array_index() {
+    let segs = parse_path(".modules[0]");
+    assert_eq!(segs.len(), 2);
+    assert!(matches!(&segs[0], PathSegment::Key(k) if k == "modules"));
+    assert!(matches!(&segs[1], PathSegment::Index(0)));
+}
+
+#[test]
+fn parse_array_index_deep() {
+    let segs = parse_path(".items[2].name");
+    assert_eq!(segs.len(), 3);
+    assert!(matches!(&segs[0], PathSegment::Key(k) if k == "items"));
+    assert!(matches!(&segs[1], PathSegment::Index(2)));
+    assert!(matches!(&segs[2], PathSegment::Key(k) if k == "name"));
+}
+
+#[test]
+fn parse_multi_index() {
+    let segs = parse_path(".matrix[0][1]");
+    assert_eq!(segs.len(), 3);
+    assert!(matches!(&segs[0], PathSegment::Key(k) if k == "matrix"));
+    assert!(matches!(&segs[1], PathSegment::Index(0)));
+    assert!(matches!(&segs[2], PathSegment::Index(1)));
+}
+
+#[test]
+fn parse_empty_path() {
+    let segs = parse_path("");
+    assert!(segs.is_empty());
+}
+
+#[test]
+fn parse_dot_only() {
+    let segs = parse_path(".");
+    assert!(segs.is_empty());
+}
+
+// --- walk ---
+
+#[test]
+fn walk_single_key() {
+    let f = temp_yaml("name: forge-test\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    let v = walk(&doc, &parse_path(".name")).unwrap();
+    assert_eq!(as_str(v), "forge-test");
+}
+
+#[test]
+fn walk_nested_key() {
+    let f = temp_yaml("user:\n  root: Vaults/Personal\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    let v = walk(&doc, &parse_path(".user.root")).unwrap();
+    assert_eq!(as_str(v), "Vaults/Personal");
+}
+
+#[test]
+fn walk_deep_nesting() {
+    let f = temp_yaml("a:\n  b:\n    c:\n      d: value\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    let v = walk(&doc, &parse_path(".a.b.c.d")).unwrap();
+    assert_eq!(as_str(v), "value");
+}
+
+#[test]
+fn walk_array_index() {
+    let f = temp_yaml("modules:\n  - alpha\n  - beta\n  - gamma\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    let v = walk(&doc, &parse_path(".modules[1]")).unwrap();
+    assert_eq!(as_str(v), "beta");
+}
+
+#[test]
+fn walk_array_nested() {
+    let f = temp_yaml("items:\n  - name: first\n    val: 1\n  - name: second\n    val: 2\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    let v = walk(&doc, &parse_path(".items[1].name")).unwrap();
+    assert_eq!(as_str(v), "second");
+}
+
+#[test]
+fn walk_missing_returns_none() {
+    let f = temp_yaml("name: test\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    assert!(walk(&doc, &parse_path(".nonexistent")).is_none());
+}
+
+#[test]
+fn walk_missing_nested_returns_none() {
+    let f = temp_yaml("a:\n  b: value\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    assert!(walk(&doc, &parse_path(".a.c")).is_none());
+}
+
+#[test]
+fn walk_out_of_bounds_returns_none() {
+    let f = temp_yaml("items:\n  - one\n  - two\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    assert!(walk(&doc, &parse_path(".items[5]")).is_none());
+}
+
+// --- keys ---
+
+#[test]
+fn keys_top_level() {
+    let f = temp_yaml("agents:\n  Foo:\n    model: fast\n  Bar:\n    model: strong\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Mapping(map)) = walk(&doc, &parse_path(".agents")) {
+        let keys: Vec<String> = map.keys().map(as_str).collect();
+        assert_eq!(keys, vec!["Foo", "Bar"]);
+    } else {
+        panic!("expected mapping");
+    }
+}
+
+#[test]
+fn keys_nested() {
+    let f = temp_yaml("skills:\n  claude:\n    SkillA:\n      scope: ws\n    SkillB:\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Mapping(map)) = walk(&doc, &parse_path(".skills.claude")) {
+        let keys: Vec<String> = map.keys().map(as_str).collect();
+        assert_eq!(keys, vec!["SkillA", "SkillB"]);
+    } else {
+        panic!("expected mapping");
+    }
+}
+
+// --- value (scalar) ---
+
+#[test]
+fn value_scalar() {
+    let f = temp_yaml("name: forge-test\nversion: 0.1.0\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    assert_eq!(
+        as_str(walk(&doc, &parse_path(".name")).unwrap()),
+        "forge-test"
+    );
+    assert_eq!(
+        as_str(walk(&doc, &parse_path(".version")).unwrap()),
+        "0.1.0"
+    );
+}
+
+#[test]
+fn value_nested_scalar() {
+    let f = temp_yaml("user:\n  root: Vaults/Personal\n  name: test\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    assert_eq!(
+        as_str(walk(&doc, &parse_path(".user.root")).unwrap()),
+        "Vaults/Personal"
+    );
+}
+
+// --- list ---
+
+#[test]
+fn list_block_syntax() {
+    let f = temp_yaml("modules:\n  - alpha\n  - beta\n  - gamma\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Sequence(items)) = walk(&doc, &parse_path(".modules")) {
+        let strs: Vec<String> = items.iter().map(as_str).collect();
+        assert_eq!(strs, vec!["alpha", "beta", "gamma"]);
+    } else {
+        panic!("expected sequence");
+    }
+}
+
+#[test]
+fn list_flow_syntax() {
+    let f = temp_yaml("events: [SessionStart, PreToolUse]\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Sequence(items)) = walk(&doc, &parse_path(".events")) {
+        let strs: Vec<String> = items.iter().map(as_str).collect();
+        assert_eq!(strs, vec!["SessionStart", "PreToolUse"]);
+    } else {
+        panic!("expected sequence");
+    }
+}
+
+// --- map ---
+
+#[test]
+fn map_scalar_values() {
+    let f = temp_yaml("user:\n  root: Vaults/Personal\n  name: test\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Mapping(map)) = walk(&doc, &parse_path(".user")) {
+        assert_eq!(as_str(map.get("root").unwrap()), "Vaults/Personal");
+        assert_eq!(as_str(map.get("name").unwrap()), "test");
+    } else {
+        panic!("expected mapping");
+    }
+}
+
+#[test]
+fn map_list_values() {
+    let f = temp_yaml("commands:\n  hooks: [pre, post]\n  run: test\n");
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Mapping(map)) = walk(&doc, &parse_path(".commands")) {
+        match map.get("hooks").unwrap() {
+            Value::Sequence(items) => {
+                assert_eq!(items.len(), 2);
+                assert_eq!(as_str(&items[0]), "pre");
+            }
+            _ => panic!("expected sequence"),
+        }
+    } else {
+        panic!("expected mapping");
+    }
+}
+
+// --- edge cases ---
+
+#[test]
+fn missing_file_returns_empty_mapping() {
+    let doc = first_doc("/nonexistent/path.yaml");
+    assert!(doc.is_mapping());
+    assert!(doc.as_mapping().unwrap().is_empty());
+}
+
+#[test]
+fn quoted_values_stripped() {
+    assert_eq!(strip_quotes("\"hello\""), "hello");
+    assert_eq!(strip_quotes("'world'"), "world");
+    assert_eq!(strip_quotes("plain"), "plain");
+}
+
+// --- realistic forge patterns ---
+
+#[test]
+fn forge_agents_keys() {
+    let yaml = "\
+agents:
+    SoftwareDeveloper:
+        model: fast
+        tools: Read, Grep, Glob
+    DatabaseEngineer:
+        model: fast
+        tools: Read, Grep, Glob, Bash
+    TheOpponent:
+        model: strong
+        tools: Read, Grep, Glob, WebSearch
+";
+    let f = temp_yaml(yaml);
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Mapping(map)) = walk(&doc, &parse_path(".agents")) {
+        let keys: Vec<String> = map.keys().map(as_str).collect();
+        assert_eq!(
+            keys,
+            vec!["SoftwareDeveloper", "DatabaseEngineer", "TheOpponent"]
+        );
+    } else {
+        panic!("expected mapping");
+    }
+}
+
+#[test]
+fn forge_skills_claude_keys() {
+    let yaml = "\
+skills:
+    claude:
+        DebateCouncil:
+            scope: workspace
+        DeveloperCouncil:
+            scope: workspace
+        HiringCouncil:
+            scope: workspace
+    gemini:
+        - DebateCouncil
+        - DeveloperCouncil
+";
+    let f = temp_yaml(yaml);
+    let doc = first_doc(f.path().to_str().unwrap());
+    if let Some(Value::Mapping(map)) = walk(&doc, &parse_path(".skills.claude")) {
+        let keys: Vec<String> = map.keys().map(as_str).collect();
+        assert_eq!(
+            keys,
+            vec!["DebateCouncil", "DeveloperCouncil", "HiringCouncil"]
+        );
+    } else {
+        panic!("expected mapping");
+    }
+}
+
+#[test]
+fn forge_agent_nested_value() {
+    let yaml = "\
+agents:
+    SoftwareDeveloper:
+        model: fast
+        tools: Read, Grep, Glob
+";
+    let f = temp_yaml(yaml);
+    let doc = first_doc(f.path().to_str().unwrap());
+    let v = walk(&doc, &parse_path(".agents.SoftwareDeveloper.model")).unwrap();
+    assert_eq!(as_str(v), "fast");
+}
+
+// --- set ---
+
+fn set_raw(path: &str, at: &str, raw: &str, yaml: bool) {
+    let value = parse_value(raw, yaml).unwrap();
+    set_in_file(path, at, &value, Docs::default().into()).unwrap();
+}
+
+#[test]
+fn set_writes_typed_values_with_yaml() {
+    let f = temp_yaml("agents:\n  Dev:\n    model: fast\n");
+    let path = f.path().to_str().unwrap();
+    set_raw(path, ".agents.Dev.model", "strong", true);
+    set_raw(path, ".agents.Dev.tools", "[Read, Grep]", true);
+    set_raw(path, ".agents.Dev.enabled", "true", true);
+    let doc = first_doc(path);
+    let dev = walk(&doc, &parse_path(".agents.Dev")).unwrap();
+    assert_eq!(dev["model"], Value::from("strong"));
+    assert_eq!(
+        dev["tools"],
+        serde_yaml::from_str::<Value>("[Read, Grep]").unwrap()
+    );
+    assert_eq!(dev["enabled"], Value::Bool(true));
+}
+
+#[test]
+fn set_writes_strings_as_given() {
+    let f = temp_yaml("version: 1.9\nagents:\n  Dev:\n    description: x\n");
+    let path = f.path().to_str().unwrap();
+    set_raw(
+        path,
+        ".agents.Dev.description",
+        "USE WHEN: reviewing code",
+        false,
+    );
+    set_raw(path, ".version", "1.10", false);
+    set_raw(path, ".notes", "first line\nsecond line\n", false);
+    set_raw(path, ".enabled", "true", false);
+    let doc = first_doc(path);
+    let at = |p: &str| walk(&doc, &parse_path(p)).cloned();
+    assert_eq!(
+        at(".agents.Dev.description"),
+        Some(Value::from("USE WHEN: reviewing code"))
+    );
+    assert_eq!(at(".version"), Some(Value::from("1.10")));
+    assert_eq!(at(".notes"), Some(Value::from("first line\nsecond line\n")));
+    assert_eq!(at(".enabled"), Some(Value::from("true")));
+}
+
+#[test]
+fn set_rejects_invalid_yaml_values() {
+    assert!(parse_value("[unclosed", true).is_err());
+    assert_eq!(
+        parse_value("[unclosed", false),
+        Ok(Value::from("[unclosed"))
+    );
+}
+
+#[test]
+fn set_creates_missing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.yaml");
+    let path = path.to_str().unwrap();
+    set_in_file(
+        path,
+        ".user.root",
+        &Value::from("Vaults/Personal"),
+        Docs::default().into(),
+    )
+    .unwrap();
+    let doc = first_doc(path);
+    assert_eq!(
+        walk(&doc, &parse_path(".user.root")),
+        Some(&Value::from("Vaults/Personal"))
+    );
+}
+
+#[test]
+fn set_leaves_invalid_file_untouched() {
+    let f = temp_yaml("a: [unclosed\n");
+    let path = f.path().to_str().unwrap();
+    assert!(set_in_file(path, ".a", &Value::from("1"), Docs::default().into()).is_err());
+    assert_eq!(fs::read_to_string(path).unwrap(), "a: [unclosed\n");
+}
+
+#[test]
+fn set_and_delete_keep_comments() {
+    let original =
+        "# defaults\nname: forge  # listed\n---\n# second\nagents:\n  Dev: {}\n  Old: x\n";
+    let f = temp_yaml(original);
+    let path = f.path().to_str().unwrap();
+    set_in_file(path, ".name", &Value::from("anvil"), Docs::default().into()).unwrap();
+    assert!(delete_in_file(path, ".agents.Old", Docs::Index(1).into()).unwrap());
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "# defaults\nname: anvil  # listed\n---\n# second\nagents:\n  Dev: {}\n"
+    );
+}
+
+#[test]
+fn set_through_scalar_fails() {
+    let f = temp_yaml("name: test\n");
+    let err = set_in_file(
+        f.path().to_str().unwrap(),
+        ".name.first",
+        &Value::from("x"),
+        Docs::default().into(),
+    )
+    .unwrap_err();
+    assert!(err.contains(".name.first"));
+}
+
+// --- delete ---
+
+#[test]
+fn delete_removes_stale_agent() {
+    let f = temp_yaml("agents:\n  Dev:\n    model: fast\n  Stale:\n    model: strong\n");
+    let path = f.path().to_str().unwrap();
+    assert!(delete_in_file(path, ".agents.Stale", Docs::default().into()).unwrap());
+    let doc = first_doc(path);
+    let keys: Vec<_> = walk(&doc, &parse_path(".agents"))
+        .and_then(Value::as_mapping)
+        .unwrap()
+        .keys()
+        .map(as_str)
+        .collect();
+    assert_eq!(keys, ["Dev"]);
+}
+
+#[test]
+fn delete_missing_path_leaves_file_alone() {
+    let f = temp_yaml("# keep me\nname: test\n");
+    let path = f.path().to_str().unwrap();
+    assert!(!delete_in_file(path, ".other", Docs::default().into()).unwrap());
+    assert_eq!(fs::read_to_string(path).unwrap(), "# keep me\nname: test\n");
+    assert!(delete_in_file(path, ".", Docs::default().into()).is_err());
+}
+
+// --- merge ---
+
+#[test]
+fn merge_overlays_like_sidecar_config() {
+    let base = temp_yaml("agents:\n  Dev:\n    model: fast\n    tools: Read\nskills: [A]\n");
+    let overlay = temp_yaml("agents:\n  Dev:\n    model: strong\nskills: [B]\n");
+    let files = [
+        base.path().to_str().unwrap().to_string(),
+        overlay.path().to_str().unwrap().to_string(),
+    ];
+    let merged = merge_files(&files, Docs::default()).unwrap();
+    assert_eq!(
+        merged,
+        serde_yaml::from_str::<Value>(
+            "agents:\n  Dev:\n    model: strong\n    tools: Read\nskills: [B]\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn merge_skips_missing_and_rejects_invalid() {
+    let base = temp_yaml("name: test\n");
+    let base = base.path().to_str().unwrap().to_string();
+    let merged = merge_files(
+        &[base.clone(), "/nonexistent/config.yaml".into()],
+        Docs::default(),
+    )
+    .unwrap();
+    assert_eq!(merged["name"], Value::from("test"));
+
+    let bad = temp_yaml("a: [unclosed\n");
+    let bad = bad.path().to_str().unwrap().to_string();
+    assert!(merge_files(&[base, bad], Docs::default()).is_err());
+}
+
+// --- multi-document ---
+
+const MULTI: &str = "name: first\n---\nname: second\nextra: 1\n";
+
+#[test]
+fn load_selects_documents() {
+    let f = temp_yaml(MULTI);
+    let path = f.path().to_str().unwrap();
+    assert_eq!(first_doc(path)["name"], Value::from("first"));
+    assert_eq!(
+        load(path, opts(Docs::Index(1)))[0]["name"],
+        Value::from("second")
+    );
+    assert_eq!(load(path, opts(Docs::All)).len(), 2);
+    assert_eq!(
+        load(path, opts(Docs::Index(5))),
+        vec![Value::Mapping(Mapping::default())]
+    );
+}
+
+#[test]
+fn set_keeps_other_documents() {
+    let f = temp_yaml(MULTI);
+    let path = f.path().to_str().unwrap();
+    set_in_file(
+        path,
+        ".name",
+        &Value::from("changed"),
+        Docs::Index(1).into(),
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "name: first\n---\nname: changed\nextra: 1\n"
+    );
+    assert!(set_in_file(path, ".name", &Value::from("x"), Docs::Index(2).into()).is_err());
+
+    assert!(delete_in_file(path, ".name", Docs::All.into()).unwrap());
+    assert_eq!(fs::read_to_string(path).unwrap(), "{}\n---\nextra: 1\n");
+}
+
+#[test]
+fn merge_all_docs_folds_in_order() {
+    let f = temp_yaml(MULTI);
+    let merged = merge_files(&[f.path().to_str().unwrap().to_string()], Docs::All).unwrap();
+    assert_eq!(merged["name"], Value::from("second"));
+    assert_eq!(merged["extra"], Value::from(1));
+}
+
+#[test]
+fn doc_flags_are_split_out() {
+    let args: Vec<String> = ["get", "f.yaml", "--doc", "2", ".a"]
+        .map(String::from)
+        .to_vec();
+    let (opts, rest) = split_flags(&args).unwrap();
+    assert_eq!(opts.docs, Docs::Index(2));
+    assert_eq!(opts.style, Style::Plain);
+    assert_eq!(rest, ["get", "f.yaml", ".a"]);
+
+    let args = ["--all-docs", "--shell-quote", "keys"].map(String::from);
+    let (opts, _) = split_flags(&args).unwrap();
+    assert_eq!(opts.docs, Docs::All);
+    assert_eq!(opts.style, Style::ShellQuote);
+    assert!(split_flags(&["--doc".to_string(), "x".to_string()]).is_err());
+}
+
+// --- env ---
+
+#[test]
+fn env_flattens_and_quotes() {
+    let doc: Value = serde_yaml::from_str(
+        "name: forge-test\nuser:\n  root: Vaults/Personal\n  note: it's here\n\
+         events: [SessionStart, PreToolUse]\nversion: 2\nmissing:\n",
+    )
+    .unwrap();
+    assert_eq!(
+        env_lines(&doc, "FORGE_"),
+        [
+            "export FORGE_NAME='forge-test'",
+            "export FORGE_USER_ROOT='Vaults/Personal'",
+            r"export FORGE_USER_NOTE='it'\''s here'",
+            "export FORGE_EVENTS='SessionStart PreToolUse'",
+            "export FORGE_VERSION='2'",
+        ]
+    );
+}
+
+#[test]
+fn env_names_are_shell_safe() {
+    assert_eq!(env_name("claude-code.model"), "CLAUDE_CODE_MODEL");
+    assert_eq!(env_name("2fa"), "_2FA");
+    assert_eq!(shell_quote("$(rm -rf /)"), "'$(rm -rf /)'");
+}
+
+// --- diff ---
+
+#[test]
+fn diff_lines_mark_each_change() {
+    let old: Value = serde_yaml::from_str("name: a\nuser: {root: x}\ngone: 1\n").unwrap();
+    let new: Value = serde_yaml::from_str("name: b\nuser: {root: x}\nskills: [A]\n").unwrap();
+    assert_eq!(
+        diff_lines(&old, &new),
+        ["~ .name: a -> b", "- .gone: 1", r#"+ .skills: ["A"]"#]
+    );
+}
+
+// --- validate ---
+
+#[test]
+fn validate_reports_schema_violations() {
+    let schema = temp_yaml(
+        r#"{"type": "object", "properties": {"agents": {"type": "object",
+            "additionalProperties": {"required": ["model"]}}}}"#,
+    );
+    let schema = schema.path().to_str().unwrap();
+    let good = temp_yaml("agents:\n  Dev:\n    model: fast\n");
+    assert!(
+        validate_file(good.path().to_str().unwrap(), schema, Docs::default())
+            .unwrap()
+            .is_empty()
+    );
+
+    let bad = temp_yaml("agents:\n  Dev:\n    tools: Read\n---\nagents: []\n");
+    let bad = bad.path().to_str().unwrap();
+    assert_eq!(
+        validate_file(bad, schema, Docs::default()).unwrap(),
+        [".agents.Dev: missing required key `model`"]
+    );
+    assert_eq!(
+        validate_file(bad, schema, Docs::All).unwrap(),
+        [
+            "document 0: .agents.Dev: missing required key `model`",
+            "document 1: .agents: expected object, found array",
+        ]
+    );
+    assert!(validate_file("/nonexistent.yaml", schema, Docs::default()).is_err());
+}
+
+// --- exists ---
+
+#[test]
+fn exists_distinguishes_missing_from_empty() {
+    let f = temp_yaml("name: \"\"\nnothing:\nitems: [a]\n---\nsecond: 1\n");
+    let path = f.path().to_str().unwrap();
+    assert!(path_exists(path, ".name", opts(Docs::default())));
+    assert!(path_exists(path, ".nothing", opts(Docs::default())));
+    assert!(path_exists(path, ".items[-1]", opts(Docs::default())));
+    assert!(!path_exists(path, ".missing", opts(Docs::default())));
+    assert!(!path_exists(path, ".second", opts(Docs::default())));
+    assert!(path_exists(path, ".second", opts(Docs::All)));
+    assert!(!path_exists(
+        "/nonexistent.yaml",
+        ".",
+        opts(Docs::default())
+    ));
+}
+
+// --- paths ---
+
+#[test]
+fn path_lines_keep_one_leaf_per_line() {
+    let doc: Value =
+        serde_yaml::from_str("name: forge\ndesc: \"two\\nlines\"\nlist: []\nuser: {root: x}\n")
+            .unwrap();
+    assert_eq!(
+        path_lines(&doc),
+        [
+            ".name\tforge",
+            ".desc\t\"two\\nlines\"",
+            ".list\t[]",
+            ".user.root\tx",
+        ]
+    );
+}
+
+// --- shell quoting ---
+
+#[test]
+fn shown_quotes_only_when_asked() {
+    assert_eq!(shown("\"multi word\"", Style::Plain), "multi word");
+    assert_eq!(
+        shown("it's a\nmulti-line", Style::ShellQuote),
+        "'it'\\''s a\nmulti-line'"
+    );
+    assert_eq!(shown("", Style::ShellQuote), "''");
+}
+
+#[test]
+fn shown_keeps_or_escapes_multi_line_strings() {
+    let block = "first line\n\tsecond \\ line\n";
+    assert_eq!(shown(block, Style::Raw), block);
+    assert_eq!(
+        shown(block, Style::EscapeNewlines),
+        "first line\\n\\tsecond \\\\ line"
+    );
+    assert_eq!(shown(block, Style::Plain), "first line\n\tsecond \\ line");
+}
+
+// --- strict ---
+
+#[test]
+fn try_load_reports_what_load_hides() {
+    let f = temp_yaml("a: [unclosed\n");
+    let err = try_load(f.path().to_str().unwrap(), opts(Docs::default())).unwrap_err();
+    assert!(err.contains(f.path().to_str().unwrap()));
+    assert!(try_load("/nonexistent.yaml", opts(Docs::default())).is_err());
+
+    let f = temp_yaml("a: 1\n---\nb: 2\n");
+    let path = f.path().to_str().unwrap();
+    assert_eq!(try_load(path, opts(Docs::Index(1))).unwrap().len(), 1);
+    assert_eq!(
+        try_load(path, opts(Docs::Index(2))).unwrap_err(),
+        format!("{path}: no such document (2 in file)")
+    );
+    let empty = temp_yaml("");
+    assert!(try_load(empty.path().to_str().unwrap(), opts(Docs::default())).is_ok());
+}
+
+// --- aliases ---
+
+#[test]
+fn aliases_resolve_by_default_or_stay_visible() {
+    let f = temp_yaml(
+        "base: &base\n  model: fast\nroster: &roster [Dev, Qa]\nDev:\n  <<: *base\n  tools: Read\nteam: *roster\n",
+    );
+    let path = f.path().to_str().unwrap();
+    let doc = first_doc(path);
+    assert_eq!(doc["Dev"]["model"], Value::from("fast"));
+    assert_eq!(doc["team"][1], Value::from("Qa"));
+
+    let keep = Options {
+        aliases: Aliases::Keep,
+        ..Options::default()
+    };
+    let doc = load(path, keep).remove(0);
+    assert_eq!(doc["Dev"]["<<"], Value::from("*base"));
+    assert!(doc["Dev"].get("model").is_none());
+    assert_eq!(doc["team"], Value::from("*roster"));
+    assert_eq!(doc["roster"][0], Value::from("Dev"));
+}
+
+// --- convert ---
+
+#[test]
+fn convert_writes_json_and_toml() {
+    let f = temp_yaml(
+        "name: forge\ntags: [a, b]\nskip: null\nagents:\n  Dev: {model: fast}\nroster:\n  - role: lead\n  - role: \"say \\\"hi\\\"\"\n",
+    );
+    let path = f.path().to_str().unwrap();
+    let json = convert_file(path, None, "json", opts(Docs::default())).unwrap();
+    assert!(json.starts_with("{\n  \"name\": \"forge\",\n"));
+    assert_eq!(
+        convert_file(path, None, "toml", opts(Docs::default())).unwrap(),
+        "name = \"forge\"\ntags = [\"a\", \"b\"]\n\
+         \n[agents.Dev]\nmodel = \"fast\"\n\
+         \n[[roster]]\nrole = \"lead\"\n\n[[roster]]\nrole = \"say \\\"hi\\\"\"\n"
+    );
+    assert!(convert_file(path, None, "xml", opts(Docs::default())).is_err());
+}
+
+#[test]
+fn convert_handles_several_documents() {
+    let f = temp_yaml("a: 1\n---\nb: 2\n");
+    let path = f.path().to_str().unwrap();
+    assert_eq!(
+        convert_file(path, None, "yaml", opts(Docs::All)).unwrap(),
+        "a: 1\n---\nb: 2\n"
+    );
+    assert_eq!(
+        convert_file(path, None, "json", opts(Docs::All)).unwrap(),
+        "[\n  {\n    \"a\": 1\n  },\n  {\n    \"b\": 2\n  }\n]\n"
+    );
+    assert!(convert_file(path, None, "toml", opts(Docs::All)).is_err());
+    assert_eq!(
+        convert_file(path, None, "toml", opts(Docs::Index(1))).unwrap(),
+        "b = 2\n"
+    );
+}
+
+#[test]
+fn convert_reads_toml() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(
+        &path,
+        "name = \"forge\"\nversion = 2\nwhen = 2024-01-02\n\n[agents.Dev]\nmodel = \"fast\"\n\n[[roster]]\nrole = \"lead\"\n",
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+    assert_eq!(
+        convert_file(path, None, "yaml", opts(Docs::default())).unwrap(),
+        "name: forge\nversion: 2\nwhen: 2024-01-02\nagents:\n  Dev:\n    model: fast\nroster:\n- role: lead\n"
+    );
+    assert_eq!(
+        convert_file(path, None, "json", opts(Docs::default())).unwrap(),
+        "{\n  \"name\": \"forge\",\n  \"version\": 2,\n  \"when\": \"2024-01-02\",\n  \"agents\": {\n    \"Dev\": {\n      \"model\": \"fast\"\n    }\n  },\n  \"roster\": [\n    {\n      \"role\": \"lead\"\n    }\n  ]\n}\n"
+    );
+
+    let yaml = temp_yaml("a = 1\n");
+    let yaml = yaml.path().to_str().unwrap();
+    assert_eq!(
+        convert_file(yaml, Some("toml"), "json", opts(Docs::default())).unwrap(),
+        "{\n  \"a\": 1\n}\n"
+    );
+    assert!(convert_file(yaml, Some("xml"), "json", opts(Docs::default())).is_err());
+}
+
+// --- sort-keys ---
+
+#[test]
+fn sort_keys_rewrites_only_when_out_of_order() {
+    let f = temp_yaml("# config\nname: forge\nagents: {}\n");
+    let path = f.path().to_str().unwrap();
+    assert!(sort_keys_in_file(path, ".", Docs::default().into()).unwrap());
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "agents: {}\n# config\nname: forge\n"
+    );
+    assert!(!sort_keys_in_file(path, ".", Docs::default().into()).unwrap());
+}
+
+// --- batch queries ---
+
+#[test]
+fn paths_and_default_splits_a_trailing_default() {
+    let args = |xs: &[&str]| xs.iter().map(|x| (*x).to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        paths_and_default(&args(&[".a", ".b", "none"])),
+        (vec![".a", ".b"], Some("none"))
+    );
+    assert_eq!(
+        paths_and_default(&args(&[".a", ".b"])),
+        (vec![".a", ".b"], None)
+    );
+    assert_eq!(paths_and_default(&args(&["a"])), (vec!["a"], None));
+    assert_eq!(
+        paths_and_default(&args(&["a", "x"])),
+        (vec!["a"], Some("x"))
+    );
+}
+
+#[test]
+fn batch_field_joins_list_items_and_falls_back() {
+    let doc: Value = serde_yaml::from_str("tags: [a, b]\nname: forge\nmeta: {x: 1}\n").unwrap();
+    let field = |path: &str| batch_field(&walk_all(&doc, &parse_path(path)), "-", Style::Plain);
+    assert_eq!(field(".tags"), "a b");
+    assert_eq!(field(".name"), "forge");
+    assert_eq!(field(".meta"), "-");
+    assert_eq!(field(".missing"), "-");
+}
+
+// --- globs ---
+
+#[test]
+fn glob_matches_stars_and_question_marks() {
+    let matches = |p: &str, n: &str| {
+        glob_matches(
+            &p.chars().collect::<Vec<_>>(),
+            &n.chars().collect::<Vec<_>>(),
+        )
+    };
+    assert!(matches("*.yaml", "module.yaml"));
+    assert!(matches("mod?le.*", "module.yml"));
+    assert!(matches("*", ""));
+    assert!(!matches("*.yaml", "module.yml"));
+    assert!(!matches("?", ""));
+}
+
+#[test]
+fn expand_glob_lists_matching_files_sorted() {
+    let dir = tempfile::tempdir().unwrap();
+    for module in ["beta", "alpha", ".hidden"] {
+        fs::create_dir(dir.path().join(module)).unwrap();
+        fs::write(dir.path().join(module).join("module.yaml"), "name: x\n").unwrap();
+    }
+    fs::create_dir(dir.path().join("empty")).unwrap();
+    let root = dir.path().to_str().unwrap();
+    assert_eq!(
+        expand_glob(&format!("{root}/*/module.yaml")),
+        [
+            format!("{root}/alpha/module.yaml"),
+            format!("{root}/beta/module.yaml"),
+        ]
+    );
+    assert!(is_glob(&format!("{root}/*/module.yaml")));
+    assert!(!is_glob(&format!("{root}/alpha/module.yaml")));
+}
+
+// --- envsubst ---
+
+#[test]
+fn expand_vars_fills_placeholders_and_reports_unset_ones() {
+    let lookup = |name: &str| match name {
+        "HOME" => Some("/home/forge".to_string()),
+        "EMPTY" => Some(String::new()),
+        _ => None,
+    };
+    let mut missing = Vec::new();
+    assert_eq!(
+        expand_vars("${HOME}/.claude", &lookup, &mut missing),
+        "/home/forge/.claude"
+    );
+    assert_eq!(
+        expand_vars("${EMPTY:-x} ${UNSET:-y} ${UNSET}!", &lookup, &mut missing),
+        "x y !"
+    );
+    assert_eq!(
+        expand_vars("cost: $5 ${open", &lookup, &mut missing),
+        "cost: $5 ${open"
+    );
+    assert_eq!(missing, ["UNSET"]);
+}
+
+#[test]
+fn substitute_expands_nested_string_values_only() {
+    let mut doc: Value =
+        serde_yaml::from_str("${KEY}: [\"${V}\", 1]\nnested: {path: \"${V}/bin\"}\n").unwrap();
+    let lookup = |_: &str| Some("v".to_string());
+    substitute(&mut doc, &lookup, &mut Vec::new());
+    assert_eq!(
+        serde_yaml::to_string(&doc).unwrap(),
+        "${KEY}:\n- v\n- 1\nnested:\n  path: v/bin\n"
+    );
+}
+
+// --- format ---
+
+#[test]
+fn format_row_fills_named_fields_once() {
+    let fields = [("key", "name"), ("value", "{key}")];
+    assert_eq!(format_row("{key}: {value}", &fields), "name: {key}");
+    assert_eq!(format_row("{key}\\t{other} {", &fields), "name\t{other} {");
+}
+
+#[test]
+fn take_flag_keeps_the_last_value() {
+    let (value, rest) = take_flag(
+        "map",
+        "--format",
+        vec!["f", "--format", "a", ".p", "--format", "b"],
+    );
+    assert_eq!(value, Some("b"));
+    assert_eq!(rest, ["f", ".p"]);
+}
+
+// --- count ---
+
+#[test]
+fn count_children_applies_the_filter() {
+    let doc: Value = serde_yaml::from_str(
+        "agents:\n  - {name: Dev, model: fast}\n  - {name: Ops, model: strong}\n  - {name: Sec, model: fast}\n\
+         skills: {A: 1, B: 2}\n",
+    )
+    .unwrap();
+    let found = |path: &str| walk_all(&doc, &parse_path(path));
+    assert_eq!(count_children(&found(".agents"), &PathSegment::Wildcard), 3);
+    assert_eq!(count_children(&found(".skills"), &PathSegment::Wildcard), 2);
+    let fast = parse_path("[?model=fast]").pop().unwrap();
+    assert_eq!(count_children(&found(".agents"), &fast), 2);
+    assert_eq!(count_children(&found(".missing"), &fast), 0);
+}
+
+// --- in-place ---
+
+#[test]
+fn take_in_place_reads_an_attached_suffix() {
+    let args = |xs: &[&str]| xs.iter().map(|x| (*x).to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        take_in_place(&args(&["-i", "f.yaml", ".a", "1"])),
+        (None, vec!["f.yaml", ".a", "1"])
+    );
+    assert_eq!(
+        take_in_place(&args(&["-i.bak", "f.yaml", ".a"])),
+        (Some(".bak"), vec!["f.yaml", ".a"])
+    );
+    assert_eq!(
+        take_in_place(&args(&["f.yaml", ".a", "--in-place=~"])),
+        (Some("~"), vec!["f.yaml", ".a"])
+    );
+    assert_eq!(
+        take_in_place(&args(&["f.yaml", ".a", "-inf"])),
+        (None, vec!["f.yaml", ".a", "-inf"])
+    );
+}
+
+#[test]
+fn backup_keeps_the_original_before_an_edit() {
+    let f = temp_yaml("# config\nname: forge\n");
+    let path = f.path().to_str().unwrap();
+    backup(path, Some(".bak")).unwrap();
+    set_in_file(path, ".name", &Value::from("lib"), Docs::default().into()).unwrap();
+    let copy = format!("{path}.bak");
+    assert_eq!(
+        fs::read_to_string(&copy).unwrap(),
+        "# config\nname: forge\n"
+    );
+    assert_eq!(fs::read_to_string(path).unwrap(), "# config\nname: lib\n");
+    fs::remove_file(copy).unwrap();
+    assert!(backup("/nonexistent/forge.yaml", Some(".bak")).is_ok());
+}
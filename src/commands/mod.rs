@@ -0,0 +1,247 @@
+use crate::deploy::provider::Provider;
+use crate::deploy::DeployResult;
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use crate::toml_fmt;
+use crate::vfs::RealFs;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Parsed `commands/<name>.md`. The command name is the file stem, which is
+/// also what users type after the slash.
+pub struct CommandMeta {
+    pub name: String,
+    pub description: String,
+    pub argument_hint: Option<String>,
+    pub allowed_tools: Option<String>,
+    pub model: Option<String>,
+    pub source: String,
+}
+
+// ─── Targets ───
+
+/// Where a provider reads custom commands. Codex only reads prompts from
+/// `~/.codex/prompts`, so it is user-scoped whatever `scope` says, and
+/// Copilot only reads prompt files from `.github/prompts`.
+pub fn command_dir(provider: &Provider, scope: &str, home: &Path) -> Result<PathBuf, String> {
+    let rel = match provider {
+        Provider::Claude => ".claude/commands",
+        Provider::Gemini => ".gemini/commands",
+        Provider::Codex => return Ok(home.join(".codex/prompts")),
+        Provider::OpenCode => ".opencode/command",
+        Provider::Cursor => ".cursor/commands",
+        Provider::Windsurf => ".windsurf/workflows",
+        Provider::Copilot if scope == "user" => {
+            return Err(
+                "Copilot reads prompt files only from the workspace: use --scope workspace".into(),
+            )
+        }
+        Provider::Copilot => ".github/prompts",
+        Provider::Zed => return Err("Zed reads no custom command files".into()),
+        Provider::Custom(custom) => {
+            return Err(format!("{} reads no custom command files", custom.name))
+        }
+    };
+    match scope {
+        "user" => Ok(home.join(rel)),
+        "workspace" => Ok(PathBuf::from(rel)),
+        other => Err(format!(
+            "invalid scope {other:?} for commands: use user or workspace"
+        )),
+    }
+}
+
+pub fn command_extension(provider: &Provider) -> &'static str {
+    match provider {
+        Provider::Gemini => "toml",
+        Provider::Copilot => "prompt.md",
+        Provider::Claude
+        | Provider::Codex
+        | Provider::OpenCode
+        | Provider::Cursor
+        | Provider::Windsurf
+        | Provider::Zed
+        | Provider::Custom(_) => "md",
+    }
+}
+
+pub fn validate_command_name(name: &str) -> Result<(), String> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "command name {name:?} must be letters, digits, '-' or '_'"
+        ))
+    }
+}
+
+// ─── Formatting ───
+
+pub fn extract_command_meta(content: &str, filename: &str, source_prefix: &str) -> CommandMeta {
+    let name = filename.trim_end_matches(".md").to_string();
+    let source = if source_prefix.is_empty() {
+        filename.to_string()
+    } else {
+        format!("{source_prefix}/{filename}")
+    };
+    CommandMeta {
+        description: parse::fm_value(content, "description").unwrap_or_else(|| name.clone()),
+        argument_hint: parse::fm_value(content, "argument-hint"),
+        allowed_tools: parse::fm_value(content, "allowed-tools"),
+        model: parse::fm_value(content, "model"),
+        name,
+        source,
+    }
+}
+
+pub fn format_command(meta: &CommandMeta, body: &str, provider: &Provider) -> String {
+    let mut out = String::new();
+    let body = body.trim_start_matches('\n');
+
+    if *provider == Provider::Gemini {
+        let _ = writeln!(out, "# source: {}", meta.source);
+        let _ = writeln!(out, "description = {}", toml_fmt::string(&meta.description));
+        let prompt = body.replace("$ARGUMENTS", "{{args}}");
+        let _ = writeln!(
+            out,
+            "prompt = \"\"\"\n{}\"\"\"",
+            toml_fmt::escape_multiline(&prompt)
+        );
+        return out;
+    }
+
+    out.push_str("---\n");
+    let _ = writeln!(out, "description: {}", meta.description);
+    if let Some(ref hint) = meta.argument_hint {
+        let _ = writeln!(out, "argument-hint: {hint}");
+    }
+    if *provider == Provider::Claude {
+        if let Some(ref tools) = meta.allowed_tools {
+            let _ = writeln!(out, "allowed-tools: {tools}");
+        }
+    }
+    if matches!(provider, Provider::Claude | Provider::OpenCode) {
+        if let Some(ref model) = meta.model {
+            let _ = writeln!(out, "model: {model}");
+        }
+    }
+    let _ = writeln!(out, "source: {}", meta.source);
+    out.push_str("---\n");
+    out.push_str(body);
+    if !body.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+// ─── Deploy ───
+
+pub fn deploy_command(
+    content: &str,
+    filename: &str,
+    dst_dir: &Path,
+    provider: &Provider,
+    dry_run: bool,
+    source_prefix: &str,
+) -> Result<DeployResult, String> {
+    let meta = extract_command_meta(content, filename, source_prefix);
+    validate_command_name(&meta.name)?;
+
+    let out_path = dst_dir.join(format!("{}.{}", meta.name, command_extension(provider)));
+    if out_path.is_symlink() {
+        return Err(format!("destination is a symlink: {}", out_path.display()));
+    }
+    if out_path.exists() {
+        let existing = std::fs::read_to_string(&out_path)
+            .map_err(|e| format!("failed to read {}: {e}", out_path.display()))?;
+        if !parse::is_synced_from(&existing, filename) {
+            return Ok(DeployResult::SkippedUserOwned);
+        }
+    }
+
+    let output = format_command(&meta, parse::fm_body(content), provider);
+    if !dry_run {
+        std::fs::create_dir_all(dst_dir)
+            .map_err(|e| format!("failed to create {}: {e}", dst_dir.display()))?;
+        std::fs::write(&out_path, output)
+            .map_err(|e| format!("failed to write {}: {e}", out_path.display()))?;
+    }
+    Ok(DeployResult::Deployed)
+}
+
+/// Deploy every allowlisted `*.md` in `src_dir`. A command is deployed for a
+/// provider only when listed under `commands.<provider>` in the sidecar
+/// config, the same opt-in rule skills follow.
+pub fn deploy_commands_from_dir(
+    src_dir: &Path,
+    dst_dir: &Path,
+    provider: &Provider,
+    config: &SidecarConfig,
+    dry_run: bool,
+    source_prefix: &str,
+) -> Result<Vec<(String, DeployResult)>, String> {
+    if !src_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(src_dir)
+        .map_err(|e| format!("failed to read {}: {e}", src_dir.display()))?;
+    let mut files: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    files.sort_by_key(std::fs::DirEntry::file_name);
+
+    let allowed = config.provider_commands(provider.as_str());
+    let mut results = Vec::new();
+    for entry in files {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        let name = filename.trim_end_matches(".md").to_string();
+        if !allowed.contains(&name) {
+            continue;
+        }
+        let path = entry.path();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let result = deploy_command(
+            &content,
+            &filename,
+            dst_dir,
+            provider,
+            dry_run,
+            source_prefix,
+        )?;
+        results.push((name, result));
+    }
+    Ok(results)
+}
+
+/// Remove commands a module deployed earlier (per its manifest entry) that
+/// are not in `current`.
+pub fn clean_orphaned_commands(
+    dst_dir: &Path,
+    module_name: &str,
+    current: &[String],
+    provider: &Provider,
+    dry_run: bool,
+) -> Result<Vec<String>, String> {
+    crate::manifest::remove_orphans(
+        &RealFs,
+        dst_dir,
+        module_name,
+        current,
+        command_extension(provider),
+        dry_run,
+    )
+    .map_err(String::from)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,162 @@
+use super::*;
+use crate::vfs::RealFs;
+use std::fs;
+use tempfile::TempDir;
+
+const REVIEW: &str = "---\ndescription: Review a change\nargument-hint: <path>\n\
+                      allowed-tools: Read, Grep\nmodel: sonnet\n---\n\nReview $ARGUMENTS carefully.\n";
+
+fn allow(dir: &Path, provider: &str, name: &str) -> SidecarConfig {
+    fs::write(
+        dir.join("defaults.yaml"),
+        format!("commands:\n  {provider}:\n    {name}: {{}}\n"),
+    )
+    .unwrap();
+    SidecarConfig::load(dir)
+}
+
+// ─── command_dir ───
+
+#[test]
+fn dirs_per_provider() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        command_dir(&Provider::Claude, "workspace", home).unwrap(),
+        PathBuf::from(".claude/commands")
+    );
+    assert_eq!(
+        command_dir(&Provider::Gemini, "user", home).unwrap(),
+        PathBuf::from("/home/u/.gemini/commands")
+    );
+    assert_eq!(
+        command_dir(&Provider::Codex, "workspace", home).unwrap(),
+        PathBuf::from("/home/u/.codex/prompts")
+    );
+    assert!(command_dir(&Provider::Claude, "all", home).is_err());
+}
+
+#[test]
+fn copilot_commands_are_workspace_prompt_files() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        command_dir(&Provider::Copilot, "workspace", home).unwrap(),
+        PathBuf::from(".github/prompts")
+    );
+    assert!(command_dir(&Provider::Copilot, "user", home).is_err());
+    assert_eq!(command_extension(&Provider::Copilot), "prompt.md");
+}
+
+#[test]
+fn command_names() {
+    assert!(validate_command_name("review").is_ok());
+    assert!(validate_command_name("fix-pr_2").is_ok());
+    assert!(validate_command_name("-x").is_err());
+    assert!(validate_command_name("a b").is_err());
+}
+
+// ─── format_command ───
+
+#[test]
+fn format_claude_keeps_frontmatter() {
+    let meta = extract_command_meta(REVIEW, "review.md", "forge-demo/commands");
+    let out = format_command(&meta, parse::fm_body(REVIEW), &Provider::Claude);
+    assert!(out.starts_with("---\ndescription: Review a change\n"));
+    assert!(out.contains("argument-hint: <path>\n"));
+    assert!(out.contains("allowed-tools: Read, Grep\n"));
+    assert!(out.contains("source: forge-demo/commands/review.md\n"));
+    assert!(out.ends_with("---\nReview $ARGUMENTS carefully.\n"));
+}
+
+#[test]
+fn format_codex_drops_claude_only_fields() {
+    let meta = extract_command_meta(REVIEW, "review.md", "");
+    let out = format_command(&meta, parse::fm_body(REVIEW), &Provider::Codex);
+    assert!(!out.contains("allowed-tools"));
+    assert!(!out.contains("model:"));
+    assert!(out.contains("argument-hint: <path>\n"));
+}
+
+#[test]
+fn format_gemini_toml() {
+    let meta = extract_command_meta(REVIEW, "review.md", "");
+    let out = format_command(&meta, parse::fm_body(REVIEW), &Provider::Gemini);
+    assert!(out.starts_with("# source: review.md\n"));
+    assert!(out.contains("description = \"Review a change\"\n"));
+    assert!(out.contains("prompt = \"\"\"\nReview {{args}} carefully.\n\"\"\"\n"));
+    assert!(parse::is_synced_from(&out, "review.md"));
+}
+
+#[test]
+fn format_gemini_escapes_triple_quotes() {
+    let meta = extract_command_meta("x", "q.md", "");
+    let out = format_command(&meta, "say \"\"\" and \\n\n", &Provider::Gemini);
+    assert!(out.contains("say \"\"\\\" and \\\\n"));
+}
+
+// ─── deploy ───
+
+#[test]
+fn deploy_only_allowlisted() {
+    let root = TempDir::new().unwrap();
+    let src = root.path().join("commands");
+    let dst = root.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("review.md"), REVIEW).unwrap();
+    fs::write(src.join("other.md"), REVIEW).unwrap();
+    let config = allow(root.path(), "claude", "review");
+
+    let results =
+        deploy_commands_from_dir(&src, &dst, &Provider::Claude, &config, false, "").unwrap();
+    assert_eq!(
+        results,
+        vec![("review".to_string(), DeployResult::Deployed)]
+    );
+    assert!(dst.join("review.md").is_file());
+    assert!(!dst.join("other.md").exists());
+}
+
+#[test]
+fn deploy_skips_user_owned() {
+    let root = TempDir::new().unwrap();
+    let src = root.path().join("commands");
+    let dst = root.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("review.md"), REVIEW).unwrap();
+    fs::write(dst.join("review.toml"), "prompt = \"mine\"\n").unwrap();
+    let config = allow(root.path(), "gemini", "review");
+
+    let results =
+        deploy_commands_from_dir(&src, &dst, &Provider::Gemini, &config, false, "").unwrap();
+    assert_eq!(results[0].1, DeployResult::SkippedUserOwned);
+    assert_eq!(
+        fs::read_to_string(dst.join("review.toml")).unwrap(),
+        "prompt = \"mine\"\n"
+    );
+}
+
+#[test]
+fn orphans_removed_via_manifest() {
+    let dst = TempDir::new().unwrap();
+    fs::write(dst.path().join("old.md"), "x").unwrap();
+    fs::write(dst.path().join("keep.md"), "x").unwrap();
+    crate::manifest::update(
+        &RealFs,
+        dst.path(),
+        "forge-demo",
+        &["old".into(), "keep".into()],
+    )
+    .unwrap();
+
+    let removed = clean_orphaned_commands(
+        dst.path(),
+        "forge-demo",
+        &["keep".to_string()],
+        &Provider::Claude,
+        false,
+    )
+    .unwrap();
+    assert_eq!(removed, vec!["old"]);
+    assert!(!dst.path().join("old.md").exists());
+    assert!(dst.path().join("keep.md").exists());
+}
@@ -0,0 +1,197 @@
+//! Council skills generated from the `councils:` roster in defaults.yaml.
+//!
+//! A council is a named list of role agents. Its SKILL.md (roster table,
+//! gate check, dispatch instructions, sequential fallback) and SKILL.yaml
+//! are rendered from the roster through the `council-skill.*` templates, so
+//! the skill cannot drift from the roster it describes.
+//!
+//! ```yaml
+//! councils:
+//!   Review:
+//!     description: Multi-perspective code review
+//!     roles: [Architect, SecurityReviewer, Tester]
+//! ```
+
+use crate::deploy::{self, provider::Provider, AgentMeta};
+use crate::sidecar::SidecarConfig;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// `generation.method` in SKILL.yaml of a generated council skill.
+pub const GENERATION_METHOD: &str = "generated-from-council";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Council {
+    pub name: String,
+    pub description: String,
+    pub argument_hint: String,
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CouncilSkill {
+    pub name: String,
+    pub skill_md: String,
+    pub skill_yaml: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CouncilResult {
+    Written,
+    Unchanged,
+    SkippedUserOwned,
+}
+
+/// Councils declared under `councils:`, in file order.
+pub fn load_councils(config: &SidecarConfig) -> Vec<Council> {
+    config
+        .councils()
+        .into_iter()
+        .map(|name| {
+            let roles = config.council_list(&name, "roles");
+            let description = config
+                .council_value(&name, "description")
+                .unwrap_or_else(|| format!("Convene the {name} council: {}", roles.join(", ")));
+            let argument_hint = config
+                .council_value(&name, "argument_hint")
+                .unwrap_or_else(|| format!("[topic, files, or question for the {name} council]"));
+            Council {
+                name,
+                description,
+                argument_hint,
+                roles,
+            }
+        })
+        .collect()
+}
+
+/// Claude-side metadata of every agent in `agents_dir`, by agent name.
+fn agent_metas(agents_dir: &Path, config: &SidecarConfig) -> BTreeMap<String, AgentMeta> {
+    let Ok(entries) = std::fs::read_dir(agents_dir) else {
+        return BTreeMap::new();
+    };
+    let mut metas = BTreeMap::new();
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let filename = entry.file_name().to_string_lossy().to_string();
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        if let Some(meta) =
+            deploy::extract_agent_meta(&content, &filename, &Provider::Claude, config, "")
+        {
+            metas.insert(meta.name.clone(), meta);
+        }
+    }
+    metas
+}
+
+/// One line of a Markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// Render `council`'s SKILL.md and SKILL.yaml. Every role must be an agent
+/// in `agents_dir`.
+pub fn render_council_skill(
+    council: &Council,
+    agents_dir: &Path,
+    config: &SidecarConfig,
+) -> Result<CouncilSkill, String> {
+    if council.roles.is_empty() {
+        return Err(format!("council {} has no roles", council.name));
+    }
+    let metas = agent_metas(agents_dir, config);
+    let mut roles = Vec::new();
+    for role in &council.roles {
+        let meta = metas.get(role).ok_or_else(|| {
+            format!(
+                "council {}: role {role} has no agent in {}",
+                council.name,
+                agents_dir.display()
+            )
+        })?;
+        roles.push(serde_json::json!({
+            "name": role,
+            "model": meta.model,
+            "focus": table_cell(&meta.description),
+        }));
+    }
+
+    let context = serde_json::json!({
+        "name": council.name,
+        "description": council.description,
+        "argument_hint": council.argument_hint,
+        "roles": roles,
+    });
+    let templates = config.templates();
+    Ok(CouncilSkill {
+        name: council.name.clone(),
+        skill_md: templates.render("council-skill.md", &context)?,
+        skill_yaml: templates.render("council-skill.yaml", &context)?,
+    })
+}
+
+/// Render every council of the module at `module_root`.
+pub fn generate_council_skills(
+    module_root: &Path,
+    config: &SidecarConfig,
+) -> Result<Vec<CouncilSkill>, String> {
+    let agents_dir = module_root.join("agents");
+    load_councils(config)
+        .iter()
+        .map(|council| render_council_skill(council, &agents_dir, config))
+        .collect()
+}
+
+/// Whether `skill_dir` is absent or holds a generated council skill, i.e.
+/// may be (re)written.
+pub fn is_generated(skill_dir: &Path) -> bool {
+    if !skill_dir.join("SKILL.md").exists() {
+        return true;
+    }
+    let Some(yaml) = crate::sidecar::load_yaml_file(&skill_dir.join("SKILL.yaml")) else {
+        return false;
+    };
+    yaml.get("generation")
+        .and_then(|g| g.get("method"))
+        .and_then(serde_yaml::Value::as_str)
+        == Some(GENERATION_METHOD)
+}
+
+/// Write `skill` into `skills_dir/<name>/`, leaving user-owned skills alone.
+/// With `dry_run`, reports `Written` when the files on disk are stale.
+pub fn write_council_skill(
+    skills_dir: &Path,
+    skill: &CouncilSkill,
+    dry_run: bool,
+) -> Result<CouncilResult, String> {
+    let dir = skills_dir.join(&skill.name);
+    if !is_generated(&dir) {
+        return Ok(CouncilResult::SkippedUserOwned);
+    }
+    let md_path = dir.join("SKILL.md");
+    let yaml_path = dir.join("SKILL.yaml");
+    let current = |path: &Path| std::fs::read_to_string(path).unwrap_or_default();
+    if current(&md_path) == skill.skill_md && current(&yaml_path) == skill.skill_yaml {
+        return Ok(CouncilResult::Unchanged);
+    }
+    if !dry_run {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+        for (path, content) in [(&md_path, &skill.skill_md), (&yaml_path, &skill.skill_yaml)] {
+            std::fs::write(path, content)
+                .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+        }
+    }
+    Ok(CouncilResult::Written)
+}
+
+#[cfg(test)]
+mod tests;
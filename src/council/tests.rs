@@ -0,0 +1,110 @@
+use super::*;
+use tempfile::TempDir;
+
+fn module(defaults: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("defaults.yaml"), defaults).unwrap();
+    let agents = dir.path().join("agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    for (name, description) in [
+        ("Critic", "Finds flaws | risks"),
+        ("Tester", "Writes tests"),
+    ] {
+        std::fs::write(
+            agents.join(format!("{name}.md")),
+            format!("---\nname: {name}\ndescription: {description}\n---\nBody\n"),
+        )
+        .unwrap();
+    }
+    dir
+}
+
+const DEFAULTS: &str = "agents:\n  Critic:\n    model: strong\n  Tester:\n    model: fast\ncouncils:\n  Review:\n    description: Code review council\n    roles:\n      - Critic\n      - Tester\n";
+
+#[test]
+fn loads_councils_with_defaults() {
+    let dir = module("councils:\n  Review:\n    roles: [Critic]\n");
+    let councils = load_councils(&SidecarConfig::load(dir.path()));
+    assert_eq!(councils.len(), 1);
+    assert_eq!(councils[0].roles, vec!["Critic"]);
+    assert_eq!(
+        councils[0].description,
+        "Convene the Review council: Critic"
+    );
+    assert!(councils[0].argument_hint.contains("Review council"));
+}
+
+#[test]
+fn renders_roster_gate_and_fallback() {
+    let dir = module(DEFAULTS);
+    let config = SidecarConfig::load(dir.path());
+    let skills = generate_council_skills(dir.path(), &config).unwrap();
+    assert_eq!(skills.len(), 1);
+    let md = &skills[0].skill_md;
+    assert!(md.starts_with("---\nname: Review\ndescription: Code review council\n"));
+    assert!(md.contains("| Critic | opus | Finds flaws \\| risks |\n"));
+    assert!(md.contains("| Tester | sonnet | Writes tests |\n"));
+    assert!(md.contains("## Gate Check"));
+    assert!(md.contains("- [ ] `Tester`"));
+    assert!(md.contains("SendMessage"));
+    assert!(md.contains("## Sequential Fallback"));
+    assert!(skills[0]
+        .skill_yaml
+        .contains("method: generated-from-council\n"));
+    assert!(skills[0]
+        .skill_yaml
+        .contains("    - Critic\n    - Tester\n"));
+}
+
+#[test]
+fn unknown_role_is_an_error() {
+    let dir = module("councils:\n  Review:\n    roles: [Critic, Ghost]\n");
+    let err = generate_council_skills(dir.path(), &SidecarConfig::load(dir.path())).unwrap_err();
+    assert!(err.contains("role Ghost has no agent"), "{err}");
+}
+
+#[test]
+fn write_is_idempotent_and_spares_user_skills() {
+    let dir = module(DEFAULTS);
+    let config = SidecarConfig::load(dir.path());
+    let skill = generate_council_skills(dir.path(), &config)
+        .unwrap()
+        .remove(0);
+    let skills_dir = dir.path().join("skills");
+
+    assert_eq!(
+        write_council_skill(&skills_dir, &skill, true).unwrap(),
+        CouncilResult::Written
+    );
+    assert!(!skills_dir.exists());
+    assert_eq!(
+        write_council_skill(&skills_dir, &skill, false).unwrap(),
+        CouncilResult::Written
+    );
+    assert_eq!(
+        write_council_skill(&skills_dir, &skill, false).unwrap(),
+        CouncilResult::Unchanged
+    );
+
+    let user = skills_dir.join("Review");
+    std::fs::write(user.join("SKILL.yaml"), "name: Review\n").unwrap();
+    assert_eq!(
+        write_council_skill(&skills_dir, &skill, false).unwrap(),
+        CouncilResult::SkippedUserOwned
+    );
+}
+
+#[test]
+fn template_override_applies() {
+    let dir = module(DEFAULTS);
+    let templates = dir.path().join("templates");
+    std::fs::create_dir_all(&templates).unwrap();
+    std::fs::write(
+        templates.join("council-skill.md.j2"),
+        "{% for role in roles %}{{ role.name }};{% endfor %}",
+    )
+    .unwrap();
+    let config = SidecarConfig::load(dir.path());
+    let skills = generate_council_skills(dir.path(), &config).unwrap();
+    assert_eq!(skills[0].skill_md, "Critic;Tester;");
+}
@@ -0,0 +1,163 @@
+//! Credentials for provider CLIs that forge shells out to (currently
+//! `gemini skills install`).
+//!
+//! A token is taken from the provider's environment variables first, then
+//! from the OS keychain (service `forge`, account = provider name) via
+//! `security` on macOS or `secret-tool` elsewhere. A CLI that has its own
+//! login on disk needs nothing injected. When none of these exist nothing
+//! is injected either: the CLI may authenticate some other way (Vertex AI,
+//! application default credentials), and [`auth_hint`] names the usual
+//! fixes for when it does not.
+
+use crate::deploy::provider::Provider;
+use std::path::Path;
+use std::process::Command;
+
+/// Keychain service under which forge looks up provider tokens.
+pub const KEYCHAIN_SERVICE: &str = "forge";
+
+struct ProviderAuth {
+    /// Variables the CLI reads its token from; the first is the one set
+    /// when injecting a keychain token.
+    env_vars: &'static [&'static str],
+    /// The CLI's own login state, relative to `$HOME`.
+    login_file: &'static str,
+}
+
+fn auth_for(provider: &Provider) -> Option<ProviderAuth> {
+    match provider {
+        Provider::Claude => Some(ProviderAuth {
+            env_vars: &["ANTHROPIC_API_KEY"],
+            login_file: ".claude/.credentials.json",
+        }),
+        Provider::Gemini => Some(ProviderAuth {
+            env_vars: &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+            login_file: ".gemini/oauth_creds.json",
+        }),
+        Provider::Codex => Some(ProviderAuth {
+            env_vars: &["OPENAI_API_KEY"],
+            login_file: ".codex/auth.json",
+        }),
+        Provider::OpenCode
+        | Provider::Cursor
+        | Provider::Windsurf
+        | Provider::Copilot
+        | Provider::Zed
+        | Provider::Custom(_) => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    Env,
+    Keychain,
+}
+
+/// A token to pass to a provider CLI as `var`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub var: String,
+    pub value: String,
+    pub origin: Origin,
+}
+
+// Keep tokens out of debug output and logs.
+impl std::fmt::Debug for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credential")
+            .field("var", &self.var)
+            .field("value", &"<redacted>")
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
+/// The credential to inject into `provider`'s CLI, if any.
+///
+/// `None` means nothing is injected: the token is already in the
+/// environment the CLI inherits, the CLI has its own login under `home`,
+/// the provider has no CLI forge calls, or no token was found. The last
+/// case is logged, and the CLI is run anyway, since it may authenticate
+/// in a way forge does not know about.
+pub fn resolve(
+    provider: &Provider,
+    home: &Path,
+    env: impl Fn(&str) -> Option<String>,
+    keychain: impl Fn(&str, &str) -> Option<String>,
+) -> Option<Credential> {
+    let auth = auth_for(provider)?;
+    let set = |var: &str| env(var).filter(|v| !v.trim().is_empty());
+    if auth.env_vars.iter().any(|var| set(var).is_some()) {
+        return None;
+    }
+    if let Some(value) = keychain(KEYCHAIN_SERVICE, provider.as_str()) {
+        let value = value.trim().to_string();
+        if !value.is_empty() {
+            return Some(Credential {
+                var: auth.env_vars[0].to_string(),
+                value,
+                origin: Origin::Keychain,
+            });
+        }
+    }
+    if !home.join(auth.login_file).is_file() {
+        tracing::warn!(
+            provider = provider.as_str(),
+            "no token or login found; running the CLI without one"
+        );
+    }
+    None
+}
+
+/// How to authenticate `provider`'s CLI, for when it fails without a
+/// credential; `None` for providers whose CLI forge does not call.
+pub fn auth_hint(provider: &Provider) -> Option<String> {
+    let auth = auth_for(provider)?;
+    let name = provider.as_str();
+    Some(format!(
+        "set {}, store a token with \
+         `security add-generic-password -s {KEYCHAIN_SERVICE} -a {name} -w` (macOS) or \
+         `secret-tool store --label='forge {name}' service {KEYCHAIN_SERVICE} account {name}` \
+         (Linux), or run `{name}` once to log in",
+        auth.env_vars.join(" or "),
+    ))
+}
+
+/// [`resolve`] against the process environment and the OS keychain.
+pub fn resolve_system(provider: &Provider, home: &Path) -> Option<Credential> {
+    resolve(
+        provider,
+        home,
+        |var| std::env::var(var).ok(),
+        system_keychain,
+    )
+}
+
+/// Look a secret up in the OS keychain. `None` when the tool is missing or
+/// has no such entry.
+pub(crate) fn system_keychain(service: &str, account: &str) -> Option<String> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", service, "-a", account, "-w"])
+            .output()
+    } else {
+        Command::new("secret-tool")
+            .args(["lookup", "service", service, "account", account])
+            .output()
+    }
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Pass `credential` (if any) to `cmd` through its environment.
+pub fn apply(cmd: &mut Command, credential: Option<&Credential>) {
+    if let Some(c) = credential {
+        cmd.env(&c.var, &c.value);
+    }
+}
+
+#[cfg(test)]
+mod tests;
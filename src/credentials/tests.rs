@@ -0,0 +1,70 @@
+use super::*;
+use tempfile::TempDir;
+
+fn no_env(_: &str) -> Option<String> {
+    None
+}
+
+fn no_keychain(_: &str, _: &str) -> Option<String> {
+    None
+}
+
+#[test]
+fn env_token_needs_no_injection() {
+    let home = TempDir::new().unwrap();
+    let env = |var: &str| (var == "GOOGLE_API_KEY").then(|| "tok".to_string());
+    let found = resolve(&Provider::Gemini, home.path(), env, |_, _| {
+        panic!("keychain consulted despite env token")
+    });
+    assert_eq!(found, None);
+}
+
+#[test]
+fn keychain_token_is_injected_as_first_env_var() {
+    let home = TempDir::new().unwrap();
+    let keychain = |service: &str, account: &str| {
+        (service == KEYCHAIN_SERVICE && account == "gemini").then(|| "secret\n".to_string())
+    };
+    let found = resolve(&Provider::Gemini, home.path(), no_env, keychain).unwrap();
+    assert_eq!(found.var, "GEMINI_API_KEY");
+    assert_eq!(found.value, "secret");
+    assert_eq!(found.origin, Origin::Keychain);
+    assert!(!format!("{found:?}").contains("secret"));
+
+    let mut cmd = Command::new("gemini");
+    apply(&mut cmd, Some(&found));
+    assert!(cmd
+        .get_envs()
+        .any(|(k, v)| k == "GEMINI_API_KEY" && v.is_some_and(|v| v == "secret")));
+}
+
+#[test]
+fn cli_login_counts_as_authenticated() {
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".gemini")).unwrap();
+    std::fs::write(home.path().join(".gemini/oauth_creds.json"), "{}").unwrap();
+    assert_eq!(
+        resolve(&Provider::Gemini, home.path(), no_env, no_keychain),
+        None
+    );
+}
+
+#[test]
+fn missing_auth_injects_nothing_and_the_hint_names_every_fix() {
+    let home = TempDir::new().unwrap();
+    let empty = |_: &str| Some("  ".to_string());
+    assert_eq!(
+        resolve(&Provider::Gemini, home.path(), empty, no_keychain),
+        None
+    );
+    let hint = auth_hint(&Provider::Gemini).unwrap();
+    assert!(hint.contains("GEMINI_API_KEY or GOOGLE_API_KEY"));
+    assert!(hint.contains("secret-tool store"));
+    assert!(hint.contains("run `gemini` once"));
+
+    assert_eq!(
+        resolve(&Provider::OpenCode, home.path(), no_env, no_keychain),
+        None
+    );
+    assert_eq!(auth_hint(&Provider::OpenCode), None);
+}
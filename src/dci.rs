@@ -6,7 +6,7 @@ use std::path::Path;
 
 /// Extract DCI lines from a SKILL.md file, skipping lines inside code fences.
 /// Returns `(line_number, line_content)` pairs.
-pub fn extract_dci_lines(content: &str) -> Vec<(usize, &str)> {
+pub(crate) fn extract_dci_lines(content: &str) -> Vec<(usize, &str)> {
     let mut in_code_fence = false;
     let mut dci_lines = Vec::new();
 
@@ -25,7 +25,7 @@ pub fn extract_dci_lines(content: &str) -> Vec<(usize, &str)> {
 
 /// Extract lines inside bash code fences from a SKILL.md file.
 /// Returns `(line_number, line_content)` pairs.
-pub fn extract_bash_block_lines(content: &str) -> Vec<(usize, &str)> {
+pub(crate) fn extract_bash_block_lines(content: &str) -> Vec<(usize, &str)> {
     let mut in_bash = false;
     let mut lines = Vec::new();
 
@@ -1,12 +1,24 @@
 pub mod provider;
 
+use crate::cache::{self, CacheEntry, DeployCache};
+use crate::error::ForgeError;
+use crate::frontmatter::AgentFrontmatter;
+use crate::merge;
 use crate::parse;
-use crate::sidecar::{resolve_model, SidecarConfig};
-use provider::Provider;
+use crate::progress::{Operation, ProgressSink};
+use crate::sidecar::{resolve_model, Provenance, SidecarConfig};
+use crate::template::Templates;
+use crate::vfs::{self, RealFs, Vfs};
+use provider::{CustomProvider, Provider};
+use serde::Serialize;
+use serde_json::json;
 use std::env;
+use std::ffi::OsStr;
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct AgentMeta {
     pub name: String,
     pub display_name: String,
@@ -17,6 +29,7 @@ pub struct AgentMeta {
     pub source_file: String,
     pub source: String,
     pub reasoning_effort: Option<String>,
+    pub provenance: Provenance,
 }
 
 pub struct AgentOutput {
@@ -24,108 +37,264 @@ pub struct AgentOutput {
     pub prompt_file: Option<(String, String)>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum DeployResult {
     Deployed,
+    /// The deployed file had user edits; they were merged with the new
+    /// render without conflicts.
+    Merged,
+    /// User edits and source changes overlap; the file holds conflict
+    /// markers (see [`crate::merge`]) until the user resolves them.
+    Conflicted,
+    /// Unchanged since the last deploy (`options.cache`); nothing written.
+    Unchanged,
     SkippedTemplate,
     SkippedUserOwned,
     SkippedNoName,
+    /// The sidecar's `agents.<Name>.providers` leaves this provider out.
+    SkippedProviderExcluded,
 }
 
-pub fn format_agent_output(
-    meta: &AgentMeta,
-    body: &str,
-    provider: Provider,
-    model_allowed: bool,
-) -> AgentOutput {
-    let mut out = String::new();
-
-    match provider {
-        Provider::Codex => {
-            let _ = writeln!(out, "# source: {}", meta.source);
-            let _ = writeln!(out, "description = \"{}\"", toml_escape(&meta.description));
-            if model_allowed {
-                let _ = writeln!(out, "model = \"{}\"", toml_escape(&meta.model));
-            }
-            if let Some(ref effort) = meta.reasoning_effort {
-                let _ = writeln!(out, "model_reasoning_effort = \"{effort}\"");
-            }
-            let prompt_filename = format!("{}.prompt.md", meta.name);
-            let instructions_path = format!("agents/{prompt_filename}");
-            let _ = writeln!(
-                out,
-                "model_instructions_file = \"{}\"",
-                toml_escape(&instructions_path)
-            );
+impl DeployResult {
+    /// Kebab-case name, as serialized and reported to a [`ProgressSink`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Deployed => "deployed",
+            Self::Merged => "merged",
+            Self::Conflicted => "conflicted",
+            Self::Unchanged => "unchanged",
+            Self::SkippedTemplate => "skipped-template",
+            Self::SkippedUserOwned => "skipped-user-owned",
+            Self::SkippedNoName => "skipped-no-name",
+            Self::SkippedProviderExcluded => "skipped-provider-excluded",
+        }
+    }
+}
 
-            let mut prompt_body = body.to_string();
-            if !prompt_body.ends_with('\n') {
-                prompt_body.push('\n');
-            }
+/// How [`deploy_agent`] and [`deploy_agents_from_dir`] deploy. Built with
+/// [`DeployOptions::new`] and the `with_*` methods, so a new flag does not
+/// change every deploy signature.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct DeployOptions {
+    pub provider: Provider,
+    /// Report what would change without writing anything.
+    pub dry_run: bool,
+    /// Overwrite user-created agents and user edits instead of skipping or
+    /// merging them, saving what they held under [`BACKUP_DIR`] first.
+    pub force: bool,
+    /// Agent names to [`force`](Self::force) alone, leaving the rest of the
+    /// directory to the usual rules.
+    pub force_agents: Vec<String>,
+    /// Prefix of the `source:` recorded in deployed files, e.g.
+    /// `forge-council/agents`.
+    pub source_prefix: String,
+    /// Where sources are read and agents written; the disk by default. A
+    /// [`crate::vfs::MemoryFs`] overlay captures the writes instead.
+    pub vfs: Arc<dyn Vfs>,
+    /// Skip agents whose source, configuration, and deployed files are
+    /// unchanged since the last deploy, per the destination's
+    /// [`crate::cache`] file, without rendering them.
+    pub cache: bool,
+    /// Save the content a deploy replaces under [`BACKUP_DIR`], so
+    /// [`restore_agents`] can bring it back.
+    pub backup: bool,
+    /// Symlink each agent to its source in this directory instead of
+    /// rendering it, so edits to the source take effect at once. Claude
+    /// only, since the provider then reads the source as written.
+    pub link: Option<PathBuf>,
+}
 
-            return AgentOutput {
-                primary: out,
-                prompt_file: Some((prompt_filename, prompt_body)),
-            };
+impl DeployOptions {
+    pub fn new(provider: Provider) -> Self {
+        Self {
+            provider,
+            dry_run: false,
+            force: false,
+            force_agents: Vec::new(),
+            source_prefix: String::new(),
+            vfs: Arc::new(RealFs),
+            cache: false,
+            backup: false,
+            link: None,
         }
-        Provider::Gemini => {
-            out.push_str("---\n");
-            let _ = writeln!(out, "name: {}", meta.display_name);
-            let _ = writeln!(out, "description: {}", meta.description);
-            out.push_str("kind: local\n");
-            if model_allowed {
-                let _ = writeln!(out, "model: {}", meta.model);
-            }
-            if let Some(ref tools) = meta.tools {
-                let mapped = provider.map_tools(tools);
-                out.push_str("tools:\n");
-                for tool in mapped.split(", ") {
-                    let _ = writeln!(out, "  - {tool}");
-                }
-            }
-            if !meta.skills.is_empty() {
-                out.push_str("skills:\n");
-                for skill in &meta.skills {
-                    let _ = writeln!(out, "  - {skill}");
-                }
-            }
+    }
+
+    #[must_use]
+    pub fn with_dry_run(self, dry_run: bool) -> Self {
+        Self { dry_run, ..self }
+    }
+
+    #[must_use]
+    pub fn with_force(self, force: bool) -> Self {
+        Self { force, ..self }
+    }
+
+    #[must_use]
+    pub fn with_force_agents<I, S>(self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            force_agents: names.into_iter().map(Into::into).collect(),
+            ..self
         }
-        Provider::Claude | Provider::OpenCode => {
-            out.push_str("---\n");
-            let _ = writeln!(out, "name: {}", meta.display_name);
-            let _ = writeln!(out, "description: {}", meta.description);
-            if model_allowed {
-                let _ = writeln!(out, "model: {}", meta.model);
-            }
-            if let Some(ref tools) = meta.tools {
-                let _ = writeln!(out, "tools: {tools}");
-            }
-            if !meta.skills.is_empty() {
-                out.push_str("skills:\n");
-                for skill in &meta.skills {
-                    let _ = writeln!(out, "  - {skill}");
-                }
-            }
+    }
+
+    /// Whether the agent `name` is overwritten regardless of edits and
+    /// ownership.
+    pub fn forces(&self, name: &str) -> bool {
+        self.force || self.force_agents.iter().any(|n| n == name)
+    }
+
+    #[must_use]
+    pub fn with_source_prefix(self, source_prefix: impl Into<String>) -> Self {
+        Self {
+            source_prefix: source_prefix.into(),
+            ..self
         }
     }
 
-    let _ = writeln!(out, "source: {}", meta.source);
-    out.push_str("---\n");
-    out.push_str(body);
-    if !body.ends_with('\n') {
-        out.push('\n');
+    #[must_use]
+    pub fn with_vfs(self, vfs: Arc<dyn Vfs>) -> Self {
+        Self { vfs, ..self }
+    }
+
+    #[must_use]
+    pub fn with_cache(self, cache: bool) -> Self {
+        Self { cache, ..self }
+    }
+
+    #[must_use]
+    pub fn with_backup(self, backup: bool) -> Self {
+        Self { backup, ..self }
     }
 
-    AgentOutput {
-        primary: out,
+    #[must_use]
+    pub fn with_link(self, src_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            link: Some(src_dir.into()),
+            ..self
+        }
+    }
+}
+
+/// Per-file outcome of [`deploy_agents_from_dir_continue`]: the source
+/// filename and either its action or the error that stopped it.
+pub type FileOutcome = (String, Result<AgentAction, ForgeError>);
+
+pub fn format_agent_output(
+    meta: &AgentMeta,
+    body: &str,
+    provider: &Provider,
+    model_allowed: bool,
+) -> Result<AgentOutput, ForgeError> {
+    render_agent_output(&Templates::default(), meta, body, provider, model_allowed)
+}
+
+/// [`format_agent_output`] through `templates`, so a module's
+/// `templates/agent-<provider>.*.j2` overrides apply.
+pub fn render_agent_output(
+    templates: &Templates,
+    meta: &AgentMeta,
+    body: &str,
+    provider: &Provider,
+    model_allowed: bool,
+) -> Result<AgentOutput, ForgeError> {
+    let mut body = body.to_string();
+    if !body.ends_with('\n') {
+        body.push('\n');
+    }
+    let model = model_allowed.then_some(meta.model.as_str());
+
+    let mapped_tools = || -> Option<Vec<String>> {
+        meta.tools.as_ref().map(|tools| {
+            provider
+                .map_tools(tools)
+                .split(", ")
+                .map(String::from)
+                .collect()
+        })
+    };
+    let (template, tools) = match provider {
+        Provider::Codex => return render_codex_agent(templates, meta, body, model),
+        Provider::Gemini => ("agent-gemini.md", json!(mapped_tools())),
+        Provider::OpenCode => ("agent-opencode.md", json!(mapped_tools())),
+        Provider::Cursor => ("agent-cursor.mdc", json!(null)),
+        Provider::Windsurf => ("agent-windsurf.md", json!(null)),
+        Provider::Copilot => ("agent-copilot.md", json!(null)),
+        Provider::Zed => ("agent-zed.md", json!(mapped_tools())),
+        Provider::Custom(custom) => (custom.template.as_str(), json!(mapped_tools())),
+        Provider::Claude => ("agent-claude.md", json!(meta.tools)),
+    };
+    let Provenance {
+        license,
+        attribution,
+    } = &meta.provenance;
+    let context = json!({
+        "name": meta.display_name,
+        "description": meta.description,
+        "model": model,
+        "tools": tools,
+        "skills": meta.skills,
+        "source": meta.source,
+        "license": license,
+        "attribution": attribution,
+        "body": body,
+    });
+    Ok(AgentOutput {
+        primary: templates
+            .render(template, &context)
+            .map_err(ForgeError::Config)?,
         prompt_file: None,
+    })
+}
+
+/// A Codex agent: the `agent-codex.toml` definition, pointing at the body
+/// in a separate `<name>.prompt.md`.
+fn render_codex_agent(
+    templates: &Templates,
+    meta: &AgentMeta,
+    mut body: String,
+    model: Option<&str>,
+) -> Result<AgentOutput, ForgeError> {
+    let Provenance {
+        license,
+        attribution,
+    } = &meta.provenance;
+    let prompt_filename = format!("{}.prompt.md", meta.name);
+    let context = json!({
+        "source": meta.source,
+        "license": license,
+        "attribution": attribution,
+        "description": meta.description,
+        "model": model,
+        "reasoning_effort": meta.reasoning_effort,
+        "instructions_file": format!("agents/{prompt_filename}"),
+    });
+    // The prompt file has no frontmatter; carry provenance as comments.
+    let mut header = String::new();
+    for (key, value) in meta.provenance.fields() {
+        let _ = writeln!(header, "<!-- {key}: {value} -->");
     }
+    if !header.is_empty() {
+        body = format!("{header}\n{body}");
+    }
+    Ok(AgentOutput {
+        primary: templates
+            .render("agent-codex.toml", &context)
+            .map_err(ForgeError::Config)?,
+        prompt_file: Some((prompt_filename, body)),
+    })
 }
 
 pub fn extract_agent_meta(
     content: &str,
     filename: &str,
-    provider: Provider,
+    provider: &Provider,
     config: &SidecarConfig,
     source_prefix: &str,
 ) -> Option<AgentMeta> {
@@ -133,37 +302,28 @@ pub fn extract_agent_meta(
         return None;
     }
 
-    let name =
-        parse::fm_value(content, "name").or_else(|| parse::fm_value(content, "claude.name"))?;
-    if name.is_empty() {
-        return None;
-    }
+    let fm = AgentFrontmatter::from_markdown(content).unwrap_or_default();
+    let name = fm.name().filter(|n| !n.is_empty())?.to_string();
 
     // Config is primary source for model/tools; frontmatter is legacy fallback
     let model_tier = config
         .agent_value(&name, "model")
-        .or_else(|| parse::fm_value(content, "claude.model"))
+        .or_else(|| fm.claude_model.clone())
         .unwrap_or_else(|| "sonnet".into());
 
-    let description = parse::fm_value(content, "description")
-        .or_else(|| parse::fm_value(content, "claude.description"))
-        .or_else(|| config.agent_value(&name, "description"))
-        .unwrap_or_else(|| "Specialist agent".into());
+    let description =
+        localized_description(content, &name, config).unwrap_or_else(|| "Specialist agent".into());
 
     let tools = config
         .agent_value(&name, "tools")
-        .or_else(|| parse::fm_list(content, "claude.tools"))
-        .or_else(|| parse::fm_value(content, "claude.tools"));
+        .or_else(|| (!fm.claude_tools.is_empty()).then(|| fm.claude_tools.join(", ")));
 
     let skills = {
         let from_config = config.agent_list(&name, "skills");
-        if !from_config.is_empty() {
-            from_config
+        if from_config.is_empty() {
+            fm.skills().to_vec()
         } else {
-            parse::fm_list(content, "claude.skills")
-                .or_else(|| parse::fm_list(content, "skills"))
-                .map(|s| s.split(", ").map(String::from).collect::<Vec<_>>())
-                .unwrap_or_default()
+            from_config
         }
     };
 
@@ -193,150 +353,917 @@ pub fn extract_agent_meta(
         source_file: filename.to_string(),
         source,
         reasoning_effort,
+        provenance: config.provenance().clone(),
     })
 }
 
-pub fn deploy_agent(
+/// The description for the configured locale (`description.<locale>` in
+/// frontmatter or the sidecar), else the plain `description`, else
+/// `description.en`.
+fn localized_description(content: &str, name: &str, config: &SidecarConfig) -> Option<String> {
+    let lookup = |key: &str| {
+        parse::fm_value(content, key)
+            .or_else(|| parse::fm_value(content, &format!("claude.{key}")))
+            .or_else(|| config.agent_value(name, key))
+    };
+    config
+        .locale()
+        .and_then(|locale| lookup(&format!("description.{locale}")))
+        .or_else(|| lookup("description"))
+        .or_else(|| lookup("description.en"))
+}
+
+/// One file [`apply_agent`] writes for an agent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedFile {
+    pub path: PathBuf,
+    /// What the file will hold: the new render, or its merge with user
+    /// edits. `None` leaves the file as it is (unresolved conflicts).
+    pub content: Option<String>,
+    /// The new render, recorded as the merge base for the next deploy.
+    pub rendered: String,
+    pub result: DeployResult,
+    pub reason: String,
+    /// What the file held before, when `options.backup` is set or the
+    /// deploy is forced and the deploy changes it; [`apply_agent`] saves it
+    /// under [`BACKUP_DIR`].
+    #[serde(skip)]
+    pub backup: Option<String>,
+    /// The source the file is a symlink to, for `options.link`; `content`
+    /// and `rendered` are then unused.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<PathBuf>,
+}
+
+/// What deploying one agent source file will do, before anything is
+/// written.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgentAction {
+    pub filename: String,
+    /// Agent name; `None` when the file is skipped before it is parsed.
+    pub name: Option<String>,
+    pub result: DeployResult,
+    pub reason: String,
+    /// Empty for skipped agents.
+    pub files: Vec<PlannedFile>,
+    /// What planning extracted from the source, so callers need not parse
+    /// it again; `None` alongside `name`.
+    #[serde(skip)]
+    pub meta: Option<AgentMeta>,
+}
+
+impl AgentAction {
+    fn skipped(
+        filename: &str,
+        meta: Option<AgentMeta>,
+        result: DeployResult,
+        reason: &str,
+    ) -> Self {
+        Self {
+            filename: filename.to_string(),
+            name: meta.as_ref().map(|m| m.name.clone()),
+            result,
+            reason: reason.to_string(),
+            files: Vec::new(),
+            meta,
+        }
+    }
+}
+
+/// Every [`AgentAction`] for one destination directory, from
+/// [`plan_agents_from_dir`]. Apply it with [`apply`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeployPlan {
+    pub dst_dir: PathBuf,
+    pub actions: Vec<AgentAction>,
+    /// The cache [`apply`] saves once everything is written, when
+    /// `options.cache` is set.
+    #[serde(skip)]
+    pub cache: Option<DeployCache>,
+}
+
+/// Decide what deploying `content` to `dst_dir` will do, reading but never
+/// writing the destination. `options.dry_run` does not apply here.
+pub fn plan_agent(
     content: &str,
     filename: &str,
     dst_dir: &Path,
-    provider: Provider,
     config: &SidecarConfig,
-    dry_run: bool,
-    source_prefix: &str,
-) -> Result<DeployResult, String> {
+    options: &DeployOptions,
+) -> Result<AgentAction, ForgeError> {
+    let DeployOptions {
+        ref provider,
+        backup,
+        ref source_prefix,
+        ref vfs,
+        ref link,
+        ..
+    } = *options;
+    let _span = tracing::debug_span!(
+        "deploy_agent",
+        file = filename,
+        provider = provider.as_str()
+    )
+    .entered();
     if filename.starts_with("_Template") || filename.starts_with("Template") {
-        return Ok(DeployResult::SkippedTemplate);
+        return Ok(AgentAction::skipped(
+            filename,
+            None,
+            DeployResult::SkippedTemplate,
+            "template",
+        ));
     }
 
     let Some(meta) = extract_agent_meta(content, filename, provider, config, source_prefix) else {
-        return Ok(DeployResult::SkippedNoName);
+        return Ok(AgentAction::skipped(
+            filename,
+            None,
+            DeployResult::SkippedNoName,
+            "no name in frontmatter",
+        ));
     };
 
-    parse::validate_agent_name(&meta.name)?;
-
-    let ext = provider.agent_extension();
-    let out_path = dst_dir.join(format!("{}.{ext}", meta.name));
+    if !config.agent_targets(&meta.name, provider.as_str()) {
+        return Ok(AgentAction::skipped(
+            filename,
+            Some(meta),
+            DeployResult::SkippedProviderExcluded,
+            "provider not listed in the agent's providers",
+        ));
+    }
 
-    if out_path.is_symlink() {
-        return Err(format!("destination is a symlink: {}", out_path.display()));
+    parse::validate_agent_name(&meta.name).map_err(ForgeError::Validation)?;
+    config
+        .policy()
+        .check(&meta, content)
+        .map_err(ForgeError::Validation)?;
+
+    let out_path = dst_dir.join(provider.agent_filename(&meta.name));
+
+    let force = options.forces(&meta.name);
+    let user_owned = is_user_owned(&**vfs, &out_path, filename)?;
+    if !force && user_owned {
+        return Ok(AgentAction::skipped(
+            filename,
+            Some(meta),
+            DeployResult::SkippedUserOwned,
+            "user-created file (no source field)",
+        ));
     }
 
-    if out_path.exists() {
-        let existing = std::fs::read_to_string(&out_path)
-            .map_err(|e| format!("failed to read {}: {e}", out_path.display()))?;
-        if !parse::is_synced_from(&existing, filename) {
-            return Ok(DeployResult::SkippedUserOwned);
-        }
+    if let Some(src_dir) = link {
+        return plan_link(filename, meta, out_path, &src_dir.join(filename), options);
     }
 
     let model_allowed = config.is_model_whitelisted(provider.as_str(), &meta.model);
+    if !model_allowed {
+        tracing::debug!(model = %meta.model, "model not whitelisted; omitted");
+    }
     let body = parse::fm_body(content);
-    let output = format_agent_output(&meta, body, provider, model_allowed);
+    let output = render_agent_output(config.templates(), &meta, body, provider, model_allowed)?;
 
-    if !dry_run {
-        std::fs::create_dir_all(dst_dir)
-            .map_err(|e| format!("failed to create {}: {e}", dst_dir.display()))?;
-        std::fs::write(&out_path, &output.primary)
-            .map_err(|e| format!("failed to write {}: {e}", out_path.display()))?;
-        if let Some((ref prompt_filename, ref prompt_content)) = output.prompt_file {
-            let prompt_path = dst_dir.join(prompt_filename);
-            std::fs::write(&prompt_path, prompt_content)
-                .map_err(|e| format!("failed to write {}: {e}", prompt_path.display()))?;
+    // The user-created file a forced deploy takes over, if any.
+    let reclaimed = user_owned.then(|| out_path.clone());
+    let mut rendered = vec![(out_path, output.primary)];
+    if let Some((prompt_filename, prompt_content)) = output.prompt_file {
+        rendered.push((dst_dir.join(prompt_filename), prompt_content));
+    }
+
+    let mut result = DeployResult::Deployed;
+    let mut files = Vec::new();
+    for (path, rendered) in rendered {
+        let mut file = if force {
+            let user_owned = reclaimed.as_ref() == Some(&path);
+            plan_forced(&**vfs, path, rendered, user_owned)
+        } else {
+            reconcile(&**vfs, &path, &rendered)
+        };
+        if backup && file.backup.is_none() && !vfs.is_symlink(&file.path) {
+            file.backup = replaced_content(&**vfs, &file);
         }
+        tracing::debug!(path = %file.path.display(), result = ?file.result, reason = %file.reason, "planned");
+        if file.result == DeployResult::Conflicted || result == DeployResult::Deployed {
+            result = file.result.clone();
+        }
+        files.push(file);
     }
 
-    Ok(DeployResult::Deployed)
+    let reason = files.first().map(|f| f.reason.clone()).unwrap_or_default();
+    Ok(AgentAction {
+        filename: filename.to_string(),
+        name: Some(meta.name.clone()),
+        result,
+        reason,
+        files,
+        meta: Some(meta),
+    })
 }
 
-pub fn deploy_agents_from_dir(
-    src_dir: &Path,
+/// The [`PlannedFile`] overwriting `path` with `rendered` for a forced
+/// deploy, with a backup of whatever a normal deploy would have kept: a
+/// user-created file or user edits.
+fn plan_forced(vfs: &dyn Vfs, path: PathBuf, rendered: String, user_owned: bool) -> PlannedFile {
+    let reason = if user_owned {
+        tracing::warn!(path = %path.display(), "overwriting user-created file (forced)");
+        "user-created file overwritten (forced)"
+    } else if reconcile(vfs, &path, &rendered).result != DeployResult::Deployed {
+        tracing::warn!(path = %path.display(), "overwriting user edits (forced)");
+        "user edits overwritten (forced)"
+    } else {
+        "forced"
+    };
+    let mut file = PlannedFile {
+        content: Some(rendered.clone()),
+        result: DeployResult::Deployed,
+        reason: reason.to_string(),
+        path,
+        rendered,
+        backup: None,
+        link: None,
+    };
+    if reason != "forced" && !vfs.is_symlink(&file.path) {
+        file.backup = replaced_content(vfs, &file);
+    }
+    file
+}
+
+/// Whether `path` holds an agent forge did not deploy from `filename`.
+/// A symlink other than a `--link` to the source is an error.
+fn is_user_owned(vfs: &dyn Vfs, path: &Path, filename: &str) -> Result<bool, ForgeError> {
+    if vfs.is_symlink(path) {
+        return if vfs::is_source_link(vfs, path, OsStr::new(filename)) {
+            Ok(false)
+        } else {
+            Err(ForgeError::Validation(format!(
+                "destination is a symlink: {}",
+                path.display()
+            )))
+        };
+    }
+    if !vfs.exists(path) {
+        return Ok(false);
+    }
+    let existing = vfs
+        .read_to_string(path)
+        .map_err(ForgeError::io("read", path))?;
+    Ok(!parse::is_synced_from(&existing, filename))
+}
+
+/// The [`AgentAction`] linking `out_path` to `target` for `options.link`.
+fn plan_link(
+    filename: &str,
+    meta: AgentMeta,
+    out_path: PathBuf,
+    target: &Path,
+    options: &DeployOptions,
+) -> Result<AgentAction, ForgeError> {
+    if options.provider != Provider::Claude {
+        return Err(ForgeError::Validation(format!(
+            "cannot link agents for {}: linked sources are not rendered, and only claude reads them as written",
+            options.provider.as_str()
+        )));
+    }
+    let reason = if options.vfs.read_link(&out_path).is_ok_and(|t| t == target) {
+        "already linked"
+    } else {
+        "linked to the source"
+    };
+    Ok(AgentAction {
+        filename: filename.to_string(),
+        name: Some(meta.name.clone()),
+        result: DeployResult::Deployed,
+        reason: reason.to_string(),
+        files: vec![PlannedFile {
+            path: out_path,
+            content: None,
+            rendered: String::new(),
+            result: DeployResult::Deployed,
+            reason: reason.to_string(),
+            backup: None,
+            link: Some(target.to_path_buf()),
+        }],
+        meta: Some(meta),
+    })
+}
+
+/// What `file` replaces at its path, if writing it changes anything.
+fn replaced_content(vfs: &dyn Vfs, file: &PlannedFile) -> Option<String> {
+    let new = file.content.as_ref()?;
+    vfs.read_to_string(&file.path)
+        .ok()
+        .filter(|existing| existing != new)
+}
+
+/// Write what `action` planned into `dst_dir` through `vfs`, with the
+/// merge bases, saving the files it replaces first when planned with
+/// `options.backup` or `options.force`.
+pub fn apply_agent(vfs: &dyn Vfs, dst_dir: &Path, action: &AgentAction) -> Result<(), ForgeError> {
+    for file in &action.files {
+        if let Some(target) = &file.link {
+            link_file(vfs, dst_dir, &file.path, target)?;
+            continue;
+        }
+        let base = base_path(&file.path);
+        let base_dir = base.parent().unwrap_or(Path::new("."));
+        for dir in [dst_dir, base_dir] {
+            vfs.create_dir_all(dir)
+                .map_err(ForgeError::io("create", dir))?;
+        }
+        if let Some(previous) = &file.backup {
+            save_backup(vfs, &file.path, previous)?;
+        }
+        if let Some(content) = &file.content {
+            // Planning refused any symlink but a `--link` one, which the
+            // render replaces instead of writing through to the source.
+            if vfs.is_symlink(&file.path) {
+                vfs.remove_file(&file.path)
+                    .map_err(ForgeError::io("remove", &file.path))?;
+            }
+            vfs.write(&file.path, content.as_bytes())
+                .map_err(ForgeError::io("write", &file.path))?;
+        }
+        vfs.write(&base, file.rendered.as_bytes())
+            .map_err(ForgeError::io("write", &base))?;
+    }
+    Ok(())
+}
+
+/// Make `path` a symlink to `target`, replacing the file or link there and
+/// dropping its merge base.
+fn link_file(vfs: &dyn Vfs, dst_dir: &Path, path: &Path, target: &Path) -> Result<(), ForgeError> {
+    if vfs.read_link(path).is_ok_and(|t| t == target) {
+        return Ok(());
+    }
+    vfs.create_dir_all(dst_dir)
+        .map_err(ForgeError::io("create", dst_dir))?;
+    if vfs.is_symlink(path) || vfs.exists(path) {
+        vfs.remove_file(path)
+            .map_err(ForgeError::io("remove", path))?;
+    }
+    let _ = vfs.remove_file(&base_path(path));
+    vfs.symlink(target, path)
+        .map_err(ForgeError::io("link", path))
+}
+
+/// [`plan_agent`], then [`apply_agent`] unless `options.dry_run`.
+pub fn deploy_agent(
+    content: &str,
+    filename: &str,
     dst_dir: &Path,
-    provider: Provider,
     config: &SidecarConfig,
-    dry_run: bool,
-    source_prefix: &str,
-) -> Result<Vec<(String, DeployResult)>, String> {
-    if !src_dir.is_dir() {
-        return Ok(Vec::new());
+    options: &DeployOptions,
+) -> Result<DeployResult, ForgeError> {
+    let action = plan_agent(content, filename, dst_dir, config, options)?;
+    tracing::debug!(result = ?action.result, reason = %action.reason, dry_run = options.dry_run, "deploy");
+    if !options.dry_run {
+        apply_agent(&*options.vfs, dst_dir, &action)?;
     }
+    Ok(action.result)
+}
+
+/// Directory beside each agent destination holding what forge keeps for
+/// it: `<parent>/.forge-state/<destination name>/`. It sits outside the
+/// destination so that providers scanning it for agents never read copies.
+pub const STATE_DIR: &str = ".forge-state";
+
+/// The [`STATE_DIR`] entry for `dst_dir`.
+pub fn state_dir(dst_dir: &Path) -> PathBuf {
+    let name = dst_dir.file_name().unwrap_or_default();
+    dst_dir
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(STATE_DIR)
+        .join(name)
+}
 
-    let entries = std::fs::read_dir(src_dir)
-        .map_err(|e| format!("failed to read {}: {e}", src_dir.display()))?;
+/// Directory in each [`state_dir`] holding the last rendered content of
+/// every deployed file: the base for merging user edits.
+pub const BASE_DIR: &str = "base";
 
-    let mut files: Vec<_> = entries
-        .filter_map(Result::ok)
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
-        .collect();
-    files.sort_by_key(std::fs::DirEntry::file_name);
+pub(crate) fn base_path(path: &Path) -> PathBuf {
+    state_file(path, BASE_DIR)
+}
 
-    let mut results = Vec::new();
-    for entry in files {
-        let path = entry.path();
-        let filename = entry.file_name().to_string_lossy().to_string();
-        let content = std::fs::read_to_string(&path)
-            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
-        let result = deploy_agent(
+/// Where `kind` ([`BASE_DIR`], ...) keeps its copy of the deployed `path`.
+fn state_file(path: &Path, kind: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default();
+    state_dir(path.parent().unwrap_or(Path::new(".")))
+        .join(kind)
+        .join(name)
+}
+
+/// Directory in each [`state_dir`] holding, per file, the content the last
+/// deploy with `options.backup` replaced; its merge base is kept under
+/// [`BACKUP_BASE_DIR`].
+pub const BACKUP_DIR: &str = "backup";
+
+/// Directory in each [`state_dir`] holding the merge base of every backup.
+pub const BACKUP_BASE_DIR: &str = "backup-base";
+
+fn backup_path(path: &Path) -> PathBuf {
+    state_file(path, BACKUP_DIR)
+}
+
+fn backup_base_path(path: &Path) -> PathBuf {
+    state_file(path, BACKUP_BASE_DIR)
+}
+
+/// Save `previous` as the backup of `path`, with the merge base it was
+/// deployed against, if any.
+fn save_backup(vfs: &dyn Vfs, path: &Path, previous: &str) -> Result<(), ForgeError> {
+    let backup = backup_path(path);
+    let backup_base = backup_base_path(path);
+    for file in [&backup, &backup_base] {
+        let dir = file.parent().unwrap_or(Path::new("."));
+        vfs.create_dir_all(dir)
+            .map_err(ForgeError::io("create", dir))?;
+    }
+    vfs.write(&backup, previous.as_bytes())
+        .map_err(ForgeError::io("write", &backup))?;
+    match vfs.read_to_string(&base_path(path)) {
+        Ok(base) => vfs
+            .write(&backup_base, base.as_bytes())
+            .map_err(ForgeError::io("write", &backup_base))?,
+        Err(_) => {
+            let _ = vfs.remove_file(&backup_base);
+        }
+    }
+    Ok(())
+}
+
+/// Decide what to write to `path` for a new render. A file unchanged since
+/// the last deploy (or deployed before bases were recorded) is replaced;
+/// one the user edited is three-way merged with the new render. A file
+/// still holding conflict markers is left alone (`None`) until resolved.
+fn reconcile(vfs: &dyn Vfs, path: &Path, rendered: &str) -> PlannedFile {
+    let planned = |content, result, reason: &str| PlannedFile {
+        path: path.to_path_buf(),
+        content,
+        rendered: rendered.to_string(),
+        result,
+        reason: reason.to_string(),
+        backup: None,
+        link: None,
+    };
+    let Ok(existing) = vfs.read_to_string(path) else {
+        return planned(
+            Some(rendered.to_string()),
+            DeployResult::Deployed,
+            "new file",
+        );
+    };
+    let Ok(base) = vfs.read_to_string(&base_path(path)) else {
+        return planned(
+            Some(rendered.to_string()),
+            DeployResult::Deployed,
+            "replaces a deploy without a merge base",
+        );
+    };
+    if existing == base {
+        let reason = if existing == rendered {
+            "unchanged"
+        } else {
+            "replaces the previous deploy"
+        };
+        return planned(Some(rendered.to_string()), DeployResult::Deployed, reason);
+    }
+    if merge::has_conflict_markers(&existing) {
+        return planned(
+            None,
+            DeployResult::Conflicted,
+            "unresolved conflict markers from an earlier deploy",
+        );
+    }
+    let merged = merge::merge3(&base, &existing, rendered);
+    if merged.conflicts == 0 {
+        planned(Some(merged.text), DeployResult::Merged, "user edits merged")
+    } else {
+        planned(
+            Some(merged.text),
+            DeployResult::Conflicted,
+            "user edits overlap source changes",
+        )
+    }
+}
+
+/// Remove the merge base recorded for a deployed file, if any.
+fn remove_base(vfs: &dyn Vfs, path: &Path) {
+    let _ = vfs.remove_file(&base_path(path));
+}
+
+/// The `.md` files in `src_dir`, sorted, with their filenames.
+fn agent_files(vfs: &dyn Vfs, src_dir: &Path) -> Result<Vec<(PathBuf, String)>, ForgeError> {
+    let entries = vfs
+        .read_dir(src_dir)
+        .map_err(ForgeError::io("read", src_dir))?;
+    Ok(entries
+        .into_iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .map(|p| {
+            let filename = p
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            (p, filename)
+        })
+        .collect())
+}
+
+/// The previous cache of a destination and the configuration fingerprint
+/// that keys it, loaded once per directory.
+struct CacheLookup {
+    previous: DeployCache,
+    fingerprint: String,
+}
+
+impl CacheLookup {
+    fn load(dst_dir: &Path, config: &SidecarConfig, options: &DeployOptions) -> Option<Self> {
+        options.cache.then(|| Self {
+            previous: DeployCache::load(&*options.vfs, dst_dir),
+            fingerprint: config.fingerprint(),
+        })
+    }
+}
+
+/// An agent's key in the deploy cache: its `source:` value, so modules
+/// sharing a destination keep apart.
+fn cache_key(filename: &str, options: &DeployOptions) -> String {
+    if options.source_prefix.is_empty() {
+        filename.to_string()
+    } else {
+        format!("{}/{filename}", options.source_prefix)
+    }
+}
+
+/// [`plan_agent`], unless `lookup` finds the agent fresh in the cache, with
+/// the cache entry to record for it.
+fn plan_cached(
+    content: &str,
+    filename: &str,
+    dst_dir: &Path,
+    config: &SidecarConfig,
+    options: &DeployOptions,
+    lookup: Option<&CacheLookup>,
+) -> Result<(AgentAction, Option<CacheEntry>), ForgeError> {
+    let Some(lookup) = lookup else {
+        return Ok((
+            plan_agent(content, filename, dst_dir, config, options)?,
+            None,
+        ));
+    };
+    let source = cache::digest(&[
+        env!("CARGO_PKG_VERSION").as_bytes(),
+        content.as_bytes(),
+        filename.as_bytes(),
+        options.provider.as_str().as_bytes(),
+        options.source_prefix.as_bytes(),
+        lookup.fingerprint.as_bytes(),
+        if options.link.is_some() { b"link" } else { b"" },
+    ]);
+    let key = cache_key(filename, options);
+    if !options.force
+        && lookup
+            .previous
+            .is_fresh(&*options.vfs, dst_dir, &key, &source)
+    {
+        let meta = extract_agent_meta(
+            content,
+            filename,
+            &options.provider,
+            config,
+            &options.source_prefix,
+        );
+        if !meta.as_ref().is_some_and(|m| options.forces(&m.name)) {
+            tracing::debug!(file = filename, "unchanged since last deploy; cached");
+            let action = AgentAction::skipped(
+                filename,
+                meta,
+                DeployResult::Unchanged,
+                "unchanged since last deploy (cached)",
+            );
+            return Ok((action, lookup.previous.get(&key).cloned()));
+        }
+    }
+    let action = plan_agent(content, filename, dst_dir, config, options)?;
+    let cacheable = matches!(action.result, DeployResult::Deployed | DeployResult::Merged)
+        && !action.files.is_empty()
+        && action.files.iter().all(|f| f.link.is_none());
+    let entry = cacheable.then(|| CacheEntry {
+        source,
+        outputs: action
+            .files
+            .iter()
+            .filter_map(|f| {
+                let name = f.path.file_name()?.to_string_lossy().to_string();
+                Some((
+                    name,
+                    crate::pack::sha256_hex(f.content.as_ref()?.as_bytes()),
+                ))
+            })
+            .collect(),
+    });
+    Ok((action, entry))
+}
+
+/// Plan every agent in `src_dir` for `dst_dir` (see [`plan_agent`]),
+/// failing with the error of the first file (in filename order) that cannot
+/// be deployed. Files are planned in parallel with the `parallel` feature.
+/// With `options.cache`, agents unchanged since the last deploy are planned
+/// as `Unchanged` with nothing to write.
+pub fn plan_agents_from_dir(
+    src_dir: &Path,
+    dst_dir: &Path,
+    config: &SidecarConfig,
+    options: &DeployOptions,
+) -> Result<DeployPlan, ForgeError> {
+    let vfs = &*options.vfs;
+    let files = if vfs.is_dir(src_dir) {
+        agent_files(vfs, src_dir)?
+    } else {
+        Vec::new()
+    };
+    let lookup = CacheLookup::load(dst_dir, config, options);
+    let span = tracing::Span::current();
+    let planned = par_map(&files, |(path, filename)| {
+        let _entered = span.enter();
+        let content = vfs
+            .read_to_string(path)
+            .map_err(ForgeError::io("read", path))?;
+        plan_cached(
             &content,
-            &filename,
+            filename,
             dst_dir,
-            provider,
             config,
-            dry_run,
-            source_prefix,
-        )?;
-        results.push((filename, result));
+            options,
+            lookup.as_ref(),
+        )
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+    let mut cache = lookup.map(|lookup| lookup.previous);
+    let mut actions = Vec::with_capacity(planned.len());
+    for (action, entry) in planned {
+        if let Some(cache) = &mut cache {
+            cache.update(cache_key(&action.filename, options), entry);
+        }
+        actions.push(action);
     }
+    Ok(DeployPlan {
+        dst_dir: dst_dir.to_path_buf(),
+        actions,
+        cache,
+    })
+}
 
+/// Perform the writes of a [`DeployPlan`] through `vfs`, returning each
+/// source file's result. `progress` sees one `deploy` item per source file,
+/// in plan order.
+///
+/// With the `parallel` feature the agents are written concurrently, unless
+/// two actions write the same path: then the last action must win, so they
+/// are written one at a time and the first failure stops the rest.
+pub fn apply(
+    vfs: &dyn Vfs,
+    plan: &DeployPlan,
+    progress: &mut dyn ProgressSink,
+) -> Result<Vec<(String, DeployResult)>, ForgeError> {
+    let mut operation = Operation::start(progress, "deploy", plan.actions.len());
+    let mut results = Vec::new();
+    let written = if cfg!(feature = "parallel") && !plan.shares_paths() {
+        par_map(&plan.actions, |action| {
+            apply_agent(vfs, &plan.dst_dir, action)
+        })
+    } else {
+        Vec::new()
+    };
+    let mut written = written.into_iter();
+    for action in &plan.actions {
+        match written.next() {
+            Some(result) => result?,
+            None => apply_agent(vfs, &plan.dst_dir, action)?,
+        }
+        operation.item(&action.filename, action.result.as_str());
+        results.push((action.filename.clone(), action.result.clone()));
+    }
+    if let Some(cache) = &plan.cache {
+        save_cache(vfs, &plan.dst_dir, cache);
+    }
     Ok(results)
 }
 
+/// Save `cache`; a failure only costs the next deploy its shortcut.
+fn save_cache(vfs: &dyn Vfs, dst_dir: &Path, cache: &DeployCache) {
+    if let Err(e) = cache.save(vfs, dst_dir) {
+        tracing::warn!("failed to save deploy cache: {e}");
+    }
+}
+
+impl DeployPlan {
+    /// Whether two actions write the same file (two sources with one agent
+    /// name), which makes the order of writes matter.
+    fn shares_paths(&self) -> bool {
+        let mut seen = std::collections::HashSet::new();
+        self.actions
+            .iter()
+            .flat_map(|a| &a.files)
+            .any(|f| !seen.insert(&f.path))
+    }
+}
+
+/// `f` over `items`, on the rayon pool with the `parallel` feature. Results
+/// keep the order of `items`.
+fn par_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// Plan `src_dir` and, unless `options.dry_run`, [`apply`] it. Nothing is
+/// written when any file fails to plan.
+pub fn deploy_agents_from_dir(
+    src_dir: &Path,
+    dst_dir: &Path,
+    config: &SidecarConfig,
+    options: &DeployOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<Vec<(String, DeployResult)>, ForgeError> {
+    let plan = deploy_plan_from_dir(src_dir, dst_dir, config, options, progress)?;
+    Ok(plan
+        .actions
+        .into_iter()
+        .map(|a| (a.filename, a.result))
+        .collect())
+}
+
+/// [`deploy_agents_from_dir`], returning the plan it carried out. Each
+/// action keeps the agent's [`AgentMeta`], so callers that need names,
+/// models, or descriptions do not read the sources again.
+pub fn deploy_plan_from_dir(
+    src_dir: &Path,
+    dst_dir: &Path,
+    config: &SidecarConfig,
+    options: &DeployOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<DeployPlan, ForgeError> {
+    let _span = tracing::info_span!(
+        "deploy_agents",
+        src = %src_dir.display(),
+        dst = %dst_dir.display(),
+        provider = options.provider.as_str()
+    )
+    .entered();
+    let plan = plan_agents_from_dir(src_dir, dst_dir, config, options)?;
+    if options.dry_run {
+        let mut operation = Operation::start(progress, "deploy", plan.actions.len());
+        for action in &plan.actions {
+            operation.item(&action.filename, action.result.as_str());
+        }
+    } else {
+        apply(&*options.vfs, &plan, progress)?;
+    }
+    Ok(plan)
+}
+
+/// [`deploy_agents_from_dir`] on tokio's blocking pool, so async callers do
+/// not stall a runtime thread on filesystem IO. Takes owned arguments since
+/// the work outlives the caller's borrows; progress is not reported.
+#[cfg(feature = "async")]
+pub async fn deploy_agents_from_dir_async(
+    src_dir: PathBuf,
+    dst_dir: PathBuf,
+    config: std::sync::Arc<SidecarConfig>,
+    options: DeployOptions,
+) -> Result<Vec<(String, DeployResult)>, ForgeError> {
+    tokio::task::spawn_blocking(move || {
+        deploy_agents_from_dir(
+            &src_dir,
+            &dst_dir,
+            &config,
+            &options,
+            &mut crate::progress::NoProgress,
+        )
+    })
+    .await?
+}
+
+/// Like [`deploy_plan_from_dir`], but a failing file (unreadable, invalid
+/// name, write error) does not stop the run: its error is returned in place
+/// of its action and the remaining files are still deployed. Only an
+/// unreadable `src_dir` fails the whole call.
+pub fn deploy_agents_from_dir_continue(
+    src_dir: &Path,
+    dst_dir: &Path,
+    config: &SidecarConfig,
+    options: &DeployOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<Vec<FileOutcome>, ForgeError> {
+    let _span = tracing::info_span!(
+        "deploy_agents",
+        src = %src_dir.display(),
+        dst = %dst_dir.display(),
+        provider = options.provider.as_str()
+    )
+    .entered();
+    if !options.vfs.is_dir(src_dir) {
+        return Ok(Vec::new());
+    }
+
+    let vfs = &*options.vfs;
+    let files = agent_files(vfs, src_dir)?;
+    let lookup = CacheLookup::load(dst_dir, config, options);
+    let mut cache = lookup
+        .as_ref()
+        .map(|lookup| lookup.previous.clone())
+        .unwrap_or_default();
+    let mut operation = Operation::start(progress, "deploy", files.len());
+    let outcomes = files
+        .into_iter()
+        .map(|(path, filename)| {
+            let result = vfs
+                .read_to_string(&path)
+                .map_err(ForgeError::io("read", &path))
+                .and_then(|content| {
+                    plan_cached(
+                        &content,
+                        &filename,
+                        dst_dir,
+                        config,
+                        options,
+                        lookup.as_ref(),
+                    )
+                })
+                .and_then(|(action, entry)| {
+                    if !options.dry_run {
+                        apply_agent(vfs, dst_dir, &action)?;
+                    }
+                    cache.update(cache_key(&filename, options), entry);
+                    Ok(action)
+                });
+            let outcome = result.as_ref().map_or("error", |a| a.result.as_str());
+            operation.item(&filename, outcome);
+            (filename, result)
+        })
+        .collect();
+    if lookup.is_some() && !options.dry_run {
+        save_cache(vfs, dst_dir, &cache);
+    }
+    Ok(outcomes)
+}
+
 pub fn clean_agents(
+    vfs: &dyn Vfs,
     src_dir: &Path,
     dst_dir: &Path,
-    provider: Provider,
+    provider: &Provider,
     dry_run: bool,
-) -> Result<Vec<String>, String> {
-    if !src_dir.is_dir() || !dst_dir.is_dir() {
+) -> Result<Vec<String>, ForgeError> {
+    if !vfs.is_dir(src_dir) || !vfs.is_dir(dst_dir) {
         return Ok(Vec::new());
     }
 
-    let entries = std::fs::read_dir(src_dir)
-        .map_err(|e| format!("failed to read {}: {e}", src_dir.display()))?;
-
-    let ext = provider.agent_extension();
     let mut removed = Vec::new();
-    for entry in entries.filter_map(Result::ok) {
-        let path = entry.path();
-        if path.extension().is_some_and(|e| e == "md") {
-            let filename = entry.file_name().to_string_lossy().to_string();
-            let content = std::fs::read_to_string(&path)
-                .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
-
-            let name = match parse::fm_value(&content, "name")
-                .or_else(|| parse::fm_value(&content, "claude.name"))
-            {
-                Some(n) if !n.is_empty() => n,
-                _ => continue,
+    for (path, filename) in agent_files(vfs, src_dir)? {
+        let content = vfs::read_header(vfs, &path).map_err(ForgeError::io("read", &path))?;
+
+        let name = match parse::fm_value(&content, "name")
+            .or_else(|| parse::fm_value(&content, "claude.name"))
+        {
+            Some(n) if !n.is_empty() => n,
+            _ => continue,
+        };
+
+        let dst_path = dst_dir.join(provider.agent_filename(&name));
+        let linked = vfs::is_source_link(vfs, &dst_path, OsStr::new(&filename));
+        if linked || vfs.exists(&dst_path) {
+            let synced = linked || {
+                let existing =
+                    vfs::read_header(vfs, &dst_path).map_err(ForgeError::io("read", &dst_path))?;
+                parse::is_synced_from(&existing, &filename)
             };
-
-            let dst_path = dst_dir.join(format!("{name}.{ext}"));
-            if dst_path.exists() {
-                let existing = std::fs::read_to_string(&dst_path)
-                    .map_err(|e| format!("failed to read {}: {e}", dst_path.display()))?;
-                if parse::is_synced_from(&existing, &filename) {
-                    if !dry_run {
-                        std::fs::remove_file(&dst_path)
-                            .map_err(|e| format!("failed to remove {}: {e}", dst_path.display()))?;
-                    }
-                    if provider == Provider::Codex {
-                        let prompt_path = dst_dir.join(format!("{name}.prompt.md"));
-                        if prompt_path.exists() && !dry_run {
-                            let _ = std::fs::remove_file(&prompt_path);
+            if synced {
+                if !dry_run {
+                    vfs.remove_file(&dst_path)
+                        .map_err(ForgeError::io("remove", &dst_path))?;
+                    remove_base(vfs, &dst_path);
+                }
+                if *provider == Provider::Codex {
+                    let prompt_path = dst_dir.join(format!("{name}.prompt.md"));
+                    if vfs.exists(&prompt_path) && !dry_run {
+                        if let Err(e) = vfs.remove_file(&prompt_path) {
+                            tracing::warn!(path = %prompt_path.display(), "failed to remove: {e}");
                         }
+                        remove_base(vfs, &prompt_path);
                     }
-                    removed.push(name);
                 }
+                tracing::debug!(agent = %name, dry_run, "removed");
+                removed.push(name);
             }
         }
     }
@@ -344,79 +1271,189 @@ pub fn clean_agents(
     Ok(removed)
 }
 
+/// Bring back the files the last deploy with `options.backup` replaced for
+/// the agents in `src_dir`, with their merge bases, and drop the backups.
+/// Returns the names of the agents restored.
+pub fn restore_agents(
+    vfs: &dyn Vfs,
+    src_dir: &Path,
+    dst_dir: &Path,
+    provider: &Provider,
+    dry_run: bool,
+) -> Result<Vec<String>, ForgeError> {
+    if !vfs.is_dir(src_dir) || !vfs.is_dir(&state_dir(dst_dir).join(BACKUP_DIR)) {
+        return Ok(Vec::new());
+    }
+
+    let mut restored = Vec::new();
+    for (path, _) in agent_files(vfs, src_dir)? {
+        let content = vfs::read_header(vfs, &path).map_err(ForgeError::io("read", &path))?;
+        let name = match parse::fm_value(&content, "name")
+            .or_else(|| parse::fm_value(&content, "claude.name"))
+        {
+            Some(n) if !n.is_empty() => n,
+            _ => continue,
+        };
+
+        let mut files = vec![dst_dir.join(provider.agent_filename(&name))];
+        if *provider == Provider::Codex {
+            files.push(dst_dir.join(format!("{name}.prompt.md")));
+        }
+        let mut any = false;
+        for file in files {
+            if !vfs.exists(&backup_path(&file)) {
+                continue;
+            }
+            any = true;
+            if !dry_run {
+                restore_file(vfs, &file)?;
+            }
+        }
+        if any {
+            tracing::debug!(agent = %name, dry_run, "restored");
+            restored.push(name);
+        }
+    }
+
+    Ok(restored)
+}
+
+/// Move the backup of `path` back to it, and the saved merge base back to
+/// the base of `path` (removing a base recorded since).
+fn restore_file(vfs: &dyn Vfs, path: &Path) -> Result<(), ForgeError> {
+    let backup = backup_path(path);
+    vfs.copy(&backup, path)
+        .map_err(ForgeError::io("write", path))?;
+    let backup_base = backup_base_path(path);
+    let base = base_path(path);
+    if vfs.exists(&backup_base) {
+        if let Some(dir) = base.parent() {
+            vfs.create_dir_all(dir)
+                .map_err(ForgeError::io("create", dir))?;
+        }
+        vfs.copy(&backup_base, &base)
+            .map_err(ForgeError::io("write", &base))?;
+        let _ = vfs.remove_file(&backup_base);
+    } else {
+        remove_base(vfs, path);
+    }
+    vfs.remove_file(&backup)
+        .map_err(ForgeError::io("remove", &backup))
+}
+
+/// Remove the agents `module_name` recorded in `dst_dir`'s manifest that
+/// are not in `current_agents`, returning their names. An agent edited since
+/// it was deployed (see [`crate::manifest::is_modified`]) is kept.
 pub fn clean_orphaned_agents(
+    vfs: &dyn Vfs,
     dst_dir: &Path,
     module_name: &str,
     current_agents: &[String],
-    provider: Provider,
+    provider: &Provider,
     dry_run: bool,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, ForgeError> {
     if module_name.is_empty() {
         return Ok(Vec::new());
     }
 
-    let previous = crate::manifest::read(dst_dir, module_name);
-    let ext = provider.agent_extension();
+    let previous = crate::manifest::read(vfs, dst_dir, module_name);
     let mut removed = Vec::new();
 
     for name in &previous {
         if current_agents.contains(name) {
             continue;
         }
-        let path = dst_dir.join(format!("{name}.{ext}"));
-        if !path.exists() {
+        let filename = provider.agent_filename(name);
+        let path = dst_dir.join(&filename);
+        if !vfs.exists(&path) {
+            continue;
+        }
+        if crate::manifest::is_modified(vfs, dst_dir, module_name, &filename) {
+            tracing::warn!(path = %path.display(), "orphan edited since deploy; kept");
             continue;
         }
         if !dry_run {
-            std::fs::remove_file(&path)
-                .map_err(|e| format!("failed to remove {}: {e}", path.display()))?;
-            if provider == Provider::Codex {
+            vfs.remove_file(&path)
+                .map_err(ForgeError::io("remove", &path))?;
+            remove_base(vfs, &path);
+            if *provider == Provider::Codex {
                 let prompt_path = dst_dir.join(format!("{name}.prompt.md"));
-                if prompt_path.exists() {
-                    let _ = std::fs::remove_file(&prompt_path);
+                if vfs.exists(&prompt_path) {
+                    if let Err(e) = vfs.remove_file(&prompt_path) {
+                        tracing::warn!(path = %prompt_path.display(), "failed to remove: {e}");
+                    }
+                    remove_base(vfs, &prompt_path);
                 }
             }
         }
+        tracing::debug!(agent = %name, dry_run, "removed orphan");
         removed.push(name.clone());
     }
 
     Ok(removed)
 }
 
-fn project_key() -> Result<String, String> {
-    let cwd = env::current_dir().map_err(|e| format!("failed to get cwd: {e}"))?;
+fn project_key() -> Result<String, ForgeError> {
+    let cwd = env::current_dir().map_err(|source| ForgeError::Io {
+        context: "failed to get cwd".to_string(),
+        source,
+    })?;
     Ok(cwd.to_string_lossy().replace('/', "-"))
 }
 
-pub fn scope_dirs(scope: &str, home: &Path, providers: &[String]) -> Result<Vec<PathBuf>, String> {
-    let user_dirs: Vec<PathBuf> = providers
-        .iter()
-        .map(|p| home.join(format!(".{p}/agents")))
-        .collect();
-    let workspace_dirs: Vec<PathBuf> = providers
-        .iter()
-        .map(|p| PathBuf::from(format!(".{p}/agents")))
-        .collect();
-
-    match scope {
-        "user" => Ok(user_dirs),
-        "workspace" => Ok(workspace_dirs),
-        "project" => {
-            let key = project_key()?;
-            Ok(providers
-                .iter()
-                .map(|p| home.join(format!(".{p}/projects/{key}/agents")))
-                .collect())
+/// The agent directories of `providers` for `scope`; `all` is every user
+/// and workspace directory, skipping the scopes a provider has none for.
+pub fn scope_dirs(
+    scope: &str,
+    home: &Path,
+    providers: &[String],
+    custom: &[Arc<CustomProvider>],
+) -> Result<Vec<PathBuf>, ForgeError> {
+    let scopes: &[&str] = match scope {
+        "user" => &["user"],
+        "workspace" => &["workspace"],
+        "project" => &["project"],
+        "all" => &["user", "workspace"],
+        other => {
+            return Err(ForgeError::Config(format!(
+                "invalid scope {other:?}: use user, workspace, project, or all"
+            )))
         }
-        "all" => {
-            let mut all = user_dirs;
-            all.extend(workspace_dirs);
-            Ok(all)
+    };
+    let mut dirs = Vec::new();
+    for &each in scopes {
+        for name in providers {
+            let provider = Provider::named(name, custom);
+            match scope_dir(name, provider.as_ref(), each, home)? {
+                Some(dir) => dirs.push(dir),
+                None if scope == "all" => {}
+                None => {
+                    return Err(ForgeError::Config(format!(
+                        "{name} reads no agents at {each} scope: use --scope workspace"
+                    )))
+                }
+            }
         }
-        other => Err(format!(
-            "invalid scope {other:?}: use user, workspace, project, or all"
-        )),
     }
+    Ok(dirs)
+}
+
+/// `provider`'s agent directory for one scope; a provider forge does not
+/// know keeps its agents in `.<name>/agents`.
+fn scope_dir(
+    name: &str,
+    provider: Option<&Provider>,
+    scope: &str,
+    home: &Path,
+) -> Result<Option<PathBuf>, ForgeError> {
+    Ok(match (provider, scope) {
+        (Some(p), "project") => p.project_agents_path(&project_key()?).map(|d| home.join(d)),
+        (Some(p), "user") => p.agents_path(scope).map(|d| home.join(d)),
+        (Some(p), _) => p.agents_path(scope).map(PathBuf::from),
+        (None, "project") => Some(home.join(format!(".{name}/projects/{}/agents", project_key()?))),
+        (None, "user") => Some(home.join(format!(".{name}/agents"))),
+        (None, _) => Some(PathBuf::from(format!(".{name}/agents"))),
+    })
 }
 
 // ─── Codex config.toml managed block ───
@@ -429,22 +1466,32 @@ pub struct CodexConfigEntry {
     pub description: String,
 }
 
-pub fn format_codex_config_block(entries: &[CodexConfigEntry], source_prefix: &str) -> String {
-    let mut out = String::new();
-    let _ = writeln!(out, "{CODEX_BLOCK_BEGIN}");
-    let _ = writeln!(out, "# Generated by install-agents ({source_prefix})");
-    for entry in entries {
-        let _ = writeln!(out);
-        let _ = writeln!(out, "[agents.{}]", entry.name);
-        let _ = writeln!(out, "description = \"{}\"", toml_escape(&entry.description));
-        let _ = writeln!(
-            out,
-            "config_file = \"agents/{}.toml\"",
-            toml_escape(&entry.name)
-        );
+pub fn format_codex_config_block(
+    entries: &[CodexConfigEntry],
+    source_prefix: &str,
+) -> Result<String, ForgeError> {
+    render_codex_config_block(&Templates::default(), entries, source_prefix)
+}
+
+/// The managed block around the `codex-config.toml` template. The markers
+/// stay outside the template so an override cannot break later cleans.
+fn render_codex_config_block(
+    templates: &Templates,
+    entries: &[CodexConfigEntry],
+    source_prefix: &str,
+) -> Result<String, ForgeError> {
+    let agents: Vec<_> = entries
+        .iter()
+        .map(|e| json!({"name": e.name, "description": e.description}))
+        .collect();
+    let context = json!({"source_prefix": source_prefix, "agents": agents});
+    let mut inner = templates
+        .render("codex-config.toml", &context)
+        .map_err(ForgeError::Config)?;
+    if !inner.is_empty() && !inner.ends_with('\n') {
+        inner.push('\n');
     }
-    let _ = writeln!(out, "{CODEX_BLOCK_END}");
-    out
+    Ok(format!("{CODEX_BLOCK_BEGIN}\n{inner}{CODEX_BLOCK_END}\n"))
 }
 
 pub fn strip_managed_block(content: &str, begin: &str, end: &str) -> String {
@@ -472,15 +1519,17 @@ pub fn strip_managed_block(content: &str, begin: &str, end: &str) -> String {
 }
 
 pub fn write_codex_config_block(
+    vfs: &dyn Vfs,
     config_path: &Path,
     entries: &[CodexConfigEntry],
     source_prefix: &str,
+    templates: &Templates,
     dry_run: bool,
-) -> Result<(), String> {
-    let existing = std::fs::read_to_string(config_path).unwrap_or_default();
+) -> Result<(), ForgeError> {
+    let existing = vfs.read_to_string(config_path).unwrap_or_default();
     let stripped = strip_managed_block(&existing, CODEX_BLOCK_BEGIN, CODEX_BLOCK_END);
 
-    let block = format_codex_config_block(entries, source_prefix);
+    let block = render_codex_config_block(templates, entries, source_prefix)?;
 
     let mut rendered = String::new();
     if !stripped.is_empty() {
@@ -494,18 +1543,22 @@ pub fn write_codex_config_block(
 
     if !dry_run {
         if let Some(parent) = config_path.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+            vfs.create_dir_all(parent)
+                .map_err(ForgeError::io("create", parent))?;
         }
-        std::fs::write(config_path, &rendered)
-            .map_err(|e| format!("failed to write {}: {e}", config_path.display()))?;
+        vfs.write(config_path, rendered.as_bytes())
+            .map_err(ForgeError::io("write", config_path))?;
     }
 
     Ok(())
 }
 
-pub fn clean_codex_config_block(config_path: &Path, dry_run: bool) -> Result<(), String> {
-    let Ok(existing) = std::fs::read_to_string(config_path) else {
+pub fn clean_codex_config_block(
+    vfs: &dyn Vfs,
+    config_path: &Path,
+    dry_run: bool,
+) -> Result<(), ForgeError> {
+    let Ok(existing) = vfs.read_to_string(config_path) else {
         return Ok(());
     };
 
@@ -516,16 +1569,12 @@ pub fn clean_codex_config_block(config_path: &Path, dry_run: bool) -> Result<(),
     let stripped = strip_managed_block(&existing, CODEX_BLOCK_BEGIN, CODEX_BLOCK_END);
 
     if !dry_run {
-        std::fs::write(config_path, &stripped)
-            .map_err(|e| format!("failed to write {}: {e}", config_path.display()))?;
+        vfs.write(config_path, stripped.as_bytes())
+            .map_err(ForgeError::io("write", config_path))?;
     }
 
     Ok(())
 }
 
-fn toml_escape(value: &str) -> String {
-    value.replace('\\', "\\\\").replace('"', "\\\"")
-}
-
 #[cfg(test)]
 mod tests;
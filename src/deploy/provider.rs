@@ -1,11 +1,45 @@
-use std::path::Path;
+use std::path::{Component, Path};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// How a custom provider names agent files: as written (`SecurityArchitect`)
+/// or kebab-case (`security-architect`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameStyle {
+    Pascal,
+    Kebab,
+}
+
+/// A provider a module declares in `defaults.yaml` (see
+/// [`crate::sidecar::SidecarConfig::custom_providers`]), for in-house
+/// assistants forge has no built-in support for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomProvider {
+    pub name: String,
+    /// Where agents deploy, relative to the home directory or workspace.
+    pub dir: String,
+    pub extension: String,
+    pub name_style: NameStyle,
+    /// The template agents render with, a built-in or one of the module's
+    /// `templates/<name>.j2`.
+    pub template: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Provider {
     Claude,
     Gemini,
     Codex,
     OpenCode,
+    Cursor,
+    Windsurf,
+    /// GitHub Copilot, which reads each agent as its own
+    /// `.github/instructions/<name>.instructions.md`, owned through its
+    /// `source:` frontmatter like other providers' agents. Its one shared
+    /// file, `.github/copilot-instructions.md`, holds only `install-memory`'s
+    /// managed block.
+    Copilot,
+    Zed,
+    Custom(Arc<CustomProvider>),
 }
 
 impl Provider {
@@ -16,18 +50,59 @@ impl Provider {
             "gemini" => Some(Self::Gemini),
             "codex" => Some(Self::Codex),
             "opencode" => Some(Self::OpenCode),
+            "cursor" => Some(Self::Cursor),
+            "windsurf" => Some(Self::Windsurf),
+            "copilot" => Some(Self::Copilot),
+            "zed" => Some(Self::Zed),
             _ => None,
         }
     }
 
+    /// The built-in provider `name`, or else the one in `custom` (declared
+    /// in a module's `defaults.yaml`) with that name.
+    pub fn named(name: &str, custom: &[Arc<CustomProvider>]) -> Option<Self> {
+        Self::from_str(name).or_else(|| {
+            custom
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(name))
+                .map(|c| Self::Custom(Arc::clone(c)))
+        })
+    }
+
+    /// The built-in provider whose directory (`.gemini`, `.github`, ...) is
+    /// one of the components of `path`; Claude when none is.
     pub fn from_path(path: &Path) -> Self {
-        let path_str = path.to_string_lossy();
-        if path_str.contains(".gemini") {
+        Self::for_path(path, &[])
+    }
+
+    /// [`Provider::from_path`], preferring a provider in `custom` whose
+    /// `dir` ends `path`.
+    pub fn for_path(path: &Path, custom: &[Arc<CustomProvider>]) -> Self {
+        if let Some(c) = custom.iter().rev().find(|c| path.ends_with(&c.dir)) {
+            return Self::Custom(Arc::clone(c));
+        }
+        let dirs: Vec<&str> = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+        let has = |dir: &str| dirs.contains(&dir);
+        if has(".gemini") {
             Self::Gemini
-        } else if path_str.contains(".codex") {
+        } else if has(".codex") {
             Self::Codex
-        } else if path_str.contains(".opencode") {
+        } else if has(".opencode") {
             Self::OpenCode
+        } else if has(".cursor") {
+            Self::Cursor
+        } else if has(".windsurf") {
+            Self::Windsurf
+        } else if has(".github") {
+            Self::Copilot
+        } else if has(".zed") {
+            Self::Zed
         } else {
             Self::Claude
         }
@@ -35,14 +110,41 @@ impl Provider {
 
     pub fn format_name(&self, name: &str) -> String {
         match self {
-            Self::Gemini | Self::OpenCode => to_kebab_case(name),
-            Self::Claude | Self::Codex => name.to_string(),
+            Self::Gemini
+            | Self::OpenCode
+            | Self::Cursor
+            | Self::Windsurf
+            | Self::Copilot
+            | Self::Zed => to_kebab_case(name),
+            Self::Custom(custom) if custom.name_style == NameStyle::Kebab => to_kebab_case(name),
+            Self::Claude | Self::Codex | Self::Custom(_) => name.to_string(),
         }
     }
 
     pub fn map_tool(&self, tool: &str) -> String {
         match self {
-            Self::Claude | Self::Codex | Self::OpenCode => tool.to_string(),
+            Self::Claude
+            | Self::Codex
+            | Self::Cursor
+            | Self::Windsurf
+            | Self::Copilot
+            | Self::Custom(_) => tool.to_string(),
+            Self::OpenCode => match tool.to_ascii_lowercase().as_str() {
+                "multiedit" => "edit".to_string(),
+                "ls" => "list".to_string(),
+                other => other.to_string(),
+            },
+            Self::Zed => match tool.to_ascii_lowercase().as_str() {
+                "read" => "read_file".to_string(),
+                "write" | "edit" | "multiedit" => "edit_file".to_string(),
+                "grep" => "grep".to_string(),
+                "glob" => "find_path".to_string(),
+                "ls" => "list_directory".to_string(),
+                "bash" | "shell" | "run" => "terminal".to_string(),
+                "websearch" => "web_search".to_string(),
+                "webfetch" => "fetch".to_string(),
+                other => other.to_string(),
+            },
             Self::Gemini => match tool.to_ascii_lowercase().as_str() {
                 "read" => "read_file".to_string(),
                 "write" => "write_file".to_string(),
@@ -67,19 +169,91 @@ impl Provider {
             .join(", ")
     }
 
-    pub fn agent_extension(&self) -> &'static str {
+    pub fn agent_extension(&self) -> &str {
         match self {
             Self::Codex => "toml",
-            Self::Claude | Self::Gemini | Self::OpenCode => "md",
+            Self::Cursor => "mdc",
+            Self::Copilot => "instructions.md",
+            Self::Custom(custom) => &custom.extension,
+            Self::Claude | Self::Gemini | Self::OpenCode | Self::Windsurf | Self::Zed => "md",
         }
     }
 
-    pub fn as_str(&self) -> &'static str {
+    /// The directory under `.<provider>` that agents deploy to: Cursor and
+    /// Windsurf read them as rules, Zed as prompts.
+    pub fn agents_dir(&self) -> &'static str {
+        match self {
+            Self::Cursor | Self::Windsurf => "rules",
+            Self::Copilot => "instructions",
+            Self::Zed => "prompts",
+            Self::Claude | Self::Gemini | Self::Codex | Self::OpenCode | Self::Custom(_) => {
+                "agents"
+            }
+        }
+    }
+
+    /// Where agents deploy for `scope`, relative to the home directory
+    /// (`user`) or the workspace (`workspace`); `None` where the provider
+    /// reads none. Copilot reads instruction files only from the
+    /// repository's `.github/instructions`. A custom provider declares one
+    /// directory for both scopes.
+    pub fn agents_path(&self, scope: &str) -> Option<String> {
+        match (self, scope) {
+            (Self::Custom(custom), "user" | "workspace") => Some(custom.dir.clone()),
+            (Self::Custom(_), _) => None,
+            (_, "workspace") => Some(format!("{}/{}", self.workspace_root(), self.agents_dir())),
+            (Self::Copilot, _) => None,
+            (_, "user") => Some(format!(".{}/{}", self.as_str(), self.agents_dir())),
+            _ => None,
+        }
+    }
+
+    /// Where agents deploy for the project keyed `key`, relative to the home
+    /// directory; `None` for providers that keep no per-project settings
+    /// under `~/.<provider>/projects`.
+    pub fn project_agents_path(&self, key: &str) -> Option<String> {
+        match self {
+            Self::Claude | Self::Gemini | Self::Codex | Self::OpenCode => {
+                Some(format!(".{}/projects/{key}/agents", self.as_str()))
+            }
+            Self::Cursor | Self::Windsurf | Self::Copilot | Self::Zed | Self::Custom(_) => None,
+        }
+    }
+
+    /// The directory a provider reads workspace files from: Copilot keeps
+    /// them under `.github`, the others under `.<provider>`.
+    pub fn workspace_root(&self) -> String {
+        match self {
+            Self::Copilot => ".github".to_string(),
+            _ => format!(".{}", self.as_str()),
+        }
+    }
+
+    /// The file an agent named `name` deploys to. Providers other than
+    /// Claude, Gemini, and Codex name agents and rules after their file, so
+    /// there it is the kebab-case name.
+    pub fn agent_filename(&self, name: &str) -> String {
+        let stem = match self {
+            Self::OpenCode | Self::Cursor | Self::Windsurf | Self::Copilot | Self::Zed => {
+                to_kebab_case(name)
+            }
+            Self::Custom(_) => self.format_name(name),
+            Self::Claude | Self::Gemini | Self::Codex => name.to_string(),
+        };
+        format!("{stem}.{}", self.agent_extension())
+    }
+
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Claude => "claude",
             Self::Gemini => "gemini",
             Self::Codex => "codex",
             Self::OpenCode => "opencode",
+            Self::Cursor => "cursor",
+            Self::Windsurf => "windsurf",
+            Self::Copilot => "copilot",
+            Self::Zed => "zed",
+            Self::Custom(custom) => &custom.name,
         }
     }
 }
@@ -1,9 +1,20 @@
 use super::*;
+use crate::deploy::provider::{CustomProvider, NameStyle};
+use crate::progress::NoProgress;
 use crate::sidecar::SidecarConfig;
+use crate::vfs::{MemoryFs, RealFs};
 use std::fs;
 use std::path::Path;
 use tempfile::TempDir;
 
+/// An agents directory inside a temporary one, which also holds the
+/// `.forge-state` kept beside it.
+fn agents_dir(tmp: &TempDir) -> PathBuf {
+    let dir = tmp.path().join("agents");
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
 fn write_yaml(dir: &Path, filename: &str, content: &str) {
     fs::write(dir.join(filename), content).unwrap();
 }
@@ -120,6 +131,58 @@ fn from_str_opencode() {
     assert_eq!(Provider::from_str("opencode"), Some(Provider::OpenCode));
 }
 
+#[test]
+fn from_str_cursor() {
+    assert_eq!(Provider::from_str("cursor"), Some(Provider::Cursor));
+}
+
+#[test]
+fn from_str_windsurf() {
+    assert_eq!(Provider::from_str("Windsurf"), Some(Provider::Windsurf));
+}
+
+#[test]
+fn from_str_copilot() {
+    assert_eq!(Provider::from_str("copilot"), Some(Provider::Copilot));
+}
+
+#[test]
+fn from_str_zed() {
+    assert_eq!(Provider::from_str("zed"), Some(Provider::Zed));
+}
+
+#[test]
+fn custom_provider_resolves_by_name_and_path() {
+    let custom = [Arc::new(CustomProvider {
+        name: "acme-reg".into(),
+        dir: ".acme-reg/agents".into(),
+        extension: "txt".into(),
+        name_style: NameStyle::Kebab,
+        template: "agent-claude.md".into(),
+    })];
+    let provider = Provider::Custom(Arc::clone(&custom[0]));
+    assert_eq!(Provider::named("Acme-Reg", &custom), Some(provider.clone()));
+    assert_eq!(Provider::from_str("acme-reg"), None);
+    assert_eq!(
+        Provider::for_path(Path::new("/home/u/.acme-reg/agents"), &custom),
+        provider
+    );
+    assert_eq!(
+        Provider::from_path(Path::new("/home/u/.acme-reg/agents")),
+        Provider::Claude
+    );
+    assert_eq!(provider.as_str(), "acme-reg");
+    assert_eq!(
+        provider.agent_filename("SecurityArchitect"),
+        "security-architect.txt"
+    );
+    assert_eq!(
+        provider.agents_path("workspace").as_deref(),
+        Some(".acme-reg/agents")
+    );
+    assert_eq!(provider.project_agents_path("key"), None);
+}
+
 #[test]
 fn from_str_invalid() {
     assert_eq!(Provider::from_str("openai"), None);
@@ -151,6 +214,38 @@ fn from_path_opencode() {
     );
 }
 
+#[test]
+fn from_path_cursor() {
+    assert_eq!(
+        Provider::from_path(Path::new("/work/.cursor/rules")),
+        Provider::Cursor
+    );
+}
+
+#[test]
+fn from_path_windsurf() {
+    assert_eq!(
+        Provider::from_path(Path::new("/work/.windsurf/rules")),
+        Provider::Windsurf
+    );
+}
+
+#[test]
+fn from_path_copilot() {
+    assert_eq!(
+        Provider::from_path(Path::new("/work/.github/instructions")),
+        Provider::Copilot
+    );
+}
+
+#[test]
+fn from_path_zed() {
+    assert_eq!(
+        Provider::from_path(Path::new("/work/.zed/prompts")),
+        Provider::Zed
+    );
+}
+
 #[test]
 fn from_path_claude_default() {
     assert_eq!(
@@ -159,6 +254,32 @@ fn from_path_claude_default() {
     );
 }
 
+#[test]
+fn from_path_matches_whole_components() {
+    assert_eq!(
+        Provider::from_path(Path::new("/work/my.github-stuff/agents")),
+        Provider::Claude
+    );
+    assert_eq!(
+        Provider::from_path(Path::new("/home/u/notes.gemini/agents")),
+        Provider::Claude
+    );
+}
+
+#[test]
+fn scope_dirs_resolve_custom_providers() {
+    let custom = [Arc::new(CustomProvider {
+        name: "acme".into(),
+        dir: ".acme/agents".into(),
+        extension: "md".into(),
+        name_style: NameStyle::Pascal,
+        template: "agent-claude.md".into(),
+    })];
+    let home = Path::new("/home/user");
+    let dirs = scope_dirs("user", home, &["acme".to_string()], &custom).unwrap();
+    assert_eq!(dirs, vec![home.join(".acme/agents")]);
+}
+
 // ─── Provider: map_tool ───
 
 #[test]
@@ -207,9 +328,21 @@ fn map_tool_claude_identity() {
 }
 
 #[test]
-fn map_tool_opencode_identity() {
-    assert_eq!(Provider::OpenCode.map_tool("Read"), "Read");
-    assert_eq!(Provider::OpenCode.map_tool("Bash"), "Bash");
+fn map_tool_opencode_lowercase() {
+    assert_eq!(Provider::OpenCode.map_tool("Read"), "read");
+    assert_eq!(Provider::OpenCode.map_tool("Bash"), "bash");
+    assert_eq!(Provider::OpenCode.map_tool("MultiEdit"), "edit");
+    assert_eq!(Provider::OpenCode.map_tool("LS"), "list");
+}
+
+#[test]
+fn map_tool_zed_builtin_tools() {
+    assert_eq!(Provider::Zed.map_tool("Read"), "read_file");
+    assert_eq!(Provider::Zed.map_tool("MultiEdit"), "edit_file");
+    assert_eq!(Provider::Zed.map_tool("Bash"), "terminal");
+    assert_eq!(Provider::Zed.map_tool("Glob"), "find_path");
+    assert_eq!(Provider::Zed.map_tool("WebFetch"), "fetch");
+    assert_eq!(Provider::Zed.map_tool("diagnostics"), "diagnostics");
 }
 
 // ─── Provider: map_tools ───
@@ -252,6 +385,30 @@ fn agent_extension_opencode_md() {
     assert_eq!(Provider::OpenCode.agent_extension(), "md");
 }
 
+#[test]
+fn agent_filename_kebab_for_opencode_only() {
+    assert_eq!(
+        Provider::OpenCode.agent_filename("SecurityArchitect"),
+        "security-architect.md"
+    );
+    assert_eq!(
+        Provider::Gemini.agent_filename("SecurityArchitect"),
+        "SecurityArchitect.md"
+    );
+    assert_eq!(
+        Provider::Codex.agent_filename("SecurityArchitect"),
+        "SecurityArchitect.toml"
+    );
+    assert_eq!(
+        Provider::Cursor.agent_filename("SecurityArchitect"),
+        "security-architect.mdc"
+    );
+    assert_eq!(
+        Provider::Copilot.agent_filename("SecurityArchitect"),
+        "security-architect.instructions.md"
+    );
+}
+
 // ─── Provider: as_str ───
 
 #[test]
@@ -260,6 +417,10 @@ fn as_str_roundtrip() {
     assert_eq!(Provider::Gemini.as_str(), "gemini");
     assert_eq!(Provider::Codex.as_str(), "codex");
     assert_eq!(Provider::OpenCode.as_str(), "opencode");
+    assert_eq!(Provider::Cursor.as_str(), "cursor");
+    assert_eq!(Provider::Windsurf.as_str(), "windsurf");
+    assert_eq!(Provider::Copilot.as_str(), "copilot");
+    assert_eq!(Provider::Zed.as_str(), "zed");
 }
 
 // ─── Deploy Fixture ───
@@ -285,10 +446,8 @@ You are a security architect.
         agent_content,
         "SecurityArchitect.md",
         &claude_dir,
-        Provider::Claude,
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
     let deployed = fs::read_to_string(claude_dir.join("SecurityArchitect.md")).unwrap();
@@ -301,10 +460,8 @@ You are a security architect.
         agent_content,
         "SecurityArchitect.md",
         &gemini_dir,
-        Provider::Gemini,
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Gemini),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
     let deployed = fs::read_to_string(gemini_dir.join("SecurityArchitect.md")).unwrap();
@@ -327,13 +484,73 @@ fn make_meta() -> AgentMeta {
         source_file: "SecurityArchitect.md".into(),
         source: "SecurityArchitect.md".into(),
         reasoning_effort: None,
+        provenance: Provenance::default(),
     }
 }
 
+#[test]
+fn format_opencode_as_subagent_with_tool_flags() {
+    let mut meta = make_meta();
+    meta.display_name = "security-architect".into();
+    meta.skills = vec!["Git".into()];
+    let output = format_agent_output(&meta, "Body.\n", &Provider::OpenCode, true).unwrap();
+    assert_eq!(
+        output.primary,
+        "---\nname: security-architect\ndescription: System architect\nmode: subagent\n\
+         model: sonnet\ntools:\n  \"*\": false\n  read: true\n  bash: true\n\
+         source: SecurityArchitect.md\n---\nBody.\n"
+    );
+    assert!(output.prompt_file.is_none());
+}
+
+#[test]
+fn format_cursor_as_rule() {
+    let meta = make_meta();
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Cursor, true).unwrap();
+    assert_eq!(
+        output.primary,
+        "---\ndescription: System architect\nglobs:\nalwaysApply: false\n\
+         source: SecurityArchitect.md\n---\nBody.\n"
+    );
+    assert!(output.prompt_file.is_none());
+}
+
+#[test]
+fn format_windsurf_as_model_decision_rule() {
+    let meta = make_meta();
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Windsurf, true).unwrap();
+    assert_eq!(
+        output.primary,
+        "---\ntrigger: model_decision\ndescription: System architect\n\
+         source: SecurityArchitect.md\n---\nBody.\n"
+    );
+}
+
+#[test]
+fn format_copilot_as_instructions() {
+    let meta = make_meta();
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Copilot, true).unwrap();
+    assert_eq!(
+        output.primary,
+        "---\ndescription: System architect\nsource: SecurityArchitect.md\n---\nBody.\n"
+    );
+}
+
+#[test]
+fn format_zed_with_mapped_tools() {
+    let meta = make_meta();
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Zed, true).unwrap();
+    assert_eq!(
+        output.primary,
+        "---\nname: SecurityArchitect\ndescription: System architect\n\
+         tools:\n  - read_file\n  - terminal\nsource: SecurityArchitect.md\n---\nBody.\n"
+    );
+}
+
 #[test]
 fn format_claude_with_model_and_tools() {
     let meta = make_meta();
-    let output = format_agent_output(&meta, "Body text.\n", Provider::Claude, true);
+    let output = format_agent_output(&meta, "Body text.\n", &Provider::Claude, true).unwrap();
     assert!(output.primary.contains("name: SecurityArchitect\n"));
     assert!(output.primary.contains("model: sonnet\n"));
     assert!(output.primary.contains("tools: Read, Bash\n"));
@@ -346,7 +563,7 @@ fn format_claude_with_model_and_tools() {
 #[test]
 fn format_claude_without_model() {
     let meta = make_meta();
-    let output = format_agent_output(&meta, "Body.\n", Provider::Claude, false);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Claude, false).unwrap();
     assert!(!output.primary.contains("model:"));
     assert!(output.primary.contains("name: SecurityArchitect"));
 }
@@ -355,7 +572,7 @@ fn format_claude_without_model() {
 fn format_claude_without_tools() {
     let mut meta = make_meta();
     meta.tools = None;
-    let output = format_agent_output(&meta, "Body.\n", Provider::Claude, true);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Claude, true).unwrap();
     assert!(!output.primary.contains("tools:"));
 }
 
@@ -371,8 +588,9 @@ fn format_gemini_with_mapped_tools() {
         source_file: "SecurityArchitect.md".into(),
         source: "SecurityArchitect.md".into(),
         reasoning_effort: None,
+        provenance: Provenance::default(),
     };
-    let output = format_agent_output(&meta, "Body.\n", Provider::Gemini, true);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Gemini, true).unwrap();
     assert!(output.primary.contains("name: security-architect\n"));
     assert!(output.primary.contains("kind: local\n"));
     assert!(output.primary.contains("model: gemini-2.0-flash\n"));
@@ -393,8 +611,9 @@ fn format_gemini_without_model() {
         source_file: "Dev.md".into(),
         source: "Dev.md".into(),
         reasoning_effort: None,
+        provenance: Provenance::default(),
     };
-    let output = format_agent_output(&meta, "Body.\n", Provider::Gemini, false);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Gemini, false).unwrap();
     assert!(!output.primary.contains("model:"));
     assert!(output.primary.contains("kind: local"));
 }
@@ -403,7 +622,7 @@ fn format_gemini_without_model() {
 fn format_codex_toml_output() {
     let mut meta = make_meta();
     meta.reasoning_effort = Some("low".into());
-    let output = format_agent_output(&meta, "Body.\n", Provider::Codex, true);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Codex, true).unwrap();
     assert!(output.primary.contains("# source: SecurityArchitect.md"));
     assert!(output
         .primary
@@ -422,7 +641,7 @@ fn format_codex_toml_output() {
 #[test]
 fn format_codex_no_reasoning_effort() {
     let meta = make_meta();
-    let output = format_agent_output(&meta, "Body.\n", Provider::Codex, true);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Codex, true).unwrap();
     assert!(!output.primary.contains("model_reasoning_effort"));
     assert!(output
         .primary
@@ -433,27 +652,54 @@ fn format_codex_no_reasoning_effort() {
 #[test]
 fn format_codex_without_model() {
     let meta = make_meta();
-    let output = format_agent_output(&meta, "Body.\n", Provider::Codex, false);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Codex, false).unwrap();
     assert!(!output.primary.contains("model ="));
     assert!(output
         .primary
         .contains("description = \"System architect\""));
 }
 
+#[test]
+fn format_embeds_provenance() {
+    let meta = AgentMeta {
+        provenance: Provenance {
+            license: Some("MIT".into()),
+            attribution: Some("Jane Doe: original prompts".into()),
+        },
+        ..make_meta()
+    };
+    let claude = format_agent_output(&meta, "B.\n", &Provider::Claude, true).unwrap();
+    assert!(claude.primary.contains("license: MIT\n"));
+    assert_eq!(
+        parse::fm_value(&claude.primary, "attribution").as_deref(),
+        Some("Jane Doe: original prompts")
+    );
+
+    let codex = format_agent_output(&meta, "B.\n", &Provider::Codex, true).unwrap();
+    assert!(codex.primary.contains("# license: MIT\n"));
+    let (_, prompt) = codex.prompt_file.unwrap();
+    assert!(prompt.starts_with("<!-- attribution: Jane Doe: original prompts -->\n"));
+    assert!(prompt.contains("<!-- license: MIT -->\n\nB.\n"));
+
+    let plain = format_agent_output(&make_meta(), "B.\n", &Provider::Claude, true).unwrap();
+    assert!(!plain.primary.contains("license:"));
+}
+
 #[test]
 fn format_source_always_present() {
     let meta = make_meta();
-    let claude = format_agent_output(&meta, "B.\n", Provider::Claude, true);
+    let claude = format_agent_output(&meta, "B.\n", &Provider::Claude, true).unwrap();
     let gemini = format_agent_output(
         &AgentMeta {
             display_name: "security-architect".into(),
             ..make_meta()
         },
         "B.\n",
-        Provider::Gemini,
+        &Provider::Gemini,
         true,
-    );
-    let codex = format_agent_output(&meta, "B.\n", Provider::Codex, true);
+    )
+    .unwrap();
+    let codex = format_agent_output(&meta, "B.\n", &Provider::Codex, true).unwrap();
     assert!(claude.primary.contains("source: SecurityArchitect.md"));
     assert!(gemini.primary.contains("source: SecurityArchitect.md"));
     assert!(codex.primary.contains("# source: SecurityArchitect.md"));
@@ -465,7 +711,7 @@ fn format_source_always_present() {
 fn format_body_preserved() {
     let meta = make_meta();
     let body = "## Role\n\nYou review architecture.\n\n## Constraints\n\nBe thorough.\n";
-    let output = format_agent_output(&meta, body, Provider::Claude, true);
+    let output = format_agent_output(&meta, body, &Provider::Claude, true).unwrap();
     assert!(output.primary.contains(body));
 }
 
@@ -473,7 +719,7 @@ fn format_body_preserved() {
 fn format_codex_body_in_prompt_file() {
     let meta = make_meta();
     let body = "## Role\n\nYou review architecture.\n\n## Constraints\n\nBe thorough.\n";
-    let output = format_agent_output(&meta, body, Provider::Codex, true);
+    let output = format_agent_output(&meta, body, &Provider::Codex, true).unwrap();
     assert!(!output.primary.contains("## Role"));
     let (_, prompt_content) = output.prompt_file.unwrap();
     assert!(prompt_content.contains(body));
@@ -485,14 +731,16 @@ fn format_codex_body_in_prompt_file() {
 fn format_claude_with_skills() {
     let mut meta = make_meta();
     meta.skills = vec!["Git".into(), "SecretScan".into()];
-    let output = format_agent_output(&meta, "Body.\n", Provider::Claude, true);
-    assert!(output.primary.contains("skills:\n  - Git\n  - SecretScan\n"));
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Claude, true).unwrap();
+    assert!(output
+        .primary
+        .contains("skills:\n  - Git\n  - SecretScan\n"));
 }
 
 #[test]
 fn format_claude_without_skills() {
     let meta = make_meta();
-    let output = format_agent_output(&meta, "Body.\n", Provider::Claude, true);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Claude, true).unwrap();
     assert!(!output.primary.contains("skills:"));
 }
 
@@ -501,7 +749,7 @@ fn format_gemini_with_skills() {
     let mut meta = make_meta();
     meta.display_name = "security-architect".into();
     meta.skills = vec!["Git".into()];
-    let output = format_agent_output(&meta, "Body.\n", Provider::Gemini, true);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Gemini, true).unwrap();
     assert!(output.primary.contains("skills:\n  - Git\n"));
 }
 
@@ -509,10 +757,56 @@ fn format_gemini_with_skills() {
 fn format_codex_ignores_skills() {
     let mut meta = make_meta();
     meta.skills = vec!["Git".into()];
-    let output = format_agent_output(&meta, "Body.\n", Provider::Codex, true);
+    let output = format_agent_output(&meta, "Body.\n", &Provider::Codex, true).unwrap();
     assert!(!output.primary.contains("skills"));
 }
 
+#[test]
+fn module_template_overrides_agent_output() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join("templates")).unwrap();
+    write_yaml(
+        dir.path(),
+        "templates/agent-claude.md.j2",
+        "---\nname: {{ name }}\nsource: {{ source }}\nteam: review\n---\n{{ body }}\n",
+    );
+    let config = SidecarConfig::load(dir.path());
+    let dst = dir.path().join("out");
+    let content = "---\nname: Reviewer\ndescription: Reviews\n---\nBody.\n";
+
+    deploy_agent(
+        content,
+        "Reviewer.md",
+        &dst,
+        &config,
+        &DeployOptions::new(Provider::Claude),
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(dst.join("Reviewer.md")).unwrap(),
+        "---\nname: Reviewer\nsource: Reviewer.md\nteam: review\n---\nBody.\n"
+    );
+
+    // Gemini has its own template, which the module does not override.
+    let gemini = dir.path().join("gemini");
+    deploy_agent(
+        content,
+        "Reviewer.md",
+        &gemini,
+        &config,
+        &DeployOptions::new(Provider::Gemini),
+    )
+    .unwrap();
+    let entry = fs::read_dir(&gemini)
+        .unwrap()
+        .filter_map(Result::ok)
+        .find(|e| e.path().is_file())
+        .unwrap();
+    assert!(fs::read_to_string(entry.path())
+        .unwrap()
+        .contains("kind: local\n"));
+}
+
 #[test]
 fn extract_skills_from_config() {
     let dir = TempDir::new().unwrap();
@@ -523,8 +817,7 @@ fn extract_skills_from_config() {
     );
     let config = SidecarConfig::load(dir.path());
     let content = "---\nname: Developer\ndescription: Dev\nversion: 0.3.0\n---\nBody.\n";
-    let meta =
-        extract_agent_meta(content, "Developer.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.skills, vec!["Git", "RustDevelopment"]);
 }
 
@@ -532,8 +825,7 @@ fn extract_skills_from_config() {
 fn extract_skills_from_frontmatter_fallback() {
     let config = SidecarConfig::default();
     let content = "---\nclaude.name: Developer\nclaude.skills:\n  - Git\n  - DefensiveProgramming\n---\nBody.\n";
-    let meta =
-        extract_agent_meta(content, "Developer.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.skills, vec!["Git", "DefensiveProgramming"]);
 }
 
@@ -541,8 +833,7 @@ fn extract_skills_from_frontmatter_fallback() {
 fn extract_no_skills_returns_empty() {
     let config = SidecarConfig::default();
     let content = "---\nname: Developer\ndescription: Dev\n---\nBody.\n";
-    let meta =
-        extract_agent_meta(content, "Developer.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Claude, &config, "").unwrap();
     assert!(meta.skills.is_empty());
 }
 
@@ -562,7 +853,7 @@ claude.tools:
 Body.
 ";
     let config = SidecarConfig::default();
-    let meta = extract_agent_meta(content, "Developer.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.name, "Developer");
     assert_eq!(meta.display_name, "Developer");
     assert_eq!(meta.model, "sonnet");
@@ -575,7 +866,7 @@ fn extract_template_returns_none() {
     let content = "---\nclaude.name: Foo\n---\nBody.\n";
     let config = SidecarConfig::default();
     assert!(
-        extract_agent_meta(content, "_TemplateFoo.md", Provider::Claude, &config, "").is_none()
+        extract_agent_meta(content, "_TemplateFoo.md", &Provider::Claude, &config, "").is_none()
     );
 }
 
@@ -583,14 +874,14 @@ fn extract_template_returns_none() {
 fn extract_missing_name_returns_none() {
     let content = "---\nclaude.model: sonnet\n---\nBody.\n";
     let config = SidecarConfig::default();
-    assert!(extract_agent_meta(content, "Foo.md", Provider::Claude, &config, "").is_none());
+    assert!(extract_agent_meta(content, "Foo.md", &Provider::Claude, &config, "").is_none());
 }
 
 #[test]
 fn extract_defaults_model_to_sonnet() {
     let content = "---\nclaude.name: Tester\n---\nBody.\n";
     let config = SidecarConfig::default();
-    let meta = extract_agent_meta(content, "Tester.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Tester.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.model, "sonnet");
 }
 
@@ -601,7 +892,7 @@ fn extract_gemini_formats_display_name() {
     let meta = extract_agent_meta(
         content,
         "SecurityArchitect.md",
-        Provider::Gemini,
+        &Provider::Gemini,
         &config,
         "",
     )
@@ -622,7 +913,7 @@ Body.
 ";
     let config = SidecarConfig::default();
     let meta =
-        extract_agent_meta(content, "TheOpponent.md", Provider::Claude, &config, "").unwrap();
+        extract_agent_meta(content, "TheOpponent.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.name, "TheOpponent");
     assert_eq!(
         meta.description,
@@ -653,12 +944,52 @@ Body.
 ";
     let config = SidecarConfig::load(dir.path());
     let meta =
-        extract_agent_meta(content, "TheOpponent.md", Provider::Claude, &config, "").unwrap();
+        extract_agent_meta(content, "TheOpponent.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.name, "TheOpponent");
     assert_eq!(meta.model, "claude-opus-4-6");
     assert_eq!(meta.tools, Some("Read, Grep, Glob, WebSearch".into()));
 }
 
+#[test]
+fn extract_localized_description() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(
+        dir.path(),
+        "defaults.yaml",
+        "locale: cs\nagents:\n  Dev:\n    description.de: Entwickler. USE WHEN Code.\n",
+    );
+    let content = "\
+---
+name: Dev
+description: Developer. USE WHEN code.
+description.cs: Vývojář. USE WHEN kód.
+---
+Body.
+";
+    let describe = |config: &SidecarConfig| {
+        extract_agent_meta(content, "Dev.md", &Provider::Claude, config, "")
+            .unwrap()
+            .description
+    };
+    let config = SidecarConfig::load(dir.path());
+    assert_eq!(describe(&config), "Vývojář. USE WHEN kód.");
+    let config = SidecarConfig::load(dir.path()).with_locale(Some("de"));
+    assert_eq!(describe(&config), "Entwickler. USE WHEN Code.");
+    let config = SidecarConfig::load(dir.path()).with_locale(Some("fr"));
+    assert_eq!(describe(&config), "Developer. USE WHEN code.");
+
+    let only_en = "---\nname: Dev\ndescription.en: Developer.\n---\nBody.\n";
+    let meta = extract_agent_meta(
+        only_en,
+        "Dev.md",
+        &Provider::Claude,
+        &SidecarConfig::default(),
+        "",
+    )
+    .unwrap();
+    assert_eq!(meta.description, "Developer.");
+}
+
 // ─── deploy_agent ───
 
 fn agent_fixture() -> String {
@@ -678,147 +1009,173 @@ You are a developer.
 
 #[test]
 fn deploy_basic() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let result = deploy_agent(
         &agent_fixture(),
         "Developer.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    assert!(dir.path().join("Developer.md").exists());
+    assert!(dir.as_path().join("Developer.md").exists());
 }
 
 #[test]
 fn deploy_template_skip() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let result = deploy_agent(
         &agent_fixture(),
         "_TemplateAgent.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::SkippedTemplate)));
 }
 
 #[test]
 fn deploy_user_protection() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     fs::write(
-        dir.path().join("Developer.md"),
+        dir.as_path().join("Developer.md"),
         "User-created agent content.\n",
     )
     .unwrap();
     let result = deploy_agent(
         &agent_fixture(),
         "Developer.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::SkippedUserOwned)));
 }
 
+#[test]
+fn deploy_force_overwrites_user_owned() {
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
+    let config = SidecarConfig::default();
+    fs::write(
+        dir.as_path().join("Developer.md"),
+        "User-created agent content.\n",
+    )
+    .unwrap();
+    let options = DeployOptions::new(Provider::Claude).with_force(true);
+    let result = deploy_agent(
+        &agent_fixture(),
+        "Developer.md",
+        dir.as_path(),
+        &config,
+        &options,
+    );
+    assert!(matches!(result, Ok(DeployResult::Deployed)));
+    let content = fs::read_to_string(dir.as_path().join("Developer.md")).unwrap();
+    assert!(parse::is_synced_from(&content, "Developer.md"));
+}
+
+#[test]
+fn deploy_options_builder() {
+    let options = DeployOptions::new(Provider::Codex)
+        .with_dry_run(true)
+        .with_source_prefix("forge-council/agents");
+    assert_eq!(options.provider, Provider::Codex);
+    assert!(options.dry_run);
+    assert!(!options.force);
+    assert_eq!(options.source_prefix, "forge-council/agents");
+}
+
 #[test]
 fn deploy_synced_overwrite() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     fs::write(
-        dir.path().join("Developer.md"),
+        dir.as_path().join("Developer.md"),
         "# synced-from: Developer.md\nOld content.\n",
     )
     .unwrap();
     let result = deploy_agent(
         &agent_fixture(),
         "Developer.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    let content = fs::read_to_string(dir.path().join("Developer.md")).unwrap();
+    let content = fs::read_to_string(dir.as_path().join("Developer.md")).unwrap();
     assert!(content.contains("You are a developer."));
 }
 
 #[test]
 fn deploy_no_name() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let content = "---\nclaude.model: sonnet\n---\nBody.\n";
     let result = deploy_agent(
         content,
         "Unnamed.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::SkippedNoName)));
 }
 
 #[test]
 fn deploy_invalid_name() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let content = "---\nclaude.name: ../evil\n---\nBody.\n";
     let result = deploy_agent(
         content,
         "Evil.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(result.is_err());
 }
 
 #[test]
 fn deploy_dry_run() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let result = deploy_agent(
         &agent_fixture(),
         "Developer.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        true,
-        "",
+        &DeployOptions::new(Provider::Claude).with_dry_run(true),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    assert!(!dir.path().join("Developer.md").exists());
+    assert!(!dir.as_path().join("Developer.md").exists());
 }
 
 #[test]
 fn deploy_symlink_rejected() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
-    let target = dir.path().join("target.md");
+    let target = dir.as_path().join("target.md");
     fs::write(&target, "target").unwrap();
-    std::os::unix::fs::symlink(&target, dir.path().join("Developer.md")).unwrap();
+    std::os::unix::fs::symlink(&target, dir.as_path().join("Developer.md")).unwrap();
     let result = deploy_agent(
         &agent_fixture(),
         "Developer.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(result.is_err());
 }
@@ -828,7 +1185,8 @@ fn deploy_symlink_rejected() {
 #[test]
 fn deploy_from_dir_multiple() {
     let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     fs::write(
         src.path().join("Developer.md"),
         "---\nclaude.name: Developer\n---\nDev body.\n",
@@ -840,92 +1198,413 @@ fn deploy_from_dir_multiple() {
     )
     .unwrap();
     let config = SidecarConfig::default();
-    let results =
-        deploy_agents_from_dir(src.path(), dst.path(), Provider::Claude, &config, false, "")
-            .unwrap();
-    assert_eq!(results.len(), 2);
-    assert!(dst.path().join("Developer.md").exists());
-    assert!(dst.path().join("Tester.md").exists());
-}
-
-#[test]
-fn deploy_from_dir_missing_src() {
-    let dst = TempDir::new().unwrap();
-    let config = SidecarConfig::default();
     let results = deploy_agents_from_dir(
-        Path::new("/nonexistent"),
-        dst.path(),
-        Provider::Claude,
+        src.path(),
+        dst.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
+        &mut NoProgress,
     )
     .unwrap();
-    assert!(results.is_empty());
+    assert_eq!(results.len(), 2);
+    assert!(dst.as_path().join("Developer.md").exists());
+    assert!(dst.as_path().join("Tester.md").exists());
 }
 
-// ─── clean_agents ───
-
 #[test]
-fn clean_removes_synced() {
+fn deploy_from_dir_skips_agents_excluding_the_provider() {
     let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     fs::write(
         src.path().join("Developer.md"),
-        "---\nclaude.name: Developer\n---\nBody.\n",
+        "---\nclaude.name: Developer\n---\nDev body.\n",
     )
     .unwrap();
     fs::write(
-        dst.path().join("Developer.md"),
-        "# synced-from: Developer.md\nDeployed content.\n",
+        src.path().join("Tester.md"),
+        "---\nclaude.name: Tester\n---\nTest body.\n",
     )
     .unwrap();
-    let removed = clean_agents(src.path(), dst.path(), Provider::Claude, false).unwrap();
-    assert_eq!(removed, vec!["Developer"]);
-    assert!(!dst.path().join("Developer.md").exists());
+    write_yaml(
+        src.path(),
+        "defaults.yaml",
+        "agents:\n  Developer:\n    providers: [claude]\n",
+    );
+    let config = SidecarConfig::load(src.path());
+    let deploy = |provider| {
+        deploy_agents_from_dir(
+            src.path(),
+            dst.as_path(),
+            &config,
+            &DeployOptions::new(provider),
+            &mut NoProgress,
+        )
+        .unwrap()
+    };
+
+    let results = deploy(Provider::Gemini);
+    assert!(results.contains(&(
+        "Developer.md".to_string(),
+        DeployResult::SkippedProviderExcluded
+    )));
+    assert!(results.contains(&("Tester.md".to_string(), DeployResult::Deployed)));
+    let gemini = |name| dst.as_path().join(Provider::Gemini.agent_filename(name));
+    assert!(!gemini("Developer").exists());
+    assert!(gemini("Tester").exists());
+
+    let results = deploy(Provider::Claude);
+    assert!(results.iter().all(|(_, r)| *r == DeployResult::Deployed));
+    assert!(dst.as_path().join("Developer.md").exists());
 }
 
 #[test]
-fn clean_protects_user_created() {
-    let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
-    fs::write(
-        src.path().join("Developer.md"),
+fn deploy_from_dir_missing_src() {
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    let config = SidecarConfig::default();
+    let results = deploy_agents_from_dir(
+        Path::new("/nonexistent"),
+        dst.as_path(),
+        &config,
+        &DeployOptions::new(Provider::Claude),
+        &mut NoProgress,
+    )
+    .unwrap();
+    assert!(results.is_empty());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "current_thread")]
+async fn deploy_from_dir_async_matches_sync() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("Developer.md"),
+        "---\nclaude.name: Developer\n---\nDev body.\n",
+    )
+    .unwrap();
+    let results = deploy_agents_from_dir_async(
+        src.path().to_path_buf(),
+        dst.as_path().to_path_buf(),
+        std::sync::Arc::new(SidecarConfig::default()),
+        DeployOptions::new(Provider::Claude),
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(dst.as_path().join("Developer.md").exists());
+}
+
+#[test]
+fn deploy_from_dir_stops_at_invalid_name() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("A.md"),
+        "---\nclaude.name: bad-name\n---\nBody.\n",
+    )
+    .unwrap();
+    fs::write(
+        src.path().join("Tester.md"),
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    )
+    .unwrap();
+    let config = SidecarConfig::default();
+    let result = deploy_agents_from_dir(
+        src.path(),
+        dst.as_path(),
+        &config,
+        &DeployOptions::new(Provider::Claude),
+        &mut NoProgress,
+    );
+    assert!(result.is_err());
+    assert!(!dst.as_path().join("Tester.md").exists());
+}
+
+#[test]
+fn plan_writes_nothing_until_applied() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("Tester.md"),
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    )
+    .unwrap();
+    fs::write(src.path().join("Notes.md"), "No frontmatter.\n").unwrap();
+    let config = SidecarConfig::default();
+    let plan = plan_agents_from_dir(
+        src.path(),
+        dst.as_path(),
+        &config,
+        &DeployOptions::new(Provider::Claude),
+    )
+    .unwrap();
+    assert!(!dst.as_path().join("Tester.md").exists());
+
+    let reasons: Vec<_> = plan
+        .actions
+        .iter()
+        .map(|a| (a.filename.as_str(), a.result.clone(), a.reason.as_str()))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            (
+                "Notes.md",
+                DeployResult::SkippedNoName,
+                "no name in frontmatter"
+            ),
+            ("Tester.md", DeployResult::Deployed, "new file"),
+        ]
+    );
+    let planned = &plan.actions[1].files[0];
+    assert_eq!(planned.path, dst.as_path().join("Tester.md"));
+    assert!(planned.content.as_deref().unwrap().contains("Test body."));
+
+    let json = serde_json::to_value(&plan).unwrap();
+    assert_eq!(json["actions"][1]["result"], "deployed");
+
+    let results = apply(&RealFs, &plan, &mut NoProgress).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(dst.as_path().join("Tester.md").exists());
+
+    let replan = plan_agents_from_dir(
+        src.path(),
+        dst.as_path(),
+        &config,
+        &DeployOptions::new(Provider::Claude),
+    )
+    .unwrap();
+    assert_eq!(replan.actions[1].reason, "unchanged");
+}
+
+#[test]
+fn deploy_from_dir_in_memory() {
+    let vfs = Arc::new(MemoryFs::new().with_file(
+        "/src/Tester.md",
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    ));
+    let options = DeployOptions::new(Provider::Claude).with_vfs(vfs.clone());
+    let results = deploy_agents_from_dir(
+        Path::new("/src"),
+        Path::new("/dst"),
+        &SidecarConfig::default(),
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+    assert_eq!(results[0].1, DeployResult::Deployed);
+    let deployed = vfs.read_to_string(Path::new("/dst/Tester.md")).unwrap();
+    assert!(deployed.contains("Test body."));
+    assert!(vfs.exists(Path::new("/.forge-state/dst/base/Tester.md")));
+    assert!(!vfs.exists(Path::new("/dst/base")));
+}
+
+#[test]
+fn clean_in_memory() {
+    let vfs = Arc::new(MemoryFs::new().with_file(
+        "/src/Tester.md",
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    ));
+    let options = DeployOptions::new(Provider::Claude).with_vfs(vfs.clone());
+    deploy_agents_from_dir(
+        Path::new("/src"),
+        Path::new("/dst"),
+        &SidecarConfig::default(),
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+    let removed = clean_agents(
+        vfs.as_ref(),
+        Path::new("/src"),
+        Path::new("/dst"),
+        &Provider::Claude,
+        false,
+    )
+    .unwrap();
+    assert_eq!(removed, vec!["Tester"]);
+    assert!(!vfs.exists(Path::new("/dst/Tester.md")));
+    assert!(!vfs.exists(Path::new("/.forge-state/dst/base/Tester.md")));
+}
+
+#[test]
+fn deploy_from_dir_keeps_filename_order() {
+    let mut vfs = MemoryFs::new();
+    for i in 0..20 {
+        vfs = vfs.with_file(
+            format!("/src/Agent{i:02}.md"),
+            format!("---\nclaude.name: Agent{i:02}\n---\nBody {i}.\n"),
+        );
+    }
+    let options = DeployOptions::new(Provider::Claude).with_vfs(Arc::new(vfs));
+    let results = deploy_agents_from_dir(
+        Path::new("/src"),
+        Path::new("/dst"),
+        &SidecarConfig::default(),
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+    let names: Vec<_> = results.iter().map(|(name, _)| name.as_str()).collect();
+    let expected: Vec<_> = (0..20).map(|i| format!("Agent{i:02}.md")).collect();
+    assert_eq!(names, expected);
+}
+
+#[test]
+fn apply_writes_shared_paths_in_plan_order() {
+    let vfs = Arc::new(
+        MemoryFs::new()
+            .with_file("/src/A.md", "---\nclaude.name: Dev\n---\nFirst.\n")
+            .with_file("/src/B.md", "---\nclaude.name: Dev\n---\nSecond.\n"),
+    );
+    let options = DeployOptions::new(Provider::Claude).with_vfs(vfs.clone());
+    deploy_agents_from_dir(
+        Path::new("/src"),
+        Path::new("/dst"),
+        &SidecarConfig::default(),
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+    let deployed = vfs.read_to_string(Path::new("/dst/Dev.md")).unwrap();
+    assert!(deployed.contains("Second."));
+}
+
+#[test]
+fn overlay_captures_writes_without_touching_disk() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("Tester.md"),
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    )
+    .unwrap();
+    let vfs = Arc::new(MemoryFs::overlay());
+    let options = DeployOptions::new(Provider::Claude).with_vfs(vfs.clone());
+    deploy_agents_from_dir(
+        src.path(),
+        dst.as_path(),
+        &SidecarConfig::default(),
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+
+    assert!(!dst.as_path().join("Tester.md").exists());
+    let written = vfs.written();
+    assert!(
+        String::from_utf8_lossy(&written[&dst.as_path().join("Tester.md")]).contains("Test body.")
+    );
+}
+
+#[test]
+fn deploy_from_dir_continue_past_invalid_name() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("A.md"),
+        "---\nclaude.name: bad-name\n---\nBody.\n",
+    )
+    .unwrap();
+    fs::write(
+        src.path().join("Tester.md"),
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    )
+    .unwrap();
+    let config = SidecarConfig::default();
+    let results = deploy_agents_from_dir_continue(
+        src.path(),
+        dst.as_path(),
+        &config,
+        &DeployOptions::new(Provider::Claude),
+        &mut NoProgress,
+    )
+    .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, "A.md");
+    assert!(results[0].1.is_err());
+    let action = results[1].1.as_ref().unwrap();
+    assert_eq!(action.result, DeployResult::Deployed);
+    assert_eq!(action.meta.as_ref().unwrap().name, "Tester");
+    assert!(dst.as_path().join("Tester.md").exists());
+}
+
+// ─── clean_agents ───
+
+#[test]
+fn clean_removes_synced() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("Developer.md"),
+        "---\nclaude.name: Developer\n---\nBody.\n",
+    )
+    .unwrap();
+    fs::write(
+        dst.as_path().join("Developer.md"),
+        "# synced-from: Developer.md\nDeployed content.\n",
+    )
+    .unwrap();
+    let removed =
+        clean_agents(&RealFs, src.path(), dst.as_path(), &Provider::Claude, false).unwrap();
+    assert_eq!(removed, vec!["Developer"]);
+    assert!(!dst.as_path().join("Developer.md").exists());
+}
+
+#[test]
+fn clean_protects_user_created() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("Developer.md"),
         "---\nclaude.name: Developer\n---\nBody.\n",
     )
     .unwrap();
-    fs::write(dst.path().join("Developer.md"), "User-created agent.\n").unwrap();
-    let removed = clean_agents(src.path(), dst.path(), Provider::Claude, false).unwrap();
+    fs::write(dst.as_path().join("Developer.md"), "User-created agent.\n").unwrap();
+    let removed =
+        clean_agents(&RealFs, src.path(), dst.as_path(), &Provider::Claude, false).unwrap();
     assert!(removed.is_empty());
-    assert!(dst.path().join("Developer.md").exists());
+    assert!(dst.as_path().join("Developer.md").exists());
 }
 
 #[test]
 fn clean_dry_run() {
     let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     fs::write(
         src.path().join("Developer.md"),
         "---\nclaude.name: Developer\n---\nBody.\n",
     )
     .unwrap();
     fs::write(
-        dst.path().join("Developer.md"),
+        dst.as_path().join("Developer.md"),
         "# synced-from: Developer.md\nContent.\n",
     )
     .unwrap();
-    let removed = clean_agents(src.path(), dst.path(), Provider::Claude, true).unwrap();
+    let removed =
+        clean_agents(&RealFs, src.path(), dst.as_path(), &Provider::Claude, true).unwrap();
     assert_eq!(removed, vec!["Developer"]);
-    assert!(dst.path().join("Developer.md").exists());
+    assert!(dst.as_path().join("Developer.md").exists());
 }
 
 #[test]
 fn clean_missing_dst() {
     let src = TempDir::new().unwrap();
     let removed = clean_agents(
+        &RealFs,
         src.path(),
         Path::new("/nonexistent"),
-        Provider::Claude,
+        &Provider::Claude,
         false,
     )
     .unwrap();
@@ -953,7 +1632,7 @@ version: 0.3.0
 ---
 You are a developer.
 ";
-    let meta = extract_agent_meta(content, "Developer.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.name, "Developer");
     assert_eq!(meta.model, "sonnet");
     assert_eq!(
@@ -974,7 +1653,7 @@ version: 0.3.0
 ---
 Body.
 ";
-    let meta = extract_agent_meta(content, "Tester.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Tester.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.name, "Tester");
     assert_eq!(meta.model, "sonnet");
     assert_eq!(meta.description, "QA specialist");
@@ -989,7 +1668,7 @@ fn extract_new_format_gemini_model_resolution() {
     ));
     let content =
         "---\nname: Opponent\ndescription: Devil's advocate\nversion: 0.3.0\n---\nBody.\n";
-    let meta = extract_agent_meta(content, "Opponent.md", Provider::Gemini, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Opponent.md", &Provider::Gemini, &config, "").unwrap();
     assert_eq!(meta.model, "gemini-2.5-pro");
     assert_eq!(meta.display_name, "opponent");
 }
@@ -1012,18 +1691,17 @@ version: 0.3.0
 ---
 You are a developer.
 ";
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     let result = deploy_agent(
         content,
         "Developer.md",
-        dst.path(),
-        Provider::Claude,
+        dst.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    let deployed = fs::read_to_string(dst.path().join("Developer.md")).unwrap();
+    let deployed = fs::read_to_string(dst.as_path().join("Developer.md")).unwrap();
     assert!(deployed.contains("name: Developer"));
     assert!(deployed.contains("model: sonnet"));
     assert!(deployed.contains("tools: Read, Write"));
@@ -1034,7 +1712,8 @@ You are a developer.
 #[test]
 fn deploy_new_format_from_dir() {
     let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     fs::write(
         src.path().join("Developer.md"),
         "---\nname: Developer\ndescription: Dev\nversion: 0.3.0\n---\nDev body.\n",
@@ -1054,65 +1733,72 @@ fn deploy_new_format_from_dir() {
     .unwrap();
     let config = SidecarConfig::load(cfg_dir.path());
 
-    let results =
-        deploy_agents_from_dir(src.path(), dst.path(), Provider::Claude, &config, false, "")
-            .unwrap();
+    let results = deploy_agents_from_dir(
+        src.path(),
+        dst.as_path(),
+        &config,
+        &DeployOptions::new(Provider::Claude),
+        &mut NoProgress,
+    )
+    .unwrap();
     assert_eq!(results.len(), 2);
-    assert!(dst.path().join("Developer.md").exists());
-    assert!(dst.path().join("Tester.md").exists());
+    assert!(dst.as_path().join("Developer.md").exists());
+    assert!(dst.as_path().join("Tester.md").exists());
 }
 
 #[test]
 fn clean_new_format() {
     let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     fs::write(
         src.path().join("Developer.md"),
         "---\nname: Developer\ndescription: Dev\nversion: 0.3.0\n---\nBody.\n",
     )
     .unwrap();
     fs::write(
-        dst.path().join("Developer.md"),
+        dst.as_path().join("Developer.md"),
         "# synced-from: Developer.md\nDeployed content.\n",
     )
     .unwrap();
-    let removed = clean_agents(src.path(), dst.path(), Provider::Claude, false).unwrap();
+    let removed =
+        clean_agents(&RealFs, src.path(), dst.as_path(), &Provider::Claude, false).unwrap();
     assert_eq!(removed, vec!["Developer"]);
-    assert!(!dst.path().join("Developer.md").exists());
+    assert!(!dst.as_path().join("Developer.md").exists());
 }
 
 // ─── Codex deploy ───
 
 #[test]
 fn deploy_codex_writes_toml_and_prompt() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let content = "---\nname: Developer\ndescription: Senior dev\nversion: 0.3.0\n---\nYou are a developer.\n";
     let result = deploy_agent(
         content,
         "Developer.md",
-        dir.path(),
-        Provider::Codex,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Codex),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    assert!(dir.path().join("Developer.toml").exists());
-    assert!(dir.path().join("Developer.prompt.md").exists());
-    let toml = fs::read_to_string(dir.path().join("Developer.toml")).unwrap();
+    assert!(dir.as_path().join("Developer.toml").exists());
+    assert!(dir.as_path().join("Developer.prompt.md").exists());
+    let toml = fs::read_to_string(dir.as_path().join("Developer.toml")).unwrap();
     assert!(toml.contains("description = \"Senior dev\""));
     assert!(toml.contains("model_instructions_file = \"agents/Developer.prompt.md\""));
-    let prompt = fs::read_to_string(dir.path().join("Developer.prompt.md")).unwrap();
+    let prompt = fs::read_to_string(dir.as_path().join("Developer.prompt.md")).unwrap();
     assert!(prompt.contains("You are a developer."));
 }
 
 #[test]
 fn deploy_codex_overwrite_with_source() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     fs::write(
-        dir.path().join("Developer.toml"),
+        dir.as_path().join("Developer.toml"),
         "# source: Developer.md\ndescription = \"Old\"\n",
     )
     .unwrap();
@@ -1121,23 +1807,22 @@ fn deploy_codex_overwrite_with_source() {
     let result = deploy_agent(
         content,
         "Developer.md",
-        dir.path(),
-        Provider::Codex,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Codex),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    let toml = fs::read_to_string(dir.path().join("Developer.toml")).unwrap();
+    let toml = fs::read_to_string(dir.as_path().join("Developer.toml")).unwrap();
     assert!(toml.contains("description = \"Updated dev\""));
 }
 
 #[test]
 fn deploy_codex_skips_user_owned_toml() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     fs::write(
-        dir.path().join("Developer.toml"),
+        dir.as_path().join("Developer.toml"),
         "description = \"My custom agent\"\n",
     )
     .unwrap();
@@ -1145,11 +1830,9 @@ fn deploy_codex_skips_user_owned_toml() {
     let result = deploy_agent(
         content,
         "Developer.md",
-        dir.path(),
-        Provider::Codex,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Codex),
     );
     assert!(matches!(result, Ok(DeployResult::SkippedUserOwned)));
 }
@@ -1157,22 +1840,148 @@ fn deploy_codex_skips_user_owned_toml() {
 #[test]
 fn clean_codex_removes_toml_and_prompt() {
     let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     fs::write(
         src.path().join("Developer.md"),
         "---\nname: Developer\n---\nBody.\n",
     )
     .unwrap();
     fs::write(
-        dst.path().join("Developer.toml"),
+        dst.as_path().join("Developer.toml"),
         "# source: Developer.md\ndescription = \"Dev\"\n",
     )
     .unwrap();
-    fs::write(dst.path().join("Developer.prompt.md"), "Body.\n").unwrap();
-    let removed = clean_agents(src.path(), dst.path(), Provider::Codex, false).unwrap();
+    fs::write(dst.as_path().join("Developer.prompt.md"), "Body.\n").unwrap();
+    let removed =
+        clean_agents(&RealFs, src.path(), dst.as_path(), &Provider::Codex, false).unwrap();
     assert_eq!(removed, vec!["Developer"]);
-    assert!(!dst.path().join("Developer.toml").exists());
-    assert!(!dst.path().join("Developer.prompt.md").exists());
+    assert!(!dst.as_path().join("Developer.toml").exists());
+    assert!(!dst.as_path().join("Developer.prompt.md").exists());
+}
+
+#[test]
+fn opencode_deploys_and_cleans_kebab_named_files() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    let content = "---\nname: SecurityArchitect\ndescription: Reviews\n---\nBody.\n";
+    fs::write(src.path().join("SecurityArchitect.md"), content).unwrap();
+    let result = deploy_agent(
+        content,
+        "SecurityArchitect.md",
+        dst.as_path(),
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::OpenCode),
+    );
+    assert!(matches!(result, Ok(DeployResult::Deployed)));
+    let deployed = dst.as_path().join("security-architect.md");
+    assert!(fs::read_to_string(&deployed)
+        .unwrap()
+        .contains("mode: subagent\n"));
+    let removed = clean_agents(
+        &RealFs,
+        src.path(),
+        dst.as_path(),
+        &Provider::OpenCode,
+        false,
+    )
+    .unwrap();
+    assert_eq!(removed, vec!["SecurityArchitect"]);
+    assert!(!deployed.exists());
+}
+
+#[test]
+fn cursor_deploys_and_cleans_rules() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    let content = "---\nname: SecurityArchitect\ndescription: Reviews\n---\nBody.\n";
+    fs::write(src.path().join("SecurityArchitect.md"), content).unwrap();
+    let result = deploy_agent(
+        content,
+        "SecurityArchitect.md",
+        dst.as_path(),
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::Cursor),
+    );
+    assert!(matches!(result, Ok(DeployResult::Deployed)));
+    let deployed = dst.as_path().join("security-architect.mdc");
+    assert!(fs::read_to_string(&deployed)
+        .unwrap()
+        .contains("alwaysApply: false\n"));
+    let removed =
+        clean_agents(&RealFs, src.path(), dst.as_path(), &Provider::Cursor, true).unwrap();
+    assert_eq!(removed, vec!["SecurityArchitect"]);
+    crate::manifest::update(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &["SecurityArchitect".to_string()],
+    )
+    .unwrap();
+    let removed = clean_orphaned_agents(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &[],
+        &Provider::Cursor,
+        false,
+    )
+    .unwrap();
+    assert_eq!(removed, vec!["SecurityArchitect"]);
+    assert!(!deployed.exists());
+}
+
+#[test]
+fn windsurf_deploys_to_kebab_named_rules() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    let content = "---\nname: SecurityArchitect\ndescription: Reviews\n---\nBody.\n";
+    fs::write(src.path().join("SecurityArchitect.md"), content).unwrap();
+    let result = deploy_agent(
+        content,
+        "SecurityArchitect.md",
+        dst.as_path(),
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::Windsurf),
+    );
+    assert!(matches!(result, Ok(DeployResult::Deployed)));
+    let deployed = dst.as_path().join("security-architect.md");
+    assert!(fs::read_to_string(&deployed)
+        .unwrap()
+        .starts_with("---\ntrigger: model_decision\n"));
+    let removed = clean_agents(
+        &RealFs,
+        src.path(),
+        dst.as_path(),
+        &Provider::Windsurf,
+        false,
+    )
+    .unwrap();
+    assert_eq!(removed, vec!["SecurityArchitect"]);
+    assert!(!deployed.exists());
+}
+
+#[test]
+fn copilot_keeps_user_owned_instructions() {
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    let owned = dst.as_path().join("security-architect.instructions.md");
+    fs::write(&owned, "---\ndescription: Mine\n---\nHand-written.\n").unwrap();
+    let content = "---\nname: SecurityArchitect\ndescription: Reviews\n---\nBody.\n";
+    let result = deploy_agent(
+        content,
+        "SecurityArchitect.md",
+        dst.as_path(),
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::Copilot),
+    );
+    assert!(matches!(result, Ok(DeployResult::SkippedUserOwned)));
+    assert!(fs::read_to_string(&owned)
+        .unwrap()
+        .contains("Hand-written."));
 }
 
 // ─── reasoning_effort extraction ───
@@ -1185,7 +1994,7 @@ fn extract_reasoning_effort_from_agent_config() {
         "    reasoning_effort:\n      fast: low\n      strong: medium\n",
     ));
     let content = "---\nname: Developer\ndescription: Dev\nversion: 0.3.0\n---\nBody.\n";
-    let meta = extract_agent_meta(content, "Developer.md", Provider::Codex, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Codex, &config, "").unwrap();
     assert_eq!(meta.reasoning_effort, Some("high".into()));
 }
 
@@ -1197,7 +2006,7 @@ fn extract_reasoning_effort_tier_fallback() {
         "    reasoning_effort:\n      fast: low\n      strong: medium\n",
     ));
     let content = "---\nname: Developer\ndescription: Dev\nversion: 0.3.0\n---\nBody.\n";
-    let meta = extract_agent_meta(content, "Developer.md", Provider::Codex, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Codex, &config, "").unwrap();
     assert_eq!(meta.reasoning_effort, Some("low".into()));
     assert_eq!(meta.model, "gpt-5.1-codex-mini");
 }
@@ -1206,7 +2015,7 @@ fn extract_reasoning_effort_tier_fallback() {
 fn extract_reasoning_effort_none_without_config() {
     let config = SidecarConfig::default();
     let content = "---\nname: Developer\ndescription: Dev\nversion: 0.3.0\n---\nBody.\n";
-    let meta = extract_agent_meta(content, "Developer.md", Provider::Claude, &config, "").unwrap();
+    let meta = extract_agent_meta(content, "Developer.md", &Provider::Claude, &config, "").unwrap();
     assert_eq!(meta.reasoning_effort, None);
 }
 
@@ -1219,7 +2028,7 @@ fn extract_source_prefix_produces_full_path() {
     let meta = extract_agent_meta(
         content,
         "Dev.md",
-        Provider::Claude,
+        &Provider::Claude,
         &config,
         "forge-council/agents",
     )
@@ -1230,44 +2039,42 @@ fn extract_source_prefix_produces_full_path() {
 
 #[test]
 fn deploy_source_in_frontmatter() {
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let content = "---\nname: Dev\ndescription: Developer\nversion: 0.3.0\n---\nBody.\n";
     let result = deploy_agent(
         content,
         "Dev.md",
-        dst.path(),
-        Provider::Claude,
+        dst.as_path(),
         &config,
-        false,
-        "forge-council/agents",
+        &DeployOptions::new(Provider::Claude).with_source_prefix("forge-council/agents"),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    let deployed = fs::read_to_string(dst.path().join("Dev.md")).unwrap();
+    let deployed = fs::read_to_string(dst.as_path().join("Dev.md")).unwrap();
     assert!(deployed.contains("source: forge-council/agents/Dev.md"));
     assert!(!deployed.contains("# synced-from:"));
 }
 
 #[test]
 fn deploy_overwrite_new_format_source() {
-    let dir = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
     let config = SidecarConfig::default();
     fs::write(
-        dir.path().join("Developer.md"),
+        dir.as_path().join("Developer.md"),
         "---\nname: Developer\nsource: Developer.md\n---\nOld.\n",
     )
     .unwrap();
     let result = deploy_agent(
         &agent_fixture(),
         "Developer.md",
-        dir.path(),
-        Provider::Claude,
+        dir.as_path(),
         &config,
-        false,
-        "",
+        &DeployOptions::new(Provider::Claude),
     );
     assert!(matches!(result, Ok(DeployResult::Deployed)));
-    let content = fs::read_to_string(dir.path().join("Developer.md")).unwrap();
+    let content = fs::read_to_string(dir.as_path().join("Developer.md")).unwrap();
     assert!(content.contains("You are a developer."));
 }
 
@@ -1286,7 +2093,7 @@ fn default_providers() -> Vec<String> {
 fn scope_user() {
     let home = Path::new("/home/user");
     let providers = default_providers();
-    let dirs = scope_dirs("user", home, &providers).unwrap();
+    let dirs = scope_dirs("user", home, &providers, &[]).unwrap();
     assert_eq!(dirs.len(), 4);
     assert_eq!(dirs[0], home.join(".claude/agents"));
     assert_eq!(dirs[1], home.join(".gemini/agents"));
@@ -1298,17 +2105,69 @@ fn scope_user() {
 fn scope_workspace() {
     let home = Path::new("/home/user");
     let providers = default_providers();
-    let dirs = scope_dirs("workspace", home, &providers).unwrap();
+    let dirs = scope_dirs("workspace", home, &providers, &[]).unwrap();
     assert_eq!(dirs.len(), 4);
     assert_eq!(dirs[0], PathBuf::from(".claude/agents"));
     assert_eq!(dirs[3], PathBuf::from(".opencode/agents"));
 }
 
+#[test]
+fn scope_cursor_and_windsurf_use_rules() {
+    let home = Path::new("/home/user");
+    let providers = ["cursor".to_string(), "windsurf".to_string()];
+    let dirs = scope_dirs("workspace", home, &providers, &[]).unwrap();
+    assert_eq!(
+        dirs,
+        vec![
+            PathBuf::from(".cursor/rules"),
+            PathBuf::from(".windsurf/rules")
+        ]
+    );
+}
+
+#[test]
+fn scope_copilot_workspace_is_github_instructions() {
+    let home = Path::new("/home/user");
+    let dirs = scope_dirs("workspace", home, &["copilot".to_string()], &[]).unwrap();
+    assert_eq!(dirs, vec![PathBuf::from(".github/instructions")]);
+}
+
+#[test]
+fn scope_copilot_has_no_user_or_project_dirs() {
+    let home = Path::new("/home/user");
+    let copilot = ["copilot".to_string()];
+    assert!(scope_dirs("user", home, &copilot, &[]).is_err());
+    assert!(scope_dirs("project", home, &copilot, &[]).is_err());
+    let providers = ["claude".to_string(), "copilot".to_string()];
+    let dirs = scope_dirs("all", home, &providers, &[]).unwrap();
+    assert_eq!(
+        dirs,
+        vec![
+            home.join(".claude/agents"),
+            PathBuf::from(".claude/agents"),
+            PathBuf::from(".github/instructions"),
+        ]
+    );
+}
+
+#[test]
+fn scope_project_needs_per_project_settings() {
+    let home = Path::new("/home/user");
+    assert!(scope_dirs("project", home, &["cursor".to_string()], &[]).is_err());
+}
+
+#[test]
+fn scope_zed_uses_prompts() {
+    let home = Path::new("/home/user");
+    let dirs = scope_dirs("user", home, &["zed".to_string()], &[]).unwrap();
+    assert_eq!(dirs, vec![home.join(".zed/prompts")]);
+}
+
 #[test]
 fn scope_all() {
     let home = Path::new("/home/user");
     let providers = default_providers();
-    let dirs = scope_dirs("all", home, &providers).unwrap();
+    let dirs = scope_dirs("all", home, &providers, &[]).unwrap();
     assert_eq!(dirs.len(), 8);
 }
 
@@ -1316,7 +2175,7 @@ fn scope_all() {
 fn scope_project() {
     let home = Path::new("/home/user");
     let providers = default_providers();
-    let dirs = scope_dirs("project", home, &providers).unwrap();
+    let dirs = scope_dirs("project", home, &providers, &[]).unwrap();
     assert_eq!(dirs.len(), 4);
     // Project key is CWD with / replaced by -
     let key = std::env::current_dir()
@@ -1336,7 +2195,7 @@ fn scope_project() {
 fn scope_subset_providers() {
     let home = Path::new("/home/user");
     let providers = vec!["claude".into(), "gemini".into()];
-    let dirs = scope_dirs("user", home, &providers).unwrap();
+    let dirs = scope_dirs("user", home, &providers, &[]).unwrap();
     assert_eq!(dirs.len(), 2);
     assert_eq!(dirs[0], home.join(".claude/agents"));
     assert_eq!(dirs[1], home.join(".gemini/agents"));
@@ -1345,24 +2204,7 @@ fn scope_subset_providers() {
 #[test]
 fn scope_invalid() {
     let providers = default_providers();
-    assert!(scope_dirs("bogus", Path::new("/tmp"), &providers).is_err());
-}
-
-// ─── toml_escape ───
-
-#[test]
-fn toml_escape_quotes_and_backslashes() {
-    assert_eq!(toml_escape(r#"say "hello""#), r#"say \"hello\""#);
-    assert_eq!(toml_escape(r"path\to\file"), r"path\\to\\file");
-    assert_eq!(
-        toml_escape(r#"mixed "quote" and \back"#),
-        r#"mixed \"quote\" and \\back"#
-    );
-}
-
-#[test]
-fn toml_escape_no_special_chars() {
-    assert_eq!(toml_escape("plain text"), "plain text");
+    assert!(scope_dirs("bogus", Path::new("/tmp"), &providers, &[]).is_err());
 }
 
 // ─── format_codex_config_block ───
@@ -1373,7 +2215,7 @@ fn format_codex_config_block_single_agent() {
         name: "DataAnalyst".into(),
         description: "Data analyst specialist".into(),
     }];
-    let block = format_codex_config_block(&entries, "forge-council/agents");
+    let block = format_codex_config_block(&entries, "forge-council/agents").unwrap();
     assert!(block.contains("# BEGIN forge-council agents"));
     assert!(block.contains("# Generated by install-agents (forge-council/agents)"));
     assert!(block.contains("[agents.DataAnalyst]"));
@@ -1394,7 +2236,7 @@ fn format_codex_config_block_multiple_agents() {
             description: "Security architect".into(),
         },
     ];
-    let block = format_codex_config_block(&entries, "test");
+    let block = format_codex_config_block(&entries, "test").unwrap();
     let da_pos = block.find("[agents.DataAnalyst]").unwrap();
     let sa_pos = block.find("[agents.SecurityArchitect]").unwrap();
     assert!(da_pos < sa_pos);
@@ -1407,7 +2249,7 @@ fn format_codex_config_block_escapes_description() {
         name: "Test".into(),
         description: r#"Agent with "quotes" and \backslash"#.into(),
     }];
-    let block = format_codex_config_block(&entries, "");
+    let block = format_codex_config_block(&entries, "").unwrap();
     assert!(block.contains(r#"description = "Agent with \"quotes\" and \\backslash""#));
 }
 
@@ -1449,7 +2291,15 @@ fn write_codex_config_preserves_existing() {
         name: "Dev".into(),
         description: "Developer".into(),
     }];
-    write_codex_config_block(&config_path, &entries, "test", false).unwrap();
+    write_codex_config_block(
+        &RealFs,
+        &config_path,
+        &entries,
+        "test",
+        &Templates::default(),
+        false,
+    )
+    .unwrap();
 
     let result = fs::read_to_string(&config_path).unwrap();
     assert!(result.contains("multi_agent = true"));
@@ -1477,7 +2327,15 @@ config_file = \"agents/OldAgent.toml\"
         name: "NewAgent".into(),
         description: "New".into(),
     }];
-    write_codex_config_block(&config_path, &entries, "test", false).unwrap();
+    write_codex_config_block(
+        &RealFs,
+        &config_path,
+        &entries,
+        "test",
+        &Templates::default(),
+        false,
+    )
+    .unwrap();
 
     let result = fs::read_to_string(&config_path).unwrap();
     assert!(result.contains("[agents.NewAgent]"));
@@ -1498,7 +2356,15 @@ fn write_codex_config_creates_new_file() {
         name: "Dev".into(),
         description: "Developer".into(),
     }];
-    write_codex_config_block(&config_path, &entries, "test", false).unwrap();
+    write_codex_config_block(
+        &RealFs,
+        &config_path,
+        &entries,
+        "test",
+        &Templates::default(),
+        false,
+    )
+    .unwrap();
 
     assert!(config_path.exists());
     let result = fs::read_to_string(&config_path).unwrap();
@@ -1522,7 +2388,7 @@ description = \"Dev\"
 ";
     fs::write(&config_path, content).unwrap();
 
-    clean_codex_config_block(&config_path, false).unwrap();
+    clean_codex_config_block(&RealFs, &config_path, false).unwrap();
 
     let result = fs::read_to_string(&config_path).unwrap();
     assert!(!result.contains("agents.Dev"));
@@ -1535,7 +2401,35 @@ fn clean_codex_config_block_noop_when_missing() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("config.toml");
     // File doesn't exist — should be a no-op
-    clean_codex_config_block(&config_path, false).unwrap();
+    clean_codex_config_block(&RealFs, &config_path, false).unwrap();
+    assert!(!config_path.exists());
+}
+
+#[test]
+fn codex_config_block_goes_through_the_vfs() {
+    let vfs = MemoryFs::overlay();
+    let config_path = Path::new("/codex/config.toml");
+    let entries = vec![CodexConfigEntry {
+        name: "Dev".into(),
+        description: "Developer".into(),
+    }];
+    write_codex_config_block(
+        &vfs,
+        config_path,
+        &entries,
+        "test",
+        &Templates::default(),
+        false,
+    )
+    .unwrap();
+    let written = vfs.read_to_string(config_path).unwrap();
+    assert!(written.contains("[agents.Dev]"));
+
+    clean_codex_config_block(&vfs, config_path, false).unwrap();
+    assert!(!vfs
+        .read_to_string(config_path)
+        .unwrap()
+        .contains("agents.Dev"));
     assert!(!config_path.exists());
 }
 
@@ -1543,88 +2437,135 @@ fn clean_codex_config_block_noop_when_missing() {
 
 #[test]
 fn orphan_removes_renamed_agent() {
-    let dst = TempDir::new().unwrap();
-    crate::manifest::update(dst.path(), "forge-council", &["OldName".to_string()]).unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    crate::manifest::update(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &["OldName".to_string()],
+    )
+    .unwrap();
     fs::write(
-        dst.path().join("OldName.md"),
+        dst.as_path().join("OldName.md"),
         "---\nname: OldName\nsource: forge-council/agents/OldName.md\n---\nOld body.\n",
     )
     .unwrap();
     let removed = clean_orphaned_agents(
-        dst.path(),
+        &RealFs,
+        dst.as_path(),
         "forge-council",
         &["NewName".to_string()],
-        Provider::Claude,
+        &Provider::Claude,
         false,
     )
     .unwrap();
     assert_eq!(removed, vec!["OldName"]);
-    assert!(!dst.path().join("OldName.md").exists());
+    assert!(!dst.as_path().join("OldName.md").exists());
 }
 
 #[test]
 fn orphan_keeps_current_agent() {
-    let dst = TempDir::new().unwrap();
-    crate::manifest::update(dst.path(), "forge-council", &["Developer".to_string()]).unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    crate::manifest::update(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &["Developer".to_string()],
+    )
+    .unwrap();
     fs::write(
-        dst.path().join("Developer.md"),
+        dst.as_path().join("Developer.md"),
         "---\nname: Developer\nsource: forge-council/agents/Developer.md\n---\nBody.\n",
     )
     .unwrap();
     let removed = clean_orphaned_agents(
-        dst.path(),
+        &RealFs,
+        dst.as_path(),
         "forge-council",
         &["Developer".to_string()],
-        Provider::Claude,
+        &Provider::Claude,
         false,
     )
     .unwrap();
     assert!(removed.is_empty());
-    assert!(dst.path().join("Developer.md").exists());
+    assert!(dst.as_path().join("Developer.md").exists());
 }
 
 #[test]
 fn orphan_dry_run_preserves_file() {
-    let dst = TempDir::new().unwrap();
-    crate::manifest::update(dst.path(), "forge-council", &["Old".to_string()]).unwrap();
-    fs::write(dst.path().join("Old.md"), "---\nname: Old\n---\nBody.\n").unwrap();
-    let removed =
-        clean_orphaned_agents(dst.path(), "forge-council", &[], Provider::Claude, true).unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    crate::manifest::update(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &["Old".to_string()],
+    )
+    .unwrap();
+    fs::write(dst.as_path().join("Old.md"), "---\nname: Old\n---\nBody.\n").unwrap();
+    let removed = clean_orphaned_agents(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &[],
+        &Provider::Claude,
+        true,
+    )
+    .unwrap();
     assert_eq!(removed, vec!["Old"]);
-    assert!(dst.path().join("Old.md").exists());
+    assert!(dst.as_path().join("Old.md").exists());
 }
 
 #[test]
 fn orphan_codex_removes_prompt_companion() {
-    let dst = TempDir::new().unwrap();
-    crate::manifest::update(dst.path(), "forge-council", &["Old".to_string()]).unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    crate::manifest::update(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &["Old".to_string()],
+    )
+    .unwrap();
     fs::write(
-        dst.path().join("Old.toml"),
+        dst.as_path().join("Old.toml"),
         "# source: forge-council/agents/Old.md\ndescription = \"Old\"\n",
     )
     .unwrap();
-    fs::write(dst.path().join("Old.prompt.md"), "Old body.\n").unwrap();
-    let removed =
-        clean_orphaned_agents(dst.path(), "forge-council", &[], Provider::Codex, false).unwrap();
+    fs::write(dst.as_path().join("Old.prompt.md"), "Old body.\n").unwrap();
+    let removed = clean_orphaned_agents(
+        &RealFs,
+        dst.as_path(),
+        "forge-council",
+        &[],
+        &Provider::Codex,
+        false,
+    )
+    .unwrap();
     assert_eq!(removed, vec!["Old"]);
-    assert!(!dst.path().join("Old.toml").exists());
-    assert!(!dst.path().join("Old.prompt.md").exists());
+    assert!(!dst.as_path().join("Old.toml").exists());
+    assert!(!dst.as_path().join("Old.prompt.md").exists());
 }
 
 #[test]
 fn orphan_empty_module_skips() {
-    let dst = TempDir::new().unwrap();
-    let removed = clean_orphaned_agents(dst.path(), "", &[], Provider::Claude, false).unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    let removed =
+        clean_orphaned_agents(&RealFs, dst.as_path(), "", &[], &Provider::Claude, false).unwrap();
     assert!(removed.is_empty());
 }
 
 #[test]
 fn orphan_missing_dst_dir() {
     let removed = clean_orphaned_agents(
+        &RealFs,
         Path::new("/nonexistent"),
         "forge-council",
         &[],
-        Provider::Claude,
+        &Provider::Claude,
         false,
     )
     .unwrap();
@@ -1636,7 +2577,8 @@ fn orphan_missing_dst_dir() {
 #[test]
 fn orphan_lifecycle_deploy_rename_clean() {
     let src = TempDir::new().unwrap();
-    let dst = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
     let config = SidecarConfig::default();
     let prefix = "forge-council/agents";
     let module = "forge-council";
@@ -1646,18 +2588,17 @@ fn orphan_lifecycle_deploy_rename_clean() {
     fs::write(src.path().join("OldName.md"), content).unwrap();
     let results = deploy_agents_from_dir(
         src.path(),
-        dst.path(),
-        Provider::Claude,
+        dst.as_path(),
         &config,
-        false,
-        prefix,
+        &DeployOptions::new(Provider::Claude).with_source_prefix(prefix),
+        &mut NoProgress,
     )
     .unwrap();
     assert_eq!(results.len(), 1);
-    assert!(dst.path().join("OldName.md").exists());
+    assert!(dst.as_path().join("OldName.md").exists());
 
     // Record in manifest
-    crate::manifest::update(dst.path(), module, &["OldName".to_string()]).unwrap();
+    crate::manifest::update(&RealFs, dst.as_path(), module, &["OldName".to_string()]).unwrap();
 
     // Step 2: Rename source to "NewName" (remove OldName, add NewName)
     fs::remove_file(src.path().join("OldName.md")).unwrap();
@@ -1667,27 +2608,484 @@ fn orphan_lifecycle_deploy_rename_clean() {
     // Step 3: Deploy again (NewName)
     let results = deploy_agents_from_dir(
         src.path(),
-        dst.path(),
-        Provider::Claude,
+        dst.as_path(),
         &config,
-        false,
-        prefix,
+        &DeployOptions::new(Provider::Claude).with_source_prefix(prefix),
+        &mut NoProgress,
     )
     .unwrap();
     assert_eq!(results.len(), 1);
-    assert!(dst.path().join("NewName.md").exists());
+    assert!(dst.as_path().join("NewName.md").exists());
     // OldName still exists (deploy doesn't clean)
-    assert!(dst.path().join("OldName.md").exists());
+    assert!(dst.as_path().join("OldName.md").exists());
 
     // Step 4: Orphan clean removes OldName
     let installed = vec!["NewName".to_string()];
-    let removed =
-        clean_orphaned_agents(dst.path(), module, &installed, Provider::Claude, false).unwrap();
+    let removed = clean_orphaned_agents(
+        &RealFs,
+        dst.as_path(),
+        module,
+        &installed,
+        &Provider::Claude,
+        false,
+    )
+    .unwrap();
     assert_eq!(removed, vec!["OldName"]);
-    assert!(!dst.path().join("OldName.md").exists());
-    assert!(dst.path().join("NewName.md").exists());
+    assert!(!dst.as_path().join("OldName.md").exists());
+    assert!(dst.as_path().join("NewName.md").exists());
 
     // Step 5: Update manifest
-    crate::manifest::update(dst.path(), module, &installed).unwrap();
-    assert_eq!(crate::manifest::read(dst.path(), module), installed);
+    crate::manifest::update(&RealFs, dst.as_path(), module, &installed).unwrap();
+    assert_eq!(
+        crate::manifest::read(&RealFs, dst.as_path(), module),
+        installed
+    );
+}
+
+// ─── Merging user edits ───
+
+fn deploy_reviewer(dst: &Path, body: &str) -> DeployResult {
+    let content = format!("---\nname: Reviewer\ndescription: Reviews\n---\n{body}");
+    deploy_agent(
+        &content,
+        "Reviewer.md",
+        dst,
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::Claude),
+    )
+    .unwrap()
+}
+
+#[test]
+fn redeploy_merges_user_edits_with_source_changes() {
+    let dir = TempDir::new().unwrap();
+    let dst = &agents_dir(&dir);
+    let path = dst.join("Reviewer.md");
+    assert_eq!(
+        deploy_reviewer(dst, "Intro.\n\nRules.\n"),
+        DeployResult::Deployed
+    );
+    assert!(base_path(&dst.join("Reviewer.md")).is_file());
+
+    let edited = fs::read_to_string(&path)
+        .unwrap()
+        .replace("Intro.", "Intro, tuned locally.");
+    fs::write(&path, &edited).unwrap();
+
+    assert_eq!(
+        deploy_reviewer(dst, "Intro.\n\nRules.\nMore rules.\n"),
+        DeployResult::Merged
+    );
+    let merged = fs::read_to_string(&path).unwrap();
+    assert!(merged.contains("Intro, tuned locally.\n\nRules.\nMore rules.\n"));
+
+    // Unchanged source keeps the edits as they are.
+    assert_eq!(
+        deploy_reviewer(dst, "Intro.\n\nRules.\nMore rules.\n"),
+        DeployResult::Merged
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), merged);
+}
+
+#[test]
+fn force_replaces_user_edits_instead_of_merging() {
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
+    let dst = dir.as_path();
+    let path = dst.join("Reviewer.md");
+    deploy_reviewer(dst, "Intro.\n");
+    let edited = fs::read_to_string(&path)
+        .unwrap()
+        .replace("Intro.", "Mine.");
+    fs::write(&path, edited).unwrap();
+
+    let result = deploy_agent(
+        "---\nname: Reviewer\ndescription: Reviews\n---\nIntro.\nMore.\n",
+        "Reviewer.md",
+        dst,
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::Claude).with_force(true),
+    )
+    .unwrap();
+    assert_eq!(result, DeployResult::Deployed);
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("Intro.\nMore.\n"));
+    assert!(!content.contains("Mine."));
+}
+
+#[test]
+fn overlapping_edits_leave_conflict_markers_until_resolved() {
+    let dir = TempDir::new().unwrap();
+    let dst = &agents_dir(&dir);
+    let path = dst.join("Reviewer.md");
+    deploy_reviewer(dst, "Rules.\n");
+    let edited = fs::read_to_string(&path)
+        .unwrap()
+        .replace("Rules.", "My rules.");
+    fs::write(&path, edited).unwrap();
+
+    assert_eq!(
+        deploy_reviewer(dst, "New rules.\n"),
+        DeployResult::Conflicted
+    );
+    let conflicted = fs::read_to_string(&path).unwrap();
+    assert!(
+        conflicted.contains("<<<<<<< deployed\nMy rules.\n=======\nNew rules.\n>>>>>>> source\n")
+    );
+
+    // Unresolved markers are not merged again.
+    assert_eq!(
+        deploy_reviewer(dst, "Newer rules.\n"),
+        DeployResult::Conflicted
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), conflicted);
+}
+
+#[test]
+fn file_without_base_is_replaced() {
+    let dir = TempDir::new().unwrap();
+    let dst = &agents_dir(&dir);
+    deploy_reviewer(dst, "Old.\n");
+    fs::remove_dir_all(state_dir(dst).join(BASE_DIR)).unwrap();
+    let path = dst.join("Reviewer.md");
+    let edited = fs::read_to_string(&path).unwrap().replace("Old.", "Mine.");
+    fs::write(&path, edited).unwrap();
+
+    assert_eq!(deploy_reviewer(dst, "New.\n"), DeployResult::Deployed);
+    assert!(fs::read_to_string(&path).unwrap().contains("New.\n"));
+}
+
+// ─── Linking ───
+
+#[cfg(unix)]
+#[test]
+fn link_deploys_a_symlink_that_a_plain_deploy_replaces() {
+    let dir = TempDir::new().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    let source = "---\nname: Reviewer\ndescription: Reviews\n---\nBody.\n";
+    write_yaml(&src, "Reviewer.md", source);
+    let config = SidecarConfig::default();
+    let linked = DeployOptions::new(Provider::Claude).with_link(&src);
+
+    let results = deploy_agents_from_dir(&src, &dst, &config, &linked, &mut NoProgress).unwrap();
+    assert_eq!(results[0].1, DeployResult::Deployed);
+    let path = dst.join("Reviewer.md");
+    assert_eq!(fs::read_link(&path).unwrap(), src.join("Reviewer.md"));
+    let again = plan_agents_from_dir(&src, &dst, &config, &linked).unwrap();
+    assert_eq!(again.actions[0].reason, "already linked");
+
+    let plain = DeployOptions::new(Provider::Claude);
+    deploy_agents_from_dir(&src, &dst, &config, &plain, &mut NoProgress).unwrap();
+    assert!(!path.is_symlink());
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("source: Reviewer.md"));
+    assert_eq!(fs::read_to_string(src.join("Reviewer.md")).unwrap(), source);
+
+    assert_eq!(
+        clean_agents(&RealFs, &src, &dst, &Provider::Claude, false).unwrap(),
+        vec!["Reviewer"]
+    );
+}
+
+#[test]
+fn link_is_claude_only() {
+    let vfs = Arc::new(MemoryFs::new());
+    let options = DeployOptions::new(Provider::Gemini)
+        .with_vfs(vfs)
+        .with_link("/src");
+    let err = plan_agent(
+        "---\nname: Reviewer\n---\nBody.\n",
+        "Reviewer.md",
+        Path::new("/dst"),
+        &SidecarConfig::default(),
+        &options,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("cannot link agents for gemini"));
+}
+
+#[test]
+fn foreign_symlink_is_still_refused() {
+    let vfs = MemoryFs::new().with_file("/elsewhere/Mine.md", "mine");
+    vfs.create_dir_all(Path::new("/dst")).unwrap();
+    vfs.symlink(
+        Path::new("/elsewhere/Mine.md"),
+        Path::new("/dst/Reviewer.md"),
+    )
+    .unwrap();
+    let options = DeployOptions::new(Provider::Claude).with_vfs(Arc::new(vfs));
+    let err = plan_agent(
+        "---\nname: Reviewer\n---\nBody.\n",
+        "Reviewer.md",
+        Path::new("/dst"),
+        &SidecarConfig::default(),
+        &options,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("destination is a symlink"));
+}
+
+// ─── Backup and restore ───
+
+fn deploy_reviewer_with_backup(dst: &Path, body: &str) -> DeployResult {
+    let content = format!("---\nname: Reviewer\ndescription: Reviews\n---\n{body}");
+    deploy_agent(
+        &content,
+        "Reviewer.md",
+        dst,
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::Claude).with_backup(true),
+    )
+    .unwrap()
+}
+
+#[test]
+fn backup_saves_the_replaced_file_and_restore_brings_it_back() {
+    let dir = TempDir::new().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    write_yaml(
+        &src,
+        "Reviewer.md",
+        "---\nname: Reviewer\ndescription: Reviews\n---\nOld.\n",
+    );
+    deploy_reviewer_with_backup(&dst, "Old.\n");
+    assert!(!state_dir(&dst).join(BACKUP_DIR).exists());
+
+    let path = dst.join("Reviewer.md");
+    let old = fs::read_to_string(&path).unwrap();
+    let old_base = fs::read_to_string(base_path(&dst.join("Reviewer.md"))).unwrap();
+    deploy_reviewer_with_backup(&dst, "New.\n");
+    assert!(fs::read_to_string(&path).unwrap().contains("New.\n"));
+    assert_eq!(
+        fs::read_to_string(backup_path(&dst.join("Reviewer.md"))).unwrap(),
+        old
+    );
+    // Backups and bases live beside the agents directory, not in it.
+    assert!(fs::read_dir(&dst)
+        .unwrap()
+        .all(|e| e.unwrap().file_type().unwrap().is_file()));
+
+    let dry = restore_agents(&RealFs, &src, &dst, &Provider::Claude, true).unwrap();
+    assert_eq!(dry, vec!["Reviewer"]);
+    assert!(fs::read_to_string(&path).unwrap().contains("New.\n"));
+
+    let restored = restore_agents(&RealFs, &src, &dst, &Provider::Claude, false).unwrap();
+    assert_eq!(restored, vec!["Reviewer"]);
+    assert_eq!(fs::read_to_string(&path).unwrap(), old);
+    assert_eq!(
+        fs::read_to_string(base_path(&dst.join("Reviewer.md"))).unwrap(),
+        old_base
+    );
+    assert!(!backup_path(&dst.join("Reviewer.md")).exists());
+    assert!(
+        restore_agents(&RealFs, &src, &dst, &Provider::Claude, false)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn backup_skips_unchanged_files_and_is_off_by_default() {
+    let dir = TempDir::new().unwrap();
+    let dst = &agents_dir(&dir);
+    deploy_reviewer_with_backup(dst, "Same.\n");
+    deploy_reviewer_with_backup(dst, "Same.\n");
+    assert!(!backup_path(&dst.join("Reviewer.md")).exists());
+
+    deploy_reviewer(dst, "Changed.\n");
+    assert!(!backup_path(&dst.join("Reviewer.md")).exists());
+}
+
+#[test]
+fn force_agent_reclaims_only_the_named_user_owned_file() {
+    let dir = TempDir::new().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    for name in ["Developer", "Reviewer"] {
+        write_yaml(
+            &src,
+            &format!("{name}.md"),
+            &format!("---\nname: {name}\ndescription: Works\n---\nBody.\n"),
+        );
+        fs::write(dst.join(format!("{name}.md")), "Mine.\n").unwrap();
+    }
+
+    let options = DeployOptions::new(Provider::Claude).with_force_agents(["Reviewer"]);
+    assert!(options.forces("Reviewer"));
+    assert!(!options.forces("Developer"));
+    let plan = deploy_plan_from_dir(
+        &src,
+        &dst,
+        &SidecarConfig::default(),
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+    let action = |name: &str| {
+        plan.actions
+            .iter()
+            .find(|a| a.name.as_deref() == Some(name))
+            .unwrap()
+    };
+    assert_eq!(action("Developer").result, DeployResult::SkippedUserOwned);
+    assert_eq!(action("Reviewer").result, DeployResult::Deployed);
+    assert_eq!(
+        action("Reviewer").reason,
+        "user-created file overwritten (forced)"
+    );
+    assert_eq!(
+        fs::read_to_string(dst.join("Developer.md")).unwrap(),
+        "Mine.\n"
+    );
+    assert!(fs::read_to_string(dst.join("Reviewer.md"))
+        .unwrap()
+        .contains("Body.\n"));
+    assert_eq!(
+        fs::read_to_string(backup_path(&dst.join("Reviewer.md"))).unwrap(),
+        "Mine.\n"
+    );
+
+    restore_agents(&RealFs, &src, &dst, &Provider::Claude, false).unwrap();
+    assert_eq!(
+        fs::read_to_string(dst.join("Reviewer.md")).unwrap(),
+        "Mine.\n"
+    );
+}
+
+#[test]
+fn force_backs_up_user_edits_but_not_clean_updates() {
+    let tmp = TempDir::new().unwrap();
+    let dir = agents_dir(&tmp);
+    let dst = dir.as_path();
+    let path = dst.join("Reviewer.md");
+    let force = |body: &str| {
+        let content = format!("---\nname: Reviewer\ndescription: Reviews\n---\n{body}");
+        deploy_agent(
+            &content,
+            "Reviewer.md",
+            dst,
+            &SidecarConfig::default(),
+            &DeployOptions::new(Provider::Claude).with_force(true),
+        )
+        .unwrap()
+    };
+    deploy_reviewer(dst, "Old.\n");
+    force("New.\n");
+    assert!(!backup_path(&dst.join("Reviewer.md")).exists());
+
+    let edited = fs::read_to_string(&path).unwrap().replace("New.", "Mine.");
+    fs::write(&path, &edited).unwrap();
+    force("Newer.\n");
+    assert!(fs::read_to_string(&path).unwrap().contains("Newer.\n"));
+    assert_eq!(
+        fs::read_to_string(backup_path(&dst.join("Reviewer.md"))).unwrap(),
+        edited
+    );
+}
+
+#[test]
+fn cache_skips_unchanged_agents() {
+    let vfs = Arc::new(MemoryFs::new().with_file(
+        "/src/Tester.md",
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    ));
+    let options = DeployOptions::new(Provider::Claude)
+        .with_vfs(vfs.clone())
+        .with_cache(true);
+    let config = SidecarConfig::default();
+    let deploy = || {
+        deploy_agents_from_dir(
+            Path::new("/src"),
+            Path::new("/dst"),
+            &config,
+            &options,
+            &mut NoProgress,
+        )
+        .unwrap()
+    };
+    deploy();
+    assert!(vfs.exists(Path::new("/dst/.forge-cache")));
+
+    let plan =
+        plan_agents_from_dir(Path::new("/src"), Path::new("/dst"), &config, &options).unwrap();
+    assert_eq!(
+        plan.actions[0].reason,
+        "unchanged since last deploy (cached)"
+    );
+    assert_eq!(plan.actions[0].name.as_deref(), Some("Tester"));
+    assert!(plan.actions[0].files.is_empty());
+    assert_eq!(plan.actions[0].result, DeployResult::Unchanged);
+    assert_eq!(deploy()[0].1, DeployResult::Unchanged);
+
+    let mut edited = vfs.read_to_string(Path::new("/dst/Tester.md")).unwrap();
+    edited.push_str("User note.\n");
+    vfs.write(Path::new("/dst/Tester.md"), edited.as_bytes())
+        .unwrap();
+    let plan =
+        plan_agents_from_dir(Path::new("/src"), Path::new("/dst"), &config, &options).unwrap();
+    assert_eq!(plan.actions[0].result, DeployResult::Merged);
+}
+
+#[test]
+fn cache_misses_on_source_change() {
+    let vfs = Arc::new(MemoryFs::new().with_file(
+        "/src/Tester.md",
+        "---\nclaude.name: Tester\n---\nTest body.\n",
+    ));
+    let options = DeployOptions::new(Provider::Claude)
+        .with_vfs(vfs.clone())
+        .with_cache(true);
+    let config = SidecarConfig::default();
+    deploy_agents_from_dir(
+        Path::new("/src"),
+        Path::new("/dst"),
+        &config,
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+
+    vfs.write(
+        Path::new("/src/Tester.md"),
+        b"---\nclaude.name: Tester\n---\nNew body.\n",
+    )
+    .unwrap();
+    deploy_agents_from_dir(
+        Path::new("/src"),
+        Path::new("/dst"),
+        &config,
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+    let deployed = vfs.read_to_string(Path::new("/dst/Tester.md")).unwrap();
+    assert!(deployed.contains("New body."));
+}
+
+#[test]
+fn deploy_plan_keeps_agent_meta() {
+    let vfs = Arc::new(MemoryFs::new().with_file(
+        "/src/dev.md",
+        "---\nname: Developer\ndescription: Writes code\n---\nBody.\n",
+    ));
+    let options = DeployOptions::new(Provider::Claude).with_vfs(vfs.clone());
+    let plan = deploy_plan_from_dir(
+        Path::new("/src"),
+        Path::new("/dst"),
+        &SidecarConfig::default(),
+        &options,
+        &mut NoProgress,
+    )
+    .unwrap();
+    let meta = plan.actions[0].meta.as_ref().unwrap();
+    assert_eq!(meta.name, "Developer");
+    assert_eq!(meta.description, "Writes code");
+    assert!(vfs.exists(Path::new("/dst/Developer.md")));
 }
@@ -0,0 +1,67 @@
+//! Typed errors for the library. Each variant's message is the text the
+//! CLIs print after `Error: `, so converting to a `String` (as the CLI layer
+//! still does) loses nothing but the kind.
+
+use std::path::Path;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForgeError {
+    /// A filesystem operation failed; `context` names the operation and
+    /// path, e.g. `failed to read agents/Dev.md`.
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+    /// YAML could not be parsed or serialized.
+    #[error("{context}: {source}")]
+    Yaml {
+        context: String,
+        source: serde_yaml::Error,
+    },
+    /// Invalid configuration: a scope, sidecar value, or output template.
+    #[error("{0}")]
+    Config(String),
+    /// Content rejected before deploy: an agent name, an organization
+    /// policy violation, or an unsafe destination.
+    #[error("{0}")]
+    Validation(String),
+    /// A provider CLI forge shells out to cannot be used.
+    #[error("{0}")]
+    ProviderCli(String),
+}
+
+impl ForgeError {
+    /// An `Io` error mapper for `action` (`read`, `write`, ...) on `path`,
+    /// for use with `map_err`.
+    pub fn io(action: &str, path: &Path) -> impl FnOnce(std::io::Error) -> Self {
+        let context = format!("failed to {action} {}", path.display());
+        move |source| Self::Io { context, source }
+    }
+
+    /// A `Yaml` error mapper with the given message prefix.
+    pub fn yaml(context: impl Into<String>) -> impl FnOnce(serde_yaml::Error) -> Self {
+        let context = context.into();
+        move |source| Self::Yaml { context, source }
+    }
+}
+
+/// A background task of the async API panicked or was cancelled.
+#[cfg(feature = "async")]
+impl From<tokio::task::JoinError> for ForgeError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        Self::Io {
+            context: "background task failed".to_string(),
+            source: std::io::Error::other(error),
+        }
+    }
+}
+
+impl From<ForgeError> for String {
+    fn from(error: ForgeError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests;
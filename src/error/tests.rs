@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn io_message_names_action_and_path() {
+    let path = Path::new("/nonexistent/forge/Dev.md");
+    let err = std::fs::read_to_string(path)
+        .map_err(ForgeError::io("read", path))
+        .unwrap_err();
+    assert!(matches!(err, ForgeError::Io { .. }));
+    assert!(err
+        .to_string()
+        .starts_with("failed to read /nonexistent/forge/Dev.md: "));
+}
+
+#[test]
+fn yaml_message_keeps_context() {
+    let err = serde_yaml::from_str::<Vec<String>>("a: [")
+        .map_err(ForgeError::yaml("invalid YAML in config.yaml"))
+        .unwrap_err();
+    assert!(matches!(err, ForgeError::Yaml { .. }));
+    assert!(err.to_string().starts_with("invalid YAML in config.yaml: "));
+}
+
+#[test]
+fn converts_to_plain_message() {
+    let message: String = ForgeError::Validation("bad name".into()).into();
+    assert_eq!(message, "bad name");
+}
@@ -0,0 +1,188 @@
+//! Agents exported for use outside the supported providers (`forge export`).
+//!
+//! `openai` is a JSON bundle of `OpenAI` assistant definitions that can be
+//! created through the Assistants API or pasted into a custom GPT. `plain`
+//! is the bare system prompt of each agent, with the model and tools kept as
+//! comments, for any other LLM tooling.
+
+use crate::deploy::{self, provider::Provider};
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use crate::strip;
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// `OpenAI` limits on assistant fields, in characters.
+const OPENAI_DESCRIPTION_MAX: usize = 512;
+const OPENAI_METADATA_VALUE_MAX: usize = 512;
+
+/// An agent reduced to what every export format needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportAgent {
+    pub name: String,
+    pub description: String,
+    /// Model resolved through the Codex (`OpenAI`) tiers.
+    pub model: String,
+    /// Source tool names, e.g. `Read`, `Bash`.
+    pub tools: Vec<String>,
+    /// Body without frontmatter or the leading title.
+    pub instructions: String,
+    pub source: String,
+}
+
+/// Every agent under `agents_dir`, sorted by file name.
+pub fn load_agents(agents_dir: &Path, config: &SidecarConfig) -> Result<Vec<ExportAgent>, String> {
+    let entries = std::fs::read_dir(agents_dir)
+        .map_err(|e| format!("failed to read {}: {e}", agents_dir.display()))?;
+    let mut files: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "md"))
+        .collect();
+    files.sort();
+
+    let mut agents = Vec::new();
+    for path in files {
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let Some(meta) =
+            deploy::extract_agent_meta(&content, &filename, &Provider::Codex, config, "agents")
+        else {
+            continue;
+        };
+        let mut instructions = strip::strip_front(parse::fm_body(&content).trim_start())
+            .trim()
+            .to_string();
+        instructions.push('\n');
+        agents.push(ExportAgent {
+            name: meta.name,
+            description: meta.description,
+            model: meta.model,
+            tools: meta
+                .tools
+                .iter()
+                .flat_map(|t| t.split(','))
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+            instructions,
+            source: meta.source,
+        });
+    }
+    Ok(agents)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => text[..i].to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Assistant tools approximating the agent's source tools: shell access
+/// becomes `code_interpreter`, file reading becomes `file_search`.
+pub fn openai_tools(tools: &[String]) -> Vec<&'static str> {
+    let mut out = Vec::new();
+    for tool in tools {
+        let mapped = match tool.as_str() {
+            "Bash" => "code_interpreter",
+            "Read" | "Grep" | "Glob" => "file_search",
+            _ => continue,
+        };
+        if !out.contains(&mapped) {
+            out.push(mapped);
+        }
+    }
+    out
+}
+
+/// Whether `model` names an `OpenAI` model. A Codex tier the module never
+/// mapped resolves to the source model (e.g. `sonnet`), which no assistant
+/// accepts.
+pub fn is_openai_model(model: &str) -> bool {
+    const PREFIXES: &[&str] = &["gpt-", "chatgpt-", "codex-", "o1", "o3", "o4"];
+    PREFIXES.iter().any(|p| model.starts_with(p))
+}
+
+/// The `openai` bundle: one assistant definition per agent. The original
+/// tool list is kept in `metadata.tool_hints`, since most Claude tools have
+/// no assistant equivalent. `model` is left out unless it is an `OpenAI`
+/// model, so the API or GPT editor picks its default.
+pub fn openai_bundle(module: &str, agents: &[ExportAgent]) -> Value {
+    let assistants: Vec<Value> = agents
+        .iter()
+        .map(|agent| {
+            let tools: Vec<Value> = openai_tools(&agent.tools)
+                .into_iter()
+                .map(|t| json!({ "type": t }))
+                .collect();
+            let mut assistant = json!({
+                "name": agent.name,
+                "description": truncate(&agent.description, OPENAI_DESCRIPTION_MAX),
+                "model": agent.model,
+                "instructions": agent.instructions,
+                "tools": tools,
+                "metadata": {
+                    "source": truncate(&agent.source, OPENAI_METADATA_VALUE_MAX),
+                    "tool_hints": truncate(&agent.tools.join(", "), OPENAI_METADATA_VALUE_MAX),
+                },
+            });
+            if !is_openai_model(&agent.model) {
+                if let Some(fields) = assistant.as_object_mut() {
+                    fields.shift_remove("model");
+                }
+            }
+            assistant
+        })
+        .collect();
+    json!({
+        "format": "openai-assistants",
+        "module": module,
+        "assistants": assistants,
+    })
+}
+
+/// One agent as a plain system prompt: a comment header with the model and
+/// tool hints, then the instructions.
+pub fn plain_prompt(agent: &ExportAgent) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "<!-- agent: {} -->", agent.name);
+    let _ = writeln!(out, "<!-- model: {} -->", agent.model);
+    if !agent.tools.is_empty() {
+        let _ = writeln!(out, "<!-- tools: {} -->", agent.tools.join(", "));
+    }
+    let _ = writeln!(out, "<!-- source: {} -->", agent.source);
+    out.push('\n');
+    out.push_str(&agent.instructions);
+    out
+}
+
+/// Every agent's [`plain_prompt`] in one document, separated by rules.
+pub fn plain_bundle(agents: &[ExportAgent]) -> String {
+    agents
+        .iter()
+        .map(plain_prompt)
+        .collect::<Vec<_>>()
+        .join("\n---\n\n")
+}
+
+/// Write one `<name>.txt` [`plain_prompt`] per agent into `dir`, returning
+/// the file names written.
+pub fn write_plain_dir(dir: &Path, agents: &[ExportAgent]) -> Result<Vec<String>, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    let mut written = Vec::new();
+    for agent in agents {
+        let filename = format!("{}.txt", agent.name);
+        let path = dir.join(&filename);
+        std::fs::write(&path, plain_prompt(agent))
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+        written.push(filename);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests;
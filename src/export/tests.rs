@@ -0,0 +1,109 @@
+use super::*;
+use tempfile::TempDir;
+
+fn module(root: &Path) {
+    std::fs::create_dir_all(root.join("agents")).unwrap();
+    std::fs::write(
+        root.join("defaults.yaml"),
+        "agents:\n  Dev:\n    model: strong\n    tools: Read, Grep, Bash, WebSearch\n\
+         providers:\n  codex:\n    fast: gpt-5-mini\n    strong: gpt-5\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("agents/Dev.md"),
+        "---\nname: Dev\ndescription: Developer. USE WHEN code.\n---\n# Dev\n\nWrite code.\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("agents/Notes.md"), "No frontmatter.\n").unwrap();
+}
+
+#[test]
+fn agents_loaded_with_codex_model_and_stripped_body() {
+    let tmp = TempDir::new().unwrap();
+    module(tmp.path());
+    let agents = load_agents(&tmp.path().join("agents"), &SidecarConfig::load(tmp.path())).unwrap();
+    assert_eq!(agents.len(), 1);
+    let dev = &agents[0];
+    assert_eq!(dev.model, "gpt-5");
+    assert_eq!(dev.tools, vec!["Read", "Grep", "Bash", "WebSearch"]);
+    assert_eq!(dev.instructions, "Write code.\n");
+    assert_eq!(dev.source, "agents/Dev.md");
+}
+
+#[test]
+fn openai_bundle_maps_tools_and_keeps_hints() {
+    let tmp = TempDir::new().unwrap();
+    module(tmp.path());
+    let agents = load_agents(&tmp.path().join("agents"), &SidecarConfig::load(tmp.path())).unwrap();
+    let bundle = openai_bundle("forge-demo", &agents);
+    let dev = &bundle["assistants"][0];
+    assert_eq!(bundle["module"], "forge-demo");
+    assert_eq!(dev["name"], "Dev");
+    assert_eq!(dev["instructions"], "Write code.\n");
+    assert_eq!(
+        dev["tools"],
+        json!([{ "type": "file_search" }, { "type": "code_interpreter" }])
+    );
+    assert_eq!(dev["metadata"]["tool_hints"], "Read, Grep, Bash, WebSearch");
+}
+
+#[test]
+fn openai_bundle_omits_non_openai_model() {
+    let agent = |model: &str| ExportAgent {
+        name: "A".into(),
+        description: String::new(),
+        model: model.into(),
+        tools: Vec::new(),
+        instructions: String::new(),
+        source: "agents/A.md".into(),
+    };
+    let bundle = openai_bundle("m", &[agent("gpt-5"), agent("o3"), agent("sonnet")]);
+    let assistants = bundle["assistants"].as_array().unwrap();
+    assert_eq!(assistants[0]["model"], "gpt-5");
+    assert_eq!(assistants[1]["model"], "o3");
+    assert!(assistants[2].get("model").is_none());
+    let keys: Vec<_> = assistants[2].as_object().unwrap().keys().collect();
+    assert_eq!(
+        keys,
+        ["name", "description", "instructions", "tools", "metadata"]
+    );
+}
+
+#[test]
+fn long_description_is_truncated() {
+    let agent = ExportAgent {
+        name: "A".into(),
+        description: "é".repeat(600),
+        model: "gpt-5".into(),
+        tools: Vec::new(),
+        instructions: String::new(),
+        source: "agents/A.md".into(),
+    };
+    let bundle = openai_bundle("m", &[agent]);
+    let description = bundle["assistants"][0]["description"].as_str().unwrap();
+    assert_eq!(description.chars().count(), 512);
+}
+
+#[test]
+fn plain_prompt_has_hint_comments_then_body() {
+    let tmp = TempDir::new().unwrap();
+    module(tmp.path());
+    let agents = load_agents(&tmp.path().join("agents"), &SidecarConfig::load(tmp.path())).unwrap();
+    assert_eq!(
+        plain_prompt(&agents[0]),
+        "<!-- agent: Dev -->\n<!-- model: gpt-5 -->\n\
+         <!-- tools: Read, Grep, Bash, WebSearch -->\n<!-- source: agents/Dev.md -->\n\n\
+         Write code.\n"
+    );
+}
+
+#[test]
+fn plain_dir_writes_one_file_per_agent() {
+    let tmp = TempDir::new().unwrap();
+    module(tmp.path());
+    let agents = load_agents(&tmp.path().join("agents"), &SidecarConfig::load(tmp.path())).unwrap();
+    let out = tmp.path().join("prompts");
+    assert_eq!(write_plain_dir(&out, &agents).unwrap(), vec!["Dev.txt"]);
+    let text = std::fs::read_to_string(out.join("Dev.txt")).unwrap();
+    assert!(text.ends_with("Write code.\n"));
+}
@@ -0,0 +1,131 @@
+//! Typed frontmatter of agent and skill Markdown files.
+//!
+//! Values are read the way [`crate::parse::fm_value`] and
+//! [`crate::parse::fm_list`] read them: numbers and booleans become strings
+//! (`version: 1.2` is `"1.2"`), and list fields take either a YAML sequence
+//! or a comma-separated string. Keys not listed here are ignored.
+
+use crate::error::ForgeError;
+use crate::parse;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_yaml::Value;
+
+/// Frontmatter of an agent file. Legacy `claude.*` keys are kept apart from
+/// their plain counterparts; [`AgentFrontmatter::name`] and
+/// [`AgentFrontmatter::skills`] apply the precedence deploy uses.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct AgentFrontmatter {
+    #[serde(deserialize_with = "scalar")]
+    pub name: Option<String>,
+    #[serde(deserialize_with = "scalar")]
+    pub description: Option<String>,
+    #[serde(deserialize_with = "scalar")]
+    pub version: Option<String>,
+    #[serde(deserialize_with = "scalar")]
+    pub model: Option<String>,
+    #[serde(deserialize_with = "list")]
+    pub tools: Vec<String>,
+    #[serde(deserialize_with = "list")]
+    pub skills: Vec<String>,
+    /// `source:` recorded in deployed files.
+    #[serde(deserialize_with = "scalar")]
+    pub source: Option<String>,
+    #[serde(rename = "claude.name", deserialize_with = "scalar")]
+    pub claude_name: Option<String>,
+    #[serde(rename = "claude.description", deserialize_with = "scalar")]
+    pub claude_description: Option<String>,
+    #[serde(rename = "claude.model", deserialize_with = "scalar")]
+    pub claude_model: Option<String>,
+    #[serde(rename = "claude.tools", deserialize_with = "list")]
+    pub claude_tools: Vec<String>,
+    #[serde(rename = "claude.skills", deserialize_with = "list")]
+    pub claude_skills: Vec<String>,
+}
+
+impl AgentFrontmatter {
+    /// Parse the frontmatter of `content`. A file without frontmatter gives
+    /// the empty default; frontmatter that is not a YAML mapping is an error.
+    pub fn from_markdown(content: &str) -> Result<Self, ForgeError> {
+        from_markdown(content, "invalid agent frontmatter")
+    }
+
+    /// `name`, falling back to the legacy `claude.name`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref().or(self.claude_name.as_deref())
+    }
+
+    /// `claude.skills`, falling back to `skills`.
+    pub fn skills(&self) -> &[String] {
+        if self.claude_skills.is_empty() {
+            &self.skills
+        } else {
+            &self.claude_skills
+        }
+    }
+}
+
+/// Frontmatter of a `SKILL.md`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct SkillFrontmatter {
+    #[serde(deserialize_with = "scalar")]
+    pub name: Option<String>,
+    #[serde(deserialize_with = "scalar")]
+    pub description: Option<String>,
+    #[serde(deserialize_with = "scalar")]
+    pub version: Option<String>,
+    #[serde(rename = "allowed-tools", deserialize_with = "list")]
+    pub allowed_tools: Vec<String>,
+}
+
+impl SkillFrontmatter {
+    /// Parse the frontmatter of `content`, as
+    /// [`AgentFrontmatter::from_markdown`] does.
+    pub fn from_markdown(content: &str) -> Result<Self, ForgeError> {
+        from_markdown(content, "invalid skill frontmatter")
+    }
+}
+
+fn from_markdown<T: Default + for<'de> Deserialize<'de>>(
+    content: &str,
+    error: &str,
+) -> Result<T, ForgeError> {
+    match parse::split_frontmatter(content) {
+        Some((yaml, _)) if !yaml.trim().is_empty() => {
+            serde_yaml::from_str(yaml).map_err(ForgeError::yaml(error))
+        }
+        _ => Ok(T::default()),
+    }
+}
+
+fn scalar_string(value: Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn scalar<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(scalar_string(Value::deserialize(deserializer)?))
+}
+
+fn list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Sequence(items) => items.into_iter().filter_map(scalar_string).collect(),
+        Value::String(s) => s
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,49 @@
+use super::*;
+
+#[test]
+fn agent_reads_plain_and_legacy_fields() {
+    let fm = AgentFrontmatter::from_markdown(
+        "---\nclaude.name: Dev\ndescription: Developer\nversion: 1.2\nclaude.tools: Read, Grep\nskills:\n  - Review\n---\nBody.\n",
+    )
+    .unwrap();
+    assert_eq!(fm.name(), Some("Dev"));
+    assert_eq!(fm.description.as_deref(), Some("Developer"));
+    assert_eq!(fm.version.as_deref(), Some("1.2"));
+    assert_eq!(fm.claude_tools, vec!["Read", "Grep"]);
+    assert_eq!(fm.skills(), ["Review"]);
+}
+
+#[test]
+fn agent_name_prefers_plain_key() {
+    let fm = AgentFrontmatter::from_markdown("---\nname: New\nclaude.name: Old\n---\n").unwrap();
+    assert_eq!(fm.name(), Some("New"));
+}
+
+#[test]
+fn missing_frontmatter_is_default() {
+    assert_eq!(
+        AgentFrontmatter::from_markdown("# Just a body\n").unwrap(),
+        AgentFrontmatter::default()
+    );
+    assert_eq!(
+        SkillFrontmatter::from_markdown("---\n---\nBody\n").unwrap(),
+        SkillFrontmatter::default()
+    );
+}
+
+#[test]
+fn invalid_yaml_is_an_error() {
+    let err = AgentFrontmatter::from_markdown("---\n- a list\n---\n").unwrap_err();
+    assert!(matches!(err, ForgeError::Yaml { .. }));
+    assert!(err.to_string().starts_with("invalid agent frontmatter"));
+}
+
+#[test]
+fn skill_reads_allowed_tools() {
+    let fm = SkillFrontmatter::from_markdown(
+        "---\nname: Review\ndescription: Reviews code\nallowed-tools: [Read, Bash]\n---\n",
+    )
+    .unwrap();
+    assert_eq!(fm.name.as_deref(), Some("Review"));
+    assert_eq!(fm.allowed_tools, vec!["Read", "Bash"]);
+}
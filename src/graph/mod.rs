@@ -0,0 +1,241 @@
+//! Roster graph: councils, their role agents, and the skills those agents
+//! use, as a DOT or Mermaid diagram (`forge graph`).
+//!
+//! Councils come from `councils:` and from skills with `roles:`; agent
+//! skills from the agent's `skills` config or frontmatter. Anything
+//! referenced but missing from `agents/` or `skills/` is drawn dashed.
+
+use crate::council;
+use crate::deploy::{self, provider::Provider};
+use crate::sidecar::SidecarConfig;
+use crate::skill;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeKind {
+    Council,
+    Agent,
+    Skill,
+}
+
+impl NodeKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Council => "council",
+            Self::Agent => "agent",
+            Self::Skill => "skill",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub name: String,
+    /// Referenced, but not present in the module.
+    pub missing: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub from: (NodeKind, String),
+    pub to: (NodeKind, String),
+    pub label: &'static str,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Graph {
+    pub nodes: BTreeMap<(NodeKind, String), Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    fn node(&mut self, kind: NodeKind, name: &str, missing: bool) {
+        self.nodes
+            .entry((kind, name.to_string()))
+            .and_modify(|n| n.missing &= missing)
+            .or_insert_with(|| Node {
+                kind,
+                name: name.to_string(),
+                missing,
+            });
+    }
+
+    fn edge(&mut self, from: (NodeKind, &str), to: (NodeKind, &str), label: &'static str) {
+        let edge = Edge {
+            from: (from.0, from.1.to_string()),
+            to: (to.0, to.1.to_string()),
+            label,
+        };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+}
+
+fn skill_names(skills_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(skills_dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().join("SKILL.md").is_file())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Build the graph of the module at `module_root`.
+pub fn build(module_root: &Path, config: &SidecarConfig) -> Graph {
+    let mut graph = Graph::default();
+    let skills = skill_names(&module_root.join("skills"));
+    for name in &skills {
+        graph.node(NodeKind::Skill, name, false);
+    }
+
+    let mut agents: Vec<(String, Vec<String>)> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(module_root.join("agents")) {
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Some(meta) =
+                deploy::extract_agent_meta(&content, &filename, &Provider::Claude, config, "")
+            {
+                agents.push((meta.name, meta.skills));
+            }
+        }
+    }
+    agents.sort();
+    for (agent, agent_skills) in &agents {
+        graph.node(NodeKind::Agent, agent, false);
+        for used in agent_skills {
+            graph.node(NodeKind::Skill, used, !skills.contains(used));
+            graph.edge((NodeKind::Agent, agent), (NodeKind::Skill, used), "uses");
+        }
+    }
+
+    let mut councils: Vec<(String, Vec<String>)> = council::load_councils(config)
+        .into_iter()
+        .map(|c| (c.name, c.roles))
+        .collect();
+    for name in &skills {
+        let roles = skill::get_council_roles(config, name);
+        if !roles.is_empty() && !councils.iter().any(|(c, _)| c == name) {
+            councils.push((name.clone(), roles));
+        }
+    }
+    for (name, roles) in &councils {
+        graph.node(NodeKind::Council, name, false);
+        if skills.contains(name) {
+            graph.edge((NodeKind::Council, name), (NodeKind::Skill, name), "skill");
+        }
+        for role in roles {
+            let missing = !agents.iter().any(|(a, _)| a == role);
+            graph.node(NodeKind::Agent, role, missing);
+            graph.edge((NodeKind::Council, name), (NodeKind::Agent, role), "role");
+        }
+    }
+
+    graph
+}
+
+fn dot_id(kind: NodeKind, name: &str) -> String {
+    format!("\"{}:{}\"", kind.prefix(), name.replace('"', "\\\""))
+}
+
+/// Graphviz DOT, one cluster per node kind.
+pub fn to_dot(graph: &Graph) -> String {
+    let mut out =
+        String::from("digraph forge {\n  rankdir=LR;\n  node [fontname=\"Helvetica\"];\n");
+    for (kind, shape, title) in [
+        (NodeKind::Council, "hexagon", "Councils"),
+        (NodeKind::Agent, "box", "Agents"),
+        (NodeKind::Skill, "ellipse", "Skills"),
+    ] {
+        let nodes: Vec<&Node> = graph.nodes.values().filter(|n| n.kind == kind).collect();
+        if nodes.is_empty() {
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "  subgraph cluster_{} {{\n    label=\"{title}\";",
+            kind.prefix()
+        );
+        for node in nodes {
+            let style = if node.missing { ", style=dashed" } else { "" };
+            let _ = writeln!(
+                out,
+                "    {} [label=\"{}\", shape={shape}{style}];",
+                dot_id(kind, &node.name),
+                node.name.replace('"', "\\\"")
+            );
+        }
+        out.push_str("  }\n");
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "  {} -> {} [label=\"{}\"];",
+            dot_id(edge.from.0, &edge.from.1),
+            dot_id(edge.to.0, &edge.to.1),
+            edge.label
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn mermaid_id(kind: NodeKind, name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}_{safe}", kind.prefix())
+}
+
+/// Mermaid `flowchart`, with missing nodes in the `missing` class.
+pub fn to_mermaid(graph: &Graph) -> String {
+    let mut out = String::from("flowchart LR\n");
+    for node in graph.nodes.values() {
+        let id = mermaid_id(node.kind, &node.name);
+        let label = node.name.replace('"', "#quot;");
+        let shape = match node.kind {
+            NodeKind::Council => format!("{{{{\"{label}\"}}}}"),
+            NodeKind::Agent => format!("[\"{label}\"]"),
+            NodeKind::Skill => format!("([\"{label}\"])"),
+        };
+        let _ = writeln!(out, "  {id}{shape}");
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "  {} -->|{}| {}",
+            mermaid_id(edge.from.0, &edge.from.1),
+            edge.label,
+            mermaid_id(edge.to.0, &edge.to.1)
+        );
+    }
+    let missing: Vec<String> = graph
+        .nodes
+        .values()
+        .filter(|n| n.missing)
+        .map(|n| mermaid_id(n.kind, &n.name))
+        .collect();
+    if !missing.is_empty() {
+        out.push_str("  classDef missing stroke-dasharray: 5 5\n");
+        let _ = writeln!(out, "  class {} missing", missing.join(","));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,95 @@
+use super::*;
+use tempfile::TempDir;
+
+fn module() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("agents")).unwrap();
+    std::fs::create_dir_all(root.join("skills/Review")).unwrap();
+    std::fs::create_dir_all(root.join("skills/Lint")).unwrap();
+    std::fs::write(
+        root.join("skills/Review/SKILL.md"),
+        "---\nname: Review\n---\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("skills/Lint/SKILL.md"), "---\nname: Lint\n---\n").unwrap();
+    for name in ["Critic", "Tester"] {
+        std::fs::write(
+            root.join(format!("agents/{name}.md")),
+            format!("---\nname: {name}\ndescription: d\n---\nBody\n"),
+        )
+        .unwrap();
+    }
+    std::fs::write(
+        root.join("defaults.yaml"),
+        "agents:\n  Critic:\n    skills: [Lint, Ghost]\ncouncils:\n  Review:\n    roles: [Critic, Tester, Absent]\n",
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn builds_councils_agents_and_skills() {
+    let dir = module();
+    let graph = build(dir.path(), &SidecarConfig::load(dir.path()));
+
+    let missing: Vec<&str> = graph
+        .nodes
+        .values()
+        .filter(|n| n.missing)
+        .map(|n| n.name.as_str())
+        .collect();
+    assert_eq!(missing, vec!["Absent", "Ghost"]);
+    let edges: Vec<(&str, &str, &str)> = graph
+        .edges
+        .iter()
+        .map(|e| (e.from.1.as_str(), e.label, e.to.1.as_str()))
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("Critic", "uses", "Lint"),
+            ("Critic", "uses", "Ghost"),
+            ("Review", "skill", "Review"),
+            ("Review", "role", "Critic"),
+            ("Review", "role", "Tester"),
+            ("Review", "role", "Absent"),
+        ]
+    );
+}
+
+#[test]
+fn legacy_skill_roles_are_councils() {
+    let dir = module();
+    std::fs::write(
+        dir.path().join("defaults.yaml"),
+        "skills:\n  Review:\n    roles:\n      - Tester\n",
+    )
+    .unwrap();
+    let graph = build(dir.path(), &SidecarConfig::load(dir.path()));
+    assert!(graph
+        .nodes
+        .contains_key(&(NodeKind::Council, "Review".to_string())));
+    assert!(graph
+        .edges
+        .iter()
+        .any(|e| e.label == "role" && e.to.1 == "Tester"));
+}
+
+#[test]
+fn renders_dot_and_mermaid() {
+    let dir = module();
+    let graph = build(dir.path(), &SidecarConfig::load(dir.path()));
+
+    let dot = to_dot(&graph);
+    assert!(dot.starts_with("digraph forge {\n"));
+    assert!(dot.contains("\"council:Review\" [label=\"Review\", shape=hexagon];"));
+    assert!(dot.contains("\"agent:Absent\" [label=\"Absent\", shape=box, style=dashed];"));
+    assert!(dot.contains("\"council:Review\" -> \"agent:Critic\" [label=\"role\"];"));
+
+    let mermaid = to_mermaid(&graph);
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains("  council_Review{{\"Review\"}}\n"));
+    assert!(mermaid.contains("  agent_Critic -->|uses| skill_Lint\n"));
+    assert!(mermaid.contains("  class agent_Absent,skill_Ghost missing\n"));
+}
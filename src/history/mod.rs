@@ -0,0 +1,86 @@
+//! Append-only audit log of deploy operations, kept per destination root
+//! (e.g. `~/.claude`) in `.forge/history.jsonl` so "who changed my agents"
+//! can be answered after the fact (`forge history`).
+
+use serde::{Deserialize, Serialize};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Log location relative to a destination root.
+pub const HISTORY_FILE: &str = ".forge/history.jsonl";
+
+/// One item an operation touched, e.g. `install Reviewer`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub action: String,
+    pub name: String,
+}
+
+/// One operation of one tool against one destination root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub timestamp: String,
+    /// Installer that ran, e.g. `install-agents`.
+    pub tool: String,
+    /// `install` or `clean`.
+    pub operation: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// `ok`, `skipped`, or `partial` (some items failed or conflicted).
+    pub result: String,
+    pub files: Vec<Change>,
+}
+
+/// The destination root a deploy target belongs to: the provider directory
+/// holding `agents/`, `skills/`, `settings.json`, and so on.
+pub fn destination_root(target: &Path) -> PathBuf {
+    match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+pub fn history_path(root: &Path) -> PathBuf {
+    root.join(HISTORY_FILE)
+}
+
+/// Append `entry` to the log under `root`.
+pub fn append(root: &Path, entry: &Entry) -> Result<(), String> {
+    let path = history_path(root);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+    writeln!(file, "{line}").map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+/// Every entry logged under `root`, oldest first. A missing log is empty;
+/// an unparseable line is an error naming it.
+pub fn read(root: &Path) -> Result<Vec<Entry>, String> {
+    let path = history_path(root);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("{}:{}: invalid entry: {e}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,55 @@
+use super::*;
+use tempfile::TempDir;
+
+fn entry(tool: &str, files: &[(&str, &str)]) -> Entry {
+    Entry {
+        timestamp: "2026-01-02 03:04:05 UTC".into(),
+        tool: tool.into(),
+        operation: "install".into(),
+        module: Some("forge-dev".into()),
+        provider: Some("claude".into()),
+        scope: None,
+        result: "ok".into(),
+        files: files
+            .iter()
+            .map(|(action, name)| Change {
+                action: (*action).into(),
+                name: (*name).into(),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn destination_root_is_target_parent() {
+    assert_eq!(
+        destination_root(Path::new("/home/u/.claude/agents")),
+        Path::new("/home/u/.claude")
+    );
+    assert_eq!(destination_root(Path::new("agents")), Path::new("."));
+}
+
+#[test]
+fn append_then_read_in_order() {
+    let tmp = TempDir::new().unwrap();
+    assert!(read(tmp.path()).unwrap().is_empty());
+    let first = entry("install-agents", &[("install", "Dev")]);
+    let second = entry("install-skills", &[("remove", "Old")]);
+    append(tmp.path(), &first).unwrap();
+    append(tmp.path(), &second).unwrap();
+    assert_eq!(read(tmp.path()).unwrap(), vec![first, second]);
+
+    let log = std::fs::read_to_string(history_path(tmp.path())).unwrap();
+    assert_eq!(log.lines().count(), 2);
+    assert!(!log.contains("scope"));
+}
+
+#[test]
+fn corrupt_line_is_reported() {
+    let tmp = TempDir::new().unwrap();
+    append(tmp.path(), &entry("install-agents", &[])).unwrap();
+    let mut log = std::fs::read_to_string(history_path(tmp.path())).unwrap();
+    log.push_str("{not json\n");
+    std::fs::write(history_path(tmp.path()), log).unwrap();
+    assert!(read(tmp.path()).unwrap_err().contains("history.jsonl:2"));
+}
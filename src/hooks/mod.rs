@@ -0,0 +1,292 @@
+//! Hook deployment: merges a module's `hooks/hooks.json` (Claude plugin
+//! format) into a Claude `settings.json`.
+//!
+//! JSON has no comments, so the managed block is tracked out of band: a
+//! `.forge-hooks.json` manifest next to `settings.json` records, per module,
+//! the exact hook groups that were inserted. Redeploying or cleaning removes
+//! those groups and nothing else, leaving user-written hooks and every other
+//! setting untouched.
+
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+pub const HOOKS_FILE: &str = "hooks/hooks.json";
+const MANIFEST_FILE: &str = ".forge-hooks.json";
+pub(crate) const PLUGIN_ROOT: &str = "${CLAUDE_PLUGIN_ROOT}";
+
+/// Hook groups keyed by event name (`PreToolUse`, `Stop`, ...), each an array
+/// of `{ "matcher": ..., "hooks": [...] }` objects.
+pub type HookGroups = Map<String, Value>;
+
+/// One inserted or removed hook group, for reporting.
+#[derive(Debug, PartialEq)]
+pub struct HookEntry {
+    pub event: String,
+    pub matcher: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct HooksReport {
+    pub removed: Vec<HookEntry>,
+    pub added: Vec<HookEntry>,
+}
+
+// ─── Module hooks ───
+
+/// Load `hooks/hooks.json` from a module, with `${CLAUDE_PLUGIN_ROOT}`
+/// replaced by the module's absolute path (settings hooks have no plugin
+/// root). Returns `Ok(None)` when the module has no hooks file.
+pub fn load_module_hooks(module_root: &Path) -> Result<Option<HookGroups>, String> {
+    let path = module_root.join(HOOKS_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let root = std::fs::canonicalize(module_root)
+        .unwrap_or_else(|_| module_root.to_path_buf())
+        .display()
+        .to_string();
+    parse_hooks(&content, &root)
+        .map(Some)
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Parse a hooks document. Accepts the plugin form `{"hooks": {...}}` or a
+/// bare event map.
+pub fn parse_hooks(content: &str, plugin_root: &str) -> Result<HookGroups, String> {
+    let doc: Value = serde_json::from_str(content).map_err(|e| format!("invalid JSON: {e}"))?;
+    let hooks = match doc {
+        Value::Object(mut map) if map.contains_key("hooks") => map.remove("hooks"),
+        other => Some(other),
+    };
+    let Some(Value::Object(events)) = hooks else {
+        return Err("expected an object of hook events".to_string());
+    };
+    let mut groups = HookGroups::new();
+    for (event, value) in events {
+        if !value.is_array() {
+            return Err(format!("hooks for {event} must be an array"));
+        }
+        groups.insert(event, substitute_root(value, plugin_root));
+    }
+    Ok(groups)
+}
+
+pub(crate) fn substitute_root(value: Value, root: &str) -> Value {
+    match value {
+        Value::String(s) => Value::String(s.replace(PLUGIN_ROOT, root)),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|v| substitute_root(v, root))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, substitute_root(v, root)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+// ─── Targets ───
+
+/// Claude settings file for a scope: `user` is `~/.claude/settings.json`,
+/// `workspace` is `.claude/settings.json`, and `project` is the personal
+/// `.claude/settings.local.json`. There is no `all`: the same hook in two
+/// settings files would run twice.
+pub fn scope_settings_path(scope: &str, home: &Path) -> Result<PathBuf, String> {
+    match scope {
+        "user" => Ok(home.join(".claude/settings.json")),
+        "workspace" => Ok(PathBuf::from(".claude/settings.json")),
+        "project" => Ok(PathBuf::from(".claude/settings.local.json")),
+        other => Err(format!(
+            "invalid scope {other:?} for hooks: use user, workspace, or project"
+        )),
+    }
+}
+
+// ─── Deploy ───
+
+/// Replace `module_name`'s previously deployed hooks in `settings_path` with
+/// `hooks`. The settings file is created if missing; an unparseable one is
+/// an error rather than being overwritten.
+pub fn deploy_hooks(
+    settings_path: &Path,
+    module_name: &str,
+    hooks: &HookGroups,
+    dry_run: bool,
+) -> Result<HooksReport, String> {
+    let mut settings = read_json_object(settings_path)?;
+    let mut manifest = read_manifest(settings_path)?;
+    let previous = take_module(&mut manifest, module_name);
+
+    let removed = remove_groups(&mut settings, &previous);
+    let added = add_groups(&mut settings, hooks);
+    if !hooks.is_empty() {
+        manifest.insert(module_name.to_string(), Value::Object(hooks.clone()));
+    }
+
+    if !dry_run {
+        write_json_object(settings_path, &settings)?;
+        write_manifest(settings_path, &manifest)?;
+    }
+    Ok(HooksReport { removed, added })
+}
+
+/// Remove every hook group `module_name` deployed to `settings_path`.
+pub fn clean_hooks(
+    settings_path: &Path,
+    module_name: &str,
+    dry_run: bool,
+) -> Result<HooksReport, String> {
+    let mut manifest = read_manifest(settings_path)?;
+    let previous = take_module(&mut manifest, module_name);
+    if previous.is_empty() {
+        return Ok(HooksReport::default());
+    }
+
+    let mut settings = read_json_object(settings_path)?;
+    let removed = remove_groups(&mut settings, &previous);
+    if !dry_run {
+        write_json_object(settings_path, &settings)?;
+        write_manifest(settings_path, &manifest)?;
+    }
+    Ok(HooksReport {
+        removed,
+        added: Vec::new(),
+    })
+}
+
+fn entry(event: &str, group: &Value) -> HookEntry {
+    HookEntry {
+        event: event.to_string(),
+        matcher: group
+            .get("matcher")
+            .and_then(Value::as_str)
+            .filter(|m| !m.is_empty())
+            .map(String::from),
+    }
+}
+
+fn take_module(manifest: &mut Map<String, Value>, module_name: &str) -> HookGroups {
+    match manifest.shift_remove(module_name) {
+        Some(Value::Object(groups)) => groups,
+        _ => HookGroups::new(),
+    }
+}
+
+/// Remove one matching group from `settings.hooks[event]` per previously
+/// deployed group. Event arrays left empty are dropped, as is an empty
+/// `hooks` object.
+fn remove_groups(settings: &mut Map<String, Value>, previous: &HookGroups) -> Vec<HookEntry> {
+    let mut removed = Vec::new();
+    let Some(Value::Object(events)) = settings.get_mut("hooks") else {
+        return removed;
+    };
+    for (event, groups) in previous {
+        let Some(Value::Array(current)) = events.get_mut(event) else {
+            continue;
+        };
+        for group in groups.as_array().into_iter().flatten() {
+            if let Some(pos) = current.iter().position(|g| g == group) {
+                current.remove(pos);
+                removed.push(entry(event, group));
+            }
+        }
+        if current.is_empty() {
+            events.shift_remove(event);
+        }
+    }
+    if events.is_empty() {
+        settings.shift_remove("hooks");
+    }
+    removed
+}
+
+fn add_groups(settings: &mut Map<String, Value>, hooks: &HookGroups) -> Vec<HookEntry> {
+    let mut added = Vec::new();
+    if hooks.is_empty() {
+        return added;
+    }
+    let events = settings
+        .entry("hooks")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !events.is_object() {
+        *events = Value::Object(Map::new());
+    }
+    let Value::Object(events) = events else {
+        return added;
+    };
+    for (event, groups) in hooks {
+        let current = events
+            .entry(event.clone())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if !current.is_array() {
+            *current = Value::Array(Vec::new());
+        }
+        let Value::Array(current) = current else {
+            continue;
+        };
+        for group in groups.as_array().into_iter().flatten() {
+            current.push(group.clone());
+            added.push(entry(event, group));
+        }
+    }
+    added
+}
+
+// ─── Files ───
+
+pub(crate) fn read_json_object(path: &Path) -> Result<Map<String, Value>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(format!("failed to read {}: {e}", path.display())),
+    };
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err(format!("{} is not a JSON object", path.display())),
+        Err(e) => Err(format!("invalid JSON in {}: {e}", path.display())),
+    }
+}
+
+pub(crate) fn write_json_object(path: &Path, map: &Map<String, Value>) -> Result<(), String> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+    }
+    let mut json = serde_json::to_string_pretty(map)
+        .map_err(|e| format!("failed to serialize {}: {e}", path.display()))?;
+    json.push('\n');
+    std::fs::write(path, json).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+pub(crate) fn manifest_path(settings_path: &Path) -> PathBuf {
+    settings_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(MANIFEST_FILE)
+}
+
+/// Deployed hook groups per module, as recorded next to `settings_path`.
+pub(crate) fn read_manifest(settings_path: &Path) -> Result<Map<String, Value>, String> {
+    read_json_object(&manifest_path(settings_path))
+}
+
+fn write_manifest(settings_path: &Path, manifest: &Map<String, Value>) -> Result<(), String> {
+    let path = manifest_path(settings_path);
+    if manifest.is_empty() {
+        let _ = std::fs::remove_file(&path);
+        return Ok(());
+    }
+    write_json_object(&path, manifest)
+}
+
+#[cfg(test)]
+mod tests;
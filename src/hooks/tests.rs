@@ -0,0 +1,150 @@
+use super::*;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+fn groups(value: &Value) -> HookGroups {
+    value.as_object().unwrap().clone()
+}
+
+fn read(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+// ─── parse_hooks ───
+
+#[test]
+fn parse_plugin_form_substitutes_root() {
+    let content = r#"{"hooks": {"Stop": [{"hooks": [{"type": "command", "command": "${CLAUDE_PLUGIN_ROOT}/hooks/stop.sh"}]}]}}"#;
+    let hooks = parse_hooks(content, "/mods/forge-demo").unwrap();
+    assert_eq!(
+        hooks["Stop"][0]["hooks"][0]["command"],
+        "/mods/forge-demo/hooks/stop.sh"
+    );
+}
+
+#[test]
+fn parse_bare_event_map() {
+    let hooks = parse_hooks(r#"{"Stop": []}"#, "/r").unwrap();
+    assert!(hooks.contains_key("Stop"));
+}
+
+#[test]
+fn parse_rejects_non_array_event() {
+    let err = parse_hooks(r#"{"hooks": {"Stop": {}}}"#, "/r").unwrap_err();
+    assert!(err.contains("Stop"));
+}
+
+#[test]
+fn parse_rejects_invalid_json() {
+    assert!(parse_hooks("{", "/r").unwrap_err().contains("invalid JSON"));
+}
+
+#[test]
+fn load_missing_hooks_file_is_none() {
+    let dir = TempDir::new().unwrap();
+    assert!(load_module_hooks(dir.path()).unwrap().is_none());
+}
+
+// ─── scope_settings_path ───
+
+#[test]
+fn scope_paths() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        scope_settings_path("user", home).unwrap(),
+        PathBuf::from("/home/u/.claude/settings.json")
+    );
+    assert_eq!(
+        scope_settings_path("project", home).unwrap(),
+        PathBuf::from(".claude/settings.local.json")
+    );
+    assert!(scope_settings_path("all", home).is_err());
+}
+
+// ─── deploy_hooks / clean_hooks ───
+
+#[test]
+fn deploy_preserves_other_settings_and_user_hooks() {
+    let dir = TempDir::new().unwrap();
+    let settings = dir.path().join("settings.json");
+    let user_group = json!({"matcher": "Edit", "hooks": [{"type": "command", "command": "mine"}]});
+    fs::write(
+        &settings,
+        json!({"model": "opus", "hooks": {"PreToolUse": [user_group.clone()]}}).to_string(),
+    )
+    .unwrap();
+
+    let module_group = json!({"matcher": "Bash", "hooks": [{"type": "command", "command": "x"}]});
+    let hooks = groups(&json!({"PreToolUse": [module_group.clone()]}));
+    let report = deploy_hooks(&settings, "forge-demo", &hooks, false).unwrap();
+    assert_eq!(
+        report.added,
+        vec![HookEntry {
+            event: "PreToolUse".into(),
+            matcher: Some("Bash".into()),
+        }]
+    );
+
+    let doc = read(&settings);
+    assert_eq!(doc["model"], "opus");
+    assert_eq!(
+        doc["hooks"]["PreToolUse"],
+        json!([user_group, module_group])
+    );
+}
+
+#[test]
+fn redeploy_replaces_previous_groups() {
+    let dir = TempDir::new().unwrap();
+    let settings = dir.path().join("settings.json");
+    let v1 = groups(&json!({"Stop": [{"hooks": [{"type": "command", "command": "v1"}]}]}));
+    let v2 = groups(&json!({"Stop": [{"hooks": [{"type": "command", "command": "v2"}]}]}));
+
+    deploy_hooks(&settings, "forge-demo", &v1, false).unwrap();
+    let report = deploy_hooks(&settings, "forge-demo", &v2, false).unwrap();
+    assert_eq!(report.removed.len(), 1);
+
+    let doc = read(&settings);
+    assert_eq!(doc["hooks"]["Stop"].as_array().unwrap().len(), 1);
+    assert_eq!(doc["hooks"]["Stop"][0]["hooks"][0]["command"], "v2");
+}
+
+#[test]
+fn clean_removes_only_module_groups() {
+    let dir = TempDir::new().unwrap();
+    let settings = dir.path().join("settings.json");
+    let a = groups(&json!({"Stop": [{"hooks": [{"type": "command", "command": "a"}]}]}));
+    let b = groups(&json!({"Stop": [{"hooks": [{"type": "command", "command": "b"}]}]}));
+    deploy_hooks(&settings, "mod-a", &a, false).unwrap();
+    deploy_hooks(&settings, "mod-b", &b, false).unwrap();
+
+    let report = clean_hooks(&settings, "mod-a", false).unwrap();
+    assert_eq!(report.removed.len(), 1);
+    let doc = read(&settings);
+    assert_eq!(doc["hooks"]["Stop"], json!(b["Stop"]));
+
+    clean_hooks(&settings, "mod-b", false).unwrap();
+    assert_eq!(read(&settings), json!({}));
+    assert!(!dir.path().join(".forge-hooks.json").exists());
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let dir = TempDir::new().unwrap();
+    let settings = dir.path().join("settings.json");
+    let hooks = groups(&json!({"Stop": [{"hooks": []}]}));
+    let report = deploy_hooks(&settings, "forge-demo", &hooks, true).unwrap();
+    assert_eq!(report.added.len(), 1);
+    assert!(!settings.exists());
+}
+
+#[test]
+fn invalid_settings_is_not_overwritten() {
+    let dir = TempDir::new().unwrap();
+    let settings = dir.path().join("settings.json");
+    fs::write(&settings, "{ not json").unwrap();
+    let hooks = groups(&json!({"Stop": []}));
+    assert!(deploy_hooks(&settings, "forge-demo", &hooks, false).is_err());
+    assert_eq!(fs::read_to_string(&settings).unwrap(), "{ not json");
+}
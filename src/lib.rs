@@ -1,8 +1,59 @@
+//! Shared library behind the forge CLIs: frontmatter parsing, markdown
+//! processing, and deploying module agents, skills, and config to AI coding
+//! assistants.
+//!
+//! Embedders should start from [`prelude`], which also states what is
+//! covered by semver. `cli` backs the bundled binaries and is not a library
+//! API.
+//!
+//! The default `cli` and `validate` features build the binaries and the
+//! module validation suites; `default-features = false` leaves the parse,
+//! deploy, and skill library without `tempfile` or `tracing-subscriber`.
+
+pub mod cache;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod cli;
+pub mod commands;
+pub mod council;
+pub mod credentials;
+#[cfg(feature = "validate")]
 pub mod dci;
 pub mod deploy;
+pub mod error;
+pub mod export;
+pub mod frontmatter;
+pub mod graph;
+pub mod history;
+pub mod hooks;
 pub mod manifest;
+pub mod mcp;
+pub mod memory;
+pub mod merge;
+pub mod migrate;
+pub mod outdated;
+pub mod pack;
 pub mod parse;
+pub mod plugin;
+pub mod policy;
+pub mod prelude;
+pub mod progress;
+pub mod query;
+pub mod registry;
+pub mod scaffold;
+pub mod schema;
 pub mod sidecar;
 pub mod skill;
+pub mod snapshot;
+pub mod status;
 pub mod strip;
+pub mod styles;
+pub mod template;
+pub mod toml_fmt;
+#[cfg(feature = "validate")]
 pub mod validate;
+pub mod vfs;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod watch;
+pub mod workspace;
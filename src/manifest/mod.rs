@@ -1,11 +1,20 @@
+use crate::error::ForgeError;
+use crate::pack::sha256_hex;
+use crate::vfs::Vfs;
+use serde::Serialize;
 use std::collections::BTreeMap;
 use std::path::Path;
 
-const MANIFEST_FILE: &str = ".manifest";
+pub(crate) const MANIFEST_FILE: &str = ".manifest";
+const VERSIONS_FILE: &str = ".manifest-versions";
+const CHECKSUMS_FILE: &str = ".manifest-checksums";
 
-pub fn read(dst_dir: &Path, module_name: &str) -> Vec<String> {
+/// SHA-256 of each deployed file, by filename, per module.
+type Checksums = BTreeMap<String, BTreeMap<String, String>>;
+
+pub fn read(vfs: &dyn Vfs, dst_dir: &Path, module_name: &str) -> Vec<String> {
     let path = dst_dir.join(MANIFEST_FILE);
-    let Ok(content) = std::fs::read_to_string(&path) else {
+    let Ok(content) = vfs.read_to_string(&path) else {
         return Vec::new();
     };
     let Ok(map) = serde_yaml::from_str::<BTreeMap<String, Vec<String>>>(&content) else {
@@ -14,29 +23,246 @@ pub fn read(dst_dir: &Path, module_name: &str) -> Vec<String> {
     map.get(module_name).cloned().unwrap_or_default()
 }
 
-pub fn update(dst_dir: &Path, module_name: &str, entries: &[String]) -> Result<(), String> {
+pub fn update(
+    vfs: &dyn Vfs,
+    dst_dir: &Path,
+    module_name: &str,
+    entries: &[String],
+) -> Result<(), ForgeError> {
     let path = dst_dir.join(MANIFEST_FILE);
-    let mut map: BTreeMap<String, Vec<String>> = std::fs::read_to_string(&path)
+    let mut map: BTreeMap<String, Vec<String>> = vfs
+        .read_to_string(&path)
         .ok()
         .and_then(|c| serde_yaml::from_str(&c).ok())
         .unwrap_or_default();
 
     if entries.is_empty() {
         map.remove(module_name);
+        write_checksums(vfs, dst_dir, module_name, BTreeMap::new())?;
     } else {
         map.insert(module_name.to_string(), entries.to_vec());
     }
 
     if map.is_empty() {
-        let _ = std::fs::remove_file(&path);
+        let _ = vfs.remove_file(&path);
     } else {
         let yaml = serde_yaml::to_string(&map)
-            .map_err(|e| format!("failed to serialize manifest: {e}"))?;
-        std::fs::write(&path, yaml)
-            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+            .map_err(ForgeError::yaml("failed to serialize manifest"))?;
+        vfs.write(&path, yaml.as_bytes())
+            .map_err(ForgeError::io("write", &path))?;
     }
     Ok(())
 }
 
+/// Update the manifest and record which `version` of the module the entries
+/// came from. The version is dropped along with the module's entries.
+pub fn record(
+    vfs: &dyn Vfs,
+    dst_dir: &Path,
+    module_name: &str,
+    version: Option<&str>,
+    entries: &[String],
+) -> Result<(), ForgeError> {
+    update(vfs, dst_dir, module_name, entries)?;
+    let version = version.filter(|_| !entries.is_empty());
+    update_version(vfs, dst_dir, module_name, version)
+}
+
+/// Deployed module versions recorded in `dst_dir`.
+pub fn read_versions(vfs: &dyn Vfs, dst_dir: &Path) -> BTreeMap<String, String> {
+    vfs.read_to_string(&dst_dir.join(VERSIONS_FILE))
+        .ok()
+        .and_then(|c| serde_yaml::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn update_version(
+    vfs: &dyn Vfs,
+    dst_dir: &Path,
+    module_name: &str,
+    version: Option<&str>,
+) -> Result<(), ForgeError> {
+    let path = dst_dir.join(VERSIONS_FILE);
+    let mut map = read_versions(vfs, dst_dir);
+    let changed = match version {
+        Some(v) => {
+            map.insert(module_name.to_string(), v.to_string())
+                .as_deref()
+                != Some(v)
+        }
+        None => map.remove(module_name).is_some(),
+    };
+    if !changed {
+        return Ok(());
+    }
+
+    if map.is_empty() {
+        let _ = vfs.remove_file(&path);
+    } else {
+        let yaml = serde_yaml::to_string(&map)
+            .map_err(ForgeError::yaml("failed to serialize manifest versions"))?;
+        vfs.write(&path, yaml.as_bytes())
+            .map_err(ForgeError::io("write", &path))?;
+    }
+    Ok(())
+}
+
+/// Checksums recorded in `dst_dir`.
+pub fn read_checksums(vfs: &dyn Vfs, dst_dir: &Path) -> Checksums {
+    vfs.read_to_string(&dst_dir.join(CHECKSUMS_FILE))
+        .ok()
+        .and_then(|c| serde_yaml::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Record the SHA-256 of each of `files` (names in `dst_dir`) as deployed by
+/// `module_name`, replacing what the module recorded before. Files that do
+/// not exist are left out, as are symlinks: a `--link` deploy's content is
+/// the source's, edited at will.
+pub fn record_checksums(
+    vfs: &dyn Vfs,
+    dst_dir: &Path,
+    module_name: &str,
+    files: &[String],
+) -> Result<(), ForgeError> {
+    let sums = files
+        .iter()
+        .filter_map(|file| {
+            let path = dst_dir.join(file);
+            if vfs.is_symlink(&path) {
+                return None;
+            }
+            let bytes = vfs.read(&path).ok()?;
+            Some((file.clone(), sha256_hex(&bytes)))
+        })
+        .collect();
+    write_checksums(vfs, dst_dir, module_name, sums)
+}
+
+fn write_checksums(
+    vfs: &dyn Vfs,
+    dst_dir: &Path,
+    module_name: &str,
+    sums: BTreeMap<String, String>,
+) -> Result<(), ForgeError> {
+    let path = dst_dir.join(CHECKSUMS_FILE);
+    let mut map = read_checksums(vfs, dst_dir);
+    let changed = if sums.is_empty() {
+        map.remove(module_name).is_some()
+    } else {
+        map.insert(module_name.to_string(), sums.clone()) != Some(sums)
+    };
+    if !changed {
+        return Ok(());
+    }
+
+    if map.is_empty() {
+        let _ = vfs.remove_file(&path);
+    } else {
+        let yaml = serde_yaml::to_string(&map)
+            .map_err(ForgeError::yaml("failed to serialize manifest checksums"))?;
+        vfs.write(&path, yaml.as_bytes())
+            .map_err(ForgeError::io("write", &path))?;
+    }
+    Ok(())
+}
+
+/// How a deployed file compares with the checksum recorded for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Integrity {
+    Unchanged,
+    /// Edited since it was deployed.
+    Modified,
+    Missing,
+}
+
+impl Integrity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unchanged => "unchanged",
+            Self::Modified => "modified",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+/// One file [`verify`] checked.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Verified {
+    pub module: String,
+    pub file: String,
+    pub integrity: Integrity,
+}
+
+fn integrity(vfs: &dyn Vfs, path: &Path, sum: &str) -> Integrity {
+    match vfs.read(path) {
+        Ok(bytes) if sha256_hex(&bytes) == sum => Integrity::Unchanged,
+        Ok(_) => Integrity::Modified,
+        Err(_) => Integrity::Missing,
+    }
+}
+
+/// Check every file with a recorded checksum in `dst_dir`, by module and
+/// filename.
+pub fn verify(vfs: &dyn Vfs, dst_dir: &Path) -> Vec<Verified> {
+    read_checksums(vfs, dst_dir)
+        .into_iter()
+        .flat_map(|(module, sums)| {
+            sums.into_iter().map(move |(file, sum)| Verified {
+                integrity: integrity(vfs, &dst_dir.join(&file), &sum),
+                module: module.clone(),
+                file,
+            })
+        })
+        .collect()
+}
+
+/// Whether `file` in `dst_dir` was edited since `module_name` deployed it.
+/// `false` when no checksum was recorded for it.
+pub fn is_modified(vfs: &dyn Vfs, dst_dir: &Path, module_name: &str, file: &str) -> bool {
+    read_checksums(vfs, dst_dir)
+        .get(module_name)
+        .and_then(|sums| sums.get(file))
+        .is_some_and(|sum| integrity(vfs, &dst_dir.join(file), sum) == Integrity::Modified)
+}
+
+/// Remove `<name>.<ext>` files recorded for `module_name` whose names are
+/// not in `current`, returning the names removed. Files edited since they
+/// were deployed (see [`is_modified`]) are kept.
+pub fn remove_orphans(
+    vfs: &dyn Vfs,
+    dst_dir: &Path,
+    module_name: &str,
+    current: &[String],
+    ext: &str,
+    dry_run: bool,
+) -> Result<Vec<String>, ForgeError> {
+    if module_name.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut removed = Vec::new();
+    for name in read(vfs, dst_dir, module_name) {
+        if current.contains(&name) {
+            continue;
+        }
+        let path = dst_dir.join(format!("{name}.{ext}"));
+        if !vfs.exists(&path) {
+            continue;
+        }
+        if is_modified(vfs, dst_dir, module_name, &format!("{name}.{ext}")) {
+            tracing::warn!(path = %path.display(), "orphan edited since deploy; kept");
+            continue;
+        }
+        if !dry_run {
+            vfs.remove_file(&path)
+                .map_err(ForgeError::io("remove", &path))?;
+        }
+        removed.push(name);
+    }
+    Ok(removed)
+}
+
 #[cfg(test)]
 mod tests;
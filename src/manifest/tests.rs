@@ -1,57 +1,153 @@
 use super::*;
-use tempfile::TempDir;
+use crate::vfs::MemoryFs;
+
+fn dst() -> (MemoryFs, &'static Path) {
+    let dir = Path::new("/dst");
+    let vfs = MemoryFs::new();
+    vfs.create_dir_all(dir).unwrap();
+    (vfs, dir)
+}
 
 #[test]
 fn roundtrip() {
-    let dir = TempDir::new().unwrap();
+    let (vfs, dir) = dst();
     let entries = vec!["Alpha".to_string(), "Beta".to_string()];
-    update(dir.path(), "forge-council", &entries).unwrap();
-    let loaded = read(dir.path(), "forge-council");
+    update(&vfs, dir, "forge-council", &entries).unwrap();
+    let loaded = read(&vfs, dir, "forge-council");
     assert_eq!(loaded, entries);
 }
 
 #[test]
 fn read_missing_returns_empty() {
-    let dir = TempDir::new().unwrap();
-    let loaded = read(dir.path(), "forge-council");
+    let (vfs, dir) = dst();
+    let loaded = read(&vfs, dir, "forge-council");
     assert!(loaded.is_empty());
 }
 
 #[test]
 fn read_wrong_module_returns_empty() {
-    let dir = TempDir::new().unwrap();
+    let (vfs, dir) = dst();
     let entries = vec!["Alpha".to_string()];
-    update(dir.path(), "forge-council", &entries).unwrap();
-    let loaded = read(dir.path(), "other-module");
+    update(&vfs, dir, "forge-council", &entries).unwrap();
+    let loaded = read(&vfs, dir, "other-module");
     assert!(loaded.is_empty());
 }
 
 #[test]
 fn multi_module() {
-    let dir = TempDir::new().unwrap();
+    let (vfs, dir) = dst();
     let council = vec!["Council".to_string()];
     let other = vec!["Helper".to_string(), "Util".to_string()];
-    update(dir.path(), "forge-council", &council).unwrap();
-    update(dir.path(), "forge-other", &other).unwrap();
-    assert_eq!(read(dir.path(), "forge-council"), council);
-    assert_eq!(read(dir.path(), "forge-other"), other);
+    update(&vfs, dir, "forge-council", &council).unwrap();
+    update(&vfs, dir, "forge-other", &other).unwrap();
+    assert_eq!(read(&vfs, dir, "forge-council"), council);
+    assert_eq!(read(&vfs, dir, "forge-other"), other);
 }
 
 #[test]
 fn empty_entries_removes_module() {
-    let dir = TempDir::new().unwrap();
+    let (vfs, dir) = dst();
     let entries = vec!["Alpha".to_string()];
-    update(dir.path(), "forge-council", &entries).unwrap();
-    update(dir.path(), "forge-council", &[]).unwrap();
-    assert!(read(dir.path(), "forge-council").is_empty());
+    update(&vfs, dir, "forge-council", &entries).unwrap();
+    update(&vfs, dir, "forge-council", &[]).unwrap();
+    assert!(read(&vfs, dir, "forge-council").is_empty());
 }
 
 #[test]
 fn empty_map_removes_file() {
-    let dir = TempDir::new().unwrap();
+    let (vfs, dir) = dst();
     let entries = vec!["Alpha".to_string()];
-    update(dir.path(), "forge-council", &entries).unwrap();
-    assert!(dir.path().join(".manifest").exists());
-    update(dir.path(), "forge-council", &[]).unwrap();
-    assert!(!dir.path().join(".manifest").exists());
+    update(&vfs, dir, "forge-council", &entries).unwrap();
+    assert!(vfs.exists(&dir.join(".manifest")));
+    update(&vfs, dir, "forge-council", &[]).unwrap();
+    assert!(!vfs.exists(&dir.join(".manifest")));
+}
+
+#[test]
+fn remove_orphans_keeps_current_and_foreign() {
+    let (vfs, dir) = dst();
+    for name in ["Old", "Keep", "Mine"] {
+        vfs.write(&dir.join(format!("{name}.md")), b"x").unwrap();
+    }
+    update(&vfs, dir, "forge-council", &["Old".into(), "Keep".into()]).unwrap();
+
+    let removed =
+        remove_orphans(&vfs, dir, "forge-council", &["Keep".into()], "md", false).unwrap();
+    assert_eq!(removed, vec!["Old"]);
+    assert!(vfs.exists(&dir.join("Keep.md")));
+    assert!(vfs.exists(&dir.join("Mine.md")));
+}
+
+#[test]
+fn record_tracks_versions() {
+    let (vfs, dir) = dst();
+    record(&vfs, dir, "forge-council", Some("1.2.0"), &["Alpha".into()]).unwrap();
+    record(&vfs, dir, "forge-other", None, &["Beta".into()]).unwrap();
+    let versions = read_versions(&vfs, dir);
+    assert_eq!(
+        versions.get("forge-council").map(String::as_str),
+        Some("1.2.0")
+    );
+    assert!(!versions.contains_key("forge-other"));
+
+    record(&vfs, dir, "forge-council", Some("1.2.0"), &[]).unwrap();
+    assert!(read_versions(&vfs, dir).is_empty());
+    assert!(!vfs.exists(&dir.join(".manifest-versions")));
+}
+
+#[test]
+fn checksums_tell_edited_files_from_unchanged() {
+    let (vfs, dir) = dst();
+    vfs.write(&dir.join("Alpha.md"), b"alpha").unwrap();
+    vfs.write(&dir.join("Beta.md"), b"beta").unwrap();
+    update(&vfs, dir, "forge-council", &["Alpha".into(), "Beta".into()]).unwrap();
+    record_checksums(
+        &vfs,
+        dir,
+        "forge-council",
+        &["Alpha.md".into(), "Beta.md".into(), "Gone.md".into()],
+    )
+    .unwrap();
+    assert_eq!(read_checksums(&vfs, dir)["forge-council"].len(), 2);
+
+    vfs.write(&dir.join("Alpha.md"), b"edited").unwrap();
+    vfs.remove_file(&dir.join("Beta.md")).unwrap();
+    assert!(is_modified(&vfs, dir, "forge-council", "Alpha.md"));
+    assert!(!is_modified(&vfs, dir, "forge-council", "Beta.md"));
+    assert!(!is_modified(&vfs, dir, "other", "Alpha.md"));
+    let states: Vec<_> = verify(&vfs, dir)
+        .into_iter()
+        .map(|v| (v.file, v.integrity))
+        .collect();
+    assert_eq!(
+        states,
+        vec![
+            ("Alpha.md".to_string(), Integrity::Modified),
+            ("Beta.md".to_string(), Integrity::Missing),
+        ]
+    );
+
+    update(&vfs, dir, "forge-council", &[]).unwrap();
+    assert!(!vfs.exists(&dir.join(".manifest-checksums")));
+}
+
+#[test]
+fn remove_orphans_keeps_edited_files() {
+    let (vfs, dir) = dst();
+    for name in ["Old", "Edited"] {
+        vfs.write(&dir.join(format!("{name}.md")), b"x").unwrap();
+    }
+    update(&vfs, dir, "forge-council", &["Old".into(), "Edited".into()]).unwrap();
+    record_checksums(
+        &vfs,
+        dir,
+        "forge-council",
+        &["Old.md".into(), "Edited.md".into()],
+    )
+    .unwrap();
+    vfs.write(&dir.join("Edited.md"), b"mine").unwrap();
+
+    let removed = remove_orphans(&vfs, dir, "forge-council", &[], "md", false).unwrap();
+    assert_eq!(removed, vec!["Old"]);
+    assert!(vfs.exists(&dir.join("Edited.md")));
 }
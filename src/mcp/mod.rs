@@ -0,0 +1,339 @@
+//! MCP server deployment: installs the server definitions in a module's
+//! `mcp/` directory into each provider's MCP configuration.
+//!
+//! JSON targets (`.mcp.json`, `~/.claude.json`, Gemini `settings.json`) keep
+//! servers under `mcpServers`; what a module inserted is recorded in a
+//! `.forge-mcp.json` manifest next to the target, as hooks do. Codex reads
+//! `[mcp_servers.<name>]` tables from `config.toml`, where each module gets
+//! its own managed block.
+
+use crate::deploy::provider::Provider;
+use crate::deploy::strip_managed_block;
+use crate::hooks::{read_json_object, substitute_root, write_json_object};
+use crate::toml_fmt;
+use serde_json::{Map, Value};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+pub const MCP_DIR: &str = "mcp";
+const MANIFEST_FILE: &str = ".forge-mcp.json";
+const SERVERS_KEY: &str = "mcpServers";
+
+/// Server definitions keyed by server name.
+pub type Servers = Map<String, Value>;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct McpReport {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Servers left alone because a definition the module does not own
+    /// already uses the name.
+    pub skipped: Vec<String>,
+}
+
+// ─── Module servers ───
+
+/// Load every `mcp/*.json` in a module, with `${CLAUDE_PLUGIN_ROOT}` replaced
+/// by the module's absolute path. Returns an empty map when there is no
+/// `mcp/` directory.
+pub fn load_module_servers(module_root: &Path) -> Result<Servers, String> {
+    let dir = module_root.join(MCP_DIR);
+    if !dir.is_dir() {
+        return Ok(Servers::new());
+    }
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("failed to read {}: {e}", dir.display()))?;
+    let mut files: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let root = std::fs::canonicalize(module_root)
+        .unwrap_or_else(|_| module_root.to_path_buf())
+        .display()
+        .to_string();
+    let mut servers = Servers::new();
+    for path in files {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        for (name, server) in
+            parse_servers(&content, &stem, &root).map_err(|e| format!("{}: {e}", path.display()))?
+        {
+            if servers.contains_key(&name) {
+                return Err(format!("{}: server {name:?} defined twice", path.display()));
+            }
+            servers.insert(name, server);
+        }
+    }
+    Ok(servers)
+}
+
+/// Parse one server file. Accepts the `.mcp.json` form
+/// `{"mcpServers": {...}}`, or a single bare server definition named after
+/// the file stem.
+pub fn parse_servers(content: &str, stem: &str, plugin_root: &str) -> Result<Servers, String> {
+    let doc: Value = serde_json::from_str(content).map_err(|e| format!("invalid JSON: {e}"))?;
+    let Value::Object(mut map) = substitute_root(doc, plugin_root) else {
+        return Err("expected a JSON object".to_string());
+    };
+    let servers = match map.shift_remove(SERVERS_KEY) {
+        Some(Value::Object(servers)) => servers,
+        Some(_) => return Err(format!("{SERVERS_KEY} must be an object")),
+        None => Servers::from_iter([(stem.to_string(), Value::Object(map))]),
+    };
+    for (name, server) in &servers {
+        validate_server(name, server)?;
+    }
+    Ok(servers)
+}
+
+fn validate_server(name: &str, server: &Value) -> Result<(), String> {
+    let valid_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Err(format!(
+            "server name {name:?} must be letters, digits, '-' or '_'"
+        ));
+    }
+    let Some(server) = server.as_object() else {
+        return Err(format!("server {name:?} must be an object"));
+    };
+    if !server.contains_key("command") && !server.contains_key("url") {
+        return Err(format!("server {name:?} needs a command or url"));
+    }
+    Ok(())
+}
+
+// ─── Targets ───
+
+/// MCP configuration file for a provider and scope, or `None` when the
+/// provider is not supported. Codex only reads `~/.codex/config.toml` and
+/// Windsurf only `~/.codeium/windsurf/mcp_config.json`.
+pub fn mcp_config_path(
+    provider: &Provider,
+    scope: &str,
+    home: &Path,
+) -> Result<Option<PathBuf>, String> {
+    let (user, workspace) = match provider {
+        Provider::Claude => (".claude.json", ".mcp.json"),
+        Provider::Gemini => (".gemini/settings.json", ".gemini/settings.json"),
+        Provider::Codex => return Ok(Some(home.join(".codex/config.toml"))),
+        Provider::Cursor => (".cursor/mcp.json", ".cursor/mcp.json"),
+        Provider::Windsurf => return Ok(Some(home.join(".codeium/windsurf/mcp_config.json"))),
+        Provider::OpenCode | Provider::Copilot | Provider::Zed | Provider::Custom(_) => {
+            return Ok(None)
+        }
+    };
+    match scope {
+        "user" => Ok(Some(home.join(user))),
+        "workspace" => Ok(Some(PathBuf::from(workspace))),
+        other => Err(format!(
+            "invalid scope {other:?} for MCP servers: use user or workspace"
+        )),
+    }
+}
+
+// ─── Deploy ───
+
+/// Replace `module_name`'s previously deployed servers in `config_path` with
+/// `servers`.
+pub fn deploy_servers(
+    config_path: &Path,
+    provider: &Provider,
+    module_name: &str,
+    servers: &Servers,
+    dry_run: bool,
+) -> Result<McpReport, String> {
+    if *provider == Provider::Codex {
+        deploy_toml(config_path, module_name, servers, dry_run)
+    } else {
+        deploy_json(config_path, module_name, servers, dry_run)
+    }
+}
+
+/// Remove every server `module_name` deployed to `config_path`.
+pub fn clean_servers(
+    config_path: &Path,
+    provider: &Provider,
+    module_name: &str,
+    dry_run: bool,
+) -> Result<McpReport, String> {
+    deploy_servers(config_path, provider, module_name, &Servers::new(), dry_run)
+}
+
+fn deploy_json(
+    config_path: &Path,
+    module_name: &str,
+    servers: &Servers,
+    dry_run: bool,
+) -> Result<McpReport, String> {
+    let manifest_path = manifest_path(config_path);
+    let mut manifest = read_json_object(&manifest_path)?;
+    let previous = match manifest.shift_remove(module_name) {
+        Some(Value::Object(previous)) => previous,
+        _ => Servers::new(),
+    };
+    if previous.is_empty() && servers.is_empty() {
+        return Ok(McpReport::default());
+    }
+
+    let mut config = read_json_object(config_path)?;
+    let mut current = match config.shift_remove(SERVERS_KEY) {
+        Some(Value::Object(current)) => current,
+        _ => Servers::new(),
+    };
+
+    // A server the user edited since deployment is no longer ours to remove.
+    let mut report = McpReport::default();
+    for (name, value) in &previous {
+        if current.get(name) == Some(value) {
+            current.shift_remove(name);
+            if !servers.contains_key(name) {
+                report.removed.push(name.clone());
+            }
+        }
+    }
+    let mut owned = Servers::new();
+    for (name, value) in servers {
+        if current.contains_key(name) {
+            report.skipped.push(name.clone());
+            continue;
+        }
+        current.insert(name.clone(), value.clone());
+        owned.insert(name.clone(), value.clone());
+        report.added.push(name.clone());
+    }
+
+    if !current.is_empty() {
+        config.insert(SERVERS_KEY.to_string(), Value::Object(current));
+    }
+    if !owned.is_empty() {
+        manifest.insert(module_name.to_string(), Value::Object(owned));
+    }
+
+    if !dry_run {
+        write_json_object(config_path, &config)?;
+        if manifest.is_empty() {
+            let _ = std::fs::remove_file(&manifest_path);
+        } else {
+            write_json_object(&manifest_path, &manifest)?;
+        }
+    }
+    Ok(report)
+}
+
+pub(crate) fn manifest_path(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(MANIFEST_FILE)
+}
+
+// ─── Codex ───
+
+fn block_markers(module_name: &str) -> (String, String) {
+    (
+        format!("# BEGIN forge mcp {module_name}"),
+        format!("# END forge mcp {module_name}"),
+    )
+}
+
+/// Server names declared inside a managed block.
+fn block_servers(content: &str, begin: &str, end: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut inside = false;
+    for line in content.lines() {
+        if line == begin {
+            inside = true;
+        } else if line == end {
+            inside = false;
+        } else if inside {
+            if let Some(name) = table_name(line) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+fn table_name(line: &str) -> Option<String> {
+    toml_fmt::parse_key(line.strip_prefix("[mcp_servers.")?.strip_suffix(']')?)
+}
+
+fn deploy_toml(
+    config_path: &Path,
+    module_name: &str,
+    servers: &Servers,
+    dry_run: bool,
+) -> Result<McpReport, String> {
+    let existing = match std::fs::read_to_string(config_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("failed to read {}: {e}", config_path.display())),
+    };
+    let (begin, end) = block_markers(module_name);
+    let previous = block_servers(&existing, &begin, &end);
+    let stripped = strip_managed_block(&existing, &begin, &end);
+    let taken: Vec<String> = stripped.lines().filter_map(table_name).collect();
+
+    let mut report = McpReport::default();
+    let mut block = String::new();
+    for (name, server) in servers {
+        if taken.contains(name) {
+            report.skipped.push(name.clone());
+            continue;
+        }
+        block.push('\n');
+        block.push_str(&format_toml_server(name, server)?);
+        report.added.push(name.clone());
+    }
+    report.removed = previous
+        .into_iter()
+        .filter(|name| !report.added.contains(name))
+        .collect();
+
+    let mut rendered = stripped;
+    if !block.is_empty() {
+        if !rendered.is_empty() {
+            rendered.push('\n');
+        }
+        let _ = writeln!(rendered, "{begin}");
+        let _ = writeln!(rendered, "# Generated by install-mcp ({module_name})");
+        rendered.push_str(&block);
+        let _ = writeln!(rendered, "{end}");
+    }
+
+    if !dry_run && rendered != existing {
+        if let Some(parent) = config_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+        }
+        std::fs::write(config_path, rendered)
+            .map_err(|e| format!("failed to write {}: {e}", config_path.display()))?;
+    }
+    Ok(report)
+}
+
+/// Render a server definition as a `[mcp_servers.<name>]` table. Nested
+/// objects such as `env` become inline tables.
+pub(crate) fn format_toml_server(name: &str, server: &Value) -> Result<String, String> {
+    let mut out = String::new();
+    let _ = writeln!(out, "[mcp_servers.{}]", toml_fmt::key(name));
+    for (key, value) in server.as_object().into_iter().flatten() {
+        if value.is_null() {
+            continue;
+        }
+        let value =
+            toml_fmt::inline(value).map_err(|e| format!("MCP server {name:?}, {key}: {e}"))?;
+        let _ = writeln!(out, "{} = {value}", toml_fmt::key(key));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests;
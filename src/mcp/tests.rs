@@ -0,0 +1,240 @@
+use super::*;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+fn servers(value: &Value) -> Servers {
+    value.as_object().unwrap().clone()
+}
+
+fn read(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+// ─── parse_servers ───
+
+#[test]
+fn parse_bare_server_named_after_file() {
+    let parsed = parse_servers(
+        r#"{"command": "${CLAUDE_PLUGIN_ROOT}/bin/srv", "args": ["--stdio"]}"#,
+        "search",
+        "/mods/forge-demo",
+    )
+    .unwrap();
+    assert_eq!(parsed["search"]["command"], "/mods/forge-demo/bin/srv");
+}
+
+#[test]
+fn parse_mcp_json_form() {
+    let parsed = parse_servers(
+        r#"{"mcpServers": {"a": {"command": "x"}, "b": {"url": "https://b"}}}"#,
+        "ignored",
+        "/r",
+    )
+    .unwrap();
+    assert_eq!(parsed.keys().collect::<Vec<_>>(), ["a", "b"]);
+}
+
+#[test]
+fn parse_rejects_server_without_command() {
+    let err = parse_servers(r#"{"args": []}"#, "srv", "/r").unwrap_err();
+    assert!(err.contains("command or url"));
+}
+
+#[test]
+fn parse_rejects_bad_name() {
+    assert!(parse_servers(r#"{"command": "x"}"#, "a b", "/r").is_err());
+}
+
+#[test]
+fn load_rejects_duplicate_names() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join("mcp")).unwrap();
+    fs::write(
+        dir.path().join("mcp/a.json"),
+        r#"{"mcpServers": {"s": {"command": "x"}}}"#,
+    )
+    .unwrap();
+    fs::write(dir.path().join("mcp/s.json"), r#"{"command": "y"}"#).unwrap();
+    assert!(load_module_servers(dir.path())
+        .unwrap_err()
+        .contains("defined twice"));
+}
+
+#[test]
+fn load_without_dir_is_empty() {
+    let dir = TempDir::new().unwrap();
+    assert!(load_module_servers(dir.path()).unwrap().is_empty());
+}
+
+// ─── mcp_config_path ───
+
+#[test]
+fn config_paths() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        mcp_config_path(&Provider::Claude, "workspace", home).unwrap(),
+        Some(PathBuf::from(".mcp.json"))
+    );
+    assert_eq!(
+        mcp_config_path(&Provider::Claude, "user", home).unwrap(),
+        Some(PathBuf::from("/home/u/.claude.json"))
+    );
+    assert_eq!(
+        mcp_config_path(&Provider::Codex, "workspace", home).unwrap(),
+        Some(PathBuf::from("/home/u/.codex/config.toml"))
+    );
+    assert_eq!(
+        mcp_config_path(&Provider::OpenCode, "user", home).unwrap(),
+        None
+    );
+    assert!(mcp_config_path(&Provider::Gemini, "project", home).is_err());
+}
+
+// ─── JSON targets ───
+
+#[test]
+fn json_deploy_preserves_other_settings_and_cleans() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(".mcp.json");
+    fs::write(
+        &path,
+        r#"{"theme": "dark", "mcpServers": {"mine": {"command": "m"}}}"#,
+    )
+    .unwrap();
+    let ours = servers(&json!({"search": {"command": "s"}}));
+
+    let report = deploy_servers(&path, &Provider::Claude, "forge-demo", &ours, false).unwrap();
+    assert_eq!(report.added, ["search"]);
+    let doc = read(&path);
+    assert_eq!(doc["theme"], "dark");
+    assert_eq!(doc["mcpServers"]["mine"]["command"], "m");
+    assert_eq!(doc["mcpServers"]["search"]["command"], "s");
+    assert!(dir.path().join(MANIFEST_FILE).is_file());
+
+    let report = clean_servers(&path, &Provider::Claude, "forge-demo", false).unwrap();
+    assert_eq!(report.removed, ["search"]);
+    let doc = read(&path);
+    assert!(doc["mcpServers"].get("search").is_none());
+    assert_eq!(doc["mcpServers"]["mine"]["command"], "m");
+    assert!(!dir.path().join(MANIFEST_FILE).exists());
+}
+
+#[test]
+fn json_redeploy_drops_removed_servers() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("settings.json");
+    let both = servers(&json!({"a": {"command": "a"}, "b": {"command": "b"}}));
+    deploy_servers(&path, &Provider::Gemini, "m", &both, false).unwrap();
+
+    let one = servers(&json!({"a": {"command": "a2"}}));
+    let report = deploy_servers(&path, &Provider::Gemini, "m", &one, false).unwrap();
+    assert_eq!(report.removed, ["b"]);
+    assert_eq!(report.added, ["a"]);
+    assert_eq!(read(&path)["mcpServers"], json!({"a": {"command": "a2"}}));
+}
+
+#[test]
+fn json_skips_user_owned_name() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(".mcp.json");
+    fs::write(&path, r#"{"mcpServers": {"search": {"command": "mine"}}}"#).unwrap();
+    let ours = servers(&json!({"search": {"command": "s"}}));
+
+    let report = deploy_servers(&path, &Provider::Claude, "m", &ours, false).unwrap();
+    assert_eq!(report.skipped, ["search"]);
+    assert_eq!(read(&path)["mcpServers"]["search"]["command"], "mine");
+    assert!(clean_servers(&path, &Provider::Claude, "m", false)
+        .unwrap()
+        .removed
+        .is_empty());
+}
+
+#[test]
+fn json_dry_run_writes_nothing() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(".mcp.json");
+    let ours = servers(&json!({"s": {"command": "s"}}));
+    deploy_servers(&path, &Provider::Claude, "m", &ours, true).unwrap();
+    assert!(!path.exists());
+}
+
+// ─── Codex ───
+
+#[test]
+fn toml_server_rendering() {
+    let out = format_toml_server(
+        "search",
+        &json!({"command": "npx", "args": ["-y", "srv"], "env": {"API_KEY": "k"}, "timeout": 30}),
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        "[mcp_servers.search]\ncommand = \"npx\"\nargs = [\"-y\", \"srv\"]\n\
+         env = { API_KEY = \"k\" }\ntimeout = 30\n"
+    );
+}
+
+#[test]
+fn toml_server_names_and_values_are_escaped() {
+    let out = format_toml_server(
+        "my.server]\n[evil",
+        &json!({"command": "a\nb", "env": {"X": "\u{1b}"}}),
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        "[mcp_servers.\"my.server]\\n[evil\"]\ncommand = \"a\\nb\"\nenv = { X = \"\\u001B\" }\n"
+    );
+    assert!(format_toml_server("s", &json!({"args": [null]})).is_err());
+}
+
+#[test]
+fn toml_quoted_names_are_tracked() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    let ours = servers(&json!({"my.server": {"command": "s"}}));
+    deploy_servers(&path, &Provider::Codex, "m", &ours, false).unwrap();
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("[mcp_servers.\"my.server\"]\n"));
+
+    let report = deploy_servers(&path, &Provider::Codex, "m", &servers(&json!({})), false).unwrap();
+    assert_eq!(report.removed, ["my.server"]);
+}
+
+#[test]
+fn toml_block_per_module() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "model = \"o3\"\n").unwrap();
+    let a = servers(&json!({"a": {"command": "a"}}));
+    let b = servers(&json!({"b": {"command": "b"}}));
+
+    deploy_servers(&path, &Provider::Codex, "mod-a", &a, false).unwrap();
+    deploy_servers(&path, &Provider::Codex, "mod-b", &b, false).unwrap();
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.starts_with("model = \"o3\"\n"));
+    assert!(content.contains("# BEGIN forge mcp mod-a\n"));
+    assert!(content.contains("[mcp_servers.b]\n"));
+
+    let report = clean_servers(&path, &Provider::Codex, "mod-a", false).unwrap();
+    assert_eq!(report.removed, ["a"]);
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(!content.contains("mod-a"));
+    assert!(content.contains("[mcp_servers.b]\n"));
+}
+
+#[test]
+fn toml_skips_table_defined_outside_block() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "[mcp_servers.a]\ncommand = \"mine\"\n").unwrap();
+    let a = servers(&json!({"a": {"command": "a"}}));
+
+    let report = deploy_servers(&path, &Provider::Codex, "m", &a, false).unwrap();
+    assert_eq!(report.skipped, ["a"]);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[mcp_servers.a]\ncommand = \"mine\"\n"
+    );
+}
@@ -0,0 +1,215 @@
+//! Managed memory-file blocks: a module-owned section in `CLAUDE.md`,
+//! `GEMINI.md`, or `AGENTS.md` holding the module's usage guidance
+//! (`memory.md`) and a roster of its agents.
+//!
+//! Each module gets its own block between HTML comment markers, so updates
+//! and cleans rewrite that block and leave the rest of the file, including
+//! other modules' blocks, untouched — the same approach as the Codex
+//! `config.toml` block.
+
+use crate::deploy::provider::Provider;
+use crate::deploy::{self, strip_managed_block};
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+pub const GUIDANCE_FILE: &str = "memory.md";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MemoryResult {
+    Installed,
+    Updated,
+    Removed,
+    Unchanged,
+}
+
+// ─── Targets ───
+
+/// Memory file a provider reads for a scope.
+pub fn memory_file(provider: &Provider, scope: &str, home: &Path) -> Result<PathBuf, String> {
+    let (user, workspace) = match provider {
+        Provider::Claude => (".claude/CLAUDE.md", "CLAUDE.md"),
+        Provider::Gemini => (".gemini/GEMINI.md", "GEMINI.md"),
+        Provider::Codex => (".codex/AGENTS.md", "AGENTS.md"),
+        Provider::OpenCode => (".config/opencode/AGENTS.md", "AGENTS.md"),
+        Provider::Cursor if scope == "user" => {
+            return Err("Cursor reads no user-level memory file: use --scope workspace".into())
+        }
+        Provider::Cursor => ("", "AGENTS.md"),
+        Provider::Windsurf => (".codeium/windsurf/memories/global_rules.md", "AGENTS.md"),
+        Provider::Copilot if scope == "user" => {
+            return Err("Copilot reads no user-level memory file: use --scope workspace".into())
+        }
+        Provider::Copilot => ("", ".github/copilot-instructions.md"),
+        Provider::Zed if scope == "user" => {
+            return Err("Zed reads no user-level memory file: use --scope workspace".into())
+        }
+        Provider::Zed => ("", ".rules"),
+        Provider::Custom(custom) => return Err(format!("{} reads no memory file", custom.name)),
+    };
+    match scope {
+        "user" => Ok(home.join(user)),
+        "workspace" => Ok(PathBuf::from(workspace)),
+        other => Err(format!(
+            "invalid scope {other:?} for memory files: use user or workspace"
+        )),
+    }
+}
+
+// ─── Block ───
+
+fn markers(module_name: &str) -> (String, String) {
+    (
+        format!("<!-- BEGIN forge {module_name} -->"),
+        format!("<!-- END forge {module_name} -->"),
+    )
+}
+
+/// Block contents for a module: `memory.md` (without frontmatter) followed by
+/// an agent roster named as `provider` shows them, rendered through the
+/// `memory-block.md` template. `None` when the module has neither.
+pub fn build_block(
+    module_root: &Path,
+    module_name: &str,
+    provider: &Provider,
+    config: &SidecarConfig,
+) -> Result<Option<String>, String> {
+    let guidance_path = module_root.join(GUIDANCE_FILE);
+    let guidance = if guidance_path.is_file() {
+        let content = std::fs::read_to_string(&guidance_path)
+            .map_err(|e| format!("failed to read {}: {e}", guidance_path.display()))?;
+        parse::fm_body(&content).trim().to_string()
+    } else {
+        String::new()
+    };
+
+    let roster = roster(&module_root.join("agents"), provider, config)?;
+    if guidance.is_empty() && roster.is_empty() {
+        return Ok(None);
+    }
+    let agents: Vec<_> = roster
+        .into_iter()
+        .map(|(name, description)| json!({"name": name, "description": description}))
+        .collect();
+    let context = json!({"module": module_name, "guidance": guidance, "agents": agents});
+    let block = config.templates().render("memory-block.md", &context)?;
+    Ok((!block.trim().is_empty()).then_some(block))
+}
+
+fn roster(
+    agents_dir: &Path,
+    provider: &Provider,
+    config: &SidecarConfig,
+) -> Result<Vec<(String, String)>, String> {
+    if !agents_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(agents_dir)
+        .map_err(|e| format!("failed to read {}: {e}", agents_dir.display()))?;
+    let mut files: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    files.sort_by_key(std::fs::DirEntry::file_name);
+
+    let mut roster = Vec::new();
+    for entry in files {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if let Some(meta) = deploy::extract_agent_meta(&content, &filename, provider, config, "") {
+            roster.push((meta.display_name, meta.description));
+        }
+    }
+    Ok(roster)
+}
+
+/// Render `content` with `module_name`'s block replaced by `block`, or
+/// removed when `block` is `None`. The block goes where the old one was, or
+/// at the end of the file.
+pub fn render(content: &str, module_name: &str, block: Option<&str>) -> String {
+    let (begin, end) = markers(module_name);
+    let position = content.lines().position(|l| l == begin);
+    let stripped = strip_managed_block(content, &begin, &end);
+    let Some(block) = block else {
+        return stripped;
+    };
+
+    let mut section = format!("{begin}\n{block}");
+    if !block.ends_with('\n') {
+        section.push('\n');
+    }
+    let _ = writeln!(section, "{end}");
+
+    let lines: Vec<&str> = stripped.lines().collect();
+    let at = position.unwrap_or(lines.len()).min(lines.len());
+    let mut out = String::new();
+    for line in &lines[..at] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if at > 0 && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+    out.push_str(&section);
+    if at < lines.len() {
+        if !lines[at].is_empty() {
+            out.push('\n');
+        }
+        for line in &lines[at..] {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Write (or remove) `module_name`'s block in the memory file at `path`.
+/// Rewriting identical content is a no-op, so repeated installs leave the
+/// file untouched.
+pub fn write_block(
+    path: &Path,
+    module_name: &str,
+    block: Option<&str>,
+    dry_run: bool,
+) -> Result<MemoryResult, String> {
+    let existing = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("failed to read {}: {e}", path.display())),
+    };
+    let (begin, _) = markers(module_name);
+    let had_block = existing.lines().any(|l| l == begin);
+    if !had_block && block.is_none() {
+        return Ok(MemoryResult::Unchanged);
+    }
+
+    let rendered = render(&existing, module_name, block);
+    let result = match (had_block, block.is_some()) {
+        _ if rendered == existing => return Ok(MemoryResult::Unchanged),
+        (_, false) => MemoryResult::Removed,
+        (true, true) => MemoryResult::Updated,
+        (false, true) => MemoryResult::Installed,
+    };
+
+    if !dry_run {
+        if rendered.trim().is_empty() {
+            std::fs::remove_file(path)
+                .map_err(|e| format!("failed to remove {}: {e}", path.display()))?;
+        } else {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+            }
+            std::fs::write(path, rendered)
+                .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests;
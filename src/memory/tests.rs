@@ -0,0 +1,181 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+const BLOCK: &str = "Use /review before merging.\n";
+
+#[test]
+fn memory_files_per_provider() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        memory_file(&Provider::Claude, "workspace", home).unwrap(),
+        PathBuf::from("CLAUDE.md")
+    );
+    assert_eq!(
+        memory_file(&Provider::Gemini, "user", home).unwrap(),
+        PathBuf::from("/home/u/.gemini/GEMINI.md")
+    );
+    assert_eq!(
+        memory_file(&Provider::Codex, "workspace", home).unwrap(),
+        PathBuf::from("AGENTS.md")
+    );
+    assert!(memory_file(&Provider::Claude, "all", home).is_err());
+}
+
+#[test]
+fn zed_memory_is_the_workspace_rules_file() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        memory_file(&Provider::Zed, "workspace", home).unwrap(),
+        PathBuf::from(".rules")
+    );
+    assert!(memory_file(&Provider::Zed, "user", home).is_err());
+}
+
+#[test]
+fn copilot_memory_is_the_workspace_instructions_file() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        memory_file(&Provider::Copilot, "workspace", home).unwrap(),
+        PathBuf::from(".github/copilot-instructions.md")
+    );
+    assert!(memory_file(&Provider::Copilot, "user", home).is_err());
+}
+
+// ─── render ───
+
+#[test]
+fn render_appends_after_existing_content() {
+    let out = render("# Project\n\nNotes.\n", "forge-demo", Some(BLOCK));
+    assert_eq!(
+        out,
+        "# Project\n\nNotes.\n\n<!-- BEGIN forge forge-demo -->\n\
+         Use /review before merging.\n<!-- END forge forge-demo -->\n"
+    );
+}
+
+#[test]
+fn render_replaces_in_place() {
+    let content = "# Top\n\n<!-- BEGIN forge m -->\nold\n<!-- END forge m -->\n\n## Tail\n";
+    let out = render(content, "m", Some("new\n"));
+    assert_eq!(
+        out,
+        "# Top\n\n<!-- BEGIN forge m -->\nnew\n<!-- END forge m -->\n\n## Tail\n"
+    );
+}
+
+#[test]
+fn render_keeps_other_modules() {
+    let content = render("", "a", Some("A\n"));
+    let content = render(&content, "b", Some("B\n"));
+    let out = render(&content, "a", None);
+    assert!(!out.contains("forge a"));
+    assert!(out.contains("<!-- BEGIN forge b -->\nB\n"));
+}
+
+// ─── write_block ───
+
+#[test]
+fn write_is_idempotent_and_cleans() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("CLAUDE.md");
+    fs::write(&path, "# Project\n").unwrap();
+
+    assert_eq!(
+        write_block(&path, "m", Some(BLOCK), false).unwrap(),
+        MemoryResult::Installed
+    );
+    assert_eq!(
+        write_block(&path, "m", Some(BLOCK), false).unwrap(),
+        MemoryResult::Unchanged
+    );
+    assert_eq!(
+        write_block(&path, "m", Some("Other.\n"), false).unwrap(),
+        MemoryResult::Updated
+    );
+    assert_eq!(
+        write_block(&path, "m", None, false).unwrap(),
+        MemoryResult::Removed
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "# Project\n");
+}
+
+#[test]
+fn clean_deletes_file_it_created() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("AGENTS.md");
+    write_block(&path, "m", Some(BLOCK), false).unwrap();
+    assert!(path.is_file());
+    write_block(&path, "m", None, false).unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn dry_run_leaves_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("CLAUDE.md");
+    assert_eq!(
+        write_block(&path, "m", Some(BLOCK), true).unwrap(),
+        MemoryResult::Installed
+    );
+    assert!(!path.exists());
+}
+
+// ─── build_block ───
+
+#[test]
+fn block_has_guidance_and_roster() {
+    let root = TempDir::new().unwrap();
+    fs::create_dir_all(root.path().join("agents")).unwrap();
+    fs::write(
+        root.path().join("memory.md"),
+        "---\ntitle: x\n---\nAsk the council for reviews.\n",
+    )
+    .unwrap();
+    fs::write(
+        root.path().join("agents/Reviewer.md"),
+        "---\nname: Reviewer\ndescription: Reviews code\n---\nBody\n",
+    )
+    .unwrap();
+    let config = SidecarConfig::load(root.path());
+
+    let block = build_block(root.path(), "forge-demo", &Provider::Claude, &config)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        block,
+        "Ask the council for reviews.\n\n## forge-demo agents\n\n- **Reviewer** — Reviews code\n"
+    );
+}
+
+#[test]
+fn empty_module_has_no_block() {
+    let root = TempDir::new().unwrap();
+    let config = SidecarConfig::load(root.path());
+    assert!(build_block(root.path(), "m", &Provider::Claude, &config)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn module_template_shapes_block() {
+    let root = TempDir::new().unwrap();
+    fs::create_dir_all(root.path().join("agents")).unwrap();
+    fs::create_dir_all(root.path().join("templates")).unwrap();
+    fs::write(
+        root.path().join("agents/Reviewer.md"),
+        "---\nname: Reviewer\ndescription: Reviews code\n---\nBody\n",
+    )
+    .unwrap();
+    fs::write(
+        root.path().join("templates/memory-block.md.j2"),
+        "{% for agent in agents %}\n{{ agent.name }}: {{ agent.description }}\n{% endfor %}\n",
+    )
+    .unwrap();
+    let config = SidecarConfig::load(root.path());
+
+    let block = build_block(root.path(), "forge-demo", &Provider::Claude, &config)
+        .unwrap()
+        .unwrap();
+    assert_eq!(block, "Reviewer: Reviews code\n");
+}
@@ -0,0 +1,231 @@
+//! Line-based three-way merge, used to carry user edits of deployed files
+//! across redeploys, and unified diffs of what a redeploy would change.
+//!
+//! `base` is what forge last deployed, `ours` the file as the user left it,
+//! `theirs` the new render. Regions changed on one side only take that
+//! side; regions changed differently on both sides become a conflict:
+//!
+//! ```text
+//! <<<<<<< deployed
+//! (user's lines)
+//! =======
+//! (new source lines)
+//! >>>>>>> source
+//! ```
+
+use std::fmt::Write;
+
+pub const CONFLICT_START: &str = "<<<<<<< deployed";
+pub const CONFLICT_SEP: &str = "=======";
+pub const CONFLICT_END: &str = ">>>>>>> source";
+
+#[derive(Debug, PartialEq)]
+pub struct Merge {
+    pub text: String,
+    /// Number of conflict regions in `text`.
+    pub conflicts: usize,
+}
+
+/// For each line of `old`, the index of the line of `new` it is matched
+/// with in a longest common subsequence.
+fn lcs_matches(old: &[&str], new: &[&str]) -> Vec<Option<usize>> {
+    // lengths[x][y]: LCS length of old[x..] and new[y..].
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for x in (0..old.len()).rev() {
+        for y in (0..new.len()).rev() {
+            lengths[x][y] = if old[x] == new[y] {
+                lengths[x + 1][y + 1] + 1
+            } else {
+                lengths[x + 1][y].max(lengths[x][y + 1])
+            };
+        }
+    }
+    let mut matches = vec![None; old.len()];
+    let (mut x, mut y) = (0, 0);
+    while x < old.len() && y < new.len() {
+        if old[x] == new[y] {
+            matches[x] = Some(y);
+            x += 1;
+            y += 1;
+        } else if lengths[x + 1][y] >= lengths[x][y + 1] {
+            x += 1;
+        } else {
+            y += 1;
+        }
+    }
+    matches
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for line in lines {
+        out.push_str(line);
+    }
+    if !out.is_empty() && !out.ends_with('\n') && !lines.is_empty() {
+        out.push('\n');
+    }
+}
+
+/// Resolve one unstable region, returning whether it conflicted.
+fn resolve(out: &mut String, base: &[&str], ours: &[&str], theirs: &[&str]) -> bool {
+    if ours == theirs || theirs == base {
+        out.extend(ours.iter().copied());
+        false
+    } else if ours == base {
+        out.extend(theirs.iter().copied());
+        false
+    } else {
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(CONFLICT_START);
+        out.push('\n');
+        push_lines(out, ours);
+        out.push_str(CONFLICT_SEP);
+        out.push('\n');
+        push_lines(out, theirs);
+        out.push_str(CONFLICT_END);
+        out.push('\n');
+        true
+    }
+}
+
+/// Merge the changes from `base` to `ours` and from `base` to `theirs`.
+pub fn merge3(base: &str, ours: &str, theirs: &str) -> Merge {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let to_ours = lcs_matches(&base, &ours);
+    let to_theirs = lcs_matches(&base, &theirs);
+
+    let mut text = String::new();
+    let mut conflicts = 0;
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    loop {
+        // Next base line kept unchanged on both sides.
+        let stable = (b..base.len()).find_map(|k| Some((k, to_ours[k]?, to_theirs[k]?)));
+        let Some((k, ko, kt)) = stable else {
+            if resolve(&mut text, &base[b..], &ours[o..], &theirs[t..]) {
+                conflicts += 1;
+            }
+            break;
+        };
+        if (k, ko, kt) != (b, o, t) && resolve(&mut text, &base[b..k], &ours[o..ko], &theirs[t..kt])
+        {
+            conflicts += 1;
+        }
+        text.push_str(base[k]);
+        (b, o, t) = (k + 1, ko + 1, kt + 1);
+    }
+
+    Merge { text, conflicts }
+}
+
+/// Lines of context around each change in [`unified_diff`].
+const CONTEXT: usize = 3;
+
+/// One step from `old` to `new`, with the line indexes it reads.
+#[derive(Clone, Copy)]
+enum Edit {
+    Keep(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// The shortest edit script from `old` to `new` (deletions before
+/// insertions within a change).
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    let matched = lcs_matches(old, new).into_iter().enumerate();
+    for (k, kn) in matched
+        .filter_map(|(k, m)| Some((k, m?)))
+        .chain([(old.len(), new.len())])
+    {
+        edits.extend((x..k).map(Edit::Delete));
+        edits.extend((y..kn).map(Edit::Insert));
+        if k < old.len() {
+            edits.push(Edit::Keep(k));
+        }
+        (x, y) = (k + 1, kn + 1);
+    }
+    edits
+}
+
+fn push_diff_line(out: &mut String, prefix: char, line: &str) {
+    out.push(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// A unified diff from `old` to `new` with three lines of context, headed
+/// `--- old_label` and `+++ new_label`; empty when they are equal.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+    let edits = edits(&old, &new);
+
+    // Line positions in `old` and `new` before each edit.
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut x, mut y) = (0, 0);
+    for edit in &edits {
+        positions.push((x, y));
+        match edit {
+            Edit::Keep(_) => (x, y) = (x + 1, y + 1),
+            Edit::Delete(_) => x += 1,
+            Edit::Insert(_) => y += 1,
+        }
+    }
+    positions.push((x, y));
+
+    let changed: Vec<usize> = (0..edits.len())
+        .filter(|&i| !matches!(edits[i], Edit::Keep(_)))
+        .collect();
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + 1 + CONTEXT).min(edits.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- {old_label}\n+++ {new_label}\n");
+    for (start, end) in hunks {
+        let (old_start, new_start) = positions[start];
+        let (old_end, new_end) = positions[end];
+        let range = |start: usize, len: usize| {
+            let first = if len == 0 { start } else { start + 1 };
+            format!("{first},{len}")
+        };
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            range(old_start, old_end - old_start),
+            range(new_start, new_end - new_start)
+        );
+        for edit in &edits[start..end] {
+            match *edit {
+                Edit::Keep(k) => push_diff_line(&mut out, ' ', old[k]),
+                Edit::Delete(k) => push_diff_line(&mut out, '-', old[k]),
+                Edit::Insert(k) => push_diff_line(&mut out, '+', new[k]),
+            }
+        }
+    }
+    out
+}
+
+/// Whether `content` still contains conflict markers from [`merge3`].
+pub fn has_conflict_markers(content: &str) -> bool {
+    content
+        .lines()
+        .any(|l| l == CONFLICT_START || l == CONFLICT_END)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,107 @@
+use super::*;
+
+#[test]
+fn unchanged_sides_keep_base() {
+    let base = "a\nb\nc\n";
+    assert_eq!(
+        merge3(base, base, base),
+        Merge {
+            text: base.into(),
+            conflicts: 0
+        }
+    );
+}
+
+#[test]
+fn one_sided_changes_are_taken() {
+    let base = "a\nb\nc\n";
+    assert_eq!(merge3(base, "a\nB\nc\n", base).text, "a\nB\nc\n");
+    assert_eq!(merge3(base, base, "a\nb\nc\nd\n").text, "a\nb\nc\nd\n");
+}
+
+#[test]
+fn disjoint_changes_combine() {
+    let base = "title\n\none\ntwo\nthree\n\nend\n";
+    let ours = "title\n\none (mine)\ntwo\nthree\n\nend\n";
+    let theirs = "title\n\none\ntwo\nthree\n\nend\nnew section\n";
+    let merged = merge3(base, ours, theirs);
+    assert_eq!(merged.conflicts, 0);
+    assert_eq!(
+        merged.text,
+        "title\n\none (mine)\ntwo\nthree\n\nend\nnew section\n"
+    );
+}
+
+#[test]
+fn same_change_on_both_sides_is_not_a_conflict() {
+    let merged = merge3("a\nb\n", "a\nx\n", "a\nx\n");
+    assert_eq!(merged.conflicts, 0);
+    assert_eq!(merged.text, "a\nx\n");
+}
+
+#[test]
+fn overlapping_changes_conflict() {
+    let merged = merge3("a\nb\nc\n", "a\nmine\nc\n", "a\ntheirs\nc\n");
+    assert_eq!(merged.conflicts, 1);
+    assert_eq!(
+        merged.text,
+        "a\n<<<<<<< deployed\nmine\n=======\ntheirs\n>>>>>>> source\nc\n"
+    );
+    assert!(has_conflict_markers(&merged.text));
+}
+
+#[test]
+fn conflict_without_trailing_newline() {
+    let merged = merge3("a\nb", "a\nmine", "a\ntheirs");
+    assert_eq!(
+        merged.text,
+        "a\n<<<<<<< deployed\nmine\n=======\ntheirs\n>>>>>>> source\n"
+    );
+}
+
+// ─── unified_diff ───
+
+#[test]
+fn diff_of_equal_text_is_empty() {
+    assert_eq!(unified_diff("a\nb\n", "a\nb\n", "x", "x"), "");
+}
+
+#[test]
+fn diff_keeps_three_lines_of_context() {
+    let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+    let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+    assert_eq!(
+        unified_diff(old, new, "a", "b"),
+        "--- a\n+++ b\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+    );
+}
+
+#[test]
+fn diff_splits_distant_changes_into_hunks() {
+    let lines = |skip: usize| {
+        let mut text = String::new();
+        for n in (1..=20).filter(|&n| n != skip) {
+            text.push_str(&if n == 2 && skip != 0 {
+                "two".to_string()
+            } else {
+                n.to_string()
+            });
+            text.push('\n');
+        }
+        text
+    };
+    let (old, new) = (lines(0), lines(19));
+    assert_eq!(
+        unified_diff(&old, &new, "a", "b"),
+        "--- a\n+++ b\n@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+         @@ -16,5 +16,4 @@\n 16\n 17\n 18\n-19\n 20\n"
+    );
+}
+
+#[test]
+fn diff_from_nothing_and_without_trailing_newline() {
+    assert_eq!(
+        unified_diff("", "a\nb", "/dev/null", "f"),
+        "--- /dev/null\n+++ f\n@@ -0,0 +1,2 @@\n+a\n+b\n\\ No newline at end of file\n"
+    );
+}
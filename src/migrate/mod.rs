@@ -0,0 +1,245 @@
+//! Migration of deployments made by the old shell installers: agent and
+//! command files marked with a `# synced-from: <file>` first body line, and
+//! flat `.manifest` files listing names without their module.
+//!
+//! Legacy markers name only the source file, so the owning module is found
+//! in module checkouts ([`SourceIndex`]) or, for manifest entries, in the
+//! `source:` field of the deployed file. Anything that cannot be attributed
+//! to exactly one module is reported and left untouched.
+
+use crate::manifest::{self, MANIFEST_FILE};
+use crate::parse;
+use crate::vfs::RealFs;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const LEGACY_MARKER: &str = "# synced-from:";
+
+/// Which modules ship each source file, keyed by `(kind, name)` where kind
+/// is `agents`, `commands`, or `skills` and name is a file (or skill
+/// directory) name.
+#[derive(Debug, Default)]
+pub struct SourceIndex {
+    files: BTreeMap<(String, String), BTreeSet<String>>,
+}
+
+impl SourceIndex {
+    /// Index the `agents/`, `commands/`, and `skills/` sources of each
+    /// module checkout.
+    pub fn from_checkouts(roots: &[PathBuf]) -> Result<Self, String> {
+        let mut index = Self::default();
+        for root in roots {
+            let path = root.join("module.yaml");
+            let module = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|c| parse::module_name(&c))
+                .ok_or_else(|| format!("{} has no module name", path.display()))?;
+            for kind in ["agents", "commands", "skills"] {
+                let Ok(entries) = std::fs::read_dir(root.join(kind)) else {
+                    continue;
+                };
+                for entry in entries.filter_map(Result::ok) {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    index
+                        .files
+                        .entry((kind.to_string(), name))
+                        .or_default()
+                        .insert(module.clone());
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// The one module shipping `kind/name`, or why there is none.
+    pub fn attribute(&self, kind: &str, name: &str) -> Result<&str, String> {
+        let modules = self.files.get(&(kind.to_string(), name.to_string()));
+        match modules.map(|m| m.iter().collect::<Vec<_>>()).as_deref() {
+            Some([module]) => Ok(module.as_str()),
+            Some(many) if many.len() > 1 => Err(format!(
+                "{kind}/{name} is shipped by several modules: {}",
+                many.iter()
+                    .map(|m| m.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            _ => Err(format!("no given module checkout ships {kind}/{name}")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Finding {
+    /// A `# synced-from:` file rewritten with a `source:` field.
+    Rewritten { path: PathBuf, source: String },
+    /// A flat manifest converted to (or a manifest updated with) per-module
+    /// entries.
+    Manifest {
+        path: PathBuf,
+        modules: BTreeMap<String, Vec<String>>,
+    },
+    /// A file or manifest entry that could not be attributed to a module.
+    Unattributed { path: PathBuf, reason: String },
+}
+
+/// The source file named by a legacy `# synced-from:` marker.
+pub(crate) fn legacy_source(content: &str) -> Option<String> {
+    let first = parse::fm_body(content).lines().next()?;
+    let source = first.strip_prefix(LEGACY_MARKER)?.trim();
+    (!source.is_empty()).then(|| source.to_string())
+}
+
+/// Replace the legacy marker with a `source:` frontmatter field.
+pub(crate) fn rewrite_legacy(content: &str, source: &str) -> String {
+    let (fm, body) = parse::split_frontmatter(content).unwrap_or(("", content));
+    let body = body
+        .split_once('\n')
+        .map_or("", |(_, rest)| rest)
+        .trim_start_matches('\n');
+    let mut out = String::from("---\n");
+    if !fm.trim().is_empty() {
+        out.push_str(fm.trim_end_matches('\n'));
+        out.push('\n');
+    }
+    let _ = writeln!(out, "source: {source}\n---");
+    out.push_str(body);
+    out
+}
+
+/// Artifact kind of a provider subdirectory (Codex prompts are commands).
+fn kind_of(dir: &Path) -> Option<&'static str> {
+    match dir.file_name()?.to_str()? {
+        "agents" => Some("agents"),
+        "commands" | "prompts" => Some("commands"),
+        "skills" => Some("skills"),
+        _ => None,
+    }
+}
+
+/// Entries of a pre-module `.manifest`: a YAML list or one name per line.
+/// `None` when the manifest is already per-module (or unreadable).
+fn flat_manifest(content: &str) -> Option<Vec<String>> {
+    if serde_yaml::from_str::<BTreeMap<String, Vec<String>>>(content).is_ok() {
+        return None;
+    }
+    if let Ok(list) = serde_yaml::from_str::<Vec<String>>(content) {
+        return Some(list);
+    }
+    let entries: Vec<String> = content
+        .lines()
+        .map(|l| l.trim().trim_start_matches("- ").trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect();
+    entries
+        .iter()
+        .all(|e| !e.contains([':', ' ']))
+        .then_some(entries)
+}
+
+/// Migrate one provider artifact directory such as `~/.claude/agents`.
+pub fn migrate_dir(dir: &Path, index: &SourceIndex, dry_run: bool) -> Result<Vec<Finding>, String> {
+    let Some(kind) = kind_of(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut findings = Vec::new();
+    // Module of each deployed entry, from rewritten or modern files.
+    let mut owners: BTreeMap<String, String> = BTreeMap::new();
+    let mut rewritten: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    if kind != "skills" {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| format!("failed to read {}: {e}", dir.display()))?
+            .filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "md"))
+            .collect();
+        files.sort();
+        for path in files {
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let stem = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let Some(synced) = legacy_source(&content) else {
+                let module = parse::extract_source_field(&content)
+                    .and_then(|s| s.split_once('/').map(|(m, _)| m.to_string()));
+                if let Some(module) = module {
+                    owners.insert(stem, module);
+                }
+                continue;
+            };
+            match index.attribute(kind, &synced) {
+                Ok(module) => {
+                    let source = format!("{module}/{kind}/{synced}");
+                    if !dry_run {
+                        std::fs::write(&path, rewrite_legacy(&content, &source))
+                            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+                    }
+                    owners.insert(stem.clone(), module.to_string());
+                    rewritten
+                        .entry(module.to_string())
+                        .or_default()
+                        .insert(stem);
+                    findings.push(Finding::Rewritten { path, source });
+                }
+                Err(reason) => findings.push(Finding::Unattributed { path, reason }),
+            }
+        }
+    }
+
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let flat = std::fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|c| flat_manifest(&c));
+    if flat.is_none() && rewritten.is_empty() {
+        return Ok(findings);
+    }
+
+    let mut modules: BTreeMap<String, BTreeSet<String>> = rewritten;
+    for entry in flat.unwrap_or_default() {
+        let file = if kind == "skills" {
+            entry.clone()
+        } else {
+            format!("{entry}.md")
+        };
+        let module = match owners.get(&entry) {
+            Some(module) => Ok(module.clone()),
+            None => index.attribute(kind, &file).map(String::from),
+        };
+        match module {
+            Ok(module) => {
+                modules.entry(module).or_default().insert(entry);
+            }
+            Err(reason) => findings.push(Finding::Unattributed {
+                path: manifest_path.clone(),
+                reason: format!("manifest entry {entry}: {reason}"),
+            }),
+        }
+    }
+
+    let mut written = BTreeMap::new();
+    for (module, entries) in modules {
+        let mut all: BTreeSet<String> = manifest::read(&RealFs, dir, &module).into_iter().collect();
+        all.extend(entries);
+        let all: Vec<String> = all.into_iter().collect();
+        if !dry_run {
+            manifest::update(&RealFs, dir, &module, &all)?;
+        }
+        written.insert(module, all);
+    }
+    if !written.is_empty() {
+        findings.push(Finding::Manifest {
+            path: manifest_path,
+            modules: written,
+        });
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests;
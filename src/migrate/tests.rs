@@ -0,0 +1,96 @@
+use super::*;
+use crate::vfs::RealFs;
+use tempfile::TempDir;
+
+fn checkout(root: &Path, module: &str, agents: &[&str]) -> PathBuf {
+    let dir = root.join(module);
+    std::fs::create_dir_all(dir.join("agents")).unwrap();
+    std::fs::write(dir.join("module.yaml"), format!("name: {module}\n")).unwrap();
+    for agent in agents {
+        std::fs::write(dir.join("agents").join(agent), "---\nname: X\n---\n").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn legacy_marker_is_replaced_by_source_field() {
+    let content = "---\nname: Dev\n---\n# synced-from: Dev.md\n\nBody.\n";
+    assert_eq!(legacy_source(content), Some("Dev.md".into()));
+    let rewritten = rewrite_legacy(content, "forge-dev/agents/Dev.md");
+    assert_eq!(
+        rewritten,
+        "---\nname: Dev\nsource: forge-dev/agents/Dev.md\n---\nBody.\n"
+    );
+    assert!(parse::is_synced_from(&rewritten, "Dev.md"));
+    assert_eq!(legacy_source(&rewritten), None);
+
+    assert_eq!(
+        rewrite_legacy("# synced-from: Dev.md\nBody.\n", "m/agents/Dev.md"),
+        "---\nsource: m/agents/Dev.md\n---\nBody.\n"
+    );
+}
+
+#[test]
+fn attribution_needs_exactly_one_module() {
+    let tmp = TempDir::new().unwrap();
+    let a = checkout(tmp.path(), "forge-a", &["Dev.md", "Shared.md"]);
+    let b = checkout(tmp.path(), "forge-b", &["Shared.md"]);
+    let index = SourceIndex::from_checkouts(&[a, b]).unwrap();
+    assert_eq!(index.attribute("agents", "Dev.md"), Ok("forge-a"));
+    assert!(index
+        .attribute("agents", "Shared.md")
+        .unwrap_err()
+        .contains("forge-a, forge-b"));
+    assert!(index
+        .attribute("agents", "Ghost.md")
+        .unwrap_err()
+        .contains("no given module checkout"));
+}
+
+#[test]
+fn migrates_files_and_flat_manifest() {
+    let tmp = TempDir::new().unwrap();
+    let index =
+        SourceIndex::from_checkouts(&[checkout(tmp.path(), "forge-a", &["Dev.md"])]).unwrap();
+    let dst = tmp.path().join(".claude/agents");
+    std::fs::create_dir_all(&dst).unwrap();
+    std::fs::write(dst.join("Dev.md"), "# synced-from: Dev.md\nBody.\n").unwrap();
+    std::fs::write(dst.join("Orphan.md"), "# synced-from: Orphan.md\nBody.\n").unwrap();
+    std::fs::write(
+        dst.join("Modern.md"),
+        "---\nname: Modern\nsource: forge-b/agents/Modern.md\n---\nBody.\n",
+    )
+    .unwrap();
+    std::fs::write(dst.join(MANIFEST_FILE), "Dev\nModern\nGone\n").unwrap();
+
+    let dry = migrate_dir(&dst, &index, true).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dst.join("Dev.md")).unwrap(),
+        "# synced-from: Dev.md\nBody.\n"
+    );
+
+    let findings = migrate_dir(&dst, &index, false).unwrap();
+    assert_eq!(findings, dry);
+    assert_eq!(
+        findings[0],
+        Finding::Rewritten {
+            path: dst.join("Dev.md"),
+            source: "forge-a/agents/Dev.md".into()
+        }
+    );
+    assert!(
+        matches!(&findings[1], Finding::Unattributed { path, .. } if path.ends_with("Orphan.md"))
+    );
+    assert!(
+        matches!(&findings[2], Finding::Unattributed { reason, .. } if reason.starts_with("manifest entry Gone"))
+    );
+    assert_eq!(manifest::read(&RealFs, &dst, "forge-a"), vec!["Dev"]);
+    assert_eq!(manifest::read(&RealFs, &dst, "forge-b"), vec!["Modern"]);
+    assert!(std::fs::read_to_string(dst.join("Dev.md"))
+        .unwrap()
+        .contains("source: forge-a/agents/Dev.md"));
+
+    // Already migrated: nothing left to do but the unattributed file.
+    let again = migrate_dir(&dst, &index, false).unwrap();
+    assert_eq!(again.len(), 1);
+}
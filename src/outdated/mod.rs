@@ -0,0 +1,138 @@
+//! Update checks: compares the module versions recorded next to deployed
+//! artifacts (see [`crate::manifest::record`]) with the versions available
+//! from source checkouts or a registry.
+
+use crate::deploy::provider::Provider;
+use crate::manifest;
+use crate::registry::compare_versions;
+use crate::vfs::RealFs;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where a newer version is available from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Checkout(PathBuf),
+    Registry,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Outdated {
+    pub module: String,
+    /// Oldest version deployed in any scanned directory.
+    pub deployed: String,
+    pub available: String,
+    pub source: Source,
+    pub dirs: Vec<PathBuf>,
+}
+
+/// Artifact directories of a provider root such as `~/.claude`.
+const ARTIFACT_DIRS: &[&str] = &[
+    "agents",
+    "rules",
+    "instructions",
+    "skills",
+    "commands",
+    "prompts",
+];
+
+/// Every artifact directory under the given provider roots that exists.
+pub fn artifact_dirs(provider_roots: &[PathBuf]) -> Vec<PathBuf> {
+    provider_roots
+        .iter()
+        .flat_map(|root| ARTIFACT_DIRS.iter().map(move |d| root.join(d)))
+        .filter(|d| d.is_dir())
+        .collect()
+}
+
+/// Deployed versions per module, with the directories each version is in.
+pub fn scan_deployed(dirs: &[PathBuf]) -> BTreeMap<String, Vec<(String, PathBuf)>> {
+    let mut deployed: BTreeMap<String, Vec<(String, PathBuf)>> = BTreeMap::new();
+    for dir in dirs {
+        for (module, version) in manifest::read_versions(&RealFs, dir) {
+            deployed
+                .entry(module)
+                .or_default()
+                .push((version, dir.clone()));
+        }
+    }
+    deployed
+}
+
+/// Offer `version` of `module` from `source`, keeping the newest offer (a
+/// checkout wins a tie with the registry).
+pub fn offer(
+    available: &mut BTreeMap<String, (String, Source)>,
+    module: &str,
+    version: &str,
+    source: Source,
+) {
+    let better = match available.get(module) {
+        None => true,
+        Some((current, current_source)) => match compare_versions(version, current) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => {
+                *current_source == Source::Registry && source != Source::Registry
+            }
+            std::cmp::Ordering::Less => false,
+        },
+    };
+    if better {
+        available.insert(module.to_string(), (version.to_string(), source));
+    }
+}
+
+/// Modules with at least one deployment older than the available version.
+pub fn find_outdated(
+    deployed: &BTreeMap<String, Vec<(String, PathBuf)>>,
+    available: &BTreeMap<String, (String, Source)>,
+) -> Vec<Outdated> {
+    let mut outdated = Vec::new();
+    for (module, copies) in deployed {
+        let Some((latest, source)) = available.get(module) else {
+            continue;
+        };
+        let behind: Vec<&(String, PathBuf)> = copies
+            .iter()
+            .filter(|(version, _)| compare_versions(version, latest).is_lt())
+            .collect();
+        let Some(oldest) = behind
+            .iter()
+            .map(|(version, _)| version)
+            .min_by(|a, b| compare_versions(a, b))
+        else {
+            continue;
+        };
+        outdated.push(Outdated {
+            module: module.clone(),
+            deployed: oldest.clone(),
+            available: latest.clone(),
+            source: source.clone(),
+            dirs: behind.iter().map(|(_, dir)| dir.clone()).collect(),
+        });
+    }
+    outdated
+}
+
+/// Provider roots (`~/.claude`, `.claude`, ...) for the standard scopes.
+pub fn provider_roots(
+    home: &Path,
+    providers: &[String],
+    scope: &str,
+) -> Result<Vec<PathBuf>, String> {
+    let user = providers.iter().map(|p| home.join(format!(".{p}")));
+    let workspace = providers.iter().map(|p| {
+        PathBuf::from(Provider::from_str(p).map_or(format!(".{p}"), |p| p.workspace_root()))
+    });
+    match scope {
+        "user" => Ok(user.collect()),
+        "workspace" => Ok(workspace.collect()),
+        "all" => Ok(user.chain(workspace).collect()),
+        other => Err(format!(
+            "invalid scope {other:?}: use user, workspace, or all"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests;
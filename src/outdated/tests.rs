@@ -0,0 +1,71 @@
+use super::*;
+use crate::vfs::RealFs;
+use tempfile::TempDir;
+
+fn record(dir: &Path, module: &str, version: &str) {
+    std::fs::create_dir_all(dir).unwrap();
+    manifest::record(&RealFs, dir, module, Some(version), &["Agent".into()]).unwrap();
+}
+
+#[test]
+fn scan_and_compare() {
+    let root = TempDir::new().unwrap();
+    let claude = root.path().join(".claude");
+    let gemini = root.path().join(".gemini");
+    record(&claude.join("agents"), "forge-council", "1.0.0");
+    record(&claude.join("skills"), "forge-council", "1.2.0");
+    record(&gemini.join("agents"), "forge-tlp", "2.0.0");
+
+    let dirs = artifact_dirs(&[claude.clone(), gemini]);
+    assert_eq!(dirs.len(), 3);
+    let deployed = scan_deployed(&dirs);
+
+    let mut available = BTreeMap::new();
+    offer(&mut available, "forge-council", "1.2.0", Source::Registry);
+    offer(
+        &mut available,
+        "forge-council",
+        "1.2.0",
+        Source::Checkout(PathBuf::from("/src/forge-council")),
+    );
+    offer(&mut available, "forge-tlp", "1.9.0", Source::Registry);
+
+    let outdated = find_outdated(&deployed, &available);
+    assert_eq!(
+        outdated,
+        vec![Outdated {
+            module: "forge-council".into(),
+            deployed: "1.0.0".into(),
+            available: "1.2.0".into(),
+            source: Source::Checkout(PathBuf::from("/src/forge-council")),
+            dirs: vec![claude.join("agents")],
+        }]
+    );
+}
+
+#[test]
+fn newer_offer_wins() {
+    let mut available = BTreeMap::new();
+    offer(
+        &mut available,
+        "m",
+        "1.0.0",
+        Source::Checkout(PathBuf::from("/src/m")),
+    );
+    offer(&mut available, "m", "1.1.0", Source::Registry);
+    assert_eq!(available["m"], ("1.1.0".to_string(), Source::Registry));
+}
+
+#[test]
+fn roots_per_scope() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        provider_roots(home, &["claude".into()], "all").unwrap(),
+        vec![PathBuf::from("/home/u/.claude"), PathBuf::from(".claude")]
+    );
+    assert!(provider_roots(home, &["claude".into()], "project").is_err());
+    assert_eq!(
+        provider_roots(home, &["copilot".into()], "workspace").unwrap(),
+        vec![PathBuf::from(".github")]
+    );
+}
@@ -0,0 +1,298 @@
+//! Module packaging: bundles a module's deployable content into a versioned,
+//! reproducible tar archive for distribution outside git checkouts.
+//!
+//! The archive holds one top-level `<name>-<version>/` directory containing
+//! the module files plus a `forge-package.json` manifest listing every file
+//! with its size and SHA-256. A `<archive>.sha256` file in `sha256sum`
+//! format is written next to the archive.
+
+pub(crate) mod tar;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+pub const PACKAGE_MANIFEST: &str = "forge-package.json";
+
+/// Top-level entries packed when present. Anything else (build output, VCS
+/// metadata, local `config.yaml` overrides) stays out.
+const INCLUDE: &[&str] = &[
+    "module.yaml",
+    "defaults.yaml",
+    "README.md",
+    "LICENSE",
+    ".claude-plugin",
+    "agents",
+    "skills",
+    "commands",
+    "hooks",
+    "mcp",
+    "output-styles",
+    "statusline.json",
+    "memory.md",
+    "templates",
+];
+
+/// Lowercase hex SHA-256 of `data`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackedFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageManifest {
+    pub name: String,
+    pub version: String,
+    pub files: Vec<PackedFile>,
+}
+
+pub struct Package {
+    pub manifest: PackageManifest,
+    pub bytes: Vec<u8>,
+    pub sha256: String,
+}
+
+impl Package {
+    pub fn file_name(&self) -> String {
+        format!("{}-{}.tar", self.manifest.name, self.manifest.version)
+    }
+}
+
+/// `name` and `version` from `module.yaml`.
+pub fn module_identity(root: &Path) -> Result<(String, String), String> {
+    let path = root.join("module.yaml");
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let doc: serde_yaml::Value = serde_yaml::from_str(&content)
+        .map_err(|e| format!("invalid YAML in {}: {e}", path.display()))?;
+    let field = |key: &str| match doc.get(key) {
+        Some(serde_yaml::Value::String(s)) if !s.trim().is_empty() => Ok(s.trim().to_string()),
+        Some(serde_yaml::Value::Number(n)) => Ok(n.to_string()),
+        _ => Err(format!("{} has no {key}", path.display())),
+    };
+    let (name, version) = (field("name")?, field("version")?);
+    for value in [&name, &version] {
+        if value.contains(['/', '\\']) || value.starts_with('.') {
+            return Err(format!("{value:?} cannot be used in an archive name"));
+        }
+    }
+    Ok((name, version))
+}
+
+/// Files to pack as `(archive-relative path, source path)`, sorted.
+pub(crate) fn collect_files(root: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut files = Vec::new();
+    for entry in INCLUDE {
+        let path = root.join(entry);
+        if path.is_symlink() {
+            return Err(format!("refusing to pack symlink {}", path.display()));
+        }
+        if path.is_file() {
+            files.push(((*entry).to_string(), path));
+        } else if path.is_dir() {
+            walk(&path, entry, &mut files)?;
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn walk(dir: &Path, rel: &str, files: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read {}: {e}", dir.display()))?;
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let rel = format!("{rel}/{name}");
+        if path.is_symlink() {
+            return Err(format!("refusing to pack symlink {}", path.display()));
+        }
+        if path.is_dir() {
+            walk(&path, &rel, files)?;
+        } else if path.is_file() {
+            files.push((rel, path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(crate) fn file_mode(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    let executable = std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0);
+    if executable {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn file_mode(_path: &Path) -> u32 {
+    0o644
+}
+
+/// Build the archive for the module at `root` in memory.
+pub fn build_package(root: &Path) -> Result<Package, String> {
+    let (name, version) = module_identity(root)?;
+    let top = format!("{name}-{version}");
+
+    let mut entries = Vec::new();
+    let mut packed = Vec::new();
+    for (rel, path) in collect_files(root)? {
+        let data =
+            std::fs::read(&path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        packed.push(PackedFile {
+            path: rel.clone(),
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+        });
+        entries.push((format!("{top}/{rel}"), data, file_mode(&path)));
+    }
+
+    let manifest = PackageManifest {
+        name,
+        version,
+        files: packed,
+    };
+    let mut json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("failed to serialize package manifest: {e}"))?;
+    json.push(b'\n');
+    entries.insert(0, (format!("{top}/{PACKAGE_MANIFEST}"), json, 0o644));
+
+    let bytes = tar::write(&entries)?;
+    let sha256 = sha256_hex(&bytes);
+    Ok(Package {
+        manifest,
+        bytes,
+        sha256,
+    })
+}
+
+/// Write the archive and its `.sha256` file into `out_dir`, returning the
+/// archive path.
+pub fn write_package(package: &Package, out_dir: &Path, dry_run: bool) -> Result<PathBuf, String> {
+    let archive = out_dir.join(package.file_name());
+    if dry_run {
+        return Ok(archive);
+    }
+    std::fs::create_dir_all(out_dir)
+        .map_err(|e| format!("failed to create {}: {e}", out_dir.display()))?;
+    std::fs::write(&archive, &package.bytes)
+        .map_err(|e| format!("failed to write {}: {e}", archive.display()))?;
+    let checksum = out_dir.join(format!("{}.sha256", package.file_name()));
+    std::fs::write(
+        &checksum,
+        format!("{}  {}\n", package.sha256, package.file_name()),
+    )
+    .map_err(|e| format!("failed to write {}: {e}", checksum.display()))?;
+    Ok(archive)
+}
+
+/// Unpack an archive built by [`build_package`] under `dest_parent`,
+/// verifying every file against the embedded manifest. Returns the unpacked
+/// module root and its manifest.
+pub fn unpack_package(
+    bytes: &[u8],
+    dest_parent: &Path,
+) -> Result<(PathBuf, PackageManifest), String> {
+    let entries = tar::read(bytes)?;
+    let Some((manifest_path, manifest_data, _)) = entries
+        .iter()
+        .find(|(path, _, _)| path.ends_with(&format!("/{PACKAGE_MANIFEST}")))
+    else {
+        return Err(format!("archive has no {PACKAGE_MANIFEST}"));
+    };
+    let manifest: PackageManifest = serde_json::from_slice(manifest_data)
+        .map_err(|e| format!("invalid {PACKAGE_MANIFEST}: {e}"))?;
+    validate_identity(&manifest)?;
+    let top = format!("{}-{}", manifest.name, manifest.version);
+    if *manifest_path != format!("{top}/{PACKAGE_MANIFEST}") {
+        return Err(format!("unexpected manifest location {manifest_path}"));
+    }
+
+    let mut files = Vec::new();
+    for (path, data, mode) in &entries {
+        if path == manifest_path {
+            continue;
+        }
+        let rel = path
+            .strip_prefix(&format!("{top}/"))
+            .filter(|rel| {
+                !rel.is_empty()
+                    && !rel.starts_with('/')
+                    && rel.split('/').all(|c| c != ".." && c != ".")
+            })
+            .ok_or_else(|| format!("unsafe path in archive: {path}"))?;
+        let Some(expected) = manifest.files.iter().find(|f| f.path == rel) else {
+            return Err(format!("{rel} is not listed in {PACKAGE_MANIFEST}"));
+        };
+        if expected.size != data.len() as u64 || expected.sha256 != sha256_hex(data) {
+            return Err(format!("checksum mismatch for {rel}"));
+        }
+        files.push((rel, data, *mode));
+    }
+    if files.len() != manifest.files.len() {
+        return Err(format!(
+            "archive is missing files listed in {PACKAGE_MANIFEST}"
+        ));
+    }
+
+    let root = dest_parent.join(&top);
+    for (rel, data, mode) in files {
+        let path = root.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+        }
+        std::fs::write(&path, data)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+        set_mode(&path, mode);
+    }
+    Ok((root, manifest))
+}
+
+/// The manifest names the directory everything is written under, so its
+/// `name` must be a module name and its `version` plain version characters.
+fn validate_identity(manifest: &PackageManifest) -> Result<(), String> {
+    crate::scaffold::validate_module_name(&manifest.name)
+        .map_err(|e| format!("invalid {PACKAGE_MANIFEST}: {e}"))?;
+    let version = &manifest.version;
+    let valid = version.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'));
+    if !valid {
+        return Err(format!(
+            "invalid {PACKAGE_MANIFEST}: version {version:?} must match ^[A-Za-z0-9][A-Za-z0-9.+_-]*$"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode));
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) {}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+//! Ustar archives on top of the `tar` crate: regular files only, fixed owner
+//! and mtime, so the same module always packs to the same bytes.
+
+use std::io::Read;
+
+/// The only modes an archived file carries: executable or not. Setuid,
+/// setgid, sticky and group/world-write bits never survive a round trip.
+pub fn normalize_mode(mode: u32) -> u32 {
+    if mode & 0o111 != 0 {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+fn header(size: u64, mode: u32) -> tar::Header {
+    let mut header = tar::Header::new_ustar();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(size);
+    header.set_mode(mode);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(0);
+    header
+}
+
+/// Archive `(path, contents, mode)` entries in order.
+pub fn write(entries: &[(String, Vec<u8>, u32)]) -> Result<Vec<u8>, String> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, data, mode) in entries {
+        let mut header = header(data.len() as u64, normalize_mode(*mode));
+        header
+            .set_path(path)
+            .map_err(|e| format!("cannot archive {path}: {e}"))?;
+        header.set_cksum();
+        builder
+            .append(&header, data.as_slice())
+            .map_err(|e| format!("cannot archive {path}: {e}"))?;
+    }
+    builder
+        .into_inner()
+        .map_err(|e| format!("failed to finish archive: {e}"))
+}
+
+/// [`write`] without the path checks or mode masking, for building the
+/// hostile archives that [`read`] callers must reject.
+#[cfg(test)]
+pub fn write_unchecked(entries: &[(String, Vec<u8>, u32)]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, data, mode) in entries {
+        let mut header = header(data.len() as u64, *mode);
+        header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+        header.set_cksum();
+        builder.append(&header, data.as_slice()).unwrap();
+    }
+    builder.into_inner().unwrap()
+}
+
+/// Read the regular files of a ustar archive as `(path, contents, mode)`,
+/// with each mode passed through [`normalize_mode`]. Directory entries are
+/// skipped; links and other types are an error.
+pub fn read(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>, u32)>, String> {
+    let corrupt = |e: std::io::Error| format!("corrupt archive: {e}");
+    let mut archive = tar::Archive::new(bytes);
+    let mut entries = Vec::new();
+    for entry in archive.entries().map_err(corrupt)? {
+        let mut entry = entry.map_err(corrupt)?;
+        let path = String::from_utf8(entry.path_bytes().into_owned())
+            .map_err(|_| "non-UTF-8 path in archive".to_string())?;
+        match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Continuous => {}
+            tar::EntryType::Directory => continue,
+            other => {
+                return Err(format!(
+                    "unsupported archive entry type {:?} for {path}",
+                    other.as_byte() as char
+                ))
+            }
+        }
+        let mode = entry.header().mode().map_err(corrupt)?;
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|_| format!("archive truncated in {path}"))?;
+        entries.push((path, data, normalize_mode(mode)));
+    }
+    Ok(entries)
+}
@@ -0,0 +1,248 @@
+use super::*;
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn module(root: &Path) {
+    fs::write(
+        root.join("module.yaml"),
+        "name: forge-demo\nversion: 1.2.0\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("agents")).unwrap();
+    fs::write(
+        root.join("agents/Reviewer.md"),
+        "---\nname: Reviewer\n---\n",
+    )
+    .unwrap();
+    fs::write(root.join("agents/.DS_Store"), "junk").unwrap();
+    fs::write(root.join("config.yaml"), "local: true\n").unwrap();
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("target/out"), "build").unwrap();
+}
+
+// ─── sha256 ───
+
+#[test]
+fn sha256_known_vectors() {
+    assert_eq!(
+        sha256_hex(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    // Two-block message (padding spills into a second block).
+    assert_eq!(
+        sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+// ─── collect / identity ───
+
+#[test]
+fn collects_only_module_content() {
+    let root = TempDir::new().unwrap();
+    module(root.path());
+    let files: Vec<String> = collect_files(root.path())
+        .unwrap()
+        .into_iter()
+        .map(|(rel, _)| rel)
+        .collect();
+    assert_eq!(files, ["agents/Reviewer.md", "module.yaml"]);
+}
+
+#[test]
+fn identity_requires_version() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join("module.yaml"), "name: forge-demo\n").unwrap();
+    assert!(module_identity(root.path())
+        .unwrap_err()
+        .contains("version"));
+}
+
+// ─── build ───
+
+#[test]
+fn package_is_reproducible() {
+    let root = TempDir::new().unwrap();
+    module(root.path());
+    let a = build_package(root.path()).unwrap();
+    let b = build_package(root.path()).unwrap();
+    assert_eq!(a.file_name(), "forge-demo-1.2.0.tar");
+    assert_eq!(a.sha256, b.sha256);
+    assert_eq!(a.bytes.len() % 512, 0);
+    assert_eq!(a.manifest.files.len(), 2);
+    assert_eq!(
+        a.manifest.files[1].sha256,
+        sha256_hex(b"name: forge-demo\nversion: 1.2.0\n")
+    );
+}
+
+#[test]
+fn archive_extracts_with_system_tar() {
+    if Command::new("tar").arg("--version").output().is_err() {
+        return;
+    }
+    let root = TempDir::new().unwrap();
+    module(root.path());
+    let out = TempDir::new().unwrap();
+    let package = build_package(root.path()).unwrap();
+    let archive = write_package(&package, out.path(), false).unwrap();
+
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(out.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let top = out.path().join("forge-demo-1.2.0");
+    assert_eq!(
+        fs::read_to_string(top.join("agents/Reviewer.md")).unwrap(),
+        "---\nname: Reviewer\n---\n"
+    );
+    let manifest: PackageManifest =
+        serde_json::from_str(&fs::read_to_string(top.join(PACKAGE_MANIFEST)).unwrap()).unwrap();
+    assert_eq!(manifest.name, "forge-demo");
+
+    let checksum = fs::read_to_string(out.path().join("forge-demo-1.2.0.tar.sha256")).unwrap();
+    assert_eq!(
+        checksum,
+        format!("{}  forge-demo-1.2.0.tar\n", package.sha256)
+    );
+}
+
+#[test]
+fn long_paths_use_prefix() {
+    let root = TempDir::new().unwrap();
+    module(root.path());
+    let deep = root.path().join(format!("skills/{}", "d".repeat(90)));
+    fs::create_dir_all(&deep).unwrap();
+    fs::write(deep.join("SKILL.md"), "x").unwrap();
+    assert!(build_package(root.path()).is_ok());
+}
+
+// ─── unpack ───
+
+#[test]
+fn unpack_round_trip() {
+    let root = TempDir::new().unwrap();
+    module(root.path());
+    let package = build_package(root.path()).unwrap();
+    let dest = TempDir::new().unwrap();
+
+    let (unpacked, manifest) = unpack_package(&package.bytes, dest.path()).unwrap();
+    assert_eq!(unpacked, dest.path().join("forge-demo-1.2.0"));
+    assert_eq!(manifest.version, "1.2.0");
+    assert_eq!(
+        fs::read_to_string(unpacked.join("module.yaml")).unwrap(),
+        "name: forge-demo\nversion: 1.2.0\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn unpack_strips_special_mode_bits() {
+    use std::os::unix::fs::PermissionsExt;
+    let files = [
+        ("hooks/run.sh", 0o4755),
+        ("hooks/group.sh", 0o2775),
+        ("agents/Shared.md", 0o1666),
+    ];
+    let manifest = PackageManifest {
+        name: "forge-demo".to_string(),
+        version: "1.0.0".to_string(),
+        files: files
+            .iter()
+            .map(|(path, _)| PackedFile {
+                path: (*path).to_string(),
+                size: 1,
+                sha256: sha256_hex(b"x"),
+            })
+            .collect(),
+    };
+    let mut entries = vec![(
+        format!("forge-demo-1.0.0/{PACKAGE_MANIFEST}"),
+        serde_json::to_vec(&manifest).unwrap(),
+        0o644,
+    )];
+    entries.extend(
+        files
+            .iter()
+            .map(|(path, mode)| (format!("forge-demo-1.0.0/{path}"), b"x".to_vec(), *mode)),
+    );
+    let dest = TempDir::new().unwrap();
+    let (root, _) = unpack_package(&tar::write_unchecked(&entries), dest.path()).unwrap();
+
+    let mode = |path: &str| fs::metadata(root.join(path)).unwrap().permissions().mode() & 0o7777;
+    assert_eq!(mode("hooks/run.sh"), 0o755);
+    assert_eq!(mode("hooks/group.sh"), 0o755);
+    assert_eq!(mode("agents/Shared.md"), 0o644);
+}
+
+#[test]
+fn unpack_rejects_tampered_file() {
+    let root = TempDir::new().unwrap();
+    module(root.path());
+    let mut package = build_package(root.path()).unwrap();
+    let needle = b"name: Reviewer";
+    let pos = package
+        .bytes
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .unwrap();
+    package.bytes[pos] = b'N';
+
+    let dest = TempDir::new().unwrap();
+    let err = unpack_package(&package.bytes, dest.path()).unwrap_err();
+    assert!(err.contains("checksum mismatch for agents/Reviewer.md"));
+    assert!(!dest.path().join("forge-demo-1.2.0").exists());
+}
+
+fn archive_with_identity(name: &str, version: &str) -> Vec<u8> {
+    let top = format!("{name}-{version}");
+    let data = b"x".to_vec();
+    let manifest = PackageManifest {
+        name: name.to_string(),
+        version: version.to_string(),
+        files: vec![PackedFile {
+            path: "agents/Evil.md".to_string(),
+            size: 1,
+            sha256: sha256_hex(&data),
+        }],
+    };
+    tar::write_unchecked(&[
+        (
+            format!("{top}/{PACKAGE_MANIFEST}"),
+            serde_json::to_vec(&manifest).unwrap(),
+            0o644,
+        ),
+        (format!("{top}/agents/Evil.md"), data, 0o644),
+    ])
+}
+
+#[test]
+fn unpack_rejects_traversal_in_identity() {
+    let dest = TempDir::new().unwrap();
+    let modules = dest.path().join("modules");
+    for (name, version) in [
+        ("..", "1.0.0"),
+        ("../escape", "1.0.0"),
+        ("forge-demo", "../../escape"),
+        ("forge-demo", "1.0.0/../.."),
+        ("Forge_Demo", "1.0.0"),
+        ("forge-demo", ""),
+    ] {
+        let bytes = archive_with_identity(name, version);
+        let err = unpack_package(&bytes, &modules).unwrap_err();
+        assert!(
+            err.contains("invalid forge-package.json"),
+            "{name}@{version}: {err}"
+        );
+    }
+    assert_eq!(fs::read_dir(dest.path()).unwrap().count(), 0);
+}
@@ -1,5 +1,6 @@
 use regex::Regex;
 use serde_yaml::Value;
+use std::io::{self, BufRead};
 use std::sync::OnceLock;
 
 const MAX_CONTENT_SIZE: usize = 256 * 1024;
@@ -29,6 +30,32 @@ pub fn split_frontmatter(content: &str) -> Option<(&str, &str)> {
     Some((yaml, body))
 }
 
+/// The start of `reader` through the closing `---` of its frontmatter,
+/// plus the first body line (where legacy `# synced-from:` markers live):
+/// enough for [`fm_value`], [`fm_list`], and [`is_synced_from`] without
+/// reading long bodies. Content without frontmatter yields its first line.
+/// `crate::vfs::read_header` opens a file for it.
+pub fn read_header(mut reader: impl BufRead) -> io::Result<String> {
+    let mut header = String::new();
+    if reader.read_line(&mut header)? == 0 || !header.starts_with("---") {
+        return Ok(header);
+    }
+    // The rest of the opening line counts as frontmatter, as in
+    // `split_frontmatter`; a closing `---` may follow it directly.
+    let mut closed = header[3..].starts_with("---");
+    while header.len() <= MAX_CONTENT_SIZE {
+        let start = header.len();
+        if reader.read_line(&mut header)? == 0 {
+            break;
+        }
+        if closed {
+            break;
+        }
+        closed = header[start..].starts_with("---");
+    }
+    Ok(header)
+}
+
 pub fn fm_value(content: &str, key: &str) -> Option<String> {
     let (yaml_text, _) = split_frontmatter(content)?;
     let value: Value = serde_yaml::from_str(yaml_text).ok()?;
@@ -105,9 +132,30 @@ pub fn extract_source_field(content: &str) -> Option<String> {
 }
 
 pub fn module_name(content: &str) -> Option<String> {
-    fm_value(content, "name").or_else(|| {
+    module_field(content, "name")
+}
+
+pub fn module_version(content: &str) -> Option<String> {
+    module_field(content, "version")
+}
+
+pub fn module_description(content: &str) -> Option<String> {
+    module_field(content, "description").filter(|v| !v.is_empty())
+}
+
+pub fn module_license(content: &str) -> Option<String> {
+    module_field(content, "license").filter(|v| !v.is_empty())
+}
+
+pub fn module_attribution(content: &str) -> Option<String> {
+    module_field(content, "attribution").filter(|v| !v.is_empty())
+}
+
+fn module_field(content: &str, key: &str) -> Option<String> {
+    let prefix = format!("{key}:");
+    fm_value(content, key).or_else(|| {
         content.lines().find_map(|l| {
-            l.strip_prefix("name:")
+            l.strip_prefix(&prefix)
                 .map(|v| v.trim().trim_matches('"').trim_matches('\'').to_string())
         })
     })
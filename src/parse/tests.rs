@@ -380,6 +380,15 @@ fn extract_source_user_content_no_source() {
 
 // --- module_name ---
 
+#[test]
+fn module_version_plain_yaml() {
+    assert_eq!(
+        module_version("name: forge-council\nversion: \"1.2.0\"\n"),
+        Some("1.2.0".into())
+    );
+    assert_eq!(module_version("name: forge-council\n"), None);
+}
+
 #[test]
 fn module_name_plain_yaml() {
     assert_eq!(
@@ -422,6 +431,42 @@ fn module_name_empty() {
     assert_eq!(module_name(""), None);
 }
 
+// --- read_header ---
+
+fn header_of(content: &str) -> String {
+    read_header(content.as_bytes()).unwrap()
+}
+
+#[test]
+fn read_header_stops_after_first_body_line() {
+    let header = header_of("---\nname: Dev\nsource: m/Dev.md\n---\nFirst.\nSecond.\n");
+    assert_eq!(header, "---\nname: Dev\nsource: m/Dev.md\n---\nFirst.\n");
+    assert_eq!(fm_value(&header, "name").as_deref(), Some("Dev"));
+    assert!(is_synced_from(&header, "Dev.md"));
+}
+
+#[test]
+fn read_header_keeps_legacy_marker() {
+    let content = "---\nname: Dev\n---\n# synced-from: Dev.md\nBody.\n";
+    assert!(is_synced_from(&header_of(content), "Dev.md"));
+}
+
+#[test]
+fn read_header_matches_full_parse() {
+    for content in [
+        "# No frontmatter\nBody.\n",
+        "---\n---\nBody.\n",
+        "---\nname: Dev\n",
+        "",
+        "---\ntools:\n  - Read\n  - Grep\n---\n",
+    ] {
+        let header = header_of(content);
+        assert!(content.starts_with(&header));
+        assert_eq!(fm_value(&header, "name"), fm_value(content, "name"));
+        assert_eq!(fm_list(&header, "tools"), fm_list(content, "tools"));
+    }
+}
+
 // --- proptest ---
 
 #[cfg(test)]
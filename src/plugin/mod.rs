@@ -0,0 +1,201 @@
+//! Claude plugin metadata generated from module content
+//! (`forge plugin generate`): the agent, command, and skill listings in
+//! `.claude-plugin/plugin.json` and this module's entry in
+//! `.claude-plugin/marketplace.json`.
+//!
+//! Listings follow what `install-*` deploys for Claude: every agent, and
+//! commands and skills in the `claude` allowlists. Keys the generator does
+//! not own (author, homepage, hooks, ...) are kept as written.
+
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+pub const PLUGIN_JSON: &str = ".claude-plugin/plugin.json";
+pub const MARKETPLACE_JSON: &str = ".claude-plugin/marketplace.json";
+
+/// Plugin-relative paths (`./agents/Dev.md`) of the content to list.
+#[derive(Debug, Default, PartialEq)]
+pub struct Listing {
+    pub agents: Vec<String>,
+    pub commands: Vec<String>,
+    pub skills: Vec<String>,
+}
+
+/// `name`, `version`, and `description` from `module.yaml`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Identity {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Identity {
+    pub fn load(module_root: &Path) -> Result<Self, String> {
+        let path = module_root.join("module.yaml");
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let name = parse::module_name(&content)
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| format!("{} has no name", path.display()))?;
+        Ok(Self {
+            name,
+            version: parse::module_version(&content),
+            description: parse::module_description(&content),
+        })
+    }
+}
+
+fn sorted_entries(dir: &Path, keep: impl Fn(&Path) -> Option<String>) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|e| keep(&e.path()))
+        .collect();
+    names.sort();
+    names
+}
+
+pub fn listing(module_root: &Path, config: &SidecarConfig) -> Listing {
+    let stem = |p: &Path| Some(p.file_stem()?.to_string_lossy().to_string());
+    let agents = sorted_entries(&module_root.join("agents"), |p| {
+        let name = stem(p)?;
+        let template = name.starts_with("_Template") || name.starts_with("Template");
+        (p.extension()? == "md" && !template).then(|| format!("./agents/{name}.md"))
+    });
+    let allowed_commands = config.provider_commands("claude");
+    let commands = sorted_entries(&module_root.join("commands"), |p| {
+        let name = stem(p)?;
+        (p.extension()? == "md" && allowed_commands.contains(&name))
+            .then(|| format!("./commands/{name}.md"))
+    });
+    let allowed_skills = config.provider_skills("claude");
+    let skills = sorted_entries(&module_root.join("skills"), |p| {
+        let name = p.file_name()?.to_string_lossy().to_string();
+        (p.join("SKILL.md").is_file() && allowed_skills.contains(&name))
+            .then(|| format!("./skills/{name}"))
+    });
+    Listing {
+        agents,
+        commands,
+        skills,
+    }
+}
+
+fn parse_object(existing: Option<&str>, file: &str) -> Result<Map<String, Value>, String> {
+    match existing.map(serde_json::from_str::<Value>) {
+        None => Ok(Map::new()),
+        Some(Ok(Value::Object(map))) => Ok(map),
+        Some(Ok(_)) => Err(format!("{file} is not a JSON object")),
+        Some(Err(e)) => Err(format!("{file} is not valid JSON: {e}")),
+    }
+}
+
+fn set_identity(map: &mut Map<String, Value>, identity: &Identity) {
+    map.insert("name".into(), json!(identity.name));
+    if let Some(version) = &identity.version {
+        map.insert("version".into(), json!(version));
+    }
+    if let Some(description) = &identity.description {
+        map.insert("description".into(), json!(description));
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    let mut out = serde_json::to_string_pretty(value).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// `plugin.json` with identity and listings from the module, over the
+/// `existing` file (if any).
+pub fn render_plugin_json(
+    existing: Option<&str>,
+    identity: &Identity,
+    listing: &Listing,
+) -> Result<String, String> {
+    let mut map = parse_object(existing, PLUGIN_JSON)?;
+    set_identity(&mut map, identity);
+    for (key, paths) in [
+        ("agents", &listing.agents),
+        ("commands", &listing.commands),
+        ("skills", &listing.skills),
+    ] {
+        if paths.is_empty() {
+            map.remove(key);
+        } else {
+            map.insert(key.into(), json!(paths));
+        }
+    }
+    Ok(pretty(&Value::Object(map)))
+}
+
+/// `marketplace.json` with this module's plugin entry (sourced from the
+/// repository root) added or refreshed, over the `existing` file (if any).
+pub fn render_marketplace_json(
+    existing: Option<&str>,
+    identity: &Identity,
+) -> Result<String, String> {
+    let mut map = parse_object(existing, MARKETPLACE_JSON)?;
+    map.entry("name").or_insert_with(|| json!(identity.name));
+    map.entry("owner")
+        .or_insert_with(|| json!({ "name": identity.name }));
+    let plugins = map.entry("plugins").or_insert_with(|| json!([]));
+    let Some(plugins) = plugins.as_array_mut() else {
+        return Err(format!("{MARKETPLACE_JSON}: plugins is not an array"));
+    };
+    let index = plugins
+        .iter()
+        .position(|p| p.get("name").and_then(Value::as_str) == Some(identity.name.as_str()))
+        .unwrap_or_else(|| {
+            plugins.push(json!({ "name": identity.name, "source": "./" }));
+            plugins.len() - 1
+        });
+    if let Some(entry) = plugins[index].as_object_mut() {
+        set_identity(entry, identity);
+    }
+    Ok(pretty(&Value::Object(map)))
+}
+
+/// Both metadata files for the module at `module_root`, rendered over what
+/// is on disk: `(path, content)` pairs.
+pub fn generate(
+    module_root: &Path,
+    config: &SidecarConfig,
+) -> Result<Vec<(PathBuf, String)>, String> {
+    let identity = Identity::load(module_root)?;
+    let current = |rel: &str| std::fs::read_to_string(module_root.join(rel)).ok();
+    let plugin = render_plugin_json(
+        current(PLUGIN_JSON).as_deref(),
+        &identity,
+        &listing(module_root, config),
+    )?;
+    let marketplace = render_marketplace_json(current(MARKETPLACE_JSON).as_deref(), &identity)?;
+    Ok(vec![
+        (module_root.join(PLUGIN_JSON), plugin),
+        (module_root.join(MARKETPLACE_JSON), marketplace),
+    ])
+}
+
+/// Write `content` to `path` unless it is already there. Returns whether
+/// the file was (or, with `dry_run`, would be) written.
+pub fn write_if_changed(path: &Path, content: &str, dry_run: bool) -> Result<bool, String> {
+    if std::fs::read_to_string(path).is_ok_and(|c| c == content) {
+        return Ok(false);
+    }
+    if !dry_run {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+        }
+        std::fs::write(path, content)
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests;
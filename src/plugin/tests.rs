@@ -0,0 +1,100 @@
+use super::*;
+use tempfile::TempDir;
+
+fn module(root: &Path) {
+    let write = |rel: &str, content: &str| {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    };
+    write(
+        "module.yaml",
+        "name: forge-demo\nversion: 1.2.0\ndescription: \"Demo module\"\n",
+    );
+    write(
+        "defaults.yaml",
+        "skills:\n  claude:\n    Review:\ncommands:\n  claude:\n    ship: {}\n",
+    );
+    write("agents/Dev.md", "---\nname: Dev\n---\n");
+    write("agents/_Template.md", "---\nname: T\n---\n");
+    write("commands/ship.md", "Ship it.\n");
+    write("commands/draft.md", "Not allowlisted.\n");
+    write("skills/Review/SKILL.md", "---\nname: Review\n---\n");
+    write("skills/Hidden/SKILL.md", "---\nname: Hidden\n---\n");
+}
+
+#[test]
+fn listing_follows_claude_deploys() {
+    let tmp = TempDir::new().unwrap();
+    module(tmp.path());
+    let listing = listing(tmp.path(), &SidecarConfig::load(tmp.path()));
+    assert_eq!(
+        listing,
+        Listing {
+            agents: vec!["./agents/Dev.md".into()],
+            commands: vec!["./commands/ship.md".into()],
+            skills: vec!["./skills/Review".into()],
+        }
+    );
+}
+
+#[test]
+fn plugin_json_keeps_foreign_keys() {
+    let identity = Identity {
+        name: "forge-demo".into(),
+        version: Some("1.2.0".into()),
+        description: None,
+    };
+    let listing = Listing {
+        agents: vec!["./agents/Dev.md".into()],
+        ..Listing::default()
+    };
+    let existing = r#"{"name": "old", "author": {"name": "Jo"}, "commands": ["./x.md"]}"#;
+    let out = render_plugin_json(Some(existing), &identity, &listing).unwrap();
+    let doc: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(doc["name"], "forge-demo");
+    assert_eq!(doc["version"], "1.2.0");
+    assert_eq!(doc["author"]["name"], "Jo");
+    assert_eq!(doc["agents"], json!(["./agents/Dev.md"]));
+    assert!(doc.get("commands").is_none());
+    assert!(render_plugin_json(Some("[]"), &identity, &listing).is_err());
+}
+
+#[test]
+fn marketplace_entry_added_then_refreshed() {
+    let mut identity = Identity {
+        name: "forge-demo".into(),
+        version: Some("1.0.0".into()),
+        description: Some("Demo".into()),
+    };
+    let first = render_marketplace_json(None, &identity).unwrap();
+    let doc: Value = serde_json::from_str(&first).unwrap();
+    assert_eq!(doc["owner"]["name"], "forge-demo");
+    assert_eq!(doc["plugins"][0]["source"], "./");
+
+    identity.version = Some("1.1.0".into());
+    let other = r#"{"name": "team", "owner": {"name": "Team"}, "plugins": [
+        {"name": "other", "source": "./other"},
+        {"name": "forge-demo", "source": "./", "category": "dev", "version": "1.0.0"}]}"#;
+    let doc: Value =
+        serde_json::from_str(&render_marketplace_json(Some(other), &identity).unwrap()).unwrap();
+    assert_eq!(doc["owner"]["name"], "Team");
+    assert_eq!(doc["plugins"].as_array().unwrap().len(), 2);
+    assert_eq!(doc["plugins"][1]["version"], "1.1.0");
+    assert_eq!(doc["plugins"][1]["category"], "dev");
+}
+
+#[test]
+fn generate_then_write_is_idempotent() {
+    let tmp = TempDir::new().unwrap();
+    module(tmp.path());
+    let config = SidecarConfig::load(tmp.path());
+    for (path, content) in generate(tmp.path(), &config).unwrap() {
+        assert!(write_if_changed(&path, &content, false).unwrap());
+    }
+    for (path, content) in generate(tmp.path(), &config).unwrap() {
+        assert!(!write_if_changed(&path, &content, false).unwrap());
+    }
+    let plugin = std::fs::read_to_string(tmp.path().join(PLUGIN_JSON)).unwrap();
+    assert!(plugin.contains("\"description\": \"Demo module\""));
+}
@@ -0,0 +1,120 @@
+//! Organization-wide deploy policy. Unlike a module's model whitelists,
+//! which only decide what gets written, a policy violation fails the
+//! deploy of that agent.
+//!
+//! ```yaml
+//! banned_tools: [WebFetch]
+//! banned_models: [claude-opus-*]
+//! required_fields: [version]
+//! max_description_length: 400
+//! ```
+
+use crate::deploy::AgentMeta;
+use crate::parse;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the policy file.
+pub const POLICY_ENV: &str = "FORGE_POLICY";
+/// Policy file looked up under the forge home when `FORGE_POLICY` is unset.
+pub const POLICY_FILE: &str = "policy.yaml";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    /// Tools no agent may be granted (source names, e.g. `Bash`), matched
+    /// ignoring case.
+    pub banned_tools: Vec<String>,
+    /// Resolved model IDs no agent may use; a trailing `*` matches a prefix.
+    pub banned_models: Vec<String>,
+    /// Keys every agent's source frontmatter must set.
+    pub required_fields: Vec<String>,
+    /// Upper bound on the deployed description, in characters.
+    pub max_description_length: Option<usize>,
+}
+
+impl Policy {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(content).map_err(|e| format!("invalid policy: {e}"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        Self::parse(&content).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// The policy file in effect: `$FORGE_POLICY` (which must exist), else
+    /// `<forge_home>/policy.yaml` if present.
+    pub fn path(forge_home: &Path) -> Option<PathBuf> {
+        match std::env::var(POLICY_ENV) {
+            Ok(path) if !path.is_empty() => Some(PathBuf::from(path)),
+            _ => Some(forge_home.join(POLICY_FILE)).filter(|p| p.is_file()),
+        }
+    }
+
+    /// Load the policy in effect (see [`Policy::path`]); none is an empty
+    /// policy that allows everything.
+    pub fn locate(forge_home: &Path) -> Result<Self, String> {
+        Self::path(forge_home).map_or_else(|| Ok(Self::default()), |p| Self::load(&p))
+    }
+
+    /// Every way `meta` (rendered from source `content`) breaks the policy.
+    pub fn violations(&self, meta: &AgentMeta, content: &str) -> Vec<String> {
+        let mut found = Vec::new();
+        for tool in meta.tools.iter().flat_map(|t| t.split(',')).map(str::trim) {
+            if self
+                .banned_tools
+                .iter()
+                .any(|b| b.eq_ignore_ascii_case(tool))
+            {
+                found.push(format!("tool {tool} is banned"));
+            }
+        }
+        if self
+            .banned_models
+            .iter()
+            .any(|pattern| model_matches(pattern, &meta.model))
+        {
+            found.push(format!("model {} is banned", meta.model));
+        }
+        for field in &self.required_fields {
+            if parse::fm_value(content, field).is_none_or(|v| v.is_empty()) {
+                found.push(format!("required field {field} is missing"));
+            }
+        }
+        if let Some(max) = self.max_description_length {
+            let len = meta.description.chars().count();
+            if len > max {
+                found.push(format!("description is {len} characters (max {max})"));
+            }
+        }
+        found
+    }
+
+    /// [`Policy::violations`] as a deploy error.
+    pub fn check(&self, meta: &AgentMeta, content: &str) -> Result<(), String> {
+        let found = self.violations(meta, content);
+        if found.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "{} violates organization policy: {}",
+            meta.name,
+            found.join("; ")
+        ))
+    }
+}
+
+fn model_matches(pattern: &str, model: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model.starts_with(prefix),
+        None => pattern == model,
+    }
+}
+
+#[cfg(test)]
+mod tests;
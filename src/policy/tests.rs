@@ -0,0 +1,88 @@
+use super::*;
+use crate::deploy::extract_agent_meta;
+use crate::deploy::provider::Provider;
+use crate::sidecar::SidecarConfig;
+use tempfile::TempDir;
+
+const AGENT: &str = "\
+---
+name: Dev
+description: Developer. USE WHEN code.
+claude.model: opus
+claude.tools: Read, Bash
+---
+Body.
+";
+
+fn meta(content: &str) -> AgentMeta {
+    extract_agent_meta(
+        content,
+        "Dev.md",
+        &Provider::Claude,
+        &SidecarConfig::default(),
+        "",
+    )
+    .unwrap()
+}
+
+#[test]
+fn empty_policy_allows_everything() {
+    let policy = Policy::parse("").unwrap();
+    assert_eq!(policy, Policy::default());
+    assert!(policy.check(&meta(AGENT), AGENT).is_ok());
+}
+
+#[test]
+fn reports_every_violation() {
+    let policy = Policy::parse(
+        "banned_tools: [Bash]\nbanned_models: [op*]\nrequired_fields: [version]\nmax_description_length: 10\n",
+    )
+    .unwrap();
+    assert_eq!(
+        policy.violations(&meta(AGENT), AGENT),
+        vec![
+            "tool Bash is banned",
+            "model opus is banned",
+            "required field version is missing",
+            "description is 25 characters (max 10)",
+        ]
+    );
+    let err = policy.check(&meta(AGENT), AGENT).unwrap_err();
+    assert!(err.starts_with("Dev violates organization policy: tool Bash"));
+}
+
+#[test]
+fn banned_tools_ignore_case() {
+    let policy = Policy::parse("banned_tools: [bash, WEBFETCH]\n").unwrap();
+    assert_eq!(
+        policy.violations(&meta(AGENT), AGENT),
+        vec!["tool Bash is banned"]
+    );
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    assert!(Policy::parse("banned_tool: [Bash]\n")
+        .unwrap_err()
+        .contains("unknown field"));
+}
+
+#[test]
+fn deploy_fails_on_violation() {
+    let tmp = TempDir::new().unwrap();
+    let config = SidecarConfig::default().with_policy(Policy {
+        banned_tools: vec!["Bash".into()],
+        ..Policy::default()
+    });
+    let err = crate::deploy::deploy_agent(
+        AGENT,
+        "Dev.md",
+        tmp.path(),
+        &config,
+        &crate::deploy::DeployOptions::new(Provider::Claude),
+    )
+    .unwrap_err();
+    assert!(matches!(err, crate::error::ForgeError::Validation(_)));
+    assert!(err.to_string().contains("tool Bash is banned"));
+    assert!(!tmp.path().join("Dev.md").exists());
+}
@@ -0,0 +1,27 @@
+//! The supported library surface, for programs that embed forge:
+//! `use forge_lib::prelude::*;`.
+//!
+//! These types, and the `pub` functions of the modules defining them
+//! (`deploy`, `skill`, `frontmatter`, `sidecar`, `validate`, `policy`,
+//! `progress`, `vfs`, `workspace`), follow semver: within a major version (a
+//! minor version while forge-lib is 0.x) they keep their names, fields, and
+//! variants, and new fields or variants are only added to
+//! `#[non_exhaustive]` types.
+//! Other modules are implementation detail of the CLIs and may change in
+//! any release.
+
+pub use crate::deploy::provider::Provider;
+pub use crate::deploy::{
+    AgentAction, AgentMeta, DeployOptions, DeployPlan, DeployResult, PlannedFile,
+};
+pub use crate::error::ForgeError;
+pub use crate::frontmatter::{AgentFrontmatter, SkillFrontmatter};
+pub use crate::policy::Policy;
+pub use crate::progress::{NoProgress, ProgressSink};
+pub use crate::sidecar::{Provenance, SidecarConfig};
+pub use crate::skill::{SkillInstallAction, SkillMeta};
+pub use crate::template::Templates;
+#[cfg(feature = "validate")]
+pub use crate::validate::{Check, Suite};
+pub use crate::vfs::{MemoryFs, RealFs, Vfs};
+pub use crate::workspace::{Module, Workspace};
@@ -0,0 +1,57 @@
+//! Progress callbacks for long library operations (agent deploys, skill
+//! copies, validation), so a CLI progress line, a JSON event stream, or a
+//! GUI can follow the work without parsing stdout.
+//!
+//! Each operation reports `on_start` once, `on_item` per finished item, and
+//! `on_done` once, even when it ends early with an error.
+
+/// Observer of a long operation. Every method defaults to doing nothing.
+pub trait ProgressSink {
+    /// `operation` (`deploy`, `skill-copy`, `validate`) begins with `total`
+    /// items.
+    fn on_start(&mut self, operation: &str, total: usize) {
+        let _ = (operation, total);
+    }
+
+    /// One item of `operation` finished with `outcome`, e.g. a deploy result
+    /// (`deployed`, `skipped-user-owned`) or a suite's `pass`/`fail`.
+    fn on_item(&mut self, operation: &str, item: &str, outcome: &str) {
+        let _ = (operation, item, outcome);
+    }
+
+    fn on_done(&mut self, operation: &str) {
+        let _ = operation;
+    }
+}
+
+/// A sink for callers that do not follow progress.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {}
+
+/// Calls `on_done` when dropped, so early returns still close the operation.
+pub(crate) struct Operation<'a> {
+    sink: &'a mut dyn ProgressSink,
+    name: &'static str,
+}
+
+impl<'a> Operation<'a> {
+    pub(crate) fn start(sink: &'a mut dyn ProgressSink, name: &'static str, total: usize) -> Self {
+        sink.on_start(name, total);
+        Self { sink, name }
+    }
+
+    pub(crate) fn item(&mut self, item: &str, outcome: &str) {
+        self.sink.on_item(self.name, item, outcome);
+    }
+}
+
+impl Drop for Operation<'_> {
+    fn drop(&mut self) {
+        self.sink.on_done(self.name);
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,105 @@
+use super::*;
+use crate::deploy::{self, provider::Provider, DeployOptions};
+use crate::sidecar::SidecarConfig;
+use crate::vfs::RealFs;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// An agents directory inside a temporary one, which also holds the
+/// `.forge-state` kept beside it.
+fn agents_dir(tmp: &TempDir) -> PathBuf {
+    let dir = tmp.path().join("agents");
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl ProgressSink for Events {
+    fn on_start(&mut self, operation: &str, total: usize) {
+        self.0.push(format!("start {operation} {total}"));
+    }
+
+    fn on_item(&mut self, operation: &str, item: &str, outcome: &str) {
+        self.0.push(format!("{operation} {item} {outcome}"));
+    }
+
+    fn on_done(&mut self, operation: &str) {
+        self.0.push(format!("done {operation}"));
+    }
+}
+
+#[test]
+fn deploy_reports_each_agent() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    fs::write(
+        src.path().join("Dev.md"),
+        "---\nclaude.name: Dev\n---\nBody.\n",
+    )
+    .unwrap();
+    fs::write(src.path().join("_Template.md"), "---\nname: T\n---\n").unwrap();
+    let mut events = Events::default();
+    deploy::deploy_agents_from_dir(
+        src.path(),
+        dst.as_path(),
+        &SidecarConfig::default(),
+        &DeployOptions::new(Provider::Claude),
+        &mut events,
+    )
+    .unwrap();
+    assert_eq!(
+        events.0,
+        vec![
+            "start deploy 2",
+            "deploy Dev.md deployed",
+            "deploy _Template.md skipped-template",
+            "done deploy",
+        ]
+    );
+}
+
+#[test]
+fn skill_copy_reports_each_file() {
+    let src = TempDir::new().unwrap();
+    let dst = TempDir::new().unwrap();
+    let skill = src.path().join("Demo");
+    fs::create_dir_all(skill.join("refs")).unwrap();
+    fs::write(skill.join("SKILL.md"), "---\nname: Demo\n---\n").unwrap();
+    fs::write(skill.join("SKILL.yaml"), "claude: {}\n").unwrap();
+    fs::write(skill.join("refs/guide.md"), "Guide.\n").unwrap();
+    let mut events = Events::default();
+    crate::skill::execute_skill_copy(&RealFs, &skill, "Demo", dst.path(), &mut events).unwrap();
+    assert_eq!(events.0.first().unwrap(), "start skill-copy 2");
+    assert!(events
+        .0
+        .contains(&"skill-copy refs/guide.md copied".to_string()));
+    assert_eq!(events.0.last().unwrap(), "done skill-copy");
+    assert_eq!(events.0.len(), 4);
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn validate_reports_each_suite() {
+    let dir = TempDir::new().unwrap();
+    let mut events = Events::default();
+    let suites = crate::validate::validate_all(dir.path(), &mut events);
+    assert_eq!(events.0[0], format!("start validate {}", suites.len()));
+    assert_eq!(events.0[1], "validate Module Structure fail");
+    assert_eq!(events.0.len(), suites.len() + 2);
+    assert_eq!(events.0.last().unwrap(), "done validate");
+}
+
+#[test]
+fn done_is_reported_when_the_operation_fails() {
+    let dir = TempDir::new().unwrap();
+    let mut events = Events::default();
+    let missing = dir.path().join("missing");
+    let result =
+        crate::skill::execute_skill_copy(&RealFs, &missing, "Demo", dir.path(), &mut events);
+    assert!(result.is_err());
+    assert_eq!(events.0, vec!["start skill-copy 0", "done skill-copy"]);
+}
@@ -0,0 +1,597 @@
+//! Dot-path queries into parsed YAML, as used by the `yaml` CLI: `.agents`,
+//! `.skills.claude.DebateCouncil.scope`, `.modules[0].name`. The leading
+//! dot is optional. A `*` segment (or `[*]`) matches every value of a
+//! mapping or item of a list, so `.agents.*.model` has one match per agent,
+//! and `..scope` matches every `scope` key at any depth. List indices can
+//! count from the end (`[-1]` is the last item) and slice a range (`[1:3]`,
+//! `[:2]`, `[-2:]`), as in Python. `[?key=value]` (or `!=`) keeps the items
+//! of a list, or values of a mapping, whose `key` (itself a path) has that
+//! scalar value: `.modules[?enabled=true].name`. A quoted key in brackets
+//! is taken literally, dots and brackets included: `.["claude.name"]`.
+//! [`set`] writes a value back at a path, [`delete`] removes one,
+//! [`sort_keys`] orders mapping keys, [`leaves`] flattens a document into
+//! paths, and [`diff`] lists the paths where two documents differ;
+//! [`set_in_source`], [`delete_in_source`], and [`sort_keys_in_source`]
+//! make the same edits to YAML text, keeping its comments, and
+//! [`quote_aliases`] keeps `*alias` references visible. Pure text in,
+//! values out, so it also runs in WASM.
+
+use serde_yaml::{Mapping, Value};
+use std::ops::Range;
+
+mod source;
+
+pub use source::{
+    delete_in_source, quote_aliases, set_in_source, sort_keys_in_source, split_documents,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+    /// `[-n]`: the n-th item from the end of a list (`[-1]` is the last).
+    FromEnd(usize),
+    /// `[start:end]`: list items from `start` up to but not including
+    /// `end`; a missing bound is the list's start or end, a negative one
+    /// counts from the end.
+    Slice(Option<isize>, Option<isize>),
+    /// `*`: every child of a mapping or list.
+    Wildcard,
+    /// `..key`: the value of `key` in every mapping at any depth, this one
+    /// included.
+    Descendant(String),
+    /// `[?path=value]` / `[?path!=value]`: the children whose value at
+    /// `path` is (or is not) the scalar `value`.
+    Filter {
+        path: Vec<PathSegment>,
+        value: String,
+        equal: bool,
+    },
+}
+
+impl PathSegment {
+    fn key(key: &str) -> Self {
+        if key == "*" {
+            Self::Wildcard
+        } else {
+            Self::Key(key.to_string())
+        }
+    }
+
+    /// The segment inside `[...]`: `*`, `N`, `-N`, `start:end`, or a quoted
+    /// key taken literally (`["claude.name"]`).
+    fn bracket(inner: &str) -> Option<Self> {
+        let bound = |b: &str| -> Option<Option<isize>> {
+            if b.is_empty() {
+                Some(None)
+            } else {
+                b.parse().ok().map(Some)
+            }
+        };
+        if let Some(key) = quoted(inner) {
+            Some(Self::Key(key.to_string()))
+        } else if inner == "*" {
+            Some(Self::Wildcard)
+        } else if let Some(filter) = inner.strip_prefix('?') {
+            let (path, value, equal) = match filter.split_once("!=") {
+                Some((path, value)) => (path, value, false),
+                None => filter.split_once('=').map(|(p, v)| (p, v, true))?,
+            };
+            Some(Self::Filter {
+                path: parse_path(path.trim()),
+                value: unquote(value.trim()).to_string(),
+                equal,
+            })
+        } else if let Some((start, end)) = inner.split_once(':') {
+            Some(Self::Slice(bound(start)?, bound(end)?))
+        } else if let Some(n) = inner.strip_prefix('-') {
+            n.parse().ok().filter(|n| *n > 0).map(Self::FromEnd)
+        } else {
+            inner.parse().ok().map(Self::Index)
+        }
+    }
+
+    /// Whether a filter keeps `child`.
+    fn keeps(&self, child: &Value) -> bool {
+        let Self::Filter { path, value, equal } = self else {
+            return true;
+        };
+        let found = walk(child, path).and_then(|v| match v {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            Value::Null => Some("null".to_string()),
+            _ => None,
+        });
+        (found.as_deref() == Some(value.as_str())) == *equal
+    }
+
+    /// The items of a list of `len` that `[-n]` or a slice selects.
+    fn range(&self, len: usize) -> Range<usize> {
+        let bound = |b: Option<isize>, default: usize| match b {
+            None => default,
+            Some(b) if b < 0 => len.saturating_sub(b.unsigned_abs()),
+            Some(b) => b.unsigned_abs().min(len),
+        };
+        match self {
+            Self::FromEnd(n) if *n <= len => len - n..len - n + 1,
+            Self::Slice(start, end) => {
+                let start = bound(*start, 0);
+                start..bound(*end, len).max(start)
+            }
+            _ => 0..0,
+        }
+    }
+}
+
+pub fn parse_path(path: &str) -> Vec<PathSegment> {
+    let path = path.strip_prefix('.').unwrap_or(path);
+    if path.is_empty() {
+        return vec![];
+    }
+
+    let mut segments = Vec::new();
+    // An empty part is the second dot of `..key`.
+    let mut descend = false;
+    for part in split_outside_brackets(path) {
+        if part.is_empty() {
+            descend = true;
+            continue;
+        }
+        let (key, suffix) = part.find('[').map_or((part, ""), |b| part.split_at(b));
+        if !key.is_empty() {
+            segments.push(if descend {
+                PathSegment::Descendant(key.to_string())
+            } else {
+                PathSegment::key(key)
+            });
+        }
+        descend = false;
+        // Parse all [N] suffixes: field[0][1]
+        segments.extend(bracket_groups(suffix).filter_map(PathSegment::bracket));
+    }
+    segments
+}
+
+/// `path` split at the dots outside `[...]`, so a filter such as
+/// `[?version=1.2]` or a quoted key such as `["claude.name"]` stays in one
+/// part.
+fn split_outside_brackets(path: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    let mut quote = None;
+    for (i, c) in path.char_indices() {
+        match c {
+            _ if quote == Some(c) => quote = None,
+            _ if quote.is_some() => {}
+            '"' | '\'' if depth > 0 => quote = Some(c),
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            '.' if depth == 0 => {
+                parts.push(&path[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&path[start..]);
+    parts
+}
+
+/// The contents of each top-level `[...]` in `suffix`, stopping at one
+/// left unclosed. Brackets inside quotes do not count.
+fn bracket_groups(suffix: &str) -> impl Iterator<Item = &str> {
+    let mut groups = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    let mut quote = None;
+    for (i, c) in suffix.char_indices() {
+        match c {
+            _ if quote == Some(c) => quote = None,
+            _ if quote.is_some() => {}
+            '"' | '\'' if depth > 0 => quote = Some(c),
+            '[' => {
+                if depth == 0 {
+                    start = i + 1;
+                }
+                depth += 1;
+            }
+            ']' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    groups.push(&suffix[start..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    groups.into_iter()
+}
+
+/// `value` without one pair of surrounding single or double quotes.
+fn unquote(value: &str) -> &str {
+    quoted(value).unwrap_or(value)
+}
+
+/// The text inside `value` when it is wrapped in single or double quotes.
+fn quoted(value: &str) -> Option<&str> {
+    ['"', '\'']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+}
+
+/// The first node matching `segments` under `doc`, borrowed; callers clone
+/// only what they keep.
+pub fn walk<'a>(doc: &'a Value, segments: &[PathSegment]) -> Option<&'a Value> {
+    walk_all(doc, segments).into_iter().next()
+}
+
+/// Every node matching `segments` under `doc`, in document order. Without a
+/// wildcard there is at most one.
+pub fn walk_all<'a>(doc: &'a Value, segments: &[PathSegment]) -> Vec<&'a Value> {
+    segments.iter().fold(vec![doc], |nodes, seg| {
+        nodes
+            .into_iter()
+            .flat_map(|node| match seg {
+                PathSegment::Key(k) => node.get(k.as_str()).into_iter().collect(),
+                PathSegment::Index(i) => node.get(*i).into_iter().collect(),
+                PathSegment::FromEnd(_) | PathSegment::Slice(..) => match node {
+                    Value::Sequence(items) => items[seg.range(items.len())].iter().collect(),
+                    _ => Vec::new(),
+                },
+                PathSegment::Wildcard => children(node),
+                PathSegment::Filter { .. } => children(node)
+                    .into_iter()
+                    .filter(|c| seg.keeps(c))
+                    .collect(),
+                PathSegment::Descendant(k) => {
+                    let mut found = Vec::new();
+                    descendants(node, k, &mut found);
+                    found
+                }
+            })
+            .collect()
+    })
+}
+
+/// The values of `key` in `node` and every mapping below it, in document
+/// order.
+fn descendants<'a>(node: &'a Value, key: &str, found: &mut Vec<&'a Value>) {
+    if let Some(v) = node.as_mapping().and_then(|map| map.get(key)) {
+        found.push(v);
+    }
+    for child in children(node) {
+        descendants(child, key, found);
+    }
+}
+
+/// The value at dot-path `path` in `doc`, if every segment exists.
+pub fn get<'a>(doc: &'a Value, path: &str) -> Option<&'a Value> {
+    walk(doc, &parse_path(path))
+}
+
+/// Replace the node at `segments` under `doc` with `value`, creating
+/// missing (or null) keys as mappings on the way. An index may name an
+/// existing item or the position just past the end, which appends. A
+/// wildcard, slice, or filter sets the path under every existing child it
+/// selects; `..key` cannot be set.
+pub fn set(doc: &mut Value, segments: &[PathSegment], value: Value) -> Result<(), String> {
+    match segments.split_first() {
+        None => {
+            *doc = value;
+            Ok(())
+        }
+        Some((seg @ (PathSegment::Wildcard | PathSegment::Filter { .. }), rest)) => {
+            children_mut(doc)
+                .into_iter()
+                .filter(|child| seg.keeps(child))
+                .try_for_each(|child| set(child, rest, value.clone()))
+        }
+        Some((seg @ PathSegment::Slice(..), rest)) => match doc {
+            Value::Sequence(items) => {
+                let range = seg.range(items.len());
+                items[range]
+                    .iter_mut()
+                    .try_for_each(|child| set(child, rest, value.clone()))
+            }
+            _ => Err(format!(
+                "cannot set {}: parent is not a list",
+                seg_text(seg)
+            )),
+        },
+        Some((seg, rest)) => set(child_mut(doc, seg)?, rest, value),
+    }
+}
+
+/// Remove every node matching `segments` from `doc`, returning them. A path
+/// that does not exist removes nothing; the empty path (the whole
+/// document) cannot be removed. `..key` is only allowed as the last
+/// segment, where it removes `key` at every depth.
+pub fn delete(doc: &mut Value, segments: &[PathSegment]) -> Vec<Value> {
+    let Some((last, parents)) = segments.split_last() else {
+        return Vec::new();
+    };
+    walk_all_mut(doc, parents)
+        .into_iter()
+        .flat_map(|parent| match (parent, last) {
+            (Value::Mapping(map), PathSegment::Key(k)) => {
+                map.shift_remove(k.as_str()).into_iter().collect()
+            }
+            (Value::Sequence(items), PathSegment::Index(i)) if *i < items.len() => {
+                vec![items.remove(*i)]
+            }
+            (Value::Mapping(map), PathSegment::Wildcard) => {
+                std::mem::take(map).into_iter().map(|(_, v)| v).collect()
+            }
+            (Value::Sequence(items), seg @ (PathSegment::FromEnd(_) | PathSegment::Slice(..))) => {
+                let range = seg.range(items.len());
+                items.drain(range).collect()
+            }
+            (Value::Sequence(items), PathSegment::Wildcard) => std::mem::take(items),
+            (Value::Sequence(items), seg @ PathSegment::Filter { .. }) => {
+                let (removed, kept) = std::mem::take(items)
+                    .into_iter()
+                    .partition(|item| seg.keeps(item));
+                *items = kept;
+                removed
+            }
+            (Value::Mapping(map), seg @ PathSegment::Filter { .. }) => {
+                let (removed, kept): (Mapping, Mapping) = std::mem::take(map)
+                    .into_iter()
+                    .partition(|(_, v)| seg.keeps(v));
+                *map = kept;
+                removed.into_iter().map(|(_, v)| v).collect()
+            }
+            (node, PathSegment::Descendant(k)) => {
+                let mut removed = Vec::new();
+                remove_descendants(node, k, &mut removed);
+                removed
+            }
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Sort the keys of every mapping matching `segments` under `doc`, and of
+/// every mapping below those, by their text. Returns whether any key moved.
+pub fn sort_keys(doc: &mut Value, segments: &[PathSegment]) -> bool {
+    walk_all_mut(doc, segments)
+        .into_iter()
+        .fold(false, |moved, node| sort_keys_below(node) | moved)
+}
+
+fn sort_keys_below(node: &mut Value) -> bool {
+    let mut moved = children_mut(node)
+        .into_iter()
+        .fold(false, |moved, child| sort_keys_below(child) | moved);
+    if let Value::Mapping(map) = node {
+        let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+        moved |= !entries.is_sorted_by(|(a, _), (b, _)| key_text(a) <= key_text(b));
+        entries.sort_by_key(|(k, _)| key_text(k));
+        *map = entries.into_iter().collect();
+    }
+    moved
+}
+
+fn children(node: &Value) -> Vec<&Value> {
+    match node {
+        Value::Mapping(map) => map.values().collect(),
+        Value::Sequence(items) => items.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn children_mut(node: &mut Value) -> Vec<&mut Value> {
+    match node {
+        Value::Mapping(map) => map.values_mut().collect(),
+        Value::Sequence(items) => items.iter_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn remove_descendants(node: &mut Value, key: &str, removed: &mut Vec<Value>) {
+    if let Some(v) = node.as_mapping_mut().and_then(|map| map.shift_remove(key)) {
+        removed.push(v);
+    }
+    for child in children_mut(node) {
+        remove_descendants(child, key, removed);
+    }
+}
+
+/// Every node matching `segments` under `node`. Matches of `..key` can
+/// nest, so it matches nothing here.
+fn walk_all_mut<'a>(node: &'a mut Value, segments: &[PathSegment]) -> Vec<&'a mut Value> {
+    let Some((seg, rest)) = segments.split_first() else {
+        return vec![node];
+    };
+    let next = match seg {
+        PathSegment::Key(k) => node.get_mut(k.as_str()).into_iter().collect(),
+        PathSegment::Index(i) => node.get_mut(*i).into_iter().collect(),
+        PathSegment::FromEnd(_) | PathSegment::Slice(..) => match node {
+            Value::Sequence(items) => {
+                let range = seg.range(items.len());
+                items[range].iter_mut().collect()
+            }
+            _ => Vec::new(),
+        },
+        PathSegment::Wildcard => children_mut(node),
+        PathSegment::Filter { .. } => children_mut(node)
+            .into_iter()
+            .filter(|child| seg.keeps(child))
+            .collect(),
+        PathSegment::Descendant(_) => Vec::new(),
+    };
+    next.into_iter()
+        .flat_map(|child| walk_all_mut(child, rest))
+        .collect()
+}
+
+/// The child at `seg` (a key or index), created as null when missing.
+fn child_mut<'a>(node: &'a mut Value, seg: &PathSegment) -> Result<&'a mut Value, String> {
+    if node.is_null() {
+        *node = Value::Mapping(Mapping::new());
+    }
+    match (node, seg) {
+        (Value::Mapping(map), PathSegment::Key(k)) => {
+            Ok(map.entry(Value::String(k.clone())).or_insert(Value::Null))
+        }
+        (Value::Sequence(items), PathSegment::Index(i)) => {
+            if *i == items.len() {
+                items.push(Value::Null);
+            }
+            let len = items.len();
+            items
+                .get_mut(*i)
+                .ok_or_else(|| format!("index [{i}] is out of range (length {len})"))
+        }
+        (Value::Sequence(items), seg @ PathSegment::FromEnd(n)) => {
+            let range = seg.range(items.len());
+            let len = items.len();
+            items
+                .get_mut(range.start)
+                .filter(|_| !range.is_empty())
+                .ok_or_else(|| format!("index [-{n}] is out of range (length {len})"))
+        }
+        (_, PathSegment::Index(i)) => Err(format!("cannot set index [{i}]: parent is not a list")),
+        (_, seg @ (PathSegment::FromEnd(_) | PathSegment::Slice(..))) => Err(format!(
+            "cannot set {}: parent is not a list",
+            seg_text(seg)
+        )),
+        (_, PathSegment::Key(k)) => Err(format!("cannot set key {k:?}: parent is not a mapping")),
+        (_, PathSegment::Wildcard | PathSegment::Filter { .. }) => {
+            Err("a wildcard or filter cannot create nodes".to_string())
+        }
+        (_, PathSegment::Descendant(k)) => Err(format!("cannot set ..{k}: use an exact path")),
+    }
+}
+
+/// `seg` as written in a path, for messages about list selectors.
+fn seg_text(seg: &PathSegment) -> String {
+    let bound = |b: &Option<isize>| b.map(|b| b.to_string()).unwrap_or_default();
+    match seg {
+        PathSegment::Key(k) => format!(".{k}"),
+        PathSegment::Index(i) => format!("[{i}]"),
+        PathSegment::FromEnd(n) => format!("[-{n}]"),
+        PathSegment::Slice(start, end) => format!("[{}:{}]", bound(start), bound(end)),
+        PathSegment::Wildcard => "*".to_string(),
+        PathSegment::Descendant(k) => format!("..{k}"),
+        PathSegment::Filter { path, value, equal } => {
+            let path: String = path.iter().map(seg_text).collect();
+            let op = if *equal { "=" } else { "!=" };
+            format!("[?{}{op}{value}]", path.trim_start_matches('.'))
+        }
+    }
+}
+
+/// Every leaf of `doc` with its path, in document order: scalars, nulls,
+/// and empty mappings or lists. The root is `.`.
+pub fn leaves(doc: &Value) -> Vec<(String, &Value)> {
+    let mut out = Vec::new();
+    leaves_at(String::new(), doc, &mut out);
+    out
+}
+
+fn leaves_at<'a>(path: String, node: &'a Value, out: &mut Vec<(String, &'a Value)>) {
+    match node {
+        Value::Mapping(map) if !map.is_empty() => {
+            for (k, v) in map {
+                leaves_at(format!("{path}{}", key_segment(k)), v, out);
+            }
+        }
+        Value::Sequence(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                leaves_at(format!("{path}[{i}]"), item, out);
+            }
+        }
+        _ => out.push((
+            if path.is_empty() {
+                ".".to_string()
+            } else {
+                path
+            },
+            node,
+        )),
+    }
+}
+
+/// How one path differs between two documents, as found by [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    Added(Value),
+    Removed(Value),
+    Changed(Value, Value),
+}
+
+/// Paths that differ from `old` to `new`, ignoring formatting and comments:
+/// keys on one side only, list items past the shorter list, and values that
+/// changed. Lists compare item by item, by position. The root is `.`.
+pub fn diff(old: &Value, new: &Value) -> Vec<(String, Difference)> {
+    let mut out = Vec::new();
+    diff_at(String::new(), old, new, &mut out);
+    out
+}
+
+fn diff_at(path: String, old: &Value, new: &Value, out: &mut Vec<(String, Difference)>) {
+    match (old, new) {
+        (Value::Mapping(a), Value::Mapping(b)) => {
+            for (k, v) in a {
+                let child = format!("{path}{}", key_segment(k));
+                match b.get(k) {
+                    Some(w) => diff_at(child, v, w, out),
+                    None => out.push((child, Difference::Removed(v.clone()))),
+                }
+            }
+            for (k, w) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                let child = format!("{path}{}", key_segment(k));
+                out.push((child, Difference::Added(w.clone())));
+            }
+        }
+        (Value::Sequence(a), Value::Sequence(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{path}[{i}]");
+                match (a.get(i), b.get(i)) {
+                    (Some(v), Some(w)) => diff_at(child, v, w, out),
+                    (Some(v), None) => out.push((child, Difference::Removed(v.clone()))),
+                    (None, Some(w)) => out.push((child, Difference::Added(w.clone()))),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => {
+            let path = if path.is_empty() {
+                ".".to_string()
+            } else {
+                path
+            };
+            out.push((path, Difference::Changed(old.clone(), new.clone())));
+        }
+        _ => {}
+    }
+}
+
+/// The path segment naming mapping key `key`: `.key`, or `["key"]` when
+/// the key would otherwise read as path syntax.
+fn key_segment(key: &Value) -> String {
+    let text = key_text(key);
+    if text.is_empty()
+        || text == "*"
+        || text.starts_with(['"', '\''])
+        || text.contains(['.', '[', ']'])
+    {
+        let q = if text.contains('"') { '\'' } else { '"' };
+        format!("[{q}{text}{q}]")
+    } else {
+        format!(".{text}")
+    }
+}
+
+fn key_text(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,875 @@
+//! [`set`], [`delete`], and [`sort_keys`] applied to YAML source text
+//! rather than parsed values, so comments, key order, quoting, and blank
+//! lines survive everywhere but the edited node.
+//!
+//! Mappings and lists reached by keys and indices are edited in place.
+//! An edit inside a flow collection (`[a, b]`, `{a: 1}`) rewrites that
+//! collection on one line, still in flow style. Anchors, tags, block
+//! scalars, and wildcard or filter paths return `None`, and so does any
+//! edit whose result does not parse back to what the structural edit
+//! produces; the caller then decides whether to re-serialize the document.
+
+use super::{delete, set, sort_keys, PathSegment};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use std::fmt::Write as _;
+use std::ops::Range;
+
+/// `source` (one YAML document) with `value` written at `segments`, or
+/// `None` when that cannot be done in place.
+pub fn set_in_source(source: &str, segments: &[PathSegment], value: &Value) -> Option<String> {
+    let mut expected = parse_one(source)?;
+    set(&mut expected, segments, value.clone()).ok()?;
+    let lines = Lines::new(source);
+    let (range, new) = match lines.root() {
+        Some(root) => lines.set_at(&root, segments, value)?,
+        None => (lines.len()..lines.len(), render(&nest(segments, value)?)),
+    };
+    lines.checked(range, new, &expected)
+}
+
+/// `source` (one YAML document) with the node at `segments` removed, or
+/// `None` when that cannot be done in place. A path that does not exist
+/// leaves `source` as it is.
+pub fn delete_in_source(source: &str, segments: &[PathSegment]) -> Option<String> {
+    let mut expected = parse_one(source)?;
+    delete(&mut expected, segments);
+    let lines = Lines::new(source);
+    let (range, new) = match lines.root() {
+        Some(root) if !segments.is_empty() => lines.delete_at(&root, segments)?,
+        _ => (0..0, Vec::new()),
+    };
+    lines.checked(range, new, &expected)
+}
+
+/// `source` (one YAML document) with the keys of the mapping at `segments`,
+/// and of every mapping below it, sorted; each entry moves with the
+/// comments directly above it. `None` when that cannot be done in place.
+pub fn sort_keys_in_source(source: &str, segments: &[PathSegment]) -> Option<String> {
+    let mut expected = parse_one(source)?;
+    sort_keys(&mut expected, segments);
+    let mut lines = Lines::new(source);
+    if let Some(root) = lines.root() {
+        let (node, start) = lines.node_at(root, 0, segments)?;
+        lines.sort_keys_at(&node, start)?;
+    }
+    let text = lines.into_text();
+    // Mappings compare equal in any order, so compare the serialized forms.
+    let sorted = serde_yaml::to_string(&parse_one(&text)?).ok()?;
+    (sorted == serde_yaml::to_string(&expected).ok()?).then_some(text)
+}
+
+/// `source` cut into one piece per `---` separated document, each piece
+/// starting at its `---` line. Comments before the first `---` stay with
+/// the first document; an empty source is one empty document.
+pub fn split_documents(source: &str) -> Vec<String> {
+    let mut docs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for line in source.split_inclusive('\n') {
+        if is_document_marker(line) && has_content(&current) {
+            docs.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+    }
+    if has_content(&current) || docs.is_empty() {
+        docs.push(current);
+    } else if let Some(last) = docs.last_mut() {
+        last.push_str(&current);
+    }
+    docs
+}
+
+/// `source` with every alias (`*name`) turned into the quoted string
+/// `"*name"`, so a parsed document shows where aliases are instead of
+/// expanding them. Quoted strings, comments, and block scalars are left
+/// alone.
+pub fn quote_aliases(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut block: Option<usize> = None;
+    for line in source.split_inclusive('\n') {
+        let indent = indent(line);
+        match (block, indent) {
+            (Some(parent), Some(i)) if i > parent => {
+                out.push_str(line);
+                continue;
+            }
+            (Some(_), None) => {
+                out.push_str(line);
+                continue;
+            }
+            _ => block = None,
+        }
+        let quoted = quote_line_aliases(line);
+        if split_comment(quoted.trim_end())
+            .0
+            .rsplit([' ', '\t'])
+            .next()
+            .is_some_and(is_block_indicator)
+        {
+            block = indent;
+        }
+        out.push_str(&quoted);
+    }
+    out
+}
+
+/// Whether `token` starts a block scalar: `|` or `>` with optional
+/// indentation and chomping indicators.
+fn is_block_indicator(token: &str) -> bool {
+    token.starts_with(['|', '>'])
+        && token[1..]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-' || c == '+')
+}
+
+fn quote_line_aliases(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut quote = None;
+    // Whether the next character can start a node (and so an alias).
+    let mut node_start = true;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') if node_start => quote = Some(c),
+            (None, '#') if i == 0 || line[..i].ends_with([' ', '\t']) => {
+                out.push_str(&line[i..]);
+                return out;
+            }
+            (None, '*') if node_start => {
+                let name: String = std::iter::from_fn(|| {
+                    chars.next_if(|(_, c)| !c.is_whitespace() && !",[]{}".contains(*c))
+                })
+                .map(|(_, c)| c)
+                .collect();
+                node_start = false;
+                if name.is_empty() {
+                    out.push('*');
+                    continue;
+                }
+                let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+                let _ = write!(out, "\"*{name}\"");
+                continue;
+            }
+            _ => {}
+        }
+        out.push(c);
+        if quote.is_none() {
+            let next_is_space = chars.peek().is_none_or(|(_, n)| n.is_whitespace());
+            node_start = match c {
+                '[' | '{' | ',' => true,
+                '-' | ':' | '?' => next_is_space && (c != '-' || node_start),
+                ' ' | '\t' => node_start,
+                _ => false,
+            };
+        }
+    }
+    out
+}
+
+fn is_document_marker(line: &str) -> bool {
+    line.strip_prefix("---")
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+/// Whether `text` holds anything but blank lines, comments, directives, and
+/// `---` markers.
+fn has_content(text: &str) -> bool {
+    text.lines().any(|line| indent(line).is_some())
+}
+
+fn parse_one(source: &str) -> Option<Value> {
+    let mut docs = serde_yaml::Deserializer::from_str(source)
+        .map(Value::deserialize)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    match docs.len() {
+        0 => Some(Value::Null),
+        1 => docs.pop(),
+        _ => None,
+    }
+}
+
+/// The column where `line`'s content starts, or `None` for a blank line,
+/// a comment, a directive, or a document marker.
+fn indent(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    let skip = trimmed.is_empty()
+        || trimmed.starts_with('#')
+        || line.starts_with('%')
+        || is_document_marker(line)
+        || line.starts_with("...");
+    (!skip).then(|| line.len() - trimmed.len())
+}
+
+/// Whether the text at a node's column is a `- ` list item.
+fn is_dash(text: &str) -> bool {
+    text.strip_prefix('-')
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+/// `text` split into the value and a trailing ` # comment` (with its
+/// leading whitespace), ignoring `#` inside quotes.
+fn split_comment(text: &str) -> (&str, &str) {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if prev.is_whitespace() => {
+                let value = text[..i].trim_end();
+                return (value, &text[value.len()..]);
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    (text.trim_end(), "")
+}
+
+/// The key of a `key: value` line whose key starts at `col`, with the byte
+/// index of its colon.
+fn parse_key(line: &str, col: usize) -> Option<(String, usize)> {
+    let text = line.get(col..)?;
+    let (key, after) = match text.chars().next()? {
+        q @ ('"' | '\'') => {
+            let end = text[1..].find(q)? + 1;
+            let key = &text[1..end];
+            if key.contains('\\') || text[end + 1..].starts_with(q) {
+                return None;
+            }
+            (key.to_string(), end + 1)
+        }
+        '-' | '{' | '[' | '?' | '&' | '*' | '!' | '#' | '|' | '>' | '%' | '@' | '`' => return None,
+        _ => {
+            let colon = text
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| text[i + 1..].is_empty() || text[i + 1..].starts_with([' ', '\t']))?;
+            (text[..colon].trim_end().to_string(), colon)
+        }
+    };
+    let colon = after + text[after..].len() - text[after..].trim_start().len();
+    let rest = &text[colon..];
+    (rest.starts_with(':') && (rest.len() == 1 || rest[1..].starts_with([' ', '\t'])))
+        .then_some((key, col + colon))
+}
+
+/// A block node: it starts at `col` of line `line` and its lines end before
+/// `end`. An empty node (a key with nothing after it) has no lines.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    line: usize,
+    col: usize,
+    end: usize,
+    empty: bool,
+}
+
+/// A `key: value` entry of a block mapping.
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    line: usize,
+    col: usize,
+    colon: usize,
+    end: usize,
+}
+
+/// A `- value` item of a block list.
+#[derive(Debug)]
+struct Item {
+    line: usize,
+    dash: usize,
+    end: usize,
+}
+
+struct Lines {
+    lines: Vec<String>,
+    newline: bool,
+}
+
+impl Lines {
+    fn new(source: &str) -> Self {
+        Self {
+            lines: source.lines().map(str::to_string).collect(),
+            newline: source.is_empty() || source.ends_with('\n'),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn indent(&self, line: usize) -> Option<usize> {
+        indent(&self.lines[line])
+    }
+
+    fn text(&self, node: &Node) -> &str {
+        &self.lines[node.line][node.col..]
+    }
+
+    /// The first content line at or after `from` and before `end`.
+    fn next_content(&self, from: usize, end: usize) -> Option<usize> {
+        (from..end).find(|&l| self.indent(l).is_some())
+    }
+
+    /// The end of a block that starts on `line` at `col`: the next content
+    /// line indented at most `col` (a `- ` item at exactly `col` still
+    /// belongs to a key, as in `key:\n- a`), less trailing comments and
+    /// blank lines, which stay with what follows.
+    fn block_end(&self, line: usize, col: usize, limit: usize, key: bool) -> usize {
+        let mut end = (line + 1..limit)
+            .find(|&l| match self.indent(l) {
+                Some(i) if i < col => true,
+                Some(i) if i == col => !(key && is_dash(&self.lines[l][i..])),
+                _ => false,
+            })
+            .unwrap_or(limit);
+        while end > line + 1 && self.indent(end - 1).is_none() {
+            end -= 1;
+        }
+        end
+    }
+
+    /// The document's top-level node, or `None` when it is empty.
+    fn root(&self) -> Option<Node> {
+        let line = self.next_content(0, self.len())?;
+        Some(Node {
+            line,
+            col: self.indent(line)?,
+            end: self.len(),
+            empty: false,
+        })
+    }
+
+    /// The node starting after `col` on `line` (past a colon or dash), or
+    /// on the following lines up to `end`.
+    fn value_after(&self, line: usize, col: usize, end: usize) -> Node {
+        let rest = &self.lines[line][col..];
+        let (value, _) = split_comment(rest);
+        if value.trim().is_empty() {
+            return match self.next_content(line + 1, end) {
+                Some(next) => Node {
+                    line: next,
+                    col: self.indent(next).unwrap_or_default(),
+                    end,
+                    empty: false,
+                },
+                None => Node {
+                    line,
+                    col,
+                    end,
+                    empty: true,
+                },
+            };
+        }
+        Node {
+            line,
+            col: col + rest.len() - rest.trim_start().len(),
+            end,
+            empty: false,
+        }
+    }
+
+    /// The entries of a block mapping, or `None` when `node` is not one.
+    fn entries(&self, node: &Node) -> Option<Vec<Entry>> {
+        if node.empty {
+            return None;
+        }
+        let mut entries = Vec::new();
+        let mut line = node.line;
+        loop {
+            let (key, colon) = parse_key(&self.lines[line], node.col)?;
+            let end = self.block_end(line, node.col, node.end, true);
+            entries.push(Entry {
+                key,
+                line,
+                col: node.col,
+                colon,
+                end,
+            });
+            match self.next_content(end, node.end) {
+                Some(next) if self.indent(next) == Some(node.col) => line = next,
+                Some(_) => return None,
+                None => return Some(entries),
+            }
+        }
+    }
+
+    /// The items of a block list, or `None` when `node` is not one.
+    fn items(&self, node: &Node) -> Option<Vec<Item>> {
+        if node.empty || !is_dash(self.text(node)) {
+            return None;
+        }
+        let mut items = Vec::new();
+        let mut line = node.line;
+        loop {
+            if !is_dash(&self.lines[line][node.col..]) {
+                return None;
+            }
+            let end = self.block_end(line, node.col, node.end, false);
+            items.push(Item {
+                line,
+                dash: node.col,
+                end,
+            });
+            match self.next_content(end, node.end) {
+                Some(next) if self.indent(next) == Some(node.col) => line = next,
+                Some(_) => return None,
+                None => return Some(items),
+            }
+        }
+    }
+
+    /// The child node of a block node: a block mapping or list, possibly
+    /// empty, but never a flow collection, anchor, alias, tag, or block
+    /// scalar, which cannot be edited line by line.
+    fn descendable(&self, node: Node) -> Option<Node> {
+        if node.empty
+            || !self
+                .text(&node)
+                .starts_with(['{', '[', '&', '*', '!', '|', '>'])
+        {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    /// The lines to replace, and their replacement, that set `segments`
+    /// under `node` to `value`.
+    fn set_at(
+        &self,
+        node: &Node,
+        segments: &[PathSegment],
+        value: &Value,
+    ) -> Option<(Range<usize>, Vec<String>)> {
+        let (seg, rest) = segments.split_first()?;
+        match seg {
+            PathSegment::Key(key) => {
+                let entries = self.entries(node)?;
+                let Some(entry) = entries.iter().find(|e| e.key == *key) else {
+                    let at = entries.last()?.end;
+                    let head = format!("{}{}:", " ".repeat(node.col), key_text(key)?);
+                    return Some((
+                        at..at,
+                        value_lines(&head, node.col, &nest(rest, value)?, true, ""),
+                    ));
+                };
+                let child = self.value_after(entry.line, entry.colon + 1, entry.end);
+                if rest.is_empty() || child.empty {
+                    let head = &self.lines[entry.line][..=entry.colon];
+                    let comment = self.inline_comment(entry.line, entry.colon + 1, entry.end);
+                    let new = value_lines(head, entry.col, &nest(rest, value)?, true, comment);
+                    return Some((entry.line..entry.end, new));
+                }
+                self.set_below(child, rest, value)
+            }
+            PathSegment::Index(_) | PathSegment::FromEnd(_) => {
+                let items = self.items(node)?;
+                let i = match seg {
+                    PathSegment::FromEnd(n) => items.len().checked_sub(*n)?,
+                    PathSegment::Index(i) => *i,
+                    _ => unreachable!(),
+                };
+                let Some(item) = items.get(i) else {
+                    let at = items.last().filter(|_| i == items.len())?.end;
+                    let head = format!("{}-", " ".repeat(node.col));
+                    return Some((
+                        at..at,
+                        value_lines(&head, node.col, &nest(rest, value)?, false, ""),
+                    ));
+                };
+                let child = self.value_after(item.line, item.dash + 1, item.end);
+                if rest.is_empty() || child.empty {
+                    let head = &self.lines[item.line][..=item.dash];
+                    let comment = self.inline_comment(item.line, item.dash + 1, item.end);
+                    let new = value_lines(head, item.dash, &nest(rest, value)?, false, comment);
+                    return Some((item.line..item.end, new));
+                }
+                self.set_below(child, rest, value)
+            }
+            _ => None,
+        }
+    }
+
+    /// [`Lines::set_at`] for `child`, the value of an entry or item, which
+    /// may be a flow collection.
+    fn set_below(
+        &self,
+        child: Node,
+        segments: &[PathSegment],
+        value: &Value,
+    ) -> Option<(Range<usize>, Vec<String>)> {
+        if self.is_flow(&child) {
+            return self.flow_edit(&child, |flow| set(flow, segments, value.clone()).is_ok());
+        }
+        self.set_at(&self.descendable(child)?, segments, value)
+    }
+
+    /// The lines holding the node at `segments` under `node`, and what
+    /// replaces them: nothing, or a flow collection with the node taken
+    /// out. An empty range when there is no such node, `None` when it
+    /// cannot be removed line by line (it shares its first line with a
+    /// parent list item).
+    fn delete_at(
+        &self,
+        node: &Node,
+        segments: &[PathSegment],
+    ) -> Option<(Range<usize>, Vec<String>)> {
+        let (seg, rest) = segments.split_first()?;
+        if node.empty {
+            return Some((0..0, Vec::new()));
+        }
+        let (line, col, end, child) = match seg {
+            PathSegment::Key(key) => {
+                let Some(entry) = self.entries(node)?.into_iter().find(|e| e.key == *key) else {
+                    return Some((0..0, Vec::new()));
+                };
+                let child = self.value_after(entry.line, entry.colon + 1, entry.end);
+                (entry.line, entry.col, entry.end, child)
+            }
+            PathSegment::Index(_) | PathSegment::FromEnd(_) => {
+                let items = self.items(node)?;
+                let i = match seg {
+                    PathSegment::FromEnd(n) => items.len().checked_sub(*n),
+                    PathSegment::Index(i) => Some(*i),
+                    _ => unreachable!(),
+                };
+                let Some(item) = i.and_then(|i| items.into_iter().nth(i)) else {
+                    return Some((0..0, Vec::new()));
+                };
+                let child = self.value_after(item.line, item.dash + 1, item.end);
+                (item.line, item.dash, item.end, child)
+            }
+            _ => return None,
+        };
+        if rest.is_empty() {
+            return (self.indent(line) == Some(col)).then_some((line..end, Vec::new()));
+        }
+        if self.is_flow(&child) {
+            return self.flow_edit(&child, |flow| {
+                delete(flow, rest);
+                true
+            });
+        }
+        self.delete_at(&self.descendable(child)?, rest)
+    }
+
+    /// The node at `segments` under `node`, with the first line that
+    /// comments attached to its first entry may start on.
+    fn node_at(&self, node: Node, start: usize, segments: &[PathSegment]) -> Option<(Node, usize)> {
+        let Some((seg, rest)) = segments.split_first() else {
+            return Some((node, start));
+        };
+        let (line, child) = match seg {
+            PathSegment::Key(key) => {
+                let entries = self.entries(&node)?;
+                let entry = entries.iter().find(|e| e.key == *key)?;
+                (
+                    entry.line,
+                    self.value_after(entry.line, entry.colon + 1, entry.end),
+                )
+            }
+            PathSegment::Index(_) | PathSegment::FromEnd(_) => {
+                let items = self.items(&node)?;
+                let i = match seg {
+                    PathSegment::FromEnd(n) => items.len().checked_sub(*n)?,
+                    PathSegment::Index(i) => *i,
+                    _ => unreachable!(),
+                };
+                let item = items.get(i)?;
+                (
+                    item.line,
+                    self.value_after(item.line, item.dash + 1, item.end),
+                )
+            }
+            _ => return None,
+        };
+        let start = if child.line == line { line } else { line + 1 };
+        self.node_at(self.descendable(child)?, start, rest)
+    }
+
+    /// Sort the keys of the block mappings in `node` and below, moving each
+    /// entry's lines, with the comments directly above it (but not before
+    /// `start`), as one unit. Blank lines and other comments stay put.
+    fn sort_keys_at(&mut self, node: &Node, start: usize) -> Option<()> {
+        if node.empty {
+            return Some(());
+        }
+        if let Some(items) = self.items(node) {
+            for item in items {
+                let child = self.value_after(item.line, item.dash + 1, item.end);
+                self.sort_child(child, item.line)?;
+            }
+            return Some(());
+        }
+        let Some(entries) = self.entries(node) else {
+            return Some(());
+        };
+        for entry in &entries {
+            let child = self.value_after(entry.line, entry.colon + 1, entry.end);
+            self.sort_child(child, entry.line)?;
+        }
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by(|&a, &b| entries[a].key.cmp(&entries[b].key));
+        if order.iter().enumerate().all(|(i, &j)| i == j) {
+            return Some(());
+        }
+        // A mapping that starts after a list item's dash keeps the dash on
+        // whichever entry ends up first.
+        let inline = self.indent(node.line) != Some(node.col);
+        let mut chunks = Vec::new();
+        let mut floor = start;
+        for entry in &entries {
+            let mut from = entry.line;
+            while !inline && from > floor && self.lines[from - 1].trim_start().starts_with('#') {
+                from -= 1;
+            }
+            chunks.push(from..entry.end);
+            floor = entry.end;
+        }
+        let span = chunks[0].start..chunks[chunks.len() - 1].end;
+        let mut sorted = Vec::new();
+        for (slot, &from) in order.iter().enumerate() {
+            if slot > 0 {
+                sorted.extend_from_slice(&self.lines[chunks[slot - 1].end..chunks[slot].start]);
+            }
+            let mut lines = self.lines[chunks[from].clone()].to_vec();
+            if inline && slot == 0 {
+                lines[0].replace_range(..node.col, &self.lines[node.line][..node.col]);
+            } else if inline && from == 0 {
+                lines[0].replace_range(..node.col, &" ".repeat(node.col));
+            }
+            sorted.extend(lines);
+        }
+        self.lines.splice(span, sorted);
+        Some(())
+    }
+
+    /// Sort below `child`, the value of the entry or item on `line`. A
+    /// flow collection on one line is rewritten sorted; anchors, scalars,
+    /// and flow collections over several lines are left as they are.
+    fn sort_child(&mut self, child: Node, line: usize) -> Option<()> {
+        if self.is_flow(&child) {
+            if let Some((range, new)) = self.flow_edit(&child, |flow| {
+                sort_keys(flow, &[]);
+                true
+            }) {
+                if range.len() == 1 {
+                    self.lines.splice(range, new);
+                }
+            }
+            return Some(());
+        }
+        match self.descendable(child) {
+            Some(child) if !child.empty => {
+                let start = if child.line == line { line } else { line + 1 };
+                self.sort_keys_at(&child, start)
+            }
+            _ => Some(()),
+        }
+    }
+
+    /// Whether `node` is a flow collection (`[a, b]` or `{a: 1}`).
+    fn is_flow(&self, node: &Node) -> bool {
+        !node.empty && self.text(node).starts_with(['[', '{'])
+    }
+
+    /// The line holding the closing bracket of the flow collection that
+    /// starts at `node`, and the byte index just past that bracket.
+    fn flow_end(&self, node: &Node) -> Option<(usize, usize)> {
+        let mut depth = 0usize;
+        let mut quote = None;
+        for line in node.line..node.end {
+            let start = if line == node.line { node.col } else { 0 };
+            let mut prev = ' ';
+            let mut chars = self.lines[line][start..].char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                match (quote, c) {
+                    (Some('"'), '\\') => {
+                        chars.next();
+                    }
+                    (Some('\''), '\'') if chars.next_if(|&(_, n)| n == '\'').is_some() => {}
+                    (Some(q), c) if c == q => quote = None,
+                    (None, '"' | '\'') if matches!(prev, ' ' | '\t' | '[' | '{' | ',' | ':') => {
+                        quote = Some(c);
+                    }
+                    (None, '#') if prev.is_whitespace() => break,
+                    (None, '[' | '{') => depth += 1,
+                    (None, ']' | '}') => {
+                        depth = depth.checked_sub(1)?;
+                        if depth == 0 {
+                            return Some((line, start + i + 1));
+                        }
+                    }
+                    _ => {}
+                }
+                prev = c;
+            }
+        }
+        None
+    }
+
+    /// The lines holding the flow collection at `node`, and the line that
+    /// replaces them: the collection with `edit` applied, written in flow
+    /// style, between whatever came before and after it. `None` when
+    /// `edit` returns false or the result cannot be written in flow style.
+    fn flow_edit(
+        &self,
+        node: &Node,
+        edit: impl FnOnce(&mut Value) -> bool,
+    ) -> Option<(Range<usize>, Vec<String>)> {
+        let (last, close) = self.flow_end(node)?;
+        let text = if last == node.line {
+            self.lines[node.line][node.col..close].to_string()
+        } else {
+            let mut text = self.lines[node.line][node.col..].to_string();
+            for line in &self.lines[node.line + 1..last] {
+                text.push('\n');
+                text.push_str(line);
+            }
+            text.push('\n');
+            text.push_str(&self.lines[last][..close]);
+            text
+        };
+        let mut value = parse_one(&text)?;
+        if !edit(&mut value) {
+            return None;
+        }
+        let line = format!(
+            "{}{}{}",
+            &self.lines[node.line][..node.col],
+            flow(&value)?,
+            &self.lines[last][close..]
+        );
+        Some((node.line..last + 1, vec![line]))
+    }
+
+    /// The trailing comment of a value written on `line` after `col`, kept
+    /// when that value is replaced by another one-line value.
+    fn inline_comment(&self, line: usize, col: usize, end: usize) -> &str {
+        if end == line + 1 {
+            split_comment(&self.lines[line][col..]).1
+        } else {
+            ""
+        }
+    }
+
+    /// The source with `range` replaced by `new`, if it parses back to
+    /// `expected`.
+    fn checked(
+        mut self,
+        range: Range<usize>,
+        new: Vec<String>,
+        expected: &Value,
+    ) -> Option<String> {
+        self.lines.splice(range, new);
+        if self.lines.iter().all(|line| indent(line).is_none()) {
+            // A document emptied by a delete is still a mapping or list.
+            match expected {
+                Value::Mapping(map) if map.is_empty() => self.lines.push("{}".to_string()),
+                Value::Sequence(items) if items.is_empty() => self.lines.push("[]".to_string()),
+                _ => {}
+            }
+        }
+        let text = self.into_text();
+        (parse_one(&text)? == *expected).then_some(text)
+    }
+
+    fn into_text(self) -> String {
+        let mut text = self.lines.join("\n");
+        if self.newline && !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// `key` as written before a colon: plain when YAML reads it back as the
+/// same string, quoted otherwise.
+fn key_text(key: &str) -> Option<String> {
+    let rendered = render(&Value::String(key.to_string()));
+    match rendered.as_slice() {
+        [one] => Some(one.clone()),
+        _ => None,
+    }
+}
+
+/// `value` at the end of `head` (`key:` or `-`), whose key or dash is at
+/// column `col`: scalars on the same line, keeping `comment`; mappings and
+/// lists below a key, or after a dash, with their lines indented to match.
+fn value_lines(head: &str, col: usize, value: &Value, key: bool, comment: &str) -> Vec<String> {
+    let rendered = render(value);
+    let block = match value {
+        Value::Mapping(map) => !map.is_empty(),
+        Value::Sequence(items) => !items.is_empty(),
+        _ => false,
+    };
+    let (first, rest) = match (block && key, rendered.split_first()) {
+        (true, _) | (false, None) => (head.to_string(), &rendered[..]),
+        (false, Some((first, rest))) if rest.is_empty() => {
+            (format!("{head} {first}{comment}"), rest)
+        }
+        (false, Some((first, rest))) => (format!("{head} {first}"), rest),
+    };
+    let pad = " ".repeat(if block { col + 2 } else { col });
+    std::iter::once(first)
+        .chain(rest.iter().map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{pad}{line}")
+            }
+        }))
+        .collect()
+}
+
+/// `value` as serialized YAML lines.
+fn render(value: &Value) -> Vec<String> {
+    serde_yaml::to_string(value)
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// `value` in flow style on one line (`[a, b]`, `{a: 1}`), or `None` for
+/// a tagged value. Strings that a flow collection would misread are
+/// double-quoted.
+fn flow(value: &Value) -> Option<String> {
+    let join = |parts: Option<Vec<String>>| parts.map(|parts| parts.join(", "));
+    match value {
+        Value::Sequence(items) => Some(format!("[{}]", join(items.iter().map(flow).collect())?)),
+        Value::Mapping(map) => {
+            let entries = map
+                .iter()
+                .map(|(k, v)| Some(format!("{}: {}", flow(k)?, flow(v)?)))
+                .collect();
+            Some(format!("{{{}}}", join(entries)?))
+        }
+        Value::Tagged(_) => None,
+        Value::String(text) => match render(value).as_slice() {
+            [one] if !one.contains([',', '[', ']', '{', '}', '#']) => Some(one.clone()),
+            _ => serde_json::to_string(text).ok(),
+        },
+        scalar => render(scalar).pop(),
+    }
+}
+
+/// `value` nested under the keys of `segments`, the way [`set`] creates
+/// missing keys; `None` when a segment is not a key.
+fn nest(segments: &[PathSegment], value: &Value) -> Option<Value> {
+    segments
+        .iter()
+        .rev()
+        .try_fold(value.clone(), |inner, seg| match seg {
+            PathSegment::Key(key) => {
+                let mut map = Mapping::new();
+                map.insert(Value::String(key.clone()), inner);
+                Some(Value::Mapping(map))
+            }
+            _ => None,
+        })
+}
@@ -0,0 +1,633 @@
+use super::*;
+
+fn doc(yaml: &str) -> Value {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn get_nested_key_and_index() {
+    let doc = doc("skills:\n  claude:\n    - Review\n    - Debate\n");
+    assert_eq!(
+        get(&doc, ".skills.claude[1]"),
+        Some(&Value::String("Debate".into()))
+    );
+    assert_eq!(
+        get(&doc, "skills.claude[0]"),
+        get(&doc, ".skills.claude[0]")
+    );
+}
+
+#[test]
+fn get_missing_is_none() {
+    let doc = doc("a:\n  b: 1\n");
+    assert!(get(&doc, ".a.c").is_none());
+    assert!(get(&doc, ".a.b[0]").is_none());
+}
+
+#[test]
+fn empty_path_is_whole_document() {
+    let doc = doc("a: 1\n");
+    assert_eq!(get(&doc, "."), Some(&doc));
+    assert_eq!(
+        parse_path("m[2]"),
+        vec![PathSegment::Key("m".into()), PathSegment::Index(2)]
+    );
+}
+
+#[test]
+fn walk_borrows_from_the_document() {
+    let doc = doc("modules:\n  - name: a\n");
+    let module = get(&doc, ".modules[0]").unwrap();
+    assert!(std::ptr::eq(
+        get(&doc, ".modules[0].name").unwrap(),
+        module.get("name").unwrap()
+    ));
+}
+
+#[test]
+fn set_creates_intermediate_mappings() {
+    let mut doc = doc("agents:\n  Dev:\n    model: fast\n");
+    set(&mut doc, &parse_path(".agents.Dev.model"), "strong".into()).unwrap();
+    set(
+        &mut doc,
+        &parse_path(".providers.claude.model"),
+        "opus".into(),
+    )
+    .unwrap();
+    assert_eq!(get(&doc, ".agents.Dev.model"), Some(&Value::from("strong")));
+    assert_eq!(
+        get(&doc, ".providers.claude.model"),
+        Some(&Value::from("opus"))
+    );
+}
+
+#[test]
+fn set_indexes_and_appends() {
+    let mut doc = doc("skills:\n  - Review\n");
+    set(&mut doc, &parse_path(".skills[0]"), "Debate".into()).unwrap();
+    set(&mut doc, &parse_path(".skills[1]"), "Council".into()).unwrap();
+    assert_eq!(doc, self::doc("skills: [Debate, Council]\n"));
+    let err = set(&mut doc, &parse_path(".skills[5]"), "X".into()).unwrap_err();
+    assert!(err.contains("out of range"));
+}
+
+#[test]
+fn set_through_scalar_is_an_error() {
+    let mut doc = doc("a: 1\n");
+    assert!(set(&mut doc, &parse_path(".a.b"), "x".into()).is_err());
+    set(&mut doc, &[], "whole".into()).unwrap();
+    assert_eq!(doc, Value::from("whole"));
+}
+
+#[test]
+fn delete_removes_keys_and_items() {
+    let mut doc = doc("agents:\n  Dev: {model: fast}\n  Old: {model: fast}\nskills: [A, B, C]\n");
+    assert_eq!(delete(&mut doc, &parse_path(".agents.Old")).len(), 1);
+    assert_eq!(
+        delete(&mut doc, &parse_path(".skills[1]")),
+        [Value::from("B")]
+    );
+    assert_eq!(
+        doc,
+        self::doc("agents:\n  Dev: {model: fast}\nskills: [A, C]\n")
+    );
+}
+
+#[test]
+fn delete_missing_path_is_empty() {
+    let mut doc = doc("a: {b: 1}\nitems: [x]\n");
+    assert!(delete(&mut doc, &parse_path(".a.c")).is_empty());
+    assert!(delete(&mut doc, &parse_path(".z.b")).is_empty());
+    assert!(delete(&mut doc, &parse_path(".items[3]")).is_empty());
+    assert!(delete(&mut doc, &[]).is_empty());
+}
+
+#[test]
+fn parse_wildcards() {
+    assert_eq!(
+        parse_path(".agents.*.model"),
+        [
+            PathSegment::Key("agents".into()),
+            PathSegment::Wildcard,
+            PathSegment::Key("model".into()),
+        ]
+    );
+    assert_eq!(
+        parse_path(".modules[*].name"),
+        parse_path(".modules.*.name")
+    );
+}
+
+#[test]
+fn walk_borrows_nodes_inside_the_document() {
+    let doc = doc("agents:\n  Dev: {tools: [Read, Grep]}\n");
+    let tools = &doc["agents"]["Dev"]["tools"];
+    assert!(std::ptr::eq(
+        walk(&doc, &parse_path(".agents.Dev.tools")).unwrap(),
+        tools
+    ));
+    assert!(std::ptr::eq(
+        walk_all(&doc, &parse_path("..tools"))[0],
+        tools
+    ));
+}
+
+#[test]
+fn walk_all_expands_wildcards() {
+    let doc = doc(
+        "agents:\n  Dev: {model: fast}\n  Ops: {tools: Read}\n  Sec: {model: strong}\n\
+         skills:\n  claude:\n    A: {scope: ws}\n  codex:\n    B: {scope: user}\n",
+    );
+    assert_eq!(
+        walk_all(&doc, &parse_path(".agents.*.model")),
+        [&Value::from("fast"), &Value::from("strong")]
+    );
+    assert_eq!(walk_all(&doc, &parse_path(".skills.*.*.scope")).len(), 2);
+    assert_eq!(get(&doc, ".agents.*.model"), Some(&Value::from("fast")));
+    assert!(walk_all(&doc, &parse_path(".agents.Dev.model.*")).is_empty());
+}
+
+#[test]
+fn set_and_delete_through_wildcards() {
+    let mut doc = doc("agents:\n  Dev: {model: fast}\n  Ops: {}\n");
+    set(&mut doc, &parse_path(".agents.*.model"), "strong".into()).unwrap();
+    assert_eq!(
+        walk_all(&doc, &parse_path(".agents.*.model")),
+        [&Value::from("strong"), &Value::from("strong")]
+    );
+    assert_eq!(delete(&mut doc, &parse_path(".agents.*.model")).len(), 2);
+    assert_eq!(delete(&mut doc, &parse_path(".agents.*")).len(), 2);
+    assert_eq!(doc, self::doc("agents: {}\n"));
+}
+
+#[test]
+fn parse_recursive_descent() {
+    assert_eq!(
+        parse_path("..scope"),
+        [PathSegment::Descendant("scope".into())]
+    );
+    assert_eq!(
+        parse_path(".skills..scope"),
+        [
+            PathSegment::Key("skills".into()),
+            PathSegment::Descendant("scope".into()),
+        ]
+    );
+    assert_eq!(
+        parse_path("..modules[0]"),
+        [
+            PathSegment::Descendant("modules".into()),
+            PathSegment::Index(0),
+        ]
+    );
+}
+
+#[test]
+fn recursive_descent_finds_keys_at_any_depth() {
+    let doc = doc(
+        "scope: top\nskills:\n  claude:\n    A: {scope: ws}\n    B: {other: 1}\n\
+         list:\n  - {scope: item}\n",
+    );
+    assert_eq!(
+        walk_all(&doc, &parse_path("..scope")),
+        [
+            &Value::from("top"),
+            &Value::from("ws"),
+            &Value::from("item")
+        ]
+    );
+    assert_eq!(
+        walk_all(&doc, &parse_path(".skills..scope")),
+        [&Value::from("ws")]
+    );
+}
+
+#[test]
+fn recursive_descent_deletes_but_does_not_set() {
+    let mut doc = doc("scope: top\nskills:\n  A: {scope: ws, name: A}\n");
+    assert!(set(&mut doc, &parse_path("..scope"), "x".into()).is_err());
+    assert_eq!(delete(&mut doc, &parse_path("..scope")).len(), 2);
+    assert_eq!(doc, self::doc("skills:\n  A: {name: A}\n"));
+}
+
+#[test]
+fn diff_reports_added_removed_and_changed() {
+    let old = doc("agents:\n  Dev: {model: fast}\n  Old: {model: fast}\nskills: [A, B]\n");
+    let new = doc("agents:\n  Dev: {model: strong}\n  New: {model: fast}\nskills: [A]\n");
+    assert_eq!(
+        diff(&old, &new),
+        [
+            (
+                ".agents.Dev.model".to_string(),
+                Difference::Changed("fast".into(), "strong".into())
+            ),
+            (
+                ".agents.Old".to_string(),
+                Difference::Removed(self::doc("{model: fast}"))
+            ),
+            (
+                ".agents.New".to_string(),
+                Difference::Added(self::doc("{model: fast}"))
+            ),
+            (".skills[1]".to_string(), Difference::Removed("B".into())),
+        ]
+    );
+}
+
+#[test]
+fn diff_ignores_formatting() {
+    let old = doc("# comment\nskills:\n  - A\n  - B\n");
+    let new = doc("skills: [A, B]\n");
+    assert!(diff(&old, &new).is_empty());
+    assert_eq!(
+        diff(&Value::from(1), &Value::from(2)),
+        [(".".to_string(), Difference::Changed(1.into(), 2.into()))]
+    );
+}
+
+#[test]
+fn parse_negative_indices_and_slices() {
+    assert_eq!(
+        parse_path(".roster[-1]"),
+        [PathSegment::Key("roster".into()), PathSegment::FromEnd(1)]
+    );
+    assert_eq!(
+        parse_path(".roster[1:3]")[1],
+        PathSegment::Slice(Some(1), Some(3))
+    );
+    assert_eq!(
+        parse_path(".roster[:2]")[1],
+        PathSegment::Slice(None, Some(2))
+    );
+    assert_eq!(
+        parse_path(".roster[-2:]")[1],
+        PathSegment::Slice(Some(-2), None)
+    );
+    assert_eq!(parse_path(".roster[x:1]").len(), 1);
+}
+
+#[test]
+fn walk_negative_indices_and_slices() {
+    let doc = doc("roster: [A, B, C, D]\n");
+    let names = |path: &str| -> Vec<String> {
+        walk_all(&doc, &parse_path(path))
+            .into_iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(names(".roster[-1]"), ["D"]);
+    assert_eq!(names(".roster[-4]"), ["A"]);
+    assert!(names(".roster[-5]").is_empty());
+    assert_eq!(names(".roster[1:3]"), ["B", "C"]);
+    assert_eq!(names(".roster[:2]"), ["A", "B"]);
+    assert_eq!(names(".roster[-2:]"), ["C", "D"]);
+    assert_eq!(names(".roster[1:-1]"), ["B", "C"]);
+    assert!(names(".roster[3:1]").is_empty());
+    assert_eq!(names(".roster[2:99]"), ["C", "D"]);
+}
+
+#[test]
+fn set_and_delete_with_negative_indices_and_slices() {
+    let mut doc = doc("roster: [A, B, C, D]\n");
+    set(&mut doc, &parse_path(".roster[-1]"), "Z".into()).unwrap();
+    set(&mut doc, &parse_path(".roster[:2]"), "X".into()).unwrap();
+    assert_eq!(doc, self::doc("roster: [X, X, C, Z]\n"));
+    assert!(set(&mut doc, &parse_path(".roster[-9]"), "Y".into()).is_err());
+
+    assert_eq!(
+        delete(&mut doc, &parse_path(".roster[-1]")),
+        [Value::from("Z")]
+    );
+    assert_eq!(delete(&mut doc, &parse_path(".roster[:2]")).len(), 2);
+    assert_eq!(doc, self::doc("roster: [C]\n"));
+}
+
+#[test]
+fn parse_filters_keep_dots_inside_brackets() {
+    assert_eq!(
+        parse_path(".modules[?meta.version=\"1.2\"].name"),
+        [
+            PathSegment::Key("modules".into()),
+            PathSegment::Filter {
+                path: vec![
+                    PathSegment::Key("meta".into()),
+                    PathSegment::Key("version".into()),
+                ],
+                value: "1.2".into(),
+                equal: true,
+            },
+            PathSegment::Key("name".into()),
+        ]
+    );
+    assert!(matches!(
+        parse_path(".a[?tags[0]!=x]")[1],
+        PathSegment::Filter { equal: false, .. }
+    ));
+}
+
+#[test]
+fn filters_select_matching_items() {
+    let doc = doc(
+        "modules:\n  - {name: a, enabled: true}\n  - {name: b, enabled: false}\n  - {name: c}\n\
+         agents:\n  Dev: {model: fast}\n  Sec: {model: strong}\n",
+    );
+    let names = |path: &str| -> Vec<&Value> { walk_all(&doc, &parse_path(path)) };
+    assert_eq!(names(".modules[?enabled=true].name"), [&Value::from("a")]);
+    assert_eq!(
+        names(".modules[?enabled!=true].name"),
+        [&Value::from("b"), &Value::from("c")]
+    );
+    assert_eq!(names(".agents[?model=strong]").len(), 1);
+}
+
+#[test]
+fn set_and_delete_through_filters() {
+    let mut doc = doc("modules:\n  - {name: a, enabled: true}\n  - {name: b, enabled: false}\n");
+    set(
+        &mut doc,
+        &parse_path(".modules[?name=b].enabled"),
+        Value::Bool(true),
+    )
+    .unwrap();
+    assert_eq!(
+        walk_all(&doc, &parse_path(".modules[?enabled=true]")).len(),
+        2
+    );
+    assert_eq!(delete(&mut doc, &parse_path(".modules[?name=a]")).len(), 1);
+    assert_eq!(doc, self::doc("modules:\n  - {name: b, enabled: true}\n"));
+}
+
+#[test]
+fn leaves_flatten_the_document() {
+    let doc =
+        doc("name: forge\nagents:\n  Dev: {model: fast, tools: [Read, Grep]}\nempty: {}\nnone:\n");
+    let paths: Vec<(String, &Value)> = leaves(&doc);
+    assert_eq!(
+        paths.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(),
+        [
+            ".name",
+            ".agents.Dev.model",
+            ".agents.Dev.tools[0]",
+            ".agents.Dev.tools[1]",
+            ".empty",
+            ".none",
+        ]
+    );
+    assert_eq!(paths[3].1, &Value::from("Grep"));
+    assert_eq!(
+        leaves(&Value::from(1)),
+        [(".".to_string(), &Value::from(1))]
+    );
+}
+
+// --- set_in_source / delete_in_source ---
+
+const COMMENTED: &str = "\
+# Module defaults
+name: forge  # shown in listings
+
+agents:
+  # The lead
+  Dev:
+    model: fast
+    tools: [Read, Write]
+  Reviewer:
+    model: 'slow'
+roster:
+- lead
+- reviewer  # optional
+";
+
+#[test]
+fn set_in_source_replaces_a_scalar_keeping_comments() {
+    let edited = set_in_source(COMMENTED, &parse_path(".agents.Dev.model"), &"smart".into());
+    assert_eq!(
+        edited.unwrap(),
+        COMMENTED.replace("model: fast", "model: smart")
+    );
+    let edited = set_in_source(COMMENTED, &parse_path(".name"), &"anvil".into()).unwrap();
+    assert!(edited.contains("name: anvil  # shown in listings\n"));
+    let edited = set_in_source(COMMENTED, &parse_path(".roster[-1]"), &"qa".into()).unwrap();
+    assert!(edited.ends_with("- lead\n- qa  # optional\n"));
+}
+
+#[test]
+fn set_in_source_adds_keys_and_items_in_place() {
+    let edited = set_in_source(
+        COMMENTED,
+        &parse_path(".agents.Reviewer.limits.turns"),
+        &Value::from(5),
+    )
+    .unwrap();
+    assert!(edited.contains("    model: 'slow'\n    limits:\n      turns: 5\nroster:\n"));
+    let edited = set_in_source(COMMENTED, &parse_path(".roster[2]"), &"qa".into()).unwrap();
+    assert!(edited.ends_with("- reviewer  # optional\n- qa\n"));
+    let value: Value = serde_yaml::from_str("{model: fast, skills: [a, b]}").unwrap();
+    let edited = set_in_source(COMMENTED, &parse_path(".agents.Dev"), &value).unwrap();
+    assert!(edited.contains(
+        "  # The lead\n  Dev:\n    model: fast\n    skills:\n    - a\n    - b\n  Reviewer:\n"
+    ));
+    assert_eq!(
+        set_in_source("", &parse_path(".a.b"), &Value::from(1)).unwrap(),
+        "a:\n  b: 1\n"
+    );
+}
+
+#[test]
+fn set_in_source_declines_what_it_cannot_edit_in_place() {
+    assert!(set_in_source(COMMENTED, &parse_path(".agents.*.model"), &"x".into()).is_none());
+    let anchored = "base: &b {model: fast}\nDev: *b\n";
+    assert!(set_in_source(anchored, &parse_path(".base.model"), &"x".into()).is_none());
+}
+
+#[test]
+fn set_in_source_edits_flow_collections_in_flow_style() {
+    let path = parse_path(".agents.Dev.tools[0]");
+    let edited = set_in_source(COMMENTED, &path, &"Grep".into()).unwrap();
+    assert_eq!(edited, COMMENTED.replace("[Read, Write]", "[Grep, Write]"));
+    let edited = set_in_source(
+        COMMENTED,
+        &parse_path(".agents.Dev.tools[2]"),
+        &"a, b".into(),
+    );
+    assert!(edited
+        .unwrap()
+        .contains("    tools: [Read, Write, \"a, b\"]\n"));
+    let source = "limits: {turns: 5}  # per run\nother: 1\n";
+    let edited = set_in_source(source, &parse_path(".limits.depth.max"), &Value::from(2));
+    assert_eq!(
+        edited.unwrap(),
+        "limits: {turns: 5, depth: {max: 2}}  # per run\nother: 1\n"
+    );
+    let spread = "tools: [Read,\n  Grep]\nx: 1\n";
+    assert_eq!(
+        set_in_source(spread, &parse_path(".tools[1]"), &"Glob".into()).unwrap(),
+        "tools: [Read, Glob]\nx: 1\n"
+    );
+}
+
+#[test]
+fn delete_in_source_edits_flow_collections_in_flow_style() {
+    let edited = delete_in_source(COMMENTED, &parse_path(".agents.Dev.tools[0]")).unwrap();
+    assert_eq!(edited, COMMENTED.replace("[Read, Write]", "[Write]"));
+    let source = "# limits\nlimits: {turns: 5, depth: 2}\n";
+    assert_eq!(
+        delete_in_source(source, &parse_path(".limits.turns")).unwrap(),
+        "# limits\nlimits: {depth: 2}\n"
+    );
+    assert_eq!(
+        delete_in_source(source, &parse_path(".limits.missing")).unwrap(),
+        source
+    );
+}
+
+#[test]
+fn delete_in_source_removes_whole_entries() {
+    let edited = delete_in_source(COMMENTED, &parse_path(".agents.Dev")).unwrap();
+    assert!(edited.contains("  # The lead\n  Reviewer:\n"));
+    assert!(!edited.contains("Dev"));
+    let edited = delete_in_source(COMMENTED, &parse_path(".roster[0]")).unwrap();
+    assert!(edited.ends_with("roster:\n- reviewer  # optional\n"));
+    assert_eq!(
+        delete_in_source(COMMENTED, &parse_path(".missing.key")).unwrap(),
+        COMMENTED
+    );
+    let items = "modules:\n  - name: a\n    on: true\n";
+    assert!(delete_in_source(items, &parse_path(".modules[0].name")).is_none());
+}
+
+#[test]
+fn split_documents_keeps_leading_comments_with_the_first() {
+    assert_eq!(
+        split_documents("# head\n---\na: 1\n---\nb: 2\n"),
+        ["# head\n---\na: 1\n", "---\nb: 2\n"]
+    );
+    assert_eq!(split_documents(""), [""]);
+}
+
+// --- quote_aliases ---
+
+#[test]
+fn quote_aliases_marks_alias_nodes_only() {
+    let source = "\
+base: &base {model: fast}
+Dev: *base
+roster: [*lead, \"*quoted\", 2 * 3]
+list:
+  - *lead  # *comment
+notes: |
+  *bold* stays
+<<: *base
+";
+    assert_eq!(
+        quote_aliases(source),
+        "\
+base: &base {model: fast}
+Dev: \"*base\"
+roster: [\"*lead\", \"*quoted\", 2 * 3]
+list:
+  - \"*lead\"  # *comment
+notes: |
+  *bold* stays
+<<: \"*base\"
+"
+    );
+}
+
+// --- sort_keys ---
+
+#[test]
+fn sort_keys_orders_nested_mappings() {
+    let mut d = doc("b: 1\na:\n  z: [{y: 1, x: 2}]\n  m: 2\n");
+    assert!(sort_keys(&mut d, &[]));
+    assert_eq!(
+        serde_yaml::to_string(&d).unwrap(),
+        "a:\n  m: 2\n  z:\n  - x: 2\n    y: 1\nb: 1\n"
+    );
+    assert!(!sort_keys(&mut d, &[]));
+
+    let mut d = doc("b: {d: 1, c: 2}\na: {d: 1, c: 2}\n");
+    assert!(sort_keys(&mut d, &parse_path(".b")));
+    assert_eq!(
+        serde_yaml::to_string(&d).unwrap(),
+        "b:\n  c: 2\n  d: 1\na:\n  d: 1\n  c: 2\n"
+    );
+}
+
+#[test]
+fn sort_keys_in_source_moves_comments_with_their_keys() {
+    let source = "\
+# Roster
+roster:
+  # The reviewer
+  Reviewer:
+    model: slow
+
+  # The lead
+  Dev:
+    tools: Read
+    model: fast  # default tier
+modules:
+  - name: b
+    enabled: true
+";
+    assert_eq!(
+        sort_keys_in_source(source, &[]).unwrap(),
+        "\
+modules:
+  - enabled: true
+    name: b
+# Roster
+roster:
+  # The lead
+  Dev:
+    model: fast  # default tier
+    tools: Read
+
+  # The reviewer
+  Reviewer:
+    model: slow
+"
+    );
+    let only_dev = sort_keys_in_source(source, &parse_path(".roster.Dev")).unwrap();
+    assert!(
+        only_dev.contains("  Reviewer:\n    model: slow\n\n  # The lead\n  Dev:\n    model: fast")
+    );
+    assert!(only_dev.contains("  - name: b\n    enabled: true\n"));
+    assert_eq!(
+        sort_keys_in_source("b: 1\na: {z: 1, y: [c, a]}  # flow\n", &[]).unwrap(),
+        "a: {y: [c, a], z: 1}  # flow\nb: 1\n"
+    );
+}
+
+#[test]
+fn quoted_keys_are_taken_literally() {
+    assert_eq!(
+        parse_path(r#".["claude.name"].model"#),
+        [
+            PathSegment::Key("claude.name".into()),
+            PathSegment::Key("model".into()),
+        ]
+    );
+    assert_eq!(
+        parse_path(".agents['my[weird]key']['*']"),
+        [
+            PathSegment::Key("agents".into()),
+            PathSegment::Key("my[weird]key".into()),
+            PathSegment::Key("*".into()),
+        ]
+    );
+    let doc = doc("claude.name: Dev\nclaude:\n  name: Ops\n");
+    assert_eq!(get(&doc, r#"["claude.name"]"#), Some(&Value::from("Dev")));
+    assert_eq!(get(&doc, ".claude.name"), Some(&Value::from("Ops")));
+}
+
+#[test]
+fn leaves_quote_keys_that_read_as_paths() {
+    let doc = doc("claude.name: Dev\n'*': 1\nplain: [x]\n");
+    let paths: Vec<String> = leaves(&doc).into_iter().map(|(path, _)| path).collect();
+    assert_eq!(paths, [r#"["claude.name"]"#, r#"["*"]"#, ".plain[0]"]);
+    for path in &paths {
+        assert!(get(&doc, path).is_some(), "{path}");
+    }
+}
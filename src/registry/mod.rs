@@ -0,0 +1,330 @@
+//! Module registry client. A registry is a static index (JSON or YAML) listing
+//! modules and, per version, the URL and SHA-256 of an archive built by
+//! `forge pack`:
+//!
+//! ```yaml
+//! modules:
+//!   forge-council:
+//!     description: Multi-agent review council
+//!     versions:
+//!       "1.2.0": { url: forge-council-1.2.0.tar, sha256: "…" }
+//! ```
+//!
+//! Relative URLs resolve against the index location. HTTPS is fetched with
+//! `curl`; plain `http://` is refused unless it points at localhost or the
+//! caller opts in with `--insecure`. Anything else is read as a local path
+//! (`file://` allowed).
+//! Installed modules are unpacked under `<forge-home>/modules` and recorded
+//! in `<forge-home>/installed.json`.
+
+use crate::pack;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const REGISTRY_ENV: &str = "FORGE_REGISTRY";
+pub const HOME_ENV: &str = "FORGE_HOME";
+const INSTALLED_FILE: &str = "installed.json";
+
+#[derive(Debug, Deserialize)]
+pub struct Index {
+    #[serde(default)]
+    pub modules: BTreeMap<String, IndexModule>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IndexModule {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub versions: BTreeMap<String, Release>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub url: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Installed {
+    pub version: String,
+    pub root: PathBuf,
+}
+
+pub type InstalledMap = BTreeMap<String, Installed>;
+
+// ─── Versions ───
+
+/// Compare dotted versions numerically (`1.10.0 > 1.9.2`); non-numeric parts
+/// compare as text, and a pre-release (`1.0.0-rc1`) sorts before its release.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> (Vec<String>, Option<String>) {
+        let v = v.trim_start_matches('v');
+        let (core, pre) = match v.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (v, None),
+        };
+        (core.split('.').map(String::from).collect(), pre)
+    };
+    let (a_core, a_pre) = split(a);
+    let (b_core, b_pre) = split(b);
+    for i in 0..a_core.len().max(b_core.len()) {
+        let x = a_core.get(i).map_or("0", String::as_str);
+        let y = b_core.get(i).map_or("0", String::as_str);
+        let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(x), Some(y)) => x.cmp(&y),
+    }
+}
+
+/// Split `name@version`; the version is optional.
+pub fn parse_spec(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once('@') {
+        Some((name, version)) if !version.is_empty() => (name, Some(version)),
+        Some((name, _)) => (name, None),
+        None => (spec, None),
+    }
+}
+
+// ─── Index ───
+
+impl Index {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        serde_yaml::from_str(content).map_err(|e| format!("invalid registry index: {e}"))
+    }
+
+    /// Modules whose name or description contains `query` (case-insensitive),
+    /// as `(name, description, latest version)`.
+    pub fn search(&self, query: &str) -> Vec<(&str, &str, Option<&str>)> {
+        let query = query.to_lowercase();
+        self.modules
+            .iter()
+            .filter(|(name, m)| {
+                name.to_lowercase().contains(&query)
+                    || m.description.to_lowercase().contains(&query)
+            })
+            .map(|(name, m)| (name.as_str(), m.description.as_str(), m.latest()))
+            .collect()
+    }
+
+    /// The release for `name` at `version`, or its latest release.
+    pub fn resolve(&self, name: &str, version: Option<&str>) -> Result<(String, &Release), String> {
+        let module = self
+            .modules
+            .get(name)
+            .ok_or_else(|| format!("module {name:?} not found in registry"))?;
+        let version = match version {
+            Some(v) => v,
+            None => module
+                .latest()
+                .ok_or_else(|| format!("module {name:?} has no releases"))?,
+        };
+        let release = module
+            .versions
+            .get(version)
+            .ok_or_else(|| format!("{name}@{version} not found in registry"))?;
+        Ok((version.to_string(), release))
+    }
+}
+
+impl IndexModule {
+    pub fn latest(&self) -> Option<&str> {
+        self.versions
+            .keys()
+            .max_by(|a, b| compare_versions(a, b))
+            .map(String::as_str)
+    }
+}
+
+// ─── Fetching ───
+
+fn is_remote(location: &str) -> bool {
+    location.starts_with("https://") || location.starts_with("http://")
+}
+
+/// Resolve `url` from an index entry against the index location.
+pub(crate) fn resolve_url(index_location: &str, url: &str) -> String {
+    if is_remote(url) || url.starts_with("file://") || url.starts_with('/') {
+        return url.to_string();
+    }
+    match index_location.rfind('/') {
+        Some(i) => format!("{}/{url}", &index_location[..i]),
+        None => url.to_string(),
+    }
+}
+
+/// Refuse plain `http://` unless `insecure` is set or the host is loopback;
+/// an index or archive fetched over http could be swapped in transit.
+pub(crate) fn check_transport(location: &str, insecure: bool) -> Result<(), String> {
+    let Some(rest) = location.strip_prefix("http://") else {
+        return Ok(());
+    };
+    if insecure || is_loopback(rest) {
+        return Ok(());
+    }
+    Err(format!(
+        "refusing to fetch {location} over plain http: use https, or pass --insecure"
+    ))
+}
+
+/// Whether the authority at the start of `rest` names the local machine.
+fn is_loopback(rest: &str) -> bool {
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = if host_port.starts_with('[') {
+        host_port
+            .split_once(']')
+            .map_or(host_port, |(h, _)| h)
+            .trim_start_matches('[')
+    } else {
+        host_port.split(':').next().unwrap_or_default()
+    };
+    host.eq_ignore_ascii_case("localhost") || host == "127.0.0.1" || host == "::1"
+}
+
+/// The curl arguments for `location`; http (including redirects to it) is
+/// only allowed once [`check_transport`] has accepted it.
+fn curl_args(location: &str) -> [&str; 6] {
+    let proto = if location.starts_with("http://") {
+        "=https,http"
+    } else {
+        "=https"
+    };
+    ["-fsSL", "--proto", proto, "--proto-redir", proto, location]
+}
+
+/// Read a local path or `file://` URL, or download over HTTPS with curl
+/// (plain http only as allowed by [`check_transport`]).
+pub(crate) fn fetch(location: &str, insecure: bool) -> Result<Vec<u8>, String> {
+    if is_remote(location) {
+        check_transport(location, insecure)?;
+        let output = Command::new("curl")
+            .args(curl_args(location))
+            .output()
+            .map_err(|e| format!("failed to run curl: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "failed to fetch {location}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        return Ok(output.stdout);
+    }
+    let path = location.strip_prefix("file://").unwrap_or(location);
+    std::fs::read(path).map_err(|e| format!("failed to read {path}: {e}"))
+}
+
+/// [`fetch`] without blocking: curl runs as a tokio child process and local
+/// files are read with `tokio::fs`.
+#[cfg(feature = "async")]
+pub async fn fetch_async(location: &str, insecure: bool) -> Result<Vec<u8>, String> {
+    if is_remote(location) {
+        check_transport(location, insecure)?;
+        let output = tokio::process::Command::new("curl")
+            .args(curl_args(location))
+            .output()
+            .await
+            .map_err(|e| format!("failed to run curl: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "failed to fetch {location}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        return Ok(output.stdout);
+    }
+    let path = location.strip_prefix("file://").unwrap_or(location);
+    tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("failed to read {path}: {e}"))
+}
+
+/// [`load_index`] through [`fetch_async`].
+#[cfg(feature = "async")]
+pub async fn load_index_async(location: &str, insecure: bool) -> Result<Index, String> {
+    let bytes = fetch_async(location, insecure).await?;
+    let content =
+        String::from_utf8(bytes).map_err(|_| "registry index is not UTF-8".to_string())?;
+    Index::parse(&content)
+}
+
+/// Fetch and parse the index at `location`; `insecure` allows plain http to
+/// hosts other than localhost.
+pub fn load_index(location: &str, insecure: bool) -> Result<Index, String> {
+    let bytes = fetch(location, insecure)?;
+    let content =
+        String::from_utf8(bytes).map_err(|_| "registry index is not UTF-8".to_string())?;
+    Index::parse(&content)
+}
+
+// ─── Install ───
+
+/// Download `release`, check its archive checksum, and unpack it (verifying
+/// every file) under `<forge_home>/modules`. Returns the module root.
+pub fn install_release(
+    index_location: &str,
+    name: &str,
+    version: &str,
+    release: &Release,
+    forge_home: &Path,
+    insecure: bool,
+) -> Result<PathBuf, String> {
+    let url = resolve_url(index_location, &release.url);
+    let bytes = fetch(&url, insecure)?;
+    let actual = pack::sha256_hex(&bytes);
+    if !actual.eq_ignore_ascii_case(&release.sha256) {
+        return Err(format!(
+            "checksum mismatch for {name}@{version}: expected {}, got {actual}",
+            release.sha256
+        ));
+    }
+
+    let (root, manifest) = pack::unpack_package(&bytes, &forge_home.join("modules"))?;
+    if manifest.name != name || manifest.version != version {
+        return Err(format!(
+            "archive contains {}@{}, expected {name}@{version}",
+            manifest.name, manifest.version
+        ));
+    }
+    Ok(root)
+}
+
+// ─── Installed modules ───
+
+pub fn read_installed(forge_home: &Path) -> Result<InstalledMap, String> {
+    let path = forge_home.join(INSTALLED_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("invalid JSON in {}: {e}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(InstalledMap::new()),
+        Err(e) => Err(format!("failed to read {}: {e}", path.display())),
+    }
+}
+
+pub fn record_installed(forge_home: &Path, name: &str, installed: Installed) -> Result<(), String> {
+    let mut map = read_installed(forge_home)?;
+    map.insert(name.to_string(), installed);
+    std::fs::create_dir_all(forge_home)
+        .map_err(|e| format!("failed to create {}: {e}", forge_home.display()))?;
+    let path = forge_home.join(INSTALLED_FILE);
+    let mut json = serde_json::to_string_pretty(&map)
+        .map_err(|e| format!("failed to serialize {}: {e}", path.display()))?;
+    json.push('\n');
+    std::fs::write(&path, json).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,191 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+const INDEX: &str = r#"{
+  "modules": {
+    "forge-council": {
+      "description": "Multi-agent review council",
+      "versions": {
+        "1.9.0": {"url": "forge-council-1.9.0.tar", "sha256": "aa"},
+        "1.10.0": {"url": "forge-council-1.10.0.tar", "sha256": "bb"}
+      }
+    },
+    "forge-tlp": {"description": "Traffic light protocol", "versions": {}}
+  }
+}"#;
+
+#[test]
+fn versions_compare_numerically() {
+    assert_eq!(compare_versions("1.10.0", "1.9.2"), Ordering::Greater);
+    assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+    assert_eq!(compare_versions("1.0.0-rc1", "1.0.0"), Ordering::Less);
+    assert_eq!(compare_versions("v2.0.0", "1.99.0"), Ordering::Greater);
+}
+
+#[test]
+fn spec_parsing() {
+    assert_eq!(
+        parse_spec("forge-council@1.2.0"),
+        ("forge-council", Some("1.2.0"))
+    );
+    assert_eq!(parse_spec("forge-council"), ("forge-council", None));
+    assert_eq!(parse_spec("forge-council@"), ("forge-council", None));
+}
+
+#[test]
+fn index_search_and_resolve() {
+    let index = Index::parse(INDEX).unwrap();
+    let hits = index.search("REVIEW");
+    assert_eq!(
+        hits,
+        vec![(
+            "forge-council",
+            "Multi-agent review council",
+            Some("1.10.0")
+        )]
+    );
+
+    let (version, release) = index.resolve("forge-council", None).unwrap();
+    assert_eq!(version, "1.10.0");
+    assert_eq!(release.sha256, "bb");
+    assert!(index.resolve("forge-council", Some("3.0.0")).is_err());
+    assert!(index
+        .resolve("forge-tlp", None)
+        .unwrap_err()
+        .contains("no releases"));
+    assert!(index.resolve("missing", None).is_err());
+}
+
+#[test]
+fn yaml_index_is_accepted() {
+    let index =
+        Index::parse("modules:\n  m:\n    versions:\n      '1.0.0': {url: m.tar, sha256: cc}\n")
+            .unwrap();
+    assert_eq!(index.modules["m"].latest(), Some("1.0.0"));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "current_thread")]
+async fn async_index_reads_local_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("index.json");
+    fs::write(&path, INDEX).unwrap();
+    let index = load_index_async(path.to_str().unwrap(), false)
+        .await
+        .unwrap();
+    assert!(index.modules.contains_key("forge-tlp"));
+    assert!(load_index_async("/nonexistent/index.json", false)
+        .await
+        .is_err());
+}
+
+#[test]
+fn urls_resolve_against_index() {
+    assert_eq!(
+        resolve_url("https://example.com/forge/index.json", "m-1.0.0.tar"),
+        "https://example.com/forge/m-1.0.0.tar"
+    );
+    assert_eq!(
+        resolve_url(
+            "https://example.com/index.json",
+            "https://cdn.example.com/m.tar"
+        ),
+        "https://cdn.example.com/m.tar"
+    );
+    assert_eq!(resolve_url("/srv/index.yaml", "m.tar"), "/srv/m.tar");
+}
+
+#[test]
+fn http_registry_is_rejected() {
+    let err = load_index("http://example.com/forge/index.json", false).unwrap_err();
+    assert!(err.contains("plain http"), "{err}");
+    assert!(err.contains("--insecure"), "{err}");
+    let release = Release {
+        url: "http://example.com/m.tar".to_string(),
+        sha256: "00".repeat(32),
+    };
+    let dir = TempDir::new().unwrap();
+    let err = install_release(
+        "https://example.com/index.json",
+        "m",
+        "1.0.0",
+        &release,
+        dir.path(),
+        false,
+    )
+    .unwrap_err();
+    assert!(err.contains("plain http"), "{err}");
+}
+
+#[test]
+fn http_allowed_for_loopback_or_insecure() {
+    assert!(check_transport("https://example.com/index.json", false).is_ok());
+    assert!(check_transport("/srv/index.json", false).is_ok());
+    assert!(check_transport("file:///srv/index.json", false).is_ok());
+    assert!(check_transport("http://localhost:8080/index.json", false).is_ok());
+    assert!(check_transport("http://127.0.0.1/index.json", false).is_ok());
+    assert!(check_transport("http://[::1]:8080/index.json", false).is_ok());
+    assert!(check_transport("http://example.com/index.json", true).is_ok());
+    assert!(check_transport("http://localhost.example.com/index.json", false).is_err());
+    assert!(check_transport("http://localhost@example.com/index.json", false).is_err());
+}
+
+fn publish(dir: &Path) -> (String, String) {
+    let module = dir.join("src");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(
+        module.join("module.yaml"),
+        "name: forge-demo\nversion: 1.0.0\n",
+    )
+    .unwrap();
+    fs::write(
+        module.join("agents/Reviewer.md"),
+        "---\nname: Reviewer\n---\n",
+    )
+    .unwrap();
+    let package = pack::build_package(&module).unwrap();
+    let archive = pack::write_package(&package, &dir.join("dist"), false).unwrap();
+    (archive.display().to_string(), package.sha256)
+}
+
+#[test]
+fn install_verifies_and_unpacks() {
+    let dir = TempDir::new().unwrap();
+    let (archive, sha256) = publish(dir.path());
+    let home = dir.path().join("home");
+    let release = Release {
+        url: archive,
+        sha256,
+    };
+
+    let root =
+        install_release("index.json", "forge-demo", "1.0.0", &release, &home, false).unwrap();
+    assert!(root.join("agents/Reviewer.md").is_file());
+    assert!(root.starts_with(home.join("modules")));
+}
+
+#[test]
+fn install_rejects_checksum_mismatch() {
+    let dir = TempDir::new().unwrap();
+    let (archive, _) = publish(dir.path());
+    let release = Release {
+        url: archive,
+        sha256: "00".repeat(32),
+    };
+    let err = install_release("i", "forge-demo", "1.0.0", &release, dir.path(), false).unwrap_err();
+    assert!(err.contains("checksum mismatch"));
+    assert!(!dir.path().join("modules").exists());
+}
+
+#[test]
+fn installed_records_round_trip() {
+    let dir = TempDir::new().unwrap();
+    assert!(read_installed(dir.path()).unwrap().is_empty());
+    let entry = Installed {
+        version: "1.0.0".into(),
+        root: PathBuf::from("/x"),
+    };
+    record_installed(dir.path(), "m", entry.clone()).unwrap();
+    assert_eq!(read_installed(dir.path()).unwrap()["m"], entry);
+}
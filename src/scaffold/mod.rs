@@ -0,0 +1,260 @@
+use crate::parse;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+// ─── Types ───
+
+/// A file to create, relative to the directory the scaffold targets.
+#[derive(Debug, PartialEq)]
+pub struct ScaffoldFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl ScaffoldFile {
+    fn new(path: impl Into<PathBuf>, content: String) -> Self {
+        Self {
+            path: path.into(),
+            content,
+        }
+    }
+}
+
+// ─── Name validation ───
+
+pub fn validate_module_name(name: &str) -> Result<(), String> {
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "module name {name:?} must be kebab-case (^[a-z][a-z0-9-]*$)"
+        ))
+    }
+}
+
+/// Skill names follow the same `PascalCase` rule as agent names.
+pub fn validate_skill_name(name: &str) -> Result<(), String> {
+    parse::validate_agent_name(name).map_err(|e| e.replacen("agent", "skill", 1))
+}
+
+// ─── Templates ───
+
+pub fn module_yaml(name: &str) -> String {
+    format!(
+        "name: {name}\nversion: 0.1.0\ndescription: \"{name} module. USE WHEN <describe when to use it>.\"\nevents: []\n"
+    )
+}
+
+pub(crate) fn plugin_json(name: &str) -> String {
+    let doc = serde_json::json!({
+        "name": name,
+        "version": "0.1.0",
+        "description": format!("{name} module"),
+    });
+    let mut out = serde_json::to_string_pretty(&doc).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+pub(crate) fn defaults_yaml() -> String {
+    let mut out = String::new();
+    out.push_str("# Module defaults: roster, model tiers, and skill allowlists.\n");
+    out.push_str("# Add entries with `forge new agent <Name>` / `forge new skill <Name>`.\n");
+    out.push_str("shared:\n");
+    out.push_str("  models:\n");
+    out.push_str("    fast: sonnet\n");
+    out.push_str("    strong: opus\n");
+    out.push_str("agents:\n");
+    out.push_str("skills:\n");
+    out.push_str("  claude:\n");
+    out
+}
+
+pub fn agent_md(name: &str, module_name: &str) -> String {
+    let mut out = String::new();
+    out.push_str("---\n");
+    let _ = writeln!(out, "name: {name}");
+    let _ = writeln!(
+        out,
+        "description: \"{name} specialist. USE WHEN <describe the tasks this agent handles>.\""
+    );
+    out.push_str("version: 0.1.0\n");
+    out.push_str("---\n");
+    let _ = writeln!(out, "# {name}\n");
+    out.push_str("## Role\n\n<One paragraph describing the agent's role.>\n\n");
+    out.push_str("## Expertise\n\n- <Area of expertise>\n\n");
+    out.push_str("## Instructions\n\n");
+    out.push_str("1. <Step the agent follows>\n");
+    out.push_str("2. When working in a team, report findings to the lead with SendMessage.\n\n");
+    out.push_str("## Output Format\n\n<Describe the expected output structure.>\n\n");
+    out.push_str("## Constraints\n\n");
+    out.push_str("- If you are unsure or lack the information to answer, say so.\n\n");
+    let _ = writeln!(out, "---\n\nShipped with {module_name}.");
+    out
+}
+
+pub fn skill_md(name: &str) -> String {
+    let mut out = String::new();
+    out.push_str("---\n");
+    let _ = writeln!(out, "name: {name}");
+    let _ = writeln!(
+        out,
+        "description: \"{name} workflow. USE WHEN <describe when to use it>.\""
+    );
+    out.push_str("---\n\n");
+    let _ = writeln!(out, "# {name}\n");
+    out.push_str("<Describe what the skill does.>\n\n");
+    out.push_str("## Gate Check\n\n<Conditions that must hold before running.>\n\n");
+    out.push_str("## Steps\n\n1. <Step>\n\n");
+    out.push_str("## Sequential Fallback\n\n<What to do when agent teams are unavailable.>\n");
+    out
+}
+
+pub fn skill_yaml(name: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "name: {name}");
+    let _ = writeln!(
+        out,
+        "description: \"{name} workflow. USE WHEN <describe when to use it>.\""
+    );
+    out.push_str("argument-hint: \"[task]\"\n");
+    out.push_str("providers:\n");
+    out.push_str("  claude:\n");
+    out.push_str("    enabled: true\n");
+    out
+}
+
+// ─── Plans ───
+
+/// Files for a new module directory named `name`.
+pub fn plan_module(name: &str) -> Result<Vec<ScaffoldFile>, String> {
+    validate_module_name(name)?;
+    Ok(vec![
+        ScaffoldFile::new("module.yaml", module_yaml(name)),
+        ScaffoldFile::new(".claude-plugin/plugin.json", plugin_json(name)),
+        ScaffoldFile::new("defaults.yaml", defaults_yaml()),
+        ScaffoldFile::new("agents/.gitkeep", String::new()),
+        ScaffoldFile::new("skills/.gitkeep", String::new()),
+    ])
+}
+
+/// Files for a new agent inside a module root.
+pub fn plan_agent(name: &str, module_name: &str) -> Result<Vec<ScaffoldFile>, String> {
+    parse::validate_agent_name(name)?;
+    Ok(vec![ScaffoldFile::new(
+        format!("agents/{name}.md"),
+        agent_md(name, module_name),
+    )])
+}
+
+/// Files for a new skill inside a module root.
+pub fn plan_skill(name: &str) -> Result<Vec<ScaffoldFile>, String> {
+    validate_skill_name(name)?;
+    Ok(vec![
+        ScaffoldFile::new(format!("skills/{name}/SKILL.md"), skill_md(name)),
+        ScaffoldFile::new(format!("skills/{name}/SKILL.yaml"), skill_yaml(name)),
+    ])
+}
+
+// ─── defaults.yaml roster edits ───
+
+/// Roster block registering a new agent (model + tools, as validate expects).
+pub fn agent_roster_entry(name: &str) -> String {
+    format!("{name}:\n  model: sonnet\n  tools: Read, Grep, Glob\n")
+}
+
+/// Allowlist block registering a new skill under `skills.claude`.
+pub fn skill_allowlist_entry(name: &str) -> String {
+    format!("{name}: {{}}\n")
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_content(line: &str) -> bool {
+    let t = line.trim();
+    !t.is_empty() && !t.starts_with('#')
+}
+
+/// Insert `entry` (a dedented YAML block) under the mapping at `parents`
+/// in `content`, editing text so comments and ordering survive. Missing
+/// parents are appended. Returns `Err` when the existing layout is not a
+/// plain block mapping (e.g. flow style) and cannot be edited textually.
+pub fn insert_entry(content: &str, parents: &[&str], entry: &str) -> Result<String, String> {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let mut start = 0;
+    let mut end = lines.len();
+    let mut parent_indent: Option<usize> = None;
+    let mut depth = 0;
+
+    while depth < parents.len() {
+        let key = parents[depth];
+        let child_indent = lines[start..end]
+            .iter()
+            .find(|l| is_content(l))
+            .map_or_else(|| parent_indent.map_or(0, |p| p + 2), |l| indent_of(l));
+        let found = (start..end).find(|&i| {
+            let l = &lines[i];
+            is_content(l)
+                && indent_of(l) == child_indent
+                && l.trim_start()
+                    .strip_prefix(key)
+                    .is_some_and(|r| r.starts_with(':'))
+        });
+        let Some(idx) = found else {
+            break;
+        };
+        let value = lines[idx].trim_start()[key.len() + 1..].trim();
+        if !value.is_empty() && !value.starts_with('#') && value != "{}" {
+            return Err(format!("`{key}` is not a block mapping"));
+        }
+        if value == "{}" {
+            lines[idx] = format!("{}{key}:", " ".repeat(child_indent));
+        }
+        parent_indent = Some(child_indent);
+        start = idx + 1;
+        end = (start..lines.len())
+            .find(|&i| is_content(&lines[i]) && indent_of(&lines[i]) <= child_indent)
+            .unwrap_or(lines.len());
+        depth += 1;
+    }
+
+    // Indentation for the first missing parent (or the entry itself).
+    let mut indent = lines[start..end]
+        .iter()
+        .find(|l| is_content(l))
+        .map_or_else(|| parent_indent.map_or(0, |p| p + 2), |l| indent_of(l));
+
+    let mut block = Vec::new();
+    for key in &parents[depth..] {
+        block.push(format!("{}{key}:", " ".repeat(indent)));
+        indent += 2;
+    }
+    for line in entry.lines() {
+        if line.is_empty() {
+            block.push(String::new());
+        } else {
+            block.push(format!("{}{line}", " ".repeat(indent)));
+        }
+    }
+
+    // Insert after the last content line of the section, keeping trailing
+    // blank lines and comments that belong to the next section below.
+    let insert_at = (start..end)
+        .rev()
+        .find(|&i| is_content(&lines[i]))
+        .map_or(start, |i| i + 1);
+    lines.splice(insert_at..insert_at, block);
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests;
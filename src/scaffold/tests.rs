@@ -0,0 +1,164 @@
+use super::*;
+#[cfg(feature = "validate")]
+use {crate::validate, std::fs, std::path::Path, tempfile::TempDir};
+
+#[cfg(feature = "validate")]
+fn materialize(root: &Path, files: &[ScaffoldFile]) {
+    for file in files {
+        let path = root.join(&file.path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, &file.content).unwrap();
+    }
+}
+
+#[cfg(feature = "validate")]
+fn register(root: &Path, parents: &[&str], entry: &str) {
+    let path = root.join("defaults.yaml");
+    let content = fs::read_to_string(&path).unwrap();
+    fs::write(&path, insert_entry(&content, parents, entry).unwrap()).unwrap();
+}
+
+// ─── Name validation ───
+
+#[test]
+fn module_name_kebab_ok() {
+    assert!(validate_module_name("forge-council").is_ok());
+}
+
+#[test]
+fn module_name_rejects_pascal() {
+    assert!(validate_module_name("ForgeCouncil").is_err());
+}
+
+#[test]
+fn module_name_rejects_empty() {
+    assert!(validate_module_name("").is_err());
+}
+
+#[test]
+fn skill_name_error_mentions_skill() {
+    let err = validate_skill_name("bad").unwrap_err();
+    assert!(err.starts_with("skill name"));
+}
+
+#[test]
+fn plan_agent_rejects_invalid_name() {
+    assert!(plan_agent("lowercase", "mod").is_err());
+}
+
+// ─── Templates ───
+
+#[test]
+fn plugin_json_is_valid() {
+    let v: serde_json::Value = serde_json::from_str(&plugin_json("demo")).unwrap();
+    assert_eq!(v["name"], "demo");
+}
+
+#[test]
+fn agent_md_has_frontmatter() {
+    let md = agent_md("Reviewer", "forge-demo");
+    assert_eq!(parse::fm_value(&md, "name").as_deref(), Some("Reviewer"));
+    assert!(parse::fm_value(&md, "description")
+        .unwrap()
+        .contains("USE WHEN"));
+    assert!(md.contains("Shipped with forge-demo"));
+}
+
+#[test]
+fn skill_md_has_frontmatter() {
+    let md = skill_md("Review");
+    assert_eq!(parse::fm_value(&md, "name").as_deref(), Some("Review"));
+}
+
+// ─── insert_entry ───
+
+#[test]
+fn insert_into_empty_section() {
+    let out = insert_entry("agents:\nskills:\n", &["agents"], "Dev:\n  model: fast\n").unwrap();
+    assert_eq!(out, "agents:\n  Dev:\n    model: fast\nskills:\n");
+}
+
+#[test]
+fn insert_after_existing_entries() {
+    let content = "agents:\n    Dev:\n        model: fast\n\n# skills below\nskills: {}\n";
+    let out = insert_entry(content, &["agents"], "QA:\n  model: fast\n").unwrap();
+    assert_eq!(
+        out,
+        "agents:\n    Dev:\n        model: fast\n    QA:\n      model: fast\n\n# skills below\nskills: {}\n"
+    );
+}
+
+#[test]
+fn insert_creates_missing_parents() {
+    let out = insert_entry("name: x\n", &["skills", "claude"], "Review: {}\n").unwrap();
+    assert_eq!(out, "name: x\nskills:\n  claude:\n    Review: {}\n");
+}
+
+#[test]
+fn insert_nested_existing_parent() {
+    let content = "skills:\n  claude:\n    A: {}\n  codex:\n    A: {}\n";
+    let out = insert_entry(content, &["skills", "claude"], "B: {}\n").unwrap();
+    assert_eq!(
+        out,
+        "skills:\n  claude:\n    A: {}\n    B: {}\n  codex:\n    A: {}\n"
+    );
+}
+
+#[test]
+fn insert_expands_empty_flow_mapping() {
+    let out = insert_entry("agents: {}\n", &["agents"], "Dev:\n  model: fast\n").unwrap();
+    assert_eq!(out, "agents:\n  Dev:\n    model: fast\n");
+}
+
+#[test]
+fn insert_rejects_flow_mapping() {
+    assert!(insert_entry("agents: {Dev: {}}\n", &["agents"], "QA: {}\n").is_err());
+}
+
+#[test]
+fn insert_preserves_comments() {
+    let content = "# header\nagents:\n  # roster\n  Dev:\n    model: fast\n";
+    let out = insert_entry(content, &["agents"], "QA: {}\n").unwrap();
+    assert!(out.starts_with("# header\nagents:\n  # roster\n"));
+    assert!(out.ends_with("  QA: {}\n"));
+}
+
+// ─── Generated module passes validation ───
+
+#[cfg(feature = "validate")]
+#[test]
+fn scaffolded_module_passes_validation() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    materialize(root, &plan_module("forge-demo").unwrap());
+    // lib/ is the forge-lib submodule, added by the user after scaffolding.
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::write(root.join("lib/Makefile"), "build:\n").unwrap();
+
+    materialize(root, &plan_agent("Reviewer", "forge-demo").unwrap());
+    register(root, &["agents"], &agent_roster_entry("Reviewer"));
+    materialize(root, &plan_skill("Review").unwrap());
+    register(
+        root,
+        &["skills", "claude"],
+        &skill_allowlist_entry("Review"),
+    );
+
+    for suite in [
+        validate::validate_structure(root),
+        validate::validate_agent_frontmatter(root),
+        validate::validate_defaults(root),
+        validate::validate_skills(root),
+        validate::validate_deploy_parity(root),
+        crate::dci::validate_dci(root),
+        validate::warn_skill_content(root),
+    ] {
+        let failures: Vec<_> = suite
+            .checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| &c.desc)
+            .collect();
+        assert!(failures.is_empty(), "{}: {failures:?}", suite.name);
+    }
+}
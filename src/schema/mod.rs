@@ -0,0 +1,340 @@
+//! JSON Schema checks for YAML config files, behind `yaml validate`.
+//!
+//! Covers the keywords a config schema needs: `type`, `enum`, `const`,
+//! `properties`, `required`, `additionalProperties`, `patternProperties`,
+//! `items`, `minItems`/`maxItems`, `uniqueItems`, `minLength`/`maxLength`,
+//! `pattern`, `minimum`/`maximum` (and their exclusive forms), `multipleOf`,
+//! `allOf`/`anyOf`/`oneOf`/`not`, and local `$ref`s (`#/$defs/...`,
+//! `#/definitions/...`). Other validation keywords are reported as
+//! unsupported rather than silently passing; annotations such as `title`
+//! and `description` are ignored. A `$ref` that leads back to itself without
+//! descending into the document (`{"$ref": "#"}`) is an error in the schema.
+
+use crate::error::ForgeError;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Keywords this checker does not implement; a schema using them would
+/// pass documents it should reject.
+const UNSUPPORTED: &[&str] = &[
+    "if",
+    "then",
+    "else",
+    "contains",
+    "prefixItems",
+    "propertyNames",
+    "dependentRequired",
+    "dependentSchemas",
+    "dependencies",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "$dynamicRef",
+];
+
+/// One way a document breaks its schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Dot-path of the offending value (`.agents.Dev.model`, `.` for the
+    /// whole document).
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Every violation of `schema` in `doc`, in document order. Fails when the
+/// schema's `$ref`s form a cycle.
+pub fn validate(doc: &Value, schema: &Value) -> Result<Vec<Violation>, ForgeError> {
+    let mut checker = Checker {
+        root: schema,
+        violations: Vec::new(),
+        active: Vec::new(),
+        cycle: None,
+    };
+    checker.check(doc, schema, "");
+    match checker.cycle {
+        Some(reference) => Err(ForgeError::Config(format!(
+            "schema $ref {reference} refers back to itself"
+        ))),
+        None => Ok(checker.violations),
+    }
+}
+
+struct Checker<'s> {
+    root: &'s Value,
+    violations: Vec<Violation>,
+    /// `$ref`s being followed, with the document value each was applied to.
+    /// Meeting the same pair again means the refs loop without progress.
+    active: Vec<(&'s str, *const Value)>,
+    cycle: Option<String>,
+}
+
+impl<'s> Checker<'s> {
+    fn report(&mut self, path: &str, message: impl Into<String>) {
+        self.violations.push(Violation {
+            path: if path.is_empty() {
+                ".".into()
+            } else {
+                path.into()
+            },
+            message: message.into(),
+        });
+    }
+
+    /// Whether `doc` satisfies `schema`, without reporting.
+    fn passes(&mut self, doc: &Value, schema: &'s Value) -> bool {
+        let mut probe = Checker {
+            root: self.root,
+            violations: Vec::new(),
+            active: self.active.clone(),
+            cycle: None,
+        };
+        probe.check(doc, schema, "");
+        if probe.cycle.is_some() {
+            self.cycle = probe.cycle;
+        }
+        probe.violations.is_empty()
+    }
+
+    fn check(&mut self, doc: &Value, schema: &'s Value, path: &str) {
+        if self.cycle.is_some() {
+            return;
+        }
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => return self.report(path, "no value is allowed here"),
+            Value::Object(schema) => schema,
+            _ => return self.report(path, "schema is not an object or boolean"),
+        };
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let key = (reference, std::ptr::from_ref(doc));
+            if self.active.contains(&key) {
+                self.cycle = Some(reference.to_string());
+                return;
+            }
+            match self.resolve(reference) {
+                Some(target) => {
+                    self.active.push(key);
+                    self.check(doc, target, path);
+                    self.active.pop();
+                }
+                None => self.report(path, format!("cannot resolve $ref {reference}")),
+            }
+        }
+        for keyword in UNSUPPORTED.iter().filter(|k| schema.contains_key(**k)) {
+            self.report(path, format!("schema keyword `{keyword}` is not supported"));
+        }
+        self.check_type(doc, schema, path);
+        self.check_value(doc, schema, path);
+        self.check_combinators(doc, schema, path);
+        match doc {
+            Value::Object(map) => self.check_object(map, schema, path),
+            Value::Array(items) => self.check_array(items, schema, path),
+            Value::String(s) => self.check_string(s, schema, path),
+            Value::Number(n) => self.check_number(n.as_f64().unwrap_or_default(), schema, path),
+            Value::Bool(_) | Value::Null => {}
+        }
+    }
+
+    /// A local `$ref` such as `#/$defs/agent`.
+    fn resolve(&self, reference: &str) -> Option<&'s Value> {
+        let pointer = reference.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+
+    fn check_type(&mut self, doc: &Value, schema: &Map<String, Value>, path: &str) {
+        let allowed: Vec<&str> = match schema.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => return,
+        };
+        if !allowed.iter().any(|t| has_type(doc, t)) {
+            self.report(
+                path,
+                format!(
+                    "expected {}, found {}",
+                    allowed.join(" or "),
+                    type_name(doc)
+                ),
+            );
+        }
+    }
+
+    fn check_value(&mut self, doc: &Value, schema: &Map<String, Value>, path: &str) {
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            if !options.contains(doc) {
+                let options: Vec<String> = options.iter().map(Value::to_string).collect();
+                self.report(path, format!("{doc} is not one of {}", options.join(", ")));
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != doc {
+                self.report(path, format!("expected {expected}, found {doc}"));
+            }
+        }
+    }
+
+    fn check_combinators(&mut self, doc: &Value, schema: &'s Map<String, Value>, path: &str) {
+        if let Some(Value::Array(all)) = schema.get("allOf") {
+            for sub in all {
+                self.check(doc, sub, path);
+            }
+        }
+        if let Some(Value::Array(any)) = schema.get("anyOf") {
+            if !any.iter().any(|sub| self.passes(doc, sub)) {
+                self.report(path, "does not match any schema in anyOf");
+            }
+        }
+        if let Some(Value::Array(one)) = schema.get("oneOf") {
+            let matches = one.iter().filter(|sub| self.passes(doc, sub)).count();
+            if matches != 1 {
+                self.report(
+                    path,
+                    format!("matches {matches} schemas in oneOf (expected exactly 1)"),
+                );
+            }
+        }
+        if let Some(not) = schema.get("not") {
+            if self.passes(doc, not) {
+                self.report(path, "matches a schema it must not (not)");
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        map: &Map<String, Value>,
+        schema: &'s Map<String, Value>,
+        path: &str,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    self.report(path, format!("missing required key `{key}`"));
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let patterns: Vec<(Regex, &'s Value)> = schema
+            .get("patternProperties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(pattern, sub)| Some((Regex::new(pattern).ok()?, sub)))
+            .collect();
+        for (key, value) in map {
+            let child = format!("{path}.{key}");
+            let mut matched = false;
+            if let Some(sub) = properties.and_then(|p| p.get(key)) {
+                matched = true;
+                self.check(value, sub, &child);
+            }
+            for (_, sub) in patterns.iter().filter(|(re, _)| re.is_match(key)) {
+                matched = true;
+                self.check(value, sub, &child);
+            }
+            if matched {
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => self.report(&child, "unknown key"),
+                Some(sub @ Value::Object(_)) => self.check(value, sub, &child),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_array(&mut self, items: &[Value], schema: &'s Map<String, Value>, path: &str) {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                self.report(path, format!("has {} items, fewer than {min}", items.len()));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if items.len() as u64 > max {
+                self.report(path, format!("has {} items, more than {max}", items.len()));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            for (i, item) in items.iter().enumerate() {
+                if items[..i].contains(item) {
+                    self.report(&format!("{path}[{i}]"), format!("duplicate item {item}"));
+                }
+            }
+        }
+        if let Some(sub) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                self.check(item, sub, &format!("{path}[{i}]"));
+            }
+        }
+    }
+
+    fn check_string(&mut self, s: &str, schema: &Map<String, Value>, path: &str) {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if len < min {
+                self.report(path, format!("is {len} characters, shorter than {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if len > max {
+                self.report(path, format!("is {len} characters, longer than {max}"));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            match Regex::new(pattern) {
+                Ok(re) if re.is_match(s) => {}
+                Ok(_) => self.report(path, format!("{s:?} does not match /{pattern}/")),
+                Err(e) => self.report(path, format!("invalid pattern /{pattern}/: {e}")),
+            }
+        }
+    }
+
+    fn check_number(&mut self, n: f64, schema: &Map<String, Value>, path: &str) {
+        let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+        if let Some(min) = bound("minimum").filter(|min| n < *min) {
+            self.report(path, format!("{n} is less than the minimum {min}"));
+        }
+        if let Some(max) = bound("maximum").filter(|max| n > *max) {
+            self.report(path, format!("{n} is more than the maximum {max}"));
+        }
+        if let Some(min) = bound("exclusiveMinimum").filter(|min| n <= *min) {
+            self.report(path, format!("{n} is not more than {min}"));
+        }
+        if let Some(max) = bound("exclusiveMaximum").filter(|max| n >= *max) {
+            self.report(path, format!("{n} is not less than {max}"));
+        }
+        if let Some(step) = bound("multipleOf").filter(|step| *step > 0.0) {
+            if (n / step).fract().abs() > f64::EPSILON {
+                self.report(path, format!("{n} is not a multiple of {step}"));
+            }
+        }
+    }
+}
+
+fn has_type(doc: &Value, name: &str) -> bool {
+    match name {
+        "integer" => doc.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => doc.is_number(),
+        other => type_name(doc) == other,
+    }
+}
+
+fn type_name(doc: &Value) -> &'static str {
+    match doc {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests;
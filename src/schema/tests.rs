@@ -0,0 +1,140 @@
+use super::*;
+use serde_json::json;
+
+fn messages(doc: &Value, schema: &Value) -> Vec<String> {
+    validate(doc, schema)
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn valid_document_has_no_violations() {
+    let schema = json!({
+        "type": "object",
+        "required": ["agents"],
+        "properties": {
+            "agents": {
+                "type": "object",
+                "additionalProperties": {"$ref": "#/$defs/agent"}
+            }
+        },
+        "$defs": {
+            "agent": {
+                "type": "object",
+                "properties": {"model": {"enum": ["fast", "strong"]}}
+            }
+        }
+    });
+    let doc = json!({"agents": {"Dev": {"model": "fast"}}});
+    assert!(validate(&doc, &schema).unwrap().is_empty());
+}
+
+#[test]
+fn violations_name_their_paths() {
+    let schema = json!({
+        "type": "object",
+        "required": ["name", "agents"],
+        "additionalProperties": false,
+        "properties": {
+            "name": {"type": "string"},
+            "agents": {
+                "type": "object",
+                "additionalProperties": {
+                    "properties": {
+                        "model": {"enum": ["fast", "strong"]},
+                        "tools": {"type": "array", "items": {"type": "string"}}
+                    }
+                }
+            }
+        }
+    });
+    let doc = json!({
+        "agents": {"Dev": {"model": "fastest", "tools": ["Read", 3]}},
+        "extra": true
+    });
+    assert_eq!(
+        messages(&doc, &schema),
+        [
+            ".: missing required key `name`",
+            r#".agents.Dev.model: "fastest" is not one of "fast", "strong""#,
+            ".agents.Dev.tools[1]: expected string, found number",
+            ".extra: unknown key",
+        ]
+    );
+}
+
+#[test]
+fn scalar_constraints() {
+    let schema = json!({
+        "properties": {
+            "slug": {"type": "string", "pattern": "^[a-z-]+$", "maxLength": 5},
+            "count": {"type": "integer", "minimum": 1, "maximum": 3},
+            "ratio": {"exclusiveMaximum": 1, "multipleOf": 0.5}
+        }
+    });
+    let doc = json!({"slug": "Bad_Slug", "count": 1.5, "ratio": 1});
+    assert_eq!(validate(&doc, &schema).unwrap().len(), 4);
+    let doc = json!({"slug": "ok", "count": 2, "ratio": 0.5});
+    assert!(validate(&doc, &schema).unwrap().is_empty());
+}
+
+#[test]
+fn combinators() {
+    let schema = json!({
+        "properties": {
+            "scope": {"anyOf": [{"const": "user"}, {"const": "workspace"}]},
+            "model": {"oneOf": [{"type": "string"}, {"minLength": 1}]},
+            "name": {"not": {"const": "forbidden"}}
+        }
+    });
+    let doc = json!({"scope": "global", "model": "fast", "name": "forbidden"});
+    let found = messages(&doc, &schema);
+    assert_eq!(found.len(), 3);
+    assert!(found[1].contains("matches 2 schemas in oneOf"));
+}
+
+#[test]
+fn unsupported_keywords_are_reported() {
+    let schema = json!({"if": {"type": "object"}, "then": {"required": ["a"]}});
+    assert_eq!(
+        messages(&json!({}), &schema),
+        [
+            ".: schema keyword `if` is not supported",
+            ".: schema keyword `then` is not supported",
+        ]
+    );
+    assert_eq!(
+        messages(&json!(1), &json!({"$ref": "#/$defs/missing"})),
+        [".: cannot resolve $ref #/$defs/missing"]
+    );
+}
+
+#[test]
+fn ref_cycles_are_errors() {
+    for schema in [
+        json!({"$ref": "#"}),
+        json!({"$ref": "#/$defs/a", "$defs": {"a": {"$ref": "#/$defs/b"}, "b": {"allOf": [{"$ref": "#/$defs/a"}]}}}),
+        json!({"not": {"$ref": "#"}}),
+    ] {
+        let err = validate(&json!({"x": 1}), &schema).unwrap_err();
+        assert!(err.to_string().contains("refers back to itself"), "{err}");
+    }
+}
+
+#[test]
+fn recursive_refs_follow_the_document() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "name": {"type": "string"},
+            "children": {"type": "array", "items": {"$ref": "#"}}
+        }
+    });
+    let doc = json!({"name": "a", "children": [{"name": "b", "children": [{"name": 3}]}]});
+    assert_eq!(
+        messages(&doc, &schema),
+        [".children[0].children[0].name: expected string, found number"]
+    );
+}
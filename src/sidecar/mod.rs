@@ -1,5 +1,13 @@
+use crate::deploy::provider::{CustomProvider, NameStyle, Provider};
+use crate::error::ForgeError;
+use crate::parse;
+use crate::policy::Policy;
+use crate::template::Templates;
+use serde::Serialize;
+use serde_json::json;
 use serde_yaml::Value;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
 
 pub struct ModelTiers {
     pub fast: String,
@@ -15,13 +23,51 @@ impl Default for ModelTiers {
     }
 }
 
+/// `license` and `attribution` from `module.yaml`, embedded into every
+/// deployed agent and skill so redistributed content keeps its provenance.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Provenance {
+    pub license: Option<String>,
+    pub attribution: Option<String>,
+}
+
+impl Provenance {
+    pub fn load(module_root: &Path) -> Self {
+        let content = std::fs::read_to_string(module_root.join("module.yaml")).unwrap_or_default();
+        Self {
+            license: parse::module_license(&content),
+            attribution: parse::module_attribution(&content),
+        }
+    }
+
+    /// The declared fields as frontmatter keys (`license`, `attribution`).
+    pub fn fields(&self) -> BTreeMap<String, String> {
+        [
+            ("license", &self.license),
+            ("attribution", &self.attribution),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.clone()?)))
+        .collect()
+    }
+}
+
+#[derive(Debug)]
 pub struct SidecarConfig {
     raw: Value,
+    templates: Templates,
+    provenance: Provenance,
+    policy: Policy,
 }
 
 impl Default for SidecarConfig {
     fn default() -> Self {
-        Self { raw: Value::Null }
+        Self {
+            raw: Value::Null,
+            templates: Templates::default(),
+            provenance: Provenance::default(),
+            policy: Policy::default(),
+        }
     }
 }
 
@@ -35,20 +81,100 @@ impl SidecarConfig {
             .unwrap_or(Value::Null);
 
         let merged = merge_values(defaults, config);
-        Self { raw: merged }
+        let custom_templates = provider_templates(&merged);
+        Self {
+            raw: merged,
+            templates: Templates::load(module_root).with_custom(module_root, &custom_templates),
+            provenance: Provenance::load(module_root),
+            policy: Policy::default(),
+        }
+    }
+
+    /// Merge extra YAML files over the loaded configuration, in order, each
+    /// overriding the last (`--config`). Unlike the module's own sidecars, an
+    /// explicitly named file that is missing or invalid is an error.
+    pub fn with_overrides(self, paths: &[PathBuf]) -> Result<Self, ForgeError> {
+        let mut raw = self.raw;
+        for path in paths {
+            let content = std::fs::read_to_string(path).map_err(ForgeError::io("read", path))?;
+            let overlay: Value = serde_yaml::from_str(&content).map_err(ForgeError::yaml(
+                format!("invalid YAML in {}", path.display()),
+            ))?;
+            raw = merge_values(raw, overlay);
+        }
+        Ok(Self {
+            raw,
+            templates: self.templates,
+            provenance: self.provenance,
+            policy: self.policy,
+        })
+    }
+
+    /// Override the configured `locale:` (`--locale`).
+    #[must_use]
+    pub fn with_locale(self, locale: Option<&str>) -> Self {
+        let Some(locale) = locale else {
+            return self;
+        };
+        let mut overlay = serde_yaml::Mapping::new();
+        overlay.insert("locale".into(), locale.into());
+        Self {
+            raw: merge_values(self.raw, Value::Mapping(overlay)),
+            ..self
+        }
+    }
+
+    /// Enforce an organization `policy` (see [`crate::policy`]) when
+    /// deploying agents with this configuration.
+    #[must_use]
+    pub fn with_policy(self, policy: Policy) -> Self {
+        Self { policy, ..self }
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// A digest of the whole configuration (values, templates, provenance,
+    /// policy), for caches of what a render produced. Hashes a JSON form
+    /// with sorted keys, so it does not change with key order or between
+    /// builds.
+    pub fn fingerprint(&self) -> String {
+        let canonical = json!({
+            "config": canonical_json(&self.raw),
+            "templates": self.templates,
+            "provenance": self.provenance,
+            "policy": self.policy,
+        });
+        crate::cache::digest(&[canonical.to_string().as_bytes()])
+    }
+
+    /// Locale for localized agent descriptions (`description.<locale>`).
+    pub fn locale(&self) -> Option<String> {
+        yaml_string(&self.raw, "locale").filter(|l| !l.is_empty())
+    }
+
+    /// Output templates, with the module's `templates/` overrides.
+    pub fn templates(&self) -> &Templates {
+        &self.templates
+    }
+
+    /// The module's declared license and attribution.
+    pub fn provenance(&self) -> &Provenance {
+        &self.provenance
     }
 
     pub fn provider_tiers(&self, provider: &str) -> ModelTiers {
         let global = self.global_tiers();
 
         let provider_section = navigate(&self.raw, &["providers", provider, "models"])
-            .filter(Value::is_mapping)
+            .filter(|v| v.is_mapping())
             .or_else(|| navigate(&self.raw, &["providers", provider]))
             .or_else(|| navigate(&self.raw, &[provider]));
         if let Some(section) = provider_section {
             ModelTiers {
-                fast: yaml_string(&section, "fast").unwrap_or(global.fast),
-                strong: yaml_string(&section, "strong").unwrap_or(global.strong),
+                fast: yaml_string(section, "fast").unwrap_or(global.fast),
+                strong: yaml_string(section, "strong").unwrap_or(global.strong),
             }
         } else {
             global
@@ -58,11 +184,11 @@ impl SidecarConfig {
     pub fn is_model_whitelisted(&self, provider: &str, model: &str) -> bool {
         let whitelist = navigate(&self.raw, &["providers", provider, "whitelist"])
             .or_else(|| {
-                navigate(&self.raw, &["providers", provider, "models"]).filter(Value::is_sequence)
+                navigate(&self.raw, &["providers", provider, "models"]).filter(|v| v.is_sequence())
             })
             .or_else(|| navigate(&self.raw, &[provider, "models"]));
         match whitelist {
-            Some(Value::Sequence(ref seq)) if seq.is_empty() => false,
+            Some(Value::Sequence(seq)) if seq.is_empty() => false,
             Some(Value::Sequence(seq)) => seq.iter().any(|v| match v {
                 Value::String(s) => s == model,
                 _ => false,
@@ -80,17 +206,36 @@ impl SidecarConfig {
     pub fn agent_list(&self, agent: &str, key: &str) -> Vec<String> {
         let val = navigate(&self.raw, &["agents", agent, key])
             .or_else(|| navigate(&self.raw, &[agent, key]));
-        match val {
-            Some(Value::Sequence(seq)) => seq
-                .iter()
-                .filter_map(|v| match v {
-                    Value::String(s) => Some(s.clone()),
-                    _ => None,
+        string_list(val)
+    }
+
+    /// Whether `agent` is deployed to `provider`: to every provider unless
+    /// `agents.<Name>.providers` lists the ones it is for.
+    pub fn agent_targets(&self, agent: &str, provider: &str) -> bool {
+        let val = navigate(&self.raw, &["agents", agent, "providers"])
+            .or_else(|| navigate(&self.raw, &[agent, "providers"]));
+        val.is_none() || string_list(val).iter().any(|p| p == provider)
+    }
+
+    /// Council names (keys of `councils:`), in file order.
+    pub fn councils(&self) -> Vec<String> {
+        navigate(&self.raw, &["councils"])
+            .and_then(|v| {
+                v.as_mapping().map(|m| {
+                    m.keys()
+                        .filter_map(|k| k.as_str().map(String::from))
+                        .collect()
                 })
-                .collect(),
-            Some(Value::String(s)) => s.split(", ").map(String::from).collect(),
-            _ => Vec::new(),
-        }
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn council_value(&self, council: &str, key: &str) -> Option<String> {
+        normalize_value(navigate(&self.raw, &["councils", council, key])?)
+    }
+
+    pub fn council_list(&self, council: &str, key: &str) -> Vec<String> {
+        string_list(navigate(&self.raw, &["councils", council, key]))
     }
 
     pub fn skill_value(&self, skill_name: &str, key: &str) -> Option<String> {
@@ -100,7 +245,16 @@ impl SidecarConfig {
     }
 
     pub fn provider_skills(&self, provider: &str) -> Vec<String> {
-        let Some(section) = navigate(&self.raw, &["skills", provider]) else {
+        self.provider_allowlist("skills", provider)
+    }
+
+    /// Commands enabled for `provider` (keys of `commands.<provider>`).
+    pub fn provider_commands(&self, provider: &str) -> Vec<String> {
+        self.provider_allowlist("commands", provider)
+    }
+
+    fn provider_allowlist(&self, section: &str, provider: &str) -> Vec<String> {
+        let Some(section) = navigate(&self.raw, &[section, provider]) else {
             return Vec::new();
         };
         let Some(mapping) = section.as_mapping() else {
@@ -138,19 +292,93 @@ impl SidecarConfig {
             .unwrap_or_else(|| vec!["claude".into()])
     }
 
+    /// Providers declared under `providers:` with a `dir`, for assistants
+    /// forge has no built-in support for:
+    ///
+    /// ```yaml
+    /// providers:
+    ///   acme:
+    ///     dir: .acme/agents
+    ///     extension: md         # default
+    ///     name_style: kebab     # or pascal, the default
+    ///     template: agent-acme.md
+    /// ```
+    ///
+    /// The template is a built-in or the module's `templates/<name>.j2`;
+    /// it defaults to `agent-claude.md`. Built-in providers ignore `dir`.
+    pub fn custom_providers(&self) -> Result<Vec<CustomProvider>, ForgeError> {
+        let Some(providers) = navigate(&self.raw, &["providers"]).and_then(Value::as_mapping)
+        else {
+            return Ok(Vec::new());
+        };
+        let mut custom = Vec::new();
+        for (name, section) in providers {
+            let (Some(name), Some(dir)) = (name.as_str(), yaml_string(section, "dir")) else {
+                continue;
+            };
+            if Provider::from_str(name).is_some() {
+                continue;
+            }
+            let name_style = match yaml_string(section, "name_style").as_deref() {
+                None | Some("pascal") => NameStyle::Pascal,
+                Some("kebab") => NameStyle::Kebab,
+                Some(other) => {
+                    return Err(ForgeError::Config(format!(
+                        "providers.{name}.name_style: {other:?} is not pascal or kebab"
+                    )))
+                }
+            };
+            check_custom_dir(name, &dir)?;
+            let extension = yaml_string(section, "extension").unwrap_or_else(|| "md".into());
+            let extension = extension.trim_start_matches('.');
+            if extension.is_empty() || !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(ForgeError::Config(format!(
+                    "providers.{name}.extension: {extension:?} must be letters and digits"
+                )));
+            }
+            custom.push(CustomProvider {
+                name: name.to_string(),
+                dir,
+                extension: extension.to_string(),
+                name_style,
+                template: yaml_string(section, "template")
+                    .unwrap_or_else(|| "agent-claude.md".into()),
+            });
+        }
+        Ok(custom)
+    }
+
     pub fn global_tiers(&self) -> ModelTiers {
         let shared =
             navigate(&self.raw, &["shared", "models"]).or_else(|| navigate(&self.raw, &["models"]));
         match shared {
             Some(section) => ModelTiers {
-                fast: yaml_string(&section, "fast").unwrap_or_else(|| "sonnet".to_string()),
-                strong: yaml_string(&section, "strong").unwrap_or_else(|| "opus".to_string()),
+                fast: yaml_string(section, "fast").unwrap_or_else(|| "sonnet".to_string()),
+                strong: yaml_string(section, "strong").unwrap_or_else(|| "opus".to_string()),
             },
             None => ModelTiers::default(),
         }
     }
 }
 
+/// A custom provider's `dir` stays under the home directory or workspace it
+/// is joined to: relative, with no `..`.
+fn check_custom_dir(name: &str, dir: &str) -> Result<(), ForgeError> {
+    let path = Path::new(dir);
+    let escapes = path.components().any(|c| {
+        matches!(
+            c,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    });
+    if dir.is_empty() || path.is_absolute() || escapes {
+        return Err(ForgeError::Config(format!(
+            "providers.{name}.dir: {dir:?} must be a relative path without `..`"
+        )));
+    }
+    Ok(())
+}
+
 pub fn resolve_model(model: &str, global: &ModelTiers, provider: &ModelTiers) -> String {
     if model == "fast" || model == global.fast {
         provider.fast.clone()
@@ -161,12 +389,54 @@ pub fn resolve_model(model: &str, global: &ModelTiers, provider: &ModelTiers) ->
     }
 }
 
+/// The `template` of every `providers:` entry, to load alongside the
+/// built-in overrides.
+fn provider_templates(raw: &Value) -> Vec<String> {
+    navigate(raw, &["providers"])
+        .and_then(Value::as_mapping)
+        .map(|m| {
+            m.values()
+                .filter_map(|v| yaml_string(v, "template"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `value` as JSON with every mapping's keys sorted. Keys that are not
+/// strings become their YAML text.
+fn canonical_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => json!(b),
+        Value::Number(n) => serde_json::to_value(n).unwrap_or(serde_json::Value::Null),
+        Value::String(s) => json!(s),
+        Value::Sequence(items) => items.iter().map(canonical_json).collect(),
+        Value::Mapping(map) => {
+            let sorted: BTreeMap<String, serde_json::Value> = map
+                .iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        Value::String(s) => s.clone(),
+                        other => serde_yaml::to_string(other)
+                            .unwrap_or_default()
+                            .trim_end()
+                            .to_string(),
+                    };
+                    (key, canonical_json(v))
+                })
+                .collect();
+            sorted.into_iter().collect()
+        }
+        Value::Tagged(tagged) => json!({ tagged.tag.to_string(): canonical_json(&tagged.value) }),
+    }
+}
+
 pub fn load_yaml_file(path: &Path) -> Option<Value> {
     let content = std::fs::read_to_string(path).ok()?;
     serde_yaml::from_str(&content).ok()
 }
 
-pub fn merge_values(base: Value, overlay: Value) -> Value {
+pub(crate) fn merge_values(base: Value, overlay: Value) -> Value {
     match (base, overlay) {
         (Value::Mapping(mut base_map), Value::Mapping(overlay_map)) => {
             for (k, v) in overlay_map {
@@ -184,28 +454,39 @@ pub fn merge_values(base: Value, overlay: Value) -> Value {
     }
 }
 
-fn normalize_value(val: Value) -> Option<String> {
+fn normalize_value(val: &Value) -> Option<String> {
     match val {
-        Value::String(s) => Some(s),
+        Value::String(s) => Some(s.clone()),
         Value::Bool(b) => Some(b.to_string()),
         Value::Number(n) => Some(n.to_string()),
         Value::Null => None,
-        _ => Some(serde_yaml::to_string(&val).ok()?.trim().to_string()),
+        _ => Some(serde_yaml::to_string(val).ok()?.trim().to_string()),
     }
 }
 
-fn navigate(value: &Value, keys: &[&str]) -> Option<Value> {
-    let mut current = value;
-    for key in keys {
-        current = current
-            .as_mapping()?
-            .get(Value::String((*key).to_string()))?;
+fn string_list(val: Option<&Value>) -> Vec<String> {
+    match val {
+        Some(Value::Sequence(seq)) => seq
+            .iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect(),
+        Some(Value::String(s)) => s.split(", ").map(String::from).collect(),
+        _ => Vec::new(),
     }
-    Some(current.clone())
+}
+
+/// The node at `keys` under `value`, borrowed: lookups run per agent and
+/// provider, so callers copy out only the scalars they need.
+fn navigate<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter()
+        .try_fold(value, |current, key| current.as_mapping()?.get(*key))
 }
 
 fn yaml_string(value: &Value, key: &str) -> Option<String> {
-    match value.as_mapping()?.get(Value::String(key.to_string()))? {
+    match value.as_mapping()?.get(key)? {
         Value::String(s) => Some(s.clone()),
         _ => None,
     }
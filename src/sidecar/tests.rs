@@ -111,6 +111,47 @@ fn load_config_overrides_defaults() {
     assert_eq!(tiers.strong, "sonnet");
 }
 
+#[test]
+fn overrides_merge_in_order() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(
+        dir.path(),
+        "defaults.yaml",
+        "shared:\n  models:\n    fast: haiku\n    strong: sonnet\n",
+    );
+    write_yaml(
+        dir.path(),
+        "a.yaml",
+        "shared:\n  models:\n    fast: a\n    strong: a\n",
+    );
+    write_yaml(dir.path(), "b.yaml", "shared:\n  models:\n    strong: b\n");
+    let config = SidecarConfig::load(dir.path())
+        .with_overrides(&[dir.path().join("a.yaml"), dir.path().join("b.yaml")])
+        .unwrap();
+    let tiers = config.global_tiers();
+    assert_eq!(tiers.fast, "a");
+    assert_eq!(tiers.strong, "b");
+}
+
+#[test]
+fn overrides_missing_file_is_error() {
+    let dir = TempDir::new().unwrap();
+    let result = SidecarConfig::load(dir.path()).with_overrides(&[dir.path().join("nope.yaml")]);
+    let err = result.unwrap_err();
+    assert!(matches!(err, ForgeError::Io { .. }));
+    assert!(err.to_string().contains("nope.yaml"));
+}
+
+#[test]
+fn overrides_invalid_yaml_is_error() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(dir.path(), "bad.yaml", "key: [unclosed\n");
+    let result = SidecarConfig::load(dir.path()).with_overrides(&[dir.path().join("bad.yaml")]);
+    let err = result.unwrap_err();
+    assert!(matches!(err, ForgeError::Yaml { .. }));
+    assert!(err.to_string().contains("invalid YAML"));
+}
+
 #[test]
 fn load_missing_dir_returns_defaults() {
     let config = SidecarConfig::load(Path::new("/nonexistent/path/that/wont/exist"));
@@ -384,6 +425,21 @@ fn agent_list_flat_fallback() {
     );
 }
 
+#[test]
+fn agent_targets_listed_providers_only() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(
+        dir.path(),
+        "defaults.yaml",
+        "agents:\n  Developer:\n    providers: [claude, codex]\n",
+    );
+    let config = SidecarConfig::load(dir.path());
+    assert!(config.agent_targets("Developer", "claude"));
+    assert!(config.agent_targets("Developer", "codex"));
+    assert!(!config.agent_targets("Developer", "gemini"));
+    assert!(config.agent_targets("Reviewer", "gemini"));
+}
+
 // --- skill_value ---
 
 #[test]
@@ -546,8 +602,17 @@ fn merge_deep_nested() {
     let merged = merge_values(base, overlay);
     let fast = navigate(&merged, &["shared", "models", "fast"]).unwrap();
     let strong = navigate(&merged, &["shared", "models", "strong"]).unwrap();
-    assert_eq!(fast, Value::String("sonnet".into()));
-    assert_eq!(strong, Value::String("opus".into()));
+    assert_eq!(*fast, Value::String("sonnet".into()));
+    assert_eq!(*strong, Value::String("opus".into()));
+}
+
+#[test]
+fn navigate_borrows_the_node() {
+    let doc: Value = serde_yaml::from_str("agents:\n  Dev:\n    model: fast\n").unwrap();
+    let agents = navigate(&doc, &["agents"]).unwrap();
+    let dev = navigate(&doc, &["agents", "Dev"]).unwrap();
+    assert!(std::ptr::eq(dev, agents.get("Dev").unwrap()));
+    assert!(navigate(&doc, &["agents", "Dev", "model", "x"]).is_none());
 }
 
 // --- provider_reasoning_effort ---
@@ -627,6 +692,92 @@ fn providers_includes_opencode() {
     assert!(providers.contains(&"opencode".to_string()));
 }
 
+#[test]
+fn custom_providers_are_entries_with_a_dir() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(
+        dir.path(),
+        "defaults.yaml",
+        "providers:\n  claude:\n    fast: sonnet\n    dir: .x\n  acme:\n    dir: .acme/agents\n\
+         \x20   extension: .txt\n    name_style: kebab\n    template: agent-acme.txt\n\
+         \x20 plain:\n    dir: plain\n",
+    );
+    let config = SidecarConfig::load(dir.path());
+    assert_eq!(
+        config.custom_providers().unwrap(),
+        vec![
+            CustomProvider {
+                name: "acme".into(),
+                dir: ".acme/agents".into(),
+                extension: "txt".into(),
+                name_style: NameStyle::Kebab,
+                template: "agent-acme.txt".into(),
+            },
+            CustomProvider {
+                name: "plain".into(),
+                dir: "plain".into(),
+                extension: "md".into(),
+                name_style: NameStyle::Pascal,
+                template: "agent-claude.md".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn custom_provider_rejects_unknown_name_style() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(
+        dir.path(),
+        "defaults.yaml",
+        "providers:\n  acme:\n    dir: .acme\n    name_style: snake\n",
+    );
+    let err = SidecarConfig::load(dir.path())
+        .custom_providers()
+        .unwrap_err();
+    assert!(err.to_string().contains("providers.acme.name_style"));
+}
+
+#[test]
+fn custom_provider_rejects_escaping_dirs() {
+    for dir in [
+        "../../../tmp/escaped/agents",
+        "/tmp/escaped",
+        ".acme/../../x",
+        "\"\"",
+    ] {
+        let root = TempDir::new().unwrap();
+        write_yaml(
+            root.path(),
+            "defaults.yaml",
+            &format!("providers:\n  acme:\n    dir: {dir}\n"),
+        );
+        let err = SidecarConfig::load(root.path())
+            .custom_providers()
+            .unwrap_err();
+        assert!(err.to_string().contains("providers.acme.dir"), "{dir}");
+    }
+}
+
+#[test]
+fn custom_provider_rejects_odd_extensions() {
+    for extension in ["txt/../../x", "a.b", "\"\""] {
+        let root = TempDir::new().unwrap();
+        write_yaml(
+            root.path(),
+            "defaults.yaml",
+            &format!("providers:\n  acme:\n    dir: .acme\n    extension: {extension}\n"),
+        );
+        let err = SidecarConfig::load(root.path())
+            .custom_providers()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("providers.acme.extension"),
+            "{extension}"
+        );
+    }
+}
+
 #[test]
 fn providers_defaults_to_claude_when_missing() {
     let config = SidecarConfig::default();
@@ -761,6 +912,46 @@ fn provider_skill_value_config_override() {
     );
 }
 
+#[test]
+fn councils_in_file_order_with_roles() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(
+        dir.path(),
+        "defaults.yaml",
+        "councils:\n  Review:\n    description: Code review\n    roles:\n      - Critic\n      - Tester\n  Arch:\n    roles: [Architect]\n",
+    );
+    let config = SidecarConfig::load(dir.path());
+    assert_eq!(config.councils(), vec!["Review", "Arch"]);
+    assert_eq!(
+        config.council_list("Review", "roles"),
+        vec!["Critic", "Tester"]
+    );
+    assert_eq!(
+        config.council_value("Review", "description"),
+        Some("Code review".into())
+    );
+    assert!(config.council_list("Missing", "roles").is_empty());
+}
+
+#[test]
+fn provenance_read_from_module_yaml() {
+    let dir = TempDir::new().unwrap();
+    write_yaml(
+        dir.path(),
+        "module.yaml",
+        "name: forge-demo\nlicense: MIT\nattribution: \"Jane Doe, https://example.com\"\n",
+    );
+    let config = SidecarConfig::load(dir.path());
+    let provenance = config.provenance();
+    assert_eq!(provenance.license.as_deref(), Some("MIT"));
+    assert_eq!(
+        provenance.attribution.as_deref(),
+        Some("Jane Doe, https://example.com")
+    );
+    assert_eq!(provenance.fields().len(), 2);
+    assert!(SidecarConfig::default().provenance().fields().is_empty());
+}
+
 // --- proptest ---
 
 #[cfg(test)]
@@ -787,3 +978,38 @@ mod proptests {
         }
     }
 }
+
+#[test]
+fn fingerprint_follows_configuration() {
+    let config = SidecarConfig::default();
+    assert_eq!(config.fingerprint(), SidecarConfig::default().fingerprint());
+    assert_ne!(
+        config.fingerprint(),
+        SidecarConfig::default()
+            .with_locale(Some("cs"))
+            .fingerprint()
+    );
+}
+
+#[test]
+fn fingerprint_ignores_key_order() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("defaults.yaml"),
+        "models:\n  fast: haiku\n  strong: opus\nlocale: cs\n",
+    )
+    .unwrap();
+    let first = SidecarConfig::load(dir.path()).fingerprint();
+    std::fs::write(
+        dir.path().join("defaults.yaml"),
+        "locale: cs\nmodels:\n  strong: opus\n  fast: haiku\n",
+    )
+    .unwrap();
+    assert_eq!(SidecarConfig::load(dir.path()).fingerprint(), first);
+    std::fs::write(
+        dir.path().join("defaults.yaml"),
+        "locale: cs\nmodels:\n  strong: opus\n  fast: sonnet\n",
+    )
+    .unwrap();
+    assert_ne!(SidecarConfig::load(dir.path()).fingerprint(), first);
+}
@@ -1,6 +1,12 @@
+use crate::cache;
 use crate::deploy::provider::Provider;
+use crate::error::ForgeError;
+use crate::frontmatter::SkillFrontmatter;
 use crate::parse;
+use crate::progress::{Operation, ProgressSink};
 use crate::sidecar::SidecarConfig;
+use crate::template::{yaml_scalar, Templates};
+use crate::vfs::Vfs;
 use std::collections::BTreeMap;
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
@@ -20,12 +26,16 @@ pub enum SkillInstallAction {
         skill_name: String,
         src_dir: PathBuf,
         dst_dir: PathBuf,
+        /// Frontmatter merged into the deployed SKILL.md: the module's
+        /// provenance plus the SKILL.yaml `claude:` fields.
         claude_fields: BTreeMap<String, String>,
     },
     GeminiCli {
         skill_name: String,
         skill_dir: PathBuf,
         scope: String,
+        /// Module provenance merged into the SKILL.md handed to the CLI.
+        fields: BTreeMap<String, String>,
     },
     Skipped {
         skill_name: String,
@@ -46,8 +56,9 @@ pub fn extract_skill_meta(skill_dir: &Path) -> Option<SkillMeta> {
     let md_path = skill_dir.join("SKILL.md");
     let content = std::fs::read_to_string(&md_path).ok()?;
 
-    let name = parse::fm_value(&content, "name").filter(|n| !n.is_empty())?;
-    let description = parse::fm_value(&content, "description").unwrap_or_else(|| "Skill".into());
+    let fm = SkillFrontmatter::from_markdown(&content).ok()?;
+    let name = fm.name.filter(|n| !n.is_empty())?;
+    let description = fm.description.unwrap_or_else(|| "Skill".into());
 
     let claude_fields = read_claude_fields(&skill_dir.join("SKILL.yaml"));
 
@@ -96,7 +107,7 @@ fn read_claude_fields(yaml_path: &Path) -> BTreeMap<String, String> {
 pub fn plan_skill_install(
     meta: &SkillMeta,
     skill_dir: &Path,
-    provider: Provider,
+    provider: &Provider,
     dst_dir: &Path,
     default_scope: &str,
     config: &SidecarConfig,
@@ -109,6 +120,7 @@ pub fn plan_skill_install(
         };
     }
 
+    let provenance = config.provenance().fields();
     match provider {
         Provider::Gemini => {
             let scope = config
@@ -118,30 +130,41 @@ pub fn plan_skill_install(
                 skill_name: meta.name.clone(),
                 skill_dir: skill_dir.to_path_buf(),
                 scope,
+                fields: provenance,
+            }
+        }
+        Provider::Claude
+        | Provider::Codex
+        | Provider::OpenCode
+        | Provider::Cursor
+        | Provider::Windsurf
+        | Provider::Copilot
+        | Provider::Zed
+        | Provider::Custom(_) => {
+            let mut claude_fields = provenance;
+            claude_fields.extend(meta.claude_fields.clone());
+            SkillInstallAction::Copy {
+                skill_name: meta.name.clone(),
+                src_dir: skill_dir.to_path_buf(),
+                dst_dir: dst_dir.to_path_buf(),
+                claude_fields,
             }
         }
-        Provider::Claude | Provider::Codex | Provider::OpenCode => SkillInstallAction::Copy {
-            skill_name: meta.name.clone(),
-            src_dir: skill_dir.to_path_buf(),
-            dst_dir: dst_dir.to_path_buf(),
-            claude_fields: meta.claude_fields.clone(),
-        },
     }
 }
 
 pub fn plan_skills_from_dir(
     root_dir: &Path,
-    provider: Provider,
+    provider: &Provider,
     dst_dir: &Path,
     default_scope: &str,
     config: &SidecarConfig,
-) -> Result<Vec<SkillInstallAction>, String> {
+) -> Result<Vec<SkillInstallAction>, ForgeError> {
     if !root_dir.is_dir() {
         return Ok(Vec::new());
     }
 
-    let entries = std::fs::read_dir(root_dir)
-        .map_err(|e| format!("failed to read {}: {e}", root_dir.display()))?;
+    let entries = std::fs::read_dir(root_dir).map_err(ForgeError::io("read", root_dir))?;
 
     let mut skill_dirs: Vec<_> = entries
         .filter_map(Result::ok)
@@ -170,45 +193,171 @@ pub fn plan_skills_from_dir(
 
 // ─── Skill Copy ───
 
-pub fn execute_skill_copy(src_dir: &Path, skill_name: &str, dst_dir: &Path) -> Result<(), String> {
-    std::fs::create_dir_all(dst_dir)
-        .map_err(|e| format!("failed to create {}: {e}", dst_dir.display()))?;
+/// Copy the skill at `src_dir` to `dst_dir/<skill_name>` through `vfs`,
+/// replacing any earlier copy. `progress` sees one `skill-copy` item per
+/// file.
+pub fn execute_skill_copy(
+    vfs: &dyn Vfs,
+    src_dir: &Path,
+    skill_name: &str,
+    dst_dir: &Path,
+    progress: &mut dyn ProgressSink,
+) -> Result<(), ForgeError> {
+    let _span = tracing::debug_span!(
+        "skill_copy",
+        skill = skill_name,
+        src = %src_dir.display(),
+        dst = %dst_dir.display()
+    )
+    .entered();
+    vfs.create_dir_all(dst_dir)
+        .map_err(ForgeError::io("create", dst_dir))?;
+
+    let target = dst_dir.join(skill_name);
+    clear_target(vfs, src_dir, &target)?;
+
+    let mut operation = Operation::start(progress, "skill-copy", count_files(vfs, src_dir));
+    copy_dir_recursive(vfs, src_dir, &target, &target, &mut operation)
+}
 
+/// Symlink `dst_dir/<skill_name>` to the skill at `src_dir` through `vfs`,
+/// replacing any earlier copy or link, so edits to the source take effect
+/// at once. Unlike a copy, no provenance fields are merged into `SKILL.md`.
+pub fn execute_skill_link(
+    vfs: &dyn Vfs,
+    src_dir: &Path,
+    skill_name: &str,
+    dst_dir: &Path,
+) -> Result<(), ForgeError> {
+    vfs.create_dir_all(dst_dir)
+        .map_err(ForgeError::io("create", dst_dir))?;
     let target = dst_dir.join(skill_name);
-    if target.is_symlink() {
-        return Err(format!("destination is a symlink: {}", target.display()));
+    if vfs.read_link(&target).is_ok_and(|t| t == src_dir) {
+        return Ok(());
     }
-    if target.exists() {
-        std::fs::remove_dir_all(&target)
-            .map_err(|e| format!("failed to remove {}: {e}", target.display()))?;
+    clear_target(vfs, src_dir, &target)?;
+    tracing::debug!(path = %target.display(), src = %src_dir.display(), "linking");
+    vfs.symlink(src_dir, &target)
+        .map_err(ForgeError::io("link", &target))
+}
+
+/// Remove the earlier copy of a skill at `target`, or the link an earlier
+/// [`execute_skill_link`] of `src_dir` left there. Any other symlink is
+/// refused.
+fn clear_target(vfs: &dyn Vfs, src_dir: &Path, target: &Path) -> Result<(), ForgeError> {
+    if vfs.is_symlink(target) {
+        let name = src_dir.file_name().unwrap_or_default();
+        if !crate::vfs::is_source_link(vfs, target, name) {
+            return Err(ForgeError::Validation(format!(
+                "destination is a symlink: {}",
+                target.display()
+            )));
+        }
+        tracing::debug!(path = %target.display(), "replacing previous link");
+        vfs.remove_file(target)
+            .map_err(ForgeError::io("remove", target))?;
+    } else if vfs.exists(target) {
+        tracing::debug!(path = %target.display(), "replacing previous copy");
+        vfs.remove_dir_all(target)
+            .map_err(ForgeError::io("remove", target))?;
     }
+    Ok(())
+}
+
+/// [`crate::cache::digest`] of everything a copy of the skill at `src_dir`
+/// deployed as `skill_name` with `fields` merged into its `SKILL.md`
+/// depends on, for the deploy cache.
+pub fn skill_digest(
+    vfs: &dyn Vfs,
+    src_dir: &Path,
+    skill_name: &str,
+    fields: &BTreeMap<String, String>,
+) -> Result<String, ForgeError> {
+    let files = cache::digest_tree(vfs, src_dir, src_dir)?;
+    let files = serde_yaml::to_string(&files)
+        .map_err(ForgeError::yaml("failed to serialize skill digests"))?;
+    let fields = serde_yaml::to_string(fields)
+        .map_err(ForgeError::yaml("failed to serialize skill fields"))?;
+    Ok(cache::digest(&[
+        env!("CARGO_PKG_VERSION").as_bytes(),
+        skill_name.as_bytes(),
+        files.as_bytes(),
+        fields.as_bytes(),
+    ]))
+}
+
+/// [`execute_skill_copy`] on tokio's blocking pool.
+#[cfg(feature = "async")]
+pub async fn execute_skill_copy_async(
+    src_dir: PathBuf,
+    skill_name: String,
+    dst_dir: PathBuf,
+) -> Result<(), ForgeError> {
+    tokio::task::spawn_blocking(move || {
+        execute_skill_copy(
+            &RealFs,
+            &src_dir,
+            &skill_name,
+            &dst_dir,
+            &mut crate::progress::NoProgress,
+        )
+    })
+    .await?
+}
 
-    copy_dir_recursive(src_dir, &target)
+/// Files [`copy_dir_recursive`] copies from `dir`.
+fn count_files(vfs: &dyn Vfs, dir: &Path) -> usize {
+    let Ok(entries) = vfs.read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .iter()
+        .filter(|path| path.file_name().is_some_and(|n| n != "SKILL.yaml"))
+        .map(|path| {
+            if vfs.is_dir(path) {
+                count_files(vfs, path)
+            } else {
+                1
+            }
+        })
+        .sum()
 }
 
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
-    std::fs::create_dir_all(dst).map_err(|e| format!("failed to create {}: {e}", dst.display()))?;
+fn copy_dir_recursive(
+    vfs: &dyn Vfs,
+    src: &Path,
+    dst: &Path,
+    root: &Path,
+    operation: &mut Operation<'_>,
+) -> Result<(), ForgeError> {
+    vfs.create_dir_all(dst)
+        .map_err(ForgeError::io("create", dst))?;
 
-    let entries =
-        std::fs::read_dir(src).map_err(|e| format!("failed to read {}: {e}", src.display()))?;
+    let entries = vfs.read_dir(src).map_err(ForgeError::io("read", src))?;
 
-    for entry in entries.filter_map(Result::ok) {
-        let src_path = entry.path();
-        let name = entry.file_name();
+    for src_path in entries {
+        let Some(name) = src_path.file_name() else {
+            continue;
+        };
         if name == "SKILL.yaml" {
             continue;
         }
         let dst_path = dst.join(name);
-        if src_path.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
+        if vfs.is_dir(&src_path) {
+            copy_dir_recursive(vfs, &src_path, &dst_path, root, operation)?;
         } else {
-            std::fs::copy(&src_path, &dst_path).map_err(|e| {
-                format!(
-                    "failed to copy {} to {}: {e}",
-                    src_path.display(),
-                    dst_path.display()
-                )
-            })?;
+            vfs.copy(&src_path, &dst_path)
+                .map_err(|source| ForgeError::Io {
+                    context: format!(
+                        "failed to copy {} to {}",
+                        src_path.display(),
+                        dst_path.display()
+                    ),
+                    source,
+                })?;
+            tracing::trace!(file = %dst_path.display(), "copied");
+            let relative = dst_path.strip_prefix(root).unwrap_or(&dst_path);
+            operation.item(&relative.to_string_lossy(), "copied");
         }
     }
 
@@ -252,16 +401,17 @@ pub fn merge_claude_fields(skill_md: &str, fields: &BTreeMap<String, String>) ->
 // ─── Orphan Cleanup ───
 
 pub fn clean_orphaned_skills(
+    vfs: &dyn Vfs,
     dst_dir: &Path,
     module_name: &str,
     current_skills: &[String],
     dry_run: bool,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, ForgeError> {
     if module_name.is_empty() {
         return Ok(Vec::new());
     }
 
-    let previous = crate::manifest::read(dst_dir, module_name);
+    let previous = crate::manifest::read(vfs, dst_dir, module_name);
     let mut removed = Vec::new();
 
     for name in &previous {
@@ -269,13 +419,14 @@ pub fn clean_orphaned_skills(
             continue;
         }
         let path = dst_dir.join(name);
-        if !path.is_dir() {
+        if !vfs.is_dir(&path) {
             continue;
         }
         if !dry_run {
-            std::fs::remove_dir_all(&path)
-                .map_err(|e| format!("failed to remove {}: {e}", path.display()))?;
+            vfs.remove_dir_all(&path)
+                .map_err(ForgeError::io("remove", &path))?;
         }
+        tracing::debug!(skill = %name, dry_run, "removed orphan");
         removed.push(name.clone());
     }
 
@@ -284,75 +435,62 @@ pub fn clean_orphaned_skills(
 
 // ─── Skill Generation (Codex wrappers) ───
 
-pub fn format_agent_skill_md(
+fn wrapper_context(
     agent_name: &str,
     description: &str,
     body: &str,
     source_filename: &str,
-) -> String {
-    let mut out = String::new();
-    out.push_str("---\n");
-    let _ = writeln!(out, "name: {agent_name}");
-    let _ = writeln!(out, "description: {}", yaml_scalar(description));
-    let _ = writeln!(
-        out,
-        "argument-hint: {}",
-        yaml_scalar(&format!("[task, files, or question for {agent_name}]"))
-    );
-    out.push_str("---\n\n");
-    let _ = writeln!(out, "# {agent_name}");
-    out.push('\n');
-    let _ = writeln!(
-        out,
-        "> Generated from agents/{source_filename}. Do not edit manually."
-    );
-    out.push('\n');
-    out.push_str("Use the specialist guidance below to handle the user's request.\n\n");
-    out.push_str(body);
+) -> serde_json::Value {
+    let mut body = body.to_string();
     if !body.ends_with('\n') {
-        out.push('\n');
+        body.push('\n');
     }
-    out
+    serde_json::json!({
+        "name": agent_name,
+        "description": description,
+        "argument_hint": format!("[task, files, or question for {agent_name}]"),
+        "source": source_filename,
+        "body": body,
+    })
 }
 
-pub fn format_agent_skill_yaml(
+pub fn format_agent_skill_md(
     agent_name: &str,
     description: &str,
+    body: &str,
     source_filename: &str,
 ) -> String {
-    let mut out = String::new();
-    let _ = writeln!(out, "name: {agent_name}");
-    let _ = writeln!(out, "description: {}", yaml_scalar(description));
-    let _ = writeln!(
-        out,
-        "argument-hint: {}",
-        yaml_scalar(&format!("[task, files, or question for {agent_name}]"))
-    );
-    out.push_str("providers:\n");
-    out.push_str("  claude:\n");
-    out.push_str("    enabled: false\n");
-    out.push_str("  gemini:\n");
-    out.push_str("    enabled: false\n");
-    out.push_str("  codex:\n");
-    out.push_str("    enabled: true\n");
-    out.push_str("generation:\n");
-    out.push_str("  method: generated-from-agent\n");
-    let _ = writeln!(out, "  agent: {agent_name}");
-    let _ = writeln!(out, "  source: {source_filename}");
-    out
+    let context = wrapper_context(agent_name, description, body, source_filename);
+    Templates::render_builtin("skill-wrapper.md", &context)
 }
 
-fn yaml_scalar(s: &str) -> String {
-    serde_yaml::to_string(s)
-        .unwrap_or_else(|_| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")))
-        .trim()
-        .to_string()
+pub fn format_agent_skill_yaml(
+    agent_name: &str,
+    description: &str,
+    source_filename: &str,
+) -> String {
+    let context = wrapper_context(agent_name, description, "", source_filename);
+    Templates::render_builtin("skill-wrapper.yaml", &context)
 }
 
 pub fn generate_skill_from_agent(content: &str, filename: &str) -> Option<GeneratedSkill> {
-    let agent_name = parse::fm_value(content, "claude.name")
+    render_skill_from_agent(&Templates::default(), content, filename)
+        .unwrap_or_else(|e| unreachable!("{e}"))
+}
+
+/// [`generate_skill_from_agent`] through `templates`, so a module's
+/// `templates/skill-wrapper.*.j2` overrides apply.
+pub fn render_skill_from_agent(
+    templates: &Templates,
+    content: &str,
+    filename: &str,
+) -> Result<Option<GeneratedSkill>, ForgeError> {
+    let Some(agent_name) = parse::fm_value(content, "claude.name")
         .or_else(|| parse::fm_value(content, "title"))
-        .filter(|n| !n.is_empty())?;
+        .filter(|n| !n.is_empty())
+    else {
+        return Ok(None);
+    };
 
     let description = parse::fm_value(content, "claude.description")
         .or_else(|| parse::fm_value(content, "description"))
@@ -360,23 +498,27 @@ pub fn generate_skill_from_agent(content: &str, filename: &str) -> Option<Genera
 
     let body = parse::fm_body(content);
 
-    let skill_md = format_agent_skill_md(&agent_name, &description, body, filename);
-    let skill_yaml = format_agent_skill_yaml(&agent_name, &description, filename);
-
-    Some(GeneratedSkill {
+    let wrapper = wrapper_context(&agent_name, &description, body, filename);
+    Ok(Some(GeneratedSkill {
+        skill_md: templates
+            .render("skill-wrapper.md", &wrapper)
+            .map_err(ForgeError::Config)?,
+        skill_yaml: templates
+            .render("skill-wrapper.yaml", &wrapper)
+            .map_err(ForgeError::Config)?,
         agent_name,
-        skill_md,
-        skill_yaml,
-    })
+    }))
 }
 
-pub fn generate_skills_from_agents_dir(agents_dir: &Path) -> Result<Vec<GeneratedSkill>, String> {
+pub fn generate_skills_from_agents_dir(
+    agents_dir: &Path,
+    templates: &Templates,
+) -> Result<Vec<GeneratedSkill>, ForgeError> {
     if !agents_dir.is_dir() {
         return Ok(Vec::new());
     }
 
-    let entries = std::fs::read_dir(agents_dir)
-        .map_err(|e| format!("failed to read {}: {e}", agents_dir.display()))?;
+    let entries = std::fs::read_dir(agents_dir).map_err(ForgeError::io("read", agents_dir))?;
 
     let mut files: Vec<_> = entries
         .filter_map(Result::ok)
@@ -388,9 +530,8 @@ pub fn generate_skills_from_agents_dir(agents_dir: &Path) -> Result<Vec<Generate
     for entry in files {
         let path = entry.path();
         let filename = entry.file_name().to_string_lossy().to_string();
-        let content = std::fs::read_to_string(&path)
-            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
-        if let Some(skill) = generate_skill_from_agent(&content, &filename) {
+        let content = std::fs::read_to_string(&path).map_err(ForgeError::io("read", &path))?;
+        if let Some(skill) = render_skill_from_agent(templates, &content, &filename)? {
             results.push(skill);
         }
     }
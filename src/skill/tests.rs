@@ -1,5 +1,7 @@
 use super::*;
+use crate::progress::NoProgress;
 use crate::sidecar::SidecarConfig;
+use crate::vfs::{MemoryFs, RealFs};
 use std::fs;
 use tempfile::TempDir;
 
@@ -128,7 +130,7 @@ fn plan_copy_when_in_allowlist() {
     let action = plan_skill_install(
         &meta,
         Path::new("/src"),
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -150,7 +152,7 @@ fn plan_skipped_when_not_in_allowlist() {
     let action = plan_skill_install(
         &meta,
         Path::new("/src"),
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -169,7 +171,7 @@ fn plan_skipped_when_empty_allowlist() {
     let action = plan_skill_install(
         &meta,
         Path::new("/src"),
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -189,7 +191,7 @@ fn plan_gemini_returns_cli_action() {
     let action = plan_skill_install(
         &meta,
         Path::new("/src"),
-        Provider::Gemini,
+        &Provider::Gemini,
         Path::new("/dst"),
         "user",
         &config,
@@ -212,7 +214,7 @@ fn plan_gemini_scope_from_config() {
     let action = plan_skill_install(
         &meta,
         Path::new("/src"),
-        Provider::Gemini,
+        &Provider::Gemini,
         Path::new("/dst"),
         "user",
         &config,
@@ -236,7 +238,7 @@ fn plan_copy_carries_claude_fields() {
     let action = plan_skill_install(
         &meta,
         Path::new("/src"),
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -254,6 +256,44 @@ fn plan_copy_carries_claude_fields() {
     }
 }
 
+#[test]
+fn plan_carries_module_provenance() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("module.yaml"), "name: m\nlicense: MIT\n").unwrap();
+    let config = config_with_allowlist(
+        dir.path(),
+        "skills:\n    claude:\n        WikiLink:\n    gemini:\n        WikiLink:\n",
+    );
+    let mut fields = BTreeMap::new();
+    fields.insert("license".into(), "Apache-2.0".into());
+    let meta = SkillMeta {
+        name: "WikiLink".into(),
+        description: "d".into(),
+        claude_fields: fields,
+    };
+    let plan = |provider| {
+        plan_skill_install(
+            &meta,
+            Path::new("/src"),
+            provider,
+            Path::new("/dst"),
+            "user",
+            &config,
+        )
+    };
+    // A skill's own SKILL.yaml field wins over the module default.
+    assert!(matches!(
+        plan(&Provider::Claude),
+        SkillInstallAction::Copy { ref claude_fields, .. }
+            if claude_fields.get("license").map(String::as_str) == Some("Apache-2.0")
+    ));
+    assert!(matches!(
+        plan(&Provider::Gemini),
+        SkillInstallAction::GeminiCli { ref fields, .. }
+            if fields.get("license").map(String::as_str) == Some("MIT")
+    ));
+}
+
 // ─── plan_skills_from_dir ───
 
 #[test]
@@ -287,7 +327,7 @@ fn plan_from_dir_with_allowlist() {
 
     let actions = plan_skills_from_dir(
         &root,
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -328,7 +368,7 @@ fn plan_from_dir_no_skill_yaml_needed() {
 
     let actions = plan_skills_from_dir(
         &root,
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -347,7 +387,7 @@ fn plan_from_dir_empty() {
     let config = SidecarConfig::default();
     let actions = plan_skills_from_dir(
         dir.path(),
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -361,7 +401,7 @@ fn plan_from_dir_missing_returns_empty() {
     let config = SidecarConfig::default();
     let actions = plan_skills_from_dir(
         Path::new("/nonexistent"),
-        Provider::Claude,
+        &Provider::Claude,
         Path::new("/dst"),
         "workspace",
         &config,
@@ -433,7 +473,7 @@ fn execute_copy_creates_and_copies() {
     fs::write(src.join("helper.sh"), "#!/bin/bash").unwrap();
 
     let dst = dir.path().join("dst");
-    execute_skill_copy(&src, "TestSkill", &dst).unwrap();
+    execute_skill_copy(&RealFs, &src, "TestSkill", &dst, &mut NoProgress).unwrap();
 
     assert!(dst.join("TestSkill").join("SKILL.md").exists());
     assert!(dst.join("TestSkill").join("helper.sh").exists());
@@ -451,11 +491,46 @@ fn execute_copy_replaces_existing() {
     fs::create_dir_all(&existing).unwrap();
     fs::write(existing.join("SKILL.md"), "# Old").unwrap();
 
-    execute_skill_copy(&src, "TestSkill", &dst).unwrap();
+    execute_skill_copy(&RealFs, &src, "TestSkill", &dst, &mut NoProgress).unwrap();
     let content = fs::read_to_string(dst.join("TestSkill").join("SKILL.md")).unwrap();
     assert_eq!(content, "# New");
 }
 
+#[test]
+fn execute_copy_in_memory() {
+    let vfs = MemoryFs::new()
+        .with_file("/src/SKILL.md", "# Test")
+        .with_file("/src/SKILL.yaml", "name: Test")
+        .with_file("/src/lib/run.sh", "echo");
+    execute_skill_copy(
+        &vfs,
+        Path::new("/src"),
+        "Test",
+        Path::new("/dst"),
+        &mut NoProgress,
+    )
+    .unwrap();
+
+    assert!(vfs.exists(Path::new("/dst/Test/SKILL.md")));
+    assert!(vfs.exists(Path::new("/dst/Test/lib/run.sh")));
+    assert!(!vfs.exists(Path::new("/dst/Test/SKILL.yaml")));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "current_thread")]
+async fn execute_copy_async_copies() {
+    let dir = TempDir::new().unwrap();
+    let src = dir.path().join("src_skill");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("SKILL.md"), "# Test").unwrap();
+
+    let dst = dir.path().join("dst");
+    execute_skill_copy_async(src, "TestSkill".to_string(), dst.clone())
+        .await
+        .unwrap();
+    assert!(dst.join("TestSkill").join("SKILL.md").exists());
+}
+
 // ─── execute_skill_copy: symlink guard ───
 
 #[test]
@@ -471,9 +546,50 @@ fn execute_copy_rejects_symlink() {
     fs::create_dir_all(&real_target).unwrap();
     std::os::unix::fs::symlink(&real_target, dst.join("TestSkill")).unwrap();
 
-    let result = execute_skill_copy(&src, "TestSkill", &dst);
-    assert!(result.is_err());
-    assert!(result.unwrap_err().contains("symlink"));
+    let result = execute_skill_copy(&RealFs, &src, "TestSkill", &dst, &mut NoProgress);
+    let err = result.unwrap_err();
+    assert!(matches!(err, ForgeError::Validation(_)));
+    assert!(err.to_string().contains("symlink"));
+}
+
+#[test]
+fn execute_link_links_and_copy_replaces_the_link() {
+    let dir = TempDir::new().unwrap();
+    let src = dir.path().join("TestSkill");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("SKILL.md"), "# Test").unwrap();
+    let dst = dir.path().join("dst");
+
+    execute_skill_link(&RealFs, &src, "TestSkill", &dst).unwrap();
+    execute_skill_link(&RealFs, &src, "TestSkill", &dst).unwrap();
+    assert_eq!(fs::read_link(dst.join("TestSkill")).unwrap(), src);
+    fs::write(src.join("SKILL.md"), "# Edited").unwrap();
+    assert_eq!(
+        fs::read_to_string(dst.join("TestSkill/SKILL.md")).unwrap(),
+        "# Edited"
+    );
+
+    execute_skill_copy(&RealFs, &src, "TestSkill", &dst, &mut NoProgress).unwrap();
+    assert!(!dst.join("TestSkill").is_symlink());
+    assert!(src.join("SKILL.md").exists());
+    assert_eq!(
+        fs::read_to_string(dst.join("TestSkill/SKILL.md")).unwrap(),
+        "# Edited"
+    );
+}
+
+#[test]
+fn execute_link_in_memory_replaces_a_copy() {
+    let vfs = MemoryFs::new()
+        .with_file("/src/Test/SKILL.md", "# Test")
+        .with_file("/dst/Test/SKILL.md", "# Old");
+    execute_skill_link(&vfs, Path::new("/src/Test"), "Test", Path::new("/dst")).unwrap();
+
+    assert!(vfs.is_symlink(Path::new("/dst/Test")));
+    assert_eq!(
+        vfs.read_link(Path::new("/dst/Test")).unwrap(),
+        Path::new("/src/Test")
+    );
 }
 
 // ─── clean_orphaned_skills ───
@@ -483,14 +599,14 @@ fn orphan_skill_removes_renamed() {
     let dir = TempDir::new().unwrap();
     let dst = dir.path();
 
-    crate::manifest::update(dst, "forge-council", &["OldCouncil".to_string()]).unwrap();
+    crate::manifest::update(&RealFs, dst, "forge-council", &["OldCouncil".to_string()]).unwrap();
 
     let old_deployed = dst.join("OldCouncil");
     fs::create_dir_all(&old_deployed).unwrap();
     fs::write(old_deployed.join("SKILL.md"), "# Old").unwrap();
 
     let current = vec!["NewCouncil".to_string()];
-    let removed = clean_orphaned_skills(dst, "forge-council", &current, false).unwrap();
+    let removed = clean_orphaned_skills(&RealFs, dst, "forge-council", &current, false).unwrap();
     assert_eq!(removed, vec!["OldCouncil"]);
     assert!(!dst.join("OldCouncil").exists());
 }
@@ -500,13 +616,13 @@ fn orphan_skill_keeps_current() {
     let dir = TempDir::new().unwrap();
     let dst = dir.path();
 
-    crate::manifest::update(dst, "forge-council", &["Council".to_string()]).unwrap();
+    crate::manifest::update(&RealFs, dst, "forge-council", &["Council".to_string()]).unwrap();
     let deployed = dst.join("Council");
     fs::create_dir_all(&deployed).unwrap();
     fs::write(deployed.join("SKILL.md"), "# Council").unwrap();
 
     let current = vec!["Council".to_string()];
-    let removed = clean_orphaned_skills(dst, "forge-council", &current, false).unwrap();
+    let removed = clean_orphaned_skills(&RealFs, dst, "forge-council", &current, false).unwrap();
     assert!(removed.is_empty());
     assert!(dst.join("Council").exists());
 }
@@ -516,19 +632,35 @@ fn orphan_skill_dry_run_preserves() {
     let dir = TempDir::new().unwrap();
     let dst = dir.path();
 
-    crate::manifest::update(dst, "forge-council", &["OldSkill".to_string()]).unwrap();
+    crate::manifest::update(&RealFs, dst, "forge-council", &["OldSkill".to_string()]).unwrap();
     let deployed = dst.join("OldSkill");
     fs::create_dir_all(&deployed).unwrap();
 
-    let removed = clean_orphaned_skills(dst, "forge-council", &[], true).unwrap();
+    let removed = clean_orphaned_skills(&RealFs, dst, "forge-council", &[], true).unwrap();
     assert_eq!(removed, vec!["OldSkill"]);
     assert!(dst.join("OldSkill").exists());
 }
 
+#[test]
+fn orphan_skill_removal_goes_through_the_vfs() {
+    let vfs = MemoryFs::new().with_file("/dst/OldSkill/SKILL.md", "# Old");
+    crate::manifest::update(
+        &vfs,
+        Path::new("/dst"),
+        "forge-council",
+        &["OldSkill".to_string()],
+    )
+    .unwrap();
+    let removed =
+        clean_orphaned_skills(&vfs, Path::new("/dst"), "forge-council", &[], false).unwrap();
+    assert_eq!(removed, vec!["OldSkill"]);
+    assert!(!vfs.exists(Path::new("/dst/OldSkill")));
+}
+
 #[test]
 fn orphan_skill_empty_module_skips() {
     let dir = TempDir::new().unwrap();
-    let removed = clean_orphaned_skills(dir.path(), "", &[], false).unwrap();
+    let removed = clean_orphaned_skills(&RealFs, dir.path(), "", &[], false).unwrap();
     assert!(removed.is_empty());
 }
 
@@ -578,7 +710,7 @@ fn generate_from_agents_dir() {
     )
     .unwrap();
 
-    let results = generate_skills_from_agents_dir(&agents).unwrap();
+    let results = generate_skills_from_agents_dir(&agents, &Templates::default()).unwrap();
     assert_eq!(results.len(), 2);
     assert_eq!(results[0].agent_name, "Dev");
     assert_eq!(results[1].agent_name, "Tester");
@@ -586,7 +718,8 @@ fn generate_from_agents_dir() {
 
 #[test]
 fn generate_from_missing_dir() {
-    let results = generate_skills_from_agents_dir(Path::new("/nonexistent")).unwrap();
+    let results =
+        generate_skills_from_agents_dir(Path::new("/nonexistent"), &Templates::default()).unwrap();
     assert!(results.is_empty());
 }
 
@@ -704,3 +837,19 @@ fn merge_roundtrip_valid_yaml() {
     assert_eq!(parsed["argument-hint"].as_str().unwrap(), "[path to file]");
     assert_eq!(parsed["disable-model-invocation"].as_str().unwrap(), "true");
 }
+
+#[test]
+fn skill_digest_follows_files_and_fields() {
+    let vfs = MemoryFs::new().with_file("/src/Review/SKILL.md", "---\nname: Review\n---\n");
+    let src = Path::new("/src/Review");
+    let fields = BTreeMap::new();
+    let before = skill_digest(&vfs, src, "Review", &fields).unwrap();
+    assert_eq!(skill_digest(&vfs, src, "Review", &fields).unwrap(), before);
+
+    let license = BTreeMap::from([("license".to_string(), "MIT".to_string())]);
+    assert_ne!(skill_digest(&vfs, src, "Review", &license).unwrap(), before);
+
+    vfs.write(Path::new("/src/Review/notes.md"), b"notes")
+        .unwrap();
+    assert_ne!(skill_digest(&vfs, src, "Review", &fields).unwrap(), before);
+}
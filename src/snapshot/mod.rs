@@ -0,0 +1,351 @@
+//! Snapshots of provider directories: tar archives of the agents, skills,
+//! and config files forge writes to, taken before destructive operations so
+//! they can be rolled back.
+//!
+//! Each snapshot is `<snapshots-dir>/<id>.tar`. Its first entry,
+//! `snapshot.json`, lists the captured paths (absolute, and whether each
+//! existed); the files of path `n` follow under `files/<n>/`. Restoring
+//! puts every captured path back exactly as it was, removing paths that did
+//! not exist at the time. A captured path that is a symlink (a dotfiles
+//! checkout linked into `~/.claude`, say) is followed: its target's files
+//! are archived and the link is recreated on restore. Symlinks inside a
+//! captured directory are recorded in the index and recreated as links.
+
+use crate::pack::{file_mode, tar};
+use crate::vfs::{RealFs, Vfs};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const INDEX: &str = "snapshot.json";
+
+/// An archive entry: `(path, contents, mode)`.
+type Entry = (String, Vec<u8>, u32);
+
+/// A file to restore under a captured path: where under it (`None` for the
+/// path itself), its contents, and its mode.
+type Planned<'a> = (Option<&'a str>, &'a [u8], u32);
+
+/// What `forge snapshot create` captures under each provider root.
+pub const PROVIDER_ENTRIES: &[&str] = &[
+    "agents",
+    "rules",
+    "instructions",
+    "skills",
+    "commands",
+    "command",
+    "prompts",
+    "output-styles",
+    "settings.json",
+    "config.toml",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedPath {
+    pub path: PathBuf,
+    /// Whether `path` (or the target of the symlink at it) existed.
+    pub existed: bool,
+    /// Where `path` pointed, when it was a symlink.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub label: String,
+    /// Seconds since the Unix epoch.
+    pub created: u64,
+    pub paths: Vec<CapturedPath>,
+    pub files: usize,
+    /// Symlinks inside captured directories, by archive path
+    /// (`files/<n>/...`), with where each pointed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<(String, PathBuf)>,
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, String> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    let cwd = std::env::current_dir().map_err(|e| format!("failed to get cwd: {e}"))?;
+    Ok(cwd.join(path))
+}
+
+fn walk(
+    dir: &Path,
+    rel: &str,
+    files: &mut Vec<(String, PathBuf)>,
+    links: &mut Vec<(String, PathBuf)>,
+) -> Result<(), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read {}: {e}", dir.display()))?;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let rel = format!("{rel}/{}", entry.file_name().to_string_lossy());
+        if path.is_symlink() {
+            let target = std::fs::read_link(&path)
+                .map_err(|e| format!("failed to read link {}: {e}", path.display()))?;
+            links.push((rel, target));
+        } else if path.is_dir() {
+            walk(&path, &rel, files, links)?;
+        } else if path.is_file() {
+            files.push((rel, path));
+        }
+    }
+    Ok(())
+}
+
+/// Turn a label into something safe for a file name.
+fn slug(label: &str) -> String {
+    let slug: String = label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    slug.trim_matches('-').to_string()
+}
+
+fn snapshot_path(snapshots_dir: &Path, id: &str) -> PathBuf {
+    snapshots_dir.join(format!("{id}.tar"))
+}
+
+/// Archive `paths` (files or directories) into a new snapshot. With
+/// `dry_run` the snapshot is built but not written.
+pub fn create(
+    snapshots_dir: &Path,
+    label: &str,
+    paths: &[PathBuf],
+    dry_run: bool,
+) -> Result<Snapshot, String> {
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let base = match slug(label) {
+        s if s.is_empty() => created.to_string(),
+        s => format!("{created}-{s}"),
+    };
+    let mut id = base.clone();
+    let mut n = 1;
+    while snapshot_path(snapshots_dir, &id).exists() {
+        n += 1;
+        id = format!("{base}-{n}");
+    }
+
+    let mut captured = Vec::new();
+    let mut files = Vec::new();
+    let mut links = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let path = absolute(path)?;
+        let link = if path.is_symlink() {
+            Some(
+                std::fs::read_link(&path)
+                    .map_err(|e| format!("failed to read link {}: {e}", path.display()))?,
+            )
+        } else {
+            None
+        };
+        // `exists` and `is_dir` follow a symlink to its target.
+        let existed = path.exists();
+        if path.is_dir() {
+            walk(&path, &format!("files/{i}"), &mut files, &mut links)?;
+        } else if existed {
+            files.push((format!("files/{i}"), path.clone()));
+        }
+        captured.push(CapturedPath {
+            path,
+            existed,
+            link,
+        });
+    }
+
+    let snapshot = Snapshot {
+        id,
+        label: label.to_string(),
+        created,
+        paths: captured,
+        files: files.len(),
+        links,
+    };
+    let index = serde_json::to_vec_pretty(&snapshot)
+        .map_err(|e| format!("failed to serialize snapshot: {e}"))?;
+    let mut entries = vec![(INDEX.to_string(), index, 0o644)];
+    for (rel, path) in files {
+        let data =
+            std::fs::read(&path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        entries.push((rel, data, file_mode(&path)));
+    }
+    let bytes = tar::write(&entries)?;
+
+    if !dry_run {
+        std::fs::create_dir_all(snapshots_dir)
+            .map_err(|e| format!("failed to create {}: {e}", snapshots_dir.display()))?;
+        let out = snapshot_path(snapshots_dir, &snapshot.id);
+        std::fs::write(&out, bytes)
+            .map_err(|e| format!("failed to write {}: {e}", out.display()))?;
+    }
+    Ok(snapshot)
+}
+
+fn read_archive(path: &Path) -> Result<(Snapshot, Vec<Entry>), String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let mut entries = tar::read(&bytes)?;
+    if entries.first().map(|(p, _, _)| p.as_str()) != Some(INDEX) {
+        return Err(format!("{} is not a forge snapshot", path.display()));
+    }
+    let (_, index, _) = entries.remove(0);
+    let snapshot: Snapshot = serde_json::from_slice(&index)
+        .map_err(|e| format!("invalid {INDEX} in {}: {e}", path.display()))?;
+    Ok((snapshot, entries))
+}
+
+/// Every snapshot in `snapshots_dir`, oldest first.
+pub fn list(snapshots_dir: &Path) -> Result<Vec<Snapshot>, String> {
+    let Ok(entries) = std::fs::read_dir(snapshots_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut snapshots = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e == "tar") {
+            snapshots.push(read_archive(&path)?.0);
+        }
+    }
+    snapshots.sort_by(|a, b| (a.created, &a.id).cmp(&(b.created, &b.id)));
+    Ok(snapshots)
+}
+
+/// Which captured path an archive path (`files/<n>` or `files/<n>/<sub>`)
+/// belongs to, and where under it; an error for anything that could land
+/// outside it.
+fn entry_target(rel: &str) -> Result<(usize, Option<&str>), String> {
+    let unsafe_path = || format!("unsafe path in snapshot: {rel}");
+    let rest = rel.strip_prefix("files/").ok_or_else(unsafe_path)?;
+    let (index, sub) = match rest.split_once('/') {
+        Some((index, sub)) => (index, Some(sub)),
+        None => (rest, None),
+    };
+    let index = index.parse().map_err(|_| unsafe_path())?;
+    if sub.is_some_and(|sub| {
+        sub.split('/')
+            .any(|c| c == ".." || c == "." || c.is_empty())
+    }) {
+        return Err(unsafe_path());
+    }
+    Ok((index, sub))
+}
+
+/// Remove whatever is at `path`, without following a symlink there.
+fn remove_path(path: &Path) -> Result<(), String> {
+    if path.is_symlink() || path.is_file() {
+        std::fs::remove_file(path)
+    } else if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        Ok(())
+    }
+    .map_err(|e| format!("failed to remove {}: {e}", path.display()))
+}
+
+fn create_parent(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) => std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {e}", parent.display())),
+        None => Ok(()),
+    }
+}
+
+fn make_link(target: &Path, link: &Path) -> Result<(), String> {
+    create_parent(link)?;
+    RealFs
+        .symlink(target, link)
+        .map_err(|e| format!("failed to link {}: {e}", link.display()))
+}
+
+/// Put every path captured by snapshot `id` back as it was. Returns the
+/// restored paths. Every archive entry is checked before anything on disk
+/// is touched.
+pub fn restore(snapshots_dir: &Path, id: &str, dry_run: bool) -> Result<Vec<PathBuf>, String> {
+    let path = snapshot_path(snapshots_dir, id);
+    if !path.is_file() {
+        return Err(format!("no snapshot {id:?} in {}", snapshots_dir.display()));
+    }
+    let (snapshot, entries) = read_archive(&path)?;
+
+    let mut files: Vec<Vec<Planned>> = vec![Vec::new(); snapshot.paths.len()];
+    for (rel, data, mode) in &entries {
+        let (i, sub) = entry_target(rel)?;
+        let slot = files
+            .get_mut(i)
+            .ok_or_else(|| format!("unsafe path in snapshot: {rel}"))?;
+        slot.push((sub, data, *mode));
+    }
+    let mut links: Vec<Vec<(&str, &Path)>> = vec![Vec::new(); snapshot.paths.len()];
+    for (rel, target) in &snapshot.links {
+        let (i, sub) = entry_target(rel)?;
+        let (Some(sub), Some(slot)) = (sub, links.get_mut(i)) else {
+            return Err(format!("unsafe path in snapshot: {rel}"));
+        };
+        slot.push((sub, target));
+    }
+
+    let mut restored = Vec::new();
+    for (i, captured) in snapshot.paths.iter().enumerate() {
+        restored.push(captured.path.clone());
+        if dry_run {
+            continue;
+        }
+        let target = &captured.path;
+        let root = match &captured.link {
+            Some(link) => {
+                if std::fs::read_link(target).ok().as_ref() != Some(link) {
+                    remove_path(target)?;
+                    make_link(link, target)?;
+                }
+                target.parent().unwrap_or(Path::new("/")).join(link)
+            }
+            None => target.clone(),
+        };
+        if captured.link.is_some() && !captured.existed {
+            continue;
+        }
+        remove_path(&root)?;
+
+        for (sub, data, mode) in &files[i] {
+            let dest = sub.map_or_else(|| root.clone(), |sub| root.join(sub));
+            create_parent(&dest)?;
+            std::fs::write(&dest, data)
+                .map_err(|e| format!("failed to write {}: {e}", dest.display()))?;
+            set_mode(&dest, *mode)?;
+        }
+        if captured.existed && files[i].is_empty() {
+            std::fs::create_dir_all(&root)
+                .map_err(|e| format!("failed to create {}: {e}", root.display()))?;
+        }
+        for (sub, link) in &links[i] {
+            make_link(link, &root.join(sub))?;
+        }
+    }
+    Ok(restored)
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .map_err(|e| format!("failed to set mode on {}: {e}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
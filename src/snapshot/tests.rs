@@ -0,0 +1,134 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn create_and_restore_round_trip() {
+    let tmp = TempDir::new().unwrap();
+    let agents = tmp.path().join(".claude/agents");
+    let settings = tmp.path().join(".claude/settings.json");
+    let fresh = tmp.path().join(".claude/skills");
+    fs::create_dir_all(agents.join("nested")).unwrap();
+    fs::write(agents.join("A.md"), "a").unwrap();
+    fs::write(agents.join("nested/B.md"), "b").unwrap();
+    fs::write(agents.join(".manifest"), "m").unwrap();
+    fs::write(&settings, "{}").unwrap();
+
+    let store = tmp.path().join("snapshots");
+    let snapshot = create(
+        &store,
+        "before clean",
+        &[agents.clone(), settings.clone(), fresh.clone()],
+        false,
+    )
+    .unwrap();
+    assert!(snapshot.id.ends_with("-before-clean"));
+    assert_eq!(snapshot.files, 4);
+    assert!(!snapshot.paths[2].existed);
+
+    fs::remove_file(agents.join("A.md")).unwrap();
+    fs::write(agents.join("C.md"), "c").unwrap();
+    fs::write(&settings, r#"{"changed": true}"#).unwrap();
+    fs::create_dir_all(&fresh).unwrap();
+
+    let restored = restore(&store, &snapshot.id, false).unwrap();
+    assert_eq!(restored.len(), 3);
+    assert_eq!(fs::read_to_string(agents.join("A.md")).unwrap(), "a");
+    assert_eq!(fs::read_to_string(agents.join("nested/B.md")).unwrap(), "b");
+    assert!(!agents.join("C.md").exists());
+    assert_eq!(fs::read_to_string(&settings).unwrap(), "{}");
+    assert!(!fresh.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_dirs_are_followed_and_relinked() {
+    use std::os::unix::fs::symlink;
+    let tmp = TempDir::new().unwrap();
+    let dotfiles = tmp.path().join("dotfiles/agents");
+    fs::create_dir_all(&dotfiles).unwrap();
+    fs::write(dotfiles.join("A.md"), "a").unwrap();
+    fs::write(tmp.path().join("Shared.md"), "shared").unwrap();
+    symlink(tmp.path().join("Shared.md"), dotfiles.join("Shared.md")).unwrap();
+    let agents = tmp.path().join(".claude/agents");
+    fs::create_dir_all(agents.parent().unwrap()).unwrap();
+    symlink(&dotfiles, &agents).unwrap();
+
+    let store = tmp.path().join("snapshots");
+    let snapshot = create(&store, "x", std::slice::from_ref(&agents), false).unwrap();
+    assert_eq!(snapshot.files, 1);
+    assert!(snapshot.paths[0].existed);
+    assert_eq!(snapshot.paths[0].link.as_deref(), Some(dotfiles.as_path()));
+
+    fs::remove_file(dotfiles.join("A.md")).unwrap();
+    fs::remove_file(dotfiles.join("Shared.md")).unwrap();
+    restore(&store, &snapshot.id, false).unwrap();
+    assert!(agents.is_symlink());
+    assert_eq!(fs::read_to_string(agents.join("A.md")).unwrap(), "a");
+    assert!(dotfiles.join("Shared.md").is_symlink());
+    assert_eq!(
+        fs::read_to_string(dotfiles.join("Shared.md")).unwrap(),
+        "shared"
+    );
+
+    fs::remove_file(&agents).unwrap();
+    restore(&store, &snapshot.id, false).unwrap();
+    assert_eq!(fs::read_link(&agents).unwrap(), dotfiles);
+}
+
+#[test]
+fn unsafe_entries_are_refused_before_anything_is_removed() {
+    let tmp = TempDir::new().unwrap();
+    let agents = tmp.path().join("agents");
+    fs::create_dir_all(&agents).unwrap();
+    fs::write(agents.join("Keep.md"), "keep").unwrap();
+    let snapshot = Snapshot {
+        id: "bad".into(),
+        label: String::new(),
+        created: 0,
+        paths: vec![CapturedPath {
+            path: agents.clone(),
+            existed: true,
+            link: None,
+        }],
+        files: 1,
+        links: Vec::new(),
+    };
+    let index = serde_json::to_vec(&snapshot).unwrap();
+    let bytes = tar::write_unchecked(&[
+        (INDEX.to_string(), index, 0o644),
+        ("files/0/../escaped.md".to_string(), b"x".to_vec(), 0o644),
+    ]);
+    let store = tmp.path().join("snapshots");
+    fs::create_dir_all(&store).unwrap();
+    fs::write(store.join("bad.tar"), bytes).unwrap();
+
+    let err = restore(&store, "bad", false).unwrap_err();
+    assert!(err.contains("unsafe path"), "{err}");
+    assert_eq!(fs::read_to_string(agents.join("Keep.md")).unwrap(), "keep");
+}
+
+#[test]
+fn list_and_dry_run() {
+    let tmp = TempDir::new().unwrap();
+    let store = tmp.path().join("snapshots");
+    assert!(list(&store).unwrap().is_empty());
+
+    let dir = tmp.path().join("agents");
+    fs::create_dir_all(&dir).unwrap();
+    create(&store, "x", std::slice::from_ref(&dir), true).unwrap();
+    assert!(list(&store).unwrap().is_empty());
+
+    let first = create(&store, "x", std::slice::from_ref(&dir), false).unwrap();
+    let second = create(&store, "x", &[dir], false).unwrap();
+    assert_ne!(first.id, second.id);
+    let ids: Vec<String> = list(&store).unwrap().into_iter().map(|s| s.id).collect();
+    assert_eq!(ids.len(), 2);
+    assert!(restore(&store, "missing", false).is_err());
+}
+
+#[test]
+fn slug_sanitizes_labels() {
+    assert_eq!(slug("agents --clean"), "agents---clean");
+    assert_eq!(slug("/"), "");
+}
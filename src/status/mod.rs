@@ -0,0 +1,115 @@
+//! Deployment status: how the agents deployed in a destination directory
+//! compare with the module's sources, its manifest, and the merge bases
+//! recorded at deploy time, without deploying anything.
+
+use crate::deploy::{self, DeployOptions, DeployResult};
+use crate::error::ForgeError;
+use crate::manifest;
+use crate::sidecar::SidecarConfig;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Where one agent stands in a destination directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Drift {
+    /// Deployed and identical to what a redeploy would write.
+    Current,
+    /// Deployed, but the source or configuration has changed since.
+    Outdated,
+    /// The deployed file was edited after it was deployed.
+    Modified,
+    /// In the sources but not deployed.
+    Missing,
+    /// A file of the same name that forge did not deploy.
+    UserOwned,
+    /// Recorded in the manifest, deployed, but no longer in the sources.
+    Orphaned,
+}
+
+impl Drift {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Current => "current",
+            Self::Outdated => "outdated",
+            Self::Modified => "modified",
+            Self::Missing => "missing",
+            Self::UserOwned => "user-owned",
+            Self::Orphaned => "orphaned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgentStatus {
+    pub name: String,
+    pub path: PathBuf,
+    pub drift: Drift,
+}
+
+/// The status of every agent in `src_dir` and of every agent `module_name`
+/// recorded in `dst_dir`'s manifest that is no longer in `src_dir`, in that
+/// order. Reads but never writes.
+pub fn scan(
+    src_dir: &Path,
+    dst_dir: &Path,
+    module_name: &str,
+    config: &SidecarConfig,
+    options: &DeployOptions,
+) -> Result<Vec<AgentStatus>, ForgeError> {
+    let plan = deploy::plan_agents_from_dir(src_dir, dst_dir, config, options)?;
+    let vfs = &*options.vfs;
+    let mut statuses = Vec::new();
+    let mut current = Vec::new();
+    for action in &plan.actions {
+        let Some(name) = &action.name else {
+            continue;
+        };
+        // An excluded agent that is still deployed shows up as orphaned.
+        if action.result == DeployResult::SkippedProviderExcluded {
+            continue;
+        }
+        current.push(name.clone());
+        let path = dst_dir.join(options.provider.agent_filename(name));
+        let drift = if action.result == DeployResult::SkippedUserOwned {
+            Drift::UserOwned
+        } else if !vfs.exists(&path) {
+            Drift::Missing
+        } else {
+            action.files.iter().fold(Drift::Current, |drift, file| {
+                let existing = vfs.read_to_string(&file.path).ok();
+                let base = vfs.read_to_string(&deploy::base_path(&file.path)).ok();
+                let edited = base.is_some() && existing != base;
+                if drift == Drift::Modified || edited {
+                    Drift::Modified
+                } else if existing.as_deref() != Some(&file.rendered) {
+                    Drift::Outdated
+                } else {
+                    drift
+                }
+            })
+        };
+        statuses.push(AgentStatus {
+            name: name.clone(),
+            path,
+            drift,
+        });
+    }
+
+    if !module_name.is_empty() {
+        for name in manifest::read(vfs, dst_dir, module_name) {
+            let path = dst_dir.join(options.provider.agent_filename(&name));
+            if !current.contains(&name) && vfs.exists(&path) {
+                statuses.push(AgentStatus {
+                    name,
+                    path,
+                    drift: Drift::Orphaned,
+                });
+            }
+        }
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,70 @@
+use super::*;
+use crate::deploy::provider::Provider;
+use crate::vfs::RealFs;
+use std::fs;
+use tempfile::TempDir;
+
+/// An agents directory inside a temporary one, which also holds the
+/// `.forge-state` kept beside it.
+fn agents_dir(tmp: &TempDir) -> PathBuf {
+    let dir = tmp.path().join("agents");
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn agent(name: &str, body: &str) -> String {
+    format!("---\nname: {name}\ndescription: Reviews\n---\n{body}\n")
+}
+
+fn drifts(statuses: &[AgentStatus]) -> Vec<(&str, Drift)> {
+    statuses
+        .iter()
+        .map(|s| (s.name.as_str(), s.drift))
+        .collect()
+}
+
+#[test]
+fn scan_reports_each_kind_of_drift() {
+    let src = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    let dst = agents_dir(&tmp);
+    let config = SidecarConfig::default();
+    let options = DeployOptions::new(Provider::Claude);
+    for name in ["Current", "Outdated", "Modified", "Gone"] {
+        let file = format!("{name}.md");
+        fs::write(src.path().join(&file), agent(name, "Body.")).unwrap();
+        deploy::deploy_agent(
+            &agent(name, "Body."),
+            &file,
+            dst.as_path(),
+            &config,
+            &options,
+        )
+        .unwrap();
+    }
+    let names = ["Current", "Outdated", "Modified", "Gone"].map(String::from);
+    manifest::update(&RealFs, dst.as_path(), "m", &names).unwrap();
+
+    fs::write(src.path().join("Outdated.md"), agent("Outdated", "New.")).unwrap();
+    let modified = dst.as_path().join("Modified.md");
+    let edited = fs::read_to_string(&modified).unwrap() + "Mine.\n";
+    fs::write(&modified, edited).unwrap();
+    fs::remove_file(src.path().join("Gone.md")).unwrap();
+    fs::write(src.path().join("Missing.md"), agent("Missing", "Body.")).unwrap();
+    fs::write(src.path().join("Owned.md"), agent("Owned", "Body.")).unwrap();
+    fs::write(dst.as_path().join("Owned.md"), "Hand-written.\n").unwrap();
+
+    let statuses = scan(src.path(), dst.as_path(), "m", &config, &options).unwrap();
+    assert_eq!(
+        drifts(&statuses),
+        vec![
+            ("Current", Drift::Current),
+            ("Missing", Drift::Missing),
+            ("Modified", Drift::Modified),
+            ("Outdated", Drift::Outdated),
+            ("Owned", Drift::UserOwned),
+            ("Gone", Drift::Orphaned),
+        ]
+    );
+    assert!(!dst.as_path().join("Missing.md").exists());
+}
@@ -0,0 +1,196 @@
+//! Claude output styles and statusline.
+//!
+//! `output-styles/*.md` are copied to a Claude `output-styles` directory with
+//! a `source:` field added to their frontmatter, and tracked in that
+//! directory's `.manifest` like agents. A module's `statusline.json` becomes
+//! the `statusLine` key of `settings.json`; the value deployed is recorded in
+//! a `.forge-statusline.json` manifest next to the settings file so it is
+//! only ever replaced or removed while it is still the one forge wrote.
+
+use crate::deploy::DeployResult;
+use crate::hooks::{read_json_object, substitute_root, write_json_object};
+use crate::parse;
+use serde_json::Value;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+pub const STYLES_DIR: &str = "output-styles";
+pub const STATUSLINE_FILE: &str = "statusline.json";
+const STATUSLINE_KEY: &str = "statusLine";
+const MANIFEST_FILE: &str = ".forge-statusline.json";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StatuslineResult {
+    Installed,
+    Removed,
+    /// A statusline forge did not write is configured.
+    SkippedUserOwned,
+    Unchanged,
+}
+
+// ─── Output styles ───
+
+/// Claude output-styles directory for a scope.
+pub fn styles_dir(scope: &str, home: &Path) -> Result<PathBuf, String> {
+    match scope {
+        "user" => Ok(home.join(".claude/output-styles")),
+        "workspace" => Ok(PathBuf::from(".claude/output-styles")),
+        other => Err(format!(
+            "invalid scope {other:?} for output styles: use user or workspace"
+        )),
+    }
+}
+
+/// Set `source:` in the frontmatter of `content`, adding frontmatter when
+/// there is none.
+pub(crate) fn with_source(content: &str, source: &str) -> String {
+    let Some((yaml, body)) = parse::split_frontmatter(content) else {
+        return format!("---\nsource: {source}\n---\n{content}");
+    };
+    let mut out = String::from("---\n");
+    for line in yaml.lines().filter(|l| !l.starts_with("source:")) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    let _ = write!(out, "source: {source}\n---\n");
+    out.push_str(body);
+    out
+}
+
+/// Copy every `*.md` in `src_dir` to `dst_dir`, leaving files that were not
+/// deployed from this source alone.
+pub fn deploy_styles_from_dir(
+    src_dir: &Path,
+    dst_dir: &Path,
+    source_prefix: &str,
+    dry_run: bool,
+) -> Result<Vec<(String, DeployResult)>, String> {
+    if !src_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(src_dir)
+        .map_err(|e| format!("failed to read {}: {e}", src_dir.display()))?;
+    let mut files: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    files.sort_by_key(std::fs::DirEntry::file_name);
+
+    let mut results = Vec::new();
+    for entry in files {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        let name = filename.trim_end_matches(".md").to_string();
+        let out_path = dst_dir.join(&filename);
+        if out_path.is_symlink() {
+            return Err(format!("destination is a symlink: {}", out_path.display()));
+        }
+        if let Ok(existing) = std::fs::read_to_string(&out_path) {
+            if !parse::is_synced_from(&existing, &filename) {
+                results.push((name, DeployResult::SkippedUserOwned));
+                continue;
+            }
+        }
+
+        let path = entry.path();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let source = if source_prefix.is_empty() {
+            filename.clone()
+        } else {
+            format!("{source_prefix}/{filename}")
+        };
+        if !dry_run {
+            std::fs::create_dir_all(dst_dir)
+                .map_err(|e| format!("failed to create {}: {e}", dst_dir.display()))?;
+            std::fs::write(&out_path, with_source(&content, &source))
+                .map_err(|e| format!("failed to write {}: {e}", out_path.display()))?;
+        }
+        results.push((name, DeployResult::Deployed));
+    }
+    Ok(results)
+}
+
+// ─── Statusline ───
+
+/// Load a module's `statusline.json`, with `${CLAUDE_PLUGIN_ROOT}` replaced
+/// by the module's absolute path.
+pub fn load_module_statusline(module_root: &Path) -> Result<Option<Value>, String> {
+    let path = module_root.join(STATUSLINE_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("{}: invalid JSON: {e}", path.display()))?;
+    if !value.get("command").is_some_and(Value::is_string) {
+        return Err(format!("{}: expected a \"command\" string", path.display()));
+    }
+    let root = std::fs::canonicalize(module_root)
+        .unwrap_or_else(|_| module_root.to_path_buf())
+        .display()
+        .to_string();
+    Ok(Some(substitute_root(value, &root)))
+}
+
+/// Set (or with `None`, remove) `module_name`'s statusline in
+/// `settings_path`.
+pub(crate) fn manifest_path(settings_path: &Path) -> PathBuf {
+    settings_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(MANIFEST_FILE)
+}
+
+pub fn deploy_statusline(
+    settings_path: &Path,
+    module_name: &str,
+    statusline: Option<&Value>,
+    dry_run: bool,
+) -> Result<StatuslineResult, String> {
+    let manifest_path = manifest_path(settings_path);
+    let mut manifest = read_json_object(&manifest_path)?;
+    let previous = manifest.shift_remove(module_name);
+    if previous.is_none() && statusline.is_none() {
+        return Ok(StatuslineResult::Unchanged);
+    }
+
+    let mut settings = read_json_object(settings_path)?;
+    let current = settings.get(STATUSLINE_KEY);
+    let ours = current.is_none() || current == previous.as_ref();
+    let result = match statusline {
+        _ if !ours => StatuslineResult::SkippedUserOwned,
+        Some(value) if current == Some(value) => {
+            manifest.insert(module_name.to_string(), value.clone());
+            StatuslineResult::Unchanged
+        }
+        Some(value) => {
+            settings.insert(STATUSLINE_KEY.to_string(), value.clone());
+            manifest.insert(module_name.to_string(), value.clone());
+            StatuslineResult::Installed
+        }
+        None if current.is_some() => {
+            settings.shift_remove(STATUSLINE_KEY);
+            StatuslineResult::Removed
+        }
+        None => StatuslineResult::Unchanged,
+    };
+
+    if !dry_run {
+        if matches!(
+            result,
+            StatuslineResult::Installed | StatuslineResult::Removed
+        ) {
+            write_json_object(settings_path, &settings)?;
+        }
+        if manifest.is_empty() {
+            let _ = std::fs::remove_file(&manifest_path);
+        } else {
+            write_json_object(&manifest_path, &manifest)?;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,128 @@
+use super::*;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+fn read(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+// ─── Output styles ───
+
+#[test]
+fn with_source_adds_field() {
+    let out = with_source(
+        "---\nname: Terse\nsource: old\n---\nBe brief.\n",
+        "m/Terse.md",
+    );
+    assert_eq!(
+        out,
+        "---\nname: Terse\nsource: m/Terse.md\n---\nBe brief.\n"
+    );
+}
+
+#[test]
+fn with_source_adds_frontmatter() {
+    assert_eq!(
+        with_source("Be brief.\n", "Terse.md"),
+        "---\nsource: Terse.md\n---\nBe brief.\n"
+    );
+}
+
+#[test]
+fn styles_dir_scopes() {
+    let home = Path::new("/home/u");
+    assert_eq!(
+        styles_dir("user", home).unwrap(),
+        PathBuf::from("/home/u/.claude/output-styles")
+    );
+    assert!(styles_dir("project", home).is_err());
+}
+
+#[test]
+fn deploy_skips_user_owned_style() {
+    let root = TempDir::new().unwrap();
+    let src = root.path().join("output-styles");
+    let dst = root.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("Terse.md"), "---\nname: Terse\n---\nBe brief.\n").unwrap();
+    fs::write(src.join("Loud.md"), "SHOUT.\n").unwrap();
+    fs::write(dst.join("Loud.md"), "mine\n").unwrap();
+
+    let results = deploy_styles_from_dir(&src, &dst, "forge-demo/output-styles", false).unwrap();
+    assert_eq!(
+        results,
+        vec![
+            ("Loud".to_string(), DeployResult::SkippedUserOwned),
+            ("Terse".to_string(), DeployResult::Deployed),
+        ]
+    );
+    assert_eq!(fs::read_to_string(dst.join("Loud.md")).unwrap(), "mine\n");
+    assert!(fs::read_to_string(dst.join("Terse.md"))
+        .unwrap()
+        .contains("source: forge-demo/output-styles/Terse.md"));
+
+    // Redeploying over our own copy is allowed.
+    let again = deploy_styles_from_dir(&src, &dst, "forge-demo/output-styles", false).unwrap();
+    assert_eq!(again[1].1, DeployResult::Deployed);
+}
+
+// ─── Statusline ───
+
+#[test]
+fn load_statusline_substitutes_root() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join(STATUSLINE_FILE),
+        r#"{"type": "command", "command": "${CLAUDE_PLUGIN_ROOT}/status.sh"}"#,
+    )
+    .unwrap();
+    let value = load_module_statusline(dir.path()).unwrap().unwrap();
+    assert!(value["command"].as_str().unwrap().ends_with("/status.sh"));
+    assert!(!value["command"].as_str().unwrap().contains("${"));
+}
+
+#[test]
+fn load_statusline_requires_command() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join(STATUSLINE_FILE), r#"{"type": "command"}"#).unwrap();
+    assert!(load_module_statusline(dir.path()).is_err());
+}
+
+#[test]
+fn statusline_install_and_remove() {
+    let dir = TempDir::new().unwrap();
+    let settings = dir.path().join("settings.json");
+    fs::write(&settings, r#"{"model": "opus"}"#).unwrap();
+    let line = json!({"type": "command", "command": "s.sh"});
+
+    let result = deploy_statusline(&settings, "m", Some(&line), false).unwrap();
+    assert_eq!(result, StatuslineResult::Installed);
+    assert_eq!(read(&settings)["statusLine"], line);
+    assert_eq!(
+        deploy_statusline(&settings, "m", Some(&line), false).unwrap(),
+        StatuslineResult::Unchanged
+    );
+
+    let result = deploy_statusline(&settings, "m", None, false).unwrap();
+    assert_eq!(result, StatuslineResult::Removed);
+    assert_eq!(read(&settings), json!({"model": "opus"}));
+    assert!(!dir.path().join(MANIFEST_FILE).exists());
+}
+
+#[test]
+fn statusline_leaves_user_value() {
+    let dir = TempDir::new().unwrap();
+    let settings = dir.path().join("settings.json");
+    fs::write(
+        &settings,
+        r#"{"statusLine": {"type": "command", "command": "mine"}}"#,
+    )
+    .unwrap();
+    let line = json!({"type": "command", "command": "s.sh"});
+
+    let result = deploy_statusline(&settings, "m", Some(&line), false).unwrap();
+    assert_eq!(result, StatuslineResult::SkippedUserOwned);
+    assert_eq!(read(&settings)["statusLine"]["command"], "mine");
+}
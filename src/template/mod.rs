@@ -0,0 +1,214 @@
+//! Text templates for generated files: agent files, Codex config blocks,
+//! generated skill wrappers, council skills, and memory-file blocks.
+//!
+//! Templates are Jinja, rendered by minijinja. Block tags own their line:
+//! leading indentation before them and the newline after them are dropped
+//! (`trim_blocks` and `lstrip_blocks`), and a single trailing newline at
+//! the end of a template is ignored. Missing values, and attributes of
+//! missing values, render empty and are false; `null` renders empty too.
+//!
+//! Besides minijinja's built-in filters, `toml` escapes for a TOML basic
+//! string and `yaml` quotes as a YAML scalar when needed.
+//!
+//! A module overrides a built-in template by shipping
+//! `templates/<name>.j2`, e.g. `templates/agent-claude.md.j2`, and adds the
+//! agent template of a custom provider the same way.
+
+use minijinja::{Environment, UndefinedBehavior};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Built-in templates by name.
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "agent-claude.md",
+        include_str!("builtin/agent-claude.md.j2"),
+    ),
+    (
+        "agent-gemini.md",
+        include_str!("builtin/agent-gemini.md.j2"),
+    ),
+    (
+        "agent-opencode.md",
+        include_str!("builtin/agent-opencode.md.j2"),
+    ),
+    (
+        "agent-cursor.mdc",
+        include_str!("builtin/agent-cursor.mdc.j2"),
+    ),
+    (
+        "agent-windsurf.md",
+        include_str!("builtin/agent-windsurf.md.j2"),
+    ),
+    (
+        "agent-copilot.md",
+        include_str!("builtin/agent-copilot.md.j2"),
+    ),
+    ("agent-zed.md", include_str!("builtin/agent-zed.md.j2")),
+    (
+        "agent-codex.toml",
+        include_str!("builtin/agent-codex.toml.j2"),
+    ),
+    (
+        "codex-config.toml",
+        include_str!("builtin/codex-config.toml.j2"),
+    ),
+    (
+        "skill-wrapper.md",
+        include_str!("builtin/skill-wrapper.md.j2"),
+    ),
+    (
+        "skill-wrapper.yaml",
+        include_str!("builtin/skill-wrapper.yaml.j2"),
+    ),
+    (
+        "council-skill.md",
+        include_str!("builtin/council-skill.md.j2"),
+    ),
+    (
+        "council-skill.yaml",
+        include_str!("builtin/council-skill.yaml.j2"),
+    ),
+    (
+        "memory-block.md",
+        include_str!("builtin/memory-block.md.j2"),
+    ),
+];
+
+/// Built-in templates plus any overrides from a module.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Templates {
+    overrides: BTreeMap<String, String>,
+    /// Module templates with no built-in version.
+    custom: BTreeMap<String, String>,
+}
+
+impl Templates {
+    /// Load `templates/<name>.j2` overrides for built-in names from a module
+    /// root. Unknown or unreadable files are ignored.
+    pub fn load(module_root: &Path) -> Self {
+        let mut overrides = BTreeMap::new();
+        for (name, _) in BUILTIN {
+            let path = module_root.join(TEMPLATES_DIR).join(format!("{name}.j2"));
+            if let Ok(source) = std::fs::read_to_string(&path) {
+                overrides.insert((*name).to_string(), source);
+            }
+        }
+        Self {
+            overrides,
+            custom: BTreeMap::new(),
+        }
+    }
+
+    /// Also load the module's `templates/<name>.j2` for each of `names` that
+    /// is not a built-in, such as custom provider agent templates.
+    #[must_use]
+    pub fn with_custom(mut self, module_root: &Path, names: &[String]) -> Self {
+        for name in names {
+            if BUILTIN.iter().any(|(n, _)| n == name) {
+                continue;
+            }
+            let path = module_root.join(TEMPLATES_DIR).join(format!("{name}.j2"));
+            if let Ok(source) = std::fs::read_to_string(&path) {
+                self.custom.insert(name.clone(), source);
+            }
+        }
+        self
+    }
+
+    /// Names the module overrides.
+    pub fn overridden(&self) -> Vec<&str> {
+        self.overrides.keys().map(String::as_str).collect()
+    }
+
+    fn source(&self, name: &str) -> Result<&str, String> {
+        if let Some(source) = self.overrides.get(name).or_else(|| self.custom.get(name)) {
+            return Ok(source);
+        }
+        BUILTIN
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, s)| *s)
+            .ok_or_else(|| format!("unknown template {name:?}"))
+    }
+
+    /// Render template `name` with `context` (a JSON object).
+    pub fn render(&self, name: &str, context: &Value) -> Result<String, String> {
+        render_str(self.source(name)?, context).map_err(|e| {
+            let origin = if self.overrides.contains_key(name) || self.custom.contains_key(name) {
+                format!("{TEMPLATES_DIR}/{name}.j2")
+            } else {
+                format!("built-in template {name}")
+            };
+            format!("{origin}: {e}")
+        })
+    }
+
+    /// Render a built-in template, which is known to be valid.
+    pub(crate) fn render_builtin(name: &str, context: &Value) -> String {
+        Self::default()
+            .render(name, context)
+            .unwrap_or_else(|e| unreachable!("{e}"))
+    }
+}
+
+// ─── Engine ───
+
+/// The shared minijinja environment: `trim_blocks` and `lstrip_blocks`,
+/// missing values (and their attributes) render empty, `null` prints
+/// nothing, and the `toml` and `yaml` filters.
+fn environment() -> &'static Environment<'static> {
+    static ENV: OnceLock<Environment<'static>> = OnceLock::new();
+    ENV.get_or_init(|| {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_undefined_behavior(UndefinedBehavior::Chainable);
+        env.set_formatter(|out, state, value| {
+            if value.is_none() {
+                Ok(())
+            } else {
+                minijinja::escape_formatter(out, state, value)
+            }
+        });
+        env.add_filter("toml", |value: minijinja::Value| {
+            crate::toml_fmt::escape(&display(&value))
+        });
+        env.add_filter("yaml", |value: minijinja::Value| {
+            yaml_scalar(&display(&value))
+        });
+        env
+    })
+}
+
+/// `value` as template output: empty when missing or `null`.
+fn display(value: &minijinja::Value) -> String {
+    if value.is_undefined() || value.is_none() {
+        String::new()
+    } else {
+        value.to_string()
+    }
+}
+
+/// `s` as a YAML scalar, quoted only when plain style would misparse.
+pub(crate) fn yaml_scalar(s: &str) -> String {
+    serde_yaml::to_string(s)
+        .unwrap_or_else(|_| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")))
+        .trim()
+        .to_string()
+}
+
+/// Render template `source` with `context`.
+pub fn render_str(source: &str, context: &Value) -> Result<String, String> {
+    environment()
+        .render_str(source, context)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,115 @@
+use super::*;
+use serde_json::json;
+use tempfile::TempDir;
+
+#[test]
+fn expressions_and_filters() {
+    let out = render_str(
+        "a = \"{{ a | toml }}\"\nb: {{ b | yaml }}\nc: {{ nested.c }}{{ missing }}\n",
+        &json!({"a": "say \"hi\"", "b": "x: y", "nested": {"c": 3}}),
+    )
+    .unwrap();
+    // Like Jinja, a single trailing newline in the template is dropped.
+    assert_eq!(out, "a = \"say \\\"hi\\\"\"\nb: 'x: y'\nc: 3");
+}
+
+#[test]
+fn block_tags_own_their_lines() {
+    let source = "\
+list:
+{% for item in items %}
+  - {{ item }}
+{% endfor %}
+{% if not empty %}
+    {# indented tags drop their indentation too #}
+full
+{% else %}
+empty
+{% endif %}
+";
+    let out = render_str(source, &json!({"items": ["a", "b"], "empty": []})).unwrap();
+    assert_eq!(out, "list:\n  - a\n  - b\nfull\n");
+}
+
+#[test]
+fn if_else_and_truthiness() {
+    let source = "{% if v %}yes{% else %}no{% endif %}";
+    for (value, expected) in [
+        (json!(""), "no"),
+        (json!("x"), "yes"),
+        (json!([]), "no"),
+        (json!(false), "no"),
+        (json!(null), "no"),
+        (json!(1), "yes"),
+    ] {
+        assert_eq!(
+            render_str(source, &json!({ "v": value })).unwrap(),
+            expected
+        );
+    }
+    assert_eq!(render_str(source, &json!({})).unwrap(), "no");
+}
+
+#[test]
+fn loop_variables_shadow_context() {
+    let out = render_str(
+        "{% for name in agents %}{{ name.id }}{{ module }};{% endfor %}",
+        &json!({"agents": [{"id": "A"}, {"id": "B"}], "module": "m"}),
+    )
+    .unwrap();
+    assert_eq!(out, "Am;Bm;");
+}
+
+#[test]
+fn syntax_errors() {
+    let ctx = json!({});
+    assert!(render_str("{% if x %}open", &ctx).is_err());
+    assert!(render_str("{% for x in xs %}", &ctx).is_err());
+    assert!(render_str("{% endif %}", &ctx).is_err());
+    assert!(render_str("{{ x | nope }}", &ctx).is_err());
+    assert!(render_str("{{ x", &ctx).is_err());
+    assert!(render_str("{% include x %}", &ctx).is_err());
+    assert!(render_str("{% for x in n %}{% endfor %}", &json!({"n": 3})).is_err());
+}
+
+#[test]
+fn jinja_builtins_are_available() {
+    let out = render_str(
+        "{{ name | upper }} {{ tools | join(\", \") }}{% if tools is defined %}!{% endif %}",
+        &json!({"name": "dev", "tools": ["Read", "Grep"]}),
+    )
+    .unwrap();
+    assert_eq!(out, "DEV Read, Grep!");
+}
+
+#[test]
+fn module_overrides_builtin() {
+    let tmp = TempDir::new().unwrap();
+    let ctx = json!({"guidance": "Use it.", "agents": [], "module": "m"});
+    assert_eq!(
+        Templates::load(tmp.path())
+            .render("memory-block.md", &ctx)
+            .unwrap(),
+        "Use it.\n"
+    );
+
+    std::fs::create_dir_all(tmp.path().join(TEMPLATES_DIR)).unwrap();
+    std::fs::write(
+        tmp.path().join("templates/memory-block.md.j2"),
+        "## {{ module }}\n{{ guidance }}\n\n",
+    )
+    .unwrap();
+    std::fs::write(tmp.path().join("templates/unknown.j2"), "x").unwrap();
+    let templates = Templates::load(tmp.path());
+    assert_eq!(templates.overridden(), vec!["memory-block.md"]);
+    assert_eq!(
+        templates.render("memory-block.md", &ctx).unwrap(),
+        "## m\nUse it.\n"
+    );
+
+    std::fs::write(tmp.path().join("templates/memory-block.md.j2"), "{% if %}").unwrap();
+    let err = Templates::load(tmp.path())
+        .render("memory-block.md", &ctx)
+        .unwrap_err();
+    assert!(err.starts_with("templates/memory-block.md.j2: "));
+}
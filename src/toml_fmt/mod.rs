@@ -0,0 +1,137 @@
+//! Writing TOML by hand: keys, basic and multi-line strings, and inline
+//! values from JSON. Used for the generated Codex and Gemini files, the
+//! Codex MCP block, and `yaml convert`, so that every writer escapes the
+//! same way. Control characters are escaped, never written raw.
+
+use serde_json::Value;
+use std::fmt::Write;
+
+/// `s` escaped for the inside of a basic string (`"..."`).
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => push_unicode_escape(&mut out, c),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `s` as a quoted basic string.
+pub fn string(s: &str) -> String {
+    format!("\"{}\"", escape(s))
+}
+
+/// `s` escaped for the inside of a multi-line basic string (`"""..."""`):
+/// newlines and tabs stay literal, a third quote in a row is escaped so the
+/// body cannot close the string, and other control characters are escaped.
+pub fn escape_multiline(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut quotes = 0;
+    for c in s.chars() {
+        match c {
+            '"' if quotes == 2 => {
+                out.push_str("\\\"");
+                quotes = 0;
+                continue;
+            }
+            '"' => {
+                out.push('"');
+                quotes += 1;
+                continue;
+            }
+            '\\' => out.push_str("\\\\"),
+            '\n' | '\t' => out.push(c),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => push_unicode_escape(&mut out, c),
+            c => out.push(c),
+        }
+        quotes = 0;
+    }
+    out
+}
+
+fn push_unicode_escape(out: &mut String, c: char) {
+    let _ = write!(out, "\\u{:04X}", u32::from(c));
+}
+
+/// `key` bare when TOML allows it, quoted otherwise.
+pub fn key(key: &str) -> String {
+    if is_bare_key(key) {
+        key.to_string()
+    } else {
+        string(key)
+    }
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The key written by [`key`], back as the original text. Returns `None`
+/// for anything [`key`] does not produce.
+pub fn parse_key(text: &str) -> Option<String> {
+    if is_bare_key(text) {
+        return Some(text.to_string());
+    }
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return None,
+            '\\' => match chars.next()? {
+                '"' => out.push('"'),
+                '\\' => out.push('\\'),
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                _ => return None,
+            },
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+/// `value` as an inline TOML value. Nulls are dropped from tables; a null
+/// list item has no TOML form and is an error.
+pub fn inline(value: &Value) -> Result<String, String> {
+    Ok(match value {
+        Value::String(s) => string(s),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::Array(items) => {
+            let items = items.iter().map(inline).collect::<Result<Vec<_>, _>>()?;
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(map) => {
+            let pairs = map
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| Ok(format!("{} = {}", key(k), inline(v)?)))
+                .collect::<Result<Vec<_>, String>>()?;
+            if pairs.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{ {} }}", pairs.join(", "))
+            }
+        }
+        Value::Null => return Err("a null list item cannot be written as TOML".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,72 @@
+use super::*;
+use serde_json::json;
+
+#[test]
+fn escape_quotes_and_backslashes() {
+    assert_eq!(escape(r#"say "hello""#), r#"say \"hello\""#);
+    assert_eq!(escape(r"path\to\file"), r"path\\to\\file");
+    assert_eq!(
+        escape(r#"mixed "quote" and \back"#),
+        r#"mixed \"quote\" and \\back"#
+    );
+    assert_eq!(escape("plain text"), "plain text");
+}
+
+#[test]
+fn escape_control_characters() {
+    assert_eq!(escape("a\nb\tc\rd"), r"a\nb\tc\rd");
+    assert_eq!(
+        escape("bell\u{7}del\u{7f}nul\0"),
+        r"bell\u0007del\u007Fnul\u0000"
+    );
+    assert_eq!(escape("\u{1b}[0m"), r"\u001B[0m");
+}
+
+#[test]
+fn string_is_quoted() {
+    assert_eq!(string("x\ny"), r#""x\ny""#);
+}
+
+#[test]
+fn multiline_keeps_newlines_and_breaks_quote_runs() {
+    assert_eq!(escape_multiline("a\n\tb"), "a\n\tb");
+    assert_eq!(escape_multiline(r#"say """hi""""#), r#"say ""\"hi""\""#);
+    assert_eq!(
+        escape_multiline("c:\\dir\r\n\u{8}"),
+        "c:\\\\dir\\r\n\\u0008"
+    );
+}
+
+#[test]
+fn keys_are_bare_or_quoted() {
+    assert_eq!(key("api_key-2"), "api_key-2");
+    assert_eq!(key("my.server"), r#""my.server""#);
+    assert_eq!(key(""), r#""""#);
+    assert_eq!(key("a]\nb"), r#""a]\nb""#);
+}
+
+#[test]
+fn parse_key_round_trips() {
+    for k in [
+        "plain",
+        "my.server",
+        "with \"quote\"",
+        "a]\nb\u{1}",
+        "",
+        "é",
+    ] {
+        assert_eq!(parse_key(&key(k)).as_deref(), Some(k), "{k:?}");
+    }
+    assert_eq!(parse_key("\"open"), None);
+    assert_eq!(parse_key("has space"), None);
+}
+
+#[test]
+fn inline_values() {
+    assert_eq!(
+        inline(&json!({"args": ["-y", "x\ty"], "n": 3, "b": true, "skip": null})).unwrap(),
+        r#"{ args = ["-y", "x\ty"], n = 3, b = true }"#
+    );
+    assert_eq!(inline(&json!({})).unwrap(), "{}");
+    assert!(inline(&json!([1, null])).is_err());
+}
@@ -1,10 +1,27 @@
-use crate::deploy::deploy_agents_from_dir;
+use crate::council::{self, CouncilResult};
 use crate::deploy::provider::Provider;
+use crate::deploy::{deploy_agents_from_dir, deploy_plan_from_dir, DeployOptions};
 use crate::parse;
+use crate::progress::{NoProgress, Operation, ProgressSink};
 use crate::sidecar::SidecarConfig;
+use crate::vfs::{self, RealFs};
+use regex::Regex;
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
+use std::sync::OnceLock;
 
+fn pascal_case_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[A-Z][a-zA-Z0-9]+$").expect("valid regex"))
+}
+
+fn slug_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[a-z][a-z0-9-]*$").expect("valid regex"))
+}
+
+#[derive(Serialize)]
 pub struct Check {
     pub desc: String,
     pub passed: bool,
@@ -25,6 +42,7 @@ impl Check {
     }
 }
 
+#[derive(Serialize)]
 pub struct Suite {
     pub name: String,
     pub checks: Vec<Check>,
@@ -70,8 +88,7 @@ impl Suite {
         });
     }
 
-    fn assert_match(&mut self, desc: &str, value: &str, pattern: &str) {
-        let re = regex::Regex::new(pattern).unwrap();
+    fn assert_match(&mut self, desc: &str, value: &str, re: &Regex) {
         self.checks.push(if re.is_match(value) {
             Check::pass(desc)
         } else {
@@ -96,6 +113,29 @@ impl Suite {
     }
 }
 
+/// Every convention suite, in report order (warnings excluded).
+/// `progress` sees one `validate` item per suite, `pass` or `fail`.
+pub fn validate_all(root: &Path, progress: &mut dyn ProgressSink) -> Vec<Suite> {
+    let suites: [fn(&Path) -> Suite; 6] = [
+        validate_structure,
+        validate_agent_frontmatter,
+        validate_defaults,
+        validate_skills,
+        validate_deploy_parity,
+        crate::dci::validate_dci,
+    ];
+    let mut operation = Operation::start(progress, "validate", suites.len());
+    suites
+        .iter()
+        .map(|validate| {
+            let suite = validate(root);
+            let outcome = if suite.failed() == 0 { "pass" } else { "fail" };
+            operation.item(&suite.name, outcome);
+            suite
+        })
+        .collect()
+}
+
 // --- Suite 1: Module Structure ---
 
 pub fn validate_structure(root: &Path) -> Suite {
@@ -115,12 +155,26 @@ pub fn validate_structure(root: &Path) -> Suite {
     s.assert_file_exists("plugin.json exists", &pjson_path);
 
     if let Ok(content) = fs::read_to_string(&pjson_path) {
-        let valid = serde_json::from_str::<serde_json::Value>(&content).is_ok();
-        s.checks.push(if valid {
+        let parsed = serde_json::from_str::<serde_json::Value>(&content);
+        s.checks.push(if parsed.is_ok() {
             Check::pass("plugin.json is valid JSON")
         } else {
             Check::fail("plugin.json is not valid JSON")
         });
+        // Listings are optional; once a module has them they must not drift.
+        let lists = |doc: &serde_json::Value| {
+            ["agents", "commands", "skills"].map(|key| doc.get(key).cloned())
+        };
+        if let Some(doc) = parsed.ok().filter(|d| lists(d).iter().any(Option::is_some)) {
+            let listing = crate::plugin::listing(root, &crate::sidecar::SidecarConfig::load(root));
+            let expected = [listing.agents, listing.commands, listing.skills]
+                .map(|paths| (!paths.is_empty()).then(|| serde_json::json!(paths)));
+            s.checks.push(if lists(&doc) == expected {
+                Check::pass("plugin.json listings match module content")
+            } else {
+                Check::fail("plugin.json listings are out of date (run forge plugin generate)")
+            });
+        }
     }
 
     s.assert_file_exists("lib/Makefile exists", &root.join("lib/Makefile"));
@@ -147,12 +201,14 @@ fn read_agents(agents_dir: &Path) -> Vec<(String, String)> {
     agents
 }
 
-const KNOWN_PROVIDERS: &[&str] = &["claude", "gemini", "codex", "opencode"];
+const KNOWN_PROVIDERS: &[&str] = &[
+    "claude", "gemini", "codex", "opencode", "cursor", "windsurf", "copilot", "zed",
+];
 
 /// Extract agent names from defaults.yaml `agents:` section.
 /// Supports two formats:
-///   Flat:     agents: { AgentName: { model: ..., tools: ... } }
-///   Nested:   agents: { claude: { AgentName: { model: ... } } }
+///   Flat:     `agents: { AgentName: { model: ..., tools: ... } }`
+///   Nested:   `agents: { claude: { AgentName: { model: ... } } }`
 fn roster_names(defaults_content: &str) -> Vec<String> {
     let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(defaults_content) else {
         return Vec::new();
@@ -251,10 +307,10 @@ fn skills_with_roles(defaults_content: &str) -> Vec<String> {
     let collect = |mapping: &serde_yaml::Mapping, out: &mut Vec<String>| {
         for (key, value) in mapping {
             if let Some(name) = key.as_str() {
-                if value.get("roles").and_then(|r| r.as_sequence()).is_some() {
-                    if !out.contains(&name.to_string()) {
-                        out.push(name.to_string());
-                    }
+                if value.get("roles").and_then(|r| r.as_sequence()).is_some()
+                    && !out.contains(&name.to_string())
+                {
+                    out.push(name.to_string());
                 }
             }
         }
@@ -267,10 +323,10 @@ fn skills_with_roles(defaults_content: &str) -> Vec<String> {
                     if let Some(inner) = value.as_mapping() {
                         collect(inner, &mut names);
                     }
-                } else if value.is_mapping() {
-                    if value.get("roles").and_then(|r| r.as_sequence()).is_some() {
-                        names.push(key_str.to_string());
-                    }
+                } else if value.is_mapping()
+                    && value.get("roles").and_then(|r| r.as_sequence()).is_some()
+                {
+                    names.push(key_str.to_string());
                 }
             }
         }
@@ -396,11 +452,7 @@ pub fn validate_agent_frontmatter(root: &Path) -> Suite {
 
     for (_, content) in &agents {
         let name = parse::fm_value(content, "name").unwrap_or_default();
-        s.assert_match(
-            &format!("{name} is PascalCase"),
-            &name,
-            r"^[A-Z][a-zA-Z0-9]+$",
-        );
+        s.assert_match(&format!("{name} is PascalCase"), &name, pascal_case_regex());
     }
 
     let valid_models = ["sonnet", "opus", "haiku", "fast", "strong"];
@@ -452,13 +504,21 @@ pub fn validate_defaults(root: &Path) -> Suite {
         for role in &roles {
             let found = roster.iter().any(|r| r == role);
             s.checks.push(if found {
-                Check::pass(format!(
-                    "skill '{skill_name}' role '{role}' is in roster"
-                ))
+                Check::pass(format!("skill '{skill_name}' role '{role}' is in roster"))
+            } else {
+                Check::fail(format!("skill '{skill_name}' role '{role}' is in roster"))
+            });
+        }
+    }
+
+    let config = SidecarConfig::load(root);
+    for council in config.councils() {
+        for role in config.council_list(&council, "roles") {
+            let desc = format!("council '{council}' role '{role}' is in roster");
+            s.checks.push(if roster.contains(&role) {
+                Check::pass(desc)
             } else {
-                Check::fail(format!(
-                    "skill '{skill_name}' role '{role}' is in roster"
-                ))
+                Check::fail(desc)
             });
         }
     }
@@ -544,7 +604,7 @@ pub fn validate_skills(root: &Path) -> Suite {
 
     for name in &skill_names {
         let md_path = skills_dir.join(name).join("SKILL.md");
-        let Ok(content) = fs::read_to_string(&md_path) else {
+        let Ok(content) = vfs::read_header(&RealFs, &md_path) else {
             continue;
         };
         let fm_name = parse::fm_value(&content, "name").unwrap_or_default();
@@ -553,6 +613,23 @@ pub fn validate_skills(root: &Path) -> Suite {
         s.assert_not_empty(&format!("{name} SKILL.md has description"), &fm_desc);
     }
 
+    let config = SidecarConfig::load(root);
+    for council in council::load_councils(&config) {
+        let desc = format!(
+            "{}: council skill matches councils roster (run forge council generate)",
+            council.name
+        );
+        let current = council::render_council_skill(&council, &root.join("agents"), &config)
+            .and_then(|skill| council::write_council_skill(&skills_dir, &skill, true));
+        match current {
+            Ok(CouncilResult::Unchanged) => s.checks.push(Check::pass(desc)),
+            // Hand-written council skills are not generated, so not compared.
+            Ok(CouncilResult::SkippedUserOwned) => {}
+            Ok(CouncilResult::Written) => s.checks.push(Check::fail(desc)),
+            Err(e) => s.checks.push(Check::fail(format!("{}: {e}", council.name))),
+        }
+    }
+
     s
 }
 
@@ -607,6 +684,8 @@ fn provider_label(path: &Path) -> String {
     }
 }
 
+/// Deployed agent markdown in `dir`, excluding Codex `*.prompt.md` bodies
+/// (those carry no frontmatter; the agent itself is the `.toml`).
 fn sorted_md_entries(dir: &Path) -> Vec<std::fs::DirEntry> {
     let Ok(entries) = fs::read_dir(dir) else {
         return Vec::new();
@@ -614,6 +693,7 @@ fn sorted_md_entries(dir: &Path) -> Vec<std::fs::DirEntry> {
     let mut files: Vec<_> = entries
         .filter_map(Result::ok)
         .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .filter(|e| !e.file_name().to_string_lossy().ends_with(".prompt.md"))
         .collect();
     files.sort_by_key(std::fs::DirEntry::file_name);
     files
@@ -629,7 +709,7 @@ fn check_synced_from(s: &mut Suite, provider_dirs: &[(&std::path::PathBuf, Provi
                 .unwrap()
                 .to_string_lossy()
                 .to_string();
-            let content = fs::read_to_string(entry.path()).unwrap_or_default();
+            let content = vfs::read_header(&RealFs, &entry.path()).unwrap_or_default();
             let has_source = parse::fm_value(&content, "source").is_some()
                 || content.lines().any(|l| l.starts_with("# synced-from:"));
             s.checks.push(if has_source {
@@ -669,7 +749,6 @@ fn check_body_matches_source(s: &mut Suite, claude_dst: &Path, agents_dir: &Path
 }
 
 fn check_gemini_formatting(s: &mut Suite, gemini_dst: &Path) {
-    let slug_re = regex::Regex::new(r"^[a-z][a-z0-9-]*$").unwrap();
     let claude_tools = [
         "Read",
         "Write",
@@ -688,10 +767,10 @@ fn check_gemini_formatting(s: &mut Suite, gemini_dst: &Path) {
             .unwrap()
             .to_string_lossy()
             .to_string();
-        let content = fs::read_to_string(entry.path()).unwrap_or_default();
+        let content = vfs::read_header(&RealFs, &entry.path()).unwrap_or_default();
 
         let gemini_name = parse::fm_value(&content, "name").unwrap_or_default();
-        s.checks.push(if slug_re.is_match(&gemini_name) {
+        s.checks.push(if slug_regex().is_match(&gemini_name) {
             Check::pass(format!(
                 "{filename}: gemini name '{gemini_name}' is slugified"
             ))
@@ -730,7 +809,7 @@ fn check_model_resolved(s: &mut Suite, provider_dirs: &[(&std::path::PathBuf, Pr
                 .unwrap()
                 .to_string_lossy()
                 .to_string();
-            let content = fs::read_to_string(entry.path()).unwrap_or_default();
+            let content = vfs::read_header(&RealFs, &entry.path()).unwrap_or_default();
             let model = parse::fm_value(&content, "model").unwrap_or_default();
             let resolved = model != "fast" && model != "strong";
             s.checks.push(if resolved {
@@ -768,23 +847,42 @@ pub fn validate_deploy_parity(root: &Path) -> Suite {
 
     for (dst, provider) in &provider_dirs {
         let _ = fs::create_dir_all(dst);
-        let _ = deploy_agents_from_dir(&agents_dir, dst, *provider, &config, false, "");
+        let _ = deploy_agents_from_dir(
+            &agents_dir,
+            dst,
+            &config,
+            &DeployOptions::new(provider.clone()),
+            &mut NoProgress,
+        );
     }
 
-    let claude_count = count_md_files(&claude_dst);
-    let gemini_count = count_md_files(&gemini_dst);
-    let codex_count = count_md_files(&codex_dst);
+    // Every named agent, whichever providers it lists, so an agent meant
+    // only for some providers does not break parity for the others.
+    let names: Vec<String> = deploy_plan_from_dir(
+        &agents_dir,
+        &claude_dst,
+        &config,
+        &DeployOptions::new(Provider::Claude),
+        &mut NoProgress,
+    )
+    .map(|plan| plan.actions.into_iter().filter_map(|a| a.name).collect())
+    .unwrap_or_default();
 
-    s.assert_eq(
-        &format!("claude count ({claude_count}) == gemini count ({gemini_count})"),
-        &claude_count.to_string(),
-        &gemini_count.to_string(),
-    );
-    s.assert_eq(
-        &format!("claude count ({claude_count}) == codex count ({codex_count})"),
-        &claude_count.to_string(),
-        &codex_count.to_string(),
-    );
+    for (dst, provider) in &provider_dirs {
+        let count = count_md_files(dst);
+        let expected = names
+            .iter()
+            .filter(|name| config.agent_targets(name, provider.as_str()))
+            .count();
+        s.assert_eq(
+            &format!(
+                "{provider} count ({count}) == agents for {provider} ({expected})",
+                provider = provider.as_str()
+            ),
+            &expected.to_string(),
+            &count.to_string(),
+        );
+    }
 
     check_synced_from(&mut s, &provider_dirs);
     check_body_matches_source(&mut s, &claude_dst, &agents_dir);
@@ -809,6 +907,15 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
+    #[test]
+    fn patterns_compile_once() {
+        assert!(std::ptr::eq(pascal_case_regex(), pascal_case_regex()));
+        let mut suite = Suite::new("names");
+        suite.assert_match("pascal", "SecurityArchitect", pascal_case_regex());
+        suite.assert_match("slug", "Security", slug_regex());
+        assert_eq!((suite.passed(), suite.failed()), (1, 1));
+    }
+
     #[test]
     fn structure_missing_files() {
         let dir = tempdir().unwrap();
@@ -839,6 +946,30 @@ mod tests {
         assert_eq!(suite.passed(), 7);
     }
 
+    #[test]
+    fn structure_plugin_listing_drift() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("agents")).unwrap();
+        fs::write(root.join("agents/Dev.md"), "---\nname: Dev\n---\n").unwrap();
+        fs::create_dir_all(root.join(".claude-plugin")).unwrap();
+        let pjson = root.join(".claude-plugin/plugin.json");
+
+        fs::write(&pjson, r#"{"name":"test","agents":["./agents/Old.md"]}"#).unwrap();
+        let suite = validate_structure(root);
+        assert!(suite
+            .checks
+            .iter()
+            .any(|c| !c.passed && c.desc.contains("listings are out of date")));
+
+        fs::write(&pjson, r#"{"name":"test","agents":["./agents/Dev.md"]}"#).unwrap();
+        let suite = validate_structure(root);
+        assert!(suite
+            .checks
+            .iter()
+            .any(|c| c.passed && c.desc.contains("listings match")));
+    }
+
     #[test]
     fn roster_flat() {
         let yaml = "agents:\n  Dev:\n    model: fast\n    tools: Read\n  QA:\n    model: fast\n    tools: Read\n";
@@ -888,6 +1019,41 @@ mod tests {
         assert_eq!(skill_roles(yaml, "Review"), vec!["Dev", "QA"]);
     }
 
+    #[test]
+    fn parity_counts_only_agents_meant_for_each_provider() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("agents")).unwrap();
+        for name in ["Dev", "Lead"] {
+            fs::write(
+                root.join(format!("agents/{name}.md")),
+                format!("---\nname: {name}\ndescription: {name}. USE WHEN testing.\n---\nBody.\n"),
+            )
+            .unwrap();
+        }
+        fs::write(
+            root.join("defaults.yaml"),
+            "agents:\n  Lead:\n    providers: [claude, codex]\n",
+        )
+        .unwrap();
+
+        let suite = validate_deploy_parity(root);
+        let counts: Vec<_> = suite
+            .checks
+            .iter()
+            .filter(|c| c.desc.contains(" count ("))
+            .map(|c| (c.desc.as_str(), c.passed))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("claude count (2) == agents for claude (2)", true),
+                ("gemini count (1) == agents for gemini (1)", true),
+                ("codex count (2) == agents for codex (2)", true),
+            ]
+        );
+    }
+
     #[test]
     fn deployed_body_extraction() {
         let content = "---\nname: Test\n---\n# synced-from: Test.md\n\nBody here.\n";
@@ -0,0 +1,463 @@
+//! Filesystem access for deploy, skill copy, and the manifest, so the same
+//! code runs against the disk ([`RealFs`]) or memory ([`MemoryFs`]).
+//!
+//! A [`MemoryFs`] laid over the disk with [`MemoryFs::overlay`] reads real
+//! files but keeps every write and removal in memory: a dry run that records
+//! exactly what a deploy would have written. An empty [`MemoryFs::new`] lets
+//! tests build fixtures without temp directories.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// The filesystem operations the deploy, skill, and manifest modules need.
+/// Writes take `&self` so one handle can be shared between reads and
+/// writes; implementations synchronize internally.
+pub trait Vfs: fmt::Debug + Send + Sync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// Paths of the entries in `dir`, sorted.
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+
+    fn exists(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+
+    fn is_symlink(&self, path: &Path) -> bool;
+
+    /// Where the symlink at `path` points.
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Create a symlink at `link` pointing to `target`.
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()>;
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// A buffered reader over the file at `path`, for callers that may
+    /// stop before the end; by default the whole file, read into memory.
+    fn open(&self, path: &Path) -> io::Result<Box<dyn io::BufRead + '_>> {
+        Ok(Box::new(io::Cursor::new(self.read(path)?)))
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.write(to, &self.read(from)?)
+    }
+}
+
+/// The frontmatter of the file at `path` and the first body line, as
+/// [`crate::parse::read_header`] reads them, without reading the rest of
+/// a file on disk.
+pub fn read_header(vfs: &dyn Vfs, path: &Path) -> io::Result<String> {
+    crate::parse::read_header(vfs.open(path)?)
+}
+
+/// The real filesystem, through `std::fs`. Writes and copies go to a
+/// temporary file next to the destination that is renamed into place, so
+/// an interrupted deploy never leaves a truncated file.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealFs;
+
+impl Vfs for RealFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        write_atomic(path, contents)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_dir_all(path)
+    }
+
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut paths = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        path.is_symlink()
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::read_link(path)
+    }
+
+    #[cfg(unix)]
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        std::os::unix::fs::symlink(target, link)
+    }
+
+    #[cfg(windows)]
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        if target.is_dir() {
+            std::os::windows::fs::symlink_dir(target, link)
+        } else {
+            std::os::windows::fs::symlink_file(target, link)
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn symlink(&self, _target: &Path, link: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{}: symlinks are not supported here", link.display()),
+        ))
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn io::BufRead + '_>> {
+        Ok(Box::new(io::BufReader::new(std::fs::File::open(path)?)))
+    }
+
+    /// A copy-on-write clone where the filesystem supports it (btrfs, XFS,
+    /// APFS; feature `reflink`), else `std::fs::copy`. Both keep permissions
+    /// (skill scripts stay executable). Never a hard link: deployed files
+    /// are rewritten in place, which would change the source too.
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        replace_with(to, |tmp| {
+            #[cfg(feature = "reflink")]
+            match reflink_copy::reflink(from, tmp) {
+                Ok(()) => return Ok(()),
+                Err(e) => tracing::trace!(from = %from.display(), "reflink failed, copying: {e}"),
+            }
+            std::fs::copy(from, tmp).map(|_| ())
+        })
+    }
+}
+
+/// Replace `path` with `contents` through a temporary file in the same
+/// directory, keeping the permissions of the file it replaces. Readers see
+/// the old content or the new, never part of it. A symlink is written
+/// through, as `std::fs::write` would, rather than replaced.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let resolved;
+    let path = if path.is_symlink() {
+        resolved = std::fs::canonicalize(path)?;
+        resolved.as_path()
+    } else {
+        path
+    };
+    let permissions = std::fs::metadata(path).ok().map(|m| m.permissions());
+    replace_with(path, |tmp| {
+        std::fs::write(tmp, contents)?;
+        match permissions {
+            Some(permissions) => std::fs::set_permissions(tmp, permissions),
+            None => Ok(()),
+        }
+    })
+}
+
+/// Let `fill` create a temporary sibling of `path`, then rename it over
+/// `path`. The temporary file is removed if either step fails.
+fn replace_with(path: &Path, fill: impl FnOnce(&Path) -> io::Result<()>) -> io::Result<()> {
+    let tmp = temp_path(path);
+    let result = fill(&tmp).and_then(|()| std::fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Whether `path` is a symlink to an entry named `name`: what a `--link`
+/// deploy of the source `name` creates, and what later deploys may replace
+/// (any other symlink at a destination is refused).
+pub fn is_source_link(vfs: &dyn Vfs, path: &Path, name: &std::ffi::OsStr) -> bool {
+    vfs.read_link(path)
+        .is_ok_and(|target| target.file_name() == Some(name))
+}
+
+/// A hidden name next to `path`, unique within this process.
+fn temp_path(path: &Path) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{name}.{}.{n}.tmp", std::process::id()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    File(Vec<u8>),
+    Dir,
+    /// A symlink to the path it holds.
+    Link(PathBuf),
+    /// Removed here, hiding whatever the disk below holds.
+    Removed,
+}
+
+/// An in-memory filesystem, optionally laid over the disk. Paths are used as
+/// given, so tests should stick to one spelling (absolute or relative).
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    nodes: Mutex<BTreeMap<PathBuf, Node>>,
+    disk: Option<RealFs>,
+}
+
+impl MemoryFs {
+    /// An empty filesystem.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A layer over the disk: reads fall through to real files until a path
+    /// is written or removed here, and nothing on disk is ever changed.
+    pub fn overlay() -> Self {
+        Self {
+            nodes: Mutex::default(),
+            disk: Some(RealFs),
+        }
+    }
+
+    /// Add a file, creating its parent directories.
+    #[must_use]
+    pub fn with_file(self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) -> Self {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            self.insert_dirs(parent);
+        }
+        self.nodes()
+            .insert(path.to_path_buf(), Node::File(contents.into()));
+        self
+    }
+
+    /// Files written through this filesystem and their final contents.
+    pub fn written(&self) -> BTreeMap<PathBuf, Vec<u8>> {
+        self.nodes()
+            .iter()
+            .filter_map(|(path, node)| match node {
+                Node::File(contents) => Some((path.clone(), contents.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Paths removed through this filesystem and not written again since.
+    pub fn removed(&self) -> Vec<PathBuf> {
+        self.nodes()
+            .iter()
+            .filter(|(_, node)| **node == Node::Removed)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    fn nodes(&self) -> MutexGuard<'_, BTreeMap<PathBuf, Node>> {
+        self.nodes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn insert_dirs(&self, path: &Path) {
+        let mut nodes = self.nodes();
+        for dir in path.ancestors().filter(|p| !p.as_os_str().is_empty()) {
+            match nodes.get(dir) {
+                Some(Node::Dir) => break,
+                Some(Node::File(_)) => {}
+                _ => {
+                    nodes.insert(dir.to_path_buf(), Node::Dir);
+                }
+            }
+        }
+    }
+
+    /// The node for `path`, following symlinks and looking through to the
+    /// disk when untouched.
+    fn lookup(&self, path: &Path) -> Option<Node> {
+        let nodes = self.nodes();
+        match nodes.get(path) {
+            Some(Node::Link(target)) => {
+                let target = target.clone();
+                drop(nodes);
+                return self.lookup(&target);
+            }
+            Some(node) => return (*node != Node::Removed).then(|| node.clone()),
+            None => {}
+        }
+        let has_children = nodes
+            .iter()
+            .any(|(p, node)| *node != Node::Removed && p.parent() == Some(path));
+        drop(nodes);
+        if has_children {
+            return Some(Node::Dir);
+        }
+        let disk = self.disk?;
+        if disk.is_dir(path) {
+            Some(Node::Dir)
+        } else {
+            disk.read(path).ok().map(Node::File)
+        }
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{}: no such file or directory", path.display()),
+    )
+}
+
+impl Vfs for MemoryFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.lookup(path) {
+            Some(Node::File(contents)) => Ok(contents),
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: is a directory", path.display()),
+            )),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !self.is_dir(parent) {
+                return Err(not_found(parent));
+            }
+        }
+        self.nodes()
+            .insert(path.to_path_buf(), Node::File(contents.to_vec()));
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        if let Some(Node::File(_)) = self.lookup(path) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{}: is a file", path.display()),
+            ));
+        }
+        self.insert_dirs(path);
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        if self.is_symlink(path) {
+            self.nodes().insert(path.to_path_buf(), Node::Removed);
+            return Ok(());
+        }
+        match self.lookup(path) {
+            Some(Node::File(_)) => {
+                self.nodes().insert(path.to_path_buf(), Node::Removed);
+                Ok(())
+            }
+            _ => Err(not_found(path)),
+        }
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        if !self.is_dir(path) {
+            return Err(not_found(path));
+        }
+        for child in self.read_dir(path)? {
+            if self.is_dir(&child) {
+                self.remove_dir_all(&child)?;
+            } else {
+                self.remove_file(&child)?;
+            }
+        }
+        self.nodes().insert(path.to_path_buf(), Node::Removed);
+        Ok(())
+    }
+
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        if !self.is_dir(dir) {
+            return Err(not_found(dir));
+        }
+        let mut paths: Vec<PathBuf> = match self.disk {
+            Some(disk) if disk.is_dir(dir) => disk.read_dir(dir)?,
+            _ => Vec::new(),
+        };
+        let nodes = self.nodes();
+        paths.extend(nodes.keys().filter(|p| p.parent() == Some(dir)).cloned());
+        paths.sort();
+        paths.dedup();
+        paths.retain(|p| nodes.get(p) != Some(&Node::Removed));
+        Ok(paths)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.lookup(path).is_some()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.lookup(path) == Some(Node::Dir)
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        match self.nodes().get(path) {
+            Some(node) => matches!(node, Node::Link(_)),
+            None => self.disk.is_some_and(|disk| disk.is_symlink(path)),
+        }
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.nodes().get(path) {
+            Some(Node::Link(target)) => return Ok(target.clone()),
+            Some(_) => {}
+            None => {
+                if let Some(disk) = self.disk {
+                    return disk.read_link(path);
+                }
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}: not a symlink", path.display()),
+        ))
+    }
+
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        if self.exists(link) || self.is_symlink(link) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{}: already exists", link.display()),
+            ));
+        }
+        if let Some(parent) = link.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !self.is_dir(parent) {
+                return Err(not_found(parent));
+            }
+        }
+        self.nodes()
+            .insert(link.to_path_buf(), Node::Link(target.to_path_buf()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,210 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn memory_fs_reads_back_writes() {
+    let vfs = MemoryFs::new();
+    vfs.create_dir_all(Path::new("/dst/sub")).unwrap();
+    vfs.write(Path::new("/dst/sub/a.md"), b"hello").unwrap();
+
+    assert_eq!(
+        vfs.read_to_string(Path::new("/dst/sub/a.md")).unwrap(),
+        "hello"
+    );
+    assert!(vfs.is_dir(Path::new("/dst")));
+    assert_eq!(
+        vfs.read_dir(Path::new("/dst")).unwrap(),
+        vec![PathBuf::from("/dst/sub")]
+    );
+}
+
+#[test]
+fn memory_fs_write_needs_parent() {
+    let vfs = MemoryFs::new();
+    let err = vfs.write(Path::new("/missing/a.md"), b"x").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn memory_fs_remove_dir_all_removes_children() {
+    let vfs = MemoryFs::new()
+        .with_file("/skills/A/SKILL.md", "# A")
+        .with_file("/skills/A/lib/run.sh", "echo");
+    vfs.remove_dir_all(Path::new("/skills/A")).unwrap();
+
+    assert!(!vfs.exists(Path::new("/skills/A")));
+    assert!(!vfs.exists(Path::new("/skills/A/lib/run.sh")));
+    assert!(vfs.read_dir(Path::new("/skills")).unwrap().is_empty());
+}
+
+#[test]
+fn overlay_reads_disk_but_keeps_writes_in_memory() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("old.md"), "disk").unwrap();
+    let vfs = MemoryFs::overlay();
+
+    assert_eq!(
+        vfs.read_to_string(&dir.path().join("old.md")).unwrap(),
+        "disk"
+    );
+    vfs.write(&dir.path().join("new.md"), b"memory").unwrap();
+    vfs.remove_file(&dir.path().join("old.md")).unwrap();
+
+    assert_eq!(
+        vfs.read_dir(dir.path()).unwrap(),
+        vec![dir.path().join("new.md")]
+    );
+    assert!(!dir.path().join("new.md").exists());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("old.md")).unwrap(),
+        "disk"
+    );
+    assert_eq!(vfs.removed(), vec![dir.path().join("old.md")]);
+    assert_eq!(
+        vfs.written().get(&dir.path().join("new.md")).unwrap(),
+        b"memory"
+    );
+}
+
+#[test]
+fn overlay_hides_removed_disk_tree() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join("Skill/lib")).unwrap();
+    fs::write(dir.path().join("Skill/lib/run.sh"), "echo").unwrap();
+    let vfs = MemoryFs::overlay();
+
+    vfs.remove_dir_all(&dir.path().join("Skill")).unwrap();
+    vfs.create_dir_all(&dir.path().join("Skill")).unwrap();
+
+    assert!(vfs.read_dir(&dir.path().join("Skill")).unwrap().is_empty());
+    assert!(dir.path().join("Skill/lib/run.sh").exists());
+}
+
+#[test]
+fn real_fs_lists_sorted() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("b"), "").unwrap();
+    fs::write(dir.path().join("a"), "").unwrap();
+    assert_eq!(
+        RealFs.read_dir(dir.path()).unwrap(),
+        vec![dir.path().join("a"), dir.path().join("b")]
+    );
+}
+
+#[test]
+fn read_header_stops_at_the_body_on_either_fs() {
+    let content = "---\nname: Dev\n---\nFirst.\nSecond.\n";
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("Dev.md");
+    fs::write(&path, content).unwrap();
+    let memory = MemoryFs::new().with_file("/Dev.md", content);
+    for (vfs, path) in [
+        (&RealFs as &dyn Vfs, path.as_path()),
+        (&memory, Path::new("/Dev.md")),
+    ] {
+        assert_eq!(
+            read_header(vfs, path).unwrap(),
+            "---\nname: Dev\n---\nFirst.\n"
+        );
+    }
+    let err = read_header(&memory, Path::new("/missing.md")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[cfg(unix)]
+#[test]
+fn real_fs_copy_overwrites_and_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = TempDir::new().unwrap();
+    let from = dir.path().join("run.sh");
+    let to = dir.path().join("copy.sh");
+    fs::write(&from, "echo new").unwrap();
+    fs::set_permissions(&from, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(&to, "echo old").unwrap();
+
+    RealFs.copy(&from, &to).unwrap();
+    assert_eq!(fs::read_to_string(&to).unwrap(), "echo new");
+    assert_eq!(
+        fs::metadata(&to).unwrap().permissions().mode() & 0o777,
+        0o755
+    );
+
+    fs::write(&to, "edited").unwrap();
+    assert_eq!(fs::read_to_string(&from).unwrap(), "echo new");
+}
+
+#[test]
+fn real_fs_write_replaces_without_leaving_temp_files() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("Agent.md");
+    RealFs.write(&path, b"old").unwrap();
+    RealFs.write(&path, b"new").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    assert_eq!(RealFs.read_dir(dir.path()).unwrap(), vec![path]);
+}
+
+#[test]
+fn write_atomic_fails_cleanly_without_a_parent() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("missing/Agent.md");
+    let err = write_atomic(&path, b"x").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(RealFs.read_dir(dir.path()).unwrap().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn write_atomic_keeps_permissions_of_the_replaced_file() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("run.sh");
+    fs::write(&path, "echo old").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    write_atomic(&path, b"echo new").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "echo new");
+    assert_eq!(
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o755
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn write_atomic_writes_through_a_symlink() {
+    let dir = TempDir::new().unwrap();
+    let target = dir.path().join("dotfiles.toml");
+    let link = dir.path().join("config.toml");
+    fs::write(&target, "old").unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    write_atomic(&link, b"new").unwrap();
+    assert!(link.is_symlink());
+    assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+}
+
+#[test]
+fn memory_fs_symlinks_read_through_and_remove_alone() {
+    let vfs = MemoryFs::new().with_file("/src/Agent.md", "body");
+    vfs.create_dir_all(Path::new("/dst")).unwrap();
+    vfs.symlink(Path::new("/src/Agent.md"), Path::new("/dst/Agent.md"))
+        .unwrap();
+
+    let link = Path::new("/dst/Agent.md");
+    assert!(vfs.is_symlink(link));
+    assert_eq!(vfs.read_to_string(link).unwrap(), "body");
+    assert!(is_source_link(&vfs, link, "Agent.md".as_ref()));
+    assert!(!is_source_link(&vfs, link, "Other.md".as_ref()));
+    assert_eq!(
+        vfs.symlink(Path::new("/src/Agent.md"), link)
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::AlreadyExists
+    );
+
+    vfs.remove_file(link).unwrap();
+    assert!(!vfs.exists(link));
+    assert!(vfs.exists(Path::new("/src/Agent.md")));
+}
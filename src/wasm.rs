@@ -0,0 +1,75 @@
+//! JavaScript bindings for the pure-text modules (feature `wasm`), so a
+//! web module editor can check frontmatter and preview stripped bodies
+//! client-side. Build with
+//! `cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm`.
+//! Nothing here touches the filesystem.
+
+use crate::frontmatter::AgentFrontmatter;
+use crate::{parse, query, strip};
+use wasm_bindgen::prelude::*;
+
+/// A frontmatter value as [`parse::fm_value`] reads it.
+#[wasm_bindgen(js_name = frontmatterValue)]
+pub fn frontmatter_value(content: &str, key: &str) -> Option<String> {
+    parse::fm_value(content, key)
+}
+
+/// The agent frontmatter of `content` as JSON.
+#[wasm_bindgen(js_name = agentFrontmatter)]
+pub fn agent_frontmatter(content: &str) -> Result<String, String> {
+    let fm = AgentFrontmatter::from_markdown(content).map_err(|e| e.to_string())?;
+    serde_json::to_string(&fm).map_err(|e| e.to_string())
+}
+
+/// Why `name` is not a valid agent name, or `undefined` when it is.
+#[wasm_bindgen(js_name = checkAgentName)]
+pub fn check_agent_name(name: &str) -> Option<String> {
+    parse::validate_agent_name(name).err()
+}
+
+#[wasm_bindgen(js_name = stripFront)]
+pub fn strip_front(content: &str) -> String {
+    strip::strip_front(content)
+}
+
+#[wasm_bindgen(js_name = stripFrontKeep)]
+pub fn strip_front_keep(content: &str, keys: &str) -> String {
+    strip::strip_front_keep(content, keys)
+}
+
+/// The value at dot-path `path` in `yaml` as JSON, or `undefined` when the
+/// path does not exist.
+#[wasm_bindgen]
+pub fn query(yaml: &str, path: &str) -> Result<Option<String>, String> {
+    let doc: serde_yaml::Value = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+    query::get(&doc, path)
+        .map(|value| serde_json::to_string(value).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontmatter_bindings() {
+        let content = "---\nname: Dev\nclaude.tools: [Read]\n---\nBody.\n";
+        assert_eq!(frontmatter_value(content, "name").as_deref(), Some("Dev"));
+        let json: serde_json::Value =
+            serde_json::from_str(&agent_frontmatter(content).unwrap()).unwrap();
+        assert_eq!(json["claude.tools"][0], "Read");
+        assert!(check_agent_name("Dev").is_none());
+        assert!(check_agent_name("dev").is_some());
+    }
+
+    #[test]
+    fn query_returns_json() {
+        let yaml = "agents:\n  Dev:\n    model: fast\n";
+        assert_eq!(
+            query(yaml, ".agents.Dev").unwrap().as_deref(),
+            Some(r#"{"model":"fast"}"#)
+        );
+        assert_eq!(query(yaml, ".missing").unwrap(), None);
+        assert!(query("a: [", ".a").is_err());
+    }
+}
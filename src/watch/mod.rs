@@ -0,0 +1,207 @@
+//! Change detection for `forge watch`: fingerprints a module's deployable
+//! sources by modification time and size, diffs two fingerprints, and maps
+//! the changed paths to the installers that need to rerun.
+//!
+//! Polling keeps this dependency-free and behaves the same on every
+//! platform; module trees are small enough that a rescan is cheap.
+
+use crate::hooks::HOOKS_FILE;
+use crate::mcp::MCP_DIR;
+use crate::memory::GUIDANCE_FILE;
+use crate::styles::{STATUSLINE_FILE, STYLES_DIR};
+use crate::template::TEMPLATES_DIR;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Module-relative paths whose contents feed a deploy.
+const WATCHED: &[&str] = &[
+    "agents",
+    "skills",
+    "commands",
+    "hooks",
+    MCP_DIR,
+    STYLES_DIR,
+    STATUSLINE_FILE,
+    GUIDANCE_FILE,
+    TEMPLATES_DIR,
+    "module.yaml",
+    "defaults.yaml",
+    "config.yaml",
+];
+
+/// Config files every installer reads: a change (or a template change)
+/// redeploys everything.
+const CONFIG_FILES: &[&str] = &["module.yaml", "defaults.yaml", "config.yaml"];
+
+/// Module-relative path to `(modified, size)`.
+pub type Fingerprint = BTreeMap<String, (SystemTime, u64)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+/// An installer `forge watch` can rerun, in deploy order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Target {
+    Agents,
+    Skills,
+    Commands,
+    Hooks,
+    Mcp,
+    Memory,
+    Styles,
+}
+
+impl Target {
+    pub const ALL: [Target; 7] = [
+        Target::Agents,
+        Target::Skills,
+        Target::Commands,
+        Target::Hooks,
+        Target::Mcp,
+        Target::Memory,
+        Target::Styles,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Target::Agents => "agents",
+            Target::Skills => "skills",
+            Target::Commands => "commands",
+            Target::Hooks => "hooks",
+            Target::Mcp => "mcp",
+            Target::Memory => "memory",
+            Target::Styles => "styles",
+        }
+    }
+}
+
+fn walk(dir: &Path, rel: &str, out: &mut Fingerprint) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Editor swap files and other dotfiles are not deployable.
+        if name.starts_with('.') || name.ends_with('~') {
+            continue;
+        }
+        let rel = format!("{rel}/{name}");
+        let path = entry.path();
+        if path.is_dir() {
+            walk(&path, &rel, out);
+        } else {
+            record(&path, rel, out);
+        }
+    }
+}
+
+fn record(path: &Path, rel: String, out: &mut Fingerprint) {
+    if let Ok(meta) = std::fs::metadata(path) {
+        let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        out.insert(rel, (modified, meta.len()));
+    }
+}
+
+/// Fingerprint every watched file under `root`.
+pub fn scan(root: &Path) -> Fingerprint {
+    let mut out = Fingerprint::new();
+    for entry in WATCHED {
+        let path = root.join(entry);
+        if path.is_dir() {
+            walk(&path, entry, &mut out);
+        } else if path.is_file() {
+            record(&path, (*entry).to_string(), &mut out);
+        }
+    }
+    out
+}
+
+/// Paths added, modified, or removed between two scans, sorted by path.
+pub fn diff(old: &Fingerprint, new: &Fingerprint) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for (path, stamp) in new {
+        let kind = match old.get(path) {
+            None => ChangeKind::Added,
+            Some(previous) if previous != stamp => ChangeKind::Modified,
+            Some(_) => continue,
+        };
+        changes.push(Change {
+            path: path.clone(),
+            kind,
+        });
+    }
+    for path in old.keys().filter(|p| !new.contains_key(*p)) {
+        changes.push(Change {
+            path: path.clone(),
+            kind: ChangeKind::Removed,
+        });
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+/// Installers affected by `changes`, deduplicated and in deploy order.
+pub fn affected_targets(changes: &[Change]) -> Vec<Target> {
+    let mut targets = Vec::new();
+    for change in changes {
+        let top = change.path.split('/').next().unwrap_or_default();
+        let hit: &[Target] = match top {
+            _ if CONFIG_FILES.contains(&top) || top == TEMPLATES_DIR => &Target::ALL,
+            // Agents also feed the memory-file roster.
+            "agents" => &[Target::Agents, Target::Memory],
+            "skills" => &[Target::Skills],
+            "commands" => &[Target::Commands],
+            _ if change.path == HOOKS_FILE => &[Target::Hooks],
+            _ if top == MCP_DIR => &[Target::Mcp],
+            _ if top == GUIDANCE_FILE => &[Target::Memory],
+            _ if top == STYLES_DIR || top == STATUSLINE_FILE => &[Target::Styles],
+            _ => &[],
+        };
+        targets.extend_from_slice(hit);
+    }
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+/// Whether `root` has (or had, per `changes`) sources for `target`, so
+/// modules without hooks or MCP servers do not run those installers.
+pub fn has_sources(root: &Path, target: Target, changes: &[Change]) -> bool {
+    let sources: &[&str] = match target {
+        Target::Agents => &["agents"],
+        Target::Skills => &["skills"],
+        Target::Commands => &["commands"],
+        Target::Hooks => &[HOOKS_FILE],
+        Target::Mcp => &[MCP_DIR],
+        Target::Memory => &[GUIDANCE_FILE],
+        Target::Styles => &[STYLES_DIR, STATUSLINE_FILE],
+    };
+    sources.iter().any(|s| {
+        root.join(s).exists()
+            || changes
+                .iter()
+                .any(|c| c.path == *s || c.path.starts_with(&format!("{s}/")))
+    })
+}
+
+/// The directory or file an installer takes as its source argument.
+pub fn source_arg(root: &Path, target: Target) -> PathBuf {
+    match target {
+        Target::Agents | Target::Skills | Target::Commands => root.join(target.as_str()),
+        _ => root.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,99 @@
+use super::*;
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn change(path: &str, kind: ChangeKind) -> Change {
+    Change {
+        path: path.to_string(),
+        kind,
+    }
+}
+
+#[test]
+fn scan_and_diff() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("agents")).unwrap();
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("agents/A.md"), "a").unwrap();
+    fs::write(root.join("agents/.A.md.swp"), "x").unwrap();
+    fs::write(root.join("target/out"), "ignored").unwrap();
+    fs::write(root.join("module.yaml"), "name: m\n").unwrap();
+
+    let before = scan(root);
+    assert_eq!(
+        before.keys().collect::<Vec<_>>(),
+        vec!["agents/A.md", "module.yaml"]
+    );
+
+    fs::write(root.join("agents/A.md"), "longer").unwrap();
+    fs::write(root.join("agents/B.md"), "b").unwrap();
+    fs::remove_file(root.join("module.yaml")).unwrap();
+    let after = scan(root);
+
+    assert_eq!(
+        diff(&before, &after),
+        vec![
+            change("agents/A.md", ChangeKind::Modified),
+            change("agents/B.md", ChangeKind::Added),
+            change("module.yaml", ChangeKind::Removed),
+        ]
+    );
+    assert!(diff(&after, &after).is_empty());
+}
+
+#[test]
+fn modified_time_alone_is_a_change() {
+    let mut old = Fingerprint::new();
+    old.insert("agents/A.md".into(), (SystemTime::UNIX_EPOCH, 1));
+    let mut new = old.clone();
+    new.insert(
+        "agents/A.md".into(),
+        (SystemTime::UNIX_EPOCH + Duration::from_secs(1), 1),
+    );
+    assert_eq!(
+        diff(&old, &new),
+        vec![change("agents/A.md", ChangeKind::Modified)]
+    );
+}
+
+#[test]
+fn targets_for_changes() {
+    assert_eq!(
+        affected_targets(&[
+            change("skills/Foo/SKILL.md", ChangeKind::Modified),
+            change("agents/A.md", ChangeKind::Added),
+        ]),
+        vec![Target::Agents, Target::Skills, Target::Memory]
+    );
+    assert_eq!(
+        affected_targets(&[change("hooks/hooks.json", ChangeKind::Modified)]),
+        vec![Target::Hooks]
+    );
+    assert_eq!(
+        affected_targets(&[change("output-styles/Terse.md", ChangeKind::Removed)]),
+        vec![Target::Styles]
+    );
+    assert_eq!(
+        affected_targets(&[change("defaults.yaml", ChangeKind::Modified)]),
+        Target::ALL.to_vec()
+    );
+    assert!(affected_targets(&[change("hooks/run.sh", ChangeKind::Modified)]).is_empty());
+}
+
+#[test]
+fn sources_include_removed_paths() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    assert!(!has_sources(root, Target::Mcp, &[]));
+    assert!(has_sources(
+        root,
+        Target::Mcp,
+        &[change("mcp/server.json", ChangeKind::Removed)]
+    ));
+    fs::create_dir_all(root.join("agents")).unwrap();
+    assert!(has_sources(root, Target::Agents, &[]));
+    assert_eq!(source_arg(root, Target::Agents), root.join("agents"));
+    assert_eq!(source_arg(root, Target::Hooks), root.to_path_buf());
+}
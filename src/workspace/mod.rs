@@ -0,0 +1,144 @@
+//! Module discovery across a repository: every directory with a
+//! `module.yaml`, as handles for batch deploy and validate and for resolving
+//! council roles that live in sibling modules.
+
+use crate::parse;
+use crate::sidecar::SidecarConfig;
+use std::path::{Path, PathBuf};
+
+/// Directories never searched for modules.
+const SKIP_DIRS: &[&str] = &["target", "node_modules"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub root: PathBuf,
+    /// `name` from `module.yaml`, else the directory name.
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl Module {
+    fn load(root: &Path) -> Self {
+        let content = std::fs::read_to_string(root.join("module.yaml")).unwrap_or_default();
+        let name = parse::module_name(&content)
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| {
+                root.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            });
+        Self {
+            root: root.to_path_buf(),
+            name,
+            version: parse::module_version(&content),
+        }
+    }
+
+    pub fn agents_dir(&self) -> PathBuf {
+        self.root.join("agents")
+    }
+
+    pub fn skills_dir(&self) -> PathBuf {
+        self.root.join("skills")
+    }
+
+    pub fn commands_dir(&self) -> PathBuf {
+        self.root.join("commands")
+    }
+
+    pub fn config(&self) -> SidecarConfig {
+        SidecarConfig::load(&self.root)
+    }
+
+    /// Agent names (file stems of `agents/*.md`), sorted.
+    pub fn agents(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(self.agents_dir()) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "md"))
+            .filter_map(|p| Some(p.file_stem()?.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workspace {
+    pub root: PathBuf,
+    /// Modules sorted by path.
+    pub modules: Vec<Module>,
+}
+
+impl Workspace {
+    pub fn module(&self, name: &str) -> Option<&Module> {
+        self.modules.iter().find(|m| m.name == name)
+    }
+
+    /// The first module (by path) shipping agent `agent`.
+    pub fn agent_owner(&self, agent: &str) -> Option<&Module> {
+        self.modules
+            .iter()
+            .find(|m| m.agents_dir().join(format!("{agent}.md")).is_file())
+    }
+
+    /// Each of `council`'s roles in `module` with the module that ships the
+    /// agent: `module` itself when it has one, else a sibling.
+    pub fn resolve_council<'a>(
+        &'a self,
+        module: &'a Module,
+        council: &str,
+    ) -> Vec<(String, Option<&'a Module>)> {
+        let local = module.agents();
+        module
+            .config()
+            .council_list(council, "roles")
+            .into_iter()
+            .map(|role| {
+                let owner = if local.contains(&role) {
+                    Some(module)
+                } else {
+                    self.agent_owner(&role)
+                };
+                (role, owner)
+            })
+            .collect()
+    }
+}
+
+/// Find every module under `root` (including `root` itself). Hidden,
+/// `target`, and `node_modules` directories are skipped and symlinks are
+/// not followed.
+pub fn discover(root: &Path) -> Result<Workspace, String> {
+    if !root.is_dir() {
+        return Err(format!("not a directory: {}", root.display()));
+    }
+    let mut modules = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        if dir.join("module.yaml").is_file() {
+            modules.push(Module::load(&dir));
+        }
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| format!("failed to read {}: {e}", dir.display()))?;
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            if is_dir && !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_str()) {
+                pending.push(entry.path());
+            }
+        }
+    }
+    modules.sort_by(|a, b| a.root.cmp(&b.root));
+    Ok(Workspace {
+        root: root.to_path_buf(),
+        modules,
+    })
+}
+
+#[cfg(test)]
+mod tests;
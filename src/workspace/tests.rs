@@ -0,0 +1,67 @@
+use super::*;
+use tempfile::TempDir;
+
+fn module(root: &Path, rel: &str, yaml: &str, agents: &[&str]) -> PathBuf {
+    let dir = root.join(rel);
+    std::fs::create_dir_all(dir.join("agents")).unwrap();
+    std::fs::write(dir.join("module.yaml"), yaml).unwrap();
+    for agent in agents {
+        std::fs::write(dir.join("agents").join(format!("{agent}.md")), "").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn discovers_nested_modules_and_skips_hidden_and_build_dirs() {
+    let tmp = TempDir::new().unwrap();
+    module(tmp.path(), "", "name: root\n", &[]);
+    module(
+        tmp.path(),
+        "modules/council",
+        "name: forge-council\nversion: 1.2.0\n",
+        &[],
+    );
+    module(tmp.path(), "modules/unnamed", "version: 0.1.0\n", &[]);
+    module(tmp.path(), ".git/modules/x", "name: hidden\n", &[]);
+    module(tmp.path(), "target/debug", "name: built\n", &[]);
+
+    let ws = discover(tmp.path()).unwrap();
+    let names: Vec<&str> = ws.modules.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["root", "forge-council", "unnamed"]);
+    let council = ws.module("forge-council").unwrap();
+    assert_eq!(council.version.as_deref(), Some("1.2.0"));
+    assert_eq!(
+        council.agents_dir(),
+        tmp.path().join("modules/council/agents")
+    );
+
+    assert!(discover(&tmp.path().join("missing")).is_err());
+}
+
+#[test]
+fn council_roles_resolve_across_modules() {
+    let tmp = TempDir::new().unwrap();
+    let council = module(tmp.path(), "council", "name: forge-council\n", &["Critic"]);
+    std::fs::write(
+        council.join("defaults.yaml"),
+        "councils:\n  Review:\n    roles: [Critic, Developer, Ghost]\n",
+    )
+    .unwrap();
+    module(tmp.path(), "dev", "name: forge-dev\n", &["Developer"]);
+
+    let ws = discover(tmp.path()).unwrap();
+    let home = ws.module("forge-council").unwrap();
+    let resolved: Vec<(String, Option<&str>)> = ws
+        .resolve_council(home, "Review")
+        .into_iter()
+        .map(|(role, owner)| (role, owner.map(|m| m.name.as_str())))
+        .collect();
+    assert_eq!(
+        resolved,
+        vec![
+            ("Critic".to_string(), Some("forge-council")),
+            ("Developer".to_string(), Some("forge-dev")),
+            ("Ghost".to_string(), None),
+        ]
+    );
+}
@@ -0,0 +1,1170 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+fn cmd() -> Command {
+    Command::cargo_bin("forge").unwrap()
+}
+
+fn agent_md(name: &str) -> String {
+    format!(
+        "---\ntitle: {name}\nclaude.name: {name}\nclaude.model: sonnet\n\
+         claude.description: Test agent\nclaude.tools: Read, Grep\n---\n\n\
+         # {name}\n\nAgent body content.\n"
+    )
+}
+
+#[test]
+fn no_args_exits_1() {
+    cmd()
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Usage: forge"));
+}
+
+#[test]
+fn version_flag() {
+    cmd()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("forge"));
+}
+
+#[test]
+fn unknown_command_exits_1() {
+    cmd()
+        .arg("frobnicate")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("unknown command"));
+}
+
+#[test]
+fn agents_requires_install_action() {
+    cmd()
+        .arg("agents")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("agents install"));
+}
+
+#[test]
+fn agents_install_with_global_dry_run() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".claude/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    fs::write(dir.path().join("module.yaml"), "name: test-module\n").unwrap();
+
+    cmd()
+        .args(["--dry-run", "agents", "install"])
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "[dry-run] Would install: TestAgent.md",
+        ));
+
+    assert!(!dst.join("TestAgent.md").exists());
+}
+
+#[test]
+fn agents_install_json() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".claude/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    let output = cmd()
+        .args(["--json", "agents", "install"])
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = doc["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["action"], "install");
+    assert_eq!(results[0]["name"], "TestAgent");
+}
+
+#[test]
+fn validate_json() {
+    let dir = tempdir().unwrap();
+
+    let output = cmd()
+        .args(["--json", "validate"])
+        .arg(dir.path().to_str().unwrap())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(doc["failed"].as_u64().unwrap() > 0);
+    assert_eq!(doc["suites"][0]["name"], "Module Structure");
+}
+
+#[test]
+fn validate_rejects_scope() {
+    let dir = tempdir().unwrap();
+    cmd()
+        .args(["--scope", "user", "validate"])
+        .arg(dir.path().to_str().unwrap())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("unknown flag --scope"));
+}
+
+#[test]
+fn yaml_passthrough() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("module.yaml");
+    fs::write(&file, "name: forge-test\n").unwrap();
+
+    cmd()
+        .args(["yaml", "value", file.to_str().unwrap(), ".name"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("forge-test\n"));
+}
+
+#[test]
+fn yaml_reads_stdin() {
+    cmd()
+        .args(["yaml", "keys", "-", ".agents"])
+        .write_stdin("agents:\n  Dev: {}\n  Ops: {}\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Dev\nOps\n"));
+
+    cmd()
+        .args(["yaml", "set", "-", ".name", "piped"])
+        .write_stdin("name: forge-test\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq("name: piped\n"));
+}
+
+#[test]
+fn yaml_set_stores_strings_unless_yaml_is_given() {
+    let set = |args: &[&str]| {
+        cmd()
+            .args(["yaml", "set"])
+            .args(args)
+            .write_stdin("version: 1.9\n")
+            .assert()
+            .success()
+    };
+    set(&["-", ".version", "1.10"]).stdout(predicate::eq("version: '1.10'\n"));
+    set(&["--yaml", "-", ".version", "[1, 2]"]).stdout(predicate::eq("version:\n  - 1\n  - 2\n"));
+}
+
+#[test]
+fn yaml_set_refuses_to_drop_comments_without_reformat() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.yaml");
+    let original = "# shared\nbase: &b {model: fast}\nDev: *b\n";
+    fs::write(&file, original).unwrap();
+    let set = || {
+        let mut cmd = cmd();
+        cmd.args(["yaml", "set", "-i.bak"])
+            .arg(&file)
+            .args([".base.model", "strong"]);
+        cmd
+    };
+    set()
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("--reformat"));
+    assert_eq!(fs::read_to_string(&file).unwrap(), original);
+    assert!(!dir.path().join("a.yaml.bak").exists());
+
+    set().arg("--reformat").assert().success();
+    let written = fs::read_to_string(&file).unwrap();
+    assert!(!written.contains("# shared"));
+    assert!(written.contains("model: strong"));
+
+    fs::write(&file, "agents:\n  Dev:\n    tools: [Read, Grep]  # core\n").unwrap();
+    cmd()
+        .args(["yaml", "set"])
+        .arg(&file)
+        .args([".agents.Dev.tools[0]", "x"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "agents:\n  Dev:\n    tools: [x, Grep]  # core\n"
+    );
+}
+
+#[test]
+fn yaml_all_docs_queries_each_document() {
+    cmd()
+        .args(["yaml", "value", "--all-docs", "-", ".name"])
+        .write_stdin("name: a\n---\nname: b\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq("a\nb\n"));
+}
+
+#[test]
+fn yaml_wildcard_prints_each_match() {
+    cmd()
+        .args(["yaml", "value", "-", ".agents.*.model"])
+        .write_stdin("agents:\n  Dev: {model: fast}\n  Ops: {}\n  Sec: {model: strong}\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq("fast\nstrong\n"));
+}
+
+#[test]
+fn yaml_diff_exits_1_on_differences() {
+    let dir = tempdir().unwrap();
+    let old = dir.path().join("old.yaml");
+    let new = dir.path().join("new.yaml");
+    fs::write(&old, "model: fast\n").unwrap();
+    fs::write(&new, "model: strong\n").unwrap();
+
+    cmd()
+        .args(["yaml", "diff", old.to_str().unwrap(), new.to_str().unwrap()])
+        .assert()
+        .code(1)
+        .stdout(predicate::eq("~ .model: fast -> strong\n"));
+    cmd()
+        .args(["yaml", "diff", old.to_str().unwrap(), old.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn yaml_filter_selects_items() {
+    cmd()
+        .args(["yaml", "value", "-", ".modules[?enabled=true].name"])
+        .write_stdin(
+            "modules:\n  - {name: a, enabled: true}\n  - {name: b}\n  - {name: c, enabled: true}\n",
+        )
+        .assert()
+        .success()
+        .stdout(predicate::eq("a\nc\n"));
+}
+
+#[test]
+fn yaml_shell_quote_survives_eval() {
+    cmd()
+        .args(["yaml", "value", "--shell-quote", "-", ".description"])
+        .write_stdin("description: \"Reviews code; won't $(run) anything\"\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq("'Reviews code; won'\\''t $(run) anything'\n"));
+}
+
+#[test]
+fn yaml_strict_fails_on_typos() {
+    cmd()
+        .args(["yaml", "value", "--strict", "-", ".nmae"])
+        .write_stdin("name: \"\"\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(".nmae: no such path"));
+    cmd()
+        .args(["yaml", "value", "--strict", "-", ".name"])
+        .write_stdin("name: \"\"\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq("\n"));
+    cmd()
+        .args(["yaml", "keys", "--strict", "/nonexistent/forge.yaml", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("/nonexistent/forge.yaml"));
+    cmd()
+        .args(["yaml", "keys", "/nonexistent/forge.yaml", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn yaml_get_prints_one_line_per_path() {
+    let doc = "name: forge\ntags: [a, b]\n";
+    cmd()
+        .args(["yaml", "get", "-", ".name", ".missing", ".tags"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("forge\n\na b\n"));
+    cmd()
+        .args(["yaml", "value", "--tab", "-", ".name", ".missing", "none"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("forge\tnone\n"));
+}
+
+#[test]
+fn yaml_value_queries_every_file_matching_a_glob() {
+    let dir = tempdir().unwrap();
+    for (module, name) in [("a", "alpha"), ("b", "beta")] {
+        fs::create_dir(dir.path().join(module)).unwrap();
+        fs::write(
+            dir.path().join(module).join("module.yaml"),
+            format!("name: {name}\n"),
+        )
+        .unwrap();
+    }
+    let root = dir.path().to_str().unwrap();
+    cmd()
+        .args(["yaml", "value", &format!("{root}/*/module.yaml"), ".name"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!(
+            "{root}/a/module.yaml\talpha\n{root}/b/module.yaml\tbeta\n"
+        )));
+}
+
+#[test]
+fn yaml_envsubst_expands_from_the_environment() {
+    let doc = "name: forge\nhome: ${FORGE_TEST_HOME}/forge\nother: ${FORGE_TEST_UNSET}\n";
+    cmd()
+        .args(["yaml", "envsubst", "-", ".home"])
+        .env("FORGE_TEST_HOME", "/opt")
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("/opt/forge\n"));
+    cmd()
+        .args(["yaml", "envsubst", "--strict", "-"])
+        .env_remove("FORGE_TEST_UNSET")
+        .env("FORGE_TEST_HOME", "/opt")
+        .write_stdin(doc)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("not set: FORGE_TEST_UNSET"));
+}
+
+#[test]
+fn yaml_map_and_list_print_rows_in_a_format() {
+    let doc = "env:\n  HOME: /root\n  PATH: /bin\ntags: [a, b]\n";
+    cmd()
+        .args(["yaml", "map", "-", ".env", "--format", "{key}={value}"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("HOME=/root\nPATH=/bin\n"));
+    cmd()
+        .args(["yaml", "map", "-", ".env", "--sep", "="])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("HOME=/root\nPATH=/bin\n"));
+    cmd()
+        .args(["yaml", "list", "--format", "{index}: {value}", "-", ".tags"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("0: a\n1: b\n"));
+}
+
+#[test]
+fn yaml_list_prints_each_selected_item() {
+    let doc = "list: [a, b, c, d]\nnested:\n  - name: x\n  - name: y\n";
+    for (path, expected) in [
+        (".list[1:3]", "b\nc\n"),
+        (".list[-1]", "d\n"),
+        (".list[0]", "a\n"),
+        (".list[*]", "a\nb\nc\nd\n"),
+        (".nested[*].name", "x\ny\n"),
+        ("..name", "x\ny\n"),
+        (".list", "a\nb\nc\nd\n"),
+    ] {
+        cmd()
+            .args(["yaml", "list", "-", path])
+            .write_stdin(doc)
+            .assert()
+            .success()
+            .stdout(predicate::eq(expected));
+    }
+    cmd()
+        .args([
+            "yaml",
+            "list",
+            "--format",
+            "{index}={value}",
+            "-",
+            ".list[-2:]",
+        ])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("0=c\n1=d\n"));
+    cmd()
+        .args(["yaml", "list", "--strict", "-", ".nested[0]"])
+        .write_stdin(doc)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a list"));
+}
+
+#[test]
+fn yaml_collections_print_a_default_only_when_missing() {
+    let doc = "tags: []\n";
+    cmd()
+        .args(["yaml", "list", "-", ".tags", "--default", "none"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd()
+        .args([
+            "yaml",
+            "list",
+            "--strict",
+            "-",
+            ".tgas",
+            "--default",
+            "none",
+        ])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("none\n"));
+    cmd()
+        .args(["yaml", "keys", "-", ".env", "--default", "-"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("-\n"));
+}
+
+#[test]
+fn yaml_count_counts_matching_items() {
+    let doc = "agents:\n  - {name: Dev, model: fast}\n  - {name: Ops, model: strong}\n";
+    cmd()
+        .args(["yaml", "count", "-", ".agents"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("2\n"));
+    cmd()
+        .args(["yaml", "count", "-", ".agents", "--where", "model!=fast"])
+        .write_stdin(doc)
+        .assert()
+        .success()
+        .stdout(predicate::eq("1\n"));
+}
+
+#[test]
+fn no_color_is_accepted_before_any_command() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("module.yaml");
+    fs::write(&file, "name: forge-test\n").unwrap();
+
+    cmd()
+        .args([
+            "--no-color",
+            "yaml",
+            "value",
+            file.to_str().unwrap(),
+            ".name",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq("forge-test\n"));
+}
+
+#[test]
+fn yaml_rejects_globals() {
+    cmd()
+        .args(["--json", "yaml", "keys", "x.yaml", "."])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("not supported by `forge yaml`"));
+}
+
+#[test]
+fn strip_passthrough() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("test.md");
+    fs::write(&file, "---\ntitle: Hello\n---\n# Heading\n\nBody.\n").unwrap();
+
+    cmd()
+        .args(["strip", file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::eq("\nBody."));
+}
+
+#[test]
+fn new_module_agent_skill() {
+    let dir = tempdir().unwrap();
+
+    cmd()
+        .args(["new", "module", "forge-demo", "--dir"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created:"));
+
+    let root = dir.path().join("forge-demo");
+    assert!(root.join("module.yaml").is_file());
+    assert!(root.join(".claude-plugin/plugin.json").is_file());
+
+    cmd()
+        .args(["new", "agent", "Reviewer", "--root"])
+        .arg(&root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Registered Reviewer"));
+
+    cmd()
+        .args(["new", "skill", "Review", "--root"])
+        .arg(&root)
+        .assert()
+        .success();
+
+    let defaults = fs::read_to_string(root.join("defaults.yaml")).unwrap();
+    assert!(defaults.contains("  Reviewer:\n    model: sonnet"));
+    assert!(defaults.contains("    Review: {}"));
+    assert!(root.join("skills/Review/SKILL.yaml").is_file());
+}
+
+#[test]
+fn new_refuses_overwrite() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("agents")).unwrap();
+    fs::write(dir.path().join("agents/Reviewer.md"), "mine").unwrap();
+
+    cmd()
+        .args(["new", "agent", "Reviewer", "--root"])
+        .arg(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("refusing to overwrite"));
+}
+
+#[test]
+fn new_dry_run_writes_nothing() {
+    let dir = tempdir().unwrap();
+    cmd()
+        .args(["--dry-run", "new", "module", "forge-demo", "--dir"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[dry-run] Would create"));
+    assert!(!dir.path().join("forge-demo").exists());
+}
+
+#[test]
+fn completions_cover_flags_and_aliases() {
+    for shell in ["bash", "zsh", "fish"] {
+        cmd()
+            .args(["completions", shell])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("--scope").or(predicate::str::contains("-l scope")))
+            .stdout(predicate::str::contains("claude gemini codex opencode"))
+            .stdout(predicate::str::contains("user workspace project all"))
+            .stdout(predicate::str::contains("install-agents"));
+    }
+}
+
+#[test]
+fn completions_reject_unknown_shell() {
+    cmd()
+        .args(["completions", "tcsh"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("unsupported shell"));
+}
+
+#[test]
+fn pack_validated_module() {
+    let dir = tempdir().unwrap();
+    cmd()
+        .args(["new", "module", "forge-demo", "--dir"])
+        .arg(dir.path())
+        .assert()
+        .success();
+    let root = dir.path().join("forge-demo");
+    let out = dir.path().join("dist");
+    // The forge-lib submodule a real module checks out.
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::write(root.join("lib/Makefile"), "").unwrap();
+
+    cmd()
+        .arg("pack")
+        .arg(&root)
+        .arg("--out")
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Packed"))
+        .stdout(predicate::str::contains("sha256: "));
+    assert!(out.join("forge-demo-0.1.0.tar").is_file());
+    assert!(out.join("forge-demo-0.1.0.tar.sha256").is_file());
+}
+
+#[test]
+fn pack_refuses_invalid_module() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("module.yaml"), "name: x\nversion: 1.0.0\n").unwrap();
+    cmd()
+        .arg("pack")
+        .arg(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("failed validation"));
+    assert!(!dir.path().join("dist").exists());
+}
+
+/// Pack a one-agent module and publish it in a local registry index.
+fn publish_module(dir: &std::path::Path) -> std::path::PathBuf {
+    let module = dir.join("forge-demo");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(
+        module.join("module.yaml"),
+        "name: forge-demo\nversion: 1.0.0\n",
+    )
+    .unwrap();
+    fs::write(module.join("agents/TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    let dist = dir.join("registry");
+    let package = forge_lib::pack::build_package(&module).unwrap();
+    forge_lib::pack::write_package(&package, &dist, false).unwrap();
+    let index = dist.join("index.json");
+    fs::write(
+        &index,
+        format!(
+            r#"{{"modules": {{"forge-demo": {{"description": "Demo agents", "versions": {{"1.0.0": {{"url": "forge-demo-1.0.0.tar", "sha256": "{}"}}}}}}}}}}"#,
+            package.sha256
+        ),
+    )
+    .unwrap();
+    index
+}
+
+#[test]
+fn search_lists_registry_modules() {
+    let dir = tempdir().unwrap();
+    let index = publish_module(dir.path());
+    cmd()
+        .args(["search", "demo", "--registry"])
+        .arg(&index)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("forge-demo 1.0.0  Demo agents"));
+}
+
+#[test]
+fn install_from_registry_deploys_and_records() {
+    let dir = tempdir().unwrap();
+    let index = publish_module(dir.path());
+    let home = dir.path().join("forge-home");
+    let dst = dir.path().join(".claude/agents");
+
+    cmd()
+        .env("FORGE_HOME", &home)
+        .env("FORGE_REGISTRY", &index)
+        .args(["install", "forge-demo@1.0.0", "--dst"])
+        .arg(&dst)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed: TestAgent.md"));
+    assert!(dst.join("TestAgent.md").is_file());
+    let installed = fs::read_to_string(home.join("installed.json")).unwrap();
+    assert!(installed.contains("\"version\": \"1.0.0\""));
+
+    cmd()
+        .env("FORGE_HOME", &home)
+        .env("FORGE_REGISTRY", &index)
+        .arg("update")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("forge-demo 1.0.0 is up to date"));
+}
+
+#[test]
+fn install_requires_registry() {
+    cmd()
+        .env_remove("FORGE_REGISTRY")
+        .args(["install", "forge-demo"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no registry configured"));
+}
+
+#[test]
+fn outdated_lists_and_redeploys_modules_behind_checkout() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("home");
+    let module = dir.path().join("forge-demo");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(
+        module.join("module.yaml"),
+        "name: forge-demo\nversion: 1.0.0\n",
+    )
+    .unwrap();
+    fs::write(module.join("agents/TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "agents", "install"])
+        .arg(module.join("agents"))
+        .assert()
+        .success();
+    fs::write(
+        module.join("module.yaml"),
+        "name: forge-demo\nversion: 1.1.0\n",
+    )
+    .unwrap();
+
+    cmd()
+        .env("HOME", &home)
+        .env_remove("FORGE_REGISTRY")
+        .arg("outdated")
+        .arg(&module)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("forge-demo 1.0.0 -> 1.1.0"));
+
+    cmd()
+        .env("HOME", &home)
+        .env_remove("FORGE_REGISTRY")
+        .args(["outdated", "--redeploy"])
+        .arg(&module)
+        .assert()
+        .success();
+    cmd()
+        .env("HOME", &home)
+        .env_remove("FORGE_REGISTRY")
+        .arg("outdated")
+        .arg(&module)
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn status_reports_agents_that_drifted() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("home");
+    let module = dir.path().join("forge-demo");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(module.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::write(module.join("agents/Dev.md"), agent_md("Dev")).unwrap();
+    fs::write(module.join("agents/Ops.md"), agent_md("Ops")).unwrap();
+
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "agents", "install"])
+        .arg(module.join("agents"))
+        .assert()
+        .success();
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "status"])
+        .arg(module.join("agents"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("current    Dev"));
+
+    let deployed = home.join(".claude/agents/Dev.md");
+    let edited = fs::read_to_string(&deployed).unwrap() + "My notes.\n";
+    fs::write(&deployed, edited).unwrap();
+    fs::remove_file(module.join("agents/Ops.md")).unwrap();
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "status"])
+        .arg(module.join("agents"))
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("modified   Dev"))
+        .stdout(predicate::str::contains("orphaned   Ops"));
+}
+
+#[test]
+fn verify_reports_edited_files_and_orphan_cleanup_keeps_them() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("home");
+    let module = dir.path().join("forge-demo");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(module.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::write(module.join("agents/Dev.md"), agent_md("Dev")).unwrap();
+    fs::write(module.join("agents/Ops.md"), agent_md("Ops")).unwrap();
+
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "agents", "install"])
+        .arg(module.join("agents"))
+        .assert()
+        .success();
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "--provider", "claude", "verify"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unchanged  Dev.md (forge-demo)"));
+
+    let deployed = home.join(".claude/agents/Ops.md");
+    let edited = fs::read_to_string(&deployed).unwrap() + "My notes.\n";
+    fs::write(&deployed, edited).unwrap();
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "--provider", "claude", "verify"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("modified   Ops.md (forge-demo)"));
+
+    fs::remove_file(module.join("agents/Ops.md")).unwrap();
+    cmd()
+        .env("HOME", &home)
+        .args(["--scope", "user", "agents", "install"])
+        .arg(module.join("agents"))
+        .assert()
+        .success();
+    assert!(deployed.exists());
+}
+
+#[test]
+fn migrate_rewrites_legacy_files_and_reports_unattributed() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("home");
+    let module = dir.path().join("forge-demo");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(module.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::write(module.join("agents/Dev.md"), agent_md("Dev")).unwrap();
+    let dst = home.join(".claude/agents");
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(dst.join("Dev.md"), "# synced-from: Dev.md\nBody.\n").unwrap();
+    fs::write(dst.join("Stray.md"), "# synced-from: Stray.md\nBody.\n").unwrap();
+    fs::write(dst.join(".manifest"), "Dev\n").unwrap();
+
+    cmd()
+        .env("HOME", &home)
+        .args([
+            "migrate",
+            "--scope",
+            "user",
+            "--provider",
+            "claude",
+            "--dry-run",
+        ])
+        .arg(&module)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[dry-run] Would rewrite"));
+    assert!(fs::read_to_string(dst.join("Dev.md"))
+        .unwrap()
+        .starts_with("# synced-from:"));
+
+    cmd()
+        .env("HOME", &home)
+        .args(["migrate", "--scope", "user", "--provider", "claude"])
+        .arg(&module)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Could not attribute"));
+    let dev = fs::read_to_string(dst.join("Dev.md")).unwrap();
+    assert!(dev.contains("source: forge-demo/agents/Dev.md"));
+    assert!(fs::read_to_string(dst.join(".manifest"))
+        .unwrap()
+        .contains("forge-demo"));
+}
+
+#[test]
+fn history_logs_installs_and_cleans() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("home");
+    let module = dir.path().join("forge-demo");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(module.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::write(module.join("agents/TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    for extra in [&["--dry-run"][..], &[], &["--clean"]] {
+        cmd()
+            .env("HOME", &home)
+            .env("FORGE_NO_SNAPSHOT", "1")
+            .args([
+                "--scope",
+                "user",
+                "--provider",
+                "claude",
+                "agents",
+                "install",
+            ])
+            .args(extra)
+            .arg(module.join("agents"))
+            .assert()
+            .success();
+    }
+
+    let log = fs::read_to_string(home.join(".claude/.forge/history.jsonl")).unwrap();
+    assert_eq!(log.lines().count(), 2, "dry runs are not logged");
+
+    cmd()
+        .env("HOME", &home)
+        .args(["history", "--scope", "user", "--module", "forge-demo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "install-agents install forge-demo (claude, user)",
+        ))
+        .stdout(predicate::str::contains("install-agents clean forge-demo"))
+        .stdout(predicate::str::contains("  install TestAgent"));
+    cmd()
+        .env("HOME", &home)
+        .args(["history", "--scope", "user", "--module", "other"])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn council_generate_writes_and_checks_roster_skill() {
+    let dir = tempdir().unwrap();
+    let module = dir.path();
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(module.join("agents/Critic.md"), agent_md("Critic")).unwrap();
+    fs::write(module.join("agents/Tester.md"), agent_md("Tester")).unwrap();
+    fs::write(
+        module.join("defaults.yaml"),
+        "councils:\n  Review:\n    roles: [Critic, Tester]\n",
+    )
+    .unwrap();
+
+    cmd()
+        .args(["council", "generate", "--check"])
+        .arg(module)
+        .assert()
+        .code(1);
+    cmd()
+        .args(["council", "generate"])
+        .arg(module)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Generated:"));
+    let skill = fs::read_to_string(module.join("skills/Review/SKILL.md")).unwrap();
+    assert!(skill.contains("| Critic | sonnet | Test agent |"));
+    cmd()
+        .args(["council", "generate", "--check"])
+        .arg(module)
+        .assert()
+        .success();
+
+    fs::write(
+        module.join("defaults.yaml"),
+        "councils:\n  Review:\n    roles: [Critic]\n",
+    )
+    .unwrap();
+    cmd()
+        .args(["council", "generate", "--check"])
+        .arg(module)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("out of date"));
+}
+
+#[test]
+fn plugin_generate_writes_and_checks_metadata() {
+    let dir = tempdir().unwrap();
+    let module = dir.path();
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(
+        module.join("module.yaml"),
+        "name: forge-demo\nversion: 1.0.0\n",
+    )
+    .unwrap();
+    fs::write(module.join("agents/TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    cmd()
+        .args(["plugin", "generate", "--check"])
+        .arg(module)
+        .assert()
+        .code(1);
+    cmd()
+        .args(["plugin", "generate"])
+        .arg(module)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Generated:"));
+    let plugin = fs::read_to_string(module.join(".claude-plugin/plugin.json")).unwrap();
+    assert!(plugin.contains("./agents/TestAgent.md"));
+    assert!(module.join(".claude-plugin/marketplace.json").is_file());
+    cmd()
+        .args(["plugin", "generate", "--check"])
+        .arg(module)
+        .assert()
+        .success();
+}
+
+#[test]
+fn graph_prints_dot_and_mermaid() {
+    let dir = tempdir().unwrap();
+    let module = dir.path();
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(module.join("agents/Critic.md"), agent_md("Critic")).unwrap();
+    fs::write(
+        module.join("defaults.yaml"),
+        "councils:\n  Review:\n    roles: [Critic]\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("graph")
+        .arg(module)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"council:Review\" -> \"agent:Critic\" [label=\"role\"];",
+        ));
+    cmd()
+        .args(["graph", "--format", "mermaid"])
+        .arg(module)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "council_Review -->|role| agent_Critic",
+        ));
+    cmd()
+        .args(["graph", "--format", "svg"])
+        .arg(module)
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn export_writes_openai_and_plain_bundles() {
+    let dir = tempdir().unwrap();
+    let module = dir.path();
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(module.join("agents/Critic.md"), agent_md("Critic")).unwrap();
+    let out = module.join("bundle.json");
+
+    cmd()
+        .args(["export", "--format", "openai", "--out"])
+        .arg(&out)
+        .arg(module)
+        .assert()
+        .success();
+    let bundle: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    let critic = &bundle["assistants"][0];
+    assert_eq!(critic["name"], "Critic");
+    assert_eq!(critic["instructions"], "Agent body content.\n");
+    assert_eq!(critic["tools"][0]["type"], "file_search");
+    let prompts = module.join("prompts");
+    cmd()
+        .args(["export", "--format", "plain", "--out"])
+        .arg(&prompts)
+        .arg(module)
+        .assert()
+        .success();
+    let prompt = fs::read_to_string(prompts.join("Critic.txt")).unwrap();
+    assert!(prompt.contains("<!-- tools: Read, Grep -->\n"));
+    assert!(prompt.ends_with("\nAgent body content.\n"));
+    cmd()
+        .args(["export", "--format", "pdf"])
+        .arg(module)
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn clean_snapshots_and_restore_rolls_back() {
+    let dir = tempdir().unwrap();
+    let forge_home = dir.path().join("forge-home");
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".claude/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(
+        dir.path().join("module.yaml"),
+        "name: forge-demo\nversion: 1.0.0\n",
+    )
+    .unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    cmd()
+        .args(["agents", "install"])
+        .arg(&src)
+        .arg("--dst")
+        .arg(&dst)
+        .assert()
+        .success();
+    fs::write(dst.join("Mine.md"), "# user agent\n").unwrap();
+    fs::remove_file(src.join("TestAgent.md")).unwrap();
+
+    cmd()
+        .env("FORGE_HOME", &forge_home)
+        .env_remove("FORGE_NO_SNAPSHOT")
+        .args(["agents", "install"])
+        .arg(&src)
+        .args(["--clean", "--yes", "--dst"])
+        .arg(&dst)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("forge snapshot restore"));
+    assert!(!dst.join("TestAgent.md").exists());
+
+    let listed = cmd()
+        .env("FORGE_HOME", &forge_home)
+        .args(["snapshot", "list", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let doc: serde_json::Value = serde_json::from_slice(&listed).unwrap();
+    let id = doc["results"][0]["name"].as_str().unwrap().to_string();
+    assert!(id.ends_with("agents-clean"));
+
+    cmd()
+        .env("FORGE_HOME", &forge_home)
+        .args(["snapshot", "restore", &id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored:"));
+    assert!(dst.join("TestAgent.md").is_file());
+    assert!(dst.join("Mine.md").is_file());
+}
+
+#[test]
+fn watch_redeploys_changed_agents() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("home");
+    let module = dir.path().join("forge-demo");
+    fs::create_dir_all(module.join("agents")).unwrap();
+    fs::write(
+        module.join("module.yaml"),
+        "name: forge-demo\nversion: 1.0.0\n",
+    )
+    .unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("forge"))
+        .env("HOME", &home)
+        .args(["watch", "--interval", "50", "--scope", "user"])
+        .args(["--provider", "claude"])
+        .arg(&module)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::write(module.join("agents/TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    let deployed = home.join(".claude/agents/TestAgent.md");
+    for _ in 0..100 {
+        if deployed.is_file() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(deployed.is_file());
+}
@@ -4,7 +4,10 @@ use std::fs;
 use tempfile::tempdir;
 
 fn cmd() -> Command {
-    Command::cargo_bin("install-agents").unwrap()
+    let mut cmd = Command::cargo_bin("install-agents").unwrap();
+    // Keep --clean runs from writing snapshots under the real ~/.forge.
+    cmd.env("FORGE_NO_SNAPSHOT", "1");
+    cmd
 }
 
 fn write_module_yaml(dir: &std::path::Path, name: &str) {
@@ -59,6 +62,192 @@ fn deploy_basic() {
     assert!(content.contains("TestAgent.md"));
 }
 
+#[test]
+fn forge_log_traces_deploy_to_stderr() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".claude/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+
+    cmd()
+        .current_dir(dir.path())
+        .env("FORGE_LOG", "debug")
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "deploy_agent{file=\"TestAgent.md\"",
+        ))
+        .stderr(predicate::str::contains("planned"));
+    cmd()
+        .current_dir(dir.path())
+        .env_remove("FORGE_LOG")
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("planned").not());
+}
+
+#[test]
+fn deploys_to_a_custom_provider_from_defaults() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(dir.path().join("templates")).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+    fs::write(
+        dir.path().join("defaults.yaml"),
+        "providers:\n  acme:\n    dir: .acme/agents\n    extension: txt\n\
+         \x20   name_style: kebab\n    template: agent-acme.txt\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("templates/agent-acme.txt.j2"),
+        "---\nsource: {{ source }}\n---\n# {{ name }}: {{ description }}\n{{ body }}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .current_dir(dir.path())
+        .arg("agents")
+        .args(["--scope", "workspace", "--provider", "acme"])
+        .assert()
+        .success();
+
+    let deployed = fs::read_to_string(dir.path().join(".acme/agents/test-agent.txt")).unwrap();
+    assert!(deployed.contains("# test-agent: Test agent\n"));
+
+    cmd()
+        .current_dir(dir.path())
+        .arg("agents")
+        .args(["--provider", "nope"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("invalid provider \"nope\""));
+}
+
+#[test]
+fn diff_shows_what_a_redeploy_changes() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- /dev/null\n"))
+        .stdout(predicate::str::contains("+Agent body content.\n"));
+    assert!(!dst.join("TestAgent.md").exists());
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success();
+    let edited = agent_md("TestAgent").replace("Agent body content.", "New body.");
+    fs::write(src.join("TestAgent.md"), edited).unwrap();
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "-Agent body content.\n+New body.\n",
+        ));
+    assert!(fs::read_to_string(dst.join("TestAgent.md"))
+        .unwrap()
+        .contains("Agent body content."));
+}
+
+#[cfg(unix)]
+#[test]
+fn link_symlinks_agents_to_their_sources() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(
+        src.join("Dev.md"),
+        "---\nname: Dev\ndescription: Develops\n---\nBody.\n",
+    )
+    .unwrap();
+    write_module_yaml(dir.path(), "test-module");
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--link"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Linked: Dev.md"));
+    assert_eq!(
+        fs::read_link(dst.join("Dev.md")).unwrap(),
+        fs::canonicalize(src.join("Dev.md")).unwrap()
+    );
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args([
+            "--dst",
+            dst.to_str().unwrap(),
+            "--provider",
+            "gemini",
+            "--link",
+        ])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("cannot link agents for gemini"));
+}
+
+#[test]
+fn restore_brings_back_what_a_backup_deploy_replaced() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success();
+    let before = fs::read_to_string(dst.join("TestAgent.md")).unwrap();
+    let edited = agent_md("TestAgent").replace("Agent body content.", "New body.");
+    fs::write(src.join("TestAgent.md"), edited).unwrap();
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--backup"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dst.join("TestAgent.md"))
+        .unwrap()
+        .contains("New body."));
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--restore"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored: TestAgent.md"));
+    assert_eq!(
+        fs::read_to_string(dst.join("TestAgent.md")).unwrap(),
+        before
+    );
+}
+
 #[test]
 fn dry_run_no_write() {
     let dir = tempdir().unwrap();
@@ -107,6 +296,29 @@ fn clean_removes_synced() {
         .stdout(predicate::str::contains("Installed: TestAgent.md"));
 }
 
+#[test]
+fn clean_with_yes_skips_prompt() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success();
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--clean", "--yes"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Proceed?").not())
+        .stdout(predicate::str::contains("Removed: TestAgent.md"));
+}
+
 #[test]
 fn skips_template() {
     let dir = tempdir().unwrap();
@@ -157,6 +369,131 @@ fn skips_user_owned() {
     assert!(content.contains("User-created content"));
 }
 
+#[test]
+fn force_agent_replaces_a_user_owned_agent_with_a_backup() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+    let mine = "---\nname: MyAgent\n---\n\nUser-created content.\n";
+    for name in ["MyAgent", "OtherAgent"] {
+        fs::write(src.join(format!("{name}.md")), agent_md(name)).unwrap();
+        fs::write(dst.join(format!("{name}.md")), mine).unwrap();
+    }
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--force-agent", "MyAgent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Replaced: MyAgent.md"))
+        .stdout(predicate::str::contains("undo with --restore"))
+        .stderr(predicate::str::contains("Skipping OtherAgent.md"));
+    assert!(fs::read_to_string(dst.join("MyAgent.md"))
+        .unwrap()
+        .contains("Agent body content."));
+    assert_eq!(
+        fs::read_to_string(dir.path().join(".forge-state/output/backup/MyAgent.md")).unwrap(),
+        mine
+    );
+    assert_eq!(fs::read_to_string(dst.join("OtherAgent.md")).unwrap(), mine);
+}
+
+#[test]
+fn interactive_asks_about_user_owned_agents() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+    let mine = "---\nname: MyAgent\n---\n\nUser-created content.\n";
+    for name in ["MyAgent", "OtherAgent"] {
+        fs::write(src.join(format!("{name}.md")), agent_md(name)).unwrap();
+        fs::write(dst.join(format!("{name}.md")), mine).unwrap();
+    }
+    let install = || {
+        let mut cmd = cmd();
+        cmd.arg(src.to_str().unwrap())
+            .args(["--dst", dst.to_str().unwrap(), "--interactive"]);
+        cmd
+    };
+
+    // Without answers, every user-created agent is skipped as before.
+    install()
+        .write_stdin("")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipping MyAgent.md"));
+    assert_eq!(fs::read_to_string(dst.join("MyAgent.md")).unwrap(), mine);
+
+    install()
+        .write_stdin("d\noverwrite\nr\n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-User-created content."))
+        .stdout(predicate::str::contains("Replaced: MyAgent.md"))
+        .stdout(predicate::str::contains("yours is now OtherAgent-local.md"))
+        .stderr(predicate::str::contains(
+            "[o]verwrite/[s]kip/[d]iff/[r]ename",
+        ));
+    assert_eq!(
+        fs::read_to_string(dir.path().join(".forge-state/output/backup/MyAgent.md")).unwrap(),
+        mine
+    );
+    assert_eq!(
+        fs::read_to_string(dst.join("OtherAgent-local.md")).unwrap(),
+        mine
+    );
+    for name in ["MyAgent", "OtherAgent"] {
+        assert!(fs::read_to_string(dst.join(format!("{name}.md")))
+            .unwrap()
+            .contains("Agent body content."));
+    }
+}
+
+#[test]
+fn detailed_exit_codes_report_skips_and_no_ops() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+    let install = || {
+        cmd()
+            .arg(src.to_str().unwrap())
+            .args(["--dst", dst.to_str().unwrap(), "--detailed-exit-codes"])
+            .assert()
+    };
+
+    install().code(4);
+
+    fs::write(src.join("MyAgent.md"), agent_md("MyAgent")).unwrap();
+    fs::write(dst.join("MyAgent.md"), "User-created content.\n").unwrap();
+    install().code(3);
+
+    fs::remove_file(dst.join("MyAgent.md")).unwrap();
+    install().code(0);
+
+    // Unchanged since the last deploy: a cache hit writes and logs nothing.
+    let history = dir.path().join(".forge/history.jsonl");
+    let logged = fs::read_to_string(&history).unwrap();
+    install()
+        .code(4)
+        .stdout(predicate::str::contains("Unchanged: MyAgent.md"))
+        .stdout(predicate::str::contains("Installed").not());
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"action\": \"unchanged\""));
+    assert_eq!(fs::read_to_string(&history).unwrap(), logged);
+}
+
 #[test]
 fn invalid_dir_exits_1() {
     cmd()
@@ -205,6 +542,39 @@ fn provider_detection_gemini() {
     assert!(content.contains("kind: local"));
 }
 
+#[test]
+fn excluded_provider_skips_and_removes_the_agent() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".gemini/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    fs::write(src.join("OtherAgent.md"), agent_md("OtherAgent")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+    let install = || {
+        cmd()
+            .arg(src.to_str().unwrap())
+            .args(["--dst", dst.to_str().unwrap()])
+            .assert()
+            .success()
+    };
+    install();
+    assert!(dst.join("TestAgent.md").exists());
+
+    fs::write(
+        dir.path().join("defaults.yaml"),
+        "agents:\n  TestAgent:\n    providers: [claude]\n",
+    )
+    .unwrap();
+    install()
+        .stderr(predicate::str::contains(
+            "agents.TestAgent.providers excludes gemini",
+        ))
+        .stdout(predicate::str::contains("Removed orphan: TestAgent.md"));
+    assert!(!dst.join("TestAgent.md").exists());
+    assert!(dst.join("OtherAgent.md").exists());
+}
+
 #[test]
 fn help_flag() {
     cmd()
@@ -213,3 +583,203 @@ fn help_flag() {
         .success()
         .stdout(predicate::str::contains("Usage"));
 }
+
+#[test]
+fn continue_on_error_deploys_the_rest() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("Aaa.md"), agent_md("bad-name")).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .code(1);
+    assert!(!dst.join("TestAgent.md").exists());
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--continue-on-error"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("Installed: TestAgent.md"))
+        .stderr(predicate::str::contains("1 error(s):"))
+        .stderr(predicate::str::contains("Aaa.md: agent name"));
+    assert!(dst.join("TestAgent.md").exists());
+}
+
+#[test]
+fn org_policy_violation_fails_deploy() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    let policy = dir.path().join("policy.yaml");
+    fs::write(&policy, "banned_tools: [Grep]\n").unwrap();
+
+    cmd()
+        .env("FORGE_POLICY", &policy)
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "TestAgent violates organization policy: tool Grep is banned",
+        ));
+    assert!(!dst.join("TestAgent.md").exists());
+
+    fs::write(&policy, "banned_tools: [WebFetch]\n").unwrap();
+    cmd()
+        .env("FORGE_POLICY", &policy)
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success();
+    assert!(dst.join("TestAgent.md").exists());
+}
+
+#[test]
+fn progress_prints_one_summary_per_directory() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    fs::write(src.join("OtherAgent.md"), agent_md("OtherAgent")).unwrap();
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--progress"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed:").not())
+        .stderr(predicate::str::contains("[1/1] claude"))
+        .stderr(predicate::str::contains(": 2 installed ("));
+}
+
+#[test]
+fn config_override_applies() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    let override_path = dir.path().join("ci.yaml");
+    fs::write(&override_path, "agents:\n  TestAgent:\n    model: haiku\n").unwrap();
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .args(["--config", override_path.to_str().unwrap()])
+        .assert()
+        .success();
+    let deployed = fs::read_to_string(dst.join("TestAgent.md")).unwrap();
+    assert!(deployed.contains("model: haiku"));
+
+    cmd()
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--config", "missing.yaml"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("missing.yaml"));
+}
+
+#[test]
+fn redeploy_reports_merge_and_conflict() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".claude/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+    let deploy = || {
+        let mut c = cmd();
+        c.current_dir(dir.path()).arg(src.to_str().unwrap()).args([
+            "--dst",
+            dst.to_str().unwrap(),
+            "--yes",
+        ]);
+        c
+    };
+    deploy().assert().success();
+
+    let deployed = dst.join("TestAgent.md");
+    let edited = fs::read_to_string(&deployed)
+        .unwrap()
+        .replace("# TestAgent", "# TestAgent (local)");
+    fs::write(&deployed, edited).unwrap();
+    fs::write(
+        src.join("TestAgent.md"),
+        agent_md("TestAgent") + "\nNew section.\n",
+    )
+    .unwrap();
+    deploy()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Merged: TestAgent.md"));
+    let merged = fs::read_to_string(&deployed).unwrap();
+    assert!(merged.contains("# TestAgent (local)"));
+    assert!(merged.contains("New section."));
+
+    fs::write(
+        src.join("TestAgent.md"),
+        agent_md("TestAgent").replace("# TestAgent", "# TestAgent (upstream)"),
+    )
+    .unwrap();
+    deploy().assert().code(2).stderr(predicate::str::contains(
+        "Conflict: your edits of TestAgent.md",
+    ));
+    assert!(fs::read_to_string(&deployed)
+        .unwrap()
+        .contains("<<<<<<< deployed\n"));
+}
+
+#[test]
+fn report_flag_writes_markdown_summary() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".claude/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("TestAgent.md"), agent_md("TestAgent")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+    let report = dir.path().join("deploy-report.md");
+
+    cmd()
+        .current_dir(dir.path())
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap(), "--yes"])
+        .args(["--report", report.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let text = fs::read_to_string(&report).unwrap();
+    assert!(text.contains("# Deployment report: install-agents"));
+    assert!(text.contains("| install | TestAgent |"));
+    assert!(text.contains("| claude | TestAgent | sonnet |"));
+}
+
+#[test]
+fn manifest_records_agent_name_not_filename() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join(".claude/agents");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("dev.md"), agent_md("Developer")).unwrap();
+    write_module_yaml(dir.path(), "test-module");
+
+    cmd()
+        .current_dir(dir.path())
+        .arg(src.to_str().unwrap())
+        .args(["--dst", dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed: Developer.md"));
+
+    let manifest = fs::read_to_string(dst.join(".manifest")).unwrap();
+    assert!(manifest.contains("- Developer"));
+    assert!(!manifest.contains("- dev"));
+}
@@ -0,0 +1,121 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const REVIEW: &str = "---\ndescription: Review a change\nargument-hint: <path>\n---\n\n\
+                      Review $ARGUMENTS carefully.\n";
+
+fn cmd() -> Command {
+    Command::cargo_bin("install-commands").unwrap()
+}
+
+fn module(root: &Path, enabled: &[&str]) {
+    fs::create_dir_all(root.join("commands")).unwrap();
+    fs::write(root.join("commands/review.md"), REVIEW).unwrap();
+    fs::write(root.join("commands/ship.md"), REVIEW).unwrap();
+    fs::write(root.join("module.yaml"), "name: forge-demo\n").unwrap();
+    let mut defaults = String::from("commands:\n");
+    for provider in ["claude", "gemini"] {
+        let _ = writeln!(defaults, "  {provider}:");
+        for name in enabled {
+            let _ = writeln!(defaults, "    {name}: {{}}");
+        }
+    }
+    fs::write(root.join("defaults.yaml"), defaults).unwrap();
+}
+
+#[test]
+fn installs_allowlisted_commands() {
+    let dir = tempdir().unwrap();
+    module(dir.path(), &["review"]);
+    let dst = dir.path().join("out");
+
+    cmd()
+        .arg(dir.path().join("commands"))
+        .args(["--provider", "claude", "--dst"])
+        .arg(&dst)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed: review.md"));
+
+    let out = fs::read_to_string(dst.join("review.md")).unwrap();
+    assert!(out.contains("argument-hint: <path>"));
+    assert!(out.contains("source: forge-demo/"));
+    assert!(!dst.join("ship.md").exists());
+}
+
+#[test]
+fn gemini_gets_toml() {
+    let dir = tempdir().unwrap();
+    module(dir.path(), &["review"]);
+    let dst = dir.path().join("out");
+
+    cmd()
+        .arg(dir.path().join("commands"))
+        .args(["--provider", "gemini", "--dst"])
+        .arg(&dst)
+        .assert()
+        .success();
+
+    let out = fs::read_to_string(dst.join("review.toml")).unwrap();
+    assert!(out.contains("Review {{args}} carefully."));
+}
+
+#[test]
+fn orphan_removed_after_allowlist_shrinks() {
+    let dir = tempdir().unwrap();
+    module(dir.path(), &["review", "ship"]);
+    let dst = dir.path().join("out");
+    let run = || {
+        cmd()
+            .arg(dir.path().join("commands"))
+            .args(["--provider", "claude", "--dst"])
+            .arg(&dst)
+            .assert()
+    };
+    run().success();
+    assert!(dst.join("ship.md").exists());
+
+    module(dir.path(), &["review"]);
+    run()
+        .success()
+        .stdout(predicate::str::contains("Removed orphan: ship.md"));
+    assert!(!dst.join("ship.md").exists());
+    assert!(dst.join("review.md").exists());
+}
+
+#[test]
+fn rejects_project_scope() {
+    let dir = tempdir().unwrap();
+    module(dir.path(), &["review"]);
+    cmd()
+        .arg(dir.path().join("commands"))
+        .args(["--scope", "project"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("use user or workspace"));
+}
+
+#[test]
+fn clean_with_yes_skips_prompt() {
+    let dir = tempdir().unwrap();
+    module(dir.path(), &["review"]);
+    let dst = dir.path().join("out");
+    let run = |extra: &[&str]| {
+        cmd()
+            .arg(dir.path().join("commands"))
+            .args(["--provider", "claude", "--dst"])
+            .arg(&dst)
+            .args(extra)
+            .assert()
+    };
+    run(&[]).success();
+
+    run(&["--clean", "--yes", "--no-snapshot"])
+        .success()
+        .stderr(predicate::str::contains("Proceed?").not())
+        .stdout(predicate::str::contains("Removed: review.md"));
+}
@@ -0,0 +1,106 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+fn cmd() -> Command {
+    let mut cmd = Command::cargo_bin("install-hooks").unwrap();
+    // Keep --clean runs from writing snapshots under the real ~/.forge.
+    cmd.env("FORGE_NO_SNAPSHOT", "1");
+    cmd
+}
+
+fn write_module(dir: &std::path::Path) {
+    fs::write(dir.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::create_dir_all(dir.join("hooks")).unwrap();
+    fs::write(
+        dir.join("hooks/hooks.json"),
+        r#"{"hooks": {"PreToolUse": [{"matcher": "Bash", "hooks": [{"type": "command", "command": "${CLAUDE_PLUGIN_ROOT}/hooks/check.sh"}]}]}}"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn version_flag() {
+    cmd()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("install-hooks"));
+}
+
+#[test]
+fn install_then_clean() {
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("module");
+    fs::create_dir_all(&module).unwrap();
+    write_module(&module);
+    let settings = dir.path().join("settings.json");
+    fs::write(&settings, "{\n  \"model\": \"opus\"\n}\n").unwrap();
+
+    cmd()
+        .arg(&module)
+        .args(["--dst", settings.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Installed hook: PreToolUse (Bash)",
+        ));
+
+    let content = fs::read_to_string(&settings).unwrap();
+    assert!(content.contains("\"model\": \"opus\""));
+    assert!(content.contains("/hooks/check.sh"));
+    assert!(!content.contains("CLAUDE_PLUGIN_ROOT"));
+    assert!(dir.path().join(".forge-hooks.json").is_file());
+
+    cmd()
+        .arg(&module)
+        .args(["--dst", settings.to_str().unwrap(), "--clean", "--yes"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Proceed?").not())
+        .stdout(predicate::str::contains("Removed hook: PreToolUse (Bash)"));
+
+    assert_eq!(
+        fs::read_to_string(&settings).unwrap(),
+        "{\n  \"model\": \"opus\"\n}\n"
+    );
+}
+
+#[test]
+fn dry_run_no_write() {
+    let dir = tempdir().unwrap();
+    write_module(dir.path());
+    let settings = dir.path().join("out/settings.json");
+
+    cmd()
+        .arg(dir.path())
+        .args(["--dst", settings.to_str().unwrap(), "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[dry-run] Would install hook"));
+    assert!(!settings.exists());
+}
+
+#[test]
+fn requires_module_name() {
+    let dir = tempdir().unwrap();
+    cmd()
+        .arg(dir.path())
+        .args(["--dst", "settings.json"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("module.yaml"));
+}
+
+#[test]
+fn rejects_all_scope() {
+    let dir = tempdir().unwrap();
+    write_module(dir.path());
+    cmd()
+        .arg(dir.path())
+        .args(["--scope", "all"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("invalid scope"));
+}
@@ -0,0 +1,93 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn cmd() -> Command {
+    let mut cmd = Command::cargo_bin("install-mcp").unwrap();
+    // Keep --clean runs from writing snapshots under the real ~/.forge.
+    cmd.env("FORGE_NO_SNAPSHOT", "1");
+    cmd
+}
+
+fn write_module(dir: &Path) {
+    fs::write(dir.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::create_dir_all(dir.join("mcp")).unwrap();
+    fs::write(
+        dir.join("mcp/search.json"),
+        r#"{"command": "${CLAUDE_PLUGIN_ROOT}/bin/search", "args": ["--stdio"]}"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn version_flag() {
+    cmd()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("install-mcp"));
+}
+
+#[test]
+fn install_then_clean_json_target() {
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("module");
+    fs::create_dir_all(&module).unwrap();
+    write_module(&module);
+    let target = dir.path().join(".mcp.json");
+
+    cmd()
+        .arg(&module)
+        .arg("--dst")
+        .arg(&target)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed server: search"));
+    let doc: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&target).unwrap()).unwrap();
+    let command = doc["mcpServers"]["search"]["command"].as_str().unwrap();
+    assert!(command.ends_with("/module/bin/search"));
+
+    cmd()
+        .arg(&module)
+        .arg("--dst")
+        .arg(&target)
+        .args(["--clean", "--yes"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Proceed?").not())
+        .stdout(predicate::str::contains("Removed server: search"));
+    assert_eq!(fs::read_to_string(&target).unwrap(), "{}\n");
+}
+
+#[test]
+fn codex_gets_managed_toml_block() {
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("module");
+    fs::create_dir_all(&module).unwrap();
+    write_module(&module);
+    let target = dir.path().join("config.toml");
+
+    cmd()
+        .arg(&module)
+        .args(["--provider", "codex", "--dst"])
+        .arg(&target)
+        .assert()
+        .success();
+    let content = fs::read_to_string(&target).unwrap();
+    assert!(content.contains("# BEGIN forge mcp forge-demo"));
+    assert!(content.contains("[mcp_servers.search]"));
+    assert!(content.contains("args = [\"--stdio\"]"));
+}
+
+#[test]
+fn requires_module_name() {
+    let dir = tempdir().unwrap();
+    cmd()
+        .arg(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("module.yaml with a name"));
+}
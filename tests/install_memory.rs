@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn cmd() -> Command {
+    let mut cmd = Command::cargo_bin("install-memory").unwrap();
+    // Keep --clean runs from writing snapshots under the real ~/.forge.
+    cmd.env("FORGE_NO_SNAPSHOT", "1");
+    cmd
+}
+
+fn write_module(dir: &Path) {
+    fs::write(dir.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::write(dir.join("memory.md"), "Ask Reviewer before merging.\n").unwrap();
+    fs::create_dir_all(dir.join("agents")).unwrap();
+    fs::write(
+        dir.join("agents/Reviewer.md"),
+        "---\nname: Reviewer\ndescription: Reviews code\n---\nBody\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn install_update_clean() {
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("module");
+    fs::create_dir_all(&module).unwrap();
+    write_module(&module);
+    let target = dir.path().join("CLAUDE.md");
+    fs::write(&target, "# Project\n").unwrap();
+    let run = |extra: &[&str]| {
+        cmd()
+            .arg(&module)
+            .arg("--dst")
+            .arg(&target)
+            .args(extra)
+            .assert()
+    };
+
+    run(&[])
+        .success()
+        .stdout(predicate::str::contains("Added forge-demo block"));
+    let content = fs::read_to_string(&target).unwrap();
+    assert!(content.starts_with("# Project\n\n<!-- BEGIN forge forge-demo -->\n"));
+    assert!(content.contains("- **Reviewer** — Reviews code"));
+
+    // Unchanged: nothing to do, which only --detailed-exit-codes reports.
+    run(&[]).success();
+    run(&["--detailed-exit-codes"]).code(4);
+
+    run(&["--clean", "--yes"])
+        .success()
+        .stderr(predicate::str::contains("Proceed?").not())
+        .stdout(predicate::str::contains("Removed forge-demo block"));
+    assert_eq!(fs::read_to_string(&target).unwrap(), "# Project\n");
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("module");
+    fs::create_dir_all(&module).unwrap();
+    write_module(&module);
+    let target = dir.path().join("GEMINI.md");
+
+    cmd()
+        .arg(&module)
+        .args(["--provider", "gemini", "--dry-run", "--dst"])
+        .arg(&target)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[dry-run] Would install"));
+    assert!(!target.exists());
+}
@@ -217,3 +217,21 @@ fn help_flag() {
         .success()
         .stdout(predicate::str::contains("Usage"));
 }
+
+#[test]
+fn empty_skills_dir_exits_nothing_to_do() {
+    let dir = tempdir().unwrap();
+    let skills = dir.path().join("skills");
+    let dst = dir.path().join("output");
+    fs::create_dir_all(&skills).unwrap();
+    let install = |extra: &[&str]| {
+        cmd()
+            .arg(skills.to_str().unwrap())
+            .args(["--provider", "claude", "--dst", dst.to_str().unwrap()])
+            .args(extra)
+            .assert()
+    };
+
+    install(&[]).success();
+    install(&["--detailed-exit-codes"]).code(4);
+}
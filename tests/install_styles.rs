@@ -0,0 +1,91 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn cmd() -> Command {
+    let mut cmd = Command::cargo_bin("install-styles").unwrap();
+    // Keep --clean runs from writing snapshots under the real ~/.forge.
+    cmd.env("FORGE_NO_SNAPSHOT", "1");
+    cmd
+}
+
+fn write_module(dir: &Path) {
+    fs::write(dir.join("module.yaml"), "name: forge-demo\n").unwrap();
+    fs::create_dir_all(dir.join("output-styles")).unwrap();
+    fs::write(
+        dir.join("output-styles/Terse.md"),
+        "---\nname: Terse\ndescription: Short answers\n---\nBe brief.\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("statusline.json"),
+        r#"{"type": "command", "command": "${CLAUDE_PLUGIN_ROOT}/status.sh"}"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn install_then_clean() {
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("module");
+    fs::create_dir_all(&module).unwrap();
+    write_module(&module);
+    let styles = dir.path().join("output-styles");
+    let settings = dir.path().join("settings.json");
+    let run = |extra: &[&str]| {
+        cmd()
+            .arg(&module)
+            .arg("--dst")
+            .arg(&styles)
+            .arg("--settings")
+            .arg(&settings)
+            .args(extra)
+            .assert()
+    };
+
+    run(&[])
+        .success()
+        .stdout(predicate::str::contains("Installed: Terse.md"))
+        .stdout(predicate::str::contains("Installed statusline"));
+    assert!(fs::read_to_string(styles.join("Terse.md"))
+        .unwrap()
+        .contains("source: forge-demo/output-styles/Terse.md"));
+    assert!(fs::read_to_string(&settings)
+        .unwrap()
+        .contains("/module/status.sh"));
+
+    run(&["--clean", "--yes"])
+        .success()
+        .stderr(predicate::str::contains("Proceed?").not())
+        .stdout(predicate::str::contains("Removed: Terse.md"))
+        .stdout(predicate::str::contains("Removed statusline"));
+    assert!(!styles.join("Terse.md").exists());
+    assert_eq!(fs::read_to_string(&settings).unwrap(), "{}\n");
+}
+
+#[test]
+fn user_statusline_is_kept() {
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("module");
+    fs::create_dir_all(&module).unwrap();
+    write_module(&module);
+    let settings = dir.path().join("settings.json");
+    fs::write(
+        &settings,
+        r#"{"statusLine": {"type": "command", "command": "mine"}}"#,
+    )
+    .unwrap();
+
+    cmd()
+        .arg(&module)
+        .arg("--dst")
+        .arg(dir.path().join("styles"))
+        .arg("--settings")
+        .arg(&settings)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("already sets one"));
+    assert!(fs::read_to_string(&settings).unwrap().contains("mine"));
+}
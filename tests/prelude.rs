@@ -0,0 +1,29 @@
+use forge_lib::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn embedder_deploys_through_prelude() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("agents");
+    let dst = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(
+        src.join("Dev.md"),
+        "---\nname: Dev\ndescription: Developer\n---\nBody.\n",
+    )
+    .unwrap();
+
+    let config = SidecarConfig::load(dir.path());
+    let options = DeployOptions::new(Provider::Claude).with_source_prefix("demo/agents");
+    let plan = forge_lib::deploy::plan_agents_from_dir(&src, &dst, &config, &options).unwrap();
+    let action: &AgentAction = &plan.actions[0];
+    assert_eq!(action.result, DeployResult::Deployed);
+
+    let results = forge_lib::deploy::apply(&RealFs, &plan, &mut NoProgress).unwrap();
+    assert_eq!(
+        results,
+        vec![("Dev.md".to_string(), DeployResult::Deployed)]
+    );
+    assert!(dst.join("Dev.md").is_file());
+}